@@ -28,19 +28,63 @@ Example:
 from __future__ import annotations
 
 import asyncio
+import inspect
+import os
 import sys
-from collections.abc import AsyncIterator, Generator
+import threading
+import uuid
+from collections.abc import AsyncIterator, Callable, Generator, Mapping
+from concurrent.futures import Future
 from contextlib import asynccontextmanager, contextmanager
 from functools import partial
-from typing import TYPE_CHECKING, TypedDict
+from typing import TYPE_CHECKING, Any, Literal, TypedDict
 
 if sys.version_info <= (3, 11):
-    from typing_extensions import Unpack
+    from typing_extensions import Self, Unpack
 else:
-    from typing import Unpack
-
-
-from .httpr import CaseInsensitiveHeaderMap, RClient, Response, StreamingResponse
+    from typing import Self, Unpack
+
+
+from .httpr import (
+    ApiKeyAuth,
+    CancellationToken,
+    CaseInsensitiveHeaderMap,
+    FetchIterator,
+    HmacAuth,
+    MultipartUploader,
+    Paginator,
+    ProblemDetails,
+    PythonTransport,
+    RClient,
+    Request,
+    RequestQueue,
+    RequestTemplate,
+    Response,
+    RobotsTxt,
+    RuntimeStats,
+    ServerTiming,
+    SitemapIterator,
+    SniffResult,
+    Stream,
+    StreamingResponse,
+    TransportRequest,
+    presign_url,
+    runtime_stats,
+)
+from .config import load_profile
+from .listing import DirectoryEntry, from_multistatus, parse_index
+from .mirror import SyncResult, Validators, conditional_headers, load_state, save_state, write_atomic
+from .soap import (
+    SoapFault,
+    SoapResponse,
+    SoapVersion,
+    build_envelope,
+    envelope_version,
+    parse_envelope,
+    request_headers,
+)
+from .tracing import _apply_trace_context
+from .webdav import DavResource, build_propfind, parse_multistatus
 
 
 class CaseInsensitiveDict(dict[str, str]):
@@ -88,6 +132,88 @@ else:
     Unpack = _Unpack()
     RequestParams = ClientRequestParams = TypedDict
 
+_METHODS = (
+    "GET",
+    "HEAD",
+    "OPTIONS",
+    "DELETE",
+    "POST",
+    "PUT",
+    "PATCH",
+    # WebDAV (RFC 4918)
+    "PROPFIND",
+    "PROPPATCH",
+    "MKCOL",
+    "COPY",
+    "MOVE",
+    "LOCK",
+    "UNLOCK",
+)
+
+
+def _apply_idempotency_key(kwargs: dict[str, Any]) -> None:
+    """Replace the `idempotency_key` request option with an `Idempotency-Key` header.
+
+    `True` keeps a key already in `headers` and otherwise generates a UUID4. The header is
+    part of the request itself, so every attempt of the request sends the same key.
+    """
+    key = kwargs.pop("idempotency_key", None)
+    if key is None or key is False:
+        return
+    headers = dict(kwargs.get("headers") or {})
+    existing = [name for name in headers if name.lower() == "idempotency-key"]
+    if key is True:
+        key = headers[existing[0]] if existing else str(uuid.uuid4())
+    for name in existing:
+        del headers[name]
+    headers["Idempotency-Key"] = key
+    kwargs["headers"] = headers
+
+
+def _apply_proto(kwargs: dict[str, Any]) -> None:
+    """Replace the `proto` request option with a protobuf body and its `Content-Type`.
+
+    `proto` is a serialized message, or a message object with `SerializeToString()`.
+    """
+    message = kwargs.pop("proto", None)
+    if message is None:
+        return
+    if any(kwargs.get(name) is not None for name in ("content", "data", "json", "files")):
+        raise ValueError("proto cannot be combined with content, data, json or files")
+    if hasattr(message, "SerializeToString"):
+        message = message.SerializeToString()
+    headers = dict(kwargs.get("headers") or {})
+    if not any(name.lower() == "content-type" for name in headers):
+        headers["Content-Type"] = "application/x-protobuf"
+    kwargs["headers"] = headers
+    kwargs["content"] = bytes(message)
+
+
+def _accept_json(kwargs: dict[str, Any]) -> dict[str, Any]:
+    """Add `Accept: application/json` to the request headers, unless they name an `Accept`."""
+    headers = dict(kwargs.get("headers") or {})
+    if not any(name.lower() == "accept" for name in headers):
+        headers["Accept"] = "application/json"
+    return {**kwargs, "headers": headers}
+
+
+def _json_body(response: Response) -> Any:
+    """The parsed body of a JSON API response, for `get_json()` and `post_json()`.
+
+    Raises `HTTPStatusError` for a 4xx/5xx status and `DecodingError` for a body that is not
+    declared as JSON. An empty body, as with `204 No Content`, gives None.
+    """
+    response.raise_for_status()
+    if not response.content:
+        return None
+    content_type = response.content_type
+    if content_type != "application/json" and not (content_type or "").endswith("+json"):
+        got = content_type or "no Content-Type"
+        err = DecodingError(f"Expected a JSON response from {response.url}, got {got}")
+        err.response = response  # type: ignore[attr-defined]
+        raise err
+    return response.json()
+
 
 class Client(RClient):
     """
@@ -131,21 +257,23 @@ class Client(RClient):
         cookies: Default cookies sent with all requests.
         auth: Basic auth credentials as (username, password) tuple.
         params: Default query parameters added to all requests.
-        timeout: Default timeout in seconds.
+        timeout: Default total timeout of each request in seconds.
+        connect_timeout: Seconds allowed for connecting (read-only).
+        read_timeout: Seconds a response may go without receiving data (read-only).
         proxy: Proxy URL for requests.
     """
 
     def __init__(
         self,
-        auth: tuple[str, str | None] | None = None,
+        auth: tuple[str, str | None] | HmacAuth | ApiKeyAuth | None = None,
         auth_bearer: str | None = None,
         params: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: dict[str, str | Callable[[], str | None]] | None = None,
         cookies: dict[str, str] | None = None,
         cookie_store: bool | None = True,
         referer: bool | None = True,
         proxy: str | None = None,
-        timeout: float | None = 30,
+        timeout: float | None = None,
         follow_redirects: bool | None = True,
         max_redirects: int | None = 20,
         verify: bool | None = True,
@@ -154,22 +282,76 @@ class Client(RClient):
         client_pem_data: bytes | None = None,
         https_only: bool | None = False,
         http2_only: bool | None = False,
+        max_download_rate: float | str | None = None,
+        max_upload_rate: float | str | None = None,
+        low_speed_limit: int | None = None,
+        low_speed_time: float | None = None,
+        dns_cache_ttl: float | None = None,
+        dns_cache_negative_ttl: float | None = None,
+        reject_third_party_cookies: bool | None = False,
+        blocked_cookie_domains: list[str] | None = None,
+        max_cookies_per_domain: int | None = None,
+        max_cookie_size: int | None = None,
+        cookie_public_suffix: bool | None = False,
+        auth_mode: Literal["preemptive", "on_challenge"] | None = "preemptive",
+        sign: Callable[[str, str, dict[str, str], bytes | None], dict[str, str] | None] | None = None,
+        history_size: int | None = None,
+        respect_robots_txt: bool = False,
+        robots_cache_ttl: float = 3600.0,
+        allow_hosts: list[str] | None = None,
+        block_hosts: list[str] | None = None,
+        block_private_ips: bool = False,
+        allowed_schemes: list[str] | None = None,
+        max_header_bytes: int | None = None,
+        max_header_count: int | None = None,
+        max_decompressed_size: int | None = None,
+        max_decompression_ratio: float | None = None,
+        json_serializer: Callable[[Any], Any] | None = None,
+        json_canonical: bool = False,
+        json_sort_keys: bool = False,
+        json_ensure_ascii: bool = False,
+        json_separators: tuple[str, str] | None = None,
+        json_indent: int | None = None,
+        on_connection_event: Callable[[dict[str, Any]], None] | None = None,
+        http2_keep_alive_interval: float | None = None,
+        http2_keep_alive_timeout: float | None = None,
+        http2_keep_alive_while_idle: bool | None = None,
+        retry_idempotent_on_connection_error: bool = True,
+        cookie_merge: Literal["merge", "replace"] = "merge",
+        connect_timeout: float | None = 30,
+        read_timeout: float | None = 30,
+        trust_env: bool = True,
+        base_url: str | None = None,
+        codecs: dict[str, tuple[Callable[[bytes], bytes] | None, Callable[[bytes], bytes] | None]] | None = None,
+        request_encoding: str | None = None,
+        url_credentials: bool = True,
+        transport: PythonTransport | None = None,
+        send_content_digest: Literal["sha-256", "sha-512"] | None = None,
+        allow_sni_mismatch: bool = False,
+        alpn_protocols: list[Literal["h2", "http/1.1"]] | None = None,
+        tls_session_tickets: bool = True,
+        tls_session_cache_size: int | None = None,
+        verify_hook: Callable[[str, list[bytes], str | None], bool] | None = None,
     ):
         """
         Initialize an HTTP client.
 
         Args:
-            auth: Basic auth credentials as (username, password). Password can be None.
+            auth: Basic auth credentials as (username, password), or an auth object
+                (HmacAuth, ApiKeyAuth) applied to every request. Password can be None.
             auth_bearer: Bearer token for Authorization header.
             params: Default query parameters to include in all requests.
-            headers: Default headers to send with all requests.
+            headers: Default headers to send with all requests. A value may be a callable returning it
+                (or None to leave the header out), called for every attempt: each redirect hop and retry
+                gets a fresh value, e.g. `{"X-Timestamp": lambda: str(int(time.time()))}`.
             cookies: Default cookies to send with all requests.
             cookie_store: Enable persistent cookie store. Cookies from responses will be
                 preserved and included in subsequent requests. Default is True.
             referer: Automatically set Referer header. Default is True.
             proxy: Proxy URL (e.g., "http://proxy:8080" or "socks5://127.0.0.1:1080").
-                Falls back to HTTPR_PROXY environment variable.
-            timeout: Request timeout in seconds. Default is 30.
+                Falls back to the HTTPR_PROXY environment variable (see trust_env).
+            timeout: Total timeout for each request in seconds, response body included. Default is
+                None (no overall limit); connect_timeout and read_timeout still apply.
             follow_redirects: Follow HTTP redirects. Default is True.
             max_redirects: Maximum redirects to follow. Default is 20.
             verify: Verify SSL certificates. Default is True.
@@ -179,6 +361,131 @@ class Client(RClient):
                 Use this instead of client_pem when you have the certificate in memory.
             https_only: Only allow HTTPS requests. Default is False.
             http2_only: Use HTTP/2 only (False uses HTTP/1.1). Default is False.
+            max_download_rate: Cap on response body throughput, in bytes per second or as a
+                string like "5MB/s". Shared by all requests made with this client.
+            max_upload_rate: Cap on request body throughput, same format as max_download_rate.
+            low_speed_limit: Abort response body transfers whose throughput stays below this many
+                bytes per second for low_speed_time seconds, raising ReadTimeout (curl semantics).
+            low_speed_time: Window for low_speed_limit in seconds. Default is 30.
+            dns_cache_ttl: Cache resolved host addresses in-process for at most this many
+                seconds. Default is None (no cache).
+            dns_cache_negative_ttl: Also cache failed lookups for this many seconds.
+                Requires dns_cache_ttl.
+            reject_third_party_cookies: Ignore cookies from, and send none to, hosts on a
+                different site than the requested URL (e.g. cross-site redirect targets).
+            blocked_cookie_domains: Never store or send cookies for these domains or their
+                subdomains.
+            max_cookies_per_domain: Ignore new cookies once a domain holds this many.
+            max_cookie_size: Ignore cookies whose name and value exceed this many bytes.
+            cookie_public_suffix: Reject cookies scoped to a public suffix such as "co.uk",
+                using the bundled Public Suffix List. Default is False.
+            auth_mode: "preemptive" sends credentials with every request. "on_challenge"
+                withholds them until the server answers 401 with a matching WWW-Authenticate
+                challenge, then repeats the request with them. Default is "preemptive".
+            sign: Called as sign(method, url, headers, body) on every request just before it
+                is sent, after client defaults and auth are applied. url includes the query
+                string and body is None for requests without one. May return a dict of
+                headers to add. Exceptions propagate to the caller.
+            history_size: Keep summaries of the last this many requests (method, url,
+                status, timing, error), returned by history(). Default is None (no history).
+            respect_robots_txt: Check the robots.txt rules of each host (cached) before
+                sending a request to it, raising RobotsDisallowed when the request's
+                User-Agent may not fetch the URL. Default is False.
+            robots_cache_ttl: Seconds to cache the robots.txt rules of a host. Default is 3600.
+            allow_hosts: Only send requests to, and follow redirects to, these hosts. Entries
+                are host names or IP addresses; "*.example.com" matches subdomains.
+                Default is None (any host).
+            block_hosts: Never send requests to these hosts, same format as allow_hosts.
+            block_private_ips: Refuse hosts that are, or resolve to, loopback, private,
+                link-local or other non-public addresses, checked after DNS resolution and
+                for every redirect target. Through a proxy, which resolves host names
+                itself, only IP addresses in URLs are checked. Raises HostBlocked.
+                Default is False.
+            allowed_schemes: URL schemes requests and redirects may use, "http" and/or
+                "https". Other schemes such as "ftp" or "file" raise UnsupportedProtocol.
+                Default is None (both).
+            max_header_bytes: Maximum total size in bytes of the headers of a response, counted
+                as "name: value\r\n" lines. Raises RemoteProtocolError when exceeded.
+                Default is None (no limit).
+            max_header_count: Maximum number of headers of a response. Raises
+                RemoteProtocolError when exceeded. Default is None (no limit).
+            max_decompressed_size: Maximum size in bytes of a decoded (gzip, deflate, brotli,
+                zstd) response body. Raises DecodingError when exceeded. Default is None.
+            max_decompression_ratio: Maximum ratio of decoded to compressed bytes of a response
+                body, enforced once more than 1 MiB has been decoded. Raises DecodingError
+                when exceeded. Default is None.
+            json_serializer: Called with each object in json= that is not a dict, list, tuple,
+                str, number, bool or None, returning a JSON-compatible value to send instead
+                (converted again, so it may contain further objects), or NotImplemented for the
+                built-in conversion of pydantic models (model_dump()), dataclasses, enums,
+                datetimes, UUIDs and public __dict__ attributes.
+            json_canonical: Serialize json= bodies in the JSON Canonicalization Scheme (RFC 8785): no
+                whitespace, object keys sorted and numbers in their shortest form, as APIs verifying a
+                detached signature over the payload require. Default is False.
+            json_sort_keys: Sort the keys of objects in json= bodies, as json.dumps(sort_keys=True).
+                Default is False (insertion order).
+            json_ensure_ascii: Escape non-ASCII characters of json= bodies as \\uXXXX, as
+                json.dumps(ensure_ascii=True). Default is False (UTF-8).
+            json_separators: The (item_separator, key_separator) of json= bodies, e.g. (", ", ": ") as
+                json.dumps uses by default. Default is None ((",", ":"), or (",", ": ") with json_indent).
+            json_indent: Pretty-print json= bodies, indenting each level by this many spaces. Default is
+                None (one line).
+            on_connection_event: Called with a dict for each DNS lookup ("dns_resolved": host,
+                addresses, elapsed, error) and for each response, saying whether its connection was
+                opened for it ("connection_created": url, remote_addr, local_addr, elapsed covering
+                connect and TLS handshake, tls, http2) or reused from the pool ("connection_reused").
+                Exceptions it raises are reported through sys.unraisablehook.
+            http2_keep_alive_interval: Seconds between HTTP/2 PING frames, which keep connections
+                alive and detect dead ones. Default is None (no pings).
+            http2_keep_alive_timeout: Seconds to wait for a PING acknowledgement before closing
+                the connection. Default is 20.
+            http2_keep_alive_while_idle: Also ping connections with no open request. Default is False.
+            retry_idempotent_on_connection_error: Send GET, HEAD, OPTIONS, PUT and DELETE requests once
+                more when connecting fails or the connection breaks before any response arrives (e.g. a
+                stale pooled connection). Timeouts and failed DNS lookups are not retried. Default is True.
+            cookie_merge: How per-request `cookies` combine with the client `cookies`. "merge" sends both,
+                a request cookie replacing a client cookie of the same name; "replace" sends only the
+                request cookies. Default is "merge".
+            connect_timeout: Seconds allowed for connecting, or None for no limit. Default is 30.
+            read_timeout: Seconds a response may go without receiving any data, or None for no limit.
+                Default is 30, which also ends Server-Sent Events and long-poll responses that stay
+                idle for longer; use None for clients reading those.
+            trust_env: Read defaults for arguments left out from the HTTPR_TIMEOUT, HTTPR_VERIFY,
+                HTTPR_PROXY, HTTPR_HTTP_PROXY, HTTPR_HTTPS_PROXY and HTTPR_DEFAULT_HEADERS (a JSON object)
+                environment variables, and use the system proxy settings. Default is True.
+            base_url: Absolute URL that relative request URLs are appended to, e.g. "users" or "/users"
+                on "https://api.example.com/v1" request "https://api.example.com/v1/users". Default is None.
+            codecs: Custom Content-Encodings, as a dict of names to (compress, decompress) pairs of callables
+                taking and returning bytes, either of which may be None. Responses in such an encoding are read
+                whole and decoded, and the names are added to Accept-Encoding. Default is None.
+            request_encoding: Compress request bodies with this encoding: "gzip", "deflate", "br", "zstd" or
+                the name of a codec. Requests setting Content-Encoding are sent as is. Default is None.
+            url_credentials: Send the "user:password@" part of request URLs as basic auth, unless `auth` or
+                `auth_bearer` is set. It is removed from the URL either way, so it never appears in
+                `response.url`, the history or logs. Default is True.
+            transport: A PythonTransport sending the requests through a Python callable instead of the
+                client's own connections, e.g. to route them through an existing SDK or tunnel. Redirects,
+                cookies, auth and decompression are still handled by the client. Default is None.
+            send_content_digest: Attach a Content-Digest header (RFC 9530) with the "sha-256" or "sha-512"
+                digest of every request body; streamed bodies get it as a trailer. Responses carrying a
+                Content-Digest or Digest header are then checked against it as they are read, raising
+                DecodingError on a mismatch. Default is None.
+            allow_sni_mismatch: Send HTTPS requests whose Host header names another host than their TLS
+                server name (`sni_hostname` or the URL host), as for domain fronting or testing CDN routing.
+                Such requests raise ValueError otherwise. Default is False.
+            alpn_protocols: The protocols offered in the TLS handshake (ALPN), in order of preference: "h2"
+                and/or "http/1.1", e.g. ["http/1.1"] to keep servers and middleboxes from choosing HTTP/2.
+                An empty list sends no ALPN extension. Default is None (["h2", "http/1.1"]).
+            tls_session_tickets: Resume TLS sessions from session tickets. With False, TLS 1.2 sessions resume
+                by session ID only and TLS 1.3 sessions, which need tickets, are not resumed. Resumed sessions
+                never send early data (0-RTT), which is not supported. Default is True.
+            tls_session_cache_size: Number of servers whose TLS sessions are kept for resumption, or 0 to never
+                resume sessions. Default is None (256).
+            verify_hook: A callable deciding whether to accept the server certificate of every TLS handshake,
+                called as verify_hook(hostname, chain, error) with the DER-encoded certificate chain (server
+                certificate first) and the reason code of the default verification failing (e.g. "expired"), or
+                None when it passed. A true result accepts the certificate, a false one rejects it, with reason
+                "rejected_by_hook" if it passed. Exceptions it raises are re-raised. Default is None.
 
         Example:
             ```python
@@ -218,6 +525,46 @@ class Client(RClient):
         """
         super().__init__()
 
+    @classmethod
+    def from_file(cls, path: str | os.PathLike[str], profile: str | None = None, **kwargs: Any) -> Self:
+        """
+        Create a client from a TOML or JSON configuration file.
+
+        The top-level keys of the file are client arguments (as for `Client()`), and
+        `profiles` holds named profiles applied on top of them. A profile's `headers`,
+        `cookies` and `params` are merged key by key. Relative `ca_cert_file` and
+        `client_pem` paths are resolved against the file's directory.
+
+        Args:
+            path: Path of the file; `.toml` files are read as TOML (Python 3.11+ or the
+                tomli package), others as JSON.
+            profile: Name of the profile to apply. Default is None (top-level options only).
+            **kwargs: Client arguments overriding those of the file.
+
+        Raises:
+            ValueError: If the profile does not exist or the file has an unknown option.
+
+        Example:
+            ```toml
+            # httpr.toml
+            timeout = 30
+            headers = { "User-Agent" = "billing/1.0" }
+
+            [profiles.staging]
+            base_url = "https://staging.example.com/api"
+            max_download_rate = "10MB/s"
+            ```
+
+            ```python
+            client = httpr.Client.from_file("httpr.toml", profile="staging")
+            response = client.get("invoices")  # https://staging.example.com/api/invoices
+            ```
+        """
+        options = set(inspect.signature(Client.__init__).parameters) - {"self"}
+        config = load_profile(path, profile, options)
+        config.update(kwargs)
+        return cls(**config)
+
     def __enter__(self) -> Client:
         """Enter context manager."""
         return self
@@ -242,12 +589,12 @@ class Client(RClient):
         del self
 
     @property
-    def headers(self) -> dict[str, str]:
-        """Headers configured for this client (case-insensitive access)."""
+    def headers(self) -> dict[str, str | Callable[[], str | None]]:
+        """Headers configured for this client (case-insensitive access), with callables as given."""
         return CaseInsensitiveDict(super().headers)
 
     @headers.setter
-    def headers(self, value: dict[str, str] | None) -> None:
+    def headers(self, value: dict[str, str | Callable[[], str | None]] | None) -> None:
         RClient.headers.__set__(self, value)  # type: ignore[attr-defined]
 
     def request(
@@ -260,21 +607,51 @@ class Client(RClient):
         Make an HTTP request.
 
         Args:
-            method: HTTP method (GET, POST, PUT, PATCH, DELETE, HEAD, OPTIONS).
+            method: HTTP method (GET, POST, PUT, PATCH, DELETE, HEAD, OPTIONS, or a WebDAV method).
             url: Request URL.
             **kwargs: Request parameters (see below).
 
         Keyword Args:
             params (Optional[dict[str, str]]): Query parameters to append to URL.
-            headers (Optional[dict[str, str]]): Request headers (merged with client defaults).
+            headers (Optional[dict[str, str | Callable | None]]): Request headers (merged with client defaults,
+                taking precedence over them). A None value leaves out the client default or generated header;
+                a callable is called for the value of every attempt.
             cookies (Optional[dict[str, str]]): Request cookies (merged with client defaults).
             auth (Optional[tuple[str, Optional[str]]]): Basic auth credentials (overrides client default).
             auth_bearer (Optional[str]): Bearer token (overrides client default).
-            timeout (Optional[float]): Request timeout in seconds (overrides client default).
-            content (Optional[bytes]): Raw bytes for request body.
+            timeout (Optional[float]): Request timeout in seconds (overrides client default); None sends
+                the request without a total timeout.
+            content (Optional[bytes | str]): Raw request body: bytes, a str, any buffer such as bytearray,
+                memoryview or a NumPy array, or an iterator of bytes chunks, such as a generator, streamed as
+                it produces them. bytes and writable C-contiguous buffers are sent without copying; a shared
+                buffer cannot be resized until the request is done, and must not be changed before then.
+            encoding (Optional[str]): Codec used to encode a str `content` (default "utf-8").
             data (Optional[dict[str, Any]]): Form data for request body (application/x-www-form-urlencoded).
             json (Optional[Any]): JSON data for request body (application/json).
-            files (Optional[dict[str, str]]): Files for multipart upload (dict mapping field names to file paths).
+            files (Optional[Mapping[str, str] | Sequence[tuple[str, str]]]): Files for multipart upload, as a
+                dict mapping field names to file paths or a list of (field, path) pairs to repeat a field.
+                Each file is opened only when its part is sent.
+            on_file_progress (Optional[Callable[[dict], None]]): Called with the upload progress of each file
+                part ("field", "filename", "index", "parts", "sent", "total").
+            max_download_rate (Optional[float | str]): Response body rate limit, e.g. "5MB/s" (overrides client default).
+            max_upload_rate (Optional[float | str]): Request body rate limit (overrides client default).
+            low_speed_limit (Optional[int]): Stalled-transfer threshold in bytes/s (overrides client default).
+            low_speed_time (Optional[float]): Window for low_speed_limit in seconds (overrides client default).
+            idempotency_key (Optional[bool | str]): Send an `Idempotency-Key` header: True generates a
+                UUID4 (or keeps one given in `headers`), a string is used as the key.
+            proto (Optional[bytes]): Serialized Protocol Buffers message (or a message object) sent as the
+                body with `Content-Type: application/x-protobuf`.
+            cancel_token (Optional[CancellationToken]): Token that aborts the request when cancelled,
+                raising RequestCancelled.
+            trace_context (Optional[dict[str, str]]): W3C trace headers (`traceparent`, `tracestate`,
+                `baggage`) to send, overriding the context of `httpr.tracing.trace_context()`.
+            raw_query (Optional[str]): Percent-encoded query string appended to the URL verbatim (after
+                `params`), for signature-sensitive URLs. Characters that would need encoding raise ValueError.
+            follow_redirects (Optional[bool]): Follow redirects for this request (overrides client default).
+            max_redirects (Optional[int]): Maximum redirects to follow for this request (overrides client default).
+            sni_hostname (Optional[str]): Send an HTTPS request to this host, which is looked up, connected
+                to and named in the TLS handshake, while the Host header names the host of the URL. A Host
+                header differing from it requires the client's `allow_sni_mismatch`.
 
         Returns:
             Response object with status, headers, and body.
@@ -292,13 +669,62 @@ class Client(RClient):
         Note:
             Only one of `content`, `data`, `json`, or `files` can be specified per request.
         """
-        if method not in ["GET", "HEAD", "OPTIONS", "DELETE", "POST", "PUT", "PATCH"]:
+        if method not in _METHODS:
             raise ValueError(f"Unsupported HTTP method: {method}")
         if "params" in kwargs and kwargs["params"] is not None:
             kwargs["params"] = {k: str(v) for k, v in kwargs["params"].items()}
+        _apply_idempotency_key(kwargs)
+        _apply_proto(kwargs)
+        _apply_trace_context(kwargs)
 
         return super().request(method=method, url=url, **kwargs)
 
+    def request_later(
+        self,
+        delay: float,
+        method: HttpMethod,
+        url: str,
+        **kwargs: Unpack[RequestParams],
+    ) -> Future[Response]:
+        """
+        Send a request after `delay` seconds, from a background thread.
+
+        Args:
+            delay: Seconds to wait before sending.
+            method: HTTP method.
+            url: Request URL.
+            **kwargs: Request parameters (same as request()), including `cancel_token`.
+
+        Returns:
+            A future resolving to the Response, or failing with the request's exception.
+            Cancelling the future before the delay has passed skips the request; a
+            `cancel_token` also aborts it once sent.
+
+        Example:
+            ```python
+            token = httpr.CancellationToken()
+            future = client.request_later(2.0, "GET", "https://example.com", cancel_token=token)
+            token.cancel()  # future fails with RequestCancelled
+            ```
+        """
+        if delay < 0:
+            raise ValueError("delay must not be negative")
+        _apply_trace_context(kwargs)
+        future: Future[Response] = Future()
+
+        def send() -> None:
+            if not future.set_running_or_notify_cancel():
+                return
+            try:
+                future.set_result(Client.request(self, method, url, **kwargs))
+            except BaseException as e:
+                future.set_exception(e)
+
+        timer = threading.Timer(delay, send)
+        timer.daemon = True
+        timer.start()
+        return future
+
     def get(self, url: str, **kwargs: Unpack[RequestParams]) -> Response:
         """
         Make a GET request.
@@ -391,15 +817,17 @@ class Client(RClient):
 
         Keyword Args:
             params (Optional[dict[str, str]]): Query parameters.
-            headers (Optional[dict[str, str]]): Request headers.
+            headers (Optional[dict[str, str | None]]): Request headers; None removes a default header.
             cookies (Optional[dict[str, str]]): Request cookies.
             auth (Optional[tuple[str, Optional[str]]]): Basic auth credentials.
             auth_bearer (Optional[str]): Bearer token.
             timeout (Optional[float]): Request timeout.
-            content (Optional[bytes]): Raw bytes body.
+            content (Optional[bytes | str]): Raw body: bytes, str, or a buffer such as bytearray.
+            encoding (Optional[str]): Codec for a str `content`.
             data (Optional[dict[str, Any]]): Form-encoded body.
             json (Optional[Any]): JSON body.
-            files (Optional[dict[str, str]]): Multipart file uploads.
+            files (Optional[Mapping[str, str] | Sequence[tuple[str, str]]]): Multipart file uploads.
+            on_file_progress (Optional[Callable[[dict], None]]): Upload progress of each file part.
 
         Returns:
             Response object.
@@ -469,6 +897,352 @@ class Client(RClient):
         """
         return self.request(method="PATCH", url=url, **kwargs)
 
+    def get_json(self, url: str, **kwargs: Unpack[RequestParams]) -> Any:
+        """
+        GET a JSON API resource and return the parsed body.
+
+        Sends `Accept: application/json` unless `headers` has an `Accept`, raises for an
+        error status and checks that the response is JSON before parsing it.
+
+        Args:
+            url: Request URL.
+            **kwargs: Request parameters (params, headers, cookies, auth, auth_bearer, timeout).
+
+        Returns:
+            The parsed JSON body, or None for an empty body (e.g. `204 No Content`).
+
+        Raises:
+            HTTPStatusError: If the status is 4xx/5xx. Its `error_body` is the parsed JSON error
+                body, if there is one.
+            DecodingError: If the response is not `application/json` or `+json`. Its `response`
+                is the response.
+
+        Example:
+            ```python
+            try:
+                user = client.get_json("https://api.example.com/users/1")
+            except httpr.HTTPStatusError as e:
+                print(e.status_code, e.error_body)
+            ```
+        """
+        return _json_body(self.request(method="GET", url=url, **_accept_json(kwargs)))
+
+    def post_json(self, url: str, json: Any = None, **kwargs: Unpack[RequestParams]) -> Any:
+        """
+        POST `json` to a JSON API and return the parsed response body.
+
+        Checks the response like `get_json()`.
+
+        Args:
+            url: Request URL.
+            json: Request body, sent as JSON.
+            **kwargs: Request parameters (params, headers, cookies, auth, auth_bearer, timeout).
+
+        Returns:
+            The parsed JSON body, or None for an empty body (e.g. `204 No Content`).
+
+        Raises:
+            HTTPStatusError: If the status is 4xx/5xx, with the parsed JSON error body as `error_body`.
+            DecodingError: If the response is not `application/json` or `+json`.
+
+        Example:
+            ```python
+            created = client.post_json("https://api.example.com/users", {"name": "Ada"})
+            ```
+        """
+        return _json_body(self.request(method="POST", url=url, json=json, **_accept_json(kwargs)))
+
+    def propfind(
+        self,
+        url: str,
+        depth: int | str = 1,
+        properties: list[str] | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> list[DavResource]:
+        """
+        Make a WebDAV PROPFIND request and parse the multistatus response.
+
+        Args:
+            url: Resource or collection URL.
+            depth: `0` (the resource itself), `1` (plus direct children) or `"infinity"`.
+            properties: Property names to fetch, bare for `DAV:` properties or in Clark
+                notation (`"{http://owncloud.org/ns}fileid"`). `None` fetches all properties.
+            **kwargs: Request parameters (params, headers, cookies, auth, auth_bearer, timeout).
+
+        Returns:
+            One entry per resource with its `href`, `status` and `properties`.
+
+        Raises:
+            HTTPStatusError: If the server does not answer with `207 Multi-Status`.
+            ValueError: If the response body is not a valid multistatus document.
+
+        Example:
+            ```python
+            for item in client.propfind(
+                "https://cloud.example.com/remote.php/dav/files/alice/",
+                properties=["displayname", "getcontentlength", "resourcetype"],
+            ):
+                print(item["href"], item["properties"])
+            ```
+        """
+        headers = {"Depth": str(depth), "Content-Type": "application/xml; charset=utf-8"}
+        kwargs["headers"] = {**headers, **(kwargs.get("headers") or {})}
+        kwargs["content"] = build_propfind(properties)
+        response = Client.request(self, method="PROPFIND", url=url, **kwargs)
+        if response.status_code != 207:
+            raise HTTPStatusError(f"PROPFIND {url} returned {response.status_code}, expected 207")
+        return parse_multistatus(response.content)
+
+    def mkcol(self, url: str, **kwargs: Unpack[RequestParams]) -> Response:
+        """
+        Make a WebDAV MKCOL request, creating a collection (directory).
+
+        Args:
+            url: URL of the collection to create.
+            **kwargs: Request parameters (params, headers, cookies, auth, auth_bearer, timeout).
+
+        Returns:
+            Response object (`201 Created` on success).
+        """
+        return Client.request(self, method="MKCOL", url=url, **kwargs)
+
+    def copy(
+        self,
+        url: str,
+        destination: str,
+        overwrite: bool = True,
+        depth: int | str = "infinity",
+        **kwargs: Unpack[RequestParams],
+    ) -> Response:
+        """
+        Make a WebDAV COPY request.
+
+        Args:
+            url: Source resource URL.
+            destination: Target URL (sent as the `Destination` header).
+            overwrite: Replace an existing resource at `destination`.
+            depth: `0` copies a collection without its members, `"infinity"` copies everything.
+            **kwargs: Request parameters (params, headers, cookies, auth, auth_bearer, timeout).
+
+        Returns:
+            Response object (`201 Created` or `204 No Content` on success). A `207` body
+            describing partial failures can be read with `httpr.webdav.parse_multistatus`.
+        """
+        headers = {"Destination": destination, "Overwrite": "T" if overwrite else "F", "Depth": str(depth)}
+        kwargs["headers"] = {**headers, **(kwargs.get("headers") or {})}
+        return Client.request(self, method="COPY", url=url, **kwargs)
+
+    def move(
+        self,
+        url: str,
+        destination: str,
+        overwrite: bool = True,
+        **kwargs: Unpack[RequestParams],
+    ) -> Response:
+        """
+        Make a WebDAV MOVE request.
+
+        Args:
+            url: Source resource URL.
+            destination: Target URL (sent as the `Destination` header).
+            overwrite: Replace an existing resource at `destination`.
+            **kwargs: Request parameters (params, headers, cookies, auth, auth_bearer, timeout).
+
+        Returns:
+            Response object (`201 Created` or `204 No Content` on success). A `207` body
+            describing partial failures can be read with `httpr.webdav.parse_multistatus`.
+        """
+        headers = {"Destination": destination, "Overwrite": "T" if overwrite else "F"}
+        kwargs["headers"] = {**headers, **(kwargs.get("headers") or {})}
+        return Client.request(self, method="MOVE", url=url, **kwargs)
+
+    def list_directory(
+        self,
+        url: str,
+        webdav: bool | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> list[DirectoryEntry]:
+        """
+        List a directory from its WebDAV collection or its auto-index HTML page.
+
+        Without `webdav`, a `Depth: 1` PROPFIND is tried first and the index page is
+        fetched when the server does not answer it with `207 Multi-Status`.
+
+        Args:
+            url: Directory URL.
+            webdav: `True` to only use PROPFIND, `False` to only read the index page.
+            **kwargs: Request parameters (params, headers, cookies, auth, auth_bearer, timeout).
+
+        Returns:
+            One entry per file or subdirectory with its `name`, `url`, `size`, `mtime` and
+            `is_dir`. Unknown sizes and times are `None`; index page times are taken as UTC.
+
+        Raises:
+            HTTPStatusError: If the directory cannot be read (4xx/5xx).
+
+        Example:
+            ```python
+            for entry in client.list_directory("https://mirror.example.com/pub/releases/"):
+                if not entry["is_dir"]:
+                    client.download_parallel(entry["url"], entry["name"])
+            ```
+        """
+        if webdav is not False:
+            dav_kwargs = dict(kwargs)
+            headers = {"Depth": "1", "Content-Type": "application/xml; charset=utf-8"}
+            dav_kwargs["headers"] = {**headers, **(kwargs.get("headers") or {})}
+            dav_kwargs["content"] = build_propfind(["resourcetype", "getcontentlength", "getlastmodified"])
+            response = Client.request(self, method="PROPFIND", url=url, **dav_kwargs)
+            if response.status_code == 207:
+                return from_multistatus(parse_multistatus(response.content), response.url)
+            if webdav or response.status_code in (401, 403, 404, 407):
+                raise HTTPStatusError(f"PROPFIND {url} returned {response.status_code}, expected 207")
+        response = Client.request(self, method="GET", url=url, **kwargs)
+        if response.status_code >= 400:
+            raise HTTPStatusError(f"GET {url} returned {response.status_code}")
+        return parse_index(response.text, response.url)
+
+    def sync(
+        self,
+        files: Mapping[str, str | os.PathLike[str]],
+        state_file: str | os.PathLike[str] | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> dict[str, SyncResult]:
+        """
+        Mirror URLs to local files, downloading only what changed since the last sync.
+
+        The `ETag` and `Last-Modified` of each download are saved in `state_file` and sent
+        back as `If-None-Match` / `If-Modified-Since` next time, so unchanged resources are
+        answered with `304 Not Modified` and left alone. Files are written to a temporary
+        file and renamed into place, and a missing local file is always downloaded again.
+
+        Args:
+            files: Local path for each URL.
+            state_file: JSON file holding the validators between runs. Without one, every
+                URL is downloaded.
+            **kwargs: Request parameters (params, headers, cookies, auth, auth_bearer, timeout).
+
+        Returns:
+            A result per URL: `status` (`"created"`, `"updated"`, `"unchanged"` or
+            `"failed"`), `path`, `status_code`, `bytes` written and the `error` of a failed URL.
+            A failed URL does not stop the others.
+
+        Example:
+            ```python
+            report = client.sync(
+                {"https://cdn.example.com/config.json": "config/config.json"},
+                state_file="config/.sync-state.json",
+            )
+            changed = [url for url, result in report.items() if result["status"] in ("created", "updated")]
+            ```
+        """
+        state = load_state(state_file)
+        report: dict[str, SyncResult] = {}
+        try:
+            for url, path in files.items():
+                report[url] = self._sync_one(url, path, state, kwargs)
+        finally:
+            if state_file is not None:
+                save_state(state_file, state)
+        return report
+
+    def _sync_one(
+        self, url: str, path: str | os.PathLike[str], state: dict[str, Validators], kwargs: dict[str, Any]
+    ) -> SyncResult:
+        headers = {**conditional_headers(state.get(url), path), **(kwargs.get("headers") or {})}
+        existed = os.path.exists(path)
+        status_code = None
+        try:
+            with Client.stream(self, "GET", url, **{**kwargs, "headers": headers}) as response:
+                status_code = response.status_code
+                if status_code == 304:
+                    return SyncResult(status="unchanged", path=str(path), status_code=304, bytes=0, error=None)
+                if not 200 <= status_code < 300:
+                    raise HTTPStatusError(f"GET {url} returned {status_code}")
+                written = write_atomic(path, response.iter_bytes())
+                validators = Validators()
+                if etag := response.headers.get("etag"):
+                    validators["etag"] = etag
+                if last_modified := response.headers.get("last-modified"):
+                    validators["last_modified"] = last_modified
+        except Exception as e:
+            return SyncResult(status="failed", path=str(path), status_code=status_code, bytes=0, error=str(e))
+        if validators:
+            state[url] = validators
+        else:
+            state.pop(url, None)
+        return SyncResult(
+            status="updated" if existed else "created",
+            path=str(path),
+            status_code=status_code,
+            bytes=written,
+            error=None,
+        )
+
+    def soap(
+        self,
+        url: str,
+        action: str,
+        envelope: str | bytes | dict[str, Any],
+        version: SoapVersion | None = None,
+        header: dict[str, Any] | None = None,
+        namespace: str | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> SoapResponse:
+        """
+        Call a SOAP operation and parse the response envelope.
+
+        Sends `SOAPAction` and `text/xml` for SOAP 1.1, or the `action` parameter of
+        `application/soap+xml` for SOAP 1.2.
+
+        Args:
+            url: Service endpoint URL.
+            action: The SOAP action URI of the operation.
+            envelope: A complete envelope as XML, or a dict of `Body` elements to build one from
+                (see `httpr.soap.build_envelope`).
+            version: `"1.1"` or `"1.2"`. Defaults to the namespace of an XML envelope, or `"1.1"`.
+            header: `Header` elements, for an envelope built from a dict.
+            namespace: Namespace of dict element names given without one.
+            **kwargs: Request parameters (params, headers, cookies, auth, auth_bearer, timeout).
+
+        Returns:
+            The response `Header` and `Body` as dicts keyed by local element names.
+
+        Raises:
+            SoapFault: If the response holds a SOAP `Fault`.
+            HTTPStatusError: If the response has a 4xx/5xx status without a `Fault`.
+            ValueError: If the envelope or the response body is not a SOAP envelope.
+
+        Example:
+            ```python
+            result = client.soap(
+                "https://example.com/stock",
+                "urn:example:stock#GetPrice",
+                {"GetPrice": {"Symbol": "ACME"}},
+                namespace="urn:example:stock",
+            )
+            print(result["body"]["GetPriceResponse"]["Price"])
+            ```
+        """
+        if isinstance(envelope, dict):
+            version = version or "1.1"
+            content = build_envelope(envelope, version, header=header, namespace=namespace)
+        else:
+            content = envelope.encode() if isinstance(envelope, str) else envelope
+            version = version or envelope_version(content)
+        kwargs["headers"] = {**request_headers(action, version), **(kwargs.get("headers") or {})}
+        kwargs["content"] = content
+        response = Client.request(self, method="POST", url=url, **kwargs)
+        try:
+            result = parse_envelope(response.content, response)
+        except ValueError:
+            if response.status_code >= 400:
+                raise HTTPStatusError(f"SOAP {action} returned {response.status_code}") from None
+            raise
+        if response.status_code >= 400:
+            raise HTTPStatusError(f"SOAP {action} returned {response.status_code}")
+        return result
+
     @contextmanager
     def stream(
         self,
@@ -526,14 +1300,26 @@ class Client(RClient):
                     pass  # Don't read the body
             ```
 
+            Saving to a file:
+
+            ```python
+            with client.stream("GET", url) as response:
+                response.raise_for_status()
+                response.stream_to("large-file.zip")
+            ```
+
         Note:
-            The response body is only read when you iterate over it or call read().
-            Always use this as a context manager to ensure proper cleanup.
+            The response body is only read when you iterate over it or call read(),
+            read_into() or stream_to(). Leaving the context manager closes the response,
+            so an unread body is never downloaded.
         """
-        if method not in ["GET", "HEAD", "OPTIONS", "DELETE", "POST", "PUT", "PATCH"]:
+        if method not in _METHODS:
             raise ValueError(f"Unsupported HTTP method: {method}")
         if "params" in kwargs and kwargs["params"] is not None:
             kwargs["params"] = {k: str(v) for k, v in kwargs["params"].items()}
+        _apply_idempotency_key(kwargs)
+        _apply_proto(kwargs)
+        _apply_trace_context(kwargs)
 
         response = super()._stream(method=method, url=url, **kwargs)
         try:
@@ -649,10 +1435,12 @@ class AsyncClient(Client):
             response = await client.request("GET", "https://httpbin.org/get")
             ```
         """
-        if method not in ["GET", "HEAD", "OPTIONS", "DELETE", "POST", "PUT", "PATCH"]:
+        if method not in _METHODS:
             raise ValueError(f"Unsupported HTTP method: {method}")
         if "params" in kwargs and kwargs["params"] is not None:
             kwargs["params"] = {k: str(v) for k, v in kwargs["params"].items()}
+        # Executor threads do not see the context variables of the calling task
+        _apply_trace_context(kwargs)
 
         return await self._run_sync_asyncio(super().request, method=method, url=url, **kwargs)
 
@@ -788,6 +1576,381 @@ class AsyncClient(Client):
         """
         return await self.request(method="PATCH", url=url, **kwargs)
 
+    async def get_json(self, url: str, **kwargs: Unpack[RequestParams]) -> Any:  # type: ignore[override]
+        """
+        GET a JSON API resource asynchronously and return the parsed body.
+
+        Args:
+            url: Request URL.
+            **kwargs: Request parameters.
+
+        Returns:
+            The parsed JSON body, or None for an empty body.
+        """
+        return _json_body(await self.request(method="GET", url=url, **_accept_json(kwargs)))
+
+    async def post_json(  # type: ignore[override]
+        self,
+        url: str,
+        json: Any = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> Any:
+        """
+        POST `json` to a JSON API asynchronously and return the parsed response body.
+
+        Args:
+            url: Request URL.
+            json: Request body, sent as JSON.
+            **kwargs: Request parameters.
+
+        Returns:
+            The parsed JSON body, or None for an empty body.
+        """
+        return _json_body(await self.request(method="POST", url=url, json=json, **_accept_json(kwargs)))
+
+    async def propfind(  # type: ignore[override]
+        self,
+        url: str,
+        depth: int | str = 1,
+        properties: list[str] | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> list[DavResource]:
+        """
+        Make an async WebDAV PROPFIND request and parse the multistatus response.
+
+        Args:
+            url: Resource or collection URL.
+            depth: `0`, `1` or `"infinity"`.
+            properties: Property names to fetch. `None` fetches all properties.
+            **kwargs: Request parameters.
+
+        Returns:
+            One entry per resource with its `href`, `status` and `properties`.
+        """
+        return await self._run_sync_asyncio(super().propfind, url, depth=depth, properties=properties, **kwargs)
+
+    async def mkcol(self, url: str, **kwargs: Unpack[RequestParams]) -> Response:  # type: ignore[override]
+        """
+        Make an async WebDAV MKCOL request.
+
+        Args:
+            url: URL of the collection to create.
+            **kwargs: Request parameters.
+
+        Returns:
+            Response object.
+        """
+        return await self._run_sync_asyncio(super().mkcol, url, **kwargs)
+
+    async def copy(  # type: ignore[override]
+        self,
+        url: str,
+        destination: str,
+        overwrite: bool = True,
+        depth: int | str = "infinity",
+        **kwargs: Unpack[RequestParams],
+    ) -> Response:
+        """
+        Make an async WebDAV COPY request.
+
+        Args:
+            url: Source resource URL.
+            destination: Target URL.
+            overwrite: Replace an existing resource at `destination`.
+            depth: `0` or `"infinity"`.
+            **kwargs: Request parameters.
+
+        Returns:
+            Response object.
+        """
+        return await self._run_sync_asyncio(super().copy, url, destination, overwrite=overwrite, depth=depth, **kwargs)
+
+    async def move(  # type: ignore[override]
+        self,
+        url: str,
+        destination: str,
+        overwrite: bool = True,
+        **kwargs: Unpack[RequestParams],
+    ) -> Response:
+        """
+        Make an async WebDAV MOVE request.
+
+        Args:
+            url: Source resource URL.
+            destination: Target URL.
+            overwrite: Replace an existing resource at `destination`.
+            **kwargs: Request parameters.
+
+        Returns:
+            Response object.
+        """
+        return await self._run_sync_asyncio(super().move, url, destination, overwrite=overwrite, **kwargs)
+
+    async def list_directory(  # type: ignore[override]
+        self,
+        url: str,
+        webdav: bool | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> list[DirectoryEntry]:
+        """
+        List a directory asynchronously from its WebDAV collection or auto-index page.
+
+        Args:
+            url: Directory URL.
+            webdav: `True` to only use PROPFIND, `False` to only read the index page.
+            **kwargs: Request parameters.
+
+        Returns:
+            One entry per file or subdirectory.
+        """
+        return await self._run_sync_asyncio(super().list_directory, url, webdav=webdav, **kwargs)
+
+    async def sync(  # type: ignore[override]
+        self,
+        files: Mapping[str, str | os.PathLike[str]],
+        state_file: str | os.PathLike[str] | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> dict[str, SyncResult]:
+        """
+        Mirror URLs to local files asynchronously, downloading only what changed.
+
+        Args:
+            files: Local path for each URL.
+            state_file: JSON file holding the validators between runs.
+            **kwargs: Request parameters.
+
+        Returns:
+            A result per URL.
+        """
+        return await self._run_sync_asyncio(super().sync, files, state_file=state_file, **kwargs)
+
+    async def soap(  # type: ignore[override]
+        self,
+        url: str,
+        action: str,
+        envelope: str | bytes | dict[str, Any],
+        version: SoapVersion | None = None,
+        header: dict[str, Any] | None = None,
+        namespace: str | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> SoapResponse:
+        """
+        Call a SOAP operation asynchronously and parse the response envelope.
+
+        Args:
+            url: Service endpoint URL.
+            action: The SOAP action URI of the operation.
+            envelope: A complete envelope as XML, or a dict of `Body` elements.
+            version: `"1.1"` or `"1.2"`.
+            header: `Header` elements, for an envelope built from a dict.
+            namespace: Namespace of dict element names given without one.
+            **kwargs: Request parameters.
+
+        Returns:
+            The response `Header` and `Body` as dicts.
+        """
+        return await self._run_sync_asyncio(
+            super().soap, url, action, envelope, version=version, header=header, namespace=namespace, **kwargs
+        )
+
+    async def download_parallel(  # type: ignore[override]
+        self,
+        url: str,
+        path: str | os.PathLike[str],
+        connections: int = 8,
+        **kwargs: Any,
+    ) -> int:
+        """
+        Download a file asynchronously using concurrent byte-range requests.
+
+        Args:
+            url: The URL to download.
+            path: Destination file path.
+            connections: Maximum number of concurrent range requests.
+            **kwargs: params, headers, cookies, auth, auth_bearer, timeout, sha256.
+
+        Returns:
+            The number of bytes written.
+        """
+        return await self._run_sync_asyncio(super().download_parallel, url, path, connections=connections, **kwargs)
+
+    async def upload_tus(  # type: ignore[override]
+        self,
+        url: str,
+        path: str | os.PathLike[str],
+        **kwargs: Any,
+    ) -> dict[str, Any]:
+        """
+        Upload a file asynchronously with the tus resumable upload protocol.
+
+        Args:
+            url: The tus creation endpoint.
+            path: The file to upload.
+            **kwargs: chunk_size, metadata, upload_url, checksum, retries, headers, timeout,
+                max_upload_rate, on_progress.
+
+        Returns:
+            The upload URL, file size and the offset the upload resumed from.
+        """
+        return await self._run_sync_asyncio(super().upload_tus, url, path, **kwargs)
+
+    async def upgrade(  # type: ignore[override]
+        self,
+        url: str,
+        protocol: str,
+        **kwargs: Any,
+    ) -> Stream:
+        """
+        Upgrade the connection of a request to another protocol asynchronously.
+
+        The reads and writes of the returned `Stream` block; run them with
+        `asyncio.to_thread()` or in an executor.
+
+        Args:
+            url: The URL to upgrade.
+            protocol: The `Upgrade` header value.
+            **kwargs: method, headers, timeout.
+
+        Returns:
+            The connection as a raw `Stream`.
+        """
+        return await self._run_sync_asyncio(super().upgrade, url, protocol, **kwargs)
+
+    async def connect_tunnel(  # type: ignore[override]
+        self,
+        host: str,
+        port: int,
+        via_proxy: bool = True,
+        **kwargs: Any,
+    ) -> Stream:
+        """
+        Open a raw TCP tunnel to `host:port` asynchronously.
+
+        The reads and writes of the returned `Stream` block; run them with
+        `asyncio.to_thread()` or in an executor.
+
+        Args:
+            host: The host to connect to.
+            port: The port to connect to.
+            via_proxy: Tunnel through the proxy requests to the host would use, with CONNECT.
+            **kwargs: headers, timeout.
+
+        Returns:
+            The tunnel as a raw `Stream`.
+        """
+        return await self._run_sync_asyncio(super().connect_tunnel, host, port, via_proxy, **kwargs)
+
+    async def send(self, request: Request) -> Response:  # type: ignore[override]
+        """
+        Send a prepared Request asynchronously.
+
+        Args:
+            request: The Request to send.
+
+        Returns:
+            Response object.
+        """
+        return await self._run_sync_asyncio(super().send, request)
+
+    async def send_template_many(  # type: ignore[override]
+        self,
+        template: RequestTemplate,
+        vars: list[dict[str, Any]],
+        max_concurrency: int | None = None,
+        return_exceptions: bool = False,
+        dedupe_on: tuple[str, ...] | list[str] | None = None,
+    ) -> list[Response | Exception]:
+        """
+        Render a RequestTemplate for each entry in `vars` and send the requests concurrently.
+
+        Args:
+            template: The RequestTemplate to render.
+            vars: One dict of placeholder values per request.
+            max_concurrency: Maximum number of requests in flight at once.
+            return_exceptions: Return failed requests as exception instances instead of raising.
+            dedupe_on: Request parts that make two rendered requests identical; each distinct
+                request is sent once.
+
+        Returns:
+            Responses in the same order as `vars`.
+        """
+        return await self._run_sync_asyncio(
+            super().send_template_many,
+            template,
+            vars,
+            max_concurrency=max_concurrency,
+            return_exceptions=return_exceptions,
+            dedupe_on=dedupe_on,
+        )
+
+    async def fetch_all(  # type: ignore[override]
+        self,
+        urls: list[str],
+        max_concurrency: int = 32,
+        ordered: bool = False,
+        **kwargs: Any,
+    ) -> AsyncIterator[Response | StreamingResponse | Exception]:
+        """
+        Fetch many URLs concurrently, yielding each response as it completes.
+
+        Accepts the same parameters as `Client.fetch_all()`. The requests run in the
+        executor while the next result is awaited.
+
+        Example:
+            ```python
+            async for result in client.fetch_all(urls, max_concurrency=16):
+                if not isinstance(result, Exception):
+                    print(result.url, result.status_code)
+            ```
+        """
+        results = super().fetch_all(urls, max_concurrency=max_concurrency, ordered=ordered, **kwargs)
+        done = object()
+        while (result := await self._run_sync_asyncio(next, results, done)) is not done:
+            yield result
+
+    async def robots(self, url: str, **kwargs: Any) -> RobotsTxt:  # type: ignore[override]
+        """
+        Fetch and parse the robots.txt file governing `url` asynchronously.
+
+        Accepts the same parameters as `Client.robots()`.
+        """
+        return await self._run_sync_asyncio(super().robots, url, **kwargs)
+
+    async def sitemap(self, url: str, **kwargs: Any) -> AsyncIterator[str]:  # type: ignore[override]
+        """
+        Iterate asynchronously over the page URLs of a sitemap.
+
+        Accepts the same parameters as `Client.sitemap()`. Each sitemap is fetched in the
+        executor when the entries of the previous one are used up.
+        """
+        entries = super().sitemap(url, **kwargs)
+        done = object()
+        while (entry := await self._run_sync_asyncio(next, entries, done)) is not done:
+            yield entry
+
+    async def paginate(  # type: ignore[override]
+        self,
+        url: str,
+        strategy: Literal["cursor", "page", "offset", "link"] = "cursor",
+        **kwargs: Any,
+    ) -> AsyncIterator[Any]:
+        """
+        Iterate asynchronously over the items of a paginated JSON API.
+
+        Accepts the same parameters as `Client.paginate()`. Each page is fetched in
+        the executor when the previous one is used up.
+
+        Example:
+            ```python
+            async for user in client.paginate(url, item_path="data.items", next_path="data.next_cursor"):
+                print(user["id"])
+            ```
+        """
+        paginator = super().paginate(url, strategy=strategy, **kwargs)
+        done = object()
+        while (item := await self._run_sync_asyncio(next, paginator, done)) is not done:
+            yield item
+
     @asynccontextmanager
     async def stream(  # type: ignore[override]
         self,
@@ -821,10 +1984,13 @@ class AsyncClient(Client):
             iter_lines). The async part is initiating the request and entering
             the context manager.
         """
-        if method not in ["GET", "HEAD", "OPTIONS", "DELETE", "POST", "PUT", "PATCH"]:
+        if method not in _METHODS:
             raise ValueError(f"Unsupported HTTP method: {method}")
         if "params" in kwargs and kwargs["params"] is not None:
             kwargs["params"] = {k: str(v) for k, v in kwargs["params"].items()}
+        _apply_idempotency_key(kwargs)
+        _apply_proto(kwargs)
+        _apply_trace_context(kwargs)
 
         # Run the sync _stream in executor
         response = await self._run_sync_asyncio(super(Client, self)._stream, method=method, url=url, **kwargs)
@@ -1033,6 +2199,7 @@ def patch(url: str, **kwargs: Unpack[ClientRequestParams]) -> Response:
 
 # Import exceptions from the Rust module
 from .httpr import (  # noqa: E402
+    CertificateVerifyError,
     CloseError,
     # Network exceptions
     ConnectError,
@@ -1040,8 +2207,10 @@ from .httpr import (  # noqa: E402
     ConnectTimeout,
     CookieConflict,
     DecodingError,
+    DNSError,
     # Base exceptions
     HTTPError,
+    HostBlocked,
     HTTPStatusError,
     # Other exceptions
     InvalidURL,
@@ -1054,9 +2223,12 @@ from .httpr import (  # noqa: E402
     ReadError,
     ReadTimeout,
     RemoteProtocolError,
+    RequestCancelled,
     RequestError,
     RequestNotRead,
     ResponseNotRead,
+    RobotsDisallowed,
+    SSLError,
     StreamClosed,
     # Stream exceptions
     StreamConsumed,
@@ -1082,10 +2254,34 @@ __all__ = [
     "post",
     "put",
     "patch",
+    "presign_url",
+    "runtime_stats",
+    "RuntimeStats",
+    "HmacAuth",
+    "ApiKeyAuth",
+    "PythonTransport",
+    "TransportRequest",
+    # Request classes
+    "Request",
+    "RequestTemplate",
     # Response classes
     "Response",
     "StreamingResponse",
+    "Stream",
     "CaseInsensitiveHeaderMap",
+    "ProblemDetails",
+    "ServerTiming",
+    "SniffResult",
+    "Paginator",
+    "FetchIterator",
+    "MultipartUploader",
+    "RequestQueue",
+    "CancellationToken",
+    "RobotsTxt",
+    "SitemapIterator",
+    "SoapResponse",
+    "DirectoryEntry",
+    "SyncResult",
     # Base exceptions
     "HTTPError",
     "RequestError",
@@ -1101,6 +2297,9 @@ __all__ = [
     "PoolTimeout",
     # Network exceptions
     "ConnectError",
+    "DNSError",
+    "SSLError",
+    "CertificateVerifyError",
     "ReadError",
     "WriteError",
     "CloseError",
@@ -1111,7 +2310,11 @@ __all__ = [
     "UnsupportedProtocol",
     "ProxyError",
     "TooManyRedirects",
+    "RobotsDisallowed",
+    "HostBlocked",
+    "RequestCancelled",
     "HTTPStatusError",
+    "SoapFault",
     "DecodingError",
     "StreamConsumed",
     "ResponseNotRead",
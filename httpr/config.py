@@ -0,0 +1,84 @@
+"""
+Client configuration files for `Client.from_file()`.
+
+A file is TOML or JSON (by extension). Its top-level keys are `Client` arguments, and
+the `profiles` table holds named profiles applied on top of them:
+
+```toml
+timeout = 30
+headers = { "User-Agent" = "billing/1.0" }
+
+[profiles.staging]
+base_url = "https://staging.example.com/api"
+proxy = "http://proxy.internal:3128"
+```
+
+Mappings (`headers`, `cookies`, `params`) of a profile are merged key by key into the
+top-level ones; other values replace them. Relative `ca_cert_file` and `client_pem`
+paths are resolved against the directory of the file.
+"""
+
+from __future__ import annotations
+
+import json
+import os
+from collections.abc import Container
+from pathlib import Path
+from typing import Any
+
+MERGED_OPTIONS = ("headers", "cookies", "params")
+PATH_OPTIONS = ("ca_cert_file", "client_pem")
+
+
+def read_config(path: Path) -> dict[str, Any]:
+    """Parse a TOML (`.toml`) or JSON (any other extension) configuration file."""
+    if path.suffix.lower() == ".toml":
+        try:
+            import tomllib
+        except ImportError:  # Python < 3.11
+            try:
+                import tomli as tomllib  # type: ignore[no-redef]
+            except ImportError:
+                raise ImportError("Reading TOML files requires Python 3.11+ or tomli (pip install tomli)") from None
+        with path.open("rb") as f:
+            config = tomllib.load(f)
+    else:
+        with path.open(encoding="utf-8") as f:
+            config = json.load(f)
+    if not isinstance(config, dict):
+        raise ValueError(f"{path}: configuration must be a table of client options")
+    return config
+
+
+def load_profile(path: str | os.PathLike[str], profile: str | None, options: Container[str]) -> dict[str, Any]:
+    """
+    Client arguments from the file at `path`, with `profile` applied if given.
+
+    Raises ValueError for an unknown profile or a key that is not one of `options`.
+    """
+    path = Path(path)
+    config = read_config(path)
+    profiles = config.pop("profiles", {})
+    if not isinstance(profiles, dict):
+        raise ValueError(f"{path}: profiles must be a table of named profiles")
+    kwargs = dict(config)
+    if profile is not None:
+        if profile not in profiles:
+            available = ", ".join(sorted(profiles)) or "none"
+            raise ValueError(f"{path}: unknown profile {profile!r} (available: {available})")
+        for key, value in profiles[profile].items():
+            if key in MERGED_OPTIONS and isinstance(kwargs.get(key), dict) and isinstance(value, dict):
+                kwargs[key] = {**kwargs[key], **value}
+            else:
+                kwargs[key] = value
+
+    for key in kwargs:
+        if key not in options:
+            raise ValueError(f"{path}: unknown client option {key!r}")
+    for key in PATH_OPTIONS:
+        if isinstance(kwargs.get(key), str):
+            kwargs[key] = str(path.parent / kwargs[key])
+    # TOML and JSON have no tuples; auth is a (username, password) pair
+    if isinstance(kwargs.get("auth"), list):
+        kwargs["auth"] = tuple(kwargs["auth"])
+    return kwargs
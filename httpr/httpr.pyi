@@ -1,28 +1,76 @@
 from __future__ import annotations
 
+import os
 import sys
-from collections.abc import Iterator
+from datetime import timedelta
+from concurrent.futures import Future
+from collections.abc import AsyncIterator, Callable, Container, Iterable, Iterator, Mapping, Sequence
 from contextlib import AbstractAsyncContextManager, AbstractContextManager
 from typing import Any, Literal, TypedDict
 
+from .listing import DirectoryEntry
+from .mirror import SyncResult
+from .soap import SoapFault as SoapFault
+from .soap import SoapResponse, SoapVersion
+from .webdav import DavResource
+
 if sys.version_info <= (3, 11):
-    from typing_extensions import Unpack
+    from typing_extensions import Self, Unpack
 else:
-    from typing import Unpack
-
-HttpMethod = Literal["GET", "HEAD", "OPTIONS", "DELETE", "POST", "PUT", "PATCH"]
+    from typing import Self, Unpack
+
+HttpMethod = Literal[
+    "GET",
+    "HEAD",
+    "OPTIONS",
+    "DELETE",
+    "POST",
+    "PUT",
+    "PATCH",
+    "PROPFIND",
+    "PROPPATCH",
+    "MKCOL",
+    "COPY",
+    "MOVE",
+    "LOCK",
+    "UNLOCK",
+]
+DedupeField = Literal["method", "url", "headers", "body"]
+AuthTypes = tuple[str, str | None] | HmacAuth | ApiKeyAuth
+# Any buffer-protocol object (NumPy arrays, mmap, ...) is accepted as well. bytes and writable
+# C-contiguous buffers are sent without copying; other buffers are copied once
+ContentTypes = bytes | bytearray | memoryview | str
+FilePath = str | os.PathLike[str]
+# A mapping of field names to paths, or (field, path) pairs to repeat a field
+FilesTypes = Mapping[str, FilePath] | Sequence[tuple[str, FilePath]]
+# A header value, or a callable returning it (None leaves the header out), called for every attempt
+HeaderValue = str | Callable[[], str | None]
 
 class RequestParams(TypedDict, total=False):
-    auth: tuple[str, str | None] | None
+    auth: AuthTypes | None
     auth_bearer: str | None
     params: dict[str, str] | None
-    headers: dict[str, str] | None
+    headers: dict[str, HeaderValue | None] | None
     cookies: dict[str, str] | None
     timeout: float | None
-    content: bytes | None
+    content: ContentTypes | Iterator[bytes | str] | None
+    encoding: str | None
     data: dict[str, Any] | None
     json: Any | None
-    files: dict[str, str] | None
+    files: FilesTypes | None
+    on_file_progress: Callable[[dict[str, Any]], None] | None
+    max_download_rate: float | str | None
+    max_upload_rate: float | str | None
+    low_speed_limit: int | None
+    low_speed_time: float | None
+    idempotency_key: bool | str | None
+    cancel_token: CancellationToken | None
+    trace_context: dict[str, str] | None
+    proto: bytes | Any | None
+    raw_query: str | None
+    follow_redirects: bool | None
+    max_redirects: int | None
+    sni_hostname: str | None
 
 class ClientRequestParams(RequestParams):
     verify: bool | None
@@ -30,6 +78,69 @@ class ClientRequestParams(RequestParams):
     client_pem: str | None
     client_pem_data: bytes | None
 
+class HistoryEntry(TypedDict):
+    """One request summary returned by `Client.history()`."""
+
+    method: str
+    url: str
+    """Final URL after redirects, or the requested URL if no response arrived."""
+    status: int | None
+    """Response status, or None if the request failed."""
+    started: float
+    """Unix timestamp when the request was started."""
+    elapsed: float
+    """Seconds until the response headers arrived or the request failed."""
+    error: str | None
+
+class Attempt(TypedDict):
+    """One time a request went out, from `Response.attempts`."""
+
+    url: str
+    started: float
+    """Unix timestamp when the attempt was started."""
+    duration: float
+    """Seconds until the response headers arrived or the attempt failed."""
+    outcome: Literal["response", "error"]
+    status: int | None
+    """Response status, or None if the attempt failed."""
+    error: str | None
+    retry: bool
+    """Whether the attempt repeated a failed one (`retry_idempotent_on_connection_error`)."""
+
+class UploadedPart(TypedDict):
+    """One part of a `MultipartUploader` upload."""
+
+    part_number: int
+    etag: str | None
+    """ETag header of the part's response."""
+    size: int
+
+class UploadResult(TypedDict):
+    """Returned by `MultipartUploader.upload()`."""
+
+    upload_id: str
+    size: int
+    parts: list[UploadedPart]
+    result: Any
+    """Return value of `complete`; None for the S3 API."""
+
+class TusUploadResult(TypedDict):
+    """Returned by `Client.upload_tus()`."""
+
+    upload_url: str
+    size: int
+    resumed_from: int
+    """Offset the upload continued from; 0 for a new upload."""
+
+class SchemaViolation(TypedDict):
+    """One violation returned by `Response.validate()`."""
+
+    path: str
+    """JSON Pointer to the offending value in the body (empty for the root)."""
+    schema_path: str
+    """JSON Pointer to the failing keyword in the schema."""
+    message: str
+
 class CaseInsensitiveHeaderMap:
     """
     A case-insensitive dictionary-like class for HTTP headers.
@@ -112,7 +223,7 @@ class Response:
         ...
     @property
     def cookies(self) -> dict[str, str]:
-        """Response cookies as a dictionary."""
+        """Response cookies as a dictionary, including those set on redirect responses."""
         ...
     @property
     def headers(self) -> CaseInsensitiveHeaderMap:
@@ -131,19 +242,79 @@ class Response:
         """Final URL after any redirects."""
         ...
     @property
+    def elapsed(self) -> timedelta:
+        """Time from sending the request until the body was read."""
+        ...
+    @property
+    def request_body_size(self) -> int:
+        """Request body bytes sent."""
+        ...
+    @property
+    def response_body_size(self) -> int:
+        """Response body bytes as transferred, before content decoding (gzip, br, ...)."""
+        ...
+    @property
+    def decompressed_body_size(self) -> int:
+        """Response body bytes after content decoding, the length of `content`."""
+        ...
+    @property
+    def server_timing(self) -> list[ServerTiming]:
+        """Metrics of the `Server-Timing` headers, in order."""
+        ...
+    @property
+    def history(self) -> list[Response]:
+        """The redirect responses followed to get this one, oldest first; empty without redirects."""
+        ...
+    @property
+    def attempts(self) -> list[Attempt]:
+        """
+        Every time the request went out, oldest first: the first send, retries after a connection
+        error, a resend after an auth challenge and each redirect hop.
+        """
+        ...
+    @property
+    def retries(self) -> int:
+        """How many of `attempts` were retries after a failed attempt."""
+        ...
+    @property
     def encoding(self) -> str:
         """
         Character encoding of the response.
 
         Detected from Content-Type header or response body.
-        Can be set to override auto-detection.
+        Can be set to override auto-detection, including a byte order mark; None restores it.
         """
         ...
     @encoding.setter
-    def encoding(self, value: str) -> None: ...
+    def encoding(self, value: str | None) -> None: ...
+    @property
+    def content_type(self) -> str | None:
+        """Media type of the Content-Type header, lowercased and without parameters such as charset."""
+        ...
+    @property
+    def links(self) -> dict[str, dict[str, str]]:
+        """
+        Entries of the Link header keyed by `rel`.
+
+        Each is a dict of the link parameters plus `url`, resolved against the response URL.
+
+        Example:
+            ```python
+            next_url = response.links.get("next", {}).get("url")
+            ```
+        """
+        ...
     @property
     def text(self) -> str:
-        """Response body decoded as text using the detected encoding."""
+        """Response body decoded as text using `encoding`."""
+        ...
+    def text_with_encoding(self, encoding: str) -> str:
+        """
+        Response body decoded with `encoding`, leaving `encoding` and `text` unchanged.
+
+        Raises:
+            LookupError: If `encoding` is unknown.
+        """
         ...
     def json(self) -> Any:
         """
@@ -163,12 +334,150 @@ class Response:
             Parsed CBOR data as Python objects.
         """
         ...
+    def arrow(self) -> Any:
+        """
+        Parse an Arrow IPC (stream or file format) or Parquet body into a `pyarrow.Table`.
+
+        The format comes from the Content-Type, or from the body's magic bytes. The table
+        exports the Arrow C stream interface for polars and other Arrow libraries.
+
+        Raises:
+            ImportError: If pyarrow is not installed.
+        """
+        ...
+    def proto_into(self, message_class: type[Any]) -> Any:
+        """
+        Parse the body as a Protocol Buffers message of `message_class`.
+
+        Uses `message_class.FromString()`, or `ParseFromString()` on a new instance.
+        """
+        ...
+    def problem(self) -> ProblemDetails | None:
+        """
+        Parse an RFC 9457 problem details body.
+
+        Returns:
+            The parsed problem for `application/problem+json` and `application/problem+xml`
+            responses, otherwise None.
+
+        Raises:
+            RuntimeError: If the body is not a valid problem document.
+        """
+        ...
+    def sniff(self) -> SniffResult:
+        """
+        Detect what the body is from its bytes, ignoring the Content-Type header.
+
+        The media type comes from magic bytes of binary formats, or from the markup, JSON or
+        plain text a text body holds. For text and HTML bodies the natural language is detected too.
+
+        Example:
+            ```python
+            sniffed = response.sniff()
+            if sniffed.mime_type != sniffed.declared_type:
+                print(f"claims {sniffed.declared_type}, looks like {sniffed.mime_type}")
+            ```
+        """
+        ...
+    def into_buffer(self, buffer: Any) -> int:
+        """
+        Copy the body into a writable buffer (bytearray, NumPy array, mmap, ...).
+
+        Multi-dimensional buffers must be C-contiguous.
+
+        Returns:
+            The number of bytes written.
+
+        Raises:
+            ValueError: If the body does not fit in the buffer.
+            TypeError: If `buffer` is read-only or does not support the buffer protocol.
+        """
+        ...
+    def validate(self, schema: dict[str, Any]) -> list[SchemaViolation]:
+        """
+        Validate the JSON body against a JSON Schema, without holding the GIL.
+
+        Args:
+            schema: The JSON Schema. Its `$schema` selects the draft (default 2020-12).
+
+        Returns:
+            One entry per violation; empty when the body is valid.
+
+        Raises:
+            ValueError: If the body is not JSON or the schema is invalid.
+        """
+        ...
+    def jsonpath(self, path: str) -> list[Any]:
+        """
+        Select values from the JSON body with a JSONPath expression, without holding the GIL.
+
+        Args:
+            path: An RFC 9535 JSONPath expression, e.g. `"$.items[*].id"`.
+
+        Returns:
+            The matched values in document order; empty when nothing matches.
+
+        Raises:
+            ValueError: If the path is invalid or the body is not JSON.
+        """
+        ...
+    def expect(
+        self,
+        status: int | Container[int] | None = None,
+        content_type: str | None = None,
+        json_schema: dict[str, Any] | None = None,
+        max_latency: float | None = None,
+    ) -> Response:
+        """
+        Check the response against expectations, for terse API tests.
+
+        Args:
+            status: Expected status code, or a container of them such as `range(200, 300)`.
+            content_type: Expected media type, compared without parameters such as `charset`.
+            json_schema: A JSON Schema the body must satisfy.
+            max_latency: Most seconds `elapsed` may take.
+
+        Returns:
+            The response itself, so calls can be chained.
+
+        Raises:
+            AssertionError: Listing every failed check.
+            ValueError: If `json_schema` is not a valid schema.
+        """
+        ...
+    def raise_for_status(self) -> Response:
+        """
+        Raise HTTPStatusError if the status is 4xx or 5xx.
+
+        The exception message includes a preview of the error body (at most 1 KiB,
+        decoded with the response charset) and, for JSON bodies, well-known error fields
+        such as `error`, `message` and `detail`. RFC 9457 problem details are attached as
+        the exception's `problem`.
+
+        Returns:
+            The response itself, so calls can be chained.
+
+        Raises:
+            HTTPStatusError: If the status is 4xx or 5xx.
+        """
+        ...
+    @property
+    def link_base(self) -> str | None:
+        """
+        URL that relative links and image sources are resolved against in `text_markdown`,
+        `text_plain` and `text_rich`.
+
+        Defaults to the final response URL. Set another URL, or None to keep links as written.
+        """
+        ...
+    @link_base.setter
+    def link_base(self, value: str | None) -> None: ...
     @property
     def text_markdown(self) -> str:
         """
         Response body converted from HTML to Markdown format.
 
-        Useful for reading HTML content as plain text.
+        Useful for reading HTML content as plain text. Link targets are absolute, see `link_base`.
         """
         ...
     @property
@@ -188,139 +497,1489 @@ class Response:
         """
         ...
 
-class TextIterator:
-    """Iterator for text chunks from a streaming response."""
-    def __iter__(self) -> TextIterator: ...
-    def __next__(self) -> str: ...
+class ProblemDetails:
+    """
+    An RFC 9457 "Problem Details for HTTP APIs" error, returned by `Response.problem()`.
 
-class LineIterator:
-    """Iterator for lines from a streaming response."""
-    def __iter__(self) -> LineIterator: ...
-    def __next__(self) -> str: ...
+    Example:
+        ```python
+        problem = response.problem()
+        if problem is not None:
+            print(problem.type, problem.title, problem.detail)
+            print(problem.extensions.get("balance"))
+        ```
+    """
+    @property
+    def type(self) -> str:
+        """URI reference identifying the problem type; "about:blank" if absent."""
+        ...
+    @property
+    def title(self) -> str | None:
+        """Short, human-readable summary of the problem type."""
+        ...
+    @property
+    def status(self) -> int | None:
+        """HTTP status code generated by the origin server."""
+        ...
+    @property
+    def detail(self) -> str | None:
+        """Human-readable explanation specific to this occurrence."""
+        ...
+    @property
+    def instance(self) -> str | None:
+        """URI reference identifying this occurrence."""
+        ...
+    @property
+    def extensions(self) -> dict[str, Any]:
+        """Members other than type, title, status, detail and instance."""
+        ...
 
-class StreamingResponse:
+class ServerTiming:
     """
-    A streaming HTTP response that allows iterating over chunks of data.
+    One metric of a `Server-Timing` response header, from `Response.server_timing`.
 
-    This class provides methods to iterate over the response body in chunks
-    without buffering the entire response in memory.
+    Example:
+        ```python
+        for metric in response.server_timing:
+            print(metric.name, metric.duration, metric.description)
+        ```
     """
+    @property
+    def name(self) -> str:
+        """Metric name, e.g. "db" or "cache"."""
+        ...
+    @property
+    def duration(self) -> float | None:
+        """The `dur` parameter in milliseconds; None if absent or not a number."""
+        ...
+    @property
+    def description(self) -> str | None:
+        """The `desc` parameter."""
+        ...
+
+class SniffResult:
+    """What `Response.sniff()` detected in a response body."""
 
     @property
-    def cookies(self) -> dict[str, str]:
-        """Response cookies."""
+    def mime_type(self) -> str:
+        """Media type the body looks like, e.g. "image/png" or "text/html"; "application/octet-stream" if unknown."""
         ...
     @property
-    def headers(self) -> CaseInsensitiveHeaderMap:
-        """Response headers (case-insensitive access)."""
+    def language(self) -> str | None:
+        """ISO 639-1 code of the language of a text or HTML body, e.g. "en"; None if not recognized."""
         ...
     @property
-    def status_code(self) -> int:
-        """HTTP status code."""
+    def declared_type(self) -> str | None:
+        """Media type of the Content-Type header, as in `Response.content_type`."""
         ...
+
+class RuntimeStats:
+    """A snapshot of the Tokio runtime shared by all clients, from `httpr.runtime_stats()`."""
+
     @property
-    def url(self) -> str:
-        """Final URL after any redirects."""
+    def workers(self) -> int:
+        """Worker threads driving tasks; always 1 for the current-thread runtime."""
         ...
     @property
-    def is_closed(self) -> bool:
-        """Whether the stream has been closed."""
+    def alive_tasks(self) -> int:
+        """Tasks spawned and not yet finished: open connections and `fetch_all()` / `queue()` requests."""
         ...
     @property
-    def is_consumed(self) -> bool:
-        """Whether the stream has been fully consumed."""
+    def queue_depth(self) -> int:
+        """Tasks scheduled to run but waiting for the worker."""
         ...
-    def __iter__(self) -> Iterator[bytes]:
-        """Iterate over the response body as bytes chunks."""
+    @property
+    def busy_time(self) -> float:
+        """Seconds the worker has spent running tasks since the runtime started."""
         ...
-    def __next__(self) -> bytes:
-        """Get the next chunk of bytes."""
+    @property
+    def blocking_threads(self) -> int:
+        """Threads of the blocking pool (DNS lookups, file reads) currently alive, busy or idle."""
+        ...
+    @property
+    def max_blocking_threads(self) -> int:
+        """Thread limit of the blocking pool."""
+        ...
+
+class FetchIterator:
+    """Iterator over the results of `Client.fetch_all()`."""
+
+    def __iter__(self) -> FetchIterator: ...
+    def __next__(self) -> Response | StreamingResponse | Exception: ...
+    def __len__(self) -> int:
+        """Number of results not yet yielded."""
+        ...
+
+class RequestQueue:
+    """
+    Background request scheduler returned by `Client.queue()`.
+
+    Submitted requests wait in a priority queue and are started by a background thread
+    as the concurrency, per-host and rate limits allow. Use it as a context manager to
+    wait for all requests on exit.
+    """
+
+    def submit(
+        self,
+        request: Request,
+        priority: int = 0,
+        deadline: float | None = None,
+        callback: Callable[[Future[Response]], None] | None = None,
+    ) -> Future[Response]:
+        """
+        Queue a request.
+
+        Args:
+            request: The request to send, with the client's defaults applied.
+            priority: Requests with a higher priority start first. Default is 0.
+            deadline: Seconds from now by which the request must start, otherwise its
+                future fails with PoolTimeout. Once started, the time left also bounds the
+                request timeout. Among equal priorities, earlier deadlines start first.
+            callback: Called with the future once it is done, from a background thread.
+
+        Returns:
+            A future resolving to the Response, or failing with the request's exception.
+            Cancelling it before the request starts removes the request.
+
+        Raises:
+            RuntimeError: If the queue is closed.
+        """
+        ...
+    def join(self, timeout: float | None = None) -> bool:
+        """Wait until every submitted request is done. Returns False on timeout."""
+        ...
+    def close(self, wait: bool = True) -> None:
+        """Stop accepting requests. With `wait`, block until the submitted ones are done."""
+        ...
+    @property
+    def closed(self) -> bool:
+        """Whether `close()` has been called."""
+        ...
+    def __len__(self) -> int:
+        """Number of requests queued or running."""
+        ...
+    def __enter__(self) -> RequestQueue: ...
+    def __exit__(self, *args: Any) -> None: ...
+
+class MultipartUploader:
+    """
+    Uploads files in parts through an initiate / upload part / complete protocol, as
+    returned by `Client.multipart_uploader()`.
+
+    Parts are read from the file and sent as PUT requests concurrently; a part that fails
+    with a connection error, a timeout or a 408, 429 or 5xx response is retried on its own.
+    """
+
+    def upload(self, path: str | os.PathLike[str]) -> UploadResult:
+        """
+        Upload a file.
+
+        Args:
+            path: The file to upload.
+
+        Returns:
+            The upload ID, file size, uploaded parts and the result of `complete`.
+
+        Raises:
+            HTTPStatusError: If a request of the upload fails with a 4xx/5xx status.
+            RequestError: If the upload fails otherwise. After initiating, the upload is
+                aborted before the error is raised.
+        """
+        ...
+
+class CancellationToken:
+    """
+    Aborts the requests it is passed to (`cancel_token=`), from any thread.
+
+    Cancelling closes the connection of in-flight requests and fails them with
+    RequestCancelled, including responses whose body is still being read. A token
+    can be shared by many requests and cannot be reset.
+    """
+
+    def __init__(self) -> None: ...
+    def cancel(self) -> None:
+        """Cancel every request using this token, including ones sent later."""
+        ...
+    @property
+    def cancelled(self) -> bool:
+        """Whether `cancel()` has been called."""
+        ...
+
+class RobotsTxt:
+    """Parsed robots.txt rules (RFC 9309), returned by `Client.robots()`."""
+
+    def __init__(self, text: str) -> None:
+        """Parse the contents of a robots.txt file."""
+        ...
+    def allowed(self, user_agent: str, path: str) -> bool:
+        """
+        Whether `user_agent` may fetch `path`.
+
+        Args:
+            user_agent: Crawler user agent; only its product token (e.g. "mybot" in
+                "MyBot/1.0") is matched, case-insensitively. Falls back to the `*` group.
+            path: Path (with query string) or full URL to check.
+        """
+        ...
+    def crawl_delay(self, user_agent: str) -> float | None:
+        """The `Crawl-delay` in seconds for `user_agent`, if any."""
+        ...
+    @property
+    def sitemaps(self) -> list[str]:
+        """URLs of the `Sitemap` lines."""
+        ...
+
+class SitemapIterator:
+    """Iterator over the page URLs of a sitemap, returned by `Client.sitemap()`."""
+
+    def __iter__(self) -> SitemapIterator: ...
+    def __next__(self) -> str: ...
+
+class Paginator:
+    """Iterator over the items of a paginated JSON API, returned by `Client.paginate()`."""
+
+    def __iter__(self) -> Paginator: ...
+    def __next__(self) -> Any: ...
+    @property
+    def pages(self) -> int:
+        """Number of pages fetched so far."""
+        ...
+
+class Request:
+    """
+    A request that has been built but not yet sent.
+
+    Arguments left as None fall back to the client defaults when the request
+    is sent with `client.send()`.
+
+    Example:
+        ```python
+        request = httpr.Request("GET", "https://httpbin.org/get", params={"q": "httpr"})
+        response = client.send(request)
+        ```
+    """
+    def __init__(
+        self,
+        method: HttpMethod,
+        url: str,
+        params: dict[str, str] | None = None,
+        headers: dict[str, HeaderValue | None] | None = None,
+        cookies: dict[str, str] | None = None,
+        content: ContentTypes | None = None,
+        data: dict[str, Any] | None = None,
+        json: Any | None = None,
+        files: FilesTypes | None = None,
+        auth: AuthTypes | None = None,
+        auth_bearer: str | None = None,
+        timeout: float | None = None,
+        encoding: str | None = None,
+    ) -> None: ...
+    @property
+    def method(self) -> str: ...
+    @property
+    def url(self) -> str: ...
+    @property
+    def params(self) -> dict[str, str] | None: ...
+    @property
+    def headers(self) -> dict[str, str] | None: ...
+    @property
+    def cookies(self) -> dict[str, str] | None: ...
+    @property
+    def content(self) -> bytes | None: ...
+    @property
+    def data(self) -> dict[str, Any] | None: ...
+    @property
+    def json(self) -> Any | None: ...
+    @property
+    def files(self) -> dict[str, str] | list[tuple[str, str]] | None: ...
+    @property
+    def auth(self) -> AuthTypes | None: ...
+    @property
+    def auth_bearer(self) -> str | None: ...
+    @property
+    def timeout(self) -> float | None: ...
+
+class RequestTemplate:
+    """
+    A reusable request description with `{name}` placeholders.
+
+    Placeholders may appear in the URL, params, headers, cookies, a text `content`
+    body, and anywhere inside `data`/`json` bodies. Formatting follows `str.format`
+    rules for named fields (`{{` and `}}` are literal braces). A JSON string that
+    consists of exactly one placeholder is replaced by the variable itself, keeping
+    its type.
+
+    Example:
+        ```python
+        template = httpr.RequestTemplate(
+            "PUT",
+            "https://api.example.com/users/{user_id}",
+            headers={"X-Tenant": "{tenant}"},
+            json={"id": "{user_id}", "active": True},
+        )
+        request = template.render(user_id=42, tenant="acme")
+        responses = client.send_template_many(template, [{"user_id": 1, "tenant": "acme"}])
+        ```
+    """
+    def __init__(
+        self,
+        method: HttpMethod,
+        url: str,
+        params: dict[str, str] | None = None,
+        headers: dict[str, str] | None = None,
+        cookies: dict[str, str] | None = None,
+        content: str | None = None,
+        data: dict[str, Any] | None = None,
+        json: Any | None = None,
+        auth: AuthTypes | None = None,
+        auth_bearer: str | None = None,
+        timeout: float | None = None,
+    ) -> None: ...
+    @property
+    def method(self) -> str: ...
+    @property
+    def url(self) -> str: ...
+    @property
+    def placeholders(self) -> list[str]:
+        """Names of all placeholders used by the template, in order of first appearance."""
+        ...
+    def render(self, **vars: Any) -> Request:
+        """
+        Fill in the placeholders and return a Request.
+
+        Raises:
+            ValueError: If a placeholder has no matching variable or a brace is unmatched.
+        """
+        ...
+
+class TextIterator:
+    """Iterator for text chunks from a streaming response."""
+    def __iter__(self) -> TextIterator: ...
+    def __next__(self) -> str: ...
+
+class LineIterator:
+    """Iterator for lines from a streaming response."""
+    def __iter__(self) -> LineIterator: ...
+    def __next__(self) -> str: ...
+
+class JsonIterator:
+    """Iterator over JSON values parsed incrementally from a streaming response."""
+    def __iter__(self) -> JsonIterator: ...
+    def __next__(self) -> Any: ...
+
+class CsvIterator:
+    """Iterator over the rows of a streaming CSV response."""
+    def __iter__(self) -> CsvIterator: ...
+    def __next__(self) -> dict[str, str] | tuple[str, ...]: ...
+
+class StreamingResponse:
+    """
+    A streaming HTTP response that allows iterating over chunks of data.
+
+    This class provides methods to iterate over the response body in chunks
+    without buffering the entire response in memory.
+    """
+
+    @property
+    def cookies(self) -> dict[str, str]:
+        """Response cookies, including those set on redirect responses."""
+        ...
+    @property
+    def headers(self) -> CaseInsensitiveHeaderMap:
+        """Response headers (case-insensitive access)."""
+        ...
+    @property
+    def status_code(self) -> int:
+        """HTTP status code."""
+        ...
+    @property
+    def url(self) -> str:
+        """Final URL after any redirects."""
+        ...
+    @property
+    def is_closed(self) -> bool:
+        """Whether the stream has been closed."""
+        ...
+    @property
+    def is_consumed(self) -> bool:
+        """Whether the stream has been fully consumed."""
+        ...
+    @property
+    def request_body_size(self) -> int:
+        """Request body bytes sent."""
+        ...
+    @property
+    def response_body_size(self) -> int:
+        """Response body bytes received so far, before content decoding."""
+        ...
+    @property
+    def decompressed_body_size(self) -> int:
+        """Response body bytes read so far, after content decoding."""
+        ...
+    @property
+    def server_timing(self) -> list[ServerTiming]:
+        """Metrics of the `Server-Timing` headers, in order."""
+        ...
+    @property
+    def history(self) -> list[Response]:
+        """The redirect responses followed to get this one, oldest first, with their bodies read."""
+        ...
+    @property
+    def attempts(self) -> list[Attempt]:
+        """Every time the request went out, oldest first, as for `Response.attempts`."""
+        ...
+    @property
+    def retries(self) -> int:
+        """How many of `attempts` were retries after a failed attempt."""
+        ...
+    @property
+    def encoding(self) -> str:
+        """
+        Character encoding of `iter_text()`, `iter_lines()` and `iter_csv()`.
+
+        From the Content-Type header, or UTF-8. Set it before iterating to override it; None restores it.
+        """
+        ...
+    @encoding.setter
+    def encoding(self, value: str | None) -> None: ...
+    @property
+    def content_type(self) -> str | None:
+        """Media type of the Content-Type header, lowercased and without parameters such as charset."""
+        ...
+    @property
+    def links(self) -> dict[str, dict[str, str]]:
+        """Entries of the Link header keyed by `rel`, as for `Response.links`."""
+        ...
+    def raise_for_status(self) -> StreamingResponse:
+        """
+        Raise HTTPStatusError if the status is 4xx or 5xx.
+
+        The body is left unread, so the exception has an empty `body_preview` and no
+        `error_body`, `error_fields` or `problem`.
+
+        Returns:
+            The response itself, so calls can be chained.
+
+        Raises:
+            HTTPStatusError: If the status is 4xx or 5xx.
+        """
+        ...
+    def __iter__(self) -> Iterator[bytes]:
+        """Iterate over the response body as bytes chunks."""
+        ...
+    def __next__(self) -> bytes:
+        """Get the next chunk of bytes."""
+        ...
+    def iter_bytes(self) -> Iterator[bytes]:
+        """
+        Iterate over the response body as bytes chunks.
+
+        Yields chunks of bytes as they are received from the server.
+        """
+        ...
+    def iter_text(self) -> TextIterator:
+        """
+        Iterate over the response body as text chunks.
+
+        Decodes each chunk using the response's encoding.
+        """
+        ...
+    def iter_lines(self) -> LineIterator:
+        """
+        Iterate over the response body line by line.
+
+        Yields complete lines including newline characters.
+        """
+        ...
+    def jsonpath_stream(self, path: str) -> JsonIterator:
+        """
+        Apply a JSONPath expression to each line of an NDJSON body, yielding every match.
+
+        Lines are parsed and queried in Rust as they arrive; only the current line is
+        buffered and blank lines are skipped.
+
+        Args:
+            path: An RFC 9535 JSONPath expression, e.g. `"$.user.id"`.
+
+        Raises:
+            ValueError: If the path is invalid, or (while iterating) a line is not JSON.
+        """
+        ...
+    def iter_json_array(self, path: str | None = None) -> JsonIterator:
+        """
+        Iterate over the elements of a JSON array in the body with bounded memory.
+
+        The body is parsed incrementally in Rust; only the current element is buffered,
+        and reading stops once the array closes.
+
+        Args:
+            path: Dot-separated object keys leading to the array, e.g. `"data.items"`.
+                By default the body must be a top-level array.
+
+        Raises:
+            ValueError: While iterating, if there is no array at `path` or the JSON is invalid.
+        """
+        ...
+    def iter_csv(self, delimiter: str = ",", header: bool = True) -> CsvIterator:
+        """
+        Iterate over the rows of a CSV body as they arrive.
+
+        Quoting and rows split across chunks are handled in Rust; only the current row
+        is buffered. Fields are decoded with the response encoding.
+
+        Args:
+            delimiter: The field separator, a single ASCII character (`"\\t"` for TSV).
+            header: Use the first row as field names and yield dicts; otherwise yield tuples.
+
+        Raises:
+            ValueError: If `delimiter` is invalid, or (while iterating) a row has a different
+                number of fields than the first.
+        """
+        ...
+    def read(self) -> bytes:
+        """
+        Read the entire remaining response body into memory.
+
+        This consumes the stream.
+        """
+        ...
+    def read_into(self, buffer: Any) -> int:
+        """
+        Read the rest of the body directly into a writable buffer (bytearray, NumPy
+        array, mmap, ...) without collecting it in memory first.
+
+        This consumes the stream.
+
+        Returns:
+            The number of bytes written.
+
+        Raises:
+            ValueError: If the body is larger than the buffer, checked up front when the
+                response has a Content-Length.
+        """
+        ...
+    def stream_to(self, path: str | os.PathLike[str]) -> int:
+        """
+        Write the rest of the body to the file at `path`, replacing it, without holding
+        the body in memory.
+
+        This consumes the stream.
+
+        Returns:
+            The number of bytes written.
+        """
+        ...
+    def close(self) -> None:
+        """
+        Close the streaming response and release resources.
+        """
+        ...
+
+class Stream:
+    """
+    A raw bidirectional byte stream: a connection taken over from HTTP by an `Upgrade:`
+    handshake (`Client.upgrade()`) or a tunnel (`Client.connect_tunnel()`).
+
+    Reading and writing are independent, so one thread may wait in `read()` while
+    another writes. Use it as a context manager to close it.
+    """
+
+    @property
+    def status_code(self) -> int | None:
+        """Status of the response that handed over the connection, None for a direct tunnel."""
+        ...
+    @property
+    def headers(self) -> CaseInsensitiveHeaderMap:
+        """Headers of the response that handed over the connection."""
+        ...
+    @property
+    def is_closed(self) -> bool:
+        """Whether `close()` has been called."""
+        ...
+    def read(self, max_bytes: int = 65536, timeout: float | None = None) -> bytes:
+        """
+        Read up to `max_bytes`, waiting until some arrive.
+
+        Returns:
+            The bytes read, or `b""` once the peer has closed its side.
+
+        Raises:
+            ReadTimeout: If nothing arrived within `timeout` seconds.
+            StreamClosed: If the stream is or gets closed.
+        """
+        ...
+    def write(self, data: ContentTypes) -> None:
+        """Write all of `data` and flush it."""
+        ...
+    def close(self) -> None:
+        """
+        Shut down the sending side, which the peer sees as the end of the data, and
+        release the connection. Reads waiting in other threads raise `StreamClosed`.
+        """
+        ...
+    def __enter__(self) -> Self: ...
+    def __exit__(self, *args: Any) -> None: ...
+
+class ApiKeyAuth:
+    """
+    A static API key sent with every request, passed as `auth=`.
+
+    Example:
+        ```python
+        client = httpr.Client(auth=httpr.ApiKeyAuth("my-key"))  # X-API-Key: my-key
+        client = httpr.Client(auth=httpr.ApiKeyAuth("my-key", where="query", name="api_key"))
+        ```
+    """
+
+    def __init__(
+        self,
+        key: str,
+        where: Literal["header", "query"] = "header",
+        name: str = "X-API-Key",
+    ) -> None:
+        """
+        Create an API key scheme.
+
+        Args:
+            key: The API key.
+            where: Send the key as a header or as a query parameter. Default is "header".
+            name: Header or query parameter name. Default is "X-API-Key".
+
+        Raises:
+            ValueError: On an unknown placement or an invalid header name or value.
+        """
+        ...
+    @property
+    def where(self) -> Literal["header", "query"]: ...
+    @property
+    def name(self) -> str: ...
+
+class HmacAuth:
+    """
+    Shared-secret HMAC request signing, passed as `auth=`.
+
+    For every request, `string_to_sign` is rendered and signed with `secret`, and
+    `header` is set to `header_template` rendered with the same placeholders plus
+    `{signature}`. Placeholders: `{method}`, `{path}` (path and query), `{host}`,
+    `{timestamp}` (Unix seconds), `{nonce}` (random hex), `{body_sha256}` (hex),
+    `{content_type}` and `{key_id}`.
+
+    Example:
+        ```python
+        auth = httpr.HmacAuth(
+            "key-1",
+            "secret",
+            string_to_sign="{method}\n{path}\n{timestamp}",
+            header="X-Signature",
+            header_template="{key_id}:{timestamp}:{signature}",
+        )
+        client = httpr.Client(auth=auth)
+        ```
+    """
+
+    def __init__(
+        self,
+        key_id: str,
+        secret: str | bytes,
+        *,
+        algorithm: Literal["sha256", "sha384", "sha512"] = "sha256",
+        string_to_sign: str = "{method}\n{path}\n{timestamp}\n{body_sha256}",
+        header: str = "Authorization",
+        header_template: str = "HMAC {key_id}:{signature}",
+        encoding: Literal["base64", "hex"] = "base64",
+        timestamp_header: str | None = None,
+    ) -> None:
+        """
+        Create an HMAC signing scheme.
+
+        Args:
+            key_id: Public key identifier, available as `{key_id}`.
+            secret: Shared secret; `str` is UTF-8 encoded.
+            algorithm: HMAC hash function. Default is "sha256".
+            string_to_sign: Template of the signed string.
+            header: Header carrying the signature. Default is "Authorization".
+            header_template: Template of the header value.
+            encoding: Signature encoding, "base64" or "hex". Default is "base64".
+            timestamp_header: Also send `{timestamp}` in this header.
+
+        Raises:
+            ValueError: On an unknown algorithm, encoding or template placeholder.
+        """
+        ...
+    @property
+    def key_id(self) -> str: ...
+    @property
+    def string_to_sign(self) -> str: ...
+    @property
+    def header(self) -> str: ...
+    @property
+    def header_template(self) -> str: ...
+    @property
+    def timestamp_header(self) -> str | None: ...
+
+class TransportRequest:
+    """
+    A request handed to the handler of a `PythonTransport`, with its final headers:
+    client defaults, auth, cookies, Accept-Encoding and any signature are applied.
+
+    The body is read with `read()` or by iterating over its chunks, either of which
+    consumes it.
+    """
+
+    @property
+    def method(self) -> str: ...
+    @property
+    def url(self) -> str: ...
+    @property
+    def headers(self) -> dict[str, str]: ...
+    @property
+    def timeout(self) -> float | None:
+        """Total timeout of the request in seconds, or None; the handler should honour it."""
+        ...
+    def read(self) -> bytes:
+        """The rest of the body; empty when there is none or it has been read."""
+        ...
+    def __iter__(self) -> Iterator[bytes]: ...
+    def __next__(self) -> bytes: ...
+
+TransportBody = bytes | str | bytearray | memoryview | Iterable[bytes] | None
+TransportResult = tuple[int, Mapping[str, str] | Sequence[tuple[str, str]], TransportBody]
+
+class PythonTransport:
+    """
+    Sends the requests of a client through a Python callable instead of its own
+    connections, passed as `transport=`.
+
+    The handler is called with a `TransportRequest` on a worker thread, and may block.
+    Redirects, cookies, auth, decompression and `response.attempts` are still handled
+    by the client. Exceptions raised by the handler, or while iterating over a streamed
+    response body, propagate to the caller as is.
+
+    Example:
+        ```python
+        def handler(request: httpr.TransportRequest):
+            resp = sdk.send(request.method, request.url, request.headers, request.read())
+            return resp.status, resp.headers, resp.iter_bytes()
+
+        client = httpr.Client(transport=httpr.PythonTransport(handler))
+        ```
+    """
+
+    def __init__(self, handler: Callable[[TransportRequest], TransportResult]) -> None:
+        """
+        Create a transport calling `handler` for every request.
+
+        Args:
+            handler: A callable taking a TransportRequest and returning (status, headers, body):
+                an int status, headers as a mapping or a sequence of (name, value) pairs (which
+                may repeat a name), and the body as bytes, str, a buffer, None, or an iterable
+                of bytes chunks, which is streamed.
+
+        Raises:
+            ValueError: If `handler` is not callable.
+        """
+        ...
+    @property
+    def handler(self) -> Callable[[TransportRequest], TransportResult]: ...
+
+class LocalServer:
+    """
+    A local HTTP server for tests, configured with canned routes.
+
+    Runs on a background thread and records every request it receives. Routes can be
+    added before or after `start()`; when several routes match, the most recently
+    added one wins. Unmatched requests get a 404. Exported as `httpr.testing.LocalServer`.
+    """
+
+    def __init__(
+        self,
+        host: str = "127.0.0.1",
+        port: int = 0,
+        tls_cert: str | None = None,
+        tls_key: str | None = None,
+    ) -> None:
+        """
+        Create a server. It does not listen until `start()` (or `with`).
+
+        Args:
+            host: Address to bind. Default is "127.0.0.1".
+            port: Port to bind; 0 picks a free port. Default is 0.
+            tls_cert: Path to a PEM certificate chain; serves HTTPS together with `tls_key`.
+            tls_key: Path to the PEM private key for `tls_cert`.
+
+        Raises:
+            ValueError: If only one of `tls_cert` / `tls_key` is given or they cannot be loaded.
+        """
+        ...
+    def route(
+        self,
+        path: str,
+        *,
+        method: str | None = None,
+        status: int = 200,
+        body: str | bytes | None = None,
+        json: Any | None = None,
+        headers: dict[str, str] | list[tuple[str, str]] | None = None,
+        delay: float | None = None,
+    ) -> None:
+        """
+        Answer `path` with a canned response.
+
+        Args:
+            path: Path to match, without the query string.
+            method: Method to match; None matches any method.
+            status: Response status code. Default is 200.
+            body: Response body.
+            json: Value serialized as the JSON response body; sets `Content-Type`.
+            headers: Response headers; a list of pairs can repeat a name.
+            delay: Seconds to wait before answering.
+        """
+        ...
+    def redirect(self, path: str, location: str, *, status: int = 302, method: str | None = None) -> None:
+        """Redirect `path` to `location` with a 3xx `status`."""
+        ...
+    def redirect_chain(self, path: str, target: str, hops: int, *, status: int = 302) -> None:
+        """Chain of `hops` redirects: `path` -> `path/1` -> ... -> `path/{hops-1}` -> `target`."""
+        ...
+    def echo(self, path: str, *, method: str | None = None, delay: float | None = None) -> None:
+        """
+        Answer `path` with a JSON description of the request: `method`, `path` (with the
+        query string), `headers` (lowercase names) and `body` (decoded as UTF-8).
+        """
+        ...
+    def start(self) -> None:
+        """Start listening on a background thread. Does nothing if already running."""
+        ...
+    def stop(self) -> None:
+        """Stop the server and close its connections. Does nothing if not running."""
+        ...
+    def __enter__(self) -> LocalServer: ...
+    def __exit__(self, *args: Any) -> None: ...
+    @property
+    def url(self) -> str:
+        """Base URL of the running server, e.g. `http://127.0.0.1:54321`."""
+        ...
+    @property
+    def port(self) -> int:
+        """Port the running server listens on."""
+        ...
+    @property
+    def requests(self) -> list[dict[str, Any]]:
+        """
+        Requests received so far, oldest first, as dicts with `method`, `path` (with the
+        query string), `headers` (lowercase names), `body` (bytes) and `tls_resumed`
+        (whether the connection resumed a TLS session, None over plain HTTP).
+        """
+        ...
+    def clear_requests(self) -> None:
+        """Forget the recorded requests."""
+        ...
+
+class RClient:
+    def __init__(
+        self,
+        auth: AuthTypes | None = None,
+        auth_bearer: str | None = None,
+        params: dict[str, str] | None = None,
+        headers: dict[str, HeaderValue] | None = None,
+        cookies: dict[str, str] | None = None,
+        timeout: float | None = None,
+        cookie_store: bool | None = True,
+        referer: bool | None = True,
+        proxy: str | None = None,
+        follow_redirects: bool | None = True,
+        max_redirects: int | None = 20,
+        verify: bool | None = True,
+        ca_cert_file: str | None = None,
+        client_pem: str | None = None,
+        client_pem_data: bytes | None = None,
+        https_only: bool | None = False,
+        http2_only: bool | None = False,
+        max_download_rate: float | str | None = None,
+        max_upload_rate: float | str | None = None,
+        low_speed_limit: int | None = None,
+        low_speed_time: float | None = None,
+        dns_cache_ttl: float | None = None,
+        dns_cache_negative_ttl: float | None = None,
+        reject_third_party_cookies: bool | None = False,
+        blocked_cookie_domains: list[str] | None = None,
+        max_cookies_per_domain: int | None = None,
+        max_cookie_size: int | None = None,
+        cookie_public_suffix: bool | None = False,
+        auth_mode: Literal["preemptive", "on_challenge"] | None = "preemptive",
+        sign: Callable[[str, str, dict[str, str], bytes | None], dict[str, str] | None] | None = None,
+        history_size: int | None = None,
+        respect_robots_txt: bool = False,
+        robots_cache_ttl: float = 3600.0,
+        allow_hosts: list[str] | None = None,
+        block_hosts: list[str] | None = None,
+        block_private_ips: bool = False,
+        allowed_schemes: list[str] | None = None,
+        max_header_bytes: int | None = None,
+        max_header_count: int | None = None,
+        max_decompressed_size: int | None = None,
+        max_decompression_ratio: float | None = None,
+        json_serializer: Callable[[Any], Any] | None = None,
+        json_canonical: bool = False,
+        json_sort_keys: bool = False,
+        json_ensure_ascii: bool = False,
+        json_separators: tuple[str, str] | None = None,
+        json_indent: int | None = None,
+        on_connection_event: Callable[[dict[str, Any]], None] | None = None,
+        http2_keep_alive_interval: float | None = None,
+        http2_keep_alive_timeout: float | None = None,
+        http2_keep_alive_while_idle: bool | None = None,
+        retry_idempotent_on_connection_error: bool = True,
+        cookie_merge: Literal["merge", "replace"] = "merge",
+        connect_timeout: float | None = 30,
+        read_timeout: float | None = 30,
+        trust_env: bool = True,
+        base_url: str | None = None,
+        codecs: dict[str, tuple[Callable[[bytes], bytes] | None, Callable[[bytes], bytes] | None]] | None = None,
+        request_encoding: str | None = None,
+        url_credentials: bool = True,
+        transport: PythonTransport | None = None,
+        send_content_digest: Literal["sha-256", "sha-512"] | None = None,
+        allow_sni_mismatch: bool = False,
+        alpn_protocols: list[Literal["h2", "http/1.1"]] | None = None,
+        tls_session_tickets: bool = True,
+        tls_session_cache_size: int | None = None,
+        verify_hook: Callable[[str, list[bytes], str | None], bool] | None = None,
+    ): ...
+    @property
+    def headers(self) -> dict[str, HeaderValue]: ...
+    @headers.setter
+    def headers(self, headers: dict[str, HeaderValue] | None) -> None: ...
+    @property
+    def cookies(self) -> dict[str, str]: ...
+    @cookies.setter
+    def cookies(self, cookies: dict[str, str] | None) -> None: ...
+    @property
+    def proxy(self) -> str | None: ...
+    @proxy.setter
+    def proxy(self, proxy: str) -> None: ...
+    @property
+    def auth(self) -> AuthTypes | None: ...
+    @auth.setter
+    def auth(self, auth: AuthTypes | None) -> None: ...
+    @property
+    def auth_bearer(self) -> str | None: ...
+    @auth_bearer.setter
+    def auth_bearer(self, auth_bearer: str | None) -> None: ...
+    @property
+    def params(self) -> dict[str, str] | None: ...
+    @params.setter
+    def params(self, params: dict[str, str] | None) -> None: ...
+    @property
+    def timeout(self) -> float | None: ...
+    @timeout.setter
+    def timeout(self, timeout: float | None) -> None: ...
+    @property
+    def base_url(self) -> str | None:
+        """Absolute URL that relative request URLs are appended to, or None."""
+        ...
+    @property
+    def connect_timeout(self) -> float | None:
+        """Seconds allowed for connecting, or None."""
+        ...
+    @property
+    def read_timeout(self) -> float | None:
+        """Seconds a response may go without receiving data, or None."""
+        ...
+    def request(self, method: HttpMethod, url: str, **kwargs: Unpack[RequestParams]) -> Response: ...
+    def _stream(self, method: HttpMethod, url: str, **kwargs: Unpack[RequestParams]) -> StreamingResponse: ...
+    def send(self, request: Request) -> Response:
+        """Send a prepared Request."""
+        ...
+    def send_template_many(
+        self,
+        template: RequestTemplate,
+        vars: list[dict[str, Any]],
+        max_concurrency: int | None = None,
+        return_exceptions: bool = False,
+        dedupe_on: tuple[DedupeField, ...] | list[DedupeField] | None = None,
+    ) -> list[Response | Exception]:
+        """
+        Render `template` once per entry in `vars` and send the requests concurrently.
+
+        Args:
+            template: The RequestTemplate to render.
+            vars: One dict of placeholder values per request.
+            max_concurrency: Maximum number of requests in flight at once. Default is unlimited.
+            return_exceptions: Return failed requests as exception instances instead of raising.
+            dedupe_on: Request parts that make two rendered requests identical, e.g.
+                `("method", "url", "body")`. Identical requests are sent once and share the result.
+
+        Returns:
+            Responses in the same order as `vars`.
+        """
+        ...
+    def download_parallel(
+        self,
+        url: str,
+        path: str | os.PathLike[str],
+        connections: int = 8,
+        params: dict[str, str] | None = None,
+        headers: dict[str, HeaderValue | None] | None = None,
+        cookies: dict[str, str] | None = None,
+        auth: AuthTypes | None = None,
+        auth_bearer: str | None = None,
+        timeout: float | None = ...,
+        sha256: str | None = None,
+        max_download_rate: float | str | None = None,
+        low_speed_limit: int | None = None,
+        low_speed_time: float | None = None,
+    ) -> int:
+        """
+        Download a file using concurrent byte-range requests.
+
+        Probes the server with `Range: bytes=0-0`. When ranges are supported the file is
+        split into up to `connections` ranges fetched concurrently; otherwise it is
+        downloaded over one connection. Each range is verified (status, `Content-Range`,
+        length, `ETag`) and data is staged in `<path>.part` until the file is complete.
+
+        Args:
+            url: The URL to download.
+            path: Destination file path.
+            connections: Maximum number of concurrent range requests.
+            params: Query parameters.
+            headers: Extra request headers; `None` and callable values as for `request()`.
+            cookies: Request cookies.
+            auth: Basic auth credentials.
+            auth_bearer: Bearer token.
+            timeout: Timeout for each range request in seconds.
+            sha256: Expected hex SHA-256 digest of the complete file.
+            max_download_rate: Cap on the combined throughput of all ranges.
+            low_speed_limit: Abort a range that stays below this many bytes per second.
+            low_speed_time: Window for low_speed_limit in seconds.
+
+        Returns:
+            The number of bytes written.
+
+        Raises:
+            HTTPStatusError: If the server answers with a 4xx/5xx status.
+            RequestError: If a range or the final file fails verification.
+
+        Example:
+            ```python
+            size = client.download_parallel("https://example.com/big.iso", "big.iso", connections=8)
+            ```
+        """
+        ...
+    def multipart_uploader(
+        self,
+        url: str | None = None,
+        *,
+        initiate: Callable[[], str] | None = None,
+        part_url: str | Callable[[str, int], str] | None = None,
+        complete: Callable[[str, list[UploadedPart]], Any] | None = None,
+        abort: Callable[[str], Any] | None = None,
+        part_size: int = 8 * 1024 * 1024,
+        max_concurrency: int = 4,
+        part_retries: int = 3,
+        headers: dict[str, str] | None = None,
+        timeout: float | None = ...,
+        max_upload_rate: float | str | None = None,
+        on_progress: Callable[[dict[str, Any]], None] | None = None,
+    ) -> MultipartUploader:
+        """
+        Create a MultipartUploader that uploads files in parts.
+
+        With `url`, the S3 multipart upload API of that object is used (`POST ?uploads`,
+        `PUT ?partNumber=N&uploadId=ID`, `POST ?uploadId=ID`), which Google Cloud Storage's
+        XML API and S3-compatible stores speak too. Any step can be replaced by a callable;
+        without `url`, `initiate`, `part_url` and `complete` are required.
+
+        Args:
+            url: URL of the object to upload to.
+            initiate: Returns the upload ID.
+            part_url: URL of each part: a template with `{upload_id}` (URL-encoded) and
+                `{part_number}` placeholders, or a callable taking both.
+            complete: Called with the upload ID and the uploaded parts; its return value is
+                the upload's `result`.
+            abort: Called with the upload ID if the upload fails. Default with `url` is
+                `DELETE ?uploadId=ID`.
+            part_size: Size of each part in bytes.
+            max_concurrency: Maximum number of parts sent at once.
+            part_retries: How often a part is sent again after a retryable failure.
+            headers: Extra headers for every request of the upload.
+            timeout: Timeout for each request in seconds.
+            max_upload_rate: Cap on the combined throughput of all parts.
+            on_progress: Called after each part with a dict ("part_number", "completed",
+                "parts", "sent", "total").
+
+        Raises:
+            ValueError: On invalid options or missing steps.
+
+        Example:
+            ```python
+            uploader = client.multipart_uploader("https://bucket.s3.amazonaws.com/big.iso", part_size=16 * 2**20)
+            result = uploader.upload("big.iso")
+            ```
+        """
+        ...
+    def upload_tus(
+        self,
+        url: str,
+        path: str | os.PathLike[str],
+        *,
+        chunk_size: int = 4 * 1024 * 1024,
+        metadata: dict[str, str] | None = None,
+        upload_url: str | None = None,
+        checksum: Literal["sha1", "sha256", "sha512"] | None = None,
+        retries: int = 3,
+        headers: dict[str, str] | None = None,
+        timeout: float | None = ...,
+        max_upload_rate: float | str | None = None,
+        on_progress: Callable[[dict[str, Any]], None] | None = None,
+    ) -> TusUploadResult:
+        """
+        Upload a file with the tus resumable upload protocol (tus.io).
+
+        The upload is created with a `POST` to `url`, then the file is sent in `PATCH`
+        requests of `chunk_size` bytes. After a connection error, a timeout, or a 408, 409,
+        429, 460 or 5xx response, the offset is queried with `HEAD` and the upload continues
+        from there. Pass `upload_url` to resume an upload created earlier.
+
+        Args:
+            url: The tus creation endpoint.
+            path: The file to upload.
+            chunk_size: Size of each `PATCH` body in bytes.
+            metadata: `Upload-Metadata` items, e.g. `{"filename": "video.mp4"}`.
+            upload_url: URL of an existing upload to resume instead of creating one.
+            checksum: `Upload-Checksum` algorithm for each chunk.
+            retries: How often the upload continues after failed chunks in a row.
+            headers: Extra headers for every request of the upload.
+            timeout: Timeout for each request in seconds.
+            max_upload_rate: Cap on the upload throughput.
+            on_progress: Called once the upload exists and after each chunk with a dict
+                ("upload_url", "sent", "total").
+
+        Returns:
+            The upload URL, file size and the offset the upload resumed from.
+
+        Raises:
+            ValueError: On invalid options or metadata keys.
+            HTTPStatusError: If a request fails with a status that is not retried.
+
+        Example:
+            ```python
+            result = client.upload_tus("https://tusd.example.com/files/", "video.mp4", metadata={"filename": "video.mp4"})
+            ```
+        """
         ...
-    def iter_bytes(self) -> Iterator[bytes]:
+    def upgrade(
+        self,
+        url: str,
+        protocol: str,
+        *,
+        method: str = "GET",
+        headers: dict[str, str] | None = None,
+        timeout: float | None = ...,
+    ) -> Stream:
         """
-        Iterate over the response body as bytes chunks.
+        Upgrade the connection of a request to another protocol and take it over as a raw `Stream`.
 
-        Yields chunks of bytes as they are received from the server.
+        Sends `Connection: Upgrade` and `Upgrade: <protocol>`. Upgrades exist in HTTP/1.1
+        only: for HTTPS servers offering HTTP/2, create the client with
+        `alpn_protocols=["http/1.1"]`.
+
+        Args:
+            url: The URL to upgrade.
+            protocol: The `Upgrade` header value, e.g. "websocket" or "h2c".
+            method: The request method.
+            headers: Extra headers for the handshake, such as protocol keys.
+            timeout: Timeout for the handshake in seconds.
+
+        Raises:
+            RemoteProtocolError: If the server answers with anything but 101.
+
+        Example:
+            ```python
+            with client.upgrade("http://example.com/chat", "chat/1.0") as stream:
+                stream.write(b"hello")
+                print(stream.read())
+            ```
         """
         ...
-    def iter_text(self) -> TextIterator:
+    def connect_tunnel(
+        self,
+        host: str,
+        port: int,
+        via_proxy: bool = True,
+        *,
+        headers: dict[str, str] | None = None,
+        timeout: float | None = ...,
+    ) -> Stream:
         """
-        Iterate over the response body as text chunks.
+        Open a raw TCP tunnel to `host:port`: through the client proxy with `CONNECT`, or
+        with `via_proxy=False` as a direct connection.
 
-        Decodes each chunk using the response's encoding.
+        A direct connection is checked against `allow_hosts`, `block_hosts`, `allowed_schemes`
+        and `block_private_ips` as an `http` URL, and resolves `host` like requests do, with
+        `dns_cache_ttl` and `block_private_ips` checking the resolved addresses.
+
+        Args:
+            host: The host to connect to.
+            port: The port to connect to.
+            via_proxy: Tunnel through the proxy requests to `http://host:port` would use:
+                `proxy`, `HTTPR_HTTP_PROXY` or, with `trust_env`, the proxy environment variables.
+            headers: Extra headers for the `CONNECT` request.
+            timeout: Timeout for opening the tunnel in seconds; by default the client
+                timeout through a proxy, and `connect_timeout` for a direct connection.
+
+        Raises:
+            ValueError: If `via_proxy` is set but no proxy applies to `host`.
+            ProxyError: If the proxy answers `CONNECT` with anything but 2xx.
+            HostBlocked: If a direct connection is refused by the host restrictions.
+            ConnectTimeout: If a direct connection is not made within the timeout.
+
+        Example:
+            ```python
+            client = httpr.Client(proxy="http://proxy.example.com:3128")
+            with client.connect_tunnel("mail.example.com", 25) as stream:
+                print(stream.read())
+            ```
         """
         ...
-    def iter_lines(self) -> LineIterator:
+    def fetch_all(
+        self,
+        urls: list[str],
+        max_concurrency: int = 32,
+        ordered: bool = False,
+        params: dict[str, str] | None = None,
+        headers: dict[str, HeaderValue | None] | None = None,
+        cookies: dict[str, str] | None = None,
+        auth: AuthTypes | None = None,
+        auth_bearer: str | None = None,
+        timeout: float | None = ...,
+        stream: bool = False,
+    ) -> FetchIterator:
         """
-        Iterate over the response body line by line.
+        Fetch many URLs concurrently, yielding each response as it completes.
 
-        Yields complete lines including newline characters.
+        Requests are sent in the background with at most `max_concurrency` in flight
+        and progress while the returned iterator is consumed. A failed request yields
+        its exception instead of raising. Dropping the iterator cancels the requests
+        not yet yielded.
+
+        Args:
+            urls: The URLs to GET.
+            max_concurrency: Maximum number of requests in flight. Default is 32.
+            ordered: Yield results in the order of `urls` instead of completion order.
+            params: Query parameters sent with every request.
+            headers: Headers sent with every request; `None` and callable values as for `request()`.
+            cookies: Cookies sent with every request.
+            auth: Auth credentials.
+            auth_bearer: Bearer token.
+            timeout: Timeout for each request in seconds.
+            stream: Yield a `StreamingResponse` for each URL instead of reading its body.
+                A request counts towards `max_concurrency` until its body is read to the
+                end or the response is closed, so at most that many streams are open.
+
+        Raises:
+            ValueError: If `max_concurrency` is 0.
+
+        Example:
+            ```python
+            for result in client.fetch_all(frontier, max_concurrency=16):
+                if isinstance(result, Exception):
+                    continue
+                print(result.url, result.status_code)
+            ```
         """
         ...
-    def read(self) -> bytes:
+    def queue(
+        self,
+        max_concurrency: int = 16,
+        max_per_host: int | None = None,
+        per_host_rate: float | None = None,
+    ) -> RequestQueue:
         """
-        Read the entire remaining response body into memory.
+        Create a queue that sends submitted requests in the background.
 
-        This consumes the stream.
+        Requests start in priority order, earliest deadline first among equal
+        priorities, as the limits allow.
+
+        Args:
+            max_concurrency: Maximum number of requests in flight. Default is 16.
+            max_per_host: Maximum number of requests in flight to one host. Default is None.
+            per_host_rate: Maximum number of requests started per second to one host.
+                Default is None.
+
+        Raises:
+            ValueError: If a limit is not positive.
+
+        Example:
+            ```python
+            with client.queue(max_per_host=2, per_host_rate=5) as queue:
+                futures = [queue.submit(httpr.Request("GET", url)) for url in urls]
+            responses = [future.result() for future in futures]
+            ```
         """
         ...
-    def close(self) -> None:
+    def robots(
+        self,
+        url: str,
+        headers: dict[str, HeaderValue | None] | None = None,
+        cookies: dict[str, str] | None = None,
+        auth: AuthTypes | None = None,
+        auth_bearer: str | None = None,
+        timeout: float | None = ...,
+    ) -> RobotsTxt:
         """
-        Close the streaming response and release resources.
+        Fetch and parse the robots.txt file governing `url`.
+
+        `url` may be any URL on the host; `/robots.txt` is requested from its origin.
+        As RFC 9309 specifies, a 5xx answer disallows everything and any other
+        unsuccessful status (such as 404) allows everything.
+
+        Args:
+            url: A URL on the host whose rules are wanted.
+            headers: Request headers; `None` and callable values as for `request()`.
+            cookies: Request cookies.
+            auth: Auth credentials.
+            auth_bearer: Bearer token.
+            timeout: Request timeout in seconds.
+
+        Example:
+            ```python
+            robots = client.robots("https://example.com/some/page")
+            if robots.allowed("mybot", "/some/page"):
+                ...
+            ```
         """
         ...
-
-class RClient:
-    def __init__(
+    def sitemap(
         self,
-        auth: tuple[str, str | None] | None = None,
+        url: str,
+        headers: dict[str, HeaderValue | None] | None = None,
+        cookies: dict[str, str] | None = None,
+        auth: AuthTypes | None = None,
         auth_bearer: str | None = None,
+        timeout: float | None = ...,
+    ) -> SitemapIterator:
+        """
+        Iterate over the page URLs (`<loc>` entries) of a sitemap.
+
+        Sitemap indexes are followed recursively, gzipped sitemaps are decompressed and
+        plain text sitemaps (one URL per line) are supported. Each sitemap is fetched
+        lazily, once the entries of the previous one have been used up.
+
+        Args:
+            url: URL of the sitemap or sitemap index.
+            headers: Headers sent with every sitemap request; `None` and callable values as for `request()`.
+            cookies: Cookies sent with every sitemap request.
+            auth: Auth credentials.
+            auth_bearer: Bearer token.
+            timeout: Timeout for each sitemap request in seconds.
+
+        Raises:
+            HTTPStatusError: If a sitemap answers with a 4xx/5xx status.
+
+        Example:
+            ```python
+            for page_url in client.sitemap("https://example.com/sitemap.xml"):
+                print(page_url)
+            ```
+        """
+        ...
+    def paginate(
+        self,
+        url: str,
+        strategy: Literal["cursor", "page", "offset", "link"] = "cursor",
+        item_path: str | None = None,
+        next_path: str | None = None,
+        param: str | None = None,
+        page_size: int | None = None,
+        limit_param: str | None = None,
+        start: int | None = None,
+        max_pages: int | None = None,
         params: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: dict[str, HeaderValue | None] | None = None,
         cookies: dict[str, str] | None = None,
-        timeout: float | None = None,
-        cookie_store: bool | None = True,
-        referer: bool | None = True,
-        proxy: str | None = None,
-        follow_redirects: bool | None = True,
-        max_redirects: int | None = 20,
-        verify: bool | None = True,
-        ca_cert_file: str | None = None,
-        client_pem: str | None = None,
-        client_pem_data: bytes | None = None,
-        https_only: bool | None = False,
-        http2_only: bool | None = False,
-    ): ...
-    @property
-    def headers(self) -> dict[str, str]: ...
-    @headers.setter
-    def headers(self, headers: dict[str, str] | None) -> None: ...
-    @property
-    def cookies(self) -> dict[str, str]: ...
-    @cookies.setter
-    def cookies(self, cookies: dict[str, str] | None) -> None: ...
-    @property
-    def proxy(self) -> str | None: ...
-    @proxy.setter
-    def proxy(self, proxy: str) -> None: ...
-    @property
-    def auth(self) -> tuple[str, str | None] | None: ...
-    @auth.setter
-    def auth(self, auth: tuple[str, str | None] | None) -> None: ...
+        auth: AuthTypes | None = None,
+        auth_bearer: str | None = None,
+        timeout: float | None = ...,
+    ) -> Paginator:
+        """
+        Iterate over the items of a paginated JSON API, requesting pages lazily.
+
+        Each page is parsed as JSON and the list at `item_path` is yielded item by item;
+        the next page is requested only once the current one is used up.
+
+        Args:
+            url: URL of the first page.
+            strategy: How the next page is requested. "cursor" sends the value at
+                `next_path` and stops when it is missing, null or empty. "page" sends page
+                numbers from `start` (default 1). "offset" sends the number of items received
+                so far, from `start` (default 0). "link" follows the `Link: <...>; rel="next"`
+                header. Page and offset pagination stop on an empty page, or one shorter
+                than `page_size`.
+            item_path: Dotted path to the items in each page, e.g. "data.items".
+                Default is None (the page itself is the list).
+            next_path: Dotted path to the next cursor. Required for "cursor".
+            param: Query parameter carrying the cursor, page or offset. Default is the
+                strategy name.
+            page_size: Sent as the `limit_param` query parameter.
+            limit_param: Query parameter for `page_size`. Default is "limit".
+            start: First page number or offset.
+            max_pages: Stop after this many pages.
+            params: Query parameters sent with every page.
+            headers: Headers sent with every page; `None` and callable values as for `request()`.
+            cookies: Cookies sent with every page.
+            auth: Auth credentials.
+            auth_bearer: Bearer token.
+            timeout: Timeout for each page in seconds.
+
+        Raises:
+            ValueError: On an unknown strategy, or "cursor" without `next_path`.
+            HTTPStatusError: If a page answers with a 4xx/5xx status.
+
+        Example:
+            ```python
+            for user in client.paginate(
+                "https://api.example.com/users",
+                strategy="cursor",
+                item_path="data.items",
+                next_path="data.next_cursor",
+            ):
+                print(user["id"])
+            ```
+        """
+        ...
+    def history(self) -> list[HistoryEntry]:
+        """
+        Summaries of the most recent requests, oldest first.
+
+        Keeps the last `history_size` requests; empty when the client was created without
+        it. Each call records one entry (an on-challenge retry is part of the same entry);
+        download_parallel() records its probe and every range request.
+        """
+        ...
+    def clear_history(self) -> None:
+        """Forget the recorded request history."""
+        ...
     @property
-    def auth_bearer(self) -> str | None: ...
-    @auth_bearer.setter
-    def auth_bearer(self, auth_bearer: str | None) -> None: ...
+    def bytes_sent(self) -> int:
+        """Request body bytes sent by this client so far."""
+        ...
     @property
-    def params(self) -> dict[str, str] | None: ...
-    @params.setter
-    def params(self, params: dict[str, str] | None) -> None: ...
+    def bytes_received(self) -> int:
+        """Response body bytes received by this client so far, before content decoding."""
+        ...
     @property
-    def timeout(self) -> float | None: ...
-    @timeout.setter
-    def timeout(self, timeout: float | None) -> None: ...
-    def request(self, method: HttpMethod, url: str, **kwargs: Unpack[RequestParams]) -> Response: ...
-    def _stream(self, method: HttpMethod, url: str, **kwargs: Unpack[RequestParams]) -> StreamingResponse: ...
+    def bytes_decompressed(self) -> int:
+        """Response body bytes received by this client so far, after content decoding."""
+        ...
+    def flush_dns(self) -> None:
+        """
+        Drop every entry from the client's DNS cache.
+
+        The next connection to any host resolves it again. Connections already in the
+        pool are kept. Does nothing when the client was created without dns_cache_ttl.
+        """
+        ...
     def get(self, url: str, **kwargs: Unpack[RequestParams]) -> Response: ...
     def head(self, url: str, **kwargs: Unpack[RequestParams]) -> Response: ...
     def options(self, url: str, **kwargs: Unpack[RequestParams]) -> Response: ...
@@ -362,15 +2021,15 @@ class Client(RClient):
     """
     def __init__(
         self,
-        auth: tuple[str, str | None] | None = None,
+        auth: AuthTypes | None = None,
         auth_bearer: str | None = None,
         params: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: dict[str, HeaderValue] | None = None,
         cookies: dict[str, str] | None = None,
         cookie_store: bool | None = True,
         referer: bool | None = True,
         proxy: str | None = None,
-        timeout: float | None = 30,
+        timeout: float | None = None,
         follow_redirects: bool | None = True,
         max_redirects: int | None = 20,
         verify: bool | None = True,
@@ -379,20 +2038,73 @@ class Client(RClient):
         client_pem_data: bytes | None = None,
         https_only: bool | None = False,
         http2_only: bool | None = False,
+        max_download_rate: float | str | None = None,
+        max_upload_rate: float | str | None = None,
+        low_speed_limit: int | None = None,
+        low_speed_time: float | None = None,
+        dns_cache_ttl: float | None = None,
+        dns_cache_negative_ttl: float | None = None,
+        reject_third_party_cookies: bool | None = False,
+        blocked_cookie_domains: list[str] | None = None,
+        max_cookies_per_domain: int | None = None,
+        max_cookie_size: int | None = None,
+        cookie_public_suffix: bool | None = False,
+        auth_mode: Literal["preemptive", "on_challenge"] | None = "preemptive",
+        sign: Callable[[str, str, dict[str, str], bytes | None], dict[str, str] | None] | None = None,
+        history_size: int | None = None,
+        respect_robots_txt: bool = False,
+        robots_cache_ttl: float = 3600.0,
+        allow_hosts: list[str] | None = None,
+        block_hosts: list[str] | None = None,
+        block_private_ips: bool = False,
+        allowed_schemes: list[str] | None = None,
+        max_header_bytes: int | None = None,
+        max_header_count: int | None = None,
+        max_decompressed_size: int | None = None,
+        max_decompression_ratio: float | None = None,
+        json_serializer: Callable[[Any], Any] | None = None,
+        json_canonical: bool = False,
+        json_sort_keys: bool = False,
+        json_ensure_ascii: bool = False,
+        json_separators: tuple[str, str] | None = None,
+        json_indent: int | None = None,
+        on_connection_event: Callable[[dict[str, Any]], None] | None = None,
+        http2_keep_alive_interval: float | None = None,
+        http2_keep_alive_timeout: float | None = None,
+        http2_keep_alive_while_idle: bool | None = None,
+        retry_idempotent_on_connection_error: bool = True,
+        cookie_merge: Literal["merge", "replace"] = "merge",
+        connect_timeout: float | None = 30,
+        read_timeout: float | None = 30,
+        trust_env: bool = True,
+        base_url: str | None = None,
+        codecs: dict[str, tuple[Callable[[bytes], bytes] | None, Callable[[bytes], bytes] | None]] | None = None,
+        request_encoding: str | None = None,
+        url_credentials: bool = True,
+        transport: PythonTransport | None = None,
+        send_content_digest: Literal["sha-256", "sha-512"] | None = None,
+        allow_sni_mismatch: bool = False,
+        alpn_protocols: list[Literal["h2", "http/1.1"]] | None = None,
+        tls_session_tickets: bool = True,
+        tls_session_cache_size: int | None = None,
+        verify_hook: Callable[[str, list[bytes], str | None], bool] | None = None,
     ) -> None:
         """
         Initialize an HTTP client.
 
         Args:
-            auth: Basic auth credentials as (username, password). Password can be None.
+            auth: Basic auth credentials as (username, password), or an auth object
+                (HmacAuth, ApiKeyAuth) applied to every request. Password can be None.
             auth_bearer: Bearer token for Authorization header.
             params: Default query parameters to include in all requests.
-            headers: Default headers to send with all requests.
+            headers: Default headers to send with all requests. A value may be a callable returning it (or None to
+                leave the header out), called for every attempt: each redirect hop and retry gets a fresh value.
             cookies: Default cookies to send with all requests.
             cookie_store: Enable persistent cookie store. Default is True.
             referer: Automatically set Referer header. Default is True.
             proxy: Proxy URL (e.g., "http://proxy:8080" or "socks5://127.0.0.1:1080").
-            timeout: Request timeout in seconds. Default is 30.
+            timeout: Total timeout for each request in seconds, response body included. Default is
+                None (no overall limit); connect_timeout and read_timeout still apply.
             follow_redirects: Follow HTTP redirects. Default is True.
             max_redirects: Maximum redirects to follow. Default is 20.
             verify: Verify SSL certificates. Default is True.
@@ -401,6 +2113,142 @@ class Client(RClient):
             client_pem_data: Client certificate and key as bytes for mTLS (PEM format).
             https_only: Only allow HTTPS requests. Default is False.
             http2_only: Use HTTP/2 only. Default is False.
+            max_download_rate: Cap on response body throughput, in bytes per second or as a
+                string like "5MB/s". Shared by all requests made with this client.
+            max_upload_rate: Cap on request body throughput, same format as max_download_rate.
+            low_speed_limit: Abort response body transfers whose throughput stays below this many
+                bytes per second for low_speed_time seconds, raising ReadTimeout (curl semantics).
+            low_speed_time: Window for low_speed_limit in seconds. Default is 30.
+            dns_cache_ttl: Cache resolved host addresses in-process for at most this many
+                seconds. Default is None (no cache).
+            dns_cache_negative_ttl: Also cache failed lookups for this many seconds.
+                Requires dns_cache_ttl.
+            reject_third_party_cookies: Ignore cookies from, and send none to, hosts on a
+                different site than the requested URL (e.g. cross-site redirect targets).
+            blocked_cookie_domains: Never store or send cookies for these domains or their
+                subdomains.
+            max_cookies_per_domain: Ignore new cookies once a domain holds this many.
+            max_cookie_size: Ignore cookies whose name and value exceed this many bytes.
+            cookie_public_suffix: Reject cookies scoped to a public suffix such as "co.uk",
+                using the bundled Public Suffix List. Default is False.
+            auth_mode: "preemptive" sends credentials with every request. "on_challenge"
+                withholds them until the server answers 401 with a matching WWW-Authenticate
+                challenge, then repeats the request with them. Default is "preemptive".
+            sign: Called as sign(method, url, headers, body) on every request just before it
+                is sent, after client defaults and auth are applied. url includes the query
+                string and body is None for requests without one. May return a dict of
+                headers to add. Exceptions propagate to the caller.
+            history_size: Keep summaries of the last this many requests (method, url,
+                status, timing, error), returned by history(). Default is None (no history).
+            respect_robots_txt: Check the robots.txt rules of each host (cached) before
+                sending a request to it, raising RobotsDisallowed when the request's
+                User-Agent may not fetch the URL. Default is False.
+            robots_cache_ttl: Seconds to cache the robots.txt rules of a host. Default is 3600.
+            allow_hosts: Only send requests to, and follow redirects to, these hosts. Entries
+                are host names or IP addresses; "*.example.com" matches subdomains.
+                Default is None (any host).
+            block_hosts: Never send requests to these hosts, same format as allow_hosts.
+            block_private_ips: Refuse hosts that are, or resolve to, loopback, private,
+                link-local or other non-public addresses, checked after DNS resolution and
+                for every redirect target. Through a proxy, which resolves host names
+                itself, only IP addresses in URLs are checked. Raises HostBlocked.
+                Default is False.
+            allowed_schemes: URL schemes requests and redirects may use, "http" and/or
+                "https". Other schemes such as "ftp" or "file" raise UnsupportedProtocol.
+                Default is None (both).
+            max_header_bytes: Maximum total size in bytes of the headers of a response, counted
+                as "name: value\r\n" lines. Raises RemoteProtocolError when exceeded.
+                Default is None (no limit).
+            max_header_count: Maximum number of headers of a response. Raises
+                RemoteProtocolError when exceeded. Default is None (no limit).
+            max_decompressed_size: Maximum size in bytes of a decoded (gzip, deflate, brotli,
+                zstd) response body. Raises DecodingError when exceeded. Default is None.
+            max_decompression_ratio: Maximum ratio of decoded to compressed bytes of a response
+                body, enforced once more than 1 MiB has been decoded. Raises DecodingError
+                when exceeded. Default is None.
+            json_serializer: Called with each object in json= that is not a dict, list, tuple,
+                str, number, bool or None, returning a JSON-compatible value to send instead
+                (converted again, so it may contain further objects), or NotImplemented for the
+                built-in conversion of pydantic models (model_dump()), dataclasses, enums,
+                datetimes, UUIDs and public __dict__ attributes.
+            json_canonical: Serialize json= bodies in the JSON Canonicalization Scheme (RFC 8785): no
+                whitespace, object keys sorted and numbers in their shortest form, as APIs verifying a
+                detached signature over the payload require. Default is False.
+            json_sort_keys: Sort the keys of objects in json= bodies, as json.dumps(sort_keys=True).
+                Default is False (insertion order).
+            json_ensure_ascii: Escape non-ASCII characters of json= bodies as \\uXXXX, as
+                json.dumps(ensure_ascii=True). Default is False (UTF-8).
+            json_separators: The (item_separator, key_separator) of json= bodies, e.g. (", ", ": ") as
+                json.dumps uses by default. Default is None ((",", ":"), or (",", ": ") with json_indent).
+            json_indent: Pretty-print json= bodies, indenting each level by this many spaces. Default is
+                None (one line).
+            on_connection_event: Called with a dict for each DNS lookup ("dns_resolved": host,
+                addresses, elapsed, error) and for each response, saying whether its connection was
+                opened for it ("connection_created": url, remote_addr, local_addr, elapsed covering
+                connect and TLS handshake, tls, http2) or reused from the pool ("connection_reused").
+                Exceptions it raises are reported through sys.unraisablehook.
+            http2_keep_alive_interval: Seconds between HTTP/2 PING frames, which keep connections
+                alive and detect dead ones. Default is None (no pings).
+            http2_keep_alive_timeout: Seconds to wait for a PING acknowledgement before closing
+                the connection. Default is 20.
+            http2_keep_alive_while_idle: Also ping connections with no open request. Default is False.
+            retry_idempotent_on_connection_error: Send GET, HEAD, OPTIONS, PUT and DELETE requests once
+                more when connecting fails or the connection breaks before any response arrives (e.g. a
+                stale pooled connection). Timeouts and failed DNS lookups are not retried. Default is True.
+            cookie_merge: How per-request `cookies` combine with the client `cookies`. "merge" sends both,
+                a request cookie replacing a client cookie of the same name; "replace" sends only the
+                request cookies. Default is "merge".
+            connect_timeout: Seconds allowed for connecting, or None for no limit. Default is 30.
+            read_timeout: Seconds a response may go without receiving any data, or None for no limit.
+                Default is 30, which also ends Server-Sent Events and long-poll responses that stay
+                idle for longer; use None for clients reading those.
+            trust_env: Read defaults for arguments left out from the HTTPR_TIMEOUT, HTTPR_VERIFY,
+                HTTPR_PROXY, HTTPR_HTTP_PROXY, HTTPR_HTTPS_PROXY and HTTPR_DEFAULT_HEADERS (a JSON object)
+                environment variables, and use the system proxy settings. Default is True.
+            base_url: Absolute URL that relative request URLs are appended to, e.g. "users" or "/users"
+                on "https://api.example.com/v1" request "https://api.example.com/v1/users". Default is None.
+            codecs: Custom Content-Encodings, as a dict of names to (compress, decompress) pairs of callables
+                taking and returning bytes, either of which may be None. Responses in such an encoding are read
+                whole and decoded, and the names are added to Accept-Encoding. Default is None.
+            request_encoding: Compress request bodies with this encoding: "gzip", "deflate", "br", "zstd" or
+                the name of a codec. Requests setting Content-Encoding are sent as is. Default is None.
+            url_credentials: Send the "user:password@" part of request URLs as basic auth, unless `auth` or
+                `auth_bearer` is set. It is removed from the URL either way, so it never appears in
+                `response.url`, the history or logs. Default is True.
+            transport: A PythonTransport sending the requests through a Python callable instead of the
+                client's own connections, e.g. to route them through an existing SDK or tunnel. Redirects,
+                cookies, auth and decompression are still handled by the client. Default is None.
+            send_content_digest: Attach a Content-Digest header (RFC 9530) with the "sha-256" or "sha-512"
+                digest of every request body; streamed bodies get it as a trailer. Responses carrying a
+                Content-Digest or Digest header are then checked against it as they are read, raising
+                DecodingError on a mismatch. Default is None.
+            allow_sni_mismatch: Send HTTPS requests whose Host header names another host than their TLS
+                server name (`sni_hostname` or the URL host), as for domain fronting or testing CDN routing.
+                Such requests raise ValueError otherwise. Default is False.
+            alpn_protocols: The protocols offered in the TLS handshake (ALPN), in order of preference: "h2"
+                and/or "http/1.1", e.g. ["http/1.1"] to keep servers and middleboxes from choosing HTTP/2.
+                An empty list sends no ALPN extension. Default is None (["h2", "http/1.1"]).
+            tls_session_tickets: Resume TLS sessions from session tickets. With False, TLS 1.2 sessions resume
+                by session ID only and TLS 1.3 sessions, which need tickets, are not resumed. Resumed sessions
+                never send early data (0-RTT), which is not supported. Default is True.
+            tls_session_cache_size: Number of servers whose TLS sessions are kept for resumption, or 0 to never
+                resume sessions. Default is None (256).
+            verify_hook: A callable deciding whether to accept the server certificate of every TLS handshake,
+                called as verify_hook(hostname, chain, error) with the DER-encoded certificate chain (server
+                certificate first) and the reason code of the default verification failing (e.g. "expired"), or
+                None when it passed. A true result accepts the certificate, a false one rejects it, with reason
+                "rejected_by_hook" if it passed. Exceptions it raises are re-raised. Default is None.
+        """
+        ...
+    @classmethod
+    def from_file(cls, path: str | os.PathLike[str], profile: str | None = None, **kwargs: Any) -> Self:
+        """
+        Create a client from a TOML or JSON configuration file.
+
+        The top-level keys of the file are client arguments, and `profiles` holds named
+        profiles applied on top of them (merging `headers`, `cookies` and `params`).
+        Relative `ca_cert_file` and `client_pem` paths are resolved against the file's
+        directory. `kwargs` override the options of the file.
         """
         ...
     def __enter__(self) -> Client: ...
@@ -408,6 +2256,86 @@ class Client(RClient):
     def close(self) -> None:
         """Close the client and release resources."""
         ...
+    def request_later(
+        self, delay: float, method: HttpMethod, url: str, **kwargs: Unpack[RequestParams]
+    ) -> Future[Response]:
+        """
+        Send a request after `delay` seconds, from a background thread.
+
+        Args:
+            delay: Seconds to wait before sending.
+            method: HTTP method.
+            url: Request URL.
+            **kwargs: Request parameters, including `cancel_token`.
+
+        Returns:
+            A future resolving to the Response. Cancelling it before the delay has
+            passed skips the request; a `cancel_token` also aborts it once sent.
+        """
+        ...
+    def get_json(self, url: str, **kwargs: Unpack[RequestParams]) -> Any:
+        """
+        GET a JSON API resource and return the parsed body (None for an empty body).
+
+        Raises HTTPStatusError (with the parsed error body as `error_body`) for a 4xx/5xx
+        status, and DecodingError if the response is not JSON.
+        """
+        ...
+    def post_json(self, url: str, json: Any = None, **kwargs: Unpack[RequestParams]) -> Any:
+        """POST `json` to a JSON API and return the parsed response body, checked like `get_json()`."""
+        ...
+    def propfind(
+        self,
+        url: str,
+        depth: int | str = 1,
+        properties: list[str] | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> list[DavResource]:
+        """Make a WebDAV PROPFIND request and parse the 207 multistatus response."""
+        ...
+    def mkcol(self, url: str, **kwargs: Unpack[RequestParams]) -> Response:
+        """Make a WebDAV MKCOL request, creating a collection."""
+        ...
+    def copy(
+        self,
+        url: str,
+        destination: str,
+        overwrite: bool = True,
+        depth: int | str = "infinity",
+        **kwargs: Unpack[RequestParams],
+    ) -> Response:
+        """Make a WebDAV COPY request."""
+        ...
+    def move(
+        self, url: str, destination: str, overwrite: bool = True, **kwargs: Unpack[RequestParams]
+    ) -> Response:
+        """Make a WebDAV MOVE request."""
+        ...
+    def list_directory(
+        self, url: str, webdav: bool | None = None, **kwargs: Unpack[RequestParams]
+    ) -> list[DirectoryEntry]:
+        """List a directory from its WebDAV collection or its auto-index HTML page."""
+        ...
+    def sync(
+        self,
+        files: Mapping[str, str | os.PathLike[str]],
+        state_file: str | os.PathLike[str] | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> dict[str, SyncResult]:
+        """Mirror URLs to local files, downloading only resources whose ETag/Last-Modified changed."""
+        ...
+    def soap(
+        self,
+        url: str,
+        action: str,
+        envelope: str | bytes | dict[str, Any],
+        version: SoapVersion | None = None,
+        header: dict[str, Any] | None = None,
+        namespace: str | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> SoapResponse:
+        """Call a SOAP operation and parse the response envelope, raising SoapFault on a Fault."""
+        ...
     def stream(
         self, method: HttpMethod, url: str, **kwargs: Unpack[RequestParams]
     ) -> AbstractContextManager[StreamingResponse]:
@@ -462,15 +2390,15 @@ class AsyncClient(Client):
     """
     def __init__(
         self,
-        auth: tuple[str, str | None] | None = None,
+        auth: AuthTypes | None = None,
         auth_bearer: str | None = None,
         params: dict[str, str] | None = None,
-        headers: dict[str, str] | None = None,
+        headers: dict[str, HeaderValue] | None = None,
         cookies: dict[str, str] | None = None,
         cookie_store: bool | None = True,
         referer: bool | None = True,
         proxy: str | None = None,
-        timeout: float | None = 30,
+        timeout: float | None = None,
         follow_redirects: bool | None = True,
         max_redirects: int | None = 20,
         verify: bool | None = True,
@@ -479,6 +2407,56 @@ class AsyncClient(Client):
         client_pem_data: bytes | None = None,
         https_only: bool | None = False,
         http2_only: bool | None = False,
+        max_download_rate: float | str | None = None,
+        max_upload_rate: float | str | None = None,
+        low_speed_limit: int | None = None,
+        low_speed_time: float | None = None,
+        dns_cache_ttl: float | None = None,
+        dns_cache_negative_ttl: float | None = None,
+        reject_third_party_cookies: bool | None = False,
+        blocked_cookie_domains: list[str] | None = None,
+        max_cookies_per_domain: int | None = None,
+        max_cookie_size: int | None = None,
+        cookie_public_suffix: bool | None = False,
+        auth_mode: Literal["preemptive", "on_challenge"] | None = "preemptive",
+        sign: Callable[[str, str, dict[str, str], bytes | None], dict[str, str] | None] | None = None,
+        history_size: int | None = None,
+        respect_robots_txt: bool = False,
+        robots_cache_ttl: float = 3600.0,
+        allow_hosts: list[str] | None = None,
+        block_hosts: list[str] | None = None,
+        block_private_ips: bool = False,
+        allowed_schemes: list[str] | None = None,
+        max_header_bytes: int | None = None,
+        max_header_count: int | None = None,
+        max_decompressed_size: int | None = None,
+        max_decompression_ratio: float | None = None,
+        json_serializer: Callable[[Any], Any] | None = None,
+        json_canonical: bool = False,
+        json_sort_keys: bool = False,
+        json_ensure_ascii: bool = False,
+        json_separators: tuple[str, str] | None = None,
+        json_indent: int | None = None,
+        on_connection_event: Callable[[dict[str, Any]], None] | None = None,
+        http2_keep_alive_interval: float | None = None,
+        http2_keep_alive_timeout: float | None = None,
+        http2_keep_alive_while_idle: bool | None = None,
+        retry_idempotent_on_connection_error: bool = True,
+        cookie_merge: Literal["merge", "replace"] = "merge",
+        connect_timeout: float | None = 30,
+        read_timeout: float | None = 30,
+        trust_env: bool = True,
+        base_url: str | None = None,
+        codecs: dict[str, tuple[Callable[[bytes], bytes] | None, Callable[[bytes], bytes] | None]] | None = None,
+        request_encoding: str | None = None,
+        url_credentials: bool = True,
+        transport: PythonTransport | None = None,
+        send_content_digest: Literal["sha-256", "sha-512"] | None = None,
+        allow_sni_mismatch: bool = False,
+        alpn_protocols: list[Literal["h2", "http/1.1"]] | None = None,
+        tls_session_tickets: bool = True,
+        tls_session_cache_size: int | None = None,
+        verify_hook: Callable[[str, list[bytes], str | None], bool] | None = None,
     ) -> None:
         """Initialize an async HTTP client. Accepts the same parameters as Client."""
         ...
@@ -527,6 +2505,180 @@ class AsyncClient(Client):
     ) -> Response:
         """Make an async PATCH request."""
         ...
+    async def get_json(  # type: ignore[override]
+        self, url: str, **kwargs: Unpack[RequestParams]
+    ) -> Any:
+        """GET a JSON API resource asynchronously and return the parsed body."""
+        ...
+    async def post_json(  # type: ignore[override]
+        self, url: str, json: Any = None, **kwargs: Unpack[RequestParams]
+    ) -> Any:
+        """POST `json` to a JSON API asynchronously and return the parsed response body."""
+        ...
+    async def propfind(  # type: ignore[override]
+        self,
+        url: str,
+        depth: int | str = 1,
+        properties: list[str] | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> list[DavResource]:
+        """Make an async WebDAV PROPFIND request."""
+        ...
+    async def mkcol(  # type: ignore[override]
+        self, url: str, **kwargs: Unpack[RequestParams]
+    ) -> Response:
+        """Make an async WebDAV MKCOL request."""
+        ...
+    async def copy(  # type: ignore[override]
+        self,
+        url: str,
+        destination: str,
+        overwrite: bool = True,
+        depth: int | str = "infinity",
+        **kwargs: Unpack[RequestParams],
+    ) -> Response:
+        """Make an async WebDAV COPY request."""
+        ...
+    async def move(  # type: ignore[override]
+        self, url: str, destination: str, overwrite: bool = True, **kwargs: Unpack[RequestParams]
+    ) -> Response:
+        """Make an async WebDAV MOVE request."""
+        ...
+    async def list_directory(  # type: ignore[override]
+        self, url: str, webdav: bool | None = None, **kwargs: Unpack[RequestParams]
+    ) -> list[DirectoryEntry]:
+        """List a directory asynchronously."""
+        ...
+    async def sync(  # type: ignore[override]
+        self,
+        files: Mapping[str, str | os.PathLike[str]],
+        state_file: str | os.PathLike[str] | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> dict[str, SyncResult]:
+        """Mirror URLs to local files asynchronously."""
+        ...
+    async def soap(  # type: ignore[override]
+        self,
+        url: str,
+        action: str,
+        envelope: str | bytes | dict[str, Any],
+        version: SoapVersion | None = None,
+        header: dict[str, Any] | None = None,
+        namespace: str | None = None,
+        **kwargs: Unpack[RequestParams],
+    ) -> SoapResponse:
+        """Call a SOAP operation asynchronously."""
+        ...
+    async def download_parallel(  # type: ignore[override]
+        self,
+        url: str,
+        path: str | os.PathLike[str],
+        connections: int = 8,
+        params: dict[str, str] | None = None,
+        headers: dict[str, HeaderValue | None] | None = None,
+        cookies: dict[str, str] | None = None,
+        auth: AuthTypes | None = None,
+        auth_bearer: str | None = None,
+        timeout: float | None = ...,
+        sha256: str | None = None,
+        max_download_rate: float | str | None = None,
+        low_speed_limit: int | None = None,
+        low_speed_time: float | None = None,
+    ) -> int:
+        """Download a file asynchronously using concurrent byte-range requests."""
+        ...
+    async def upgrade(  # type: ignore[override]
+        self,
+        url: str,
+        protocol: str,
+        *,
+        method: str = "GET",
+        headers: dict[str, str] | None = None,
+        timeout: float | None = ...,
+    ) -> Stream:
+        """Upgrade the connection of a request asynchronously; the `Stream` itself blocks."""
+        ...
+    async def connect_tunnel(  # type: ignore[override]
+        self,
+        host: str,
+        port: int,
+        via_proxy: bool = True,
+        *,
+        headers: dict[str, str] | None = None,
+        timeout: float | None = ...,
+    ) -> Stream:
+        """Open a raw TCP tunnel asynchronously; the `Stream` itself blocks."""
+        ...
+    async def send(self, request: Request) -> Response:  # type: ignore[override]
+        """Send a prepared Request asynchronously."""
+        ...
+    async def send_template_many(  # type: ignore[override]
+        self,
+        template: RequestTemplate,
+        vars: list[dict[str, Any]],
+        max_concurrency: int | None = None,
+        return_exceptions: bool = False,
+        dedupe_on: tuple[DedupeField, ...] | list[DedupeField] | None = None,
+    ) -> list[Response | Exception]:
+        """Render a RequestTemplate per entry in `vars` and send the requests concurrently."""
+        ...
+    def fetch_all(  # type: ignore[override]
+        self,
+        urls: list[str],
+        max_concurrency: int = 32,
+        ordered: bool = False,
+        params: dict[str, str] | None = None,
+        headers: dict[str, HeaderValue | None] | None = None,
+        cookies: dict[str, str] | None = None,
+        auth: AuthTypes | None = None,
+        auth_bearer: str | None = None,
+        timeout: float | None = ...,
+        stream: bool = False,
+    ) -> AsyncIterator[Response | StreamingResponse | Exception]:
+        """Fetch many URLs concurrently, yielding each result as it completes (`async for`)."""
+        ...
+    async def robots(  # type: ignore[override]
+        self,
+        url: str,
+        headers: dict[str, HeaderValue | None] | None = None,
+        cookies: dict[str, str] | None = None,
+        auth: AuthTypes | None = None,
+        auth_bearer: str | None = None,
+        timeout: float | None = ...,
+    ) -> RobotsTxt:
+        """Fetch and parse the robots.txt file governing `url` asynchronously."""
+        ...
+    def sitemap(  # type: ignore[override]
+        self,
+        url: str,
+        headers: dict[str, HeaderValue | None] | None = None,
+        cookies: dict[str, str] | None = None,
+        auth: AuthTypes | None = None,
+        auth_bearer: str | None = None,
+        timeout: float | None = ...,
+    ) -> AsyncIterator[str]:
+        """Iterate asynchronously over the page URLs of a sitemap (`async for`)."""
+        ...
+    def paginate(  # type: ignore[override]
+        self,
+        url: str,
+        strategy: Literal["cursor", "page", "offset", "link"] = "cursor",
+        item_path: str | None = None,
+        next_path: str | None = None,
+        param: str | None = None,
+        page_size: int | None = None,
+        limit_param: str | None = None,
+        start: int | None = None,
+        max_pages: int | None = None,
+        params: dict[str, str] | None = None,
+        headers: dict[str, HeaderValue | None] | None = None,
+        cookies: dict[str, str] | None = None,
+        auth: AuthTypes | None = None,
+        auth_bearer: str | None = None,
+        timeout: float | None = ...,
+    ) -> AsyncIterator[Any]:
+        """Iterate asynchronously over the items of a paginated JSON API (`async for`)."""
+        ...
     def stream(  # type: ignore[override]
         self, method: HttpMethod, url: str, **kwargs: Unpack[RequestParams]
     ) -> AbstractAsyncContextManager[StreamingResponse]:
@@ -552,6 +2704,57 @@ def delete(url: str, **kwargs: Unpack[ClientRequestParams]) -> Response: ...
 def post(url: str, **kwargs: Unpack[ClientRequestParams]) -> Response: ...
 def put(url: str, **kwargs: Unpack[ClientRequestParams]) -> Response: ...
 def patch(url: str, **kwargs: Unpack[ClientRequestParams]) -> Response: ...
+def presign_url(
+    url: str,
+    access_key: str,
+    secret_key: str,
+    region: str,
+    method: str = "GET",
+    expires: int = 3600,
+    service: str = "s3",
+    session_token: str | None = None,
+    timestamp: int | None = None,
+) -> str:
+    """
+    Generate an AWS Signature Version 4 presigned URL.
+
+    The returned URL performs a single `method` request until it expires, without
+    sharing credentials. Works with S3 and S3-compatible stores (MinIO, R2, ...).
+
+    Args:
+        url: Object URL, e.g. `https://bucket.s3.eu-west-1.amazonaws.com/key.txt`.
+        access_key: AWS access key id.
+        secret_key: AWS secret access key.
+        region: Signing region, e.g. `us-east-1`.
+        method: HTTP method the URL is valid for.
+        expires: Lifetime in seconds, at most 604800 (7 days).
+        service: Signing service name.
+        session_token: Session token for temporary credentials.
+        timestamp: Signing time as a Unix timestamp. Defaults to now.
+
+    Returns:
+        The presigned URL.
+
+    Raises:
+        InvalidURL: If `url` cannot be parsed.
+        ValueError: If `expires` is out of range.
+    """
+    ...
+
+def runtime_stats() -> RuntimeStats:
+    """
+    Statistics of the Tokio runtime shared by all clients.
+
+    Use them to diagnose saturation: a growing `queue_depth`, or `busy_time` rising
+    almost as fast as wall-clock time, means the single runtime thread is the bottleneck.
+
+    Example:
+        ```python
+        stats = httpr.runtime_stats()
+        print(stats.alive_tasks, stats.queue_depth, stats.blocking_threads)
+        ```
+    """
+    ...
 
 # Exception hierarchy - Base exceptions
 class HTTPError(Exception):
@@ -566,6 +2769,16 @@ class TransportError(RequestError):
 class NetworkError(TransportError):
     """The base class for network-related errors."""
 
+    errno: int | None
+    """
+    OS error number of the failed socket operation, to compare with the `errno` module
+    (e.g. `errno.ECONNREFUSED`, `errno.EHOSTUNREACH`, `errno.ECONNRESET`), or None.
+    """
+    os_error: str | None
+    """OS message for the socket error, e.g. "Connection refused", or None."""
+    remote_address: tuple[str, int] | None
+    """(host, port) the request was sent to, or None."""
+
 class TimeoutException(TransportError):
     """The base class for timeout errors."""
 
@@ -592,6 +2805,34 @@ class PoolTimeout(TimeoutException):
 class ConnectError(NetworkError):
     """Failed to establish a connection."""
 
+class DNSError(ConnectError):
+    """The host name could not be resolved."""
+
+    host: str
+    kind: Literal["not_found", "temporary", "other"]
+    """
+    "not_found" for a name that does not exist or has no addresses (NXDOMAIN), "temporary" for an
+    unreachable resolver or a temporary failure.
+    """
+
+class SSLError(ConnectError):
+    """The TLS handshake failed."""
+
+    reason: str
+    """
+    "alert_received" (the server aborted the handshake, e.g. rejecting the client certificate),
+    "peer_incompatible", "protocol_error" or "other"; see `CertificateVerifyError` for the rest.
+    """
+
+class CertificateVerifyError(SSLError):
+    """The server certificate could not be verified."""
+
+    reason: str
+    """
+    "expired", "not_yet_valid", "hostname_mismatch", "unknown_ca" (including self-signed certificates),
+    "revoked", "bad_signature", "invalid_purpose", "no_certificate" or "other".
+    """
+
 class ReadError(NetworkError):
     """Failed to receive data from the network."""
 
@@ -610,7 +2851,7 @@ class RemoteProtocolError(ProtocolError):
 
 # Other transport/request exceptions
 class UnsupportedProtocol(TransportError):
-    """Attempted to make a request to an unsupported protocol."""
+    """Attempted to make a request to, or follow a redirect to, a URL scheme that is not allowed (`allowed_schemes`)."""
 
 class ProxyError(TransportError):
     """An error occurred while establishing a proxy connection."""
@@ -618,11 +2859,31 @@ class ProxyError(TransportError):
 class TooManyRedirects(RequestError):
     """Too many redirects."""
 
+class RobotsDisallowed(RequestError):
+    """The robots.txt rules of the host disallow the request (`respect_robots_txt=True`)."""
+
+class HostBlocked(RequestError):
+    """The host of the request or a redirect target is not allowed (`allow_hosts`, `block_hosts`, `block_private_ips`)."""
+
 class HTTPStatusError(HTTPError):
     """The response had an error HTTP status of 4xx or 5xx."""
 
+    response: Response
+    status_code: int
+    body_preview: str
+    """At most 1 KiB of the response body, decoded with the response charset."""
+    error_body: Any | None
+    """The whole parsed body, for a JSON error body."""
+    error_fields: dict[str, Any] | None
+    """Well-known error fields (`error`, `message`, `detail`, ...) of a JSON error body."""
+    problem: ProblemDetails | None
+    """RFC 9457 problem details, for `application/problem+json` / `+xml` bodies."""
+
+class RequestCancelled(RequestError):
+    """The request was aborted through its `CancellationToken`."""
+
 class DecodingError(RequestError):
-    """Decoding of the response failed, due to a malformed encoding."""
+    """Decoding of the response failed, due to a malformed encoding or a decompression limit (`max_decompressed_size`, `max_decompression_ratio`)."""
 
 # Stream exceptions
 class StreamConsumed(StreamError):
@@ -649,6 +2910,9 @@ __all__ = [
     "HttpMethod",
     "RequestParams",
     "ClientRequestParams",
+    # Request types
+    "Request",
+    "RequestTemplate",
     # Response types
     "Response",
     "StreamingResponse",
@@ -668,6 +2932,11 @@ __all__ = [
     "post",
     "put",
     "patch",
+    "presign_url",
+    "runtime_stats",
+    "RuntimeStats",
+    "HmacAuth",
+    "ApiKeyAuth",
     # Base exceptions
     "HTTPError",
     "RequestError",
@@ -683,6 +2952,9 @@ __all__ = [
     "PoolTimeout",
     # Network exceptions
     "ConnectError",
+    "DNSError",
+    "SSLError",
+    "CertificateVerifyError",
     "ReadError",
     "WriteError",
     "CloseError",
@@ -693,6 +2965,9 @@ __all__ = [
     "UnsupportedProtocol",
     "ProxyError",
     "TooManyRedirects",
+    "RobotsDisallowed",
+    "HostBlocked",
+    "RequestCancelled",
     "HTTPStatusError",
     "DecodingError",
     # Stream exceptions
@@ -0,0 +1,167 @@
+"""
+Directory listings from auto-index pages and WebDAV.
+
+Used by `Client.list_directory()`. Index pages are the HTML listings generated by
+nginx (`autoindex`), Apache (`mod_autoindex`) and similar servers; sizes and
+modification times are read from the text next to each link when present.
+"""
+
+from __future__ import annotations
+
+import re
+from datetime import datetime, timezone
+from email.utils import parsedate_to_datetime
+from html.parser import HTMLParser
+from typing import TypedDict
+from urllib.parse import unquote, urljoin, urlsplit
+
+from .webdav import DavResource
+
+_DATE_FORMATS = [
+    (re.compile(r"\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}"), "%Y-%m-%d %H:%M:%S"),
+    (re.compile(r"\d{4}-\d{2}-\d{2} \d{2}:\d{2}"), "%Y-%m-%d %H:%M"),
+    (re.compile(r"\d{2}-[A-Za-z]{3}-\d{4} \d{2}:\d{2}"), "%d-%b-%Y %H:%M"),
+]
+_SIZE_RE = re.compile(r"^(\d+(?:\.\d+)?)([KMGTP]?)i?B?$", re.IGNORECASE)
+_UNITS = {"": 1, "K": 1 << 10, "M": 1 << 20, "G": 1 << 30, "T": 1 << 40, "P": 1 << 50}
+
+
+class DirectoryEntry(TypedDict):
+    """One file or subdirectory of a listing."""
+
+    name: str
+    url: str
+    size: int | None
+    mtime: datetime | None
+    is_dir: bool
+
+
+def _size(token: str) -> int | None:
+    """Exact (`1234`) or abbreviated (`1.2K`, `3M`) sizes; `-` and anything else is unknown."""
+    match = _SIZE_RE.match(token)
+    if match is None:
+        return None
+    return int(float(match.group(1)) * _UNITS[match.group(2).upper()])
+
+
+def _details(text: str) -> tuple[datetime | None, int | None]:
+    """Modification time and size from the text after a link, e.g. `16-Oct-2026 13:40   1234`."""
+    for pattern, fmt in _DATE_FORMATS:
+        match = pattern.search(text)
+        if match is None:
+            continue
+        try:
+            mtime = datetime.strptime(match.group(0), fmt).replace(tzinfo=timezone.utc)
+        except ValueError:
+            continue
+        rest = text[match.end() :].split()
+        return mtime, _size(rest[0]) if rest else None
+    return None, None
+
+
+class _IndexParser(HTMLParser):
+    """Collects each link with the text that follows it up to the next link or row."""
+
+    def __init__(self) -> None:
+        super().__init__(convert_charrefs=True)
+        self.links: list[list[str]] = []
+        self._current: list[str] | None = None
+
+    def handle_starttag(self, tag: str, attrs: list[tuple[str, str | None]]) -> None:
+        if tag == "a":
+            href = dict(attrs).get("href")
+            self._current = [href, ""] if href else None
+            if self._current is not None:
+                self.links.append(self._current)
+        elif tag in ("tr", "li", "p", "div"):
+            self._current = None
+
+    def handle_data(self, data: str) -> None:
+        if self._current is None:
+            return
+        line, newline, _ = data.partition("\n")
+        self._current[1] += line
+        if newline:
+            self._current = None
+
+
+def parse_index(html: str, base_url: str) -> list[DirectoryEntry]:
+    """
+    Parse an auto-index HTML page.
+
+    Links leading out of the directory (the parent, column sort links, other hosts)
+    are skipped. Times are taken as UTC, nginx's default.
+
+    Args:
+        html: The page.
+        base_url: URL of the directory, which links are resolved against.
+
+    Returns:
+        One entry per file or subdirectory, in page order.
+    """
+    parser = _IndexParser()
+    parser.feed(html)
+    parser.close()
+    base = urlsplit(base_url)
+    directory = base.path if base.path.endswith("/") else base.path + "/"
+    entries: list[DirectoryEntry] = []
+    seen: set[str] = set()
+    for href, text in parser.links:
+        url = urljoin(base_url, href)
+        target = urlsplit(url)
+        if target.query or (target.scheme, target.netloc) != (base.scheme, base.netloc):
+            continue
+        relative = target.path[len(directory) :] if target.path.startswith(directory) else ""
+        if not relative.strip("/") or "/" in relative.rstrip("/") or url in seen:
+            continue
+        seen.add(url)
+        mtime, size = _details(text)
+        is_dir = relative.endswith("/")
+        entries.append(
+            DirectoryEntry(
+                name=unquote(relative.rstrip("/")),
+                url=url.split("#", 1)[0],
+                size=None if is_dir else size,
+                mtime=mtime,
+                is_dir=is_dir,
+            )
+        )
+    return entries
+
+
+def from_multistatus(resources: list[DavResource], base_url: str) -> list[DirectoryEntry]:
+    """
+    Directory entries from a `Depth: 1` PROPFIND result, without the collection itself.
+
+    Args:
+        resources: Parsed multistatus responses (see `httpr.webdav.parse_multistatus`).
+        base_url: URL of the collection, which hrefs are resolved against.
+    """
+    collection = urlsplit(base_url).path.rstrip("/")
+    entries: list[DirectoryEntry] = []
+    for resource in resources:
+        url = urljoin(base_url, resource["href"])
+        path = urlsplit(url).path
+        if path.rstrip("/") == collection:
+            continue
+        properties = resource["properties"]
+        resource_type = properties.get("resourcetype")
+        length = properties.get("getcontentlength")
+        modified = properties.get("getlastmodified")
+        try:
+            mtime = parsedate_to_datetime(modified) if modified else None
+        except (TypeError, ValueError):
+            mtime = None
+        entries.append(
+            DirectoryEntry(
+                name=unquote(path.rstrip("/").rsplit("/", 1)[-1]),
+                url=url,
+                size=int(length) if isinstance(length, str) and length.isdigit() else None,
+                mtime=mtime,
+                is_dir=isinstance(resource_type, list) and "collection" in resource_type,
+            )
+        )
+    return entries
+
+
+__all__ = ["DirectoryEntry", "from_multistatus", "parse_index"]
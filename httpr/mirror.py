@@ -0,0 +1,99 @@
+"""
+Validator state and atomic writes for mirroring URLs to local files.
+
+Used by `Client.sync()`. The state file is JSON mapping each URL to the `ETag` and
+`Last-Modified` validators of the copy on disk, which are sent back as
+`If-None-Match` / `If-Modified-Since` so that unchanged resources answer `304`.
+"""
+
+from __future__ import annotations
+
+import json
+import os
+import tempfile
+from collections.abc import Iterable
+from pathlib import Path
+from typing import Literal, TypedDict
+
+SyncStatus = Literal["created", "updated", "unchanged", "failed"]
+
+
+class SyncResult(TypedDict):
+    """What `Client.sync()` did for one URL."""
+
+    status: SyncStatus
+    path: str
+    status_code: int | None
+    bytes: int
+    error: str | None
+
+
+class Validators(TypedDict, total=False):
+    etag: str
+    last_modified: str
+
+
+def load_state(state_file: str | os.PathLike[str] | None) -> dict[str, Validators]:
+    """The saved validators by URL; empty when there is no state file yet."""
+    if state_file is None:
+        return {}
+    try:
+        with open(state_file, encoding="utf-8") as f:
+            state = json.load(f)
+    except FileNotFoundError:
+        return {}
+    if not isinstance(state, dict):
+        raise ValueError(f"Invalid sync state file: {state_file}")
+    return state
+
+
+def save_state(state_file: str | os.PathLike[str], state: dict[str, Validators]) -> None:
+    """Replace the state file atomically."""
+    data = json.dumps(state, indent=2, sort_keys=True).encode()
+    write_atomic(state_file, [data])
+
+
+def conditional_headers(validators: Validators | None, path: str | os.PathLike[str]) -> dict[str, str]:
+    """Validator headers for a copy at `path`; none when the file is missing."""
+    if not validators or not os.path.exists(path):
+        return {}
+    headers = {}
+    if "etag" in validators:
+        headers["If-None-Match"] = validators["etag"]
+    if "last_modified" in validators:
+        headers["If-Modified-Since"] = validators["last_modified"]
+    return headers
+
+
+def write_atomic(path: str | os.PathLike[str], chunks: Iterable[bytes]) -> int:
+    """
+    Write `chunks` to a temporary file next to `path`, then rename it over `path`.
+
+    Readers see either the old or the new file, never a partial one. Missing parent
+    directories are created.
+
+    Returns:
+        The number of bytes written.
+    """
+    target = Path(path)
+    target.parent.mkdir(parents=True, exist_ok=True)
+    fd, tmp = tempfile.mkstemp(dir=target.parent, prefix=f".{target.name}.", suffix=".tmp")
+    written = 0
+    try:
+        with os.fdopen(fd, "wb") as f:
+            for chunk in chunks:
+                f.write(chunk)
+                written += len(chunk)
+            f.flush()
+            os.fsync(f.fileno())
+        os.replace(tmp, target)
+    except BaseException:
+        try:
+            os.unlink(tmp)
+        except FileNotFoundError:
+            pass
+        raise
+    return written
+
+
+__all__ = ["SyncResult", "SyncStatus", "conditional_headers", "load_state", "save_state", "write_atomic"]
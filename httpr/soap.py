@@ -0,0 +1,241 @@
+"""
+SOAP 1.1 / 1.2 envelopes and fault parsing.
+
+Used by `Client.soap()`. Envelopes are given either as XML or as a dict of body
+elements, whose names are bare or in Clark notation (`"{urn:example:stock}GetPrice"`).
+Values may be dicts (child elements), lists (repeated elements), `None` (`xsi:nil`) or
+anything else, which is sent as its string form.
+"""
+
+from __future__ import annotations
+
+import re
+import xml.etree.ElementTree as ET
+from collections.abc import Mapping
+from typing import Any, Literal, TypedDict
+
+from .httpr import HTTPError
+
+SOAP11_NS = "http://schemas.xmlsoap.org/soap/envelope/"
+SOAP12_NS = "http://www.w3.org/2003/05/soap-envelope"
+XSI_NS = "http://www.w3.org/2001/XMLSchema-instance"
+
+SoapVersion = Literal["1.1", "1.2"]
+
+_ENVELOPE_NS: dict[str, str] = {"1.1": SOAP11_NS, "1.2": SOAP12_NS}
+_CLARK_RE = re.compile(r"^\{(?P<ns>[^}]*)\}(?P<local>.+)$")
+
+
+class SoapResponse(TypedDict):
+    """The parsed `Header` and `Body` of a response envelope."""
+
+    header: dict[str, Any]
+    body: dict[str, Any]
+
+
+class SoapFault(HTTPError):
+    """The server answered with a SOAP `Fault`.
+
+    Attributes:
+        code: `faultcode` (1.1) or `Code/Value` (1.2), e.g. `"soap:Server"`.
+        reason: `faultstring` (1.1) or the first `Reason/Text` (1.2).
+        actor: `faultactor` (1.1) or `Role` (1.2), if given.
+        detail: The fault `detail` element as a dict, if given.
+        response: The response carrying the fault.
+    """
+
+    def __init__(
+        self,
+        code: str,
+        reason: str,
+        actor: str | None = None,
+        detail: dict[str, Any] | None = None,
+        response: Any = None,
+    ) -> None:
+        super().__init__(f"SOAP fault {code}: {reason}")
+        self.code = code
+        self.reason = reason
+        self.actor = actor
+        self.detail = detail
+        self.response = response
+
+
+def _local(tag: str) -> str:
+    match = _CLARK_RE.match(tag)
+    return match.group("local") if match else tag
+
+
+def _value(element: ET.Element) -> Any:
+    """Child elements as a dict by local name (repeated names as lists), else the text."""
+    children = list(element)
+    if not children:
+        if element.get(f"{{{XSI_NS}}}nil") in ("true", "1"):
+            return None
+        return element.text or ""
+    result: dict[str, Any] = {}
+    repeated: set[str] = set()
+    for child in children:
+        name = _local(child.tag)
+        value = _value(child)
+        if name not in result:
+            result[name] = value
+        elif name in repeated:
+            result[name].append(value)
+        else:
+            result[name] = [result[name], value]
+            repeated.add(name)
+    return result
+
+
+class _Builder:
+    """Assigns a prefix per namespace while building, declared on the envelope."""
+
+    def __init__(self, envelope_ns: str, namespace: str | None) -> None:
+        self.namespaces: dict[str, str] = {envelope_ns: "soap"}
+        self.namespace = namespace
+
+    def tag(self, name: str) -> str:
+        match = _CLARK_RE.match(name)
+        ns, local = (match.group("ns"), match.group("local")) if match else (self.namespace, name)
+        if not ns:
+            return local
+        prefix = self.namespaces.setdefault(ns, f"ns{len(self.namespaces)}")
+        return f"{prefix}:{local}"
+
+    def add(self, parent: ET.Element, elements: Mapping[str, Any]) -> None:
+        for name, value in elements.items():
+            for item in value if isinstance(value, (list, tuple)) else [value]:
+                child = ET.SubElement(parent, self.tag(name))
+                if isinstance(item, Mapping):
+                    self.add(child, item)
+                elif item is None:
+                    self.namespaces.setdefault(XSI_NS, "xsi")
+                    child.set(f"{self.namespaces[XSI_NS]}:nil", "true")
+                elif isinstance(item, bool):
+                    child.text = "true" if item else "false"
+                else:
+                    child.text = str(item)
+
+
+def build_envelope(
+    body: Mapping[str, Any],
+    version: SoapVersion = "1.1",
+    header: Mapping[str, Any] | None = None,
+    namespace: str | None = None,
+) -> bytes:
+    """
+    Build a SOAP envelope.
+
+    Args:
+        body: Elements of the `Body`, usually a single operation element.
+        version: SOAP version, `"1.1"` or `"1.2"`.
+        header: Elements of the `Header`, which is left out when `None`.
+        namespace: Namespace of element names given without one.
+
+    Returns:
+        The XML envelope.
+    """
+    if version not in _ENVELOPE_NS:
+        raise ValueError(f"Unsupported SOAP version: {version}")
+    builder = _Builder(_ENVELOPE_NS[version], namespace)
+    envelope = ET.Element("soap:Envelope")
+    if header is not None:
+        builder.add(ET.SubElement(envelope, "soap:Header"), header)
+    builder.add(ET.SubElement(envelope, "soap:Body"), body)
+    for ns, prefix in builder.namespaces.items():
+        envelope.set(f"xmlns:{prefix}", ns)
+    return ET.tostring(envelope, encoding="utf-8", xml_declaration=True)
+
+
+def _parse(content: bytes) -> tuple[ET.Element, SoapVersion]:
+    try:
+        root = ET.fromstring(content)
+    except ET.ParseError as e:
+        raise ValueError(f"Invalid SOAP XML: {e}") from None
+    for version, ns in _ENVELOPE_NS.items():
+        if root.tag == f"{{{ns}}}Envelope":
+            return root, version  # type: ignore[return-value]
+    raise ValueError(f"Expected a SOAP Envelope, got {root.tag}")
+
+
+def envelope_version(content: bytes) -> SoapVersion:
+    """
+    The SOAP version of an envelope, from its namespace.
+
+    Raises:
+        ValueError: If `content` is not a SOAP 1.1 or 1.2 envelope.
+    """
+    return _parse(content)[1]
+
+
+def _fault(fault: ET.Element, ns: str, response: Any) -> SoapFault:
+    detail: ET.Element | None
+    if ns == SOAP11_NS:
+        code = fault.findtext("faultcode", default="")
+        reason = fault.findtext("faultstring", default="")
+        actor = fault.findtext("faultactor")
+        detail = fault.find("detail")
+    else:
+        code = fault.findtext(f"{{{ns}}}Code/{{{ns}}}Value", default="")
+        reason = fault.findtext(f"{{{ns}}}Reason/{{{ns}}}Text", default="")
+        actor = fault.findtext(f"{{{ns}}}Role")
+        detail = fault.find(f"{{{ns}}}Detail")
+    parsed = _value(detail) if detail is not None else None
+    return SoapFault(
+        code.strip(),
+        reason.strip(),
+        actor.strip() if actor is not None else None,
+        parsed if isinstance(parsed, dict) else None,
+        response,
+    )
+
+
+def parse_envelope(content: bytes, response: Any = None) -> SoapResponse:
+    """
+    Parse a SOAP response envelope.
+
+    Element names are reduced to their local names.
+
+    Args:
+        content: The response body.
+        response: Attached to a raised `SoapFault`.
+
+    Returns:
+        The `Header` and `Body` elements as dicts.
+
+    Raises:
+        SoapFault: If the body holds a `Fault`.
+        ValueError: If `content` is not a SOAP envelope.
+    """
+    root, version = _parse(content)
+    ns = _ENVELOPE_NS[version]
+    body = root.find(f"{{{ns}}}Body")
+    if body is None:
+        raise ValueError("SOAP Envelope has no Body")
+    fault = body.find(f"{{{ns}}}Fault")
+    if fault is not None:
+        raise _fault(fault, ns, response)
+    header = root.find(f"{{{ns}}}Header")
+    parsed_header = _value(header) if header is not None else {}
+    parsed_body = _value(body)
+    return SoapResponse(
+        header=parsed_header if isinstance(parsed_header, dict) else {},
+        body=parsed_body if isinstance(parsed_body, dict) else {},
+    )
+
+
+def request_headers(action: str, version: SoapVersion) -> dict[str, str]:
+    """The `Content-Type` (and for SOAP 1.1, `SOAPAction`) headers for `action`."""
+    if version == "1.1":
+        return {"Content-Type": "text/xml; charset=utf-8", "SOAPAction": f'"{action}"'}
+    return {"Content-Type": f'application/soap+xml; charset=utf-8; action="{action}"'}
+
+
+__all__ = [
+    "SoapFault",
+    "SoapResponse",
+    "build_envelope",
+    "envelope_version",
+    "parse_envelope",
+    "request_headers",
+]
@@ -0,0 +1,27 @@
+"""
+Hermetic test helpers.
+
+`LocalServer` is a small in-process HTTP/1.1 and HTTP/2 server (optionally over TLS)
+with canned routes, delays and redirect chains, so code using httpr can be tested
+without network access or an external service such as httpbin.
+
+Example:
+    ```python
+    import httpr
+    from httpr.testing import LocalServer
+
+    with LocalServer() as server:
+        server.route("/users", json=[{"id": 1}])
+        server.redirect_chain("/old", "/users", hops=3)
+
+        response = httpr.get(f"{server.url}/old")
+        assert response.json() == [{"id": 1}]
+        assert len(server.requests) == 4
+    ```
+"""
+
+from __future__ import annotations
+
+from .httpr import LocalServer
+
+__all__ = ["LocalServer"]
@@ -0,0 +1,112 @@
+"""
+W3C Trace Context propagation.
+
+Requests sent inside a `trace_context()` block carry its `traceparent` (and
+`tracestate` / `baggage`) headers, so a trace started by Python middleware continues
+through httpr's outbound calls. A single request can also be given its context with
+the `trace_context=` request option, which takes precedence.
+
+Example:
+    ```python
+    import httpr
+    from httpr.tracing import trace_context
+
+    with trace_context("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"):
+        httpr.get("https://example.com")  # sends the traceparent header
+
+    # With OpenTelemetry, inject the current span into a carrier dict
+    from opentelemetry.propagate import inject
+
+    carrier = {}
+    inject(carrier)
+    client.get("https://example.com", trace_context=carrier)
+    ```
+"""
+
+from __future__ import annotations
+
+import re
+from collections.abc import Iterator, Mapping
+from contextlib import contextmanager
+from contextvars import ContextVar
+from typing import Any
+
+__all__ = ["trace_context", "current_trace_context"]
+
+_HEADERS = ("traceparent", "tracestate", "baggage")
+_TRACEPARENT = re.compile(r"^([0-9a-f]{2})-([0-9a-f]{32})-([0-9a-f]{16})-([0-9a-f]{2})$")
+
+_current: ContextVar[dict[str, str] | None] = ContextVar("httpr_trace_context", default=None)
+
+
+def _validated(context: Mapping[str, str]) -> dict[str, str]:
+    """Lowercase the keys of `context` and check its `traceparent`."""
+    headers = {name.lower(): value for name, value in context.items() if value is not None}
+    unknown = set(headers) - set(_HEADERS)
+    if unknown:
+        raise ValueError(f"trace_context only takes {', '.join(_HEADERS)}, got {sorted(unknown)}")
+    traceparent = headers.get("traceparent")
+    if traceparent is None:
+        if headers:
+            raise ValueError("trace_context requires a traceparent")
+        return headers
+    match = _TRACEPARENT.match(traceparent)
+    if (
+        match is None
+        or match.group(1) == "ff"
+        or set(match.group(2)) == {"0"}
+        or set(match.group(3)) == {"0"}
+    ):
+        raise ValueError(f"Invalid traceparent: {traceparent!r}")
+    return headers
+
+
+@contextmanager
+def trace_context(
+    traceparent: str, tracestate: str | None = None, baggage: str | None = None
+) -> Iterator[None]:
+    """
+    Send the given trace context with every request made inside the block.
+
+    The context is held in a `contextvars.ContextVar`, so it follows threads and asyncio
+    tasks the way other context variables do. Nested blocks replace the outer context.
+
+    Args:
+        traceparent: The W3C `traceparent` header, e.g. `00-<trace-id>-<parent-id>-01`.
+        tracestate: The W3C `tracestate` header.
+        baggage: The W3C `baggage` header.
+
+    Raises:
+        ValueError: If `traceparent` is malformed.
+    """
+    context = _validated({"traceparent": traceparent, "tracestate": tracestate, "baggage": baggage})
+    token = _current.set(context)
+    try:
+        yield
+    finally:
+        _current.reset(token)
+
+
+def current_trace_context() -> dict[str, str] | None:
+    """The trace context headers set by the innermost `trace_context()` block, if any."""
+    context = _current.get()
+    return dict(context) if context is not None else None
+
+
+def _apply_trace_context(kwargs: dict[str, Any]) -> None:
+    """Replace the `trace_context` request option, or the current context, with headers.
+
+    A `traceparent` already given in `headers` wins, together with the rest of its context.
+    """
+    context = kwargs.pop("trace_context", None)
+    context = _validated(context) if context is not None else _current.get()
+    if not context:
+        return
+    headers = dict(kwargs.get("headers") or {})
+    present = {name.lower() for name in headers}
+    if "traceparent" in present:
+        return
+    for name, value in context.items():
+        if name not in present:
+            headers[name] = value
+    kwargs["headers"] = headers
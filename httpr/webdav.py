@@ -0,0 +1,126 @@
+"""
+WebDAV (RFC 4918) request bodies and multistatus parsing.
+
+Used by `Client.propfind()`, `Client.mkcol()`, `Client.copy()` and `Client.move()`.
+Property names are given either as bare names in the `DAV:` namespace
+(`"getcontentlength"`) or in Clark notation for other namespaces
+(`"{http://owncloud.org/ns}fileid"`).
+"""
+
+from __future__ import annotations
+
+import re
+import xml.etree.ElementTree as ET
+from typing import Any, TypedDict
+
+DAV_NS = "DAV:"
+
+_CLARK_RE = re.compile(r"^\{(?P<ns>[^}]*)\}(?P<local>.+)$")
+_STATUS_RE = re.compile(r"^HTTP/\d(?:\.\d)?\s+(\d{3})")
+
+
+class DavResource(TypedDict):
+    """One `<response>` element of a multistatus body."""
+
+    href: str
+    status: int | None
+    properties: dict[str, Any]
+
+
+def _split(name: str) -> tuple[str, str]:
+    match = _CLARK_RE.match(name)
+    if match:
+        return match.group("ns"), match.group("local")
+    return DAV_NS, name
+
+
+def _name(tag: str) -> str:
+    """Element tag as a property name: bare for `DAV:`, Clark notation otherwise."""
+    ns, local = _split(tag)
+    return local if ns == DAV_NS else f"{{{ns}}}{local}"
+
+
+def _status(element: ET.Element | None) -> int | None:
+    if element is None or element.text is None:
+        return None
+    match = _STATUS_RE.match(element.text.strip())
+    return int(match.group(1)) if match else None
+
+
+def _value(element: ET.Element) -> Any:
+    """Text for simple properties, list of child names for e.g. `resourcetype`."""
+    children = list(element)
+    if children:
+        return [_name(child.tag) for child in children]
+    return element.text or ""
+
+
+def build_propfind(properties: list[str] | None = None) -> bytes:
+    """
+    Build a PROPFIND request body.
+
+    Args:
+        properties: Property names to request. `None` requests all properties (`<allprop/>`).
+
+    Returns:
+        The XML body.
+    """
+    namespaces: dict[str, str] = {DAV_NS: "d"}
+    propfind = ET.Element("d:propfind")
+    if properties is None:
+        ET.SubElement(propfind, "d:allprop")
+    else:
+        prop = ET.SubElement(propfind, "d:prop")
+        for name in properties:
+            ns, local = _split(name)
+            prefix = namespaces.setdefault(ns, f"ns{len(namespaces)}")
+            ET.SubElement(prop, f"{prefix}:{local}")
+    for ns, prefix in namespaces.items():
+        propfind.set(f"xmlns:{prefix}", ns)
+    return ET.tostring(propfind, encoding="utf-8", xml_declaration=True)
+
+
+def parse_multistatus(content: bytes) -> list[DavResource]:
+    """
+    Parse a `207 Multi-Status` response body.
+
+    Only properties from successful (2xx) `<propstat>` blocks are returned.
+
+    Args:
+        content: The response body.
+
+    Returns:
+        One entry per `<response>` element, in document order.
+
+    Raises:
+        ValueError: If the body is not a `DAV:multistatus` document.
+    """
+    try:
+        root = ET.fromstring(content)
+    except ET.ParseError as e:
+        raise ValueError(f"Invalid multistatus XML: {e}") from None
+    if root.tag != f"{{{DAV_NS}}}multistatus":
+        raise ValueError(f"Expected DAV:multistatus, got {root.tag}")
+
+    resources: list[DavResource] = []
+    for response in root.findall(f"{{{DAV_NS}}}response"):
+        href = response.findtext(f"{{{DAV_NS}}}href", default="").strip()
+        properties: dict[str, Any] = {}
+        for propstat in response.findall(f"{{{DAV_NS}}}propstat"):
+            status = _status(propstat.find(f"{{{DAV_NS}}}status"))
+            if status is not None and not 200 <= status < 300:
+                continue
+            for prop in propstat.findall(f"{{{DAV_NS}}}prop"):
+                for element in prop:
+                    properties[_name(element.tag)] = _value(element)
+        resources.append(
+            DavResource(
+                href=href,
+                status=_status(response.find(f"{{{DAV_NS}}}status")),
+                properties=properties,
+            )
+        )
+    return resources
+
+
+__all__ = ["DavResource", "build_propfind", "parse_multistatus"]
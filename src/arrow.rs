@@ -0,0 +1,89 @@
+use pyo3::exceptions::PyImportError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+/// Columnar body formats understood by `Response.arrow()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArrowFormat {
+    /// Arrow IPC streaming format (`application/vnd.apache.arrow.stream`).
+    Stream,
+    /// Arrow IPC file format (`application/vnd.apache.arrow.file`), starting with `ARROW1`.
+    File,
+    /// Apache Parquet (`application/vnd.apache.parquet`), starting with `PAR1`.
+    Parquet,
+}
+
+impl ArrowFormat {
+    /// The format of `body` from its `Content-Type`, or from its magic bytes when the
+    /// media type is missing or generic (e.g. `application/octet-stream`).
+    pub fn detect(content_type: &str, body: &[u8]) -> Self {
+        let media_type = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match media_type.as_str() {
+            "application/vnd.apache.arrow.stream" => ArrowFormat::Stream,
+            "application/vnd.apache.arrow.file" => ArrowFormat::File,
+            "application/vnd.apache.parquet" | "application/x-parquet" => ArrowFormat::Parquet,
+            _ if body.starts_with(b"ARROW1") => ArrowFormat::File,
+            _ if body.starts_with(b"PAR1") => ArrowFormat::Parquet,
+            _ => ArrowFormat::Stream,
+        }
+    }
+
+    /// Read `body` into a `pyarrow.Table`, wrapping the bytes without copying them.
+    pub fn read_table<'py>(
+        self,
+        py: Python<'py>,
+        body: &Bound<'py, PyBytes>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let pyarrow = py.import("pyarrow").map_err(|_| {
+            PyImportError::new_err("Response.arrow() requires pyarrow (pip install pyarrow)")
+        })?;
+        let buffer = pyarrow.call_method1("py_buffer", (body,))?;
+        match self {
+            ArrowFormat::Stream => py
+                .import("pyarrow.ipc")?
+                .call_method1("open_stream", (buffer,))?
+                .call_method0("read_all"),
+            ArrowFormat::File => py
+                .import("pyarrow.ipc")?
+                .call_method1("open_file", (buffer,))?
+                .call_method0("read_all"),
+            ArrowFormat::Parquet => {
+                let reader = pyarrow.call_method1("BufferReader", (buffer,))?;
+                py.import("pyarrow.parquet")?
+                    .call_method1("read_table", (reader,))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let stream = b"\xff\xff\xff\xff\x10\x00\x00\x00";
+        assert_eq!(
+            ArrowFormat::detect("application/vnd.apache.arrow.stream", b"ARROW1"),
+            ArrowFormat::Stream
+        );
+        assert_eq!(
+            ArrowFormat::detect("Application/Vnd.Apache.Parquet; charset=binary", stream),
+            ArrowFormat::Parquet
+        );
+        assert_eq!(
+            ArrowFormat::detect("application/octet-stream", b"ARROW1\0\0"),
+            ArrowFormat::File
+        );
+        assert_eq!(
+            ArrowFormat::detect("", b"PAR1...PAR1"),
+            ArrowFormat::Parquet
+        );
+        assert_eq!(ArrowFormat::detect("", stream), ArrowFormat::Stream);
+    }
+}
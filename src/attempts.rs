@@ -0,0 +1,89 @@
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use url::Url;
+
+/// One time a request went out on the wire, for `response.attempts`.
+#[derive(Clone, Debug)]
+pub struct Attempt {
+    pub url: String,
+    /// Seconds since the Unix epoch when the attempt was started.
+    pub started: f64,
+    /// Time until the response headers arrived or the attempt failed.
+    pub duration: Duration,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+    /// Sent again because the previous attempt failed on a stale connection.
+    pub retry: bool,
+}
+
+impl Attempt {
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("url", &self.url)?;
+        dict.set_item("started", self.started)?;
+        dict.set_item("duration", self.duration.as_secs_f64())?;
+        let outcome = if self.error.is_some() {
+            "error"
+        } else {
+            "response"
+        };
+        dict.set_item("outcome", outcome)?;
+        dict.set_item("status", self.status)?;
+        dict.set_item("error", &self.error)?;
+        dict.set_item("retry", self.retry)?;
+        Ok(dict)
+    }
+}
+
+/// The attempts of one request: its first send, a replay after a connection error, the
+/// credentialed resend after an auth challenge and every redirect hop. Clones share the
+/// records; `RequestSpec::send` starts each request with a fresh log and attaches it to
+/// the response.
+#[derive(Clone, Default)]
+pub struct AttemptLog(Arc<Mutex<Vec<Attempt>>>);
+
+impl AttemptLog {
+    /// Send an attempt to `url` via `send` and record its outcome.
+    pub async fn record<F>(&self, url: &Url, retry: bool, send: F) -> Result<reqwest::Response>
+    where
+        F: Future<Output = Result<reqwest::Response>>,
+    {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let start = Instant::now();
+        let result = send.await;
+        let (status, error) = match &result {
+            Ok(resp) => (Some(resp.status().as_u16()), None),
+            Err(e) => (None, Some(format!("{:#}", e))),
+        };
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Attempt {
+                url: url.to_string(),
+                started,
+                duration: start.elapsed(),
+                status,
+                error,
+                retry,
+            });
+        result
+    }
+
+    /// Recorded attempts, oldest first.
+    pub fn entries(&self) -> Vec<Attempt> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// How many of `attempts` were retries, for `response.retries`.
+pub fn count_retries(attempts: &[Attempt]) -> usize {
+    attempts.iter().filter(|attempt| attempt.retry).count()
+}
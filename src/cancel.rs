@@ -0,0 +1,114 @@
+use std::fmt;
+use std::future::Future;
+
+use anyhow::Result;
+use futures_util::{stream, StreamExt};
+use http_body_util::BodyExt;
+use pyo3::prelude::*;
+use reqwest::Body;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A request aborted through its `CancellationToken`. Raised as `RequestCancelled`.
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Request cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Aborts the requests it is passed to (`cancel_token=`), from any thread.
+///
+/// Cancelling drops the in-flight request, which closes its connection instead of
+/// returning it to the pool, and fails the request with `RequestCancelled`. Responses
+/// already returned stop with the same error on the next read of their body. A token
+/// can be shared by any number of requests and cannot be reset.
+///
+/// # Example
+///
+/// ```python
+/// token = httpr.CancellationToken()
+/// threading.Timer(5, token.cancel).start()
+/// response = client.get("https://example.com/slow", cancel_token=token)
+/// ```
+#[pyclass(frozen)]
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: tokio_util::sync::CancellationToken,
+}
+
+#[pymethods]
+impl CancellationToken {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel every request using this token, including ones sent later.
+    fn cancel(&self) {
+        self.inner.cancel();
+    }
+
+    /// Whether `cancel()` has been called.
+    #[getter]
+    fn cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<CancellationToken cancelled={}>", self.cancelled())
+    }
+}
+
+impl CancellationToken {
+    /// Run `fut` unless or until the token is cancelled, dropping it in that case.
+    pub async fn guard<T>(&self, fut: impl Future<Output = Result<T>>) -> Result<T> {
+        tokio::select! {
+            biased;
+            _ = self.inner.cancelled() => Err(Cancelled.into()),
+            result = fut => result,
+        }
+    }
+
+    /// Wrap `body` so that reading it fails with `Cancelled` once the token is cancelled.
+    /// The inner body is dropped right away, so its connection is not kept alive.
+    pub fn watch_body(&self, body: Body) -> Body {
+        let state = (Some(body.into_data_stream()), self.inner.clone());
+        Body::wrap_stream(stream::unfold(state, |(inner, token)| async move {
+            let mut inner = inner?;
+            tokio::select! {
+                biased;
+                _ = token.cancelled() => Some((Err(Box::new(Cancelled) as BoxError), (None, token))),
+                next = inner.next() => {
+                    let next = next?.map_err(|e| Box::new(e) as BoxError);
+                    Some((next, (Some(inner), token)))
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guard() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let token = CancellationToken::default();
+        let ok = runtime.block_on(token.guard(async { Ok(1) }));
+        assert_eq!(ok.unwrap(), 1);
+
+        token.cancel();
+        assert!(token.cancelled());
+        let pending = runtime.block_on(token.guard(std::future::pending::<Result<()>>()));
+        assert!(pending.unwrap_err().downcast_ref::<Cancelled>().is_some());
+    }
+}
@@ -0,0 +1,204 @@
+use std::io;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use async_compression::tokio::bufread::{BrotliEncoder, GzipEncoder, ZlibEncoder, ZstdEncoder};
+use bytes::Bytes;
+use foldhash::fast::RandomState;
+use futures_util::StreamExt;
+use http_body_util::BodyExt;
+use indexmap::IndexMap;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedBytes;
+use pyo3::types::PyBytes;
+use reqwest::header::{HeaderValue, CONTENT_ENCODING};
+use reqwest::Body;
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncReadExt};
+use tokio_util::io::{ReaderStream, StreamReader};
+
+/// Encodings httpr compresses and decompresses itself.
+pub const BUILTIN_ENCODINGS: [&str; 4] = ["gzip", "deflate", "br", "zstd"];
+
+/// Value of `codecs=`: names to `(compress, decompress)` callables.
+pub type CodecArgs = IndexMap<String, (Option<Py<PyAny>>, Option<Py<PyAny>>), RandomState>;
+
+/// A `Content-Encoding` registered with `codecs=`: Python callables taking and
+/// returning `bytes`. Either may be missing.
+struct Codec {
+    compress: Option<Py<PyAny>>,
+    decompress: Option<Py<PyAny>>,
+}
+
+/// Custom content codecs (`codecs=`) and the encoding of request bodies
+/// (`request_encoding=`).
+///
+/// Custom codecs work on whole bodies: responses in such an encoding are read before
+/// `decompress` is called, and `compress` needs a buffered request body.
+#[derive(Clone, Default)]
+pub struct Codecs {
+    codecs: Arc<IndexMap<String, Codec, RandomState>>,
+    request_encoding: Option<String>,
+}
+
+impl Codecs {
+    pub fn from_args(
+        codecs: Option<CodecArgs>,
+        request_encoding: Option<String>,
+    ) -> PyResult<Self> {
+        let mut registered = IndexMap::with_hasher(RandomState::default());
+        Python::attach(|py| {
+            for (name, (compress, decompress)) in codecs.into_iter().flatten() {
+                let name = name.trim().to_ascii_lowercase();
+                if HeaderValue::from_str(&name).is_err() || name.is_empty() || name.contains(',') {
+                    return Err(PyValueError::new_err(format!(
+                        "Invalid codec name {:?}",
+                        name
+                    )));
+                }
+                if BUILTIN_ENCODINGS.contains(&name.as_str()) || name == "x-gzip" {
+                    return Err(PyValueError::new_err(format!(
+                        "codecs cannot replace the built-in {:?} encoding",
+                        name
+                    )));
+                }
+                let callables = [&compress, &decompress];
+                if callables.iter().all(|callable| callable.is_none())
+                    || callables
+                        .iter()
+                        .any(|callable| matches!(callable, Some(c) if !c.bind(py).is_callable()))
+                {
+                    return Err(PyValueError::new_err(format!(
+                        "codec {:?} must be a (compress, decompress) pair of callables or None",
+                        name
+                    )));
+                }
+                registered.insert(
+                    name,
+                    Codec {
+                        compress,
+                        decompress,
+                    },
+                );
+            }
+            Ok(())
+        })?;
+
+        let request_encoding = request_encoding.map(|name| name.trim().to_ascii_lowercase());
+        if let Some(name) = &request_encoding {
+            let known = BUILTIN_ENCODINGS.contains(&name.as_str())
+                || registered
+                    .get(name)
+                    .is_some_and(|codec| codec.compress.is_some());
+            if !known {
+                return Err(PyValueError::new_err(format!(
+                    "request_encoding must be gzip, deflate, br, zstd or a codec with a compress callable, got {:?}",
+                    name
+                )));
+            }
+        }
+        Ok(Codecs {
+            codecs: Arc::new(registered),
+            request_encoding,
+        })
+    }
+
+    /// Names of the custom codecs that can decode responses, for `Accept-Encoding`.
+    pub fn accepted(&self) -> impl Iterator<Item = &str> {
+        self.codecs
+            .iter()
+            .filter(|(_, codec)| codec.decompress.is_some())
+            .map(|(name, _)| name.as_str())
+    }
+
+    pub fn can_decode(&self, encoding: &str) -> bool {
+        self.codecs
+            .get(encoding)
+            .is_some_and(|codec| codec.decompress.is_some())
+    }
+
+    /// Read `reader` to the end and decode it with the `decompress` callable of `encoding`.
+    pub async fn decode(
+        self,
+        encoding: &str,
+        mut reader: impl AsyncRead + Unpin,
+    ) -> io::Result<Bytes> {
+        let mut compressed = Vec::new();
+        reader.read_to_end(&mut compressed).await?;
+        let codec = self
+            .codecs
+            .get(encoding)
+            .and_then(|codec| codec.decompress.as_ref());
+        let decompress = codec.ok_or_else(|| io::Error::other("no decompress callable"))?;
+        call(decompress, &compressed).map_err(io::Error::other)
+    }
+
+    /// Compress the body of `request` with `request_encoding`, setting `Content-Encoding`.
+    /// Requests without a body, or that set `Content-Encoding` themselves, are left as is.
+    pub async fn encode_request(&self, request: &mut reqwest::Request) -> Result<()> {
+        let Some(encoding) = &self.request_encoding else {
+            return Ok(());
+        };
+        if request.headers().contains_key(CONTENT_ENCODING) {
+            return Ok(());
+        }
+        let Some(body) = request.body_mut().take() else {
+            return Ok(());
+        };
+        let body = match (body.as_bytes(), self.codecs.get(encoding)) {
+            (Some([]), _) => {
+                *request.body_mut() = Some(body);
+                return Ok(());
+            }
+            (Some(bytes), Some(codec)) => {
+                let compress = codec.compress.as_ref().expect("checked by from_args");
+                Body::from(call(compress, bytes)?)
+            }
+            (Some(bytes), None) => {
+                let mut compressed = Vec::new();
+                encoder(encoding, bytes).read_to_end(&mut compressed).await?;
+                Body::from(compressed)
+            }
+            (None, Some(_)) => bail!(
+                "request_encoding {:?} needs a buffered request body; streamed bodies such as files cannot be compressed by a codec",
+                encoding
+            ),
+            (None, None) => {
+                let reader = StreamReader::new(
+                    body.into_data_stream()
+                        .map(|chunk| chunk.map_err(io::Error::other)),
+                );
+                Body::wrap_stream(ReaderStream::new(encoder(encoding, reader)))
+            }
+        };
+        *request.body_mut() = Some(body);
+        request
+            .headers_mut()
+            .insert(CONTENT_ENCODING, HeaderValue::from_str(encoding)?);
+        Ok(())
+    }
+}
+
+/// A built-in encoder compressing `reader` with `encoding`.
+fn encoder<'a>(
+    encoding: &str,
+    reader: impl AsyncBufRead + Send + Unpin + 'a,
+) -> Box<dyn AsyncRead + Send + Unpin + 'a> {
+    match encoding {
+        "gzip" => Box::new(GzipEncoder::new(reader)),
+        "deflate" => Box::new(ZlibEncoder::new(reader)),
+        "br" => Box::new(BrotliEncoder::new(reader)),
+        _ => Box::new(ZstdEncoder::new(reader)),
+    }
+}
+
+/// Call a codec callable with `data`, expecting `bytes` or `bytearray` back.
+fn call(callable: &Py<PyAny>, data: &[u8]) -> Result<Bytes> {
+    Python::attach(|py| {
+        let output = callable.bind(py).call1((PyBytes::new(py, data),))?;
+        let output: PyBackedBytes = output
+            .extract()
+            .map_err(|_| anyhow!("codec returned {}, expected bytes", output.get_type()))?;
+        Ok(Bytes::copy_from_slice(&output))
+    })
+}
@@ -0,0 +1,213 @@
+use std::io;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::dns::DnsFailure;
+use crate::hosts::Refused;
+use crate::tls::TlsFailure;
+
+/// Default `connect_timeout` and `read_timeout` of a client, in seconds.
+pub const DEFAULT_TIMEOUT: f64 = 30.0;
+
+/// Client timeouts in seconds (`timeout`, `connect_timeout`, `read_timeout`), each
+/// turned off by `None`.
+///
+/// `timeout` caps a whole request including its response body and is applied per
+/// request, so that it can be changed on the client and overridden by a request. The
+/// connect and read timeouts are set on the connector and only fire when a connection
+/// cannot be set up or goes quiet, so slow but steady downloads are not cut off.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Timeouts {
+    pub total: Option<f64>,
+    pub connect: Option<f64>,
+    pub read: Option<f64>,
+}
+
+impl Timeouts {
+    pub fn from_args(total: Option<f64>, connect: Option<f64>, read: Option<f64>) -> Result<Self> {
+        for (value, name) in [
+            (total, "timeout"),
+            (connect, "connect_timeout"),
+            (read, "read_timeout"),
+        ] {
+            if value.is_some_and(|value| !value.is_finite() || value <= 0.0) {
+                bail!("{} must be a positive number of seconds or None", name);
+            }
+        }
+        Ok(Timeouts {
+            total,
+            connect,
+            read,
+        })
+    }
+
+    pub fn apply(&self, mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        if let Some(seconds) = self.connect {
+            builder = builder.connect_timeout(Duration::from_secs_f64(seconds));
+        }
+        if let Some(seconds) = self.read {
+            builder = builder.read_timeout(Duration::from_secs_f64(seconds));
+        }
+        builder
+    }
+}
+
+/// The `timeout` of a single request: the client `timeout` if left out, and no total
+/// timeout at all if given as `None`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RequestTimeout {
+    Client,
+    Off,
+    Seconds(f64),
+}
+
+impl RequestTimeout {
+    /// The total timeout of the request, given that of the client.
+    pub fn or_client(self, client: Option<f64>) -> Option<f64> {
+        match self {
+            RequestTimeout::Client => client,
+            RequestTimeout::Off => None,
+            RequestTimeout::Seconds(seconds) => Some(seconds),
+        }
+    }
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for RequestTimeout {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        if obj.is_none() {
+            return Ok(RequestTimeout::Off);
+        }
+        let seconds: f64 = obj.extract()?;
+        if !seconds.is_finite() || seconds < 0.0 {
+            return Err(PyValueError::new_err(
+                "timeout must be a non-negative number of seconds or None",
+            ));
+        }
+        Ok(RequestTimeout::Seconds(seconds))
+    }
+}
+
+/// HTTP/2 PING keep-alive settings (`http2_keep_alive_interval`,
+/// `http2_keep_alive_timeout`, `http2_keep_alive_while_idle`).
+///
+/// Pings let the client notice a dead HTTP/2 connection, e.g. one silently dropped by
+/// a NAT or load balancer, and evict it from the pool before a request is sent on it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Http2KeepAlive {
+    interval: Duration,
+    timeout: Option<Duration>,
+    while_idle: bool,
+}
+
+impl Http2KeepAlive {
+    pub fn from_args(
+        interval: Option<f64>,
+        timeout: Option<f64>,
+        while_idle: Option<bool>,
+    ) -> Result<Option<Self>> {
+        let seconds = |value: f64, name: &str| {
+            if !value.is_finite() || value <= 0.0 {
+                bail!("{} must be a positive number of seconds", name);
+            }
+            Ok(Duration::from_secs_f64(value))
+        };
+        let Some(interval) = interval else {
+            if timeout.is_some() || while_idle.is_some() {
+                bail!("http2_keep_alive_timeout and http2_keep_alive_while_idle require http2_keep_alive_interval");
+            }
+            return Ok(None);
+        };
+        Ok(Some(Http2KeepAlive {
+            interval: seconds(interval, "http2_keep_alive_interval")?,
+            timeout: timeout
+                .map(|value| seconds(value, "http2_keep_alive_timeout"))
+                .transpose()?,
+            while_idle: while_idle.unwrap_or(false),
+        }))
+    }
+
+    pub fn apply(&self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        let builder = builder
+            .http2_keep_alive_interval(self.interval)
+            .http2_keep_alive_while_idle(self.while_idle);
+        match self.timeout {
+            Some(timeout) => builder.http2_keep_alive_timeout(timeout),
+            None => builder,
+        }
+    }
+}
+
+/// Whether a request failed with `err` on the connection level, before any response
+/// arrived, so that an idempotent request may be sent again: connecting failed, or the
+/// peer closed or reset the connection under the request, as happens when a pooled
+/// connection has gone stale.
+///
+/// Timeouts, failed name lookups and TLS handshakes, and requests refused by the client's
+/// own host policy do not count.
+pub fn is_connection_error(err: &reqwest::Error) -> bool {
+    if err.is_timeout() {
+        return false;
+    }
+    let mut stale = false;
+    let mut source = std::error::Error::source(err);
+    while let Some(err) = source {
+        if err.is::<Refused>() || err.is::<DnsFailure>() || TlsFailure::from_error(err).is_some() {
+            return false;
+        }
+        if let Some(err) = err.downcast_ref::<hyper::Error>() {
+            stale |= err.is_incomplete_message();
+        }
+        if let Some(err) = err.downcast_ref::<io::Error>() {
+            stale |= matches!(
+                err.kind(),
+                io::ErrorKind::ConnectionReset
+                    | io::ErrorKind::ConnectionAborted
+                    | io::ErrorKind::BrokenPipe
+                    | io::ErrorKind::UnexpectedEof
+            );
+        }
+        source = err.source();
+    }
+    stale || err.is_connect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeouts_from_args() {
+        assert_eq!(
+            Timeouts::from_args(None, Some(DEFAULT_TIMEOUT), None).unwrap(),
+            Timeouts {
+                total: None,
+                connect: Some(30.0),
+                read: None,
+            }
+        );
+        assert!(Timeouts::from_args(Some(0.0), None, None).is_err());
+        assert!(Timeouts::from_args(None, Some(-1.0), None).is_err());
+        assert!(Timeouts::from_args(None, None, Some(f64::INFINITY)).is_err());
+    }
+
+    #[test]
+    fn test_http2_keep_alive_from_args() {
+        assert_eq!(Http2KeepAlive::from_args(None, None, None).unwrap(), None);
+        assert_eq!(
+            Http2KeepAlive::from_args(Some(30.0), Some(5.0), Some(true)).unwrap(),
+            Some(Http2KeepAlive {
+                interval: Duration::from_secs(30),
+                timeout: Some(Duration::from_secs(5)),
+                while_idle: true,
+            })
+        );
+        assert!(Http2KeepAlive::from_args(Some(0.0), None, None).is_err());
+        assert!(Http2KeepAlive::from_args(Some(10.0), Some(f64::NAN), None).is_err());
+        assert!(Http2KeepAlive::from_args(None, Some(5.0), None).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use anyhow::{bail, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use bytes::Bytes;
+use futures_util::{stream, StreamExt};
+use http_body_util::BodyExt;
+use hyper::body::{Body as HttpBody, Frame, SizeHint};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_LENGTH, TRAILER};
+use reqwest::{Body, Method, StatusCode};
+use ring::digest;
+
+use crate::decompress::DecodeError;
+use crate::request::map_response_body;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+const CONTENT_DIGEST: &str = "content-digest";
+/// The RFC 3230 predecessor of `Content-Digest`, still sent by some servers.
+const DIGEST: &str = "digest";
+
+/// Hash algorithms of RFC 9530, by their registered keys.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Algorithm {
+    Sha256,
+    Sha512,
+}
+
+impl Algorithm {
+    fn from_key(key: &str) -> Option<Self> {
+        match key.to_ascii_lowercase().as_str() {
+            "sha-256" => Some(Algorithm::Sha256),
+            "sha-512" => Some(Algorithm::Sha512),
+            _ => None,
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Algorithm::Sha256 => "sha-256",
+            Algorithm::Sha512 => "sha-512",
+        }
+    }
+
+    fn ring(self) -> &'static digest::Algorithm {
+        match self {
+            Algorithm::Sha256 => &digest::SHA256,
+            Algorithm::Sha512 => &digest::SHA512,
+        }
+    }
+
+    /// `Content-Digest` value for a digest, e.g. `sha-256=:X48E9q...=:`.
+    fn header_value(self, digest: &[u8]) -> HeaderValue {
+        HeaderValue::from_str(&format!("{}=:{}:", self.key(), BASE64.encode(digest)))
+            .expect("base64 is a valid header value")
+    }
+}
+
+/// `send_content_digest=`: a `Content-Digest` (RFC 9530) on every request body, and
+/// verification of the `Content-Digest` or `Digest` headers of responses.
+///
+/// Bodies held in memory get the header up front. Streamed bodies (files, generators)
+/// are hashed as they are sent and get it as a chunked-encoding trailer announced with
+/// `Trailer: content-digest`, as their digest is only known at the end.
+#[derive(Clone, Copy, Debug)]
+pub struct ContentDigest(Algorithm);
+
+impl ContentDigest {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match Algorithm::from_key(name) {
+            Some(algorithm) => Ok(ContentDigest(algorithm)),
+            None => bail!(
+                "Unknown send_content_digest '{}': expected 'sha-256' or 'sha-512'",
+                name
+            ),
+        }
+    }
+
+    /// Set `Content-Digest` on a request with a body held in memory, or announce it as a
+    /// trailer for a streamed one. Requests that already carry the header are left alone.
+    pub fn attach(&self, request: &mut reqwest::Request) {
+        if request.headers().contains_key(CONTENT_DIGEST) {
+            return;
+        }
+        let Some(body) = request.body() else {
+            return;
+        };
+        match body.as_bytes() {
+            Some(bytes) => {
+                let value = self
+                    .0
+                    .header_value(digest::digest(self.0.ring(), bytes).as_ref());
+                request.headers_mut().insert(CONTENT_DIGEST, value);
+            }
+            None => {
+                // Trailers need chunked encoding, which hyper only uses without a length
+                request.headers_mut().remove(CONTENT_LENGTH);
+                request
+                    .headers_mut()
+                    .append(TRAILER, HeaderValue::from_static(CONTENT_DIGEST));
+            }
+        }
+    }
+
+    /// Hash a streamed request body announced by `attach`, ending it with the trailer.
+    /// Must wrap the body last, as the other body wrappers pass on data frames only.
+    pub fn wrap_body(&self, request: &mut reqwest::Request) {
+        let announced = request.headers().get_all(TRAILER).iter().any(|value| {
+            value.to_str().is_ok_and(|names| {
+                names
+                    .split(',')
+                    .any(|name| name.trim().eq_ignore_ascii_case(CONTENT_DIGEST))
+            })
+        });
+        let streamed = request.body().is_some_and(|body| body.as_bytes().is_none());
+        if !(announced && streamed) {
+            return;
+        }
+        if let Some(body) = request.body_mut().take() {
+            *request.body_mut() = Some(Body::wrap(DigestBody {
+                inner: body,
+                algorithm: self.0,
+                context: Some(digest::Context::new(self.0.ring())),
+            }));
+        }
+    }
+
+    /// Check the body of `resp` against the SHA-256 and SHA-512 digests in its
+    /// `Content-Digest` and `Digest` headers as it is read, failing with a `DecodeError`
+    /// at the end of a mismatching body. Other algorithms are ignored.
+    pub fn verify(&self, resp: reqwest::Response, method: &Method) -> reqwest::Response {
+        if *method == Method::HEAD
+            || matches!(
+                resp.status(),
+                StatusCode::NO_CONTENT | StatusCode::NOT_MODIFIED
+            )
+        {
+            return resp;
+        }
+        let expected = expected_digests(resp.headers());
+        if expected.is_empty() {
+            return resp;
+        }
+        map_response_body(resp, |body| {
+            let contexts: Vec<_> = expected
+                .into_iter()
+                .map(|(header, algorithm, digest)| {
+                    let context = digest::Context::new(algorithm.ring());
+                    (header, algorithm, digest, context)
+                })
+                .collect();
+            let state = (body.into_data_stream(), Some(contexts));
+            Body::wrap_stream(stream::unfold(state, |(mut inner, contexts)| async move {
+                let mut contexts = contexts?;
+                match inner.next().await {
+                    Some(Ok(chunk)) => {
+                        for (_, _, _, context) in &mut contexts {
+                            context.update(&chunk);
+                        }
+                        Some((Ok(chunk), (inner, Some(contexts))))
+                    }
+                    Some(Err(e)) => Some((Err(Box::new(e) as BoxError), (inner, None))),
+                    None => {
+                        let mismatch = contexts.into_iter().find(|(_, _, expected, context)| {
+                            context.clone().finish().as_ref() != expected.as_slice()
+                        });
+                        let (header, algorithm, _, _) = mismatch?;
+                        let error = DecodeError::new(format!(
+                            "Response body does not match its {} {} digest",
+                            header,
+                            algorithm.key()
+                        ));
+                        Some((Err(Box::new(error) as BoxError), (inner, None)))
+                    }
+                }
+            }))
+        })
+    }
+}
+
+/// The supported digests announced by response headers, with the header they came from.
+fn expected_digests(headers: &HeaderMap) -> Vec<(&'static str, Algorithm, Vec<u8>)> {
+    let mut expected = Vec::new();
+    for (header, name, wrapped) in [
+        ("Content-Digest", CONTENT_DIGEST, true),
+        ("Digest", DIGEST, false),
+    ] {
+        for value in headers.get_all(name) {
+            let Ok(value) = value.to_str() else {
+                continue;
+            };
+            for (algorithm, digest) in parse_digests(value, wrapped) {
+                expected.push((header, algorithm, digest));
+            }
+        }
+    }
+    expected
+}
+
+/// Parse a `Content-Digest` dictionary (`sha-256=:<base64>:`, with `wrapped`) or a
+/// legacy `Digest` list (`SHA-256=<base64>`), skipping unknown algorithms, parameters
+/// and malformed members.
+fn parse_digests(value: &str, wrapped: bool) -> Vec<(Algorithm, Vec<u8>)> {
+    value
+        .split(',')
+        .filter_map(|member| {
+            let (key, digest) = member.split_once('=')?;
+            let algorithm = Algorithm::from_key(key.trim())?;
+            let digest = digest.split(';').next()?.trim();
+            let digest = if wrapped {
+                digest.strip_prefix(':')?.strip_suffix(':')?
+            } else {
+                digest
+            };
+            Some((algorithm, BASE64.decode(digest).ok()?))
+        })
+        .collect()
+}
+
+/// A streamed request body hashed as it is sent, ending with a `Content-Digest` trailer.
+struct DigestBody {
+    inner: Body,
+    algorithm: Algorithm,
+    /// Taken when the trailer has been sent.
+    context: Option<digest::Context>,
+}
+
+impl HttpBody for DigestBody {
+    type Data = Bytes;
+    type Error = reqwest::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, reqwest::Error>>> {
+        match Pin::new(&mut self.inner).poll_frame(cx) {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let (Some(data), Some(context)) = (frame.data_ref(), self.context.as_mut()) {
+                    context.update(data);
+                }
+                Poll::Ready(Some(Ok(frame)))
+            }
+            Poll::Ready(None) => {
+                let Some(context) = self.context.take() else {
+                    return Poll::Ready(None);
+                };
+                let mut trailers = HeaderMap::new();
+                trailers.insert(
+                    CONTENT_DIGEST,
+                    self.algorithm.header_value(context.finish().as_ref()),
+                );
+                Poll::Ready(Some(Ok(Frame::trailers(trailers))))
+            }
+            other => other,
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.context.is_none() && self.inner.is_end_stream()
+    }
+
+    /// Unknown, so that hyper uses chunked encoding, which carries the trailer.
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_digests() {
+        let sha256 = digest::digest(&digest::SHA256, b"{\"hello\": \"world\"}\n");
+        let encoded = BASE64.encode(sha256.as_ref());
+        let value = format!("md5=:AAAA:, sha-256=:{}:;p=1", encoded);
+        assert_eq!(
+            parse_digests(&value, true),
+            vec![(Algorithm::Sha256, sha256.as_ref().to_vec())]
+        );
+        let value = format!("SHA-256={},UNIXsum=30637", encoded);
+        assert_eq!(
+            parse_digests(&value, false),
+            vec![(Algorithm::Sha256, sha256.as_ref().to_vec())]
+        );
+        assert!(parse_digests("sha-256=:not base64:", true).is_empty());
+        assert!(parse_digests(&format!("sha-256={}", encoded), true).is_empty());
+    }
+
+    #[test]
+    fn test_header_value() {
+        // Example from RFC 9530, section 2
+        let digest = digest::digest(&digest::SHA256, b"{\"hello\": \"world\"}");
+        assert_eq!(
+            Algorithm::Sha256.header_value(digest.as_ref()),
+            "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:"
+        );
+    }
+}
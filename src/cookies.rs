@@ -0,0 +1,469 @@
+use std::future::Future;
+use std::net::IpAddr;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+
+use anyhow::{bail, Result};
+use cookie_store::{Cookie, CookieStore};
+use foldhash::fast::RandomState;
+use indexmap::IndexMap;
+use publicsuffix::{List, Psl};
+use reqwest::header::HeaderValue;
+use url::{Host, Url};
+
+/// Snapshot of the Public Suffix List (<https://publicsuffix.org/list/>), parsed on first use.
+static PUBLIC_SUFFIX_LIST: LazyLock<List> = LazyLock::new(|| {
+    include_str!("public_suffix_list.dat")
+        .parse()
+        .expect("Failed to parse the bundled Public Suffix List")
+});
+
+tokio::task_local! {
+    /// Site of the URL the caller asked for, set around each request so that the jar can
+    /// tell first-party responses from redirect targets on other sites.
+    static FIRST_PARTY: String;
+}
+
+/// Run `fut` (sending a request to `url`) with `url`'s site as the first party.
+pub async fn with_first_party<F: Future>(url: &Url, fut: F) -> F::Output {
+    match url.host_str() {
+        Some(host) => FIRST_PARTY.scope(site(host), fut).await,
+        None => fut.await,
+    }
+}
+
+type IndexMapSSR = IndexMap<String, String, RandomState>;
+
+tokio::task_local! {
+    /// Cookies set by the responses of the current request, collected by `RecordingJar`.
+    static RECEIVED: Arc<Mutex<IndexMapSSR>>;
+}
+
+/// Cookies set by every response of a request, redirects included, attached to the
+/// final response by `RequestSpec::send`.
+#[derive(Clone, Default)]
+pub struct ReceivedCookies(pub IndexMapSSR);
+
+/// Run `fut` (sending a request), collecting the cookies its responses set.
+pub async fn record_cookies<F: Future>(fut: F) -> (F::Output, ReceivedCookies) {
+    let received = Arc::new(Mutex::new(IndexMap::with_hasher(RandomState::default())));
+    let output = RECEIVED.scope(Arc::clone(&received), fut).await;
+    let cookies = std::mem::take(&mut *received.lock().unwrap_or_else(|e| e.into_inner()));
+    (output, ReceivedCookies(cookies))
+}
+
+/// Cookies of `resp`: those set along its redirect chain, then its own, a later cookie
+/// replacing an earlier one of the same name.
+pub fn response_cookies(resp: &reqwest::Response) -> IndexMapSSR {
+    let mut cookies = resp
+        .extensions()
+        .get::<ReceivedCookies>()
+        .map(|received| received.0.clone())
+        .unwrap_or_default();
+    cookies.extend(
+        resp.cookies()
+            .map(|cookie| (cookie.name().to_string(), cookie.value().to_string())),
+    );
+    cookies
+}
+
+/// How per-request `cookies=` combine with the client `cookies=` (`cookie_merge`).
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum CookieMerge {
+    /// Send the client cookies too; a request cookie replaces a client cookie of the
+    /// same name.
+    #[default]
+    Merge,
+    /// Send only the request cookies.
+    Replace,
+}
+
+impl CookieMerge {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "merge" => Ok(CookieMerge::Merge),
+            "replace" => Ok(CookieMerge::Replace),
+            _ => bail!("cookie_merge must be 'merge' or 'replace', got {:?}", name),
+        }
+    }
+
+    /// `Cookie` header for the request `cookies`, given the client `Cookie` header.
+    pub fn header(
+        self,
+        client: Option<&HeaderValue>,
+        cookies: &IndexMap<String, String, RandomState>,
+    ) -> String {
+        let client = match self {
+            CookieMerge::Merge => client.and_then(|value| value.to_str().ok()),
+            CookieMerge::Replace => None,
+        };
+        client
+            .into_iter()
+            .flat_map(|value| value.split(';'))
+            .filter_map(|pair| pair.trim().split_once('='))
+            .filter(|(name, _)| !cookies.contains_key(*name))
+            .chain(
+                cookies
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str())),
+            )
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+/// Registrable domain ("site") of `host`, e.g. `example.co.uk` for `www.example.co.uk`.
+/// IP addresses and hosts without a registrable domain are their own site.
+fn site(host: &str) -> String {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.starts_with('[') || host.parse::<IpAddr>().is_ok() {
+        return host;
+    }
+    match PUBLIC_SUFFIX_LIST.domain(host.as_bytes()) {
+        Some(domain) => String::from_utf8_lossy(domain.as_bytes()).into_owned(),
+        None => host,
+    }
+}
+
+/// `domain` equals `parent` or is a subdomain of it.
+fn domain_match(domain: &str, parent: &str) -> bool {
+    domain == parent
+        || domain
+            .strip_suffix(parent)
+            .is_some_and(|rest| rest.ends_with('.'))
+}
+
+/// Rules applied by `PolicyJar` on top of RFC 6265 cookie handling.
+#[derive(Default)]
+pub struct CookiePolicy {
+    /// Ignore cookies from, and send none to, hosts on a different site than the
+    /// requested URL (e.g. a cross-site redirect target).
+    pub reject_third_party: bool,
+    /// Never store or send cookies for these domains or their subdomains.
+    pub blocked_domains: Vec<String>,
+    /// Ignore new cookies once a domain holds this many.
+    pub max_per_domain: Option<usize>,
+    /// Ignore cookies whose name and value together exceed this many bytes.
+    pub max_size: Option<usize>,
+    /// Reject cookies scoped to a public suffix such as `co.uk`.
+    pub public_suffix: bool,
+}
+
+impl CookiePolicy {
+    /// Build from the Python arguments; `None` when no rule is set, so the client can
+    /// keep reqwest's default cookie jar.
+    pub fn from_args(
+        reject_third_party: Option<bool>,
+        blocked_domains: Option<Vec<String>>,
+        max_per_domain: Option<usize>,
+        max_size: Option<usize>,
+        public_suffix: Option<bool>,
+    ) -> Result<Option<Self>> {
+        if max_per_domain == Some(0) || max_size == Some(0) {
+            bail!("max_cookies_per_domain and max_cookie_size must be positive");
+        }
+        let policy = CookiePolicy {
+            reject_third_party: reject_third_party.unwrap_or(false),
+            blocked_domains: blocked_domains
+                .unwrap_or_default()
+                .into_iter()
+                .map(|d| d.trim_start_matches('.').to_ascii_lowercase())
+                .collect(),
+            max_per_domain,
+            max_size,
+            public_suffix: public_suffix.unwrap_or(false),
+        };
+        let enabled = policy.reject_third_party
+            || !policy.blocked_domains.is_empty()
+            || policy.max_per_domain.is_some()
+            || policy.max_size.is_some()
+            || policy.public_suffix;
+        Ok(enabled.then_some(policy))
+    }
+
+    fn is_blocked(&self, domain: &str) -> bool {
+        self.blocked_domains
+            .iter()
+            .any(|blocked| domain_match(domain, blocked))
+    }
+
+    fn is_third_party(&self, host: &str) -> bool {
+        self.reject_third_party
+            && FIRST_PARTY
+                .try_with(|first_party| *first_party != site(host))
+                .unwrap_or(false)
+    }
+}
+
+/// Cookie jar enforcing a `CookiePolicy`; otherwise behaves like reqwest's `Jar`.
+pub struct PolicyJar {
+    store: RwLock<CookieStore>,
+    policy: CookiePolicy,
+}
+
+impl PolicyJar {
+    pub fn new(policy: CookiePolicy) -> Self {
+        let psl = policy.public_suffix.then(|| PUBLIC_SUFFIX_LIST.clone());
+        PolicyJar {
+            store: RwLock::new(CookieStore::new_with_public_suffix(psl)),
+            policy,
+        }
+    }
+
+    fn host(url: &Url) -> Option<String> {
+        match url.host()? {
+            Host::Domain(domain) => Some(domain.to_ascii_lowercase()),
+            host => Some(host.to_string()),
+        }
+    }
+
+    /// Store one `Set-Cookie` value received from `url`, if the policy allows it.
+    fn insert(&self, store: &mut CookieStore, header: &HeaderValue, url: &Url, host: &str) {
+        let Some(raw) = header
+            .to_str()
+            .ok()
+            .and_then(|s| cookie::Cookie::parse(s).ok())
+        else {
+            return;
+        };
+        if self
+            .policy
+            .max_size
+            .is_some_and(|max| raw.name().len() + raw.value().len() > max)
+        {
+            return;
+        }
+        let Ok(cookie) = Cookie::try_from_raw_cookie(&raw, url) else {
+            return;
+        };
+        let domain = String::from(&cookie.domain);
+        if self.policy.is_blocked(&domain) || self.policy.is_blocked(host) {
+            return;
+        }
+        if let Some(max) = self.policy.max_per_domain {
+            let exists = store.contains_any(&domain, &String::from(&cookie.path), cookie.name());
+            let count = store
+                .iter_unexpired()
+                .filter(|c| String::from(&c.domain) == domain)
+                .count();
+            if !exists && count >= max {
+                return;
+            }
+        }
+        let _ = store.insert(cookie.into_owned(), url);
+    }
+}
+
+impl reqwest::cookie::CookieStore for PolicyJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let Some(host) = PolicyJar::host(url) else {
+            return;
+        };
+        if self.policy.is_third_party(&host) {
+            return;
+        }
+        let mut store = self.store.write().unwrap_or_else(|e| e.into_inner());
+        for header in cookie_headers {
+            self.insert(&mut store, header, url, &host);
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let host = PolicyJar::host(url)?;
+        if self.policy.is_blocked(&host) || self.policy.is_third_party(&host) {
+            return None;
+        }
+        let value = self
+            .store
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_request_values(url)
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if value.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&value).ok()
+    }
+}
+
+/// Cookie store in front of the client jar, if any, that records the cookies each
+/// response sets, including redirect responses the caller never sees.
+pub struct RecordingJar {
+    jar: Option<Arc<dyn reqwest::cookie::CookieStore>>,
+}
+
+impl RecordingJar {
+    pub fn new(jar: Option<Arc<dyn reqwest::cookie::CookieStore>>) -> Self {
+        RecordingJar { jar }
+    }
+}
+
+impl reqwest::cookie::CookieStore for RecordingJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let headers: Vec<&HeaderValue> = cookie_headers.collect();
+        let _ = RECEIVED.try_with(|received| {
+            let mut received = received.lock().unwrap_or_else(|e| e.into_inner());
+            for header in &headers {
+                if let Some(cookie) = header
+                    .to_str()
+                    .ok()
+                    .and_then(|s| cookie::Cookie::parse(s).ok())
+                {
+                    received.insert(cookie.name().to_string(), cookie.value().to_string());
+                }
+            }
+        });
+        if let Some(jar) = &self.jar {
+            jar.set_cookies(&mut headers.into_iter(), url);
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        self.jar.as_ref()?.cookies(url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(jar: &dyn reqwest::cookie::CookieStore, url: &str, cookies: &[&str]) {
+        let headers: Vec<_> = cookies
+            .iter()
+            .map(|c| HeaderValue::from_str(c).unwrap())
+            .collect();
+        jar.set_cookies(&mut headers.iter(), &url.parse().unwrap());
+    }
+
+    fn get(jar: &dyn reqwest::cookie::CookieStore, url: &str) -> Option<String> {
+        jar.cookies(&url.parse().unwrap())
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    #[test]
+    fn test_cookie_merge() {
+        let client = HeaderValue::from_static("session=abc; theme=dark");
+        let mut cookies = IndexMap::with_hasher(RandomState::default());
+        cookies.insert("theme".to_string(), "light".to_string());
+        cookies.insert("lang".to_string(), "en".to_string());
+        assert_eq!(
+            CookieMerge::Merge.header(Some(&client), &cookies),
+            "session=abc; theme=light; lang=en"
+        );
+        assert_eq!(
+            CookieMerge::Replace.header(Some(&client), &cookies),
+            "theme=light; lang=en"
+        );
+        assert_eq!(
+            CookieMerge::Merge.header(None, &cookies),
+            "theme=light; lang=en"
+        );
+        assert!(CookieMerge::from_name("append").is_err());
+    }
+
+    #[test]
+    fn test_site() {
+        assert_eq!(site("www.example.co.uk"), "example.co.uk");
+        assert_eq!(site("a.b.example.com"), "example.com");
+        assert_eq!(site("127.0.0.1"), "127.0.0.1");
+        assert_eq!(site("[::1]"), "[::1]");
+        assert_eq!(site("localhost"), "localhost");
+    }
+
+    #[test]
+    fn test_from_args() {
+        assert!(CookiePolicy::from_args(None, None, None, None, None)
+            .unwrap()
+            .is_none());
+        assert!(CookiePolicy::from_args(None, None, Some(0), None, None).is_err());
+        let policy = CookiePolicy::from_args(None, Some(vec![".Ads.com".into()]), None, None, None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(policy.blocked_domains, vec!["ads.com"]);
+    }
+
+    #[test]
+    fn test_blocked_domains() {
+        let jar = PolicyJar::new(CookiePolicy {
+            blocked_domains: vec!["tracker.com".into()],
+            ..Default::default()
+        });
+        set(&jar, "http://cdn.tracker.com/", &["id=1"]);
+        set(&jar, "http://example.com/", &["id=2"]);
+        assert_eq!(get(&jar, "http://cdn.tracker.com/"), None);
+        assert_eq!(get(&jar, "http://example.com/"), Some("id=2".into()));
+        assert!(!domain_match("nottracker.com", "tracker.com"));
+    }
+
+    #[test]
+    fn test_limits() {
+        let jar = PolicyJar::new(CookiePolicy {
+            max_per_domain: Some(2),
+            max_size: Some(8),
+            ..Default::default()
+        });
+        set(&jar, "http://example.com/", &["a=1", "b=2", "c=3", "a=4"]);
+        set(&jar, "http://example.com/", &["big=0123456789"]);
+        let mut stored: Vec<_> = get(&jar, "http://example.com/")
+            .unwrap()
+            .split("; ")
+            .map(String::from)
+            .collect();
+        stored.sort();
+        assert_eq!(stored, vec!["a=4", "b=2"]);
+    }
+
+    #[test]
+    fn test_public_suffix() {
+        let jar = PolicyJar::new(CookiePolicy {
+            public_suffix: true,
+            ..Default::default()
+        });
+        set(
+            &jar,
+            "http://shop.example.co.uk/",
+            &["super=1; Domain=co.uk"],
+        );
+        assert_eq!(get(&jar, "http://other.co.uk/"), None);
+    }
+
+    #[tokio::test]
+    async fn test_recording_jar() {
+        let jar = RecordingJar::new(None);
+        let ((), received) = record_cookies(async {
+            set(&jar, "http://example.com/login", &["session=1", "step=1"]);
+            set(&jar, "http://example.com/home", &["step=2; Path=/"]);
+        })
+        .await;
+        let received: Vec<_> = received.0.into_iter().collect();
+        assert_eq!(
+            received,
+            vec![
+                ("session".to_string(), "1".to_string()),
+                ("step".to_string(), "2".to_string())
+            ]
+        );
+        // Outside of a request nothing is recorded, and without a jar nothing is stored
+        set(&jar, "http://example.com/", &["other=1"]);
+        assert_eq!(get(&jar, "http://example.com/"), None);
+    }
+
+    #[tokio::test]
+    async fn test_third_party() {
+        let jar = PolicyJar::new(CookiePolicy {
+            reject_third_party: true,
+            ..Default::default()
+        });
+        let first_party: Url = "http://www.example.com/".parse().unwrap();
+        with_first_party(&first_party, async {
+            set(&jar, "http://api.example.com/", &["first=1"]);
+            set(&jar, "http://tracker.net/", &["third=1"]);
+            assert_eq!(get(&jar, "http://tracker.net/"), None);
+        })
+        .await;
+        assert_eq!(get(&jar, "http://api.example.com/"), Some("first=1".into()));
+        assert_eq!(get(&jar, "http://tracker.net/"), None);
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::{bail, Result};
+use csv_core::{ReadRecordResult, Reader, ReaderBuilder};
+use encoding_rs::Encoding;
+
+/// Splits a CSV body fed in arbitrary chunks into rows of decoded fields.
+///
+/// Quoting, escaped quotes and line breaks inside quoted fields may straddle chunk
+/// boundaries; only the current row is buffered. A UTF-8 BOM at the start is ignored,
+/// blank lines are skipped, and every row must have as many fields as the first.
+pub struct CsvRows {
+    reader: Reader,
+    encoding: &'static Encoding,
+    output: Vec<u8>,
+    output_len: usize,
+    ends: Vec<usize>,
+    ends_len: usize,
+    width: Option<usize>,
+    row: usize,
+}
+
+impl CsvRows {
+    pub fn new(delimiter: u8, encoding: &'static Encoding) -> Self {
+        CsvRows {
+            reader: ReaderBuilder::new().delimiter(delimiter).build(),
+            encoding,
+            output: vec![0; 1024],
+            output_len: 0,
+            ends: vec![0; 16],
+            ends_len: 0,
+            width: None,
+            row: 0,
+        }
+    }
+
+    /// Push every row completed by `chunk` onto `rows`.
+    pub fn feed(&mut self, chunk: &[u8], rows: &mut Vec<Vec<String>>) -> Result<()> {
+        self.read(chunk, false, rows)
+    }
+
+    /// Push the final row, which may lack a trailing newline, onto `rows`.
+    pub fn finish(&mut self, rows: &mut Vec<Vec<String>>) -> Result<()> {
+        self.read(&[], true, rows)
+    }
+
+    fn read(&mut self, mut input: &[u8], eof: bool, rows: &mut Vec<Vec<String>>) -> Result<()> {
+        loop {
+            // An empty input tells the reader the body has ended
+            if input.is_empty() && !eof {
+                return Ok(());
+            }
+            let (result, nin, nout, nend) = self.reader.read_record(
+                input,
+                &mut self.output[self.output_len..],
+                &mut self.ends[self.ends_len..],
+            );
+            input = &input[nin..];
+            self.output_len += nout;
+            self.ends_len += nend;
+            match result {
+                ReadRecordResult::InputEmpty => {}
+                ReadRecordResult::OutputFull => self.output.resize(self.output.len() * 2, 0),
+                ReadRecordResult::OutputEndsFull => self.ends.resize(self.ends.len() * 2, 0),
+                ReadRecordResult::Record => rows.push(self.take_row()?),
+                ReadRecordResult::End => return Ok(()),
+            }
+        }
+    }
+
+    fn take_row(&mut self) -> Result<Vec<String>> {
+        self.row += 1;
+        let ends = &self.ends[..self.ends_len];
+        let mut start = 0;
+        let mut fields = Vec::with_capacity(ends.len());
+        for &end in ends {
+            let (field, _) = self
+                .encoding
+                .decode_without_bom_handling(&self.output[start..end]);
+            fields.push(field.into_owned());
+            start = end;
+        }
+        self.output_len = 0;
+        self.ends_len = 0;
+        // The reader only drops a BOM that arrives whole in the first chunk
+        if self.row == 1 {
+            if let Some(first) = fields.first_mut() {
+                if let Some(rest) = first.strip_prefix('\u{feff}') {
+                    *first = rest.to_string();
+                }
+            }
+        }
+        match self.width {
+            None => self.width = Some(fields.len()),
+            Some(width) if width != fields.len() => bail!(
+                "CSV row {} has {} fields, expected {}",
+                self.row,
+                fields.len(),
+                width
+            ),
+            Some(_) => {}
+        }
+        Ok(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(body: &[u8], delimiter: u8, chunk_size: usize) -> Result<Vec<Vec<String>>> {
+        let mut parser = CsvRows::new(delimiter, encoding_rs::UTF_8);
+        let mut rows = Vec::new();
+        for chunk in body.chunks(chunk_size) {
+            parser.feed(chunk, &mut rows)?;
+        }
+        parser.finish(&mut rows)?;
+        Ok(rows)
+    }
+
+    #[test]
+    fn test_csv_rows_across_chunks() {
+        let body =
+            "\u{feff}id,note\r\n1,\"a, \"\"quoted\"\"\nvalue\"\r\n\r\n2,plain\n3,".as_bytes();
+        let expected = vec![
+            vec!["id", "note"],
+            vec!["1", "a, \"quoted\"\nvalue"],
+            vec!["2", "plain"],
+            vec!["3", ""],
+        ];
+        for chunk_size in [1, 2, 5, body.len()] {
+            assert_eq!(parse(body, b',', chunk_size).unwrap(), expected);
+        }
+        let long = "x".repeat(5000);
+        let body = format!("{}\tb\n", long);
+        assert_eq!(
+            parse(body.as_bytes(), b'\t', 7).unwrap(),
+            vec![vec![long.as_str(), "b"]]
+        );
+    }
+
+    #[test]
+    fn test_csv_rows_width_mismatch() {
+        let err = parse(b"a,b\n1,2\n3\n", b',', 4).unwrap_err();
+        assert_eq!(err.to_string(), "CSV row 3 has 1 fields, expected 2");
+    }
+}
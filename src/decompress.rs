@@ -0,0 +1,243 @@
+use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use async_compression::tokio::bufread::{BrotliDecoder, GzipDecoder, ZlibDecoder, ZstdDecoder};
+use futures_util::{stream, StreamExt};
+use http_body_util::BodyExt;
+use reqwest::header::{HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH};
+use reqwest::{Body, Method, StatusCode};
+use tokio::io::AsyncRead;
+use tokio_util::io::{ReaderStream, StreamReader};
+
+use crate::codecs::Codecs;
+use crate::request::map_response_body;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Same as reqwest sends with its own decoders enabled.
+const ACCEPT_ENCODINGS: &str = "zstd,gzip,deflate,br";
+
+/// Decompressed bytes below which `max_ratio` is not enforced: small, repetitive bodies
+/// legitimately compress far better than a bomb needs to.
+const RATIO_GRACE_BYTES: u64 = 1024 * 1024;
+
+/// A response body that could not be decoded, or exceeded the limits of the
+/// `DecompressionGuard`. Raised as `DecodingError`.
+#[derive(Debug)]
+pub struct DecodeError(String);
+
+impl DecodeError {
+    pub fn new(message: String) -> Self {
+        DecodeError(message)
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Content decoding with limits on the decompressed size (`max_decompressed_size`) and
+/// the ratio of decompressed to compressed bytes (`max_decompression_ratio`).
+///
+/// reqwest's decoders do not report the compressed size, so clients turn them off and
+/// decode response bodies themselves, along with those in a custom `Codecs` encoding;
+/// the default guard has no limits.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DecompressionGuard {
+    max_size: Option<u64>,
+    max_ratio: Option<f64>,
+}
+
+impl DecompressionGuard {
+    pub fn from_args(max_size: Option<u64>, max_ratio: Option<f64>) -> Result<Option<Self>> {
+        if max_size == Some(0) {
+            bail!("max_decompressed_size must be positive");
+        }
+        if let Some(ratio) = max_ratio {
+            if !ratio.is_finite() || ratio < 1.0 {
+                bail!("max_decompression_ratio must be a number of at least 1");
+            }
+        }
+        if max_size.is_none() && max_ratio.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(DecompressionGuard {
+            max_size,
+            max_ratio,
+        }))
+    }
+
+    /// Advertise the supported encodings, as reqwest would, plus the custom codecs that
+    /// decode, unless the request sets its own.
+    pub fn accept_encoding(&self, request: &mut reqwest::Request, codecs: &Codecs) {
+        let value = match codecs.accepted().collect::<Vec<_>>() {
+            custom if custom.is_empty() => HeaderValue::from_static(ACCEPT_ENCODINGS),
+            custom => HeaderValue::from_str(&format!("{},{}", ACCEPT_ENCODINGS, custom.join(",")))
+                .expect("codec names are valid header values"),
+        };
+        request
+            .headers_mut()
+            .entry(ACCEPT_ENCODING)
+            .or_insert(value);
+    }
+
+    /// Decode the body of `resp` according to its `Content-Encoding`, removing the
+    /// `Content-Encoding` and `Content-Length` headers like reqwest does.
+    pub fn decode(
+        self,
+        resp: reqwest::Response,
+        method: &Method,
+        codecs: &Codecs,
+    ) -> reqwest::Response {
+        let encoding = resp
+            .headers()
+            .get(CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_ascii_lowercase());
+        let Some(encoding) = encoding.as_deref() else {
+            return resp;
+        };
+        let codecs = match encoding {
+            "gzip" | "x-gzip" | "deflate" | "br" | "zstd" => None,
+            _ if codecs.can_decode(encoding) => Some(codecs.clone()),
+            _ => return resp,
+        };
+        let status = resp.status();
+        if *method == Method::HEAD
+            || status == StatusCode::NO_CONTENT
+            || status == StatusCode::NOT_MODIFIED
+            || resp.headers().get(CONTENT_LENGTH) == Some(&HeaderValue::from_static("0"))
+        {
+            return resp;
+        }
+        let encoding = encoding.to_string();
+        let mut resp = map_response_body(resp, |body| self.decode_body(body, &encoding, codecs));
+        resp.headers_mut().remove(CONTENT_ENCODING);
+        resp.headers_mut().remove(CONTENT_LENGTH);
+        resp
+    }
+
+    fn decode_body(self, body: Body, encoding: &str, codecs: Option<Codecs>) -> Body {
+        let compressed = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&compressed);
+        let reader = StreamReader::new(body.into_data_stream().map(move |chunk| {
+            chunk
+                .inspect(|chunk| {
+                    counter.fetch_add(chunk.len() as u64, Ordering::Relaxed);
+                })
+                .map_err(io::Error::other)
+        }));
+        let decoder: Box<dyn AsyncRead + Send + Unpin> = match (encoding, codecs) {
+            (_, Some(codecs)) => {
+                let encoding = encoding.to_string();
+                let decoded = async move { codecs.decode(&encoding, reader).await };
+                Box::new(StreamReader::new(stream::once(Box::pin(decoded))))
+            }
+            ("gzip" | "x-gzip", _) => Box::new(GzipDecoder::new(reader)),
+            ("deflate", _) => Box::new(ZlibDecoder::new(reader)),
+            ("br", _) => Box::new(BrotliDecoder::new(reader)),
+            _ => Box::new(ZstdDecoder::new(reader)),
+        };
+        let encoding = encoding.to_string();
+
+        struct State {
+            inner: ReaderStream<Box<dyn AsyncRead + Send + Unpin>>,
+            decompressed: u64,
+            failed: bool,
+        }
+
+        let state = State {
+            inner: ReaderStream::new(decoder),
+            decompressed: 0,
+            failed: false,
+        };
+        Body::wrap_stream(stream::unfold(state, move |mut state| {
+            let compressed = Arc::clone(&compressed);
+            let encoding = encoding.clone();
+            async move {
+                if state.failed {
+                    return None;
+                }
+                let chunk = match state.inner.next().await? {
+                    Ok(chunk) => chunk,
+                    Err(e) => {
+                        state.failed = true;
+                        let error: BoxError = match e.downcast::<reqwest::Error>() {
+                            // Errors reading the compressed body (timeouts etc.) pass through
+                            Ok(e) => Box::new(e),
+                            Err(e) => Box::new(DecodeError(format!(
+                                "Error decoding {} response body: {}",
+                                encoding, e
+                            ))),
+                        };
+                        return Some((Err(error), state));
+                    }
+                };
+                state.decompressed += chunk.len() as u64;
+                let compressed = compressed.load(Ordering::Relaxed);
+                if let Some(message) = self.exceeded(state.decompressed, compressed) {
+                    state.failed = true;
+                    return Some((Err(Box::new(DecodeError(message)) as BoxError), state));
+                }
+                Some((Ok(chunk), state))
+            }
+        }))
+    }
+
+    /// Why `decompressed` bytes decoded from `compressed` bytes exceed the limits, if they do.
+    fn exceeded(&self, decompressed: u64, compressed: u64) -> Option<String> {
+        if let Some(max_size) = self.max_size {
+            if decompressed > max_size {
+                return Some(format!(
+                    "Decompressed response body exceeds max_decompressed_size ({} bytes)",
+                    max_size
+                ));
+            }
+        }
+        if let Some(max_ratio) = self.max_ratio {
+            let ratio = decompressed as f64 / compressed.max(1) as f64;
+            if decompressed > RATIO_GRACE_BYTES && ratio > max_ratio {
+                return Some(format!(
+                    "Response body decompresses {:.0}x, more than max_decompression_ratio ({})",
+                    ratio, max_ratio
+                ));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_args() {
+        assert!(DecompressionGuard::from_args(None, None).unwrap().is_none());
+        assert!(DecompressionGuard::from_args(Some(0), None).is_err());
+        assert!(DecompressionGuard::from_args(None, Some(0.5)).is_err());
+        assert!(DecompressionGuard::from_args(None, Some(f64::NAN)).is_err());
+        assert!(DecompressionGuard::from_args(Some(1024), Some(100.0))
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_exceeded() {
+        let guard = DecompressionGuard::from_args(Some(10 << 20), Some(100.0))
+            .unwrap()
+            .unwrap();
+        assert!(guard.exceeded(5 << 20, 1 << 20).is_none());
+        assert!(guard.exceeded(11 << 20, 1 << 20).is_some());
+        // Ratios only count past the grace size
+        assert!(guard.exceeded(1 << 20, 1).is_none());
+        assert!(guard.exceeded(2 << 20, 1 << 10).is_some());
+    }
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use tokio::time::Instant;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Why a host name could not be resolved, as far as `getaddrinfo` tells.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DnsErrorKind {
+    /// The name does not exist or has no addresses (NXDOMAIN, NODATA).
+    NotFound,
+    /// The resolver could not be reached or answered with a temporary failure.
+    Temporary,
+    Other,
+}
+
+impl DnsErrorKind {
+    /// Classify a system resolver error by its message, the only place the `EAI_*` code
+    /// survives in `std::io::Error`.
+    fn classify(message: &str) -> Self {
+        let message = message.to_ascii_lowercase();
+        if [
+            "name or service not known",
+            "nodename nor servname provided",
+            "no address associated with hostname",
+            "no such host is known",
+        ]
+        .iter()
+        .any(|text| message.contains(text))
+        {
+            DnsErrorKind::NotFound
+        } else if message.contains("temporary failure") || message.contains("temporary error") {
+            DnsErrorKind::Temporary
+        } else {
+            DnsErrorKind::Other
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DnsErrorKind::NotFound => "not_found",
+            DnsErrorKind::Temporary => "temporary",
+            DnsErrorKind::Other => "other",
+        }
+    }
+}
+
+/// A failed lookup, raised as `DNSError` with the host name and `DnsErrorKind`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DnsFailure {
+    pub host: String,
+    pub kind: DnsErrorKind,
+    pub message: String,
+}
+
+impl fmt::Display for DnsFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed to resolve {}: {}", self.host, self.message)
+    }
+}
+
+impl std::error::Error for DnsFailure {}
+
+/// Resolve `host` with the system resolver.
+pub async fn lookup(host: &str) -> Result<Vec<SocketAddr>, DnsFailure> {
+    let failure = |message: String| DnsFailure {
+        host: host.to_string(),
+        kind: DnsErrorKind::classify(&message),
+        message,
+    };
+    match tokio::net::lookup_host((host, 0)).await {
+        Ok(addrs) => {
+            let addrs: Vec<SocketAddr> = addrs.collect();
+            if addrs.is_empty() {
+                return Err(failure("no addresses found".to_string()));
+            }
+            Ok(addrs)
+        }
+        Err(err) => Err(failure(err.to_string())),
+    }
+}
+
+/// The client's resolver when no other is configured, so that every lookup failure
+/// is a `DnsFailure`.
+pub struct SystemResolver;
+
+impl Resolve for SystemResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs = lookup(&host).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+enum Entry {
+    Resolved(Vec<SocketAddr>),
+    Failed(DnsFailure),
+}
+
+struct Cached {
+    entry: Entry,
+    expires: Instant,
+}
+
+/// In-process DNS cache used as the client's resolver.
+///
+/// Lookups go through the system resolver (`getaddrinfo`), which does not expose record
+/// TTLs, so successful answers are kept for `ttl` (an upper bound on staleness) and
+/// failures for `negative_ttl`, if set. Clones share the same cache.
+#[derive(Clone)]
+pub struct DnsCache {
+    ttl: Duration,
+    negative_ttl: Option<Duration>,
+    entries: Arc<Mutex<HashMap<String, Cached>>>,
+}
+
+impl DnsCache {
+    pub fn new(ttl: Duration, negative_ttl: Option<Duration>) -> Self {
+        DnsCache {
+            ttl,
+            negative_ttl,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Build from the Python arguments. The cache is enabled by `ttl`; a negative TTL
+    /// alone is an error since there would be no cache to put failures in.
+    pub fn from_args(ttl: Option<f64>, negative_ttl: Option<f64>) -> Result<Option<Self>> {
+        let Some(ttl) = ttl else {
+            if negative_ttl.is_some() {
+                bail!("dns_cache_negative_ttl requires dns_cache_ttl");
+            }
+            return Ok(None);
+        };
+        let seconds = |value: f64, name: &str| {
+            if !value.is_finite() || value < 0.0 {
+                bail!("{} must be a non-negative number of seconds", name);
+            }
+            Ok(Duration::from_secs_f64(value))
+        };
+        Ok(Some(DnsCache::new(
+            seconds(ttl, "dns_cache_ttl")?,
+            negative_ttl
+                .map(|value| seconds(value, "dns_cache_negative_ttl"))
+                .transpose()?,
+        )))
+    }
+
+    /// Drop every cached answer, successful or not.
+    pub fn flush(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+
+    fn lookup(&self, host: &str) -> Option<Result<Vec<SocketAddr>, DnsFailure>> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match entries.get(host) {
+            Some(cached) if cached.expires > Instant::now() => Some(match &cached.entry {
+                Entry::Resolved(addrs) => Ok(addrs.clone()),
+                Entry::Failed(failure) => Err(failure.clone()),
+            }),
+            Some(_) => {
+                entries.remove(host);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn store(&self, host: String, entry: Entry) {
+        let ttl = match entry {
+            Entry::Resolved(_) => self.ttl,
+            Entry::Failed(_) => match self.negative_ttl {
+                Some(ttl) => ttl,
+                None => return,
+            },
+        };
+        let cached = Cached {
+            entry,
+            expires: Instant::now() + ttl,
+        };
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(host, cached);
+    }
+}
+
+impl Resolve for DnsCache {
+    fn resolve(&self, name: Name) -> Resolving {
+        let cache = self.clone();
+        let host = name.as_str().to_ascii_lowercase();
+        Box::pin(async move {
+            let result = match cache.lookup(&host) {
+                Some(result) => result,
+                None => {
+                    let result = lookup(&host).await;
+                    let entry = match &result {
+                        Ok(addrs) => Entry::Resolved(addrs.clone()),
+                        Err(failure) => Entry::Failed(failure.clone()),
+                    };
+                    cache.store(host, entry);
+                    result
+                }
+            };
+            match result {
+                Ok(addrs) => Ok(Box::new(addrs.into_iter()) as Addrs),
+                Err(failure) => Err(BoxError::from(failure)),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_from_args() {
+        assert!(DnsCache::from_args(None, None).unwrap().is_none());
+        assert!(DnsCache::from_args(None, Some(5.0)).is_err());
+        assert!(DnsCache::from_args(Some(-1.0), None).is_err());
+        let cache = DnsCache::from_args(Some(60.0), Some(5.0)).unwrap().unwrap();
+        assert_eq!(cache.ttl, Duration::from_secs(60));
+        assert_eq!(cache.negative_ttl, Some(Duration::from_secs(5)));
+    }
+
+    #[tokio::test]
+    async fn test_cached_answer_is_reused() {
+        let cache = DnsCache::new(Duration::from_secs(60), None);
+        cache.store(
+            "example.test".to_string(),
+            Entry::Resolved(vec![addr("10.0.0.1:0")]),
+        );
+        let addrs: Vec<_> = cache
+            .resolve("Example.TEST".parse().unwrap())
+            .await
+            .unwrap()
+            .collect();
+        assert_eq!(addrs, vec![addr("10.0.0.1:0")]);
+    }
+
+    #[tokio::test]
+    async fn test_expired_and_flushed_entries_are_dropped() {
+        let cache = DnsCache::new(Duration::ZERO, None);
+        cache.store(
+            "a.test".to_string(),
+            Entry::Resolved(vec![addr("10.0.0.1:0")]),
+        );
+        assert!(cache.lookup("a.test").is_none());
+
+        let cache = DnsCache::new(Duration::from_secs(60), None);
+        cache.store(
+            "a.test".to_string(),
+            Entry::Resolved(vec![addr("10.0.0.1:0")]),
+        );
+        cache.flush();
+        assert!(cache.lookup("a.test").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_negative_caching() {
+        let failure = DnsFailure {
+            host: "bad.test".to_string(),
+            kind: DnsErrorKind::NotFound,
+            message: "no such host".to_string(),
+        };
+        let cache = DnsCache::new(Duration::from_secs(60), None);
+        cache.store("bad.test".to_string(), Entry::Failed(failure.clone()));
+        assert!(cache.lookup("bad.test").is_none());
+
+        let cache = DnsCache::new(Duration::from_secs(60), Some(Duration::from_secs(5)));
+        cache.store("bad.test".to_string(), Entry::Failed(failure.clone()));
+        assert_eq!(cache.lookup("bad.test"), Some(Err(failure)));
+    }
+
+    #[test]
+    fn test_error_kind() {
+        let kind = DnsErrorKind::classify;
+        assert_eq!(
+            kind("failed to lookup address information: Name or service not known"),
+            DnsErrorKind::NotFound
+        );
+        assert_eq!(
+            kind("failed to lookup address information: nodename nor servname provided, or not known"),
+            DnsErrorKind::NotFound
+        );
+        assert_eq!(
+            kind("failed to lookup address information: Temporary failure in name resolution"),
+            DnsErrorKind::Temporary
+        );
+        assert_eq!(kind("no addresses found"), DnsErrorKind::Other);
+    }
+}
@@ -0,0 +1,273 @@
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use reqwest::{
+    header::{HeaderMap, CONTENT_RANGE, ETAG},
+    Method, StatusCode,
+};
+use ring::digest;
+use tokio::{
+    fs::{self, File, OpenOptions},
+    io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt},
+    task::JoinSet,
+};
+
+use crate::request::RequestSpec;
+
+/// Ranges smaller than this are not worth a separate connection.
+const MIN_PART_SIZE: u64 = 1024 * 1024;
+
+/// Parse `bytes <start>-<end>/<total>` into its parts; `total` is `None` for `*`.
+fn parse_content_range(value: &str) -> Option<(u64, u64, Option<u64>)> {
+    let range = value.trim().strip_prefix("bytes ")?;
+    let (span, total) = range.split_once('/')?;
+    let (start, end) = span.split_once('-')?;
+    let total = match total {
+        "*" => None,
+        total => Some(total.parse().ok()?),
+    };
+    Some((start.parse().ok()?, end.parse().ok()?, total))
+}
+
+/// Split `total` bytes into at most `connections` inclusive `(start, end)` ranges.
+fn split_ranges(total: u64, connections: usize) -> Vec<(u64, u64)> {
+    let parts = (connections as u64)
+        .min(total.div_ceil(MIN_PART_SIZE))
+        .max(1);
+    let part_size = total.div_ceil(parts);
+    (0..parts)
+        .map(|i| i * part_size)
+        .take_while(|&start| start < total)
+        .map(|start| (start, (start + part_size).min(total) - 1))
+        .collect()
+}
+
+/// Clone `spec` as a GET for `bytes=<start>-<end>` without transfer compression.
+fn range_spec(spec: &RequestSpec, start: u64, end: u64) -> RequestSpec {
+    let mut spec = spec.clone();
+    spec.method = Method::GET;
+    let headers = spec.headers.get_or_insert_with(Default::default);
+    headers.insert("Range".to_string(), format!("bytes={}-{}", start, end));
+    headers.insert("Accept-Encoding".to_string(), "identity".to_string());
+    spec
+}
+
+/// Stream the body of `resp` into `file` at its current position, returning the byte count.
+pub async fn write_body(mut resp: reqwest::Response, file: &mut File) -> Result<u64> {
+    let mut written = 0;
+    while let Some(chunk) = resp.chunk().await? {
+        file.write_all(&chunk).await?;
+        written += chunk.len() as u64;
+    }
+    file.flush().await?;
+    Ok(written)
+}
+
+/// Hex SHA-256 digest of the file at `path`, read in chunks.
+async fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path).await?;
+    let mut context = digest::Context::new(&digest::SHA256);
+    let mut buf = vec![0; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        context.update(&buf[..n]);
+    }
+    Ok(hex::encode(context.finish()))
+}
+
+/// Download one range into `part_path` at offset `start`, verifying what the server sent.
+async fn download_range(
+    spec: RequestSpec,
+    client: reqwest::Client,
+    client_headers: HeaderMap,
+    part_path: PathBuf,
+    (start, end): (u64, u64),
+    etag: Option<String>,
+) -> Result<()> {
+    let resp = range_spec(&spec, start, end)
+        .send(client, client_headers)
+        .await?
+        .error_for_status()?;
+    if resp.status() != StatusCode::PARTIAL_CONTENT {
+        bail!(
+            "Range bytes={}-{} answered with status {} instead of 206",
+            start,
+            end,
+            resp.status().as_u16()
+        );
+    }
+    let content_range = resp
+        .headers()
+        .get(CONTENT_RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_content_range);
+    if !matches!(content_range, Some((s, e, _)) if s == start && e == end) {
+        bail!(
+            "Range bytes={}-{} answered with a different Content-Range",
+            start,
+            end
+        );
+    }
+    let part_etag = resp
+        .headers()
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok());
+    if let (Some(expected), Some(actual)) = (etag.as_deref(), part_etag) {
+        if expected != actual {
+            bail!(
+                "Resource changed during download (ETag {} -> {})",
+                expected,
+                actual
+            );
+        }
+    }
+
+    let mut file = OpenOptions::new().write(true).open(&part_path).await?;
+    file.seek(SeekFrom::Start(start)).await?;
+    let written = write_body(resp, &mut file).await?;
+    if written != end - start + 1 {
+        bail!(
+            "Range bytes={}-{} returned {} bytes, expected {}",
+            start,
+            end,
+            written,
+            end - start + 1
+        );
+    }
+    Ok(())
+}
+
+/// Download `spec.url` to `path`, using up to `connections` concurrent range requests.
+///
+/// The resource is probed with a `bytes=0-0` range request. If the server does not honour
+/// ranges, or does not report the total size, the probe response body is streamed to disk
+/// over a single connection instead. Data is written to `<path>.part`, which is renamed to
+/// `path` only after every range (and the optional SHA-256 digest) has been verified.
+pub async fn download_parallel(
+    spec: RequestSpec,
+    client: reqwest::Client,
+    client_headers: HeaderMap,
+    path: &Path,
+    connections: usize,
+    sha256: Option<String>,
+) -> Result<u64> {
+    let mut part_name = path.as_os_str().to_owned();
+    part_name.push(".part");
+    let part_path = PathBuf::from(part_name);
+
+    let result: Result<u64> = async {
+        let probe = range_spec(&spec, 0, 0)
+            .send(client.clone(), client_headers.clone())
+            .await?
+            .error_for_status()?;
+        let total = match probe.status() {
+            StatusCode::PARTIAL_CONTENT => probe
+                .headers()
+                .get(CONTENT_RANGE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_content_range)
+                .and_then(|(_, _, total)| total),
+            _ => None,
+        };
+
+        let total = match total {
+            Some(total) if total > 1 && connections > 1 => {
+                let etag = probe
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|value| value.to_str().ok())
+                    .map(str::to_string);
+                drop(probe);
+                File::create(&part_path).await?.set_len(total).await?;
+
+                let mut tasks = JoinSet::new();
+                for range in split_ranges(total, connections) {
+                    tasks.spawn(download_range(
+                        spec.clone(),
+                        client.clone(),
+                        client_headers.clone(),
+                        part_path.clone(),
+                        range,
+                        etag.clone(),
+                    ));
+                }
+                while let Some(joined) = tasks.join_next().await {
+                    joined??;
+                }
+                total
+            }
+            _ => {
+                // No usable range support: fetch the whole body over one connection.
+                let resp = match probe.status() {
+                    StatusCode::PARTIAL_CONTENT => spec
+                        .clone()
+                        .send(client, client_headers)
+                        .await?
+                        .error_for_status()?,
+                    _ => probe,
+                };
+                let mut file = File::create(&part_path).await?;
+                write_body(resp, &mut file).await?
+            }
+        };
+
+        let written = fs::metadata(&part_path).await?.len();
+        if written != total {
+            bail!("Downloaded {} bytes, expected {}", written, total);
+        }
+        if let Some(expected) = sha256 {
+            let actual = sha256_file(&part_path).await?;
+            if !actual.eq_ignore_ascii_case(&expected) {
+                bail!("SHA-256 mismatch: expected {}, got {}", expected, actual);
+            }
+        }
+        fs::rename(&part_path, path)
+            .await
+            .with_context(|| format!("Failed to move download to {}", path.display()))?;
+        Ok(total)
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = fs::remove_file(&part_path).await;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_content_range() {
+        assert_eq!(
+            parse_content_range("bytes 0-0/1234"),
+            Some((0, 0, Some(1234)))
+        );
+        assert_eq!(parse_content_range("bytes 10-19/*"), Some((10, 19, None)));
+        assert_eq!(parse_content_range("items 0-0/1"), None);
+        assert_eq!(parse_content_range("bytes 0-x/1"), None);
+    }
+
+    #[test]
+    fn test_split_ranges_covers_everything() {
+        let total = 10 * MIN_PART_SIZE + 3;
+        let ranges = split_ranges(total, 4);
+        assert_eq!(ranges.len(), 4);
+        assert_eq!(ranges[0].0, 0);
+        assert_eq!(ranges[3].1, total - 1);
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].1 + 1, pair[1].0);
+        }
+    }
+
+    #[test]
+    fn test_split_ranges_small_files_use_one_part() {
+        assert_eq!(split_ranges(100, 8), vec![(0, 99)]);
+        assert_eq!(split_ranges(MIN_PART_SIZE + 1, 8).len(), 2);
+    }
+}
@@ -0,0 +1,130 @@
+use std::env;
+
+use anyhow::{bail, Context, Result};
+use foldhash::fast::RandomState;
+use indexmap::IndexMap;
+
+type IndexMapSSR = IndexMap<String, String, RandomState>;
+
+/// Client defaults read from `HTTPR_*` environment variables at client construction,
+/// unless `trust_env=False`. Each applies only where the argument was left out:
+///
+/// * `HTTPR_TIMEOUT` - `timeout` in seconds.
+/// * `HTTPR_VERIFY` - `verify`: `1`, `true`, `yes`, `on` or `0`, `false`, `no`, `off`.
+/// * `HTTPR_PROXY` - `proxy`, for every request.
+/// * `HTTPR_HTTP_PROXY`, `HTTPR_HTTPS_PROXY` - Proxies for `http://` and `https://` URLs,
+///   used when no `proxy` is set.
+/// * `HTTPR_DEFAULT_HEADERS` - A JSON object of headers, which `headers` override.
+#[derive(Debug, Default, PartialEq)]
+pub struct EnvConfig {
+    pub timeout: Option<f64>,
+    pub verify: Option<bool>,
+    pub proxy: Option<String>,
+    pub http_proxy: Option<String>,
+    pub https_proxy: Option<String>,
+    pub headers: Option<IndexMapSSR>,
+}
+
+impl EnvConfig {
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| env::var(name).ok())
+    }
+
+    /// Parse the variables returned by `var`; empty ones count as unset.
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let var = |name: &str| {
+            var(name)
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let timeout = var("HTTPR_TIMEOUT")
+            .map(|value| {
+                value.parse::<f64>().with_context(|| {
+                    format!("HTTPR_TIMEOUT must be a number of seconds, got {:?}", value)
+                })
+            })
+            .transpose()?;
+        let verify = var("HTTPR_VERIFY")
+            .map(|value| match value.to_ascii_lowercase().as_str() {
+                "1" | "true" | "yes" | "on" => Ok(true),
+                "0" | "false" | "no" | "off" => Ok(false),
+                _ => bail!("HTTPR_VERIFY must be true or false, got {:?}", value),
+            })
+            .transpose()?;
+        let headers = var("HTTPR_DEFAULT_HEADERS")
+            .map(|value| {
+                serde_json::from_str::<IndexMapSSR>(&value)
+                    .context("HTTPR_DEFAULT_HEADERS must be a JSON object of strings")
+            })
+            .transpose()?;
+        Ok(EnvConfig {
+            timeout,
+            verify,
+            proxy: var("HTTPR_PROXY"),
+            http_proxy: var("HTTPR_HTTP_PROXY"),
+            https_proxy: var("HTTPR_HTTPS_PROXY"),
+            headers,
+        })
+    }
+
+    /// `headers` on top of `HTTPR_DEFAULT_HEADERS`, names compared case-insensitively.
+    pub fn headers(&self, headers: Option<IndexMapSSR>) -> Option<IndexMapSSR> {
+        let Some(defaults) = &self.headers else {
+            return headers;
+        };
+        let mut merged = defaults.clone();
+        for (name, value) in headers.unwrap_or_default() {
+            merged.retain(|default, _| !default.eq_ignore_ascii_case(&name));
+            merged.insert(name, value);
+        }
+        Some(merged)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(vars: &[(&str, &str)]) -> Result<EnvConfig> {
+        EnvConfig::from_vars(|name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn test_from_vars() {
+        assert_eq!(config(&[]).unwrap(), EnvConfig::default());
+        let env = config(&[
+            ("HTTPR_TIMEOUT", "12.5"),
+            ("HTTPR_VERIFY", "False"),
+            ("HTTPR_PROXY", " "),
+            ("HTTPR_HTTPS_PROXY", "http://proxy:3128"),
+            (
+                "HTTPR_DEFAULT_HEADERS",
+                r#"{"X-Team": "search", "User-Agent": "env"}"#,
+            ),
+        ])
+        .unwrap();
+        assert_eq!(env.timeout, Some(12.5));
+        assert_eq!(env.verify, Some(false));
+        assert_eq!(env.proxy, None);
+        assert_eq!(env.https_proxy.as_deref(), Some("http://proxy:3128"));
+
+        let mut headers = IndexMap::with_hasher(RandomState::default());
+        headers.insert("user-agent".to_string(), "app".to_string());
+        let merged: Vec<_> = env.headers(Some(headers)).unwrap().into_iter().collect();
+        assert_eq!(
+            merged,
+            vec![
+                ("X-Team".to_string(), "search".to_string()),
+                ("user-agent".to_string(), "app".to_string())
+            ]
+        );
+
+        assert!(config(&[("HTTPR_TIMEOUT", "soon")]).is_err());
+        assert!(config(&[("HTTPR_VERIFY", "maybe")]).is_err());
+        assert!(config(&[("HTTPR_DEFAULT_HEADERS", "[1]")]).is_err());
+    }
+}
@@ -0,0 +1,230 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use foldhash::fast::RandomState;
+use http::Extensions;
+use hyper_util::client::legacy::connect::{Connected, Connection, HttpInfo};
+use indexmap::IndexMap;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use tower_layer::Layer;
+use tower_service::Service;
+use url::Url;
+
+use crate::dns;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Opened connections remembered to tell new connections from reused ones. reqwest does
+/// not report when a pooled connection closes, so the oldest are forgotten past this.
+const MAX_TRACKED_CONNECTIONS: usize = 4096;
+
+/// A connection opened by the connector and not yet seen on a response.
+struct Opened {
+    elapsed: Duration,
+    http2: bool,
+    proxied: bool,
+    used: bool,
+}
+
+/// Python callable given as `on_connection_event=`, called with a dict for each DNS
+/// lookup (`dns_resolved`) and for each response, telling whether its connection was
+/// opened for it (`connection_created`) or taken from the pool (`connection_reused`).
+///
+/// Clones share the table of opened connections, keyed by local address.
+#[derive(Clone)]
+pub struct ConnectionEvents {
+    callback: Arc<Py<PyAny>>,
+    opened: Arc<Mutex<IndexMap<SocketAddr, Opened, RandomState>>>,
+}
+
+impl ConnectionEvents {
+    pub fn new(callable: Py<PyAny>) -> PyResult<Self> {
+        Python::attach(|py| {
+            if !callable.bind(py).is_callable() {
+                return Err(PyValueError::new_err(
+                    "on_connection_event must be callable",
+                ));
+            }
+            Ok(ConnectionEvents {
+                callback: Arc::new(callable),
+                opened: Arc::default(),
+            })
+        })
+    }
+
+    /// Call the callback with an event dict. Exceptions it raises are reported as
+    /// unraisable instead of failing the request.
+    fn emit(&self, event: &str, fill: impl FnOnce(&Bound<'_, PyDict>) -> PyResult<()>) {
+        Python::attach(|py| {
+            let dict = PyDict::new(py);
+            let result = dict
+                .set_item("event", event)
+                .and_then(|_| fill(&dict))
+                .and_then(|_| self.callback.bind(py).call1((dict,)));
+            if let Err(err) = result {
+                err.write_unraisable(py, Some(self.callback.bind(py)));
+            }
+        });
+    }
+
+    /// Connector layer recording every connection it opens, with the time taken to
+    /// resolve, connect and complete the TLS handshake.
+    pub fn connector_layer(&self) -> ConnectLayer {
+        ConnectLayer(self.clone())
+    }
+
+    /// Resolver reporting each lookup, resolving through `inner` if set.
+    pub fn resolver(&self, inner: Option<Arc<dyn Resolve>>) -> EventResolver {
+        EventResolver {
+            events: self.clone(),
+            inner,
+        }
+    }
+
+    fn opened(&self, connected: &Connected, elapsed: Duration) {
+        let mut extensions = Extensions::new();
+        connected.get_extras(&mut extensions);
+        let Some(info) = extensions.get::<HttpInfo>() else {
+            return;
+        };
+        let mut opened = self.opened.lock().unwrap_or_else(|e| e.into_inner());
+        // A reused local address belongs to a new connection
+        opened.shift_remove(&info.local_addr());
+        if opened.len() >= MAX_TRACKED_CONNECTIONS {
+            opened.shift_remove_index(0);
+        }
+        opened.insert(
+            info.local_addr(),
+            Opened {
+                elapsed,
+                http2: connected.is_negotiated_h2(),
+                proxied: connected.is_proxied(),
+                used: false,
+            },
+        );
+    }
+
+    /// Report whether the connection that carried `resp`, a response for `url`, was
+    /// opened for it or reused.
+    pub fn observe(&self, url: &Url, resp: &reqwest::Response) {
+        let Some(info) = resp.extensions().get::<HttpInfo>() else {
+            return;
+        };
+        let local_addr = info.local_addr();
+        let first_use = {
+            let mut opened = self.opened.lock().unwrap_or_else(|e| e.into_inner());
+            match opened.get_mut(&local_addr) {
+                Some(entry) if !entry.used => {
+                    entry.used = true;
+                    Some((entry.elapsed, entry.http2, entry.proxied))
+                }
+                _ => None,
+            }
+        };
+        let url = url.as_str();
+        let remote_addr = info.remote_addr().to_string();
+        let local = local_addr.to_string();
+        match first_use {
+            Some((elapsed, http2, proxied)) => self.emit("connection_created", |dict| {
+                dict.set_item("url", url)?;
+                dict.set_item("remote_addr", &remote_addr)?;
+                dict.set_item("local_addr", &local)?;
+                dict.set_item("elapsed", elapsed.as_secs_f64())?;
+                dict.set_item("tls", url.starts_with("https:") && !proxied)?;
+                dict.set_item("http2", http2)
+            }),
+            None => self.emit("connection_reused", |dict| {
+                dict.set_item("url", url)?;
+                dict.set_item("remote_addr", &remote_addr)?;
+                dict.set_item("local_addr", &local)
+            }),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ConnectLayer(ConnectionEvents);
+
+impl<S> Layer<S> for ConnectLayer {
+    type Service = ConnectService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ConnectService {
+            inner,
+            events: self.0.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ConnectService<S> {
+    inner: S,
+    events: ConnectionEvents,
+}
+
+impl<S, R> Service<R> for ConnectService<S>
+where
+    S: Service<R>,
+    S::Response: Connection + Send + 'static,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<S::Response, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: R) -> Self::Future {
+        let started = Instant::now();
+        let connecting = self.inner.call(request);
+        let events = self.events.clone();
+        Box::pin(async move {
+            let conn = connecting.await?;
+            events.opened(&conn.connected(), started.elapsed());
+            Ok(conn)
+        })
+    }
+}
+
+pub struct EventResolver {
+    events: ConnectionEvents,
+    inner: Option<Arc<dyn Resolve>>,
+}
+
+impl Resolve for EventResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let events = self.events.clone();
+        let inner = self.inner.clone();
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let started = Instant::now();
+            let result: Result<Vec<SocketAddr>, BoxError> = match inner {
+                Some(inner) => inner.resolve(name).await.map(Iterator::collect),
+                None => dns::lookup(&host).await.map_err(BoxError::from),
+            };
+            let elapsed = started.elapsed().as_secs_f64();
+            events.emit("dns_resolved", |dict| {
+                dict.set_item("host", &host)?;
+                let addresses: Vec<String> = result
+                    .iter()
+                    .flatten()
+                    .map(|addr| addr.ip().to_string())
+                    .collect();
+                dict.set_item("addresses", addresses)?;
+                dict.set_item("elapsed", elapsed)?;
+                dict.set_item("error", result.as_ref().err().map(ToString::to_string))
+            });
+            result.map(|addrs| Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
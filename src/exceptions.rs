@@ -2,6 +2,14 @@ use pyo3::create_exception;
 use pyo3::exceptions::PyException;
 use pyo3::prelude::*;
 
+use crate::cancel::Cancelled;
+use crate::decompress::DecodeError;
+use crate::dns::DnsFailure;
+use crate::hosts::Refused;
+use crate::redirect::RedirectLimit;
+use crate::tls::TlsFailure;
+use url::Url;
+
 // Base exception - HTTPError
 create_exception!(
     httpr,
@@ -91,6 +99,19 @@ create_exception!(
     NetworkError,
     "Failed to establish a connection."
 );
+create_exception!(
+    httpr,
+    DNSError,
+    ConnectError,
+    "The host name could not be resolved."
+);
+create_exception!(httpr, SSLError, ConnectError, "The TLS handshake failed.");
+create_exception!(
+    httpr,
+    CertificateVerifyError,
+    SSLError,
+    "The server certificate could not be verified."
+);
 create_exception!(
     httpr,
     ReadError,
@@ -144,11 +165,29 @@ create_exception!(
     HTTPError,
     "The response had an error HTTP status of 4xx or 5xx."
 );
+create_exception!(
+    httpr,
+    RobotsDisallowed,
+    RequestError,
+    "The robots.txt rules of the host disallow the request (`respect_robots_txt=True`)."
+);
+create_exception!(
+    httpr,
+    HostBlocked,
+    RequestError,
+    "The host of the request or a redirect target is not allowed (`allow_hosts`, `block_hosts`, `block_private_ips`)."
+);
+create_exception!(
+    httpr,
+    RequestCancelled,
+    RequestError,
+    "The request was aborted through its `CancellationToken`."
+);
 create_exception!(
     httpr,
     DecodingError,
     RequestError,
-    "Decoding of the response failed, due to a malformed encoding."
+    "Decoding of the response failed, due to a malformed encoding or a decompression limit (`max_decompressed_size`, `max_decompression_ratio`)."
 );
 
 // Stream exceptions
@@ -191,54 +230,174 @@ create_exception!(
     "Attempted to lookup a cookie by name, but multiple cookies existed."
 );
 
-/// Helper function to convert reqwest errors to appropriate httpr exceptions
-pub fn map_reqwest_error(err: reqwest::Error) -> PyErr {
-    // Check timeout first
-    if err.is_timeout() {
-        // Try to determine if it's connect, read, or write timeout
-        let err_str = err.to_string().to_lowercase();
-        if err_str.contains("connect") {
-            return ConnectTimeout::new_err(err.to_string());
-        } else if err_str.contains("read") || err_str.contains("recv") {
-            return ReadTimeout::new_err(err.to_string());
-        } else if err_str.contains("write") || err_str.contains("send") {
-            return WriteTimeout::new_err(err.to_string());
-        }
-        // Default to read timeout for generic timeouts
-        return ReadTimeout::new_err(err.to_string());
+/// Error message including its sources, e.g. "error decoding response body: <cause>".
+fn message_with_sources(err: &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        message.push_str(": ");
+        message.push_str(&err.to_string());
+        source = err.source();
     }
+    message
+}
 
-    // Check for connection errors
-    if err.is_connect() {
-        return ConnectError::new_err(err.to_string());
-    }
+fn map_dns_failure(failure: &DnsFailure) -> PyErr {
+    Python::attach(|py| {
+        let err = DNSError::new_err(failure.to_string());
+        let value = err.value(py);
+        let attributes = value
+            .setattr("host", &failure.host)
+            .and_then(|_| value.setattr("kind", failure.kind.as_str()));
+        attributes.err().unwrap_or(err)
+    })
+}
 
-    // Check for redirect errors
-    if err.is_redirect() {
-        return TooManyRedirects::new_err(err.to_string());
-    }
+fn map_tls_failure(failure: &TlsFailure) -> PyErr {
+    Python::attach(|py| {
+        let err = if failure.certificate {
+            CertificateVerifyError::new_err(failure.message.clone())
+        } else {
+            SSLError::new_err(failure.message.clone())
+        };
+        let attributes = err.value(py).setattr("reason", failure.reason);
+        attributes.err().unwrap_or(err)
+    })
+}
 
-    // Check for decode errors
-    if err.is_decode() {
-        return DecodingError::new_err(err.to_string());
+fn map_refused(refused: &Refused) -> PyErr {
+    match refused {
+        Refused::Host(message) => HostBlocked::new_err(message.clone()),
+        Refused::Scheme(message) => UnsupportedProtocol::new_err(message.clone()),
     }
+}
 
-    // Check for request errors (builder errors, body errors)
-    if err.is_request() || err.is_body() {
-        return RequestError::new_err(err.to_string());
+/// The socket error behind a failed connect, read or write: the first `io::Error` in the
+/// source chain, unwrapping the ones hyper and the connectors nest inside each other.
+struct SocketFailure {
+    errno: Option<i32>,
+    /// The OS message for `errno`, e.g. "Connection refused", or the error message.
+    os_error: String,
+    /// Failed while sending the request rather than receiving the response.
+    writing: bool,
+}
+
+impl SocketFailure {
+    fn from_error(err: &(dyn std::error::Error + 'static)) -> Option<Self> {
+        let mut writing = false;
+        let mut source = Some(err);
+        while let Some(mut err) = source {
+            // hyper reports failed body writes as "error writing a body to connection"
+            if err.is::<hyper::Error>() && err.to_string().starts_with("error writing") {
+                writing = true;
+            }
+            while let Some(inner) = err
+                .downcast_ref::<std::io::Error>()
+                .and_then(std::io::Error::get_ref)
+            {
+                err = inner;
+            }
+            if let Some(io_error) = err.downcast_ref::<std::io::Error>() {
+                let errno = io_error.raw_os_error();
+                let os_error = match errno {
+                    // Display appends " (os error N)" to the OS message
+                    Some(code) => {
+                        let message = io_error.to_string();
+                        let suffix = format!(" (os error {})", code);
+                        message
+                            .strip_suffix(&suffix)
+                            .unwrap_or(&message)
+                            .to_string()
+                    }
+                    None => io_error.to_string(),
+                };
+                return Some(SocketFailure {
+                    errno,
+                    os_error,
+                    writing: writing || io_error.kind() == std::io::ErrorKind::BrokenPipe,
+                });
+            }
+            source = err.source();
+        }
+        None
     }
+}
 
-    // Check for status errors (4xx, 5xx)
-    if err.is_status() {
-        return HTTPStatusError::new_err(err.to_string());
+/// Set `errno`, `os_error` and `remote_address` on a `NetworkError`. The address is the
+/// `(host, port)` the request was sent to, as the connector does not report the resolved
+/// IP of a failed connection.
+fn with_socket_details(err: PyErr, failure: Option<&SocketFailure>, url: Option<&Url>) -> PyErr {
+    Python::attach(|py| {
+        let value = err.value(py);
+        let remote_address = url.and_then(|url| {
+            let host = url
+                .host_str()?
+                .trim_start_matches('[')
+                .trim_end_matches(']');
+            Some((host.to_string(), url.port_or_known_default()?))
+        });
+        let attributes = value
+            .setattr("errno", failure.and_then(|failure| failure.errno))
+            .and_then(|_| value.setattr("os_error", failure.map(|failure| &failure.os_error)))
+            .and_then(|_| value.setattr("remote_address", remote_address));
+        attributes.err().unwrap_or(err)
+    })
+}
+
+/// The exception for a host policy refusal, a failed lookup or TLS handshake, a body
+/// decoding failure, a cancellation, exceeding `max_redirects` or a Python exception
+/// anywhere in the source chain of `err`, which is where reqwest puts errors from the
+/// resolver, the connector and wrapped response bodies.
+fn own_error(err: &(dyn std::error::Error + 'static)) -> Option<PyErr> {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        // Raised by Python code feeding a body, e.g. the chunks of a `PythonTransport` response
+        if let Some(py_err) = err.downcast_ref::<PyErr>() {
+            return Some(Python::attach(|py| py_err.clone_ref(py)));
+        }
+        if let Some(refused) = err.downcast_ref::<Refused>() {
+            return Some(map_refused(refused));
+        }
+        if let Some(failure) = err.downcast_ref::<DnsFailure>() {
+            return Some(map_dns_failure(failure));
+        }
+        if let Some(py_err) = TlsFailure::hook_error(err) {
+            return Some(Python::attach(|py| py_err.clone_ref(py)));
+        }
+        if let Some(failure) = TlsFailure::from_error(err) {
+            return Some(map_tls_failure(&failure));
+        }
+        if let Some(decode_error) = err.downcast_ref::<DecodeError>() {
+            return Some(DecodingError::new_err(decode_error.to_string()));
+        }
+        if err.is::<Cancelled>() {
+            return Some(RequestCancelled::new_err(err.to_string()));
+        }
+        if err.is::<RedirectLimit>() {
+            return Some(TooManyRedirects::new_err(err.to_string()));
+        }
+        source = err.source();
     }
+    None
+}
 
-    // Default to generic RequestError for unknown errors
-    RequestError::new_err(err.to_string())
+/// Helper function to convert reqwest errors to appropriate httpr exceptions
+pub fn map_reqwest_error(err: reqwest::Error) -> PyErr {
+    map_reqwest_error_ref(&err)
 }
 
 /// Helper function to convert anyhow errors to appropriate httpr exceptions
 pub fn map_anyhow_error(err: anyhow::Error) -> PyErr {
+    // Python exceptions raised by callbacks (e.g. the `sign` hook) are re-raised as is
+    let err = match err.downcast::<PyErr>() {
+        Ok(py_err) => return py_err,
+        Err(err) => err,
+    };
+
+    if let Some(py_err) = own_error(err.as_ref()) {
+        return py_err;
+    }
+
     // First, try to downcast to reqwest::Error if possible
     if let Some(reqwest_err) = err.downcast_ref::<reqwest::Error>() {
         return map_reqwest_error_ref(reqwest_err);
@@ -295,6 +454,10 @@ pub fn map_anyhow_error(err: anyhow::Error) -> PyErr {
 
 /// Helper function to convert reqwest error references to appropriate httpr exceptions
 fn map_reqwest_error_ref(err: &reqwest::Error) -> PyErr {
+    if let Some(py_err) = own_error(err) {
+        return py_err;
+    }
+
     // Check timeout first
     if err.is_timeout() {
         // Try to determine if it's connect, read, or write timeout
@@ -306,13 +469,26 @@ fn map_reqwest_error_ref(err: &reqwest::Error) -> PyErr {
         } else if err_str.contains("write") || err_str.contains("send") {
             return WriteTimeout::new_err(err.to_string());
         }
-        // Default to read timeout for generic timeouts
-        return ReadTimeout::new_err(err.to_string());
+        // Default to read timeout for generic timeouts (e.g. a stalled body transfer)
+        return ReadTimeout::new_err(message_with_sources(&err));
     }
 
     // Check for connection errors
+    let failure = SocketFailure::from_error(err);
     if err.is_connect() {
-        return ConnectError::new_err(err.to_string());
+        let py_err = ConnectError::new_err(err.to_string());
+        return with_socket_details(py_err, failure.as_ref(), err.url());
+    }
+
+    // Connection reset, closed or broken after it was established
+    if let Some(failure) = failure {
+        let message = message_with_sources(err);
+        let py_err = if failure.writing {
+            WriteError::new_err(message)
+        } else {
+            ReadError::new_err(message)
+        };
+        return with_socket_details(py_err, Some(&failure), err.url());
     }
 
     // Check for redirect errors
@@ -358,10 +534,22 @@ pub fn register_exceptions(m: &Bound<'_, PyModule>) -> PyResult<()> {
 
     // Network exceptions
     m.add("ConnectError", m.py().get_type::<ConnectError>())?;
+    m.add("DNSError", m.py().get_type::<DNSError>())?;
+    m.add("SSLError", m.py().get_type::<SSLError>())?;
+    m.add(
+        "CertificateVerifyError",
+        m.py().get_type::<CertificateVerifyError>(),
+    )?;
     m.add("ReadError", m.py().get_type::<ReadError>())?;
     m.add("WriteError", m.py().get_type::<WriteError>())?;
     m.add("CloseError", m.py().get_type::<CloseError>())?;
 
+    // Socket details, set on the instances that have them
+    let network_error = m.py().get_type::<NetworkError>();
+    for name in ["errno", "os_error", "remote_address"] {
+        network_error.setattr(name, m.py().None())?;
+    }
+
     // Protocol exceptions
     m.add(
         "LocalProtocolError",
@@ -380,6 +568,9 @@ pub fn register_exceptions(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add("ProxyError", m.py().get_type::<ProxyError>())?;
     m.add("TooManyRedirects", m.py().get_type::<TooManyRedirects>())?;
     m.add("HTTPStatusError", m.py().get_type::<HTTPStatusError>())?;
+    m.add("RobotsDisallowed", m.py().get_type::<RobotsDisallowed>())?;
+    m.add("HostBlocked", m.py().get_type::<HostBlocked>())?;
+    m.add("RequestCancelled", m.py().get_type::<RequestCancelled>())?;
     m.add("DecodingError", m.py().get_type::<DecodingError>())?;
 
     // Stream exceptions
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use futures_util::{stream, StreamExt};
+use http_body_util::BodyExt;
+use pyo3::prelude::*;
+use reqwest::header::HeaderMap;
+use reqwest::Body;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinSet;
+
+use crate::exceptions::map_anyhow_error;
+use crate::request::{map_response_body, RequestSpec, ResponseParts};
+use crate::response::{Response, StreamingResponse};
+use crate::{block_on, RUNTIME};
+
+/// A finished request of `client.fetch_all()`.
+enum Fetched {
+    /// Response with its body read.
+    Read(ResponseParts),
+    /// Response whose body is still to be streamed (`stream=True`).
+    Stream(reqwest::Response),
+}
+
+/// Iterator over the responses of `client.fetch_all()`.
+///
+/// All requests are spawned up front and bounded by a semaphore. Tasks make progress
+/// while the iterator waits for the next result, so at most `max_concurrency` requests
+/// are in flight at once. With `stream=True` a request keeps its permit until its body
+/// has been read to the end or the `StreamingResponse` is closed, which bounds the open
+/// streams as well. Dropping the iterator cancels the requests not yet yielded.
+#[pyclass(module = "httpr")]
+pub struct FetchIterator {
+    tasks: JoinSet<(usize, Result<Fetched>)>,
+    ordered: bool,
+    /// Results that completed ahead of their turn (ordered mode only).
+    pending: HashMap<usize, Result<Fetched>>,
+    next_index: usize,
+}
+
+impl FetchIterator {
+    pub fn spawn(
+        specs: Vec<RequestSpec>,
+        client: reqwest::Client,
+        client_headers: HeaderMap,
+        max_concurrency: usize,
+        ordered: bool,
+        stream: bool,
+    ) -> Self {
+        let semaphore = Arc::new(Semaphore::new(max_concurrency));
+        let mut tasks = JoinSet::new();
+        for (index, spec) in specs.into_iter().enumerate() {
+            let client = client.clone();
+            let client_headers = client_headers.clone();
+            let semaphore = Arc::clone(&semaphore);
+            tasks.spawn_on(
+                async move {
+                    let result = async {
+                        let permit = semaphore.acquire_owned().await?;
+                        let resp = spec.send(client, client_headers).await?;
+                        if stream {
+                            let resp = map_response_body(resp, |body| hold_permit(body, permit));
+                            return Ok(Fetched::Stream(resp));
+                        }
+                        ResponseParts::read(resp).await.map(Fetched::Read)
+                    }
+                    .await;
+                    (index, result)
+                },
+                RUNTIME.handle(),
+            );
+        }
+        FetchIterator {
+            tasks,
+            ordered,
+            pending: HashMap::new(),
+            next_index: 0,
+        }
+    }
+
+    /// Wait for the next result to yield, or `None` once every request has been yielded.
+    fn next_result(&mut self, py: Python) -> PyResult<Option<Result<Fetched>>> {
+        loop {
+            if self.ordered {
+                if let Some(result) = self.pending.remove(&self.next_index) {
+                    self.next_index += 1;
+                    return Ok(Some(result));
+                }
+            }
+            let tasks = &mut self.tasks;
+            let joined = block_on(py, || tasks.join_next())?;
+            let Some(joined) = joined else {
+                return Ok(None);
+            };
+            let (index, result) = joined.map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?;
+            if !self.ordered {
+                return Ok(Some(result));
+            }
+            self.pending.insert(index, result);
+        }
+    }
+}
+
+#[pymethods]
+impl FetchIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// The next `Response` (`StreamingResponse` with `stream=True`), or the exception of
+    /// a failed request.
+    fn __next__(&mut self, py: Python) -> PyResult<Option<Py<PyAny>>> {
+        Ok(match self.next_result(py)? {
+            Some(Ok(Fetched::Read(parts))) => Some(
+                Response::from_parts(py, parts)
+                    .into_pyobject(py)?
+                    .into_any()
+                    .unbind(),
+            ),
+            Some(Ok(Fetched::Stream(resp))) => Some(
+                StreamingResponse::from_response(resp)
+                    .into_pyobject(py)?
+                    .into_any()
+                    .unbind(),
+            ),
+            Some(Err(e)) => Some(map_anyhow_error(e).into_value(py).into_any()),
+            None => None,
+        })
+    }
+
+    /// Number of requests not yet yielded.
+    fn __len__(&self) -> usize {
+        self.tasks.len() + self.pending.len()
+    }
+}
+
+/// Wrap `body` so that `permit` is held until the body ends, fails or is dropped.
+fn hold_permit(body: Body, permit: OwnedSemaphorePermit) -> Body {
+    let state = Some((body.into_data_stream(), permit));
+    Body::wrap_stream(stream::unfold(state, |state| async move {
+        let (mut inner, permit) = state?;
+        match inner.next().await? {
+            Ok(chunk) => Some((Ok(chunk), Some((inner, permit)))),
+            Err(e) => Some((Err(e), None)),
+        }
+    }))
+}
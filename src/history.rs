@@ -0,0 +1,150 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Result};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use reqwest::Method;
+
+/// Summary of one sent request, kept by `History`.
+#[derive(Clone, Debug)]
+pub struct Entry {
+    pub method: String,
+    /// Final URL of the response, or the requested URL if no response arrived.
+    pub url: String,
+    pub status: Option<u16>,
+    /// Seconds since the Unix epoch when the request was started.
+    pub started: f64,
+    /// Time until the response headers arrived (or the request failed).
+    pub elapsed: Duration,
+    pub error: Option<String>,
+}
+
+impl Entry {
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("method", &self.method)?;
+        dict.set_item("url", &self.url)?;
+        dict.set_item("status", self.status)?;
+        dict.set_item("started", self.started)?;
+        dict.set_item("elapsed", self.elapsed.as_secs_f64())?;
+        dict.set_item("error", &self.error)?;
+        Ok(dict)
+    }
+}
+
+/// Ring buffer of the last `capacity` requests sent by a client. Clones share the buffer.
+#[derive(Clone)]
+pub struct History {
+    capacity: usize,
+    entries: Arc<Mutex<VecDeque<Entry>>>,
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            bail!("history_size must be at least 1");
+        }
+        Ok(History {
+            capacity,
+            entries: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+        })
+    }
+
+    /// Send a request via `send` and record its outcome.
+    pub async fn record<F>(&self, method: &Method, url: &str, send: F) -> Result<reqwest::Response>
+    where
+        F: std::future::Future<Output = Result<reqwest::Response>>,
+    {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let start = Instant::now();
+        let result = send.await;
+        let (url, status, error) = match &result {
+            Ok(resp) => (resp.url().to_string(), Some(resp.status().as_u16()), None),
+            Err(e) => (url.to_string(), None, Some(format!("{:#}", e))),
+        };
+        self.push(Entry {
+            method: method.to_string(),
+            url,
+            status,
+            started,
+            elapsed: start.elapsed(),
+            error,
+        });
+        result
+    }
+
+    fn push(&self, entry: Entry) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Recorded entries, oldest first.
+    pub fn entries(&self) -> Vec<Entry> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(url: &str) -> Entry {
+        Entry {
+            method: "GET".into(),
+            url: url.into(),
+            status: Some(200),
+            started: 0.0,
+            elapsed: Duration::ZERO,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer() {
+        assert!(History::new(0).is_err());
+        let history = History::new(2).unwrap();
+        for url in ["a", "b", "c"] {
+            history.push(entry(url));
+        }
+        let urls: Vec<_> = history.entries().into_iter().map(|e| e.url).collect();
+        assert_eq!(urls, vec!["b", "c"]);
+        history.clear();
+        assert!(history.entries().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_record_error() {
+        let history = History::new(10).unwrap();
+        let result = history
+            .record(&Method::POST, "http://example.test/", async {
+                Err(anyhow::anyhow!("connection refused"))
+            })
+            .await;
+        assert!(result.is_err());
+        let [recorded] = history.entries().try_into().unwrap();
+        assert_eq!(recorded.method, "POST");
+        assert_eq!(recorded.url, "http://example.test/");
+        assert_eq!(recorded.status, None);
+        assert_eq!(recorded.error.as_deref(), Some("connection refused"));
+    }
+}
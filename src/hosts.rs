@@ -0,0 +1,371 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use url::{Host, Url};
+
+use crate::dns::{self, DnsCache};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Schemes accepted by default, and the only ones the client can send.
+const SUPPORTED_SCHEMES: [&str; 2] = ["http", "https"];
+
+/// A request refused by the client's `HostPolicy`.
+#[derive(Debug)]
+pub enum Refused {
+    /// Raised as `HostBlocked`.
+    Host(String),
+    /// Raised as `UnsupportedProtocol`.
+    Scheme(String),
+}
+
+impl fmt::Display for Refused {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refused::Host(message) | Refused::Scheme(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for Refused {}
+
+/// Where a client may send requests: URL schemes (`allowed_schemes`) and hosts
+/// (`allow_hosts`, `block_hosts`, `block_private_ips`). Host patterns are host names or
+/// IP addresses; `*.example.com` matches the subdomains of `example.com`, but not
+/// `example.com` itself.
+#[derive(Clone, Debug)]
+pub struct HostPolicy {
+    schemes: Vec<String>,
+    allow: Option<Vec<String>>,
+    block: Vec<String>,
+    block_private_ips: bool,
+}
+
+impl HostPolicy {
+    pub fn from_args(
+        allowed_schemes: Option<Vec<String>>,
+        allow_hosts: Option<Vec<String>>,
+        block_hosts: Option<Vec<String>>,
+        block_private_ips: bool,
+    ) -> Result<Self> {
+        let schemes = match allowed_schemes {
+            Some(schemes) => schemes
+                .into_iter()
+                .map(|scheme| {
+                    let scheme = scheme.trim().trim_end_matches("://").to_ascii_lowercase();
+                    if !SUPPORTED_SCHEMES.contains(&scheme.as_str()) {
+                        bail!(
+                            "allowed_schemes may only contain 'http' and 'https', got {:?}",
+                            scheme
+                        );
+                    }
+                    Ok(scheme)
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => SUPPORTED_SCHEMES.map(String::from).to_vec(),
+        };
+        if schemes.is_empty() {
+            bail!("allowed_schemes must not be empty");
+        }
+        let patterns = |hosts: Vec<String>, name: &str| {
+            hosts
+                .into_iter()
+                .map(|host| {
+                    let host = host
+                        .trim()
+                        .trim_end_matches('.')
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .to_ascii_lowercase();
+                    if host.is_empty() || host == "*" {
+                        bail!("{} entries must be host names or IP addresses", name);
+                    }
+                    Ok(host)
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        Ok(HostPolicy {
+            schemes,
+            allow: allow_hosts
+                .map(|hosts| patterns(hosts, "allow_hosts"))
+                .transpose()?,
+            block: patterns(block_hosts.unwrap_or_default(), "block_hosts")?,
+            block_private_ips,
+        })
+    }
+
+    /// Check the scheme of `url`, its host against the allow and block lists, and an IP
+    /// address host against `block_private_ips`. Host names are resolved later, see
+    /// `GuardedResolver`.
+    pub fn check_url(&self, url: &Url) -> Result<(), Refused> {
+        if !self.schemes.iter().any(|scheme| scheme == url.scheme()) {
+            return Err(Refused::Scheme(format!(
+                "Scheme {:?} is not allowed (allowed: {})",
+                url.scheme(),
+                self.schemes.join(", ")
+            )));
+        }
+        let (name, ip) = match url.host() {
+            Some(Host::Domain(domain)) => (domain.trim_end_matches('.').to_ascii_lowercase(), None),
+            Some(Host::Ipv4(ip)) => (ip.to_string(), Some(IpAddr::V4(ip))),
+            Some(Host::Ipv6(ip)) => (ip.to_string(), Some(IpAddr::V6(ip))),
+            None => return Ok(()),
+        };
+        if let Some(allow) = &self.allow {
+            if !allow.iter().any(|pattern| host_matches(pattern, &name)) {
+                return Err(Refused::Host(format!(
+                    "Host {:?} is not in allow_hosts",
+                    name
+                )));
+            }
+        }
+        if self
+            .block
+            .iter()
+            .any(|pattern| host_matches(pattern, &name))
+        {
+            return Err(Refused::Host(format!("Host {:?} is in block_hosts", name)));
+        }
+        match ip {
+            Some(ip) => self.check_ip(&name, ip),
+            None => Ok(()),
+        }
+    }
+
+    fn check_ip(&self, name: &str, ip: IpAddr) -> Result<(), Refused> {
+        if !self.block_private_ips || !is_private(ip) {
+            return Ok(());
+        }
+        if name == ip.to_string() {
+            return Err(Refused::Host(format!("Host {} is a private address", ip)));
+        }
+        Err(Refused::Host(format!(
+            "Host {:?} resolves to the private address {}",
+            name, ip
+        )))
+    }
+
+    /// Whether host names must be resolved through a `GuardedResolver`.
+    pub fn blocks_private_ips(&self) -> bool {
+        self.block_private_ips
+    }
+}
+
+/// Whether `host` matches an allow or block list entry.
+fn host_matches(pattern: &str, host: &str) -> bool {
+    match pattern.strip_prefix("*.") {
+        Some(domain) => host
+            .strip_suffix(domain)
+            .is_some_and(|prefix| prefix.ends_with('.')),
+        None => host == pattern,
+    }
+}
+
+/// Loopback, private (RFC 1918, unique local), link-local, shared (RFC 6598),
+/// multicast, reserved and unspecified addresses, i.e. anything that is not a public
+/// unicast address. IPv6 addresses embedding an IPv4 address are judged by that address.
+fn is_private(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_v4(ip),
+        IpAddr::V6(ip) => match embedded_v4(ip) {
+            Some(ip) => is_private_v4(ip),
+            None => is_private_v6(ip),
+        },
+    }
+}
+
+/// The IPv4 address of an IPv4-mapped (`::ffff:a.b.c.d`), IPv4-compatible (`::a.b.c.d`),
+/// 6to4 (`2002:aabb:ccdd::/48`) or NAT64 (`64:ff9b::a.b.c.d`) address.
+fn embedded_v4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let v4 = |high: u16, low: u16| Ipv4Addr::from((u32::from(high) << 16) | u32::from(low));
+    match ip.segments() {
+        [0x2002, high, low, ..] => Some(v4(high, low)),
+        [0x64, 0xff9b, 0, 0, 0, 0, high, low] => Some(v4(high, low)),
+        // Also `::` and `::1`, as 0.0.0.0/8
+        _ => ip.to_ipv4(),
+    }
+}
+
+fn is_private_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // 0.0.0.0/8 "this network", 100.64.0.0/10 carrier-grade NAT, 192.0.0.0/24 IETF
+        // protocol assignments, 198.18.0.0/15 benchmarking and 240.0.0.0/4 reserved,
+        // which includes the broadcast address
+        || a == 0
+        || (a == 100 && (b & 0xc0) == 64)
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && (b & 0xfe) == 18)
+        || a >= 240
+}
+
+fn is_private_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // fc00::/7 unique local, fe80::/10 link-local and deprecated fec0::/10 site-local
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first & 0xffc0) == 0xfec0
+}
+
+/// Resolver rejecting host names with a blocked address, so that `block_private_ips`
+/// also covers names pointing at private addresses (including redirect targets and
+/// DNS rebinding). Resolves through `inner` (the DNS cache) if set.
+pub struct GuardedResolver {
+    policy: Arc<HostPolicy>,
+    inner: Option<DnsCache>,
+    /// The proxy hosts, which may legitimately be on a private network.
+    exempt: HashSet<String>,
+}
+
+impl GuardedResolver {
+    pub fn new<'a>(
+        policy: Arc<HostPolicy>,
+        inner: Option<DnsCache>,
+        proxies: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let exempt = proxies
+            .into_iter()
+            .filter_map(|proxy| Url::parse(proxy).ok())
+            .filter_map(|url| url.host_str().map(str::to_ascii_lowercase))
+            .collect();
+        GuardedResolver {
+            policy,
+            inner,
+            exempt,
+        }
+    }
+}
+
+impl Resolve for GuardedResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let policy = Arc::clone(&self.policy);
+        let inner = self.inner.clone();
+        let host = name.as_str().to_ascii_lowercase();
+        let exempt = self.exempt.contains(&host);
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = match inner {
+                Some(inner) => inner.resolve(name).await?.collect(),
+                None => dns::lookup(&host).await?,
+            };
+            if !exempt {
+                // One blocked address fails the lookup, so that no connection attempt can
+                // fall through to it
+                for addr in &addrs {
+                    policy.check_ip(&host, addr.ip()).map_err(BoxError::from)?;
+                }
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allow: Option<&[&str]>, block: &[&str], private: bool) -> HostPolicy {
+        let hosts = |hosts: &[&str]| hosts.iter().map(|h| h.to_string()).collect();
+        HostPolicy::from_args(None, allow.map(hosts), Some(hosts(block)), private).unwrap()
+    }
+
+    fn check(policy: &HostPolicy, url: &str) -> bool {
+        policy.check_url(&Url::parse(url).unwrap()).is_ok()
+    }
+
+    #[test]
+    fn test_from_args() {
+        assert!(HostPolicy::from_args(None, Some(vec!["".into()]), None, false).is_err());
+        assert!(HostPolicy::from_args(None, None, Some(vec!["*.".into()]), false).is_err());
+        assert!(HostPolicy::from_args(Some(vec![]), None, None, false).is_err());
+        assert!(HostPolicy::from_args(Some(vec!["ftp".into()]), None, None, false).is_err());
+    }
+
+    #[test]
+    fn test_schemes() {
+        let default = policy(None, &[], false);
+        assert!(check(&default, "http://example.com/"));
+        assert!(check(&default, "HTTPS://example.com/"));
+        for url in ["ftp://example.com/", "file:///etc/passwd", "gopher://x/"] {
+            assert!(matches!(
+                default.check_url(&Url::parse(url).unwrap()),
+                Err(Refused::Scheme(_))
+            ));
+        }
+        let https = HostPolicy::from_args(Some(vec!["HTTPS".into()]), None, None, false).unwrap();
+        assert!(check(&https, "https://example.com/"));
+        assert!(!check(&https, "http://example.com/"));
+    }
+
+    #[test]
+    fn test_allow_and_block_hosts() {
+        let hosts = policy(
+            Some(&["Example.com", "*.api.example.com", "[::1]"]),
+            &["internal.api.example.com"],
+            false,
+        );
+        assert!(check(&hosts, "https://example.com/"));
+        assert!(check(&hosts, "https://EXAMPLE.com./a"));
+        assert!(!check(&hosts, "https://www.example.com/"));
+        assert!(check(&hosts, "https://v1.api.example.com/"));
+        assert!(!check(&hosts, "https://api.example.com/"));
+        assert!(!check(&hosts, "https://evilapi.example.com/"));
+        assert!(!check(&hosts, "https://internal.api.example.com/"));
+        assert!(check(&hosts, "http://[::1]:8080/"));
+    }
+
+    #[test]
+    fn test_private_ips() {
+        let hosts = policy(None, &[], true);
+        for url in [
+            "http://127.0.0.1/",
+            "http://10.1.2.3/",
+            "http://172.16.0.1/",
+            "http://192.168.1.1/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://100.64.0.1/",
+            "http://0.0.0.0/",
+            "http://[::1]/",
+            "http://[fd00::1]/",
+            "http://[fe80::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://224.0.0.1/",
+            "http://239.255.255.250/",
+            "http://240.0.0.1/",
+            "http://255.255.255.255/",
+            "http://198.18.0.1/",
+            "http://198.19.255.255/",
+            "http://192.0.0.8/",
+            "http://[::10.0.0.1]/",
+            "http://[2002:a9fe:a9fe::1]/",
+            "http://[64:ff9b::192.168.0.1]/",
+            "http://[ff02::1]/",
+        ] {
+            assert!(!check(&hosts, url), "{}", url);
+        }
+        for url in [
+            "http://93.184.216.34/",
+            "http://172.32.0.1/",
+            "http://100.128.0.1/",
+            "http://198.20.0.1/",
+            "http://192.0.2.1/",
+            "http://223.255.255.255/",
+            "http://[2606:4700::1111]/",
+            "http://[2002:5db8:d822::1]/",
+            "http://[64:ff9b::93.184.216.34]/",
+            "http://example.com/",
+        ] {
+            assert!(check(&hosts, url), "{}", url);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use html2text::render::{TaggedLine, TextDecorator};
+use html2text::Colour;
+use url::Url;
+
+/// A `TextDecorator` resolving the `href` of links and the `src` of images against a
+/// base URL before handing them to `inner`, for `Response.text_markdown` and friends.
+///
+/// URLs that do not parse as references are passed through as written, as is everything
+/// when there is no base.
+pub struct AbsoluteLinks<D> {
+    inner: D,
+    base: Option<Url>,
+}
+
+impl<D: TextDecorator> AbsoluteLinks<D> {
+    pub fn new(inner: D, base: Option<Url>) -> Self {
+        AbsoluteLinks { inner, base }
+    }
+
+    fn resolve(&self, url: &str) -> String {
+        self.base
+            .as_ref()
+            .and_then(|base| base.join(url.trim()).ok())
+            .map_or_else(|| url.to_string(), String::from)
+    }
+}
+
+impl<D: TextDecorator> TextDecorator for AbsoluteLinks<D> {
+    type Annotation = D::Annotation;
+
+    fn decorate_link_start(&mut self, url: &str) -> (String, Self::Annotation) {
+        let url = self.resolve(url);
+        self.inner.decorate_link_start(&url)
+    }
+
+    fn decorate_link_end(&mut self) -> String {
+        self.inner.decorate_link_end()
+    }
+
+    fn decorate_em_start(&self) -> (String, Self::Annotation) {
+        self.inner.decorate_em_start()
+    }
+
+    fn decorate_em_end(&self) -> String {
+        self.inner.decorate_em_end()
+    }
+
+    fn decorate_strong_start(&self) -> (String, Self::Annotation) {
+        self.inner.decorate_strong_start()
+    }
+
+    fn decorate_strong_end(&self) -> String {
+        self.inner.decorate_strong_end()
+    }
+
+    fn decorate_strikeout_start(&self) -> (String, Self::Annotation) {
+        self.inner.decorate_strikeout_start()
+    }
+
+    fn decorate_strikeout_end(&self) -> String {
+        self.inner.decorate_strikeout_end()
+    }
+
+    fn decorate_code_start(&self) -> (String, Self::Annotation) {
+        self.inner.decorate_code_start()
+    }
+
+    fn decorate_code_end(&self) -> String {
+        self.inner.decorate_code_end()
+    }
+
+    fn decorate_preformat_first(&self) -> Self::Annotation {
+        self.inner.decorate_preformat_first()
+    }
+
+    fn decorate_preformat_cont(&self) -> Self::Annotation {
+        self.inner.decorate_preformat_cont()
+    }
+
+    fn decorate_image(&mut self, src: &str, title: &str) -> (String, Self::Annotation) {
+        let src = self.resolve(src);
+        self.inner.decorate_image(&src, title)
+    }
+
+    fn header_prefix(&self, level: usize) -> String {
+        self.inner.header_prefix(level)
+    }
+
+    fn quote_prefix(&self) -> String {
+        self.inner.quote_prefix()
+    }
+
+    fn unordered_item_prefix(&self) -> String {
+        self.inner.unordered_item_prefix()
+    }
+
+    fn ordered_item_prefix(&self, i: i64) -> String {
+        self.inner.ordered_item_prefix(i)
+    }
+
+    fn make_subblock_decorator(&self) -> Self {
+        AbsoluteLinks {
+            inner: self.inner.make_subblock_decorator(),
+            base: self.base.clone(),
+        }
+    }
+
+    fn push_colour(&mut self, colour: Colour) -> Option<Self::Annotation> {
+        self.inner.push_colour(colour)
+    }
+
+    fn pop_colour(&mut self) -> bool {
+        self.inner.pop_colour()
+    }
+
+    fn push_bgcolour(&mut self, colour: Colour) -> Option<Self::Annotation> {
+        self.inner.push_bgcolour(colour)
+    }
+
+    fn pop_bgcolour(&mut self) -> bool {
+        self.inner.pop_bgcolour()
+    }
+
+    fn decorate_superscript_start(&self) -> (String, Self::Annotation) {
+        self.inner.decorate_superscript_start()
+    }
+
+    fn decorate_superscript_end(&self) -> String {
+        self.inner.decorate_superscript_end()
+    }
+
+    /// The link footnotes of `PlainDecorator` are rendered here from the raw targets.
+    fn finalise(&mut self, links: Vec<String>) -> Vec<TaggedLine<Self::Annotation>> {
+        let links = links.iter().map(|link| self.resolve(link)).collect();
+        self.inner.finalise(links)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use html2text::from_read_with_decorator;
+    use html2text::render::PlainDecorator;
+
+    #[test]
+    fn test_absolute_links() {
+        let html = br#"<a href="../b?x=1">B</a> <a href="https://other.example/">O</a>
+            <a href="mailto:a@example.com">M</a> <img src="/img.png" alt="I">"#;
+        let base = Url::parse("https://example.com/docs/a/page").unwrap();
+        let decorator = AbsoluteLinks::new(PlainDecorator::new(), Some(base));
+        let text = from_read_with_decorator(&html[..], 100, decorator).unwrap();
+        assert!(text.contains("[1]: https://example.com/docs/b?x=1"));
+        assert!(text.contains("[2]: https://other.example/"));
+        assert!(text.contains("[3]: mailto:a@example.com"));
+
+        let decorator = AbsoluteLinks::new(PlainDecorator::new(), None);
+        let text = from_read_with_decorator(&html[..], 100, decorator).unwrap();
+        assert!(text.contains("[1]: ../b?x=1"));
+    }
+}
@@ -0,0 +1,283 @@
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+
+enum Frame {
+    Object {
+        key: Option<String>,
+        expect_key: bool,
+    },
+    Array,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Element {
+    Container,
+    String,
+    Scalar,
+}
+
+/// Yields the elements of one array inside a JSON document fed in arbitrary chunks.
+///
+/// The array is found by a path of object keys (`["data", "items"]` for
+/// `{"data": {"items": [...]}}`, empty for a top-level array). Everything around it is
+/// scanned without being kept, and only the bytes of the current element are buffered,
+/// so memory is bounded by the largest element rather than the document.
+pub struct JsonArrayScanner {
+    target: Vec<String>,
+    stack: Vec<Frame>,
+    in_string: bool,
+    escaped: bool,
+    /// Raw bytes (quotes included) of the object key being read
+    key: Option<Vec<u8>>,
+    /// Depth of the target array once it has been opened
+    array_depth: Option<usize>,
+    element: Vec<u8>,
+    element_kind: Option<Element>,
+    done: bool,
+    offset: usize,
+}
+
+impl JsonArrayScanner {
+    /// Scan for the array at a dot-separated key path, e.g. `data.items`; `None` or an
+    /// empty path selects a top-level array.
+    pub fn new(path: Option<&str>) -> Self {
+        let target = match path {
+            Some(path) if !path.is_empty() => path.split('.').map(str::to_string).collect(),
+            _ => Vec::new(),
+        };
+        JsonArrayScanner {
+            target,
+            stack: Vec::new(),
+            in_string: false,
+            escaped: false,
+            key: None,
+            array_depth: None,
+            element: Vec::new(),
+            element_kind: None,
+            done: false,
+            offset: 0,
+        }
+    }
+
+    /// Whether the target array has been closed; the rest of the body is not needed.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Push every element completed by `chunk` onto `elements`.
+    pub fn feed(&mut self, chunk: &[u8], elements: &mut Vec<Value>) -> Result<()> {
+        for &b in chunk {
+            if self.done {
+                break;
+            }
+            self.step(b, elements)?;
+            self.offset += 1;
+        }
+        Ok(())
+    }
+
+    /// Check that the body contained the whole array.
+    pub fn finish(&self) -> Result<()> {
+        if self.done {
+            return Ok(());
+        }
+        if self.array_depth.is_none() {
+            bail!("No JSON array found at {}", self.location());
+        }
+        bail!("JSON body ended inside the array at {}", self.location())
+    }
+
+    fn location(&self) -> String {
+        if self.target.is_empty() {
+            "the top level".to_string()
+        } else {
+            format!("{:?}", self.target.join("."))
+        }
+    }
+
+    fn depth(&self) -> usize {
+        self.array_depth.unwrap_or(usize::MAX)
+    }
+
+    /// Whether a value starting now sits at the target path.
+    fn at_target(&self) -> bool {
+        self.stack.len() == self.target.len()
+            && self.stack.iter().zip(&self.target).all(|(frame, segment)| {
+                matches!(frame, Frame::Object { key: Some(key), .. } if key == segment)
+            })
+    }
+
+    fn emit(&mut self, elements: &mut Vec<Value>) -> Result<()> {
+        let value = serde_json::from_slice(&self.element)
+            .map_err(|e| anyhow!("Invalid JSON array element at byte {}: {}", self.offset, e))?;
+        elements.push(value);
+        self.element.clear();
+        self.element_kind = None;
+        Ok(())
+    }
+
+    fn step(&mut self, b: u8, elements: &mut Vec<Value>) -> Result<()> {
+        // Numbers and literals have no closing delimiter
+        if self.element_kind == Some(Element::Scalar)
+            && self.stack.len() == self.depth()
+            && (b == b',' || b == b']' || b.is_ascii_whitespace())
+        {
+            self.emit(elements)?;
+        }
+        if self.element_kind.is_some() {
+            self.element.push(b);
+        }
+
+        if self.in_string {
+            if let Some(key) = self.key.as_mut() {
+                key.push(b);
+            }
+            if self.escaped {
+                self.escaped = false;
+            } else if b == b'\\' {
+                self.escaped = true;
+            } else if b == b'"' {
+                self.in_string = false;
+                if let Some(raw) = self.key.take() {
+                    let key: String = serde_json::from_slice(&raw)
+                        .map_err(|e| anyhow!("Invalid JSON at byte {}: {}", self.offset, e))?;
+                    if let Some(Frame::Object { key: current, .. }) = self.stack.last_mut() {
+                        *current = Some(key);
+                    }
+                } else if self.element_kind == Some(Element::String)
+                    && self.stack.len() == self.depth()
+                {
+                    self.emit(elements)?;
+                }
+            }
+            return Ok(());
+        }
+
+        match b {
+            b' ' | b'\t' | b'\n' | b'\r' | b':' => {}
+            b',' => {
+                if let Some(Frame::Object { expect_key, .. }) = self.stack.last_mut() {
+                    *expect_key = true;
+                }
+            }
+            b'}' | b']' => {
+                match (self.stack.pop(), b) {
+                    (Some(Frame::Object { .. }), b'}') | (Some(Frame::Array), b']') => {}
+                    _ => bail!("Invalid JSON at byte {}", self.offset),
+                }
+                if self.stack.len() + 1 == self.depth() {
+                    self.done = true;
+                } else if self.element_kind == Some(Element::Container)
+                    && self.stack.len() == self.depth()
+                {
+                    self.emit(elements)?;
+                }
+            }
+            _ => {
+                if let Some(Frame::Object {
+                    expect_key: expect_key @ true,
+                    ..
+                }) = self.stack.last_mut()
+                {
+                    if b != b'"' {
+                        bail!("Invalid JSON at byte {}", self.offset);
+                    }
+                    *expect_key = false;
+                    self.in_string = true;
+                    self.key = Some(vec![b]);
+                    return Ok(());
+                }
+
+                if self.array_depth.is_none() && self.at_target() {
+                    if b != b'[' {
+                        bail!("Expected a JSON array at {}", self.location());
+                    }
+                    self.stack.push(Frame::Array);
+                    self.array_depth = Some(self.stack.len());
+                    return Ok(());
+                }
+                if self.element_kind.is_none() && self.stack.len() == self.depth() {
+                    self.element_kind = Some(match b {
+                        b'{' | b'[' => Element::Container,
+                        b'"' => Element::String,
+                        _ => Element::Scalar,
+                    });
+                    self.element.push(b);
+                }
+                match b {
+                    b'{' => self.stack.push(Frame::Object {
+                        key: None,
+                        expect_key: true,
+                    }),
+                    b'[' => self.stack.push(Frame::Array),
+                    b'"' => self.in_string = true,
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scan(path: Option<&str>, body: &str, chunk_size: usize) -> Result<Vec<Value>> {
+        let mut scanner = JsonArrayScanner::new(path);
+        let mut elements = Vec::new();
+        for chunk in body.as_bytes().chunks(chunk_size) {
+            scanner.feed(chunk, &mut elements)?;
+        }
+        scanner.finish()?;
+        Ok(elements)
+    }
+
+    #[test]
+    fn test_scan_nested_array_across_chunks() {
+        let body = r#"{"meta": {"items": [0]}, "data": {"count": 4, "items": [
+            {"id": 1, "tags": ["a]", "}"]}, "quote \" ]", -1.5e3, true, null, [[]]
+        ], "after": [9]}, "items": "not me"}"#;
+        let expected = vec![
+            json!({"id": 1, "tags": ["a]", "}"]}),
+            json!("quote \" ]"),
+            json!(-1.5e3),
+            json!(true),
+            json!(null),
+            json!([[]]),
+        ];
+        for chunk_size in [1, 3, 7, body.len()] {
+            assert_eq!(
+                scan(Some("data.items"), body, chunk_size).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_scan_top_level_array() {
+        assert_eq!(
+            scan(None, "[1, 22 ,\"x\"]", 2).unwrap(),
+            vec![json!(1), json!(22), json!("x")]
+        );
+        assert_eq!(scan(None, " [ ] ", 1).unwrap(), Vec::<Value>::new());
+        assert_eq!(
+            scan(Some("k\u{e9}y"), r#"{"kéy": [1]}"#, 4).unwrap(),
+            vec![json!(1)]
+        );
+    }
+
+    #[test]
+    fn test_scan_errors() {
+        let err = scan(Some("items"), r#"{"other": []}"#, 4).unwrap_err();
+        assert_eq!(err.to_string(), "No JSON array found at \"items\"");
+        let err = scan(Some("items"), r#"{"items": {}}"#, 4).unwrap_err();
+        assert_eq!(err.to_string(), "Expected a JSON array at \"items\"");
+        let err = scan(None, "{}", 4).unwrap_err();
+        assert_eq!(err.to_string(), "Expected a JSON array at the top level");
+        let err = scan(None, "[1, {\"a\": 2}", 4).unwrap_err();
+        assert!(err.to_string().contains("ended inside the array"));
+        assert!(scan(None, "[1, tru]", 4).is_err());
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use serde_json_path::JsonPath;
+
+/// Parse an RFC 9535 JSONPath expression such as `$.items[*].id`.
+pub fn compile(path: &str) -> Result<JsonPath> {
+    JsonPath::parse(path).map_err(|e| anyhow!("Invalid JSONPath {:?}: {}", path, e))
+}
+
+/// Every node `path` selects in `value`, in document order.
+pub fn query(path: &JsonPath, value: &Value) -> Vec<Value> {
+    path.query(value).into_iter().cloned().collect()
+}
+
+/// Applies a JSONPath to each document of an NDJSON body fed in arbitrary chunks.
+///
+/// Only the current, incomplete line is buffered; blank lines are skipped.
+pub struct NdjsonMatcher {
+    path: JsonPath,
+    buffer: Vec<u8>,
+    line: usize,
+}
+
+impl NdjsonMatcher {
+    pub fn new(path: JsonPath) -> Self {
+        NdjsonMatcher {
+            path,
+            buffer: Vec::new(),
+            line: 0,
+        }
+    }
+
+    /// Push the matches from every line completed by `chunk` onto `matches`.
+    ///
+    /// On an invalid line, `matches` keeps everything from the lines before it.
+    pub fn feed(&mut self, chunk: &[u8], matches: &mut Vec<Value>) -> Result<()> {
+        let mut rest = chunk;
+        while let Some(pos) = rest.iter().position(|&b| b == b'\n') {
+            self.buffer.extend_from_slice(&rest[..pos]);
+            rest = &rest[pos + 1..];
+            let line = std::mem::take(&mut self.buffer);
+            self.match_line(&line, matches)?;
+        }
+        self.buffer.extend_from_slice(rest);
+        Ok(())
+    }
+
+    /// Push the matches from a final line without a trailing newline onto `matches`.
+    pub fn finish(&mut self, matches: &mut Vec<Value>) -> Result<()> {
+        let line = std::mem::take(&mut self.buffer);
+        self.match_line(&line, matches)
+    }
+
+    fn match_line(&mut self, line: &[u8], matches: &mut Vec<Value>) -> Result<()> {
+        self.line += 1;
+        if line.iter().all(u8::is_ascii_whitespace) {
+            return Ok(());
+        }
+        let value: Value = serde_json::from_slice(line)
+            .map_err(|e| anyhow!("Invalid JSON on NDJSON line {}: {}", self.line, e))?;
+        matches.extend(query(&self.path, &value));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_query() {
+        let path = compile("$.items[*].id").unwrap();
+        let value = json!({"items": [{"id": 1}, {"name": "x"}, {"id": 3}]});
+        assert_eq!(query(&path, &value), vec![json!(1), json!(3)]);
+        assert!(compile("items[").is_err());
+    }
+
+    #[test]
+    fn test_ndjson_matcher_splits_across_chunks() {
+        let mut matcher = NdjsonMatcher::new(compile("$.id").unwrap());
+        let mut matches = Vec::new();
+        matcher.feed(b"{\"id\": 1}\r\n{\"i", &mut matches).unwrap();
+        assert_eq!(matches, vec![json!(1)]);
+        matcher
+            .feed(b"d\": 2}\n\n{\"other\": 0}\n", &mut matches)
+            .unwrap();
+        matcher.feed(b"{\"id\": 4}", &mut matches).unwrap();
+        assert_eq!(matches, vec![json!(1), json!(2)]);
+        matcher.finish(&mut matches).unwrap();
+        assert_eq!(matches, vec![json!(1), json!(2), json!(4)]);
+
+        let mut matcher = NdjsonMatcher::new(compile("$").unwrap());
+        let mut matches = Vec::new();
+        let err = matcher.feed(b"{}\nnot json\n", &mut matches).unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+        assert_eq!(matches, vec![json!({})]);
+    }
+}
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use foldhash::fast::RandomState;
+use indexmap::IndexMap;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use reqwest::header::{HeaderName, HeaderValue};
+
+type IndexMapSSR = IndexMap<String, String, RandomState>;
+
+/// A header value given in Python: a string, or a callable returning one when the
+/// request is sent.
+#[derive(FromPyObject)]
+pub enum HeaderValueArg {
+    Value(String),
+    Lazy(Py<PyAny>),
+}
+
+/// Headers whose values come from Python callables, called without arguments once per
+/// attempt (every redirect hop and connection-error replay) just before the `sign` hook.
+/// A callable returning `None` leaves its header out of that attempt.
+#[derive(Clone, Default)]
+pub struct LazyHeaders(Vec<(HeaderName, Arc<Py<PyAny>>)>);
+
+impl LazyHeaders {
+    /// Split `headers=` into the plain values and the callables.
+    pub fn split(
+        headers: IndexMap<String, HeaderValueArg, RandomState>,
+    ) -> PyResult<(IndexMapSSR, LazyHeaders)> {
+        let mut values =
+            IndexMapSSR::with_capacity_and_hasher(headers.len(), RandomState::default());
+        let mut lazy = LazyHeaders::default();
+        for (name, value) in headers {
+            match value {
+                HeaderValueArg::Value(value) => {
+                    values.insert(name, value);
+                }
+                HeaderValueArg::Lazy(callable) => lazy.push(&name, callable)?,
+            }
+        }
+        Ok((values, lazy))
+    }
+
+    pub fn push(&mut self, name: &str, callable: Py<PyAny>) -> PyResult<()> {
+        let is_callable = Python::attach(|py| callable.bind(py).is_callable());
+        if !is_callable {
+            return Err(PyValueError::new_err(format!(
+                "Header '{}' must be a str or a callable returning one",
+                name
+            )));
+        }
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| PyValueError::new_err(format!("Invalid header name '{}': {}", name, e)))?;
+        self.0.retain(|(existing, _)| *existing != name);
+        self.0.push((name, Arc::new(callable)));
+        Ok(())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.0
+            .iter()
+            .any(|(existing, _)| existing.as_str().eq_ignore_ascii_case(name))
+    }
+
+    /// These client headers under those of a request: the ones it sets, removes or also
+    /// gives as callables are dropped, and its callables added.
+    pub fn under(
+        &self,
+        headers: Option<&IndexMapSSR>,
+        removed: &[HeaderName],
+        request: LazyHeaders,
+    ) -> LazyHeaders {
+        let overridden = |name: &HeaderName| {
+            removed.contains(name)
+                || request.0.iter().any(|(other, _)| other == name)
+                || headers
+                    .into_iter()
+                    .flatten()
+                    .any(|(other, _)| other.eq_ignore_ascii_case(name.as_str()))
+        };
+        let mut merged: Vec<_> = self
+            .0
+            .iter()
+            .filter(|(name, _)| !overridden(name))
+            .cloned()
+            .collect();
+        merged.extend(request.0);
+        LazyHeaders(merged)
+    }
+
+    /// Add the callables to `dict` under their header names, for `client.headers`.
+    pub fn add_to(&self, dict: &Bound<'_, PyDict>) -> PyResult<()> {
+        for (name, callable) in &self.0 {
+            dict.set_item(name.as_str(), callable.bind(dict.py()))?;
+        }
+        Ok(())
+    }
+
+    /// Call every callable and set its header on `request`, replacing any value there.
+    pub fn apply(&self, request: &mut reqwest::Request) -> Result<()> {
+        if self.0.is_empty() {
+            return Ok(());
+        }
+        let values = Python::attach(|py| {
+            self.0
+                .iter()
+                .map(|(name, callable)| {
+                    let value = callable.bind(py).call0()?;
+                    let value: Option<String> = value.extract().map_err(|_| {
+                        anyhow!("Header '{}' callable must return a str or None", name)
+                    })?;
+                    Ok((name, value))
+                })
+                .collect::<Result<Vec<_>>>()
+        })?;
+        for (name, value) in values {
+            match value {
+                Some(value) => {
+                    let value = HeaderValue::from_str(&value)
+                        .map_err(|e| anyhow!("Invalid value for header '{}': {}", name, e))?;
+                    request.headers_mut().insert(name.clone(), value);
+                }
+                None => {
+                    request.headers_mut().remove(name);
+                }
+            }
+        }
+        Ok(())
+    }
+}
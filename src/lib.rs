@@ -1,5 +1,8 @@
 #![allow(clippy::too_many_arguments)]
-use std::sync::{Arc, LazyLock, Mutex};
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use std::thread::{self, ThreadId};
 use std::time::Duration;
 use std::{fs, str};
 
@@ -9,32 +12,178 @@ use foldhash::fast::RandomState;
 use indexmap::IndexMap;
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3::types::PyBytes;
+use pyo3::types::PyDict;
 use pythonize::depythonize;
 use reqwest::{
+    cookie::Jar,
     header::{HeaderValue, COOKIE},
-    multipart,
-    redirect::Policy,
-    Body, Identity, Method,
+    Method,
 };
 use serde_json::Value;
 use tokio::{
-    fs::File,
     runtime::{self, Runtime},
+    sync::Semaphore,
+    task::JoinSet,
+    time::MissedTickBehavior,
+};
+use url::Url;
+
+mod arrow;
+
+mod attempts;
+use attempts::AttemptLog;
+
+mod auth;
+use auth::{ApiKeyAuth, Auth, HmacAuth, SignHook};
+
+mod cancel;
+use cancel::CancellationToken;
+
+mod codecs;
+use codecs::{CodecArgs, Codecs};
+
+mod connection;
+use connection::{Http2KeepAlive, RequestTimeout, Timeouts, DEFAULT_TIMEOUT};
+
+mod contentdigest;
+use contentdigest::ContentDigest;
+
+mod cookies;
+use cookies::{response_cookies, CookieMerge, CookiePolicy, PolicyJar, RecordingJar};
+
+mod csvrows;
+
+mod decompress;
+use decompress::DecompressionGuard;
+
+mod dns;
+use dns::DnsCache;
+
+mod download;
+
+mod env;
+use env::EnvConfig;
+
+mod events;
+use events::ConnectionEvents;
+
+mod fetch;
+use fetch::FetchIterator;
+
+mod history;
+use history::History;
+
+mod hosts;
+use hosts::HostPolicy;
+
+mod htmltext;
+
+mod jsonarray;
+
+mod jsonpath;
+
+mod lazyheaders;
+use lazyheaders::{HeaderValueArg, LazyHeaders};
+
+mod limits;
+use limits::HeaderLimits;
+
+mod links;
+
+mod lowspeed;
+use lowspeed::LowSpeed;
+
+mod multipart;
+use multipart::FileParts;
+
+mod paginate;
+use paginate::{PageOptions, Paginator};
+
+mod problem;
+use problem::ProblemDetails;
+
+mod queue;
+use queue::RequestQueue;
+
+mod ratelimit;
+use ratelimit::{Rate, RateLimiter};
+
+mod redirect;
+use redirect::Redirects;
+
+mod request;
+use request::{
+    check_raw_query, content_bytes, dedupe_groups, split_headers, AuthMode, ContentStream,
+    DedupeField, HeaderArgs, Request, RequestSpec, ResponseParts,
 };
-use tokio_util::codec::{BytesCodec, FramedRead};
 
 mod response;
-use response::{CaseInsensitiveHeaderMap, LineIterator, Response, StreamingResponse, TextIterator};
+use response::{
+    CaseInsensitiveHeaderMap, CsvIterator, JsonIterator, LineIterator, Response, StreamingResponse,
+    TextIterator,
+};
+
+mod robots;
+use robots::{RobotsCache, RobotsTxt};
+
+mod runtimestats;
+use runtimestats::{runtime_stats, RuntimeStats, MAX_BLOCKING_THREADS};
+
+mod schema;
+
+mod serialize;
+use serialize::{to_json, JsonFormat, JsonSerializer};
+
+mod servertiming;
+use servertiming::ServerTiming;
+
+mod settings;
+use settings::ClientSettings;
+
+mod sigv4;
+use sigv4::presign_url;
+
+mod sitemap;
+use sitemap::SitemapIterator;
+
+mod sni;
+use sni::check_sni_hostname;
+
+mod sniff;
+use sniff::SniffResult;
+
+mod template;
+use template::RequestTemplate;
+
+mod testing;
+use testing::LocalServer;
+
+mod tls;
+use tls::{TlsOptions, VerifyHook};
 
 mod traits;
 use traits::{CookiesTraits, HeadersTraits};
 
+mod transfer;
+use transfer::Meter;
+
+mod transport;
+use transport::{PythonTransport, Transport, TransportRequest};
+
+mod tus;
+use tus::{encode_metadata, TusOptions};
+
+mod upload;
+use upload::{MultipartUploader, PartUrl, UploadOptions};
+
+mod upgrade;
+use upgrade::Stream;
+
 mod utils;
-use utils::load_ca_certs;
+use utils::{join_base_url, load_ca_certs, parse_base_url};
 
 mod exceptions;
-use exceptions::{map_anyhow_error, map_reqwest_error};
+use exceptions::map_anyhow_error;
 
 type IndexMapSSR = IndexMap<String, String, RandomState>;
 
@@ -42,25 +191,104 @@ type IndexMapSSR = IndexMap<String, String, RandomState>;
 static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
     runtime::Builder::new_current_thread()
         .enable_all()
+        .max_blocking_threads(MAX_BLOCKING_THREADS)
+        .on_thread_start(runtimestats::thread_started)
+        .on_thread_stop(runtimestats::thread_stopped)
         .build()
         .expect("Failed to initialize Tokio runtime")
 });
 
+/// How often `block_on` checks for pending Python signals.
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The Python main thread, recorded when the module is imported on it.
+static MAIN_THREAD: OnceLock<ThreadId> = OnceLock::new();
+
+/// Run the future made by `make` on the global runtime, releasing the GIL meanwhile.
+///
+/// Python only runs signal handlers on the main thread, and not while it waits in Rust.
+/// There, pending signals are checked every `SIGNAL_CHECK_INTERVAL`, so that Ctrl-C
+/// raises `KeyboardInterrupt` promptly. The future is dropped in that case, which
+/// aborts its requests.
+fn block_on<F, Fut>(py: Python, make: F) -> PyResult<Fut::Output>
+where
+    F: FnOnce() -> Fut + Send,
+    Fut: Future,
+    Fut::Output: Send,
+{
+    // Without a recorded main thread every thread checks, which is a no-op off the main one
+    if MAIN_THREAD
+        .get()
+        .is_some_and(|main| *main != thread::current().id())
+    {
+        return Ok(py.detach(|| RUNTIME.block_on(make())));
+    }
+    py.detach(|| {
+        RUNTIME.block_on(async {
+            let future = make();
+            tokio::pin!(future);
+            let mut checks = tokio::time::interval(SIGNAL_CHECK_INTERVAL);
+            checks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                tokio::select! {
+                    biased;
+                    output = &mut future => return Ok(output),
+                    _ = checks.tick() => Python::attach(|py| py.check_signals())?,
+                }
+            }
+        })
+    })
+}
+
 #[pyclass(subclass)]
 /// HTTP client that can impersonate web browsers.
 pub struct RClient {
     client: Arc<Mutex<reqwest::Client>>,
+    /// What `client` is built from, to build it again when the proxy changes.
+    settings: ClientSettings,
     headers: Arc<Mutex<reqwest::header::HeaderMap>>,
+    /// Client headers given as callables, evaluated for every attempt.
+    lazy_headers: Arc<Mutex<LazyHeaders>>,
     #[pyo3(get, set)]
-    auth: Option<(String, Option<String>)>,
+    auth: Option<Auth>,
     #[pyo3(get, set)]
     auth_bearer: Option<String>,
     #[pyo3(get, set)]
     params: Option<IndexMapSSR>,
     #[pyo3(get, set)]
     proxy: Option<String>,
+    base_url: Option<Url>,
     #[pyo3(get, set)]
     timeout: Option<f64>,
+    /// Seconds allowed for connecting, or None.
+    #[pyo3(get)]
+    connect_timeout: Option<f64>,
+    /// Seconds a response may go without receiving data, or None.
+    #[pyo3(get)]
+    read_timeout: Option<f64>,
+    max_download_rate: Option<RateLimiter>,
+    max_upload_rate: Option<RateLimiter>,
+    low_speed: Option<LowSpeed>,
+    dns_cache: Option<DnsCache>,
+    auth_mode: AuthMode,
+    cookie_merge: CookieMerge,
+    sign: Option<SignHook>,
+    history: Option<History>,
+    robots: Option<RobotsCache>,
+    hosts: Arc<HostPolicy>,
+    redirects: Redirects,
+    header_limits: Option<HeaderLimits>,
+    decompression: DecompressionGuard,
+    codecs: Codecs,
+    json_serializer: Option<JsonSerializer>,
+    json_format: JsonFormat,
+    meter: Meter,
+    connection_events: Option<ConnectionEvents>,
+    transport: Option<Transport>,
+    content_digest: Option<ContentDigest>,
+    allow_sni_mismatch: bool,
+    retry_idempotent: bool,
+    url_credentials: bool,
 }
 
 #[pymethods]
@@ -75,20 +303,138 @@ impl RClient {
     ///
     /// * `auth` - A tuple containing the username and an optional password for basic authentication. Default is None.
     /// * `auth_bearer` - A string representing the bearer token for bearer token authentication. Default is None.
+    /// * `auth_mode` - `"preemptive"` sends credentials with every request; `"on_challenge"` withholds them
+    ///         until the server answers 401 with a matching `WWW-Authenticate` challenge. Default is `"preemptive"`.
+    /// * `sign` - A callable run on every request just before it is sent, after all defaults and auth are
+    ///         applied. It gets `(method, url, headers, body)` and may return a dict of headers to add. Default is None.
     /// * `params` - A map of query parameters to append to the URL. Default is None.
-    /// * `headers` - An optional map of HTTP headers to send with requests.
+    /// * `headers` - An optional map of HTTP headers to send with requests. A value may be a callable
+    ///         returning the value (or None to leave the header out), called once per attempt.
     /// * `cookies` - An optional map of cookies to send with requests as the `Cookie` header.
     /// * `cookie_store` - Enable a persistent cookie store. Received cookies will be preserved and included
     ///         in additional requests. Default is `true`.
     /// * `referer` - Enable or disable automatic setting of the `Referer` header. Default is `true`.
     /// * `proxy` - An optional proxy URL for HTTP requests.
-    /// * `timeout` - An optional timeout for HTTP requests in seconds.
+    /// * `timeout` - Total timeout for each request in seconds, response body included. Default is `None`
+    ///         (no overall limit).
     /// * `follow_redirects` - A boolean to enable or disable following redirects. Default is `true`.
     /// * `max_redirects` - The maximum number of redirects to follow. Default is 20. Applies if `follow_redirects` is `true`.
     /// * `verify` - An optional boolean indicating whether to verify SSL certificates. Default is `true`.
     /// * `ca_cert_file` - Path to CA certificate store. Default is None.
     /// * `https_only` - Restrict the Client to be used with HTTPS only requests. Default is `false`.
     /// * `http2_only` - If true - use only HTTP/2, if false - use only HTTP/1. Default is `false`.
+    /// * `max_download_rate` - Cap on response body throughput, in bytes per second or as a string
+    ///         like `"5MB/s"`. Shared by all requests of the client. Default is None.
+    /// * `max_upload_rate` - Cap on request body throughput, same format. Shared by all requests
+    ///         of the client. Default is None.
+    /// * `low_speed_limit` - Abort a response body transfer that stays below this many bytes per second
+    ///         for `low_speed_time` seconds, raising `ReadTimeout` (curl semantics). Default is None.
+    /// * `low_speed_time` - Window for `low_speed_limit` in seconds. Default is 30.
+    /// * `dns_cache_ttl` - Enable an in-process DNS cache keeping resolved addresses for at most
+    ///         this many seconds. Default is None (every connection asks the system resolver).
+    /// * `dns_cache_negative_ttl` - Also cache failed lookups for this many seconds. Requires
+    ///         `dns_cache_ttl`. Default is None.
+    /// * `reject_third_party_cookies` - Ignore cookies from, and send none to, hosts on a different site
+    ///         (registrable domain) than the requested URL, such as cross-site redirect targets. Default is `false`.
+    /// * `blocked_cookie_domains` - Never store or send cookies for these domains or their subdomains. Default is None.
+    /// * `max_cookies_per_domain` - Ignore new cookies once a domain holds this many. Default is None.
+    /// * `max_cookie_size` - Ignore cookies whose name and value exceed this many bytes. Default is None.
+    /// * `cookie_public_suffix` - Reject cookies scoped to a public suffix (e.g. `Domain=co.uk`) using
+    ///         the bundled Public Suffix List. Default is `false`.
+    /// * `history_size` - Keep summaries of the last this many requests (method, URL, status,
+    ///         timing, error), returned by `history()`. Default is None (no history).
+    /// * `respect_robots_txt` - Check the robots.txt rules of each host before sending a request to it,
+    ///         raising `RobotsDisallowed` when the request's `User-Agent` may not fetch the URL. Default is `false`.
+    /// * `robots_cache_ttl` - Seconds to cache the robots.txt rules of a host. Default is 3600.
+    /// * `allowed_schemes` - URL schemes requests and redirects may use, a subset of `["http", "https"]`.
+    ///         Other schemes raise `UnsupportedProtocol`. Default is None (both).
+    /// * `allow_hosts` - Only send requests to, and follow redirects to, these hosts. Entries are host
+    ///         names or IP addresses; `*.example.com` matches subdomains. Default is None (any host).
+    /// * `block_hosts` - Never send requests to these hosts, same format. Default is None.
+    /// * `block_private_ips` - Refuse hosts that are, or resolve to, loopback, private, link-local or
+    ///         other non-public addresses, checked after DNS resolution and for every redirect target.
+    ///         Through a proxy, which resolves host names itself, only IP addresses in URLs are
+    ///         checked. Default is `false`.
+    /// * `max_header_bytes` - Maximum total size of the headers of a response, raising
+    ///         `RemoteProtocolError` when exceeded. Default is None (no limit).
+    /// * `max_header_count` - Maximum number of headers of a response, raising `RemoteProtocolError`
+    ///         when exceeded. Default is None (no limit).
+    /// * `max_decompressed_size` - Maximum size in bytes of a decoded (gzip, deflate, brotli, zstd)
+    ///         response body, raising `DecodingError` when exceeded. Default is None (no limit).
+    /// * `max_decompression_ratio` - Maximum ratio of decoded to compressed bytes of a response body,
+    ///         enforced once more than 1 MiB has been decoded, raising `DecodingError`. Default is None.
+    /// * `json_serializer` - A callable converting objects in `json=` that are not plain JSON values,
+    ///         tried before the built-in handling of pydantic models, dataclasses and other objects.
+    ///         It may return `NotImplemented` to use the built-in conversion. Default is None.
+    /// * `json_canonical` - Serialize `json=` bodies in the JSON Canonicalization Scheme (RFC 8785):
+    ///         no whitespace, object keys sorted and numbers in their shortest form, as APIs verifying
+    ///         a detached signature over the payload require. Default is False.
+    /// * `json_sort_keys` - Sort the keys of objects in `json=` bodies, as `json.dumps(sort_keys=True)`.
+    ///         Default is False (insertion order).
+    /// * `json_ensure_ascii` - Escape non-ASCII characters of `json=` bodies as `\uXXXX`, as
+    ///         `json.dumps(ensure_ascii=True)`. Default is False (UTF-8).
+    /// * `json_separators` - The `(item_separator, key_separator)` of `json=` bodies, e.g. `(", ", ": ")`
+    ///         as `json.dumps` uses by default. Default is None (`(",", ":")`, or `(",", ": ")` with
+    ///         `json_indent`).
+    /// * `json_indent` - Pretty-print `json=` bodies, indenting each level by this many spaces.
+    ///         Default is None (one line).
+    /// * `on_connection_event` - A callable receiving a dict for each DNS lookup (`dns_resolved`)
+    ///         and for each response, telling whether its connection was opened for it
+    ///         (`connection_created`) or reused from the pool (`connection_reused`). Default is None.
+    /// * `http2_keep_alive_interval` - Seconds between HTTP/2 PING frames keeping connections alive
+    ///         and detecting dead ones. Default is None (no pings).
+    /// * `http2_keep_alive_timeout` - Seconds to wait for a PING acknowledgement before closing the
+    ///         connection. Default is the reqwest default (20 seconds).
+    /// * `http2_keep_alive_while_idle` - Whether to also ping connections without open requests.
+    ///         Default is False.
+    /// * `retry_idempotent_on_connection_error` - Send GET, HEAD, OPTIONS, PUT and DELETE requests
+    ///         once more when connecting fails or the connection breaks before any response arrives,
+    ///         e.g. on a stale pooled connection. Default is True.
+    /// * `cookie_merge` - How per-request `cookies` combine with the client `cookies`: `"merge"` sends both,
+    ///         a request cookie replacing a client cookie of the same name; `"replace"` sends only the request
+    ///         cookies. Default is `"merge"`.
+    /// * `connect_timeout` - Seconds allowed for connecting, or `None` for no limit. Default is 30.
+    /// * `read_timeout` - Seconds a response may go without receiving data, or `None` for no limit.
+    ///         Default is 30, which also ends Server-Sent Events and long-poll responses that stay idle
+    ///         for longer; use `None` for clients reading those.
+    /// * `trust_env` - Read defaults for arguments left out from `HTTPR_TIMEOUT`, `HTTPR_VERIFY`,
+    ///         `HTTPR_PROXY`, `HTTPR_HTTP_PROXY`, `HTTPR_HTTPS_PROXY` and `HTTPR_DEFAULT_HEADERS` (a JSON
+    ///         object), and use the system proxy settings. Default is `true`.
+    /// * `base_url` - Absolute URL that relative request URLs are appended to, e.g. `users` or `/users`
+    ///         on `https://api.example.com/v1` request `https://api.example.com/v1/users`.
+    /// * `codecs` - Custom `Content-Encoding`s, as a dict of names to `(compress, decompress)` pairs of
+    ///         callables taking and returning `bytes`, either of which may be None. Responses in such an
+    ///         encoding are read whole and decoded, and the names are added to `Accept-Encoding`.
+    /// * `request_encoding` - Compress request bodies with this encoding: `"gzip"`, `"deflate"`, `"br"`,
+    ///         `"zstd"` or the name of a codec. Requests setting `Content-Encoding` are sent as is.
+    ///         Default is None.
+    /// * `url_credentials` - Send the `user:password@` part of request URLs as basic auth, unless
+    ///         `auth` or `auth_bearer` is set. It is removed from the URL either way, so it never shows
+    ///         up in `response.url`, the history or logs. Default is `true`.
+    /// * `transport` - A `PythonTransport` sending the requests through a Python callable instead
+    ///         of the client's own connections, e.g. to route them through an existing SDK or tunnel.
+    ///         Redirects, cookies, auth and decompression are still handled by the client. Default is None.
+    /// * `send_content_digest` - Attach a `Content-Digest` header (RFC 9530) with the `"sha-256"` or
+    ///         `"sha-512"` digest of every request body; streamed bodies get it as a trailer. Responses
+    ///         carrying a `Content-Digest` or `Digest` header are then checked against it as they are
+    ///         read, raising `DecodingError` on a mismatch. Default is None.
+    /// * `allow_sni_mismatch` - Send HTTPS requests whose `Host` header names another host than
+    ///         their TLS server name (`sni_hostname` or the URL host), as for domain fronting or testing
+    ///         CDN routing. Such requests raise `ValueError` otherwise. Default is False.
+    /// * `alpn_protocols` - The protocols offered in the TLS handshake (ALPN), in order of preference:
+    ///         `"h2"` and/or `"http/1.1"`, e.g. `["http/1.1"]` to keep servers and middleboxes from
+    ///         choosing HTTP/2. An empty list sends no ALPN extension. Default is None (`["h2", "http/1.1"]`).
+    /// * `tls_session_tickets` - Resume TLS sessions from session tickets. With False, TLS 1.2 sessions
+    ///         resume by session ID only and TLS 1.3 sessions, which need tickets, are not resumed.
+    ///         Resumed sessions never send early data (0-RTT), which is not supported. Default is True.
+    /// * `tls_session_cache_size` - Number of servers whose TLS sessions are kept for resumption, or 0 to
+    ///         never resume sessions. Default is None (256).
+    /// * `verify_hook` - A callable deciding whether to accept the server certificate of every TLS
+    ///         handshake, called as `verify_hook(hostname, chain, error)` with the DER-encoded certificate
+    ///         chain (server certificate first) and the reason code of the default verification failing
+    ///         (e.g. `"expired"`), or None when it passed. A true result accepts the certificate, a false one
+    ///         rejects it, with reason `"rejected_by_hook"` if it passed. Exceptions it raises are re-raised.
+    ///         Default is None.
     ///
     /// # Example
     ///
@@ -116,12 +462,26 @@ impl RClient {
     #[new]
     #[pyo3(signature = (auth=None, auth_bearer=None, params=None, headers=None, cookies=None,
         cookie_store=true, referer=true, proxy=None, timeout=None, follow_redirects=true,
-        max_redirects=20, verify=true, ca_cert_file=None, client_pem=None, client_pem_data=None, https_only=false, http2_only=false))]
+        max_redirects=20, verify=None, ca_cert_file=None, client_pem=None, client_pem_data=None, https_only=false, http2_only=false,
+        max_download_rate=None, max_upload_rate=None, low_speed_limit=None, low_speed_time=None,
+        dns_cache_ttl=None, dns_cache_negative_ttl=None, reject_third_party_cookies=false,
+        blocked_cookie_domains=None, max_cookies_per_domain=None, max_cookie_size=None,
+        cookie_public_suffix=false, auth_mode=None, sign=None, history_size=None,
+        respect_robots_txt=false, robots_cache_ttl=3600.0, allow_hosts=None, block_hosts=None,
+        block_private_ips=false, allowed_schemes=None, max_header_bytes=None, max_header_count=None,
+        max_decompressed_size=None, max_decompression_ratio=None, json_serializer=None, json_canonical=false,
+        json_sort_keys=false, json_ensure_ascii=false, json_separators=None, json_indent=None,
+        on_connection_event=None, http2_keep_alive_interval=None, http2_keep_alive_timeout=None,
+        http2_keep_alive_while_idle=None, retry_idempotent_on_connection_error=true,
+        cookie_merge=None, connect_timeout=Some(DEFAULT_TIMEOUT), read_timeout=Some(DEFAULT_TIMEOUT), trust_env=true, base_url=None,
+        codecs=None, request_encoding=None, url_credentials=true, transport=None,
+        send_content_digest=None, allow_sni_mismatch=false, alpn_protocols=None,
+        tls_session_tickets=true, tls_session_cache_size=None, verify_hook=None))]
     fn new(
-        auth: Option<(String, Option<String>)>,
+        auth: Option<Auth>,
         auth_bearer: Option<String>,
         params: Option<IndexMapSSR>,
-        headers: Option<IndexMapSSR>,
+        headers: Option<IndexMap<String, HeaderValueArg, RandomState>>,
         cookies: Option<IndexMapSSR>,
         cookie_store: Option<bool>,
         referer: Option<bool>,
@@ -135,14 +495,82 @@ impl RClient {
         client_pem_data: Option<Vec<u8>>,
         https_only: Option<bool>,
         http2_only: Option<bool>,
+        max_download_rate: Option<Rate>,
+        max_upload_rate: Option<Rate>,
+        low_speed_limit: Option<u64>,
+        low_speed_time: Option<f64>,
+        dns_cache_ttl: Option<f64>,
+        dns_cache_negative_ttl: Option<f64>,
+        reject_third_party_cookies: Option<bool>,
+        blocked_cookie_domains: Option<Vec<String>>,
+        max_cookies_per_domain: Option<usize>,
+        max_cookie_size: Option<usize>,
+        cookie_public_suffix: Option<bool>,
+        auth_mode: Option<String>,
+        sign: Option<Py<PyAny>>,
+        history_size: Option<usize>,
+        respect_robots_txt: bool,
+        robots_cache_ttl: f64,
+        allow_hosts: Option<Vec<String>>,
+        block_hosts: Option<Vec<String>>,
+        block_private_ips: bool,
+        allowed_schemes: Option<Vec<String>>,
+        max_header_bytes: Option<usize>,
+        max_header_count: Option<usize>,
+        max_decompressed_size: Option<u64>,
+        max_decompression_ratio: Option<f64>,
+        json_serializer: Option<Py<PyAny>>,
+        json_canonical: bool,
+        json_sort_keys: bool,
+        json_ensure_ascii: bool,
+        json_separators: Option<(String, String)>,
+        json_indent: Option<usize>,
+        on_connection_event: Option<Py<PyAny>>,
+        http2_keep_alive_interval: Option<f64>,
+        http2_keep_alive_timeout: Option<f64>,
+        http2_keep_alive_while_idle: Option<bool>,
+        retry_idempotent_on_connection_error: bool,
+        cookie_merge: Option<String>,
+        connect_timeout: Option<f64>,
+        read_timeout: Option<f64>,
+        trust_env: bool,
+        base_url: Option<String>,
+        codecs: Option<CodecArgs>,
+        request_encoding: Option<String>,
+        url_credentials: bool,
+        transport: Option<Py<PythonTransport>>,
+        send_content_digest: Option<String>,
+        allow_sni_mismatch: bool,
+        alpn_protocols: Option<Vec<String>>,
+        tls_session_tickets: bool,
+        tls_session_cache_size: Option<usize>,
+        verify_hook: Option<Py<PyAny>>,
     ) -> PyResult<Self> {
         if client_pem.is_some() && client_pem_data.is_some() {
             return Err(PyValueError::new_err(
                 "Only one of client_pem or client_pem_data may be set.",
             ));
         }
-        // Client builder
-        let mut client_builder = reqwest::Client::builder();
+        // Defaults from HTTPR_* environment variables for arguments left out
+        let env = if trust_env {
+            EnvConfig::from_env().map_err(|e| PyValueError::new_err(format!("{:#}", e)))?
+        } else {
+            EnvConfig::default()
+        };
+        let (headers, lazy_headers) = match headers {
+            Some(headers) => {
+                let (values, lazy) = LazyHeaders::split(headers)?;
+                (Some(values), lazy)
+            }
+            None => (None, LazyHeaders::default()),
+        };
+        let mut headers = env.headers(headers);
+        // A callable overrides the environment default of its header
+        if let Some(headers) = &mut headers {
+            headers.retain(|name, _| !lazy_headers.contains(name));
+        }
+        let timeout = timeout.or(env.timeout);
+        let verify = verify.or(env.verify).unwrap_or(true);
 
         // Headers || Cookies
         let headers_headermap = if headers.is_some() || cookies.is_some() {
@@ -156,119 +584,281 @@ impl RClient {
                         .map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?,
                 );
             }
-            client_builder = client_builder.default_headers(headers_headermap.clone());
             headers_headermap
         } else {
             reqwest::header::HeaderMap::new()
         };
 
         // Cookie_store
-        if cookie_store.unwrap_or(true) {
-            client_builder = client_builder.cookie_store(true);
-        }
+        let cookie_policy = CookiePolicy::from_args(
+            reject_third_party_cookies,
+            blocked_cookie_domains,
+            max_cookies_per_domain,
+            max_cookie_size,
+            cookie_public_suffix,
+        )
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let jar: Option<Arc<dyn reqwest::cookie::CookieStore>> =
+            match (cookie_store.unwrap_or(true), cookie_policy) {
+                (true, Some(policy)) => Some(Arc::new(PolicyJar::new(policy))),
+                (true, None) => Some(Arc::new(Jar::default())),
+                (false, Some(_)) => {
+                    return Err(PyValueError::new_err(
+                        "Cookie policy options require cookie_store=True.",
+                    ))
+                }
+                (false, None) => None,
+            };
+        // Installed even without a store, to collect the cookies set on redirects
+        let cookie_provider = Arc::new(RecordingJar::new(jar));
+        // A transport replaces the connections, and with them reqwest's cookie handling
+        let transport = transport.map(|transport| {
+            Transport::new(
+                transport.get(),
+                cookie_provider.clone() as Arc<dyn reqwest::cookie::CookieStore>,
+            )
+        });
 
-        // Referer
-        if referer.unwrap_or(true) {
-            client_builder = client_builder.referer(true);
-        }
+        let proxy = proxy.or(env.proxy);
 
-        // Proxy
-        let proxy = proxy.or_else(|| std::env::var("HTTPR_PROXY").ok());
-        if let Some(proxy) = &proxy {
-            client_builder =
-                client_builder.proxy(reqwest::Proxy::all(proxy).map_err(map_reqwest_error)?);
-        }
+        let timeouts = Timeouts::from_args(timeout, connect_timeout, read_timeout)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
 
-        // Timeout
-        if let Some(seconds) = timeout {
-            client_builder = client_builder.timeout(Duration::from_secs_f64(seconds));
-        }
+        let decompression =
+            DecompressionGuard::from_args(max_decompressed_size, max_decompression_ratio)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?
+                .unwrap_or_default();
 
-        // Redirects
-        if follow_redirects.unwrap_or(true) {
-            client_builder = client_builder.redirect(Policy::limited(max_redirects.unwrap_or(20)));
-        } else {
-            client_builder = client_builder.redirect(Policy::none());
-        }
+        // Scheme and host restrictions, checked for the request itself, redirect targets and
+        // resolved addresses
+        let hosts = Arc::new(
+            HostPolicy::from_args(allowed_schemes, allow_hosts, block_hosts, block_private_ips)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+        );
 
-        // Ca_cert_file. BEFORE!!! verify (fn load_ca_certs() reads env var HTTPR_CA_BUNDLE)
-        if let Some(ca_bundle_path) = &ca_cert_file {
-            std::env::set_var("HTTPR_CA_BUNDLE", ca_bundle_path);
-        }
+        // Redirects are followed per request, see `RequestSpec::dispatch`
+        let redirects = Redirects {
+            follow: follow_redirects.unwrap_or(true),
+            max: max_redirects.unwrap_or(20),
+            referer: referer.unwrap_or(true),
+        };
 
-        // Verify
-        if verify.unwrap_or(true) {
-            client_builder = client_builder.tls_built_in_root_certs(true);
-            for cert in load_ca_certs().map_err(map_anyhow_error)? {
-                client_builder = client_builder.add_root_certificate(cert);
-            }
+        // Ca_cert_file, or else the HTTPR_CA_BUNDLE environment variable
+        let ca_certs = if verify {
+            load_ca_certs(ca_cert_file.as_deref()).map_err(map_anyhow_error)?
         } else {
-            client_builder = client_builder.danger_accept_invalid_certs(true);
-        }
+            Vec::new()
+        };
 
-        // Client mTLS identity must be applied regardless of `verify`: disabling
-        // server verification doesn't imply disabling client authentication.
-        let client_identity_pem = if let Some(pem_data) = &client_pem_data {
-            Some(pem_data.clone())
+        let identity_pem = if let Some(pem_data) = client_pem_data {
+            Some(pem_data)
         } else if let Some(pem_path) = &client_pem {
             Some(fs::read(pem_path).map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?)
         } else {
             None
         };
 
-        if let Some(pem_bytes) = client_identity_pem {
-            let identity = Identity::from_pem(&pem_bytes).map_err(map_reqwest_error)?;
-            client_builder = client_builder.identity(identity);
-        }
+        let tls_options = TlsOptions::from_args(
+            alpn_protocols,
+            http2_only.unwrap_or(false),
+            tls_session_tickets,
+            tls_session_cache_size,
+            verify_hook.map(VerifyHook::new).transpose()?,
+        )
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
 
-        // Https_only
-        if let Some(true) = https_only {
-            client_builder = client_builder.https_only(true);
-        }
+        let keep_alive = Http2KeepAlive::from_args(
+            http2_keep_alive_interval,
+            http2_keep_alive_timeout,
+            http2_keep_alive_while_idle,
+        )
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
 
-        // Http2_only
-        if let Some(true) = http2_only {
-            client_builder = client_builder.http2_prior_knowledge();
-        }
-        let client = Arc::new(Mutex::new(
-            client_builder.build().map_err(map_reqwest_error)?,
-        ));
+        let dns_cache = DnsCache::from_args(dns_cache_ttl, dns_cache_negative_ttl)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        let connection_events = on_connection_event.map(ConnectionEvents::new).transpose()?;
+
+        // Kept to build the client again when the proxy changes
+        let settings = ClientSettings {
+            cookie_provider,
+            trust_env,
+            http_proxy: env.http_proxy,
+            https_proxy: env.https_proxy,
+            timeouts,
+            hosts: Arc::clone(&hosts),
+            verify,
+            ca_certs,
+            identity_pem,
+            tls_options,
+            https_only: https_only.unwrap_or(false),
+            http2_only: http2_only.unwrap_or(false),
+            keep_alive,
+            dns_cache: dns_cache.clone(),
+            connection_events: connection_events.clone(),
+        };
+        let client = Arc::new(Mutex::new(settings.build(proxy.as_deref())?));
         let headers = Arc::new(Mutex::new(headers_headermap));
 
         Ok(RClient {
             client,
+            settings,
             headers,
+            lazy_headers: Arc::new(Mutex::new(lazy_headers)),
             auth,
             auth_bearer,
             params,
             proxy,
-            timeout,
+            base_url: base_url
+                .map(|base_url| parse_base_url(&base_url))
+                .transpose()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            timeout: timeouts.total,
+            connect_timeout: timeouts.connect,
+            read_timeout: timeouts.read,
+            max_download_rate: rate_limiter(max_download_rate)?,
+            max_upload_rate: rate_limiter(max_upload_rate)?,
+            low_speed: LowSpeed::from_args(low_speed_limit, low_speed_time)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            dns_cache,
+            auth_mode: auth_mode
+                .map(|name| AuthMode::from_name(&name))
+                .transpose()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?
+                .unwrap_or_default(),
+            cookie_merge: cookie_merge
+                .map(|name| CookieMerge::from_name(&name))
+                .transpose()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?
+                .unwrap_or_default(),
+            sign: sign.map(SignHook::new).transpose()?,
+            history: history_size
+                .map(History::new)
+                .transpose()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            robots: respect_robots_txt
+                .then(|| RobotsCache::new(robots_cache_ttl))
+                .transpose()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            hosts,
+            redirects,
+            header_limits: HeaderLimits::from_args(max_header_bytes, max_header_count)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            decompression,
+            codecs: Codecs::from_args(codecs, request_encoding)?,
+            json_serializer: json_serializer.map(JsonSerializer::new).transpose()?,
+            json_format: JsonFormat::from_args(
+                json_canonical,
+                json_sort_keys,
+                json_ensure_ascii,
+                json_separators,
+                json_indent,
+            )?,
+            meter: Meter::default(),
+            connection_events,
+            transport,
+            content_digest: send_content_digest
+                .map(|name| ContentDigest::from_name(&name))
+                .transpose()
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            allow_sni_mismatch,
+            retry_idempotent: retry_idempotent_on_connection_error,
+            url_credentials,
         })
     }
 
+    /// Absolute URL that relative request URLs are appended to, or None.
     #[getter]
-    pub fn get_headers(&self) -> PyResult<IndexMapSSR> {
+    pub fn base_url(&self) -> Option<String> {
+        self.base_url.as_ref().map(Url::to_string)
+    }
+
+    /// Request body bytes sent by this client so far.
+    #[getter]
+    pub fn bytes_sent(&self) -> u64 {
+        self.meter.totals().sent()
+    }
+
+    /// Response body bytes received by this client so far, as transferred (before
+    /// content decoding).
+    #[getter]
+    pub fn bytes_received(&self) -> u64 {
+        self.meter.totals().received()
+    }
+
+    /// Response body bytes received by this client so far, after content decoding.
+    #[getter]
+    pub fn bytes_decompressed(&self) -> u64 {
+        self.meter.totals().decompressed()
+    }
+
+    /// Returns summaries of the most recent requests, oldest first, as dicts with `method`,
+    /// `url` (final URL after redirects), `status` (None if no response arrived), `started`
+    /// (Unix timestamp), `elapsed` (seconds until the response headers arrived) and `error`.
+    /// Empty when the client was created without `history_size`.
+    pub fn history<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.history
+            .as_ref()
+            .map(History::entries)
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| entry.to_dict(py))
+            .collect()
+    }
+
+    /// Forgets the recorded request history.
+    pub fn clear_history(&self) {
+        if let Some(history) = &self.history {
+            history.clear();
+        }
+    }
+
+    /// Drops every entry from the client's DNS cache, so the next connection to any
+    /// host resolves it again. Does nothing when the client has no DNS cache.
+    pub fn flush_dns(&self) {
+        if let Some(dns_cache) = &self.dns_cache {
+            dns_cache.flush();
+        }
+    }
+
+    #[getter]
+    pub fn get_headers<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
         let headers = self
             .headers
             .lock()
             .map_err(|e| map_anyhow_error(anyhow!("Failed to acquire headers lock: {}", e)))?;
         let mut headers_clone = headers.clone();
         headers_clone.remove(COOKIE);
-        Ok(headers_clone.to_indexmap())
+        let dict = headers_clone.to_indexmap().into_pyobject(py)?;
+        self.lazy_headers
+            .lock()
+            .map_err(|e| map_anyhow_error(anyhow!("Failed to acquire headers lock: {}", e)))?
+            .add_to(&dict)?;
+        Ok(dict)
     }
 
     #[setter]
-    pub fn set_headers(&self, new_headers: Option<IndexMapSSR>) -> PyResult<()> {
+    pub fn set_headers(
+        &self,
+        new_headers: Option<IndexMap<String, HeaderValueArg, RandomState>>,
+    ) -> PyResult<()> {
+        let (new_headers, lazy_headers) = match new_headers {
+            Some(new_headers) => LazyHeaders::split(new_headers)?,
+            None => Default::default(),
+        };
         let mut headers = self
             .headers
             .lock()
             .map_err(|e| map_anyhow_error(anyhow!("Failed to acquire headers lock: {}", e)))?;
         headers.clear();
-        if let Some(new_headers) = new_headers {
-            for (k, v) in new_headers {
-                headers.insert_key_value(k, v).map_err(map_anyhow_error)?
-            }
+        for (k, v) in new_headers {
+            headers.insert_key_value(k, v).map_err(map_anyhow_error)?
         }
+        *self
+            .lazy_headers
+            .lock()
+            .map_err(|e| map_anyhow_error(anyhow!("Failed to acquire headers lock: {}", e)))? =
+            lazy_headers;
         Ok(())
     }
 
@@ -318,11 +908,7 @@ impl RClient {
 
     #[setter]
     pub fn set_proxy(&mut self, proxy: String) -> PyResult<()> {
-        let rproxy = reqwest::Proxy::all(proxy.clone()).map_err(map_reqwest_error)?;
-        let new_client = reqwest::Client::builder()
-            .proxy(rproxy)
-            .build()
-            .map_err(map_reqwest_error)?;
+        let new_client = self.settings.build(Some(&proxy))?;
         let mut client = self
             .client
             .lock()
@@ -340,16 +926,39 @@ impl RClient {
     /// * `method` - The HTTP method to use (e.g., "GET", "POST").
     /// * `url` - The URL to which the request will be made.
     /// * `params` - A map of query parameters to append to the URL. Default is None.
-    /// * `headers` - A map of HTTP headers to send with the request. Default is None.
+    /// * `headers` - A map of HTTP headers to send with the request, taking precedence over the client
+    ///         headers. A `None` value leaves out the client default or generated header. Default is None.
     /// * `cookies` - An optional map of cookies to send with requests as the `Cookie` header.
-    /// * `content` - The content to send in the request body as bytes. Default is None.
+    /// * `content` - The request body as `bytes`, `str`, any buffer-protocol object such as
+    ///         `bytearray`, `memoryview` or a NumPy array, or an iterator of `bytes` chunks, such as a
+    ///         generator, streamed as it produces them. Default is None.
     /// * `data` - The form data to send in the request body. Default is None.
     /// * `json` -  A JSON serializable object to send in the request body. Default is None.
     /// * `cbor` -  A CBOR serializable object to send in the request body. Default is None.
-    /// * `files` - A map of file fields to file paths to be sent as multipart/form-data. Default is None.
+    /// * `files` - File paths to send as multipart/form-data, as a map of field names to paths or a
+    ///         sequence of `(field, path)` pairs. Files are opened one at a time as the body is sent.
+    ///         Default is None.
     /// * `auth` - A tuple containing the username and an optional password for basic authentication. Default is None.
     /// * `auth_bearer` - A string representing the bearer token for bearer token authentication. Default is None.
-    /// * `timeout` - The timeout for the request in seconds. Default is 30.
+    /// * `timeout` - Total timeout for the request in seconds, or `None` for no total timeout. Default
+    ///         is the client `timeout`.
+    /// * `max_download_rate` - Cap on response body throughput for this request, in bytes per second
+    ///         or as a string like `"5MB/s"`. Default is the client setting.
+    /// * `max_upload_rate` - Cap on request body throughput for this request. Default is the client setting.
+    /// * `low_speed_limit` - Stalled-transfer threshold in bytes per second. Default is the client setting.
+    /// * `low_speed_time` - Window for `low_speed_limit` in seconds. Default is the client setting, or 30.
+    /// * `cancel_token` - A `CancellationToken` that aborts the request when cancelled. Default is None.
+    /// * `raw_query` - A percent-encoded query string appended to the URL verbatim, after `params`.
+    ///         Default is None.
+    /// * `encoding` - The codec used to encode a `str` `content`. Default is UTF-8.
+    /// * `on_file_progress` - A callable receiving a dict with the upload progress of each part of
+    ///         `files`. Default is None.
+    /// * `follow_redirects` - Whether to follow redirects for this request. Default is the client setting.
+    /// * `max_redirects` - The maximum number of redirects to follow for this request. Default is
+    ///         the client setting.
+    /// * `sni_hostname` - Send an HTTPS request to this host, which is looked up, connected to and
+    ///         named in the TLS handshake, while the `Host` header names the host of the URL. A `Host`
+    ///         header differing from it needs the client's `allow_sni_mismatch`. Default is None.
     ///
     /// # Returns
     ///
@@ -367,157 +976,94 @@ impl RClient {
     /// * `ProxyError` - If proxy connection fails
     /// * `TooManyRedirects` - If too many redirects occur
     /// * `HTTPStatusError` - If HTTP status is 4xx or 5xx
+    /// * `RequestCancelled` - If the request is cancelled through `cancel_token`
     /// * `RequestError` - For other request failures
     #[pyo3(signature = (method, url, params=None, headers=None, cookies=None, content=None,
-        data=None, json=None, files=None, auth=None, auth_bearer=None, timeout=None))]
+        data=None, json=None, files=None, auth=None, auth_bearer=None, timeout=RequestTimeout::Client,
+        max_download_rate=None, max_upload_rate=None, low_speed_limit=None, low_speed_time=None,
+        cancel_token=None, raw_query=None, encoding=None, on_file_progress=None,
+        follow_redirects=None, max_redirects=None, sni_hostname=None))]
     fn request(
         &self,
         py: Python,
         method: &str,
         url: &str,
         params: Option<IndexMapSSR>,
-        headers: Option<IndexMapSSR>,
+        headers: Option<HeaderArgs>,
         cookies: Option<IndexMapSSR>,
-        content: Option<Vec<u8>>,
+        content: Option<&Bound<'_, PyAny>>,
         data: Option<&Bound<'_, PyAny>>,
         json: Option<&Bound<'_, PyAny>>,
-        files: Option<IndexMap<String, String>>,
-        auth: Option<(String, Option<String>)>,
+        files: Option<&Bound<'_, PyAny>>,
+        auth: Option<Auth>,
         auth_bearer: Option<String>,
-        timeout: Option<f64>,
+        timeout: RequestTimeout,
+        max_download_rate: Option<Rate>,
+        max_upload_rate: Option<Rate>,
+        low_speed_limit: Option<u64>,
+        low_speed_time: Option<f64>,
+        cancel_token: Option<CancellationToken>,
+        raw_query: Option<String>,
+        encoding: Option<String>,
+        on_file_progress: Option<Py<PyAny>>,
+        follow_redirects: Option<bool>,
+        max_redirects: Option<usize>,
+        sni_hostname: Option<String>,
     ) -> PyResult<Response> {
-        let client = Arc::clone(&self.client);
-        let method = Method::from_bytes(method.as_bytes())
-            .map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?;
-        let is_post_put_patch = matches!(method, Method::POST | Method::PUT | Method::PATCH);
-        let params = params.or_else(|| self.params.clone());
-        let data_value: Option<Value> = data
-            .map(depythonize)
-            .transpose()
-            .map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?;
-        let json_value: Option<Value> = json
-            .map(depythonize)
-            .transpose()
-            .map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?;
-        let auth = auth.or(self.auth.clone());
-        let auth_bearer = auth_bearer.or(self.auth_bearer.clone());
-        let timeout: Option<f64> = timeout.or(self.timeout);
+        let (headers, removed_headers, lazy_headers) = split_headers(headers)?;
+        let content_stream = content
+            .map(|content| ContentStream::from_content(content, encoding.as_deref()))
+            .transpose()?
+            .flatten();
+        let mut spec = self.request_spec(
+            method,
+            url,
+            params,
+            headers,
+            cookies,
+            content
+                .filter(|_| content_stream.is_none())
+                .map(|content| content_bytes(content, encoding.as_deref()))
+                .transpose()?,
+            data.map(depythonize).transpose(),
+            json.map(|json| to_json(json, self.json_serializer.as_ref()))
+                .transpose()?,
+            FileParts::from_args(files, on_file_progress)?,
+            auth,
+            auth_bearer,
+            timeout,
+            max_download_rate,
+            max_upload_rate,
+            low_speed_limit,
+            low_speed_time,
+        )?;
+        spec.content_stream = content_stream;
+        spec.cancel = cancel_token;
+        spec.set_header_args(removed_headers, lazy_headers);
+        spec.redirects = self
+            .redirects
+            .with_overrides(follow_redirects, max_redirects);
+        if let Some(raw_query) = &raw_query {
+            check_raw_query(raw_query).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        }
+        spec.raw_query = raw_query;
+        if let Some(sni_hostname) = &sni_hostname {
+            check_sni_hostname(sni_hostname).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        }
+        spec.sni_hostname = sni_hostname;
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
 
         let future = async {
-            // Create request builder
-            let mut request_builder = client
-                .lock()
-                .map_err(|e| anyhow!("Failed to acquire client lock: {}", e))?
-                .request(method, url);
-
-            // Params
-            if let Some(params) = params {
-                request_builder = request_builder.query(&params);
-            }
-
-            // Headers from client
-            let client_headers = self
-                .headers
-                .lock()
-                .map_err(|e| anyhow!("Failed to acquire headers lock: {}", e))?
-                .clone();
-            request_builder = request_builder.headers(client_headers.clone());
-
-            // Headers
-            let mut combined_headers = client_headers;
-            if let Some(ref headers) = headers {
-                let header_map = headers.to_headermap();
-                for (key, value) in header_map.iter() {
-                    combined_headers.insert(key.clone(), value.clone());
-                }
-                request_builder = request_builder.headers(headers.to_headermap());
-            }
-
-            // Cookies
-            if let Some(cookies) = cookies {
-                request_builder = request_builder.header(
-                    COOKIE,
-                    HeaderValue::from_str(&cookies.to_string()).map_err(anyhow::Error::new)?,
-                );
-            }
-
-            // Only if method POST || PUT || PATCH
-            if is_post_put_patch {
-                // Content
-                if let Some(content) = content {
-                    request_builder = request_builder.body(content);
-                }
-                // Data
-                if let Some(form_data) = data_value {
-                    request_builder = request_builder.form(&form_data);
-                }
-                // Json - always serialize as JSON regardless of Accept header
-                if let Some(json_data) = json_value {
-                    request_builder = request_builder.json(&json_data);
-                }
-                // Files
-                if let Some(files) = files {
-                    let mut form = multipart::Form::new();
-                    for (file_name, file_path) in files {
-                        let file = File::open(file_path).await.map_err(anyhow::Error::new)?;
-                        let stream = FramedRead::new(file, BytesCodec::new());
-                        let file_body = Body::wrap_stream(stream);
-                        let part = multipart::Part::stream(file_body).file_name(file_name.clone());
-                        form = form.part(file_name, part);
-                    }
-                    request_builder = request_builder.multipart(form);
-                }
-            }
-
-            // Auth
-            if let Some((username, password)) = auth {
-                request_builder = request_builder.basic_auth(username, password);
-            } else if let Some(token) = auth_bearer {
-                request_builder = request_builder.bearer_auth(token);
-            }
-
-            // Timeout
-            if let Some(seconds) = timeout {
-                request_builder = request_builder.timeout(Duration::from_secs_f64(seconds));
-            }
-
-            // Send the request and await the response
-            let resp = request_builder.send().await.map_err(anyhow::Error::new)?;
-
-            // Response items
-            let cookies: IndexMapSSR = resp
-                .cookies()
-                .map(|cookie| (cookie.name().to_string(), cookie.value().to_string()))
-                .collect();
-            let headers: IndexMapSSR = resp.headers().to_indexmap();
-            let status_code = resp.status().as_u16();
-            let url = resp.url().to_string();
-            let buf = resp.bytes().await.map_err(anyhow::Error::new)?;
-
-            tracing::info!("response: {} {} {}", url, status_code, buf.len());
-            Ok::<(Bytes, IndexMapSSR, IndexMapSSR, u16, String), anyhow::Error>((
-                buf,
-                cookies,
-                headers,
-                status_code,
-                url,
-            ))
+            let resp = spec.send(client, client_headers).await?;
+            ResponseParts::read(resp).await
         };
 
         // Execute an async future, releasing the Python GIL for concurrency.
         // Use Tokio global runtime to block on the future.
-        let result = py.detach(|| RUNTIME.block_on(future));
-        let (f_buf, f_cookies, f_headers, f_status_code, f_url) =
-            result.map_err(map_anyhow_error)?;
+        let result = block_on(py, || future)?;
+        let parts = result.map_err(map_anyhow_error)?;
 
-        Ok(Response {
-            content: PyBytes::new(py, &f_buf).unbind(),
-            cookies: f_cookies,
-            encoding: String::new(),
-            headers: CaseInsensitiveHeaderMap::from_indexmap(f_headers),
-            status_code: f_status_code,
-            url: f_url,
-        })
+        Ok(Response::from_parts(py, parts))
     }
 
     /// Constructs an HTTP request and returns a StreamingResponse for iterating over chunks.
@@ -542,126 +1088,86 @@ impl RClient {
     ///         process(chunk)
     /// ```
     #[pyo3(signature = (method, url, params=None, headers=None, cookies=None, content=None,
-        data=None, json=None, files=None, auth=None, auth_bearer=None, timeout=None))]
+        data=None, json=None, files=None, auth=None, auth_bearer=None, timeout=RequestTimeout::Client,
+        max_download_rate=None, max_upload_rate=None, low_speed_limit=None, low_speed_time=None,
+        cancel_token=None, raw_query=None, encoding=None, on_file_progress=None,
+        follow_redirects=None, max_redirects=None, sni_hostname=None))]
     fn _stream(
         &self,
         py: Python,
         method: &str,
         url: &str,
         params: Option<IndexMapSSR>,
-        headers: Option<IndexMapSSR>,
+        headers: Option<HeaderArgs>,
         cookies: Option<IndexMapSSR>,
-        content: Option<Vec<u8>>,
+        content: Option<&Bound<'_, PyAny>>,
         data: Option<&Bound<'_, PyAny>>,
         json: Option<&Bound<'_, PyAny>>,
-        files: Option<IndexMap<String, String>>,
-        auth: Option<(String, Option<String>)>,
+        files: Option<&Bound<'_, PyAny>>,
+        auth: Option<Auth>,
         auth_bearer: Option<String>,
-        timeout: Option<f64>,
+        timeout: RequestTimeout,
+        max_download_rate: Option<Rate>,
+        max_upload_rate: Option<Rate>,
+        low_speed_limit: Option<u64>,
+        low_speed_time: Option<f64>,
+        cancel_token: Option<CancellationToken>,
+        raw_query: Option<String>,
+        encoding: Option<String>,
+        on_file_progress: Option<Py<PyAny>>,
+        follow_redirects: Option<bool>,
+        max_redirects: Option<usize>,
+        sni_hostname: Option<String>,
     ) -> PyResult<StreamingResponse> {
-        let client = Arc::clone(&self.client);
-        let method = Method::from_bytes(method.as_bytes())
-            .map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?;
-        let is_post_put_patch = matches!(method, Method::POST | Method::PUT | Method::PATCH);
-        let params = params.or_else(|| self.params.clone());
-        let data_value: Option<Value> = data
-            .map(depythonize)
-            .transpose()
-            .map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?;
-        let json_value: Option<Value> = json
-            .map(depythonize)
-            .transpose()
-            .map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?;
-        let auth = auth.or(self.auth.clone());
-        let auth_bearer = auth_bearer.or(self.auth_bearer.clone());
-        let timeout: Option<f64> = timeout.or(self.timeout);
+        let (headers, removed_headers, lazy_headers) = split_headers(headers)?;
+        let content_stream = content
+            .map(|content| ContentStream::from_content(content, encoding.as_deref()))
+            .transpose()?
+            .flatten();
+        let mut spec = self.request_spec(
+            method,
+            url,
+            params,
+            headers,
+            cookies,
+            content
+                .filter(|_| content_stream.is_none())
+                .map(|content| content_bytes(content, encoding.as_deref()))
+                .transpose()?,
+            data.map(depythonize).transpose(),
+            json.map(|json| to_json(json, self.json_serializer.as_ref()))
+                .transpose()?,
+            FileParts::from_args(files, on_file_progress)?,
+            auth,
+            auth_bearer,
+            timeout,
+            max_download_rate,
+            max_upload_rate,
+            low_speed_limit,
+            low_speed_time,
+        )?;
+        spec.content_stream = content_stream;
+        spec.cancel = cancel_token;
+        spec.set_header_args(removed_headers, lazy_headers);
+        spec.redirects = self
+            .redirects
+            .with_overrides(follow_redirects, max_redirects);
+        if let Some(raw_query) = &raw_query {
+            check_raw_query(raw_query).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        }
+        spec.raw_query = raw_query;
+        if let Some(sni_hostname) = &sni_hostname {
+            check_sni_hostname(sni_hostname).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        }
+        spec.sni_hostname = sni_hostname;
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
 
         let future = async {
-            // Create request builder
-            let mut request_builder = client
-                .lock()
-                .map_err(|e| anyhow!("Failed to acquire client lock: {}", e))?
-                .request(method, url);
-
-            // Params
-            if let Some(params) = params {
-                request_builder = request_builder.query(&params);
-            }
-
-            // Headers from client
-            let client_headers = self
-                .headers
-                .lock()
-                .map_err(|e| anyhow!("Failed to acquire headers lock: {}", e))?
-                .clone();
-            request_builder = request_builder.headers(client_headers.clone());
-
-            // Headers
-            let mut combined_headers = client_headers;
-            if let Some(ref headers) = headers {
-                let header_map = headers.to_headermap();
-                for (key, value) in header_map.iter() {
-                    combined_headers.insert(key.clone(), value.clone());
-                }
-                request_builder = request_builder.headers(headers.to_headermap());
-            }
-
-            // Cookies
-            if let Some(cookies) = cookies {
-                request_builder = request_builder.header(
-                    COOKIE,
-                    HeaderValue::from_str(&cookies.to_string()).map_err(anyhow::Error::new)?,
-                );
-            }
-
-            // Only if method POST || PUT || PATCH
-            if is_post_put_patch {
-                // Content
-                if let Some(content) = content {
-                    request_builder = request_builder.body(content);
-                }
-                // Data
-                if let Some(form_data) = data_value {
-                    request_builder = request_builder.form(&form_data);
-                }
-                // Json - always serialize as JSON regardless of Accept header
-                if let Some(json_data) = json_value {
-                    request_builder = request_builder.json(&json_data);
-                }
-                // Files
-                if let Some(files) = files {
-                    let mut form = multipart::Form::new();
-                    for (file_name, file_path) in files {
-                        let file = File::open(file_path).await.map_err(anyhow::Error::new)?;
-                        let stream = FramedRead::new(file, BytesCodec::new());
-                        let file_body = Body::wrap_stream(stream);
-                        let part = multipart::Part::stream(file_body).file_name(file_name.clone());
-                        form = form.part(file_name, part);
-                    }
-                    request_builder = request_builder.multipart(form);
-                }
-            }
-
-            // Auth
-            if let Some((username, password)) = auth {
-                request_builder = request_builder.basic_auth(username, password);
-            } else if let Some(token) = auth_bearer {
-                request_builder = request_builder.bearer_auth(token);
-            }
-
-            // Timeout
-            if let Some(seconds) = timeout {
-                request_builder = request_builder.timeout(Duration::from_secs_f64(seconds));
-            }
-
             // Send the request and await the response (but don't read body)
-            let resp = request_builder.send().await.map_err(anyhow::Error::new)?;
+            let resp = spec.send(client, client_headers).await?;
 
             // Response items (extract before we move resp)
-            let cookies: IndexMapSSR = resp
-                .cookies()
-                .map(|cookie| (cookie.name().to_string(), cookie.value().to_string()))
-                .collect();
+            let cookies = response_cookies(&resp);
             let headers: IndexMapSSR = resp.headers().to_indexmap();
             let status_code = resp.status().as_u16();
             let url = resp.url().to_string();
@@ -677,7 +1183,7 @@ impl RClient {
         };
 
         // Execute an async future, releasing the Python GIL for concurrency.
-        let result = py.detach(|| RUNTIME.block_on(future));
+        let result = block_on(py, || future)?;
         let (f_resp, f_cookies, f_headers, f_status_code, f_url) =
             result.map_err(map_anyhow_error)?;
 
@@ -689,18 +1195,1048 @@ impl RClient {
             f_url,
         ))
     }
+
+    /// Sends a prepared `Request` and returns the `Response`.
+    ///
+    /// Arguments left unset on the request fall back to the client defaults,
+    /// exactly as with `request()`.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// request = httpr.Request("GET", "https://httpbin.org/get", params={"q": "1"})
+    /// response = client.send(request)
+    /// ```
+    fn send(&self, py: Python, request: &Request) -> PyResult<Response> {
+        let spec = self.spec_from_request(request.clone())?;
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
+
+        let future = async {
+            let resp = spec.send(client, client_headers).await?;
+            ResponseParts::read(resp).await
+        };
+        let result = block_on(py, || future)?;
+        let parts = result.map_err(map_anyhow_error)?;
+
+        Ok(Response::from_parts(py, parts))
+    }
+
+    /// Renders `template` once per entry in `vars` and sends the resulting requests concurrently.
+    ///
+    /// All templates are rendered up front, so a missing variable fails the whole batch
+    /// before anything is sent. Responses are returned in the same order as `vars`.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - The `RequestTemplate` to render.
+    /// * `vars` - A list of dicts, one per request, mapping placeholder names to values.
+    /// * `max_concurrency` - Maximum number of requests in flight at once. Default is unlimited.
+    /// * `return_exceptions` - If `true`, failed requests are returned as exception instances
+    ///   in the result list instead of raising the first error. Default is `false`.
+    /// * `dedupe_on` - Request parts (`"method"`, `"url"`, `"headers"`, `"body"`) that make
+    ///   two rendered requests the same. Each distinct request is sent once and its response
+    ///   (the same object) or exception is returned for every entry. Default is no dedupe.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// template = httpr.RequestTemplate("PUT", "https://api.example.com/users/{user_id}",
+    ///                                  json={"id": "{user_id}", "active": True})
+    /// responses = client.send_template_many(template, [{"user_id": 1}, {"user_id": 2}])
+    /// ```
+    #[pyo3(signature = (template, vars, max_concurrency=None, return_exceptions=false, dedupe_on=None))]
+    fn send_template_many(
+        &self,
+        py: Python,
+        template: &RequestTemplate,
+        vars: Vec<Bound<'_, PyDict>>,
+        max_concurrency: Option<usize>,
+        return_exceptions: bool,
+        dedupe_on: Option<Vec<String>>,
+    ) -> PyResult<Vec<Py<PyAny>>> {
+        if max_concurrency == Some(0) {
+            return Err(PyValueError::new_err("max_concurrency must be at least 1"));
+        }
+        let dedupe_on = dedupe_on
+            .iter()
+            .flatten()
+            .map(|name| DedupeField::from_name(name))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let mut specs = vars
+            .iter()
+            .map(|vars| self.spec_from_request(template.render_with(vars)?))
+            .collect::<PyResult<Vec<_>>>()?;
+        let owners = if dedupe_on.is_empty() {
+            (0..specs.len()).collect()
+        } else {
+            let (firsts, owners) = dedupe_groups(specs.iter().map(|s| s.dedupe_key(&dedupe_on)));
+            let mut specs_by_index: Vec<_> = specs.into_iter().map(Some).collect();
+            specs = firsts
+                .into_iter()
+                .filter_map(|index| specs_by_index[index].take())
+                .collect();
+            owners
+        };
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
+        let semaphore = Arc::new(Semaphore::new(
+            max_concurrency.unwrap_or(Semaphore::MAX_PERMITS),
+        ));
+
+        let future = async move {
+            let mut tasks = JoinSet::new();
+            for (index, spec) in specs.into_iter().enumerate() {
+                let client = client.clone();
+                let client_headers = client_headers.clone();
+                let semaphore = Arc::clone(&semaphore);
+                tasks.spawn(async move {
+                    let result = async {
+                        let _permit = semaphore.acquire_owned().await?;
+                        let resp = spec.send(client, client_headers).await?;
+                        ResponseParts::read(resp).await
+                    }
+                    .await;
+                    (index, result)
+                });
+            }
+            let mut results: Vec<Option<anyhow::Result<ResponseParts>>> =
+                (0..tasks.len()).map(|_| None).collect();
+            while let Some(joined) = tasks.join_next().await {
+                let (index, result) = joined.map_err(anyhow::Error::new)?;
+                results[index] = Some(result);
+            }
+            Ok::<_, anyhow::Error>(results)
+        };
+        let results = block_on(py, || future)?.map_err(map_anyhow_error)?;
+
+        let results = results
+            .into_iter()
+            .flatten()
+            .map(|result| {
+                let parts = result.map_err(map_anyhow_error)?;
+                Ok(Response::from_parts(py, parts)
+                    .into_pyobject(py)?
+                    .into_any()
+                    .unbind())
+            })
+            .collect::<Vec<PyResult<Py<PyAny>>>>();
+        owners
+            .into_iter()
+            .map(|slot| match &results[slot] {
+                Ok(response) => Ok(response.clone_ref(py)),
+                Err(e) if return_exceptions => Ok(e.clone_ref(py).into_value(py).into_any()),
+                Err(e) => Err(e.clone_ref(py)),
+            })
+            .collect()
+    }
+
+    /// Fetches many URLs concurrently, yielding each response as it completes.
+    ///
+    /// Requests are sent in the background with at most `max_concurrency` in flight and
+    /// progress while the returned iterator is consumed. A failed request yields its
+    /// exception instead of raising, so one bad URL does not end a crawl. Dropping the
+    /// iterator cancels the requests not yet yielded.
+    ///
+    /// # Arguments
+    ///
+    /// * `urls` - The URLs to GET.
+    /// * `max_concurrency` - Maximum number of requests in flight. Default is 32.
+    /// * `ordered` - Yield results in the order of `urls` instead of completion order.
+    ///   Default is False.
+    /// * `params`, `headers`, `cookies`, `auth`, `auth_bearer`, `timeout` - As for `request()`,
+    ///   applied to every URL.
+    /// * `stream` - Yield a `StreamingResponse` for each URL instead of reading its body.
+    ///   A request counts towards `max_concurrency` until its body is read to the end or
+    ///   the response is closed, so at most that many streams are open at once.
+    ///   Default is False.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// for result in client.fetch_all(frontier, max_concurrency=16):
+    ///     if isinstance(result, Exception):
+    ///         continue
+    ///     print(result.url, result.status_code)
+    /// ```
+    #[pyo3(signature = (urls, max_concurrency=32, ordered=false, params=None, headers=None,
+        cookies=None, auth=None, auth_bearer=None, timeout=RequestTimeout::Client, stream=false))]
+    fn fetch_all(
+        &self,
+        urls: Vec<String>,
+        max_concurrency: usize,
+        ordered: bool,
+        params: Option<IndexMapSSR>,
+        headers: Option<HeaderArgs>,
+        cookies: Option<IndexMapSSR>,
+        auth: Option<Auth>,
+        auth_bearer: Option<String>,
+        timeout: RequestTimeout,
+        stream: bool,
+    ) -> PyResult<FetchIterator> {
+        if max_concurrency == 0 {
+            return Err(PyValueError::new_err("max_concurrency must be at least 1"));
+        }
+        let (headers, removed_headers, lazy_headers) = split_headers(headers)?;
+        let specs = urls
+            .iter()
+            .map(|url| {
+                let mut spec = self.request_spec(
+                    "GET",
+                    url,
+                    params.clone(),
+                    headers.clone(),
+                    cookies.clone(),
+                    None,
+                    Ok(None),
+                    None,
+                    None,
+                    auth.clone(),
+                    auth_bearer.clone(),
+                    timeout,
+                    None,
+                    None,
+                    None,
+                    None,
+                )?;
+                spec.set_header_args(removed_headers.clone(), lazy_headers.clone());
+                Ok(spec)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
+        Ok(FetchIterator::spawn(
+            specs,
+            client,
+            client_headers,
+            max_concurrency,
+            ordered,
+            stream,
+        ))
+    }
+
+    /// Creates a `RequestQueue` that sends submitted requests in the background.
+    ///
+    /// Requests start in priority order, earliest deadline first among equal priorities,
+    /// as the limits allow. Each `submit()` returns a `concurrent.futures.Future`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_concurrency` - Maximum number of requests in flight. Default is 16.
+    /// * `max_per_host` - Maximum number of requests in flight to one host. Default is None.
+    /// * `per_host_rate` - Maximum number of requests started per second to one host.
+    ///   Default is None.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// with client.queue(max_per_host=2, per_host_rate=5) as queue:
+    ///     futures = [queue.submit(httpr.Request("GET", url)) for url in urls]
+    /// responses = [future.result() for future in futures]
+    /// ```
+    #[pyo3(signature = (max_concurrency=16, max_per_host=None, per_host_rate=None))]
+    fn queue(
+        slf: &Bound<'_, Self>,
+        max_concurrency: usize,
+        max_per_host: Option<usize>,
+        per_host_rate: Option<f64>,
+    ) -> PyResult<RequestQueue> {
+        let limits = queue::Limits::from_args(max_concurrency, max_per_host, per_host_rate)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        RequestQueue::start(slf.clone().unbind(), limits)
+    }
+
+    /// Iterates over the items of a paginated JSON API, requesting pages lazily.
+    ///
+    /// Each page is parsed as JSON and the list at `item_path` is yielded item by item;
+    /// the next page is requested only once the current one is used up.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the first page.
+    /// * `strategy` - How the next page is requested:
+    ///   - `"cursor"`: send the value at `next_path` as the `param` query parameter; stops
+    ///     when it is missing, null or empty.
+    ///   - `"page"`: send page numbers `start`, `start + 1`, ... (default start 1).
+    ///   - `"offset"`: send the number of items received so far (default start 0).
+    ///   - `"link"`: follow the `Link: <...>; rel="next"` response header.
+    ///
+    ///   Page and offset pagination stop on an empty page, or one shorter than `page_size`.
+    /// * `item_path` - Dotted path to the items in each page, e.g. `"data.items"`.
+    ///   Default is None (the page itself is the list).
+    /// * `next_path` - Dotted path to the next cursor. Required for `"cursor"`.
+    /// * `param` - Query parameter carrying the cursor, page or offset. Default is the strategy name.
+    /// * `page_size` - Sent as the `limit_param` query parameter. Default is None.
+    /// * `limit_param` - Query parameter for `page_size`. Default is `"limit"`.
+    /// * `start` - First page number or offset.
+    /// * `max_pages` - Stop after this many pages. Default is None (no limit).
+    /// * `params`, `headers`, `cookies`, `auth`, `auth_bearer`, `timeout` - As for `request()`,
+    ///   applied to every page.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// for user in client.paginate("https://api.example.com/users", strategy="cursor",
+    ///                             item_path="data.items", next_path="data.next_cursor"):
+    ///     print(user["id"])
+    /// ```
+    #[pyo3(signature = (url, strategy="cursor", item_path=None, next_path=None, param=None,
+        page_size=None, limit_param=None, start=None, max_pages=None, params=None, headers=None,
+        cookies=None, auth=None, auth_bearer=None, timeout=RequestTimeout::Client))]
+    fn paginate(
+        &self,
+        url: &str,
+        strategy: &str,
+        item_path: Option<String>,
+        next_path: Option<String>,
+        param: Option<String>,
+        page_size: Option<u64>,
+        limit_param: Option<String>,
+        start: Option<u64>,
+        max_pages: Option<usize>,
+        params: Option<IndexMapSSR>,
+        headers: Option<HeaderArgs>,
+        cookies: Option<IndexMapSSR>,
+        auth: Option<Auth>,
+        auth_bearer: Option<String>,
+        timeout: RequestTimeout,
+    ) -> PyResult<Paginator> {
+        let options = PageOptions::new(
+            strategy,
+            item_path,
+            next_path,
+            param,
+            limit_param,
+            page_size,
+            start,
+            max_pages,
+        )
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let (headers, removed_headers, lazy_headers) = split_headers(headers)?;
+        let mut spec = self.request_spec(
+            "GET",
+            url,
+            params,
+            headers,
+            cookies,
+            None,
+            Ok(None),
+            None,
+            None,
+            auth,
+            auth_bearer,
+            timeout,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        spec.set_header_args(removed_headers, lazy_headers);
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
+        Ok(Paginator::new(client, client_headers, spec, options))
+    }
+
+    /// Fetches and parses the robots.txt file governing `url`.
+    ///
+    /// `url` may be any URL on the host; `/robots.txt` is requested from its origin. As
+    /// RFC 9309 specifies, a 5xx answer disallows everything and any other unsuccessful
+    /// status (such as 404) allows everything.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - A URL on the host whose rules are wanted.
+    /// * `headers`, `cookies`, `auth`, `auth_bearer`, `timeout` - As for `request()`.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// robots = client.robots("https://example.com/some/page")
+    /// if robots.allowed("mybot", "/some/page"):
+    ///     ...
+    /// ```
+    #[pyo3(signature = (url, headers=None, cookies=None, auth=None, auth_bearer=None, timeout=RequestTimeout::Client))]
+    fn robots(
+        &self,
+        py: Python,
+        url: &str,
+        headers: Option<HeaderArgs>,
+        cookies: Option<IndexMapSSR>,
+        auth: Option<Auth>,
+        auth_bearer: Option<String>,
+        timeout: RequestTimeout,
+    ) -> PyResult<RobotsTxt> {
+        let robots_url = robots::robots_url(url).map_err(map_anyhow_error)?;
+        let (headers, removed_headers, lazy_headers) = split_headers(headers)?;
+        let mut spec = self.request_spec(
+            "GET",
+            &robots_url,
+            None,
+            headers,
+            cookies,
+            None,
+            Ok(None),
+            None,
+            None,
+            auth,
+            auth_bearer,
+            timeout,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        spec.set_header_args(removed_headers, lazy_headers);
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
+        let future =
+            async { robots::from_response(spec.send(client, client_headers).await?).await };
+        block_on(py, || future)?.map_err(map_anyhow_error)
+    }
+
+    /// Iterates over the page URLs (`<loc>` entries) of a sitemap.
+    ///
+    /// Sitemap indexes are followed recursively, gzipped sitemaps are decompressed and
+    /// plain text sitemaps (one URL per line) are supported. Each sitemap is fetched
+    /// lazily, once the entries of the previous one have been used up.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the sitemap or sitemap index.
+    /// * `headers`, `cookies`, `auth`, `auth_bearer`, `timeout` - As for `request()`,
+    ///   applied to every sitemap.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// for page_url in client.sitemap("https://example.com/sitemap.xml"):
+    ///     print(page_url)
+    /// ```
+    #[pyo3(signature = (url, headers=None, cookies=None, auth=None, auth_bearer=None, timeout=RequestTimeout::Client))]
+    fn sitemap(
+        &self,
+        url: &str,
+        headers: Option<HeaderArgs>,
+        cookies: Option<IndexMapSSR>,
+        auth: Option<Auth>,
+        auth_bearer: Option<String>,
+        timeout: RequestTimeout,
+    ) -> PyResult<SitemapIterator> {
+        let (headers, removed_headers, lazy_headers) = split_headers(headers)?;
+        let mut spec = self.request_spec(
+            "GET",
+            url,
+            None,
+            headers,
+            cookies,
+            None,
+            Ok(None),
+            None,
+            None,
+            auth,
+            auth_bearer,
+            timeout,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        spec.set_header_args(removed_headers, lazy_headers);
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
+        Ok(SitemapIterator::new(client, client_headers, spec))
+    }
+
+    /// Downloads `url` to `path` using several concurrent byte-range requests.
+    ///
+    /// The server is probed with a `Range: bytes=0-0` request. When it honours ranges and
+    /// reports the total size, the file is split into up to `connections` ranges that are
+    /// fetched concurrently and written in place; otherwise the body is downloaded over a
+    /// single connection. Every range is checked against its `Content-Range`, length and
+    /// `ETag`, and the data is only moved to `path` once the whole file has been verified.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to download.
+    /// * `path` - Destination file path. Data is staged in `<path>.part`.
+    /// * `connections` - Maximum number of concurrent range requests. Default is 8.
+    /// * `params` - Query parameters. Default is None.
+    /// * `headers` - Extra request headers. Default is None.
+    /// * `cookies` - Request cookies. Default is None.
+    /// * `auth` - Basic auth credentials. Default is None.
+    /// * `auth_bearer` - Bearer token. Default is None.
+    /// * `timeout` - Timeout for each range request in seconds. Default is the client timeout.
+    /// * `sha256` - Expected hex SHA-256 digest of the complete file. Default is None.
+    /// * `max_download_rate` - Cap on the combined throughput of all ranges. Default is the client setting.
+    /// * `low_speed_limit` - Abort a range that stays below this many bytes per second. Default is the client setting.
+    /// * `low_speed_time` - Window for `low_speed_limit` in seconds. Default is the client setting, or 30.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// size = client.download_parallel("https://example.com/big.iso", "big.iso", connections=8)
+    /// ```
+    #[pyo3(signature = (url, path, connections=8, params=None, headers=None, cookies=None,
+        auth=None, auth_bearer=None, timeout=RequestTimeout::Client, sha256=None, max_download_rate=None, low_speed_limit=None, low_speed_time=None))]
+    fn download_parallel(
+        &self,
+        py: Python,
+        url: &str,
+        path: PathBuf,
+        connections: usize,
+        params: Option<IndexMapSSR>,
+        headers: Option<HeaderArgs>,
+        cookies: Option<IndexMapSSR>,
+        auth: Option<Auth>,
+        auth_bearer: Option<String>,
+        timeout: RequestTimeout,
+        sha256: Option<String>,
+        max_download_rate: Option<Rate>,
+        low_speed_limit: Option<u64>,
+        low_speed_time: Option<f64>,
+    ) -> PyResult<u64> {
+        if connections == 0 {
+            return Err(PyValueError::new_err("connections must be at least 1"));
+        }
+        let (headers, removed_headers, lazy_headers) = split_headers(headers)?;
+        let mut spec = self.request_spec(
+            "GET",
+            url,
+            params,
+            headers,
+            cookies,
+            None,
+            Ok(None),
+            None,
+            None,
+            auth,
+            auth_bearer,
+            timeout,
+            max_download_rate,
+            None,
+            low_speed_limit,
+            low_speed_time,
+        )?;
+        spec.set_header_args(removed_headers, lazy_headers);
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
+
+        let future =
+            download::download_parallel(spec, client, client_headers, &path, connections, sha256);
+        block_on(py, || future)?.map_err(map_anyhow_error)
+    }
+
+    /// Creates a `MultipartUploader` that uploads files in parts.
+    ///
+    /// With `url`, the S3 multipart upload API of that object is used: `POST ?uploads`
+    /// to initiate, `PUT ?partNumber=N&uploadId=ID` for each part, and `POST ?uploadId=ID`
+    /// to complete. Google Cloud Storage's XML API and S3-compatible stores speak it too.
+    /// Any step can be replaced by a callable; without `url`, `initiate`, `part_url` and
+    /// `complete` are required.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the object to upload to. Default is None.
+    /// * `initiate` - Callable returning the upload ID. Default is None.
+    /// * `part_url` - URL of each part: a template with `{upload_id}` (URL-encoded) and
+    ///   `{part_number}` placeholders, or a callable taking both. Default is None.
+    /// * `complete` - Callable taking the upload ID and the list of uploaded parts (dicts with
+    ///   `part_number`, `etag` and `size`); its return value is the upload's `result`.
+    ///   Default is None.
+    /// * `abort` - Callable taking the upload ID, called if the upload fails. Default is None.
+    /// * `part_size` - Size of each part in bytes. Default is 8 MiB.
+    /// * `max_concurrency` - Maximum number of parts sent at once. Default is 4.
+    /// * `part_retries` - How often a part is sent again after a connection error, timeout, or
+    ///   408, 429 or 5xx response. Default is 3.
+    /// * `headers` - Extra headers for every request of the upload. Default is None.
+    /// * `timeout` - Timeout for each request in seconds. Default is the client timeout.
+    /// * `max_upload_rate` - Cap on the combined throughput of all parts. Default is the client setting.
+    /// * `on_progress` - Callable receiving a dict after each part is uploaded. Default is None.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// uploader = client.multipart_uploader("https://bucket.s3.amazonaws.com/big.iso", part_size=16 * 2**20)
+    /// result = uploader.upload("big.iso")
+    /// ```
+    #[pyo3(signature = (url=None, *, initiate=None, part_url=None, complete=None, abort=None,
+        part_size=8 * 1024 * 1024, max_concurrency=4, part_retries=3, headers=None, timeout=RequestTimeout::Client,
+        max_upload_rate=None, on_progress=None))]
+    fn multipart_uploader(
+        &self,
+        py: Python,
+        url: Option<&str>,
+        initiate: Option<Py<PyAny>>,
+        part_url: Option<PartUrl>,
+        complete: Option<Py<PyAny>>,
+        abort: Option<Py<PyAny>>,
+        part_size: u64,
+        max_concurrency: usize,
+        part_retries: u32,
+        headers: Option<IndexMapSSR>,
+        timeout: RequestTimeout,
+        max_upload_rate: Option<Rate>,
+        on_progress: Option<Py<PyAny>>,
+    ) -> PyResult<MultipartUploader> {
+        let options = UploadOptions::from_args(part_size, max_concurrency, part_retries)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let url = url.map(|url| self.resolve_url(url)).transpose()?;
+        let spec = self.request_spec(
+            "PUT",
+            url.as_deref().unwrap_or_default(),
+            None,
+            headers,
+            None,
+            None,
+            Ok(None),
+            None,
+            None,
+            None,
+            None,
+            timeout,
+            None,
+            max_upload_rate,
+            None,
+            None,
+        )?;
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
+        MultipartUploader::new(
+            py,
+            spec,
+            client,
+            client_headers,
+            url.as_deref(),
+            initiate,
+            part_url,
+            complete,
+            abort,
+            options,
+            on_progress,
+        )
+    }
+
+    /// Uploads the file at `path` with the tus resumable upload protocol (tus.io).
+    ///
+    /// The upload is created with a `POST` to `url` announcing its length and metadata,
+    /// then the file is sent in `PATCH` requests of `chunk_size` bytes. When a chunk fails
+    /// with a connection error, a timeout, or a 408, 409, 429, 460 or 5xx response, the
+    /// server is asked for its offset with `HEAD` and the upload continues from there. An
+    /// upload interrupted for good can be resumed later by passing its `upload_url`.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The tus creation endpoint.
+    /// * `path` - The file to upload.
+    /// * `chunk_size` - Size of each `PATCH` body in bytes. Default is 4 MiB.
+    /// * `metadata` - `Upload-Metadata` items, e.g. the file name. Default is None.
+    /// * `upload_url` - URL of an upload created earlier, to resume instead of creating one.
+    ///   Default is None.
+    /// * `checksum` - `Upload-Checksum` algorithm for each chunk, `"sha1"`, `"sha256"` or
+    ///   `"sha512"`. Default is None.
+    /// * `retries` - How often the upload continues after failed chunks in a row. Default is 3.
+    /// * `headers` - Extra headers for every request of the upload. Default is None.
+    /// * `timeout` - Timeout for each request in seconds. Default is the client timeout.
+    /// * `max_upload_rate` - Cap on the upload throughput. Default is the client setting.
+    /// * `on_progress` - Callable receiving a dict once the upload exists and after each
+    ///   chunk. Default is None.
+    ///
+    /// # Returns
+    ///
+    /// A dict with the `upload_url`, the file `size` and the offset it `resumed_from`.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// result = client.upload_tus("https://tusd.example.com/files/", "video.mp4", metadata={"filename": "video.mp4"})
+    /// ```
+    #[pyo3(signature = (url, path, *, chunk_size=4 * 1024 * 1024, metadata=None, upload_url=None,
+        checksum=None, retries=3, headers=None, timeout=RequestTimeout::Client, max_upload_rate=None, on_progress=None))]
+    fn upload_tus<'py>(
+        &self,
+        py: Python<'py>,
+        url: &str,
+        path: PathBuf,
+        chunk_size: u64,
+        metadata: Option<IndexMapSSR>,
+        upload_url: Option<&str>,
+        checksum: Option<&str>,
+        retries: u32,
+        headers: Option<IndexMapSSR>,
+        timeout: RequestTimeout,
+        max_upload_rate: Option<Rate>,
+        on_progress: Option<Py<PyAny>>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let options = TusOptions::from_args(chunk_size, checksum, retries)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let metadata = metadata
+            .filter(|metadata| !metadata.is_empty())
+            .map(|metadata| encode_metadata(&metadata.into_iter().collect::<Vec<_>>()))
+            .transpose()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        if on_progress
+            .as_ref()
+            .is_some_and(|callback| !callback.bind(py).is_callable())
+        {
+            return Err(PyValueError::new_err("on_progress must be callable"));
+        }
+        let upload_url = upload_url.map(|url| self.resolve_url(url)).transpose()?;
+        let spec = self.request_spec(
+            "POST",
+            url,
+            None,
+            headers,
+            None,
+            None,
+            Ok(None),
+            None,
+            None,
+            None,
+            None,
+            timeout,
+            None,
+            max_upload_rate,
+            None,
+            None,
+        )?;
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
+        let future = tus::upload_tus(
+            spec,
+            client,
+            client_headers,
+            &path,
+            metadata,
+            upload_url,
+            options,
+            on_progress,
+        );
+        block_on(py, || future)?
+            .map_err(map_anyhow_error)?
+            .to_dict(py)
+    }
+
+    /// Sends a request with `Connection: Upgrade` and `Upgrade: <protocol>`, and once the
+    /// server agrees with `101 Switching Protocols`, hands over its connection as a raw
+    /// `Stream`, for protocols layered on HTTP upgrades.
+    ///
+    /// Upgrades exist in HTTP/1.1 only: for HTTPS servers offering HTTP/2, create the client
+    /// with `alpn_protocols=["http/1.1"]`.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The URL to upgrade.
+    /// * `protocol` - The `Upgrade` header value, e.g. `"websocket"` or `"h2c"`.
+    /// * `method` - The request method. Default is `"GET"`.
+    /// * `headers` - Extra headers for the handshake, such as protocol keys. Default is None.
+    /// * `timeout` - Timeout for the handshake in seconds. Default is the client timeout.
+    ///
+    /// # Raises
+    ///
+    /// * `RemoteProtocolError` - If the server answers with anything but 101.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// with client.upgrade("http://example.com/chat", "chat/1.0") as stream:
+    ///     stream.write(b"hello")
+    ///     print(stream.read())
+    /// ```
+    #[pyo3(signature = (url, protocol, *, method="GET", headers=None, timeout=RequestTimeout::Client))]
+    fn upgrade(
+        &self,
+        py: Python,
+        url: &str,
+        protocol: &str,
+        method: &str,
+        headers: Option<IndexMapSSR>,
+        timeout: RequestTimeout,
+    ) -> PyResult<Stream> {
+        let mut headers = headers.unwrap_or_default();
+        headers.insert("Connection".to_string(), "Upgrade".to_string());
+        headers.insert("Upgrade".to_string(), protocol.to_string());
+        let spec = self.request_spec(
+            method,
+            url,
+            None,
+            Some(headers),
+            None,
+            None,
+            Ok(None),
+            None,
+            None,
+            None,
+            None,
+            timeout,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
+        block_on(py, || {
+            upgrade::upgrade(spec, client, client_headers, protocol)
+        })?
+        .map_err(map_anyhow_error)
+    }
+
+    /// Opens a raw TCP tunnel to `host:port` as a `Stream`: through the client `proxy` with
+    /// `CONNECT`, or with `via_proxy=False` as a direct connection.
+    ///
+    /// A direct connection is checked against `allow_hosts`, `block_hosts`, `allowed_schemes`
+    /// and `block_private_ips` as an `http` URL, and resolves `host` like requests do, with
+    /// `dns_cache_ttl` and `block_private_ips` checking the resolved addresses.
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - The host to connect to.
+    /// * `port` - The port to connect to.
+    /// * `via_proxy` - Tunnel through the proxy requests to `http://host:port` would use:
+    ///   `proxy`, `HTTPR_HTTP_PROXY` or, with `trust_env`, the proxy environment variables.
+    ///   Default is True.
+    /// * `headers` - Extra headers for the `CONNECT` request. Default is None.
+    /// * `timeout` - Timeout for opening the tunnel in seconds. Default is the client timeout
+    ///   through a proxy, and `connect_timeout` for a direct connection.
+    ///
+    /// # Raises
+    ///
+    /// * `ValueError` - If `via_proxy` is set but no proxy applies to `host`.
+    /// * `ProxyError` - If the proxy answers `CONNECT` with anything but 2xx.
+    /// * `HostBlocked` - If a direct connection is refused by the host restrictions.
+    /// * `ConnectTimeout` - If a direct connection is not made within the timeout.
+    ///
+    /// # Example
+    ///
+    /// ```python
+    /// client = httpr.Client(proxy="http://proxy.example.com:3128")
+    /// with client.connect_tunnel("mail.example.com", 25) as stream:
+    ///     print(stream.read())
+    /// ```
+    #[pyo3(signature = (host, port, via_proxy=true, *, headers=None, timeout=RequestTimeout::Client))]
+    fn connect_tunnel(
+        &self,
+        py: Python,
+        host: &str,
+        port: u16,
+        via_proxy: bool,
+        headers: Option<IndexMapSSR>,
+        timeout: RequestTimeout,
+    ) -> PyResult<Stream> {
+        let authority = if host.contains(':') && !host.starts_with('[') {
+            format!("[{}]:{}", host, port)
+        } else {
+            format!("{}:{}", host, port)
+        };
+        let url = format!("http://{}/", authority);
+        let parsed = Url::parse(&url).map_err(|e| map_anyhow_error(e.into()))?;
+        if !via_proxy {
+            self.hosts
+                .check_url(&parsed)
+                .map_err(|e| map_anyhow_error(e.into()))?;
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+            let resolver = self.settings.resolver([]);
+            let timeout = timeout.or_client(self.connect_timeout);
+            return block_on(py, || upgrade::connect_direct(host, port, resolver, timeout))?
+                .map_err(map_anyhow_error);
+        }
+        if !self.settings.proxies_url(self.proxy.as_deref(), &parsed) {
+            return Err(PyValueError::new_err(
+                "connect_tunnel needs a proxy for this host; pass via_proxy=False to connect directly",
+            ));
+        }
+        let spec = self.request_spec(
+            "CONNECT",
+            &url,
+            None,
+            headers,
+            None,
+            None,
+            Ok(None),
+            None,
+            None,
+            None,
+            None,
+            timeout,
+            None,
+            None,
+            None,
+            None,
+        )?;
+        let (client, client_headers) = self.snapshot().map_err(map_anyhow_error)?;
+        block_on(py, || {
+            upgrade::connect_via_proxy(spec, client, client_headers, &authority)
+        })?
+        .map_err(map_anyhow_error)
+    }
+}
+
+impl RClient {
+    /// `url` resolved against the client `base_url`, if any.
+    fn resolve_url(&self, url: &str) -> PyResult<String> {
+        match &self.base_url {
+            Some(base_url) => join_base_url(base_url, url).map_err(map_anyhow_error),
+            None => Ok(url.to_string()),
+        }
+    }
+
+    /// Clone out the underlying client and the client-level default headers.
+    fn snapshot(&self) -> anyhow::Result<(reqwest::Client, reqwest::header::HeaderMap)> {
+        let client = self
+            .client
+            .lock()
+            .map_err(|e| anyhow!("Failed to acquire client lock: {}", e))?
+            .clone();
+        let headers = self
+            .headers
+            .lock()
+            .map_err(|e| anyhow!("Failed to acquire headers lock: {}", e))?
+            .clone();
+        Ok((client, headers))
+    }
+
+    /// Build a `RequestSpec` from per-request arguments, falling back to the client defaults.
+    fn request_spec(
+        &self,
+        method: &str,
+        url: &str,
+        params: Option<IndexMapSSR>,
+        headers: Option<IndexMapSSR>,
+        cookies: Option<IndexMapSSR>,
+        content: Option<Bytes>,
+        data: Result<Option<Value>, pythonize::PythonizeError>,
+        json: Option<Value>,
+        files: Option<FileParts>,
+        auth: Option<Auth>,
+        auth_bearer: Option<String>,
+        timeout: RequestTimeout,
+        max_download_rate: Option<Rate>,
+        max_upload_rate: Option<Rate>,
+        low_speed_limit: Option<u64>,
+        low_speed_time: Option<f64>,
+    ) -> PyResult<RequestSpec> {
+        let method = Method::from_bytes(method.as_bytes())
+            .map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?;
+        let data = data.map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?;
+        Ok(RequestSpec {
+            method,
+            url: self.resolve_url(url)?,
+            params: params.or_else(|| self.params.clone()),
+            headers,
+            removed_headers: Vec::new(),
+            lazy_headers: self
+                .lazy_headers
+                .lock()
+                .map_err(|e| map_anyhow_error(anyhow!("Failed to acquire headers lock: {}", e)))?
+                .clone(),
+            cookies,
+            cookie_merge: self.cookie_merge,
+            content,
+            content_stream: None,
+            data,
+            json,
+            json_format: self.json_format.clone(),
+            files,
+            auth: auth.or(self.auth.clone()),
+            auth_bearer: auth_bearer.or(self.auth_bearer.clone()),
+            timeout: timeout.or_client(self.timeout),
+            max_download_rate: rate_limiter(max_download_rate)?
+                .or_else(|| self.max_download_rate.clone()),
+            max_upload_rate: rate_limiter(max_upload_rate)?
+                .or_else(|| self.max_upload_rate.clone()),
+            low_speed: LowSpeed::with_defaults(low_speed_limit, low_speed_time, self.low_speed)
+                .map_err(|e| PyValueError::new_err(e.to_string()))?,
+            auth_mode: self.auth_mode,
+            sign: self.sign.clone(),
+            history: self.history.clone(),
+            robots: self.robots.clone(),
+            hosts: self.hosts.clone(),
+            redirects: self.redirects,
+            header_limits: self.header_limits,
+            decompression: self.decompression,
+            codecs: self.codecs.clone(),
+            meter: self.meter.clone(),
+            attempts: AttemptLog::default(),
+            connection_events: self.connection_events.clone(),
+            transport: self.transport.clone(),
+            content_digest: self.content_digest,
+            sni_hostname: None,
+            allow_sni_mismatch: self.allow_sni_mismatch,
+            retry_idempotent: self.retry_idempotent,
+            url_credentials: self.url_credentials,
+            cancel: None,
+            raw_query: None,
+        })
+    }
+
+    fn spec_from_request(&self, request: Request) -> PyResult<RequestSpec> {
+        let mut spec = self.request_spec(
+            &request.method,
+            &request.url,
+            request.params,
+            request.headers,
+            request.cookies,
+            request.content,
+            Ok(request.data),
+            request.json,
+            request.files,
+            request.auth,
+            request.auth_bearer,
+            request
+                .timeout
+                .map_or(RequestTimeout::Client, RequestTimeout::Seconds),
+            None,
+            None,
+            None,
+            None,
+        )?;
+        spec.set_header_args(request.removed_headers, request.lazy_headers);
+        Ok(spec)
+    }
+}
+
+/// A fresh `RateLimiter` for `rate`, if one was given.
+fn rate_limiter(rate: Option<Rate>) -> PyResult<Option<RateLimiter>> {
+    rate.map(|rate| Ok(RateLimiter::new(rate.bytes_per_second()?)))
+        .transpose()
 }
 
 #[pymodule(gil_used = false)]
-fn httpr(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
+fn httpr(py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     pyo3_log::init();
 
+    let threading = py.import("threading")?;
+    if threading
+        .call_method0("current_thread")?
+        .is(&threading.call_method0("main_thread")?)
+    {
+        let _ = MAIN_THREAD.set(thread::current().id());
+    }
+
     m.add_class::<RClient>()?;
+    m.add_class::<HmacAuth>()?;
+    m.add_class::<ApiKeyAuth>()?;
+    m.add_class::<CancellationToken>()?;
+    m.add_class::<Request>()?;
+    m.add_class::<RequestTemplate>()?;
     m.add_class::<Response>()?;
+    m.add_class::<ProblemDetails>()?;
+    m.add_class::<ServerTiming>()?;
+    m.add_class::<SniffResult>()?;
+    m.add_class::<PythonTransport>()?;
+    m.add_class::<TransportRequest>()?;
+    m.add_class::<RuntimeStats>()?;
+    m.add_class::<Paginator>()?;
+    m.add_class::<FetchIterator>()?;
+    m.add_class::<RequestQueue>()?;
+    m.add_class::<MultipartUploader>()?;
+    m.add_class::<RobotsTxt>()?;
+    m.add_class::<SitemapIterator>()?;
     m.add_class::<StreamingResponse>()?;
+    m.add_class::<Stream>()?;
     m.add_class::<CaseInsensitiveHeaderMap>()?;
     m.add_class::<TextIterator>()?;
     m.add_class::<LineIterator>()?;
+    m.add_class::<JsonIterator>()?;
+    m.add_class::<CsvIterator>()?;
+    m.add_class::<LocalServer>()?;
+    m.add_function(wrap_pyfunction!(presign_url, m)?)?;
+    m.add_function(wrap_pyfunction!(runtime_stats, m)?)?;
 
     // Register all exception types
     exceptions::register_exceptions(m)?;
@@ -0,0 +1,87 @@
+use anyhow::{bail, Result};
+use reqwest::header::HeaderMap;
+
+use crate::exceptions::RemoteProtocolError;
+
+/// Caps on the headers of each response (`max_header_bytes`, `max_header_count`).
+#[derive(Clone, Copy, Debug)]
+pub struct HeaderLimits {
+    max_bytes: Option<usize>,
+    max_count: Option<usize>,
+}
+
+impl HeaderLimits {
+    pub fn from_args(max_bytes: Option<usize>, max_count: Option<usize>) -> Result<Option<Self>> {
+        if max_bytes == Some(0) {
+            bail!("max_header_bytes must be positive");
+        }
+        if max_count == Some(0) {
+            bail!("max_header_count must be positive");
+        }
+        if max_bytes.is_none() && max_count.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(HeaderLimits {
+            max_bytes,
+            max_count,
+        }))
+    }
+
+    /// Fail with `RemoteProtocolError` if `headers` exceed the limits. Header bytes are
+    /// counted as on the wire in HTTP/1.1: `name: value\r\n`.
+    pub fn check(&self, headers: &HeaderMap) -> Result<()> {
+        if let Some(max_count) = self.max_count {
+            if headers.len() > max_count {
+                return Err(RemoteProtocolError::new_err(format!(
+                    "Response has {} headers, more than max_header_count ({})",
+                    headers.len(),
+                    max_count
+                ))
+                .into());
+            }
+        }
+        if let Some(max_bytes) = self.max_bytes {
+            let size = header_bytes(headers);
+            if size > max_bytes {
+                return Err(RemoteProtocolError::new_err(format!(
+                    "Response headers are {} bytes, more than max_header_bytes ({})",
+                    size, max_bytes
+                ))
+                .into());
+            }
+        }
+        Ok(())
+    }
+}
+
+fn header_bytes(headers: &HeaderMap) -> usize {
+    headers
+        .iter()
+        .map(|(name, value)| name.as_str().len() + value.len() + 4)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_from_args() {
+        assert!(HeaderLimits::from_args(None, None).unwrap().is_none());
+        assert!(HeaderLimits::from_args(Some(0), None).is_err());
+        assert!(HeaderLimits::from_args(None, Some(0)).is_err());
+        assert!(HeaderLimits::from_args(Some(1024), None).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_header_bytes() {
+        let mut headers = HeaderMap::new();
+        headers.insert("content-type", HeaderValue::from_static("text/plain"));
+        headers.append("set-cookie", HeaderValue::from_static("a=1"));
+        headers.append("set-cookie", HeaderValue::from_static("b=2"));
+        // "content-type: text/plain\r\n" and twice "set-cookie: a=1\r\n"
+        assert_eq!(header_bytes(&headers), 26 + 2 * 17);
+    }
+}
@@ -0,0 +1,153 @@
+use foldhash::fast::RandomState;
+use indexmap::IndexMap;
+use url::Url;
+
+type IndexMapSSR = IndexMap<String, String, RandomState>;
+
+/// One entry of a `Link` header (RFC 8288).
+#[derive(Debug, PartialEq)]
+pub struct Link {
+    pub target: String,
+    /// Parameters in order, names lowercased and values unquoted.
+    pub params: Vec<(String, String)>,
+}
+
+impl Link {
+    /// Whether the space-separated `rel` parameter contains `rel`, ignoring case.
+    pub fn has_rel(&self, rel: &str) -> bool {
+        self.param("rel")
+            .is_some_and(|rels| rels.split_whitespace().any(|r| r.eq_ignore_ascii_case(rel)))
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Parse the entries of a `Link` header value. Commas and semicolons inside `<...>`
+/// targets and quoted strings do not split; malformed entries are skipped.
+pub fn parse_links(value: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut rest = value;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        let Some(after) = rest.strip_prefix('<') else {
+            // Not a link: skip to the next top-level comma
+            match split_unquoted(rest, ',') {
+                (_, Some(next)) => {
+                    rest = next;
+                    continue;
+                }
+                (_, None) => return links,
+            }
+        };
+        let Some((target, after)) = after.split_once('>') else {
+            return links;
+        };
+        let (params, next) = split_unquoted(after, ',');
+        links.push(Link {
+            target: target.trim().to_string(),
+            params: parse_params(params),
+        });
+        match next {
+            Some(next) => rest = next,
+            None => return links,
+        }
+    }
+}
+
+/// The links of a response keyed by their `rel` (or target without one), each a dict
+/// of its parameters plus `url`, the target resolved against `base`.
+pub fn links_by_rel(value: &str, base: &str) -> IndexMap<String, IndexMapSSR, RandomState> {
+    let base = Url::parse(base).ok();
+    let mut links = IndexMap::with_hasher(RandomState::default());
+    for link in parse_links(value) {
+        let url = base
+            .as_ref()
+            .and_then(|base| base.join(&link.target).ok())
+            .map(String::from)
+            .unwrap_or_else(|| link.target.clone());
+        let key = link.param("rel").map(str::to_string).unwrap_or(url.clone());
+        let mut entry = IndexMap::with_hasher(RandomState::default());
+        entry.insert("url".to_string(), url);
+        entry.extend(link.params);
+        links.insert(key, entry);
+    }
+    links
+}
+
+/// `; name=value; name` parameters of a link.
+fn parse_params(params: &str) -> Vec<(String, String)> {
+    let mut parsed = Vec::new();
+    let mut rest = params;
+    loop {
+        let (param, next) = split_unquoted(rest, ';');
+        let (name, value) = param.split_once('=').unwrap_or((param, ""));
+        let name = name.trim().to_ascii_lowercase();
+        if !name.is_empty() && !parsed.iter().any(|(key, _)| *key == name) {
+            parsed.push((name, unquote(value.trim())));
+        }
+        match next {
+            Some(next) => rest = next,
+            None => break,
+        }
+    }
+    parsed
+}
+
+/// Split `value` at the first `separator` outside a quoted string.
+fn split_unquoted(value: &str, separator: char) -> (&str, Option<&str>) {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if quoted && c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            quoted = !quoted;
+        } else if c == separator && !quoted {
+            return (&value[..i], Some(&value[i + 1..]));
+        }
+    }
+    (value, None)
+}
+
+fn unquote(value: &str) -> String {
+    match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(inner) => inner.replace("\\\"", "\"").replace("\\\\", "\\"),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_links() {
+        let links = parse_links(
+            r#"<https://api.example.com/items?page=1,2>; rel="prev first"; title="a; b, c", </items?page=3>;REL=next, junk, <x>"#,
+        );
+        assert_eq!(links.len(), 3);
+        assert_eq!(links[0].target, "https://api.example.com/items?page=1,2");
+        assert_eq!(links[0].param("title"), Some("a; b, c"));
+        assert!(links[0].has_rel("FIRST"));
+        assert!(links[1].has_rel("next"));
+        assert_eq!(links[2].params, vec![]);
+
+        let by_rel = links_by_rel(
+            r#"</items?page=3>; rel="next", <https://cdn.example.com/a>"#,
+            "https://api.example.com/items?page=2",
+        );
+        assert_eq!(
+            by_rel["next"]["url"],
+            "https://api.example.com/items?page=3"
+        );
+        assert_eq!(by_rel["next"]["rel"], "next");
+        assert!(by_rel.contains_key("https://cdn.example.com/a"));
+    }
+}
@@ -0,0 +1,157 @@
+use std::io;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use futures_util::{stream, StreamExt};
+use http_body_util::BodyExt;
+use reqwest::Body;
+use tokio::time::{timeout_at, Instant};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Default `low_speed_time` when only `low_speed_limit` is given, in seconds.
+const DEFAULT_LOW_SPEED_TIME: f64 = 30.0;
+
+/// Abort a transfer whose throughput stays below `limit` bytes per second for `time`
+/// (curl's `--speed-limit` / `--speed-time`).
+#[derive(Clone, Copy)]
+pub struct LowSpeed {
+    limit: u64,
+    time: Duration,
+}
+
+impl LowSpeed {
+    /// Build from the Python arguments. Detection is enabled by `low_speed_limit`;
+    /// `low_speed_time` alone is an error since there would be nothing to compare against.
+    pub fn from_args(limit: Option<u64>, time: Option<f64>) -> Result<Option<Self>> {
+        let Some(limit) = limit else {
+            if time.is_some() {
+                bail!("low_speed_time requires low_speed_limit");
+            }
+            return Ok(None);
+        };
+        let time = time.unwrap_or(DEFAULT_LOW_SPEED_TIME);
+        if !time.is_finite() || time <= 0.0 {
+            bail!("low_speed_time must be a positive number of seconds");
+        }
+        if limit == 0 {
+            return Ok(None);
+        }
+        Ok(Some(LowSpeed {
+            limit,
+            time: Duration::from_secs_f64(time),
+        }))
+    }
+
+    /// Like `from_args`, filling unset arguments from the client-level `defaults`.
+    /// A per-request `low_speed_limit` of 0 disables detection for that request.
+    pub fn with_defaults(
+        limit: Option<u64>,
+        time: Option<f64>,
+        defaults: Option<LowSpeed>,
+    ) -> Result<Option<Self>> {
+        LowSpeed::from_args(
+            limit.or(defaults.map(|d| d.limit)),
+            time.or(defaults.map(|d| d.time.as_secs_f64())),
+        )
+    }
+
+    fn too_slow(&self, bytes: u64, elapsed: Duration) -> bool {
+        (bytes as f64) < self.limit as f64 * elapsed.as_secs_f64()
+    }
+
+    fn error(&self) -> BoxError {
+        Box::new(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!(
+                "transfer speed stayed below {} bytes/s for {:?} (low_speed_limit)",
+                self.limit, self.time
+            ),
+        ))
+    }
+
+    /// Wrap `body` so that reading it fails with a `TimedOut` error once a full
+    /// `time` window passes with fewer than `limit * time` bytes received.
+    ///
+    /// The window is checked on a timer, so a body that stops sending entirely is
+    /// caught as well as one that trickles.
+    pub fn watch_body(self, body: Body) -> Body {
+        struct State<S> {
+            inner: S,
+            window_start: Instant,
+            window_bytes: u64,
+            failed: bool,
+        }
+
+        let state = State {
+            inner: body.into_data_stream(),
+            window_start: Instant::now(),
+            window_bytes: 0,
+            failed: false,
+        };
+        Body::wrap_stream(stream::unfold(state, move |mut state| async move {
+            if state.failed {
+                return None;
+            }
+            loop {
+                let deadline = state.window_start + self.time;
+                let next = timeout_at(deadline, state.inner.next()).await;
+                let chunk = match next {
+                    Ok(None) => return None,
+                    Ok(Some(Err(e))) => return Some((Err(Box::new(e) as BoxError), state)),
+                    Ok(Some(Ok(chunk))) => {
+                        state.window_bytes += chunk.len() as u64;
+                        Some(chunk)
+                    }
+                    Err(_) => None,
+                };
+                let now = Instant::now();
+                if now >= deadline {
+                    if self.too_slow(state.window_bytes, now - state.window_start) {
+                        state.failed = true;
+                        return Some((Err(self.error()), state));
+                    }
+                    state.window_start = now;
+                    state.window_bytes = 0;
+                }
+                // No chunk means the window elapsed with enough data; keep waiting.
+                if let Some(chunk) = chunk {
+                    return Some((Ok(chunk), state));
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_args() {
+        assert!(LowSpeed::from_args(None, None).unwrap().is_none());
+        assert!(LowSpeed::from_args(Some(0), Some(5.0)).unwrap().is_none());
+        let low_speed = LowSpeed::from_args(Some(1024), None).unwrap().unwrap();
+        assert_eq!(low_speed.time, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_with_defaults() {
+        let client = LowSpeed::from_args(Some(1024), Some(10.0)).unwrap();
+        let request = LowSpeed::with_defaults(None, Some(5.0), client)
+            .unwrap()
+            .unwrap();
+        assert_eq!(request.limit, 1024);
+        assert_eq!(request.time, Duration::from_secs(5));
+        assert!(LowSpeed::with_defaults(Some(0), None, client)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_too_slow() {
+        let low_speed = LowSpeed::from_args(Some(1000), Some(2.0)).unwrap().unwrap();
+        assert!(low_speed.too_slow(1999, Duration::from_secs(2)));
+        assert!(!low_speed.too_slow(2000, Duration::from_secs(2)));
+    }
+}
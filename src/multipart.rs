@@ -0,0 +1,187 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use bytes::Bytes;
+use futures_util::{stream, Stream, TryStreamExt};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyMapping};
+use reqwest::{multipart, Body};
+use tokio::fs::File;
+use tokio_util::io::ReaderStream;
+
+/// Read size of file parts, and so the granularity of progress reports.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The files of `files=`, sent as a multipart/form-data body.
+///
+/// Each file is opened only when the body stream reaches its part and closed once it
+/// has been sent, so a request can carry thousands of files without holding as many
+/// descriptors.
+#[derive(Clone)]
+pub struct FileParts {
+    parts: Vec<(String, PathBuf)>,
+    /// Whether `files=` was a mapping rather than a sequence of pairs.
+    mapping: bool,
+    progress: Option<Arc<Py<PyAny>>>,
+}
+
+impl fmt::Debug for FileParts {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.parts).finish()
+    }
+}
+
+impl FileParts {
+    /// Build from `files=`, a mapping of field names to paths or a sequence of
+    /// `(field, path)` pairs (which may repeat a field), and `on_file_progress=`.
+    pub fn from_py(files: &Bound<'_, PyAny>, progress: Option<Py<PyAny>>) -> PyResult<Self> {
+        let py = files.py();
+        let (items, mapping) = match files.cast::<PyMapping>() {
+            Ok(mapping) => (mapping.items()?.into_any(), true),
+            Err(_) => (files.clone(), false),
+        };
+        let parts = items
+            .try_iter()?
+            .map(|item| item?.extract::<(String, PathBuf)>())
+            .collect::<PyResult<Vec<_>>>()
+            .map_err(|_| {
+                PyTypeError::new_err(
+                    "files must be a mapping of field names to paths, or a sequence of (field, path) pairs",
+                )
+            })?;
+        if let Some(callback) = &progress {
+            if !callback.bind(py).is_callable() {
+                return Err(PyValueError::new_err("on_file_progress must be callable"));
+            }
+        }
+        Ok(FileParts {
+            parts,
+            mapping,
+            progress: progress.map(Arc::new),
+        })
+    }
+
+    /// `files=` and `on_file_progress=` of a request, if files were given.
+    pub fn from_args(
+        files: Option<&Bound<'_, PyAny>>,
+        progress: Option<Py<PyAny>>,
+    ) -> PyResult<Option<Self>> {
+        match files {
+            Some(files) => Self::from_py(files, progress).map(Some),
+            None if progress.is_some() => {
+                Err(PyValueError::new_err("on_file_progress requires files"))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// `files=` with paths as `str`: a dict for a mapping, otherwise a list of pairs.
+    pub fn to_py<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let parts = self
+            .parts
+            .iter()
+            .map(|(field, path)| (field.as_str(), path.as_os_str()));
+        if self.mapping {
+            let dict = PyDict::new(py);
+            for (field, path) in parts {
+                dict.set_item(field, path)?;
+            }
+            return Ok(dict.into_any());
+        }
+        parts
+            .collect::<Vec<_>>()
+            .into_pyobject(py)
+            .map(Bound::into_any)
+    }
+
+    /// Build the form. Every file is checked to exist up front, so that a missing one
+    /// fails the request before anything is sent, but none is opened yet.
+    pub async fn form(&self) -> Result<multipart::Form> {
+        let mut form = multipart::Form::new();
+        for (index, (field, path)) in self.parts.iter().enumerate() {
+            tokio::fs::metadata(path)
+                .await
+                .with_context(|| format!("Failed to read file {}", path.display()))?;
+            let file_name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| field.clone());
+            let progress = self.progress.as_ref().map(|callback| PartProgress {
+                callback: Arc::clone(callback),
+                field: field.clone(),
+                file_name: file_name.clone(),
+                index,
+                parts: self.parts.len(),
+            });
+            let body = Body::wrap_stream(file_stream(path.clone(), progress));
+            form = form.part(
+                field.clone(),
+                multipart::Part::stream(body).file_name(file_name),
+            );
+        }
+        Ok(form)
+    }
+}
+
+/// The contents of the file at `path`, opened on the first poll.
+fn file_stream(
+    path: PathBuf,
+    progress: Option<PartProgress>,
+) -> impl Stream<Item = io::Result<Bytes>> {
+    stream::once(async move {
+        let file = File::open(&path).await.map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("Failed to open {}: {}", path.display(), e),
+            )
+        })?;
+        let total = file.metadata().await.ok().map(|metadata| metadata.len());
+        let mut sent = 0;
+        if let Some(progress) = &progress {
+            progress.report(sent, total);
+        }
+        Ok::<_, io::Error>(
+            ReaderStream::with_capacity(file, CHUNK_SIZE).inspect_ok(move |chunk| {
+                sent += chunk.len() as u64;
+                if let Some(progress) = &progress {
+                    progress.report(sent, total);
+                }
+            }),
+        )
+    })
+    .try_flatten()
+}
+
+/// Reports the progress of one part to the `on_file_progress` callback.
+struct PartProgress {
+    callback: Arc<Py<PyAny>>,
+    field: String,
+    file_name: String,
+    index: usize,
+    parts: usize,
+}
+
+impl PartProgress {
+    /// Call the callback with the bytes of the part sent so far. Exceptions it raises
+    /// are reported as unraisable instead of failing the upload.
+    fn report(&self, sent: u64, total: Option<u64>) {
+        Python::attach(|py| {
+            let dict = PyDict::new(py);
+            let result = dict
+                .set_item("field", &self.field)
+                .and_then(|_| dict.set_item("filename", &self.file_name))
+                .and_then(|_| dict.set_item("index", self.index))
+                .and_then(|_| dict.set_item("parts", self.parts))
+                .and_then(|_| dict.set_item("sent", sent))
+                .and_then(|_| dict.set_item("total", total))
+                .and_then(|_| self.callback.bind(py).call1((dict,)));
+            if let Err(err) = result {
+                err.write_unraisable(py, Some(self.callback.bind(py)));
+            }
+        });
+    }
+}
@@ -0,0 +1,439 @@
+use std::collections::VecDeque;
+
+use anyhow::{anyhow, bail, Result};
+use pyo3::prelude::*;
+use pythonize::pythonize;
+use reqwest::header::{HeaderMap, LINK};
+use serde_json::Value;
+use url::Url;
+
+use crate::block_on;
+use crate::exceptions::map_anyhow_error;
+use crate::links::parse_links;
+use crate::request::RequestSpec;
+
+type IndexMapSSR = indexmap::IndexMap<String, String, foldhash::fast::RandomState>;
+
+/// How the next page is requested.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    /// Send the cursor found at `next_path` in the previous page.
+    Cursor,
+    /// Send an increasing page number.
+    Page,
+    /// Send the number of items received so far.
+    Offset,
+    /// Follow the `Link: <...>; rel="next"` response header.
+    Link,
+}
+
+impl Strategy {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "cursor" => Ok(Strategy::Cursor),
+            "page" => Ok(Strategy::Page),
+            "offset" => Ok(Strategy::Offset),
+            "link" => Ok(Strategy::Link),
+            _ => bail!(
+                "strategy must be 'cursor', 'page', 'offset' or 'link', got {:?}",
+                name
+            ),
+        }
+    }
+
+    /// Query parameter carrying the position, unless overridden.
+    fn default_param(self) -> &'static str {
+        match self {
+            Strategy::Cursor => "cursor",
+            Strategy::Page => "page",
+            Strategy::Offset => "offset",
+            Strategy::Link => "",
+        }
+    }
+}
+
+/// Position of the next page to fetch.
+#[derive(Clone, Debug, PartialEq)]
+enum Position {
+    /// `None` for the first page, which is sent without a cursor.
+    Cursor(Option<String>),
+    Page(u64),
+    Offset(u64),
+    Url(String),
+}
+
+/// Pagination settings taken from the `paginate()` arguments.
+pub struct PageOptions {
+    pub strategy: Strategy,
+    /// Dotted path to the list of items in each page; `None` if the page is the list.
+    pub item_path: Option<String>,
+    /// Dotted path to the next cursor (cursor strategy only).
+    pub next_path: Option<String>,
+    pub param: String,
+    pub limit_param: String,
+    pub page_size: Option<u64>,
+    pub start: u64,
+    pub max_pages: Option<usize>,
+}
+
+impl PageOptions {
+    pub fn new(
+        strategy: &str,
+        item_path: Option<String>,
+        next_path: Option<String>,
+        param: Option<String>,
+        limit_param: Option<String>,
+        page_size: Option<u64>,
+        start: Option<u64>,
+        max_pages: Option<usize>,
+    ) -> Result<Self> {
+        let strategy = Strategy::from_name(strategy)?;
+        if strategy == Strategy::Cursor && next_path.is_none() {
+            bail!("the cursor strategy requires next_path");
+        }
+        if page_size == Some(0) {
+            bail!("page_size must be at least 1");
+        }
+        let start = start.unwrap_or(match strategy {
+            Strategy::Page => 1,
+            _ => 0,
+        });
+        Ok(PageOptions {
+            strategy,
+            item_path,
+            next_path,
+            param: param.unwrap_or_else(|| strategy.default_param().to_string()),
+            limit_param: limit_param.unwrap_or_else(|| "limit".to_string()),
+            page_size,
+            start,
+            max_pages,
+        })
+    }
+
+    fn first(&self, url: &str) -> Position {
+        match self.strategy {
+            Strategy::Cursor => Position::Cursor(None),
+            Strategy::Page => Position::Page(self.start),
+            Strategy::Offset => Position::Offset(self.start),
+            Strategy::Link => Position::Url(url.to_string()),
+        }
+    }
+
+    /// URL and query parameters of the page at `position`, given those of the request.
+    fn page_query(
+        &self,
+        url: String,
+        params: Option<IndexMapSSR>,
+        position: &Position,
+    ) -> (String, Option<IndexMapSSR>) {
+        let value = match position {
+            // The next link already carries the query
+            Position::Url(next) if *next != url => return (next.clone(), None),
+            Position::Url(_) => return (url, params),
+            Position::Cursor(cursor) => cursor.clone(),
+            Position::Page(number) | Position::Offset(number) => Some(number.to_string()),
+        };
+        let mut params = params.unwrap_or_default();
+        if let Some(page_size) = self.page_size {
+            params.insert(self.limit_param.clone(), page_size.to_string());
+        }
+        if let Some(value) = value {
+            params.insert(self.param.clone(), value);
+        }
+        (url, Some(params))
+    }
+
+    /// Where the page after `position` is, given the page received; `None` when done.
+    fn advance(
+        &self,
+        position: &Position,
+        page: &Value,
+        headers: &HeaderMap,
+        url: &Url,
+        items: usize,
+    ) -> Option<Position> {
+        let short_page = items == 0 || self.page_size.is_some_and(|size| (items as u64) < size);
+        match position {
+            Position::Cursor(previous) => {
+                let cursor = match lookup(page, self.next_path.as_deref()?)? {
+                    Value::String(cursor) if !cursor.is_empty() => cursor.clone(),
+                    Value::Number(cursor) => cursor.to_string(),
+                    _ => return None,
+                };
+                // A server repeating its cursor would loop forever
+                (previous.as_ref() != Some(&cursor)).then_some(Position::Cursor(Some(cursor)))
+            }
+            Position::Page(number) => (!short_page).then_some(Position::Page(number + 1)),
+            Position::Offset(offset) => {
+                (!short_page).then_some(Position::Offset(offset + items as u64))
+            }
+            Position::Url(current) => next_link(headers, url)
+                .filter(|next| next != current)
+                .map(Position::Url),
+        }
+    }
+}
+
+/// Value at a dotted `path` such as `data.items` or `results.0.rows`.
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|key| !key.is_empty())
+        .try_fold(value, |value, key| match value {
+            Value::Object(map) => map.get(key),
+            Value::Array(list) => list.get(key.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+/// The items of one page: the list at `item_path`, or the page itself.
+fn page_items(page: Value, item_path: Option<&str>) -> Result<Vec<Value>> {
+    let items = match item_path {
+        Some(path) => lookup(&page, path)
+            .cloned()
+            .ok_or_else(|| anyhow!("item_path {:?} is missing from the page", path))?,
+        None => page,
+    };
+    match items {
+        Value::Array(items) => Ok(items),
+        Value::Null => Ok(Vec::new()),
+        _ => bail!(
+            "item_path {:?} does not point to a list",
+            item_path.unwrap_or_default()
+        ),
+    }
+}
+
+/// Target of the `rel="next"` entry of the `Link` headers, resolved against `base`.
+fn next_link(headers: &HeaderMap, base: &Url) -> Option<String> {
+    headers
+        .get_all(LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(parse_links)
+        .find(|link| link.has_rel("next"))
+        .and_then(|link| base.join(&link.target).ok().map(String::from))
+}
+
+/// Iterator over the items of a paginated JSON API, returned by `client.paginate()`.
+///
+/// Pages are requested lazily: the next page is fetched only once every item of the
+/// previous one has been yielded.
+#[pyclass(module = "httpr")]
+pub struct Paginator {
+    client: reqwest::Client,
+    client_headers: HeaderMap,
+    spec: RequestSpec,
+    options: PageOptions,
+    position: Option<Position>,
+    items: VecDeque<Value>,
+    pages: usize,
+}
+
+impl Paginator {
+    pub fn new(
+        client: reqwest::Client,
+        client_headers: HeaderMap,
+        spec: RequestSpec,
+        options: PageOptions,
+    ) -> Self {
+        let position = options.first(&spec.url);
+        Paginator {
+            client,
+            client_headers,
+            spec,
+            options,
+            position: Some(position),
+            items: VecDeque::new(),
+            pages: 0,
+        }
+    }
+
+    /// Fetch the page at the current position and queue its items.
+    fn fetch(&mut self, py: Python, position: Position) -> PyResult<()> {
+        let mut spec = self.spec.clone();
+        (spec.url, spec.params) = self
+            .options
+            .page_query(spec.url, spec.params.take(), &position);
+        let client = self.client.clone();
+        let client_headers = self.client_headers.clone();
+        let future = async {
+            let resp = spec
+                .send(client, client_headers)
+                .await?
+                .error_for_status()?;
+            let headers = resp.headers().clone();
+            let url = resp.url().clone();
+            let body = resp.bytes().await?;
+            Ok::<_, anyhow::Error>((headers, url, body))
+        };
+        let (headers, url, body) = block_on(py, || future)?.map_err(map_anyhow_error)?;
+
+        let page: Value = serde_json::from_slice(&body)
+            .map_err(|e| map_anyhow_error(anyhow!("Failed to parse page as JSON: {}", e)))?;
+        let items = page_items(page.clone(), self.options.item_path.as_deref())
+            .map_err(map_anyhow_error)?;
+        self.position = self
+            .options
+            .advance(&position, &page, &headers, &url, items.len());
+        self.pages += 1;
+        self.items.extend(items);
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl Paginator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<Py<PyAny>>> {
+        loop {
+            if let Some(item) = self.items.pop_front() {
+                return Ok(Some(pythonize(py, &item)?.unbind()));
+            }
+            if self.options.max_pages.is_some_and(|max| self.pages >= max) {
+                return Ok(None);
+            }
+            let Some(position) = self.position.take() else {
+                return Ok(None);
+            };
+            self.fetch(py, position)?;
+        }
+    }
+
+    /// Number of pages fetched so far.
+    #[getter]
+    fn pages(&self) -> usize {
+        self.pages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+    use serde_json::json;
+
+    use super::*;
+
+    fn options(strategy: &str, page_size: Option<u64>) -> PageOptions {
+        PageOptions::new(
+            strategy,
+            Some("data.items".into()),
+            Some("data.next".into()),
+            None,
+            None,
+            page_size,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn base() -> Url {
+        "https://api.example.com/items?page=1".parse().unwrap()
+    }
+
+    #[test]
+    fn test_options() {
+        assert!(PageOptions::new("cursor", None, None, None, None, None, None, None).is_err());
+        assert!(PageOptions::new("pages", None, None, None, None, None, None, None).is_err());
+        let options = options("page", None);
+        assert_eq!(options.param, "page");
+        assert_eq!(options.first("u"), Position::Page(1));
+    }
+
+    #[test]
+    fn test_page_items() {
+        let page = json!({"data": {"items": [1, 2], "rows": [[3]]}});
+        assert_eq!(
+            page_items(page.clone(), Some("data.items")).unwrap(),
+            vec![json!(1), json!(2)]
+        );
+        assert_eq!(
+            page_items(page.clone(), Some("data.rows.0")).unwrap(),
+            vec![json!(3)]
+        );
+        assert!(page_items(page.clone(), Some("data.missing")).is_err());
+        assert!(page_items(page, Some("data")).is_err());
+        assert_eq!(page_items(json!([1]), None).unwrap(), vec![json!(1)]);
+    }
+
+    #[test]
+    fn test_advance_cursor() {
+        let options = options("cursor", None);
+        let headers = HeaderMap::new();
+        let first = Position::Cursor(None);
+        let page = json!({"data": {"items": [1], "next": "abc"}});
+        let next = options.advance(&first, &page, &headers, &base(), 1);
+        assert_eq!(next, Some(Position::Cursor(Some("abc".into()))));
+        assert_eq!(
+            options.advance(&next.unwrap(), &page, &headers, &base(), 1),
+            None
+        );
+        let last = json!({"data": {"items": [], "next": null}});
+        assert_eq!(options.advance(&first, &last, &headers, &base(), 0), None);
+    }
+
+    #[test]
+    fn test_advance_page_and_offset() {
+        let headers = HeaderMap::new();
+        let page = json!({});
+        let options_page = options("page", Some(2));
+        assert_eq!(
+            options_page.advance(&Position::Page(1), &page, &headers, &base(), 2),
+            Some(Position::Page(2))
+        );
+        assert_eq!(
+            options_page.advance(&Position::Page(2), &page, &headers, &base(), 1),
+            None
+        );
+        let options_offset = options("offset", None);
+        assert_eq!(
+            options_offset.advance(&Position::Offset(10), &page, &headers, &base(), 5),
+            Some(Position::Offset(15))
+        );
+        assert_eq!(
+            options_offset.advance(&Position::Offset(15), &page, &headers, &base(), 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_next_link() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            LINK,
+            HeaderValue::from_static(
+                r#"<https://api.example.com/items?page=1>; rel="prev", </items?page=3>; rel="next""#,
+            ),
+        );
+        assert_eq!(
+            next_link(&headers, &base()).as_deref(),
+            Some("https://api.example.com/items?page=3")
+        );
+        assert_eq!(next_link(&HeaderMap::new(), &base()), None);
+    }
+
+    #[test]
+    fn test_page_query() {
+        let url = "https://api.example.com/items".to_string();
+        let options = options("offset", Some(50));
+        let (_, params) = options.page_query(url.clone(), None, &Position::Offset(100));
+        let params = params.unwrap();
+        assert_eq!(params.get("offset").map(String::as_str), Some("100"));
+        assert_eq!(params.get("limit").map(String::as_str), Some("50"));
+
+        let options = PageOptions::new("link", None, None, None, None, None, None, None).unwrap();
+        let first = options.first(&url);
+        let mut params = IndexMapSSR::default();
+        params.insert("q".into(), "x".into());
+        let (page_url, page_params) = options.page_query(url.clone(), Some(params), &first);
+        assert_eq!(page_url, url);
+        assert!(page_params.is_some());
+        let next = Position::Url("https://api.example.com/items?q=x&page=2".into());
+        let (page_url, page_params) = options.page_query(url, None, &next);
+        assert_eq!(page_url, "https://api.example.com/items?q=x&page=2");
+        assert!(page_params.is_none());
+    }
+}
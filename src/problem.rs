@@ -0,0 +1,217 @@
+use anyhow::{bail, Result};
+use pyo3::prelude::*;
+use pythonize::pythonize;
+use serde_json::{Map, Value};
+
+/// XML namespace of `application/problem+xml` documents (RFC 9457, appendix B).
+const PROBLEM_XML_NS: &str = "urn:ietf:rfc:7807";
+
+/// An RFC 9457 "Problem Details for HTTP APIs" error, from `response.problem()`.
+#[pyclass(frozen, module = "httpr")]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ProblemDetails {
+    /// URI reference identifying the problem type; `"about:blank"` if absent.
+    #[pyo3(get, name = "type")]
+    pub type_: String,
+    #[pyo3(get)]
+    pub title: Option<String>,
+    #[pyo3(get)]
+    pub status: Option<u16>,
+    #[pyo3(get)]
+    pub detail: Option<String>,
+    #[pyo3(get)]
+    pub instance: Option<String>,
+    pub extensions: Map<String, Value>,
+}
+
+impl ProblemDetails {
+    /// Parse the body of a response with `content_type`; `None` unless the media type is
+    /// `application/problem+json` or `application/problem+xml`.
+    pub fn parse(content: &[u8], content_type: &str) -> Option<Result<Self>> {
+        let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+        match mime.as_str() {
+            "application/problem+json" => Some(Self::from_json(content)),
+            "application/problem+xml" => Some(Self::from_xml(content)),
+            _ => None,
+        }
+    }
+
+    /// Members of the wrong type are ignored, as RFC 9457 requires.
+    fn from_json(content: &[u8]) -> Result<Self> {
+        let Value::Object(members) = serde_json::from_slice(content)? else {
+            bail!("Problem details must be a JSON object");
+        };
+        Ok(Self::from_members(members))
+    }
+
+    fn from_xml(content: &[u8]) -> Result<Self> {
+        let text = std::str::from_utf8(content)?;
+        let document = roxmltree::Document::parse(text)?;
+        let root = document.root_element();
+        if root.tag_name().name() != "problem"
+            || root.tag_name().namespace() != Some(PROBLEM_XML_NS)
+        {
+            bail!(
+                "Problem details must have a <problem xmlns=\"{}\"> root",
+                PROBLEM_XML_NS
+            );
+        }
+        let members = root
+            .children()
+            .filter(|node| node.is_element())
+            .map(|node| {
+                let value = xml_value(node);
+                // Members are text in XML; the status is a number in the JSON model
+                let value = match (node.tag_name().name(), value) {
+                    ("status", Value::String(text)) => text
+                        .trim()
+                        .parse::<u16>()
+                        .map(Value::from)
+                        .unwrap_or(Value::String(text)),
+                    (_, value) => value,
+                };
+                (node.tag_name().name().to_string(), value)
+            })
+            .collect();
+        Ok(Self::from_members(members))
+    }
+
+    fn from_members(mut members: Map<String, Value>) -> Self {
+        let mut string = |name: &str| match members.shift_remove(name) {
+            Some(Value::String(text)) => Some(text),
+            _ => None,
+        };
+        let type_ = string("type").unwrap_or_else(|| "about:blank".to_string());
+        let title = string("title");
+        let detail = string("detail");
+        let instance = string("instance");
+        let status = members
+            .shift_remove("status")
+            .and_then(|status| status.as_u64())
+            .and_then(|status| u16::try_from(status).ok());
+        // Whatever is left are extension members
+        ProblemDetails {
+            type_,
+            title,
+            status,
+            detail,
+            instance,
+            extensions: members,
+        }
+    }
+
+    /// Title and detail, e.g. for an exception message.
+    pub fn summary(&self) -> Option<String> {
+        match (&self.title, &self.detail) {
+            (Some(title), Some(detail)) => Some(format!("{}: {}", title, detail)),
+            (Some(text), None) | (None, Some(text)) => Some(text.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// JSON value of an XML element: arrays are `<i>` children, objects other children,
+/// and anything else the element text.
+fn xml_value(node: roxmltree::Node) -> Value {
+    let children: Vec<_> = node.children().filter(|child| child.is_element()).collect();
+    if children.is_empty() {
+        return Value::String(node.text().unwrap_or_default().to_string());
+    }
+    if children.iter().all(|child| child.tag_name().name() == "i") {
+        return Value::Array(children.into_iter().map(xml_value).collect());
+    }
+    Value::Object(
+        children
+            .into_iter()
+            .map(|child| (child.tag_name().name().to_string(), xml_value(child)))
+            .collect(),
+    )
+}
+
+#[pymethods]
+impl ProblemDetails {
+    /// Members other than `type`, `title`, `status`, `detail` and `instance`.
+    #[getter]
+    fn extensions<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        Ok(pythonize(py, &self.extensions)?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<ProblemDetails [{} {}]>",
+            self.status
+                .map(|status| status.to_string())
+                .unwrap_or_default(),
+            self.title.as_deref().unwrap_or(&self.type_)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json() {
+        let body = br#"{
+            "type": "https://example.com/probs/out-of-credit",
+            "title": "You do not have enough credit.",
+            "status": 403,
+            "detail": "Your current balance is 30, but that costs 50.",
+            "instance": 42,
+            "balance": 30
+        }"#;
+        let problem = ProblemDetails::parse(body, "application/problem+json; charset=utf-8")
+            .unwrap()
+            .unwrap();
+        assert_eq!(problem.type_, "https://example.com/probs/out-of-credit");
+        assert_eq!(problem.status, Some(403));
+        assert_eq!(problem.instance, None);
+        assert_eq!(problem.extensions.get("balance"), Some(&Value::from(30)));
+        assert!(!problem.extensions.contains_key("instance"));
+        assert_eq!(
+            problem.summary().unwrap(),
+            "You do not have enough credit.: Your current balance is 30, but that costs 50."
+        );
+
+        assert!(ProblemDetails::parse(body, "application/json").is_none());
+        assert!(ProblemDetails::parse(b"[]", "application/problem+json")
+            .unwrap()
+            .is_err());
+        let problem = ProblemDetails::parse(b"{}", "application/problem+json")
+            .unwrap()
+            .unwrap();
+        assert_eq!(problem.type_, "about:blank");
+    }
+
+    #[test]
+    fn test_parse_xml() {
+        let body = br#"<?xml version="1.0" encoding="UTF-8"?>
+            <problem xmlns="urn:ietf:rfc:7807">
+              <type>https://example.com/probs/out-of-credit</type>
+              <title>You do not have enough credit.</title>
+              <status>403</status>
+              <balance>30</balance>
+              <accounts><i>/account/12345</i><i>/account/67890</i></accounts>
+            </problem>"#;
+        let problem = ProblemDetails::parse(body, "application/problem+xml")
+            .unwrap()
+            .unwrap();
+        assert_eq!(problem.status, Some(403));
+        assert_eq!(
+            problem.title.as_deref(),
+            Some("You do not have enough credit.")
+        );
+        assert_eq!(problem.extensions.get("balance"), Some(&Value::from("30")));
+        assert_eq!(
+            problem.extensions.get("accounts"),
+            Some(&serde_json::json!(["/account/12345", "/account/67890"]))
+        );
+
+        assert!(
+            ProblemDetails::parse(b"<problem/>", "application/problem+xml")
+                .unwrap()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,575 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use reqwest::header::HeaderMap;
+use tokio::sync::{watch, Notify};
+use tokio::time::Instant;
+use url::Url;
+
+use crate::exceptions::{map_anyhow_error, PoolTimeout};
+use crate::request::{Request, RequestSpec, ResponseParts};
+use crate::response::Response;
+use crate::{block_on, RClient, RUNTIME};
+
+/// Limits the `Scheduler` starts requests within.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    max_concurrency: usize,
+    max_per_host: Option<usize>,
+    /// Minimum time between two request starts to the same host (`per_host_rate`).
+    host_interval: Option<Duration>,
+}
+
+impl Limits {
+    pub fn from_args(
+        max_concurrency: usize,
+        max_per_host: Option<usize>,
+        per_host_rate: Option<f64>,
+    ) -> Result<Self> {
+        if max_concurrency == 0 {
+            bail!("max_concurrency must be at least 1");
+        }
+        if max_per_host == Some(0) {
+            bail!("max_per_host must be at least 1");
+        }
+        let host_interval = match per_host_rate {
+            Some(rate) if !rate.is_finite() || rate <= 0.0 => {
+                bail!("per_host_rate must be a positive number of requests per second")
+            }
+            Some(rate) => Some(Duration::from_secs_f64(1.0 / rate)),
+            None => None,
+        };
+        Ok(Limits {
+            max_concurrency,
+            max_per_host,
+            host_interval,
+        })
+    }
+}
+
+/// Higher priority first, then earliest deadline (none last), then submission order.
+type Key = (Reverse<i64>, bool, Option<Instant>, u64);
+
+struct Entry<T> {
+    host: String,
+    deadline: Option<Instant>,
+    item: T,
+}
+
+/// Order in which queued items start, honoring the concurrency and per-host limits.
+pub struct Scheduler<T> {
+    limits: Limits,
+    waiting: BTreeMap<Key, Entry<T>>,
+    running: usize,
+    per_host: HashMap<String, usize>,
+    next_start: HashMap<String, Instant>,
+    seq: u64,
+}
+
+impl<T> Scheduler<T> {
+    pub fn new(limits: Limits) -> Self {
+        Scheduler {
+            limits,
+            waiting: BTreeMap::new(),
+            running: 0,
+            per_host: HashMap::new(),
+            next_start: HashMap::new(),
+            seq: 0,
+        }
+    }
+
+    pub fn push(&mut self, host: String, priority: i64, deadline: Option<Instant>, item: T) {
+        self.seq += 1;
+        let key = (Reverse(priority), deadline.is_none(), deadline, self.seq);
+        self.waiting.insert(
+            key,
+            Entry {
+                host,
+                deadline,
+                item,
+            },
+        );
+    }
+
+    /// Remove the waiting items whose deadline has passed.
+    pub fn expire(&mut self, now: Instant) -> Vec<T> {
+        let expired: Vec<Key> = self
+            .waiting
+            .iter()
+            .filter(|(_, entry)| entry.deadline.is_some_and(|deadline| deadline <= now))
+            .map(|(key, _)| *key)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|key| self.waiting.remove(&key))
+            .map(|entry| entry.item)
+            .collect()
+    }
+
+    fn host_ready(&self, host: &str, now: Instant) -> bool {
+        let below_limit = self
+            .limits
+            .max_per_host
+            .is_none_or(|max| self.per_host.get(host).copied().unwrap_or(0) < max);
+        below_limit && self.next_start.get(host).is_none_or(|start| *start <= now)
+    }
+
+    /// Take the next item that may start now, counting it as running until `finish`.
+    pub fn next(&mut self, now: Instant) -> Option<(String, Option<Instant>, T)> {
+        if self.running >= self.limits.max_concurrency {
+            return None;
+        }
+        let key = *self
+            .waiting
+            .iter()
+            .find(|(_, entry)| self.host_ready(&entry.host, now))?
+            .0;
+        let entry = self.waiting.remove(&key)?;
+        self.running += 1;
+        *self.per_host.entry(entry.host.clone()).or_default() += 1;
+        if let Some(interval) = self.limits.host_interval {
+            self.next_start.insert(entry.host.clone(), now + interval);
+        }
+        Some((entry.host, entry.deadline, entry.item))
+    }
+
+    pub fn finish(&mut self, host: &str) {
+        self.running = self.running.saturating_sub(1);
+        if let Some(count) = self.per_host.get_mut(host) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                self.per_host.remove(host);
+            }
+        }
+    }
+
+    /// The earliest time a waiting item can expire or a rate-limited host becomes ready.
+    pub fn wake_at(&self, now: Instant) -> Option<Instant> {
+        let deadlines = self.waiting.values().filter_map(|entry| entry.deadline);
+        let starts = self
+            .waiting
+            .values()
+            .filter_map(|entry| self.next_start.get(&entry.host))
+            .filter(|start| **start > now)
+            .copied();
+        deadlines.chain(starts).min()
+    }
+
+    /// Waiting plus running items.
+    pub fn len(&self) -> usize {
+        self.waiting.len() + self.running
+    }
+}
+
+struct Job {
+    spec: RequestSpec,
+    client: reqwest::Client,
+    client_headers: HeaderMap,
+    future: Py<PyAny>,
+    submitted: Instant,
+}
+
+struct State {
+    scheduler: Scheduler<Job>,
+    closed: bool,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    /// Wakes the drain loop on submissions, completions and `close()`.
+    wake: Notify,
+    /// Number of requests not yet resolved, for `join()`.
+    outstanding: watch::Sender<usize>,
+}
+
+impl Shared {
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn resolved(&self) {
+        self.outstanding.send_modify(|n| *n = n.saturating_sub(1));
+    }
+}
+
+/// Resolve `future` with a `Response` or the exception of the failed request.
+fn set_result(py: Python, future: &Py<PyAny>, result: Result<ResponseParts>) -> PyResult<()> {
+    match result {
+        Ok(parts) => future.call_method1(py, "set_result", (Response::from_parts(py, parts),))?,
+        Err(e) => {
+            future.call_method1(py, "set_exception", (map_anyhow_error(e).into_value(py),))?
+        }
+    };
+    Ok(())
+}
+
+/// Python calls wait for the GIL, so they run on the blocking pool rather than stalling
+/// the runtime.
+async fn with_gil<R: Send + 'static>(f: impl FnOnce(Python) -> R + Send + 'static) -> Option<R> {
+    tokio::task::spawn_blocking(move || Python::attach(f))
+        .await
+        .ok()
+}
+
+async fn run(shared: Arc<Shared>, host: String, deadline: Option<Instant>, job: Job) {
+    let Job {
+        mut spec,
+        client,
+        client_headers,
+        future,
+        ..
+    } = job;
+    let started = with_gil(move |py| {
+        let running = future
+            .call_method0(py, "set_running_or_notify_cancel")
+            .and_then(|running| running.extract::<bool>(py))
+            .unwrap_or(false);
+        running.then_some(future)
+    })
+    .await
+    .flatten();
+    // None if the future was cancelled while queued
+    if let Some(future) = started {
+        // The time left until the deadline bounds the request timeout
+        if let Some(deadline) = deadline {
+            let left = deadline
+                .saturating_duration_since(Instant::now())
+                .as_secs_f64();
+            spec.timeout = Some(spec.timeout.map_or(left, |timeout| timeout.min(left)));
+        }
+        let result = async {
+            let resp = spec.send(client, client_headers).await?;
+            ResponseParts::read(resp).await
+        }
+        .await;
+        with_gil(move |py| set_result(py, &future, result)).await;
+    }
+    shared.lock().scheduler.finish(&host);
+    shared.resolved();
+    shared.wake.notify_one();
+}
+
+async fn expire(shared: Arc<Shared>, job: Job) {
+    let waited = job.submitted.elapsed();
+    let future = job.future;
+    with_gil(move |py| {
+        let running = future
+            .call_method0(py, "set_running_or_notify_cancel")
+            .and_then(|running| running.extract::<bool>(py))?;
+        if running {
+            let e = PoolTimeout::new_err(format!(
+                "Deadline passed after {:.1}s in the queue before the request could start",
+                waited.as_secs_f64()
+            ));
+            future.call_method1(py, "set_exception", (e.into_value(py),))?;
+        }
+        Ok::<_, PyErr>(())
+    })
+    .await;
+    shared.resolved();
+}
+
+/// Start queued requests as the limits allow, until the queue is closed and empty.
+async fn drain(shared: Arc<Shared>) {
+    loop {
+        let now = Instant::now();
+        let (expired, started, wake_at, done) = {
+            let mut state = shared.lock();
+            let expired = state.scheduler.expire(now);
+            let mut started = Vec::new();
+            while let Some(next) = state.scheduler.next(now) {
+                started.push(next);
+            }
+            let done = state.closed && state.scheduler.len() == 0;
+            (expired, started, state.scheduler.wake_at(now), done)
+        };
+        for job in expired {
+            tokio::spawn(expire(Arc::clone(&shared), job));
+        }
+        for (host, deadline, job) in started {
+            tokio::spawn(run(Arc::clone(&shared), host, deadline, job));
+        }
+        if done {
+            // Let the last expirations resolve their futures
+            let mut outstanding = shared.outstanding.subscribe();
+            let _ = outstanding.wait_for(|n| *n == 0).await;
+            return;
+        }
+        match wake_at {
+            Some(wake_at) => {
+                tokio::select! {
+                    _ = shared.wake.notified() => {}
+                    _ = tokio::time::sleep_until(wake_at) => {}
+                }
+            }
+            None => shared.wake.notified().await,
+        }
+    }
+}
+
+/// Background request scheduler returned by `client.queue()`.
+///
+/// Submitted requests wait in a priority queue and are started by a background thread,
+/// which drives the tokio runtime, as the concurrency, per-host and rate limits allow.
+/// Each submission returns a `concurrent.futures.Future`.
+#[pyclass(module = "httpr")]
+pub struct RequestQueue {
+    client: Py<RClient>,
+    shared: Arc<Shared>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl RequestQueue {
+    pub fn start(client: Py<RClient>, limits: Limits) -> PyResult<Self> {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                scheduler: Scheduler::new(limits),
+                closed: false,
+            }),
+            wake: Notify::new(),
+            outstanding: watch::Sender::new(0),
+        });
+        let drained = Arc::clone(&shared);
+        let thread = std::thread::Builder::new()
+            .name("httpr-queue".to_string())
+            .spawn(move || RUNTIME.block_on(drain(drained)))
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        Ok(RequestQueue {
+            client,
+            shared,
+            thread: Mutex::new(Some(thread)),
+        })
+    }
+
+    fn shut_down(&self) {
+        self.shared.lock().closed = true;
+        self.shared.wake.notify_one();
+    }
+}
+
+impl Drop for RequestQueue {
+    /// Requests already submitted still run; the thread exits once they are done.
+    fn drop(&mut self) {
+        self.shut_down();
+    }
+}
+
+#[pymethods]
+impl RequestQueue {
+    /// Queue `request` and return a `concurrent.futures.Future` resolving to its
+    /// `Response`, or failing with its exception.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The `Request` to send, with the client's defaults applied.
+    /// * `priority` - Requests with a higher priority start first. Default is 0.
+    /// * `deadline` - Seconds from now by which the request must start; otherwise its
+    ///   future fails with `PoolTimeout`. Once started, the time left also bounds the
+    ///   request timeout. Among equal priorities, earlier deadlines start first.
+    /// * `callback` - Called with the future once it is done, from a background thread.
+    #[pyo3(signature = (request, priority=0, deadline=None, callback=None))]
+    fn submit(
+        &self,
+        py: Python,
+        request: Request,
+        priority: i64,
+        deadline: Option<f64>,
+        callback: Option<Py<PyAny>>,
+    ) -> PyResult<Py<PyAny>> {
+        let deadline = deadline
+            .map(|seconds| {
+                if !seconds.is_finite() || seconds < 0.0 {
+                    return Err(pyo3::exceptions::PyValueError::new_err(
+                        "deadline must be a non-negative number of seconds",
+                    ));
+                }
+                Ok(Instant::now() + Duration::from_secs_f64(seconds))
+            })
+            .transpose()?;
+        let (spec, client, client_headers) = {
+            let rclient = self.client.borrow(py);
+            let spec = rclient.spec_from_request(request)?;
+            let (client, client_headers) = rclient.snapshot().map_err(map_anyhow_error)?;
+            (spec, client, client_headers)
+        };
+        let host = Url::parse(&spec.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            .unwrap_or_default();
+
+        let future = py
+            .import("concurrent.futures")?
+            .getattr("Future")?
+            .call0()?
+            .unbind();
+        if let Some(callback) = callback {
+            future.call_method1(py, "add_done_callback", (callback,))?;
+        }
+        let job = Job {
+            spec,
+            client,
+            client_headers,
+            future: future.clone_ref(py),
+            submitted: Instant::now(),
+        };
+        {
+            let mut state = self.shared.lock();
+            if state.closed {
+                return Err(PyRuntimeError::new_err("RequestQueue is closed"));
+            }
+            state.scheduler.push(host, priority, deadline, job);
+            self.shared.outstanding.send_modify(|n| *n += 1);
+        }
+        self.shared.wake.notify_one();
+        Ok(future)
+    }
+
+    /// Wait until every submitted request is done. Returns False if `timeout` seconds
+    /// passed first.
+    #[pyo3(signature = (timeout=None))]
+    fn join(&self, py: Python, timeout: Option<f64>) -> PyResult<bool> {
+        let mut outstanding = self.shared.outstanding.subscribe();
+        let wait = async move { outstanding.wait_for(|n| *n == 0).await.is_ok() };
+        block_on(py, || async move {
+            match timeout {
+                Some(timeout) => {
+                    tokio::time::timeout(Duration::from_secs_f64(timeout.max(0.0)), wait)
+                        .await
+                        .unwrap_or(false)
+                }
+                None => wait.await,
+            }
+        })
+    }
+
+    /// Stop accepting requests. With `wait`, block until the submitted ones are done.
+    #[pyo3(signature = (wait=true))]
+    fn close(&self, py: Python, wait: bool) -> PyResult<()> {
+        self.shut_down();
+        if !wait {
+            return Ok(());
+        }
+        // Waits interruptibly; the background thread exits right after
+        self.join(py, None)?;
+        let thread = self.thread.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(thread) = thread {
+            py.detach(|| {
+                let _ = thread.join();
+            });
+        }
+        Ok(())
+    }
+
+    /// Whether `close()` has been called.
+    #[getter]
+    fn closed(&self) -> bool {
+        self.shared.lock().closed
+    }
+
+    /// Number of requests queued or running.
+    fn __len__(&self) -> usize {
+        *self.shared.outstanding.borrow()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<()> {
+        self.close(py, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scheduler(
+        max: usize,
+        per_host: Option<usize>,
+        rate: Option<f64>,
+    ) -> Scheduler<&'static str> {
+        Scheduler::new(Limits::from_args(max, per_host, rate).unwrap())
+    }
+
+    #[test]
+    fn test_limits() {
+        assert!(Limits::from_args(0, None, None).is_err());
+        assert!(Limits::from_args(1, Some(0), None).is_err());
+        assert!(Limits::from_args(1, None, Some(0.0)).is_err());
+        assert!(Limits::from_args(1, None, Some(f64::INFINITY)).is_err());
+    }
+
+    #[test]
+    fn test_order() {
+        let now = Instant::now();
+        let mut queue = scheduler(10, None, None);
+        queue.push("a".into(), 0, None, "low");
+        queue.push("a".into(), 5, None, "high");
+        queue.push("a".into(), 0, Some(now + Duration::from_secs(9)), "late");
+        queue.push("a".into(), 0, Some(now + Duration::from_secs(1)), "soon");
+        queue.push("a".into(), 0, None, "low2");
+        let order: Vec<_> =
+            std::iter::from_fn(|| queue.next(now).map(|(_, _, item)| item)).collect();
+        assert_eq!(order, ["high", "soon", "late", "low", "low2"]);
+        assert_eq!(queue.len(), 5);
+    }
+
+    #[test]
+    fn test_per_host_limits() {
+        let now = Instant::now();
+        let mut queue = scheduler(3, Some(1), None);
+        queue.push("a".into(), 0, None, "a1");
+        queue.push("a".into(), 0, None, "a2");
+        queue.push("b".into(), 0, None, "b1");
+        assert_eq!(queue.next(now).unwrap().2, "a1");
+        // a2 waits for a1, but b1 may start
+        assert_eq!(queue.next(now).unwrap().2, "b1");
+        assert!(queue.next(now).is_none());
+        queue.finish("a");
+        assert_eq!(queue.next(now).unwrap().2, "a2");
+    }
+
+    #[test]
+    fn test_concurrency_and_rate() {
+        let now = Instant::now();
+        let mut queue = scheduler(1, None, None);
+        queue.push("a".into(), 0, None, "a1");
+        queue.push("b".into(), 0, None, "b1");
+        assert!(queue.next(now).is_some());
+        assert!(queue.next(now).is_none());
+
+        let mut queue = scheduler(10, None, Some(2.0));
+        queue.push("a".into(), 0, None, "a1");
+        queue.push("a".into(), 0, None, "a2");
+        assert!(queue.next(now).is_some());
+        assert!(queue.next(now).is_none());
+        let ready = now + Duration::from_millis(500);
+        assert_eq!(queue.wake_at(now), Some(ready));
+        assert_eq!(queue.next(ready).unwrap().2, "a2");
+    }
+
+    #[test]
+    fn test_expire() {
+        let now = Instant::now();
+        let mut queue = scheduler(10, None, None);
+        queue.push("a".into(), 0, Some(now), "expired");
+        queue.push("a".into(), 0, Some(now + Duration::from_secs(5)), "later");
+        queue.push("a".into(), 0, None, "never");
+        assert_eq!(queue.expire(now), ["expired"]);
+        assert_eq!(queue.wake_at(now), Some(now + Duration::from_secs(5)));
+        assert_eq!(queue.len(), 2);
+    }
+}
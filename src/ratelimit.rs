@@ -0,0 +1,148 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use futures_util::StreamExt;
+use http_body_util::BodyExt;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use reqwest::Body;
+use tokio::time::Instant;
+
+/// A transfer rate given from Python, either bytes per second or a string like `"5MB/s"`.
+#[derive(FromPyObject)]
+pub enum Rate {
+    BytesPerSecond(f64),
+    Text(String),
+}
+
+impl Rate {
+    pub fn bytes_per_second(&self) -> PyResult<f64> {
+        let rate = match self {
+            Rate::BytesPerSecond(rate) => *rate,
+            Rate::Text(text) => {
+                parse_rate(text).map_err(|e| PyValueError::new_err(e.to_string()))?
+            }
+        };
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(PyValueError::new_err("rate must be a positive number"));
+        }
+        Ok(rate)
+    }
+}
+
+/// Parse a rate such as `"5MB/s"`, `"512 KiB/s"` or `"1000"` into bytes per second.
+///
+/// Units are bytes: `K`, `M`, `G` are powers of 1000, `Ki`, `Mi`, `Gi` powers of 1024.
+/// The `B` and `/s` suffixes are optional and units are case-insensitive.
+fn parse_rate(text: &str) -> Result<f64> {
+    let lower = text.trim().to_ascii_lowercase();
+    let unit = lower.strip_suffix("/s").unwrap_or(&lower).trim_end();
+    let split = unit
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(unit.len());
+    let (number, suffix) = unit.split_at(split);
+    let number: f64 = number
+        .parse()
+        .with_context(|| format!("Invalid rate: {:?}", text))?;
+    let multiplier = match suffix.trim().trim_end_matches('b') {
+        "" => 1.0,
+        "k" => 1e3,
+        "m" => 1e6,
+        "g" => 1e9,
+        "ki" => 1024.0,
+        "mi" => 1024.0 * 1024.0,
+        "gi" => 1024.0 * 1024.0 * 1024.0,
+        _ => bail!("Invalid rate unit in {:?}", text),
+    };
+    Ok(number * multiplier)
+}
+
+struct TokenBucket {
+    rate: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token bucket limiting a transfer to `rate` bytes per second.
+///
+/// Clones share the same bucket, so one limiter can cap the combined throughput of
+/// several concurrent transfers. The bucket starts empty and holds at most one second
+/// worth of tokens, so the average rate never exceeds the limit.
+#[derive(Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(rate: f64) -> Self {
+        RateLimiter {
+            bucket: Arc::new(Mutex::new(TokenBucket {
+                rate,
+                tokens: 0.0,
+                updated: Instant::now(),
+            })),
+        }
+    }
+
+    /// Take `n` bytes worth of tokens, sleeping until the bucket is back in credit.
+    async fn acquire(&self, n: usize) {
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * bucket.rate).min(bucket.rate);
+            bucket.updated = now;
+            bucket.tokens -= n as f64;
+            if bucket.tokens < 0.0 {
+                Duration::from_secs_f64(-bucket.tokens / bucket.rate)
+            } else {
+                Duration::ZERO
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Wrap `body` so that its chunks are released no faster than the limit.
+    pub fn throttle_body(&self, body: Body) -> Body {
+        let limiter = self.clone();
+        Body::wrap_stream(body.into_data_stream().then(move |chunk| {
+            let limiter = limiter.clone();
+            async move {
+                if let Ok(chunk) = &chunk {
+                    limiter.acquire(chunk.len()).await;
+                }
+                chunk
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rate() {
+        assert_eq!(parse_rate("1000").unwrap(), 1000.0);
+        assert_eq!(parse_rate("5MB/s").unwrap(), 5e6);
+        assert_eq!(parse_rate("1.5 kb/s").unwrap(), 1500.0);
+        assert_eq!(parse_rate("2MiB/s").unwrap(), 2.0 * 1024.0 * 1024.0);
+        assert_eq!(parse_rate("64KiB").unwrap(), 65536.0);
+        assert!(parse_rate("fast").is_err());
+        assert!(parse_rate("5 parsecs/s").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_paces_transfers() {
+        let limiter = RateLimiter::new(100_000.0);
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire(10_000).await;
+        }
+        // 50 KB at 100 KB/s from an empty bucket.
+        assert!(start.elapsed() >= Duration::from_millis(450));
+    }
+}
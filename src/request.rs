@@ -0,0 +1,1136 @@
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use bytes::Bytes;
+use foldhash::fast::RandomState;
+use indexmap::IndexMap;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedBytes;
+use pyo3::types::{PyBytes, PyIterator, PyMemoryView, PyString};
+use pythonize::{depythonize, pythonize};
+use reqwest::{
+    header::{
+        HeaderMap, HeaderName, HeaderValue, ACCEPT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, COOKIE,
+        WWW_AUTHENTICATE,
+    },
+    Body, Method, ResponseBuilderExt, StatusCode,
+};
+use serde_json::Value;
+use url::Url;
+
+use crate::attempts::{Attempt, AttemptLog};
+use crate::auth::{Auth, HmacAuth, SignHook};
+use crate::cancel::CancellationToken;
+use crate::codecs::Codecs;
+use crate::connection::is_connection_error;
+use crate::contentdigest::ContentDigest;
+use crate::cookies::{record_cookies, response_cookies, with_first_party, CookieMerge};
+use crate::decompress::DecompressionGuard;
+use crate::events::ConnectionEvents;
+use crate::exceptions::map_anyhow_error;
+use crate::history::History;
+use crate::hosts::HostPolicy;
+use crate::lazyheaders::{HeaderValueArg, LazyHeaders};
+use crate::limits::HeaderLimits;
+use crate::lowspeed::LowSpeed;
+use crate::multipart::FileParts;
+use crate::ratelimit::RateLimiter;
+use crate::redirect::{
+    is_cross_host, redirect_location, RedirectHistory, RedirectLimit, Redirects, Replay,
+};
+use crate::robots::RobotsCache;
+use crate::serialize::{to_json, JsonFormat};
+use crate::servertiming::ServerTiming;
+use crate::sni;
+use crate::traits::HeadersTraits;
+use crate::transfer::{Meter, Transfer};
+use crate::transport::{stream_body, Transport};
+
+type IndexMapSSR = IndexMap<String, String, RandomState>;
+
+/// `headers=` of a single request, where a `None` value removes a header and a callable
+/// is called for the value of each attempt.
+pub type HeaderArgs = IndexMap<String, Option<HeaderValueArg>, RandomState>;
+
+/// Split `headers=` into the headers to send, the names given as `None`, which leave
+/// out the client default or automatic header of that name, and the callables.
+pub fn split_headers(
+    headers: Option<HeaderArgs>,
+) -> PyResult<(Option<IndexMapSSR>, Vec<HeaderName>, LazyHeaders)> {
+    let Some(headers) = headers else {
+        return Ok((None, Vec::new(), LazyHeaders::default()));
+    };
+    let mut set = IndexMapSSR::with_capacity_and_hasher(headers.len(), RandomState::default());
+    let mut removed = Vec::new();
+    let mut lazy = LazyHeaders::default();
+    for (name, value) in headers {
+        match value {
+            Some(HeaderValueArg::Value(value)) => {
+                set.insert(name, value);
+            }
+            Some(HeaderValueArg::Lazy(callable)) => lazy.push(&name, callable)?,
+            None => removed.push(HeaderName::from_bytes(name.as_bytes()).map_err(|e| {
+                PyValueError::new_err(format!("Invalid header name '{}': {}", name, e))
+            })?),
+        }
+    }
+    Ok((Some(set), removed, lazy))
+}
+
+/// WebDAV extension methods (RFC 4918, RFC 3253) whose requests carry an XML body.
+const WEBDAV_BODY_METHODS: [&str; 5] = ["PROPFIND", "PROPPATCH", "MKCOL", "LOCK", "REPORT"];
+
+/// Whether request bodies (`content`, `data`, `json`, `files`) are sent for `method`.
+fn method_has_body(method: &Method) -> bool {
+    matches!(*method, Method::POST | Method::PUT | Method::PATCH)
+        || WEBDAV_BODY_METHODS.contains(&method.as_str())
+}
+
+/// The request body given as `content=`.
+///
+/// `bytes` are sent without copying: the body keeps a reference to the Python object.
+/// A `str` is encoded with `encoding` (UTF-8 by default). Other buffer-protocol
+/// objects (`bytearray`, `memoryview`, NumPy arrays, ...) are shared too when they are
+/// C-contiguous and writable, see `SharedBuffer`; others are copied once, in C order.
+pub fn content_bytes(content: &Bound<'_, PyAny>, encoding: Option<&str>) -> PyResult<Bytes> {
+    body_bytes(content, encoding, true)
+}
+
+/// A chunk of a body streamed from an iterator. Unlike `content_bytes`, buffers other than
+/// `bytes` are copied: iterators often refill one buffer for every chunk.
+pub fn chunk_bytes(chunk: &Bound<'_, PyAny>) -> PyResult<Bytes> {
+    body_bytes(chunk, None, false)
+}
+
+fn body_bytes(content: &Bound<'_, PyAny>, encoding: Option<&str>, share: bool) -> PyResult<Bytes> {
+    if let Ok(text) = content.cast::<PyString>() {
+        let encoded = text.call_method1("encode", (encoding.unwrap_or("utf-8"),))?;
+        return Ok(Bytes::from_owner(encoded.extract::<PyBackedBytes>()?));
+    }
+    if encoding.is_some() {
+        return Err(PyTypeError::new_err(
+            "encoding= only applies to str content",
+        ));
+    }
+    if content.is_instance_of::<PyBytes>() {
+        return Ok(Bytes::from_owner(content.extract::<PyBackedBytes>()?));
+    }
+    let view = PyMemoryView::from(content).map_err(|_| {
+        PyTypeError::new_err(format!(
+            "content must be bytes, str or a buffer such as bytearray or memoryview, not {}",
+            content
+                .get_type()
+                .name()
+                .map(|name| name.to_string())
+                .unwrap_or_default()
+        ))
+    })?;
+    let len: usize = view.getattr("nbytes")?.extract()?;
+    if len == 0 {
+        return Ok(Bytes::new());
+    }
+    if view.getattr("c_contiguous")?.extract::<bool>()? {
+        if share && !view.getattr("readonly")?.extract::<bool>()? {
+            return Ok(Bytes::from_owner(SharedBuffer::new(&view, len)?));
+        }
+        // A read-only view of a whole `bytes` object, e.g. `memoryview(b"...")`
+        let obj = view.getattr("obj")?;
+        if obj.is_instance_of::<PyBytes>() && obj.len()? == len {
+            return Ok(Bytes::from_owner(obj.extract::<PyBackedBytes>()?));
+        }
+    }
+    Ok(Bytes::from_owner(
+        view.call_method0("tobytes")?.extract::<PyBackedBytes>()?,
+    ))
+}
+
+/// The memory of a writable, C-contiguous buffer, sent without copying.
+///
+/// The limited API has no `PyObject_GetBuffer` before Python 3.11, so the buffer is
+/// exported to a ctypes array instead. While the array lives the memory stays in place: a
+/// `bytearray` cannot be resized and an `mmap` cannot be closed, both raising `BufferError`.
+/// Changes made to the buffer in place before the request is sent show in the body.
+struct SharedBuffer {
+    _array: Py<PyAny>,
+    ptr: *const u8,
+    len: usize,
+}
+
+// SAFETY: the memory is only read, and stays valid as long as the ctypes array holds the
+// buffer export
+unsafe impl Send for SharedBuffer {}
+unsafe impl Sync for SharedBuffer {}
+
+impl SharedBuffer {
+    fn new(view: &Bound<'_, PyMemoryView>, len: usize) -> PyResult<Self> {
+        let ctypes = view.py().import("ctypes")?;
+        let array = ctypes
+            .getattr("c_char")?
+            .mul(len)?
+            .call_method1("from_buffer", (view,))?;
+        let address: usize = ctypes.call_method1("addressof", (&array,))?.extract()?;
+        Ok(SharedBuffer {
+            _array: array.unbind(),
+            ptr: address as *const u8,
+            len,
+        })
+    }
+}
+
+impl AsRef<[u8]> for SharedBuffer {
+    fn as_ref(&self) -> &[u8] {
+        // SAFETY: see `Send`
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+/// A request body given as an iterator of chunks, such as a generator, pulled as the
+/// request is sent. The response can be read while it is still being produced, for
+/// full-duplex exchanges over HTTP/2. As it is consumed by sending, it is not sent again
+/// on a redirect or an authentication challenge.
+#[derive(Clone)]
+pub struct ContentStream(Arc<Py<PyIterator>>);
+
+impl ContentStream {
+    /// `content=` if it is an iterator.
+    pub fn from_content(
+        content: &Bound<'_, PyAny>,
+        encoding: Option<&str>,
+    ) -> PyResult<Option<Self>> {
+        let Ok(chunks) = content.cast::<PyIterator>() else {
+            return Ok(None);
+        };
+        if encoding.is_some() {
+            return Err(PyTypeError::new_err(
+                "encoding= only applies to str content",
+            ));
+        }
+        Ok(Some(ContentStream(Arc::new(chunks.clone().unbind()))))
+    }
+
+    fn body(&self) -> Body {
+        stream_body(Arc::clone(&self.0))
+    }
+}
+
+/// Fully owned description of a single request.
+///
+/// Everything that crosses from Python into the request path is converted into a
+/// `RequestSpec` while the GIL is held, so that the spec can be moved into a future
+/// and sent from the Tokio runtime (possibly alongside many others).
+#[derive(Clone)]
+pub struct RequestSpec {
+    pub method: Method,
+    pub url: String,
+    pub params: Option<IndexMapSSR>,
+    pub headers: Option<IndexMapSSR>,
+    /// Headers given as `None`, left out even if the client or httpr would add them.
+    pub removed_headers: Vec<HeaderName>,
+    /// Client and request headers given as callables, evaluated by `execute`.
+    pub lazy_headers: LazyHeaders,
+    pub cookies: Option<IndexMapSSR>,
+    pub cookie_merge: CookieMerge,
+    pub content: Option<Bytes>,
+    /// `content=` given as an iterator, streamed in place of `content`.
+    pub content_stream: Option<ContentStream>,
+    pub data: Option<Value>,
+    pub json: Option<Value>,
+    /// How `json` is written: the client's JSON options.
+    pub json_format: JsonFormat,
+    pub files: Option<FileParts>,
+    pub auth: Option<Auth>,
+    pub auth_bearer: Option<String>,
+    pub timeout: Option<f64>,
+    pub max_download_rate: Option<RateLimiter>,
+    pub max_upload_rate: Option<RateLimiter>,
+    pub low_speed: Option<LowSpeed>,
+    pub auth_mode: AuthMode,
+    pub sign: Option<SignHook>,
+    pub history: Option<History>,
+    pub robots: Option<RobotsCache>,
+    pub hosts: Arc<HostPolicy>,
+    pub redirects: Redirects,
+    pub header_limits: Option<HeaderLimits>,
+    pub decompression: DecompressionGuard,
+    pub codecs: Codecs,
+    pub cancel: Option<CancellationToken>,
+    /// Percent-encoded query string appended to the URL as is, after `params`.
+    pub raw_query: Option<String>,
+    pub meter: Meter,
+    /// The sends of this request, attached to its response by `send`.
+    pub attempts: AttemptLog,
+    pub connection_events: Option<ConnectionEvents>,
+    /// Sends the requests in place of the reqwest client, if set.
+    pub transport: Option<Transport>,
+    /// Attach `Content-Digest` to request bodies and verify it on responses.
+    pub content_digest: Option<ContentDigest>,
+    /// Host to connect to and name in the TLS handshake, apart from the `Host` header.
+    pub sni_hostname: Option<String>,
+    /// Send HTTPS requests whose `Host` header differs from their TLS server name.
+    pub allow_sni_mismatch: bool,
+    /// Send idempotent requests once more after a connection error.
+    pub retry_idempotent: bool,
+    /// Send the userinfo of the URL as basic auth. It is removed from the URL either way.
+    pub url_credentials: bool,
+}
+
+impl RequestSpec {
+    /// Apply the header names `headers=` gives as `None` and its callables, as split by
+    /// `split_headers`, over the client callables.
+    pub fn set_header_args(&mut self, removed: Vec<HeaderName>, lazy: LazyHeaders) {
+        self.lazy_headers = self
+            .lazy_headers
+            .under(self.headers.as_ref(), &removed, lazy);
+        self.removed_headers = removed;
+    }
+
+    /// Build the `reqwest` request on top of the client default headers and send it,
+    /// recording the outcome in the client history if there is one.
+    pub async fn send(
+        mut self,
+        client: reqwest::Client,
+        client_headers: HeaderMap,
+    ) -> Result<reqwest::Response> {
+        self.meter = self.meter.fresh();
+        self.attempts = AttemptLog::default();
+        self.take_url_credentials();
+        let transfer = self.meter.transfer();
+        let attempts = self.attempts.clone();
+        let history = self.history.clone();
+        let (method, url) = (self.method.clone(), self.url.clone());
+        let cancel = self.cancel.clone();
+        let started = Started(Instant::now());
+        let dispatch = async move {
+            match cancel {
+                Some(cancel) => cancel.guard(self.dispatch(client, client_headers)).await,
+                None => self.dispatch(client, client_headers).await,
+            }
+        };
+        let (result, cookies) = record_cookies(async {
+            match history {
+                Some(history) => history.record(&method, &url, dispatch).await,
+                None => dispatch.await,
+            }
+        })
+        .await;
+        let mut resp = result?;
+        resp.extensions_mut().insert(cookies);
+        resp.extensions_mut().insert(started);
+        resp.extensions_mut().insert(transfer);
+        resp.extensions_mut().insert(attempts);
+        Ok(resp)
+    }
+
+    /// Strip `user:pass@` from the URL before it is sent, recorded or logged, and use it as
+    /// basic auth unless `url_credentials` is off or `auth` or `auth_bearer` is set.
+    fn take_url_credentials(&mut self) {
+        let Ok(mut url) = Url::parse(&self.url) else {
+            return;
+        };
+        let Some(auth) = Auth::take_from_url(&mut url) else {
+            return;
+        };
+        self.url = url.into();
+        if self.url_credentials && self.auth.is_none() && self.auth_bearer.is_none() {
+            self.auth = Some(auth);
+        }
+    }
+
+    /// With `AuthMode::OnChallenge` the request first goes out without credentials, and is
+    /// repeated with them only if the same origin answers 401 with a matching challenge.
+    async fn dispatch(
+        mut self,
+        client: reqwest::Client,
+        client_headers: HeaderMap,
+    ) -> Result<reqwest::Response> {
+        let content = self.content.take();
+        let scheme = match (&self.auth, &self.auth_bearer) {
+            (Some(auth), _) => auth.challenge_scheme(),
+            (None, Some(_)) => Some("Bearer"),
+            (None, None) => None,
+        };
+        let on_challenge = self.auth_mode == AuthMode::OnChallenge && scheme.is_some();
+        let hmac = match &self.auth {
+            Some(Auth::Hmac(hmac)) => Some(hmac),
+            _ => None,
+        };
+
+        // Before building, as reqwest fails with a generic builder error on other schemes
+        if let Ok(url) = Url::parse(&self.url) {
+            self.hosts.check_url(&url)?;
+        }
+        let request = self
+            .build(&client, &client_headers, content.clone(), !on_challenge)
+            .await?;
+        if let Some(robots) = &self.robots {
+            robots
+                .check(&client, &client_headers, &request, self.transport.as_ref())
+                .await?;
+        }
+        let url = request.url().clone();
+        let mut history = Vec::new();
+        let mut resp = self.follow(&client, request, hmac, &mut history).await?;
+
+        if let (true, Some(scheme)) = (on_challenge, scheme) {
+            if resp.status() == StatusCode::UNAUTHORIZED
+                && resp.url().origin() == url.origin()
+                && has_challenge(resp.headers(), scheme)
+            {
+                let request = self.build(&client, &client_headers, content, true).await?;
+                history.clear();
+                resp = self.follow(&client, request, hmac, &mut history).await?;
+            }
+        }
+        if let Some(fragment) = url.fragment() {
+            resp = restore_fragment(resp, fragment);
+        }
+
+        if let Some(digest) = &self.content_digest {
+            resp = digest.verify(resp, &self.method);
+        }
+        let meter = &self.meter;
+        resp = map_response_body(resp, |body| meter.count_received(body));
+        resp = self.decompression.decode(resp, &self.method, &self.codecs);
+        resp = map_response_body(resp, |body| meter.count_decompressed(body));
+
+        // Stalled-transfer detection, download rate limit and cancellation wrap the body
+        if self.low_speed.is_some() || self.max_download_rate.is_some() || self.cancel.is_some() {
+            resp = map_response_body(resp, |mut body| {
+                if let Some(cancel) = &self.cancel {
+                    body = cancel.watch_body(body);
+                }
+                if let Some(low_speed) = self.low_speed {
+                    body = low_speed.watch_body(body);
+                }
+                if let Some(limiter) = &self.max_download_rate {
+                    body = limiter.throttle_body(body);
+                }
+                body
+            });
+        }
+        resp.extensions_mut().insert(RedirectHistory(history));
+        Ok(resp)
+    }
+
+    /// Send `request` and follow the redirects it gets as far as `redirects` allows,
+    /// reading the responses redirected by into `history`.
+    ///
+    /// Every hop is signed anew with `hmac`, as the signature covers its URL and time,
+    /// until a redirect leaves the host, after which requests go out unsigned.
+    async fn follow(
+        &self,
+        client: &reqwest::Client,
+        mut request: reqwest::Request,
+        mut hmac: Option<&HmacAuth>,
+        history: &mut Vec<ResponseParts>,
+    ) -> Result<reqwest::Response> {
+        let first_party = request.url().clone();
+        loop {
+            let method = request.method().clone();
+            let credential_header = self.auth.as_ref().and_then(Auth::credential_header);
+            let replay = Replay::of(&request, credential_header);
+            let started = Started(Instant::now());
+            let resp = self.execute(client, request, hmac, &first_party).await?;
+            let location = match redirect_location(&resp) {
+                Some(location) if self.redirects.follow => location,
+                _ => return Ok(resp),
+            };
+            // Also refuses schemes reqwest cannot request, such as `file:`
+            self.hosts.check_url(&location)?;
+            if history.len() >= self.redirects.max {
+                return Err(RedirectLimit {
+                    max: self.redirects.max,
+                    url: location,
+                }
+                .into());
+            }
+            if is_cross_host(resp.url(), &location) {
+                hmac = None;
+            }
+            let Some(next) = replay.follow(resp.status(), location, self.redirects.referer) else {
+                return Ok(resp);
+            };
+            let mut resp = self.decompression.decode(resp, &method, &self.codecs);
+            resp.extensions_mut().insert(started);
+            history.push(ResponseParts::read(resp).await?);
+            request = next;
+        }
+    }
+
+    /// Build the `reqwest` request, with the `Authorization` header only if `with_auth`.
+    async fn build(
+        &self,
+        client: &reqwest::Client,
+        client_headers: &HeaderMap,
+        content: Option<Bytes>,
+        with_auth: bool,
+    ) -> Result<reqwest::Request> {
+        let has_body = method_has_body(&self.method);
+
+        // Create request builder
+        let mut request_builder = client.request(self.method.clone(), &self.url);
+
+        // Params
+        if let Some(params) = &self.params {
+            request_builder = request_builder.query(params);
+        }
+
+        // Headers from client
+        request_builder = request_builder.headers(client_headers.clone());
+
+        // Headers
+        if let Some(headers) = &self.headers {
+            request_builder = request_builder.headers(headers.to_headermap());
+        }
+
+        // Cookies, merged with or replacing the client cookies
+        if let Some(cookies) = &self.cookies {
+            let value = self
+                .cookie_merge
+                .header(client_headers.get(COOKIE), cookies);
+            let mut cookie_header = HeaderMap::new();
+            cookie_header.insert(
+                COOKIE,
+                HeaderValue::from_str(&value).map_err(anyhow::Error::new)?,
+            );
+            request_builder = request_builder.headers(cookie_header);
+        }
+
+        // Only if method POST || PUT || PATCH, or a WebDAV method that carries a body
+        if has_body {
+            // Content
+            if let Some(content) = content {
+                request_builder = request_builder.body(content);
+            }
+            if let Some(chunks) = &self.content_stream {
+                request_builder = request_builder.body(chunks.body());
+            }
+            // Data
+            if let Some(form_data) = &self.data {
+                request_builder = request_builder.form(form_data);
+            }
+            // Json - always serialize as JSON regardless of Accept header
+            if let Some(json_data) = &self.json {
+                if let Some(body) = self.json_format.serialize(json_data) {
+                    // Content-Type as `RequestBuilder::json` sets it, unless already given
+                    let has_content_type = client_headers.contains_key(CONTENT_TYPE)
+                        || self
+                            .headers
+                            .iter()
+                            .flatten()
+                            .any(|(name, _)| name.eq_ignore_ascii_case(CONTENT_TYPE.as_str()));
+                    if !has_content_type {
+                        request_builder = request_builder.header(CONTENT_TYPE, "application/json");
+                    }
+                    request_builder = request_builder.body(body);
+                } else {
+                    request_builder = request_builder.json(json_data);
+                }
+            }
+            // Files
+            if let Some(files) = &self.files {
+                request_builder = request_builder.multipart(files.form().await?);
+            }
+        }
+
+        // Auth
+        if with_auth {
+            match &self.auth {
+                Some(Auth::Basic((username, password))) => {
+                    request_builder = request_builder.basic_auth(username, password.as_ref());
+                }
+                Some(Auth::ApiKey(api_key)) => {
+                    request_builder = api_key.apply(request_builder)?;
+                }
+                Some(Auth::Hmac(_)) => {}
+                None => {
+                    if let Some(token) = &self.auth_bearer {
+                        request_builder = request_builder.bearer_auth(token);
+                    }
+                }
+            }
+        }
+
+        // Timeout
+        if let Some(seconds) = self.timeout {
+            request_builder = request_builder.timeout(Duration::from_secs_f64(seconds));
+        }
+
+        let mut request = request_builder.build().map_err(anyhow::Error::new)?;
+
+        // Request headers take precedence over client headers, which take precedence
+        // over generated ones; `None` removes the header whatever its source
+        for name in &self.removed_headers {
+            request.headers_mut().remove(name);
+        }
+
+        // Set directly, as `RequestBuilder::query` would re-encode it
+        if let Some(raw_query) = &self.raw_query {
+            let url = request.url_mut();
+            let query = match url.query() {
+                Some(query) if !query.is_empty() => format!("{}&{}", query, raw_query),
+                _ => raw_query.clone(),
+            };
+            url.set_query(Some(&query));
+        }
+
+        self.codecs.encode_request(&mut request).await?;
+        Ok(request)
+    }
+
+    /// Send a built request, evaluating the lazy headers, signing it with `hmac`, running
+    /// the `sign` hook and applying the upload rate limit. Cookies are judged first- or
+    /// third-party against `first_party`, the URL the caller asked for.
+    ///
+    /// With `retry_idempotent`, an idempotent request that fails on the connection level
+    /// before any response arrives, e.g. on a stale pooled connection, is sent once more,
+    /// with the lazy headers, the signature and the `sign` hook made again.
+    async fn execute(
+        &self,
+        client: &reqwest::Client,
+        mut request: reqwest::Request,
+        hmac: Option<&HmacAuth>,
+        first_party: &Url,
+    ) -> Result<reqwest::Response> {
+        // Streamed bodies cannot be cloned, so those requests are never replayed
+        let replay = if self.retry_idempotent && self.method.is_idempotent() {
+            request.try_clone()
+        } else {
+            None
+        };
+        self.prepare(&mut request, hmac)?;
+
+        // Send the request and await the response
+        let url = request.url().clone();
+        let send = with_first_party(first_party, self.send_once(client, self.wrap_body(request)));
+        let mut result = self.attempts.record(&url, false, send).await;
+        if let (Err(err), Some(mut request)) = (&result, replay) {
+            if err
+                .downcast_ref::<reqwest::Error>()
+                .is_some_and(is_connection_error)
+            {
+                tracing::debug!(
+                    "retrying {} {} on a new connection: {}",
+                    self.method,
+                    url,
+                    err
+                );
+                self.prepare(&mut request, hmac)?;
+                let send =
+                    with_first_party(first_party, self.send_once(client, self.wrap_body(request)));
+                result = self.attempts.record(&url, true, send).await;
+            }
+        }
+        let resp = result?;
+        if let Some(events) = &self.connection_events {
+            events.observe(&url, &resp);
+        }
+        if let Some(limits) = &self.header_limits {
+            limits.check(resp.headers())?;
+        }
+        Ok(resp)
+    }
+
+    /// Send `request` once, through the transport if there is one, or else to the host
+    /// given by `sni_hostname`.
+    async fn send_once(
+        &self,
+        client: &reqwest::Client,
+        mut request: reqwest::Request,
+    ) -> Result<reqwest::Response> {
+        if let Some(transport) = &self.transport {
+            return transport.send(request).await;
+        }
+        let requested = sni::route(
+            &mut request,
+            self.sni_hostname.as_deref(),
+            self.allow_sni_mismatch,
+        )?;
+        let resp = client.execute(request).await?;
+        Ok(match requested {
+            Some(url) => rebuild_response(resp, url, |body| body),
+            None => resp,
+        })
+    }
+
+    /// Finish a request for one attempt: lazy headers and `Content-Digest`, then the
+    /// `HmacAuth` signature over the final URL, headers and body, then the `sign` hook,
+    /// which sees them all, then `Accept-Encoding`.
+    fn prepare(&self, request: &mut reqwest::Request, hmac: Option<&HmacAuth>) -> Result<()> {
+        self.lazy_headers.apply(request)?;
+        if let Some(digest) = &self.content_digest {
+            digest.attach(request);
+        }
+        if let Some(hmac) = hmac {
+            hmac.sign(request)?;
+        }
+        if let Some(sign) = &self.sign {
+            sign.apply(request)?;
+        }
+        if !self.removed_headers.contains(&ACCEPT_ENCODING) {
+            self.decompression.accept_encoding(request, &self.codecs);
+        }
+        Ok(())
+    }
+
+    /// Stream the request body through the upload rate limit and the byte counter, and
+    /// the `Content-Digest` trailer hasher.
+    fn wrap_body(&self, mut request: reqwest::Request) -> reqwest::Request {
+        if let Some(limiter) = &self.max_upload_rate {
+            if let Some(body) = request.body_mut().take() {
+                if let Some(len) = body.as_bytes().map(<[u8]>::len) {
+                    request
+                        .headers_mut()
+                        .insert(CONTENT_LENGTH, HeaderValue::from(len));
+                }
+                *request.body_mut() = Some(limiter.throttle_body(body));
+            }
+        }
+        if let Some(body) = request.body_mut().take() {
+            *request.body_mut() = Some(self.meter.count_sent(body));
+        }
+        if let Some(digest) = &self.content_digest {
+            digest.wrap_body(&mut request);
+        }
+        request
+    }
+
+    /// Identity of this request over `fields`, for `dedupe_on`. Requests with equal keys
+    /// are sent once.
+    pub fn dedupe_key(&self, fields: &[DedupeField]) -> String {
+        let mut key = String::new();
+        for field in fields {
+            match field {
+                DedupeField::Method => key.push_str(self.method.as_str()),
+                DedupeField::Url => {
+                    key.push_str(&self.url);
+                    for (name, value) in self.params.iter().flatten() {
+                        let _ = write!(key, "&{name}={value}");
+                    }
+                }
+                DedupeField::Headers => {
+                    let mut headers: Vec<_> = self
+                        .headers
+                        .iter()
+                        .flatten()
+                        .map(|(name, value)| (name.to_ascii_lowercase(), value))
+                        .collect();
+                    headers.sort();
+                    for (name, value) in headers {
+                        let _ = writeln!(key, "{name}: {value}");
+                    }
+                    for name in &self.removed_headers {
+                        let _ = writeln!(key, "{name}: None");
+                    }
+                }
+                DedupeField::Body => {
+                    let _ = write!(
+                        key,
+                        "{:?}{:?}{:?}{:?}",
+                        self.content, self.data, self.json, self.files
+                    );
+                }
+            }
+            key.push('\0');
+        }
+        key
+    }
+}
+
+/// Request parts compared by `dedupe_on` in batch sends.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DedupeField {
+    Method,
+    /// The URL together with the query `params`.
+    Url,
+    /// Per-request headers, compared case-insensitively by name.
+    Headers,
+    /// `content`, `data`, `json` and `files`.
+    Body,
+}
+
+impl DedupeField {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "method" => Ok(DedupeField::Method),
+            "url" => Ok(DedupeField::Url),
+            "headers" => Ok(DedupeField::Headers),
+            "body" => Ok(DedupeField::Body),
+            _ => anyhow::bail!(
+                "dedupe_on takes 'method', 'url', 'headers' and 'body', got {:?}",
+                name
+            ),
+        }
+    }
+}
+
+/// Group equal `keys`: returns the index of the first occurrence of each distinct key,
+/// and for every key the position of its group in that list.
+pub fn dedupe_groups<K: std::hash::Hash + Eq>(
+    keys: impl IntoIterator<Item = K>,
+) -> (Vec<usize>, Vec<usize>) {
+    let mut groups = std::collections::HashMap::new();
+    let mut firsts = Vec::new();
+    let owners = keys
+        .into_iter()
+        .enumerate()
+        .map(|(index, key)| {
+            *groups.entry(key).or_insert_with(|| {
+                firsts.push(index);
+                firsts.len() - 1
+            })
+        })
+        .collect();
+    (firsts, owners)
+}
+
+/// When credentials are attached to requests.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum AuthMode {
+    /// Send the `Authorization` header with the first request.
+    #[default]
+    Preemptive,
+    /// Send credentials only after a 401 whose `WWW-Authenticate` offers the scheme.
+    OnChallenge,
+}
+
+impl AuthMode {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "preemptive" => Ok(AuthMode::Preemptive),
+            "on_challenge" => Ok(AuthMode::OnChallenge),
+            _ => anyhow::bail!(
+                "auth_mode must be 'preemptive' or 'on_challenge', got {:?}",
+                name
+            ),
+        }
+    }
+}
+
+/// Whether any `WWW-Authenticate` header offers an auth `scheme` (case-insensitive).
+///
+/// Challenges and their parameters are both comma separated, so a piece is a challenge
+/// when its first token is not a `name=value` parameter.
+fn has_challenge(headers: &HeaderMap, scheme: &str) -> bool {
+    headers
+        .get_all(WWW_AUTHENTICATE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|piece| piece.split_whitespace().next())
+        .any(|token| token.eq_ignore_ascii_case(scheme))
+}
+
+/// Check that `query` can be sent verbatim as `raw_query`: `url` would percent-encode
+/// spaces, non-ASCII and a few other characters, changing the query string.
+pub fn check_raw_query(query: &str) -> Result<()> {
+    if let Some(c) = query
+        .chars()
+        .find(|c| !c.is_ascii_graphic() || matches!(c, '"' | '#' | '<' | '>' | '\''))
+    {
+        bail!("raw_query must be percent-encoded, found {:?}", c);
+    }
+    Ok(())
+}
+
+/// Give `resp` the fragment of the request URL. Fragments are never sent to the server,
+/// and the response URL is rebuilt from the request line, which drops it.
+fn restore_fragment(resp: reqwest::Response, fragment: &str) -> reqwest::Response {
+    if resp.url().fragment().is_some() {
+        return resp;
+    }
+    let mut url = resp.url().clone();
+    url.set_fragment(Some(fragment));
+    rebuild_response(resp, url, |body| body)
+}
+
+/// Replace the body of `resp` with `wrap(body)`, keeping status, headers and URL.
+pub fn map_response_body(
+    resp: reqwest::Response,
+    wrap: impl FnOnce(Body) -> Body,
+) -> reqwest::Response {
+    let url = resp.url().clone();
+    rebuild_response(resp, url, wrap)
+}
+
+/// `resp` with the body `wrap(body)`, reported for `url`. Its extensions are kept, such
+/// as the connection info and the pending connection upgrade.
+fn rebuild_response(
+    resp: reqwest::Response,
+    url: Url,
+    wrap: impl FnOnce(Body) -> Body,
+) -> reqwest::Response {
+    let (parts, body) = http::Response::<Body>::from(resp).into_parts();
+    let mut builder = http::Response::builder()
+        .status(parts.status)
+        .version(parts.version);
+    if let Some(extensions) = builder.extensions_mut() {
+        *extensions = parts.extensions;
+    }
+    let mut builder = builder.url(url);
+    if let Some(headers) = builder.headers_mut() {
+        *headers = parts.headers;
+    }
+    let resp = builder
+        .body(wrap(body))
+        .expect("status, version and headers come from a valid response");
+    reqwest::Response::from(resp)
+}
+
+/// When a request was sent, attached to its response by `RequestSpec::send`.
+#[derive(Clone, Copy)]
+pub struct Started(pub Instant);
+
+/// Response items extracted from a `reqwest::Response` once its body has been read.
+#[derive(Clone)]
+pub struct ResponseParts {
+    pub content: Bytes,
+    pub cookies: IndexMapSSR,
+    pub headers: IndexMapSSR,
+    pub status_code: u16,
+    pub url: String,
+    /// From sending the request until the body was read.
+    pub elapsed: Duration,
+    /// Body bytes sent and received, before and after content decoding.
+    pub request_body_size: u64,
+    pub response_body_size: u64,
+    pub decompressed_body_size: u64,
+    pub server_timing: Vec<ServerTiming>,
+    /// The redirect responses that led to this one, oldest first.
+    pub history: Vec<ResponseParts>,
+    /// Every send of the request, including redirect hops and retries.
+    pub attempts: Vec<Attempt>,
+}
+
+impl ResponseParts {
+    /// Read the full body of `resp` and collect the response items.
+    pub async fn read(mut resp: reqwest::Response) -> Result<Self> {
+        let history = resp
+            .extensions_mut()
+            .remove::<RedirectHistory>()
+            .unwrap_or_default();
+        let cookies = response_cookies(&resp);
+        let headers: IndexMapSSR = resp.headers().to_indexmap();
+        let server_timing = ServerTiming::from_headers(resp.headers());
+        let status_code = resp.status().as_u16();
+        let url = resp.url().to_string();
+        let started = resp.extensions().get::<Started>().copied();
+        let transfer = resp.extensions().get::<Transfer>().cloned();
+        let attempts = resp
+            .extensions()
+            .get::<AttemptLog>()
+            .map(AttemptLog::entries)
+            .unwrap_or_default();
+        // Body errors carry no URL, which the exception's remote_address comes from
+        let response_url = resp.url().clone();
+        let content = resp
+            .bytes()
+            .await
+            .map_err(|e| anyhow::Error::new(e.with_url(response_url)))?;
+        let elapsed = started.map(|s| s.0.elapsed()).unwrap_or_default();
+        let counts = transfer.map(|transfer| transfer.0).unwrap_or_default();
+
+        tracing::info!("response: {} {} {}", url, status_code, content.len());
+        Ok(ResponseParts {
+            content,
+            cookies,
+            headers,
+            status_code,
+            url,
+            elapsed,
+            request_body_size: counts.sent(),
+            response_body_size: counts.received(),
+            decompressed_body_size: counts.decompressed(),
+            server_timing,
+            history: history.0,
+            attempts,
+        })
+    }
+}
+
+/// A request that has been built but not yet sent.
+///
+/// Instances are created directly or by rendering a `RequestTemplate`, and are sent
+/// with `client.send(request)`. Any argument left as `None` falls back to the client
+/// defaults at send time, exactly as with `client.request()`.
+#[pyclass]
+#[derive(Clone)]
+pub struct Request {
+    #[pyo3(get)]
+    pub method: String,
+    #[pyo3(get)]
+    pub url: String,
+    #[pyo3(get)]
+    pub params: Option<IndexMapSSR>,
+    #[pyo3(get)]
+    pub headers: Option<IndexMapSSR>,
+    pub removed_headers: Vec<HeaderName>,
+    pub lazy_headers: LazyHeaders,
+    #[pyo3(get)]
+    pub cookies: Option<IndexMapSSR>,
+    pub content: Option<Bytes>,
+    pub data: Option<Value>,
+    pub json: Option<Value>,
+    pub files: Option<FileParts>,
+    #[pyo3(get)]
+    pub auth: Option<Auth>,
+    #[pyo3(get)]
+    pub auth_bearer: Option<String>,
+    #[pyo3(get)]
+    pub timeout: Option<f64>,
+}
+
+#[pymethods]
+impl Request {
+    #[new]
+    #[pyo3(signature = (method, url, params=None, headers=None, cookies=None, content=None,
+        data=None, json=None, files=None, auth=None, auth_bearer=None, timeout=None,
+        encoding=None))]
+    fn new(
+        method: String,
+        url: String,
+        params: Option<IndexMapSSR>,
+        headers: Option<HeaderArgs>,
+        cookies: Option<IndexMapSSR>,
+        content: Option<&Bound<'_, PyAny>>,
+        data: Option<&Bound<'_, PyAny>>,
+        json: Option<&Bound<'_, PyAny>>,
+        files: Option<&Bound<'_, PyAny>>,
+        auth: Option<Auth>,
+        auth_bearer: Option<String>,
+        timeout: Option<f64>,
+        encoding: Option<String>,
+    ) -> PyResult<Self> {
+        let (headers, removed_headers, lazy_headers) = split_headers(headers)?;
+        let files = files
+            .map(|files| FileParts::from_py(files, None))
+            .transpose()?;
+        let content = content
+            .map(|content| content_bytes(content, encoding.as_deref()))
+            .transpose()?;
+        let data: Option<Value> = data
+            .map(depythonize)
+            .transpose()
+            .map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?;
+        let json: Option<Value> = json.map(|json| to_json(json, None)).transpose()?;
+        Ok(Request {
+            method: method.to_uppercase(),
+            url,
+            params,
+            headers,
+            removed_headers,
+            lazy_headers,
+            cookies,
+            content,
+            data,
+            json,
+            files,
+            auth,
+            auth_bearer,
+            timeout,
+        })
+    }
+
+    #[getter]
+    fn content(&self, py: Python) -> Option<Py<PyBytes>> {
+        self.content
+            .as_ref()
+            .map(|content| PyBytes::new(py, content).unbind())
+    }
+
+    #[getter]
+    fn files<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.files.as_ref().map(|files| files.to_py(py)).transpose()
+    }
+
+    #[getter]
+    fn data(&self, py: Python) -> PyResult<Option<Py<PyAny>>> {
+        self.data
+            .as_ref()
+            .map(|data| {
+                pythonize(py, data)
+                    .map(|obj| obj.unbind())
+                    .map_err(|e| map_anyhow_error(anyhow::Error::new(e)))
+            })
+            .transpose()
+    }
+
+    #[getter]
+    fn json(&self, py: Python) -> PyResult<Option<Py<PyAny>>> {
+        self.json
+            .as_ref()
+            .map(|json| {
+                pythonize(py, json)
+                    .map(|obj| obj.unbind())
+                    .map_err(|e| map_anyhow_error(anyhow::Error::new(e)))
+            })
+            .transpose()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Request [{} {}]>", self.method, self.url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn challenge_headers(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(WWW_AUTHENTICATE, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn test_dedupe_groups() {
+        let (firsts, owners) = dedupe_groups(["a", "b", "a", "c", "b"]);
+        assert_eq!(firsts, vec![0, 1, 3]);
+        assert_eq!(owners, vec![0, 1, 0, 2, 1]);
+
+        assert!(DedupeField::from_name("body").is_ok());
+        assert!(DedupeField::from_name("cookies").is_err());
+    }
+
+    #[test]
+    fn test_check_raw_query() {
+        assert!(check_raw_query("a=1&a=2&weird=%2F&sig=abc+/=").is_ok());
+        assert!(check_raw_query("").is_ok());
+        for query in ["a=b c", "a=\"x\"", "a=1#frag", "a='x'", "caf\u{e9}=1"] {
+            assert!(check_raw_query(query).is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_has_challenge() {
+        let headers = challenge_headers(&[r#"Basic realm="api", charset="UTF-8""#]);
+        assert!(has_challenge(&headers, "Basic"));
+        assert!(!has_challenge(&headers, "Bearer"));
+
+        let headers = challenge_headers(&[r#"Negotiate, bearer realm="x", error="invalid_token""#]);
+        assert!(has_challenge(&headers, "Bearer"));
+        assert!(!has_challenge(&headers, "Basic"));
+
+        let headers = challenge_headers(&["Digest realm=\"a\"", "Basic"]);
+        assert!(has_challenge(&headers, "Basic"));
+        assert!(!has_challenge(&HeaderMap::new(), "Basic"));
+    }
+
+    #[test]
+    fn test_auth_mode_from_name() {
+        assert_eq!(
+            AuthMode::from_name("on_challenge").unwrap(),
+            AuthMode::OnChallenge
+        );
+        assert_eq!(
+            AuthMode::from_name("preemptive").unwrap(),
+            AuthMode::Preemptive
+        );
+        assert!(AuthMode::from_name("lazy").is_err());
+    }
+}
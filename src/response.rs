@@ -2,21 +2,138 @@
 // and the MutexGuards are intentionally held across block_on calls
 #![allow(clippy::await_holding_lock)]
 
-use crate::exceptions::{StreamClosed, StreamConsumed};
+use crate::arrow::ArrowFormat;
+use crate::attempts::{count_retries, Attempt, AttemptLog};
+use crate::block_on;
+use crate::cookies::response_cookies;
+use crate::csvrows::CsvRows;
+use crate::download::write_body;
+use crate::exceptions::{map_anyhow_error, HTTPStatusError, StreamClosed, StreamConsumed};
+use crate::htmltext::AbsoluteLinks;
+use crate::jsonarray::JsonArrayScanner;
+use crate::jsonpath::{self, NdjsonMatcher};
+use crate::links::links_by_rel;
+use crate::problem::ProblemDetails;
+use crate::redirect::RedirectHistory;
+use crate::request::ResponseParts;
+use crate::schema;
+use crate::servertiming::ServerTiming;
+use crate::sniff::{detect_language, sniff_mime_type, SniffResult};
+use crate::traits::HeadersTraits;
+use crate::transfer::{ByteCounts, Transfer};
 use crate::utils::{get_encoding_from_case_insensitive_headers, get_encoding_from_content};
-use crate::RUNTIME;
 use anyhow::{anyhow, Result};
+use bytes::Bytes;
 use encoding_rs::Encoding;
 use foldhash::fast::RandomState;
 use html2text::{
-    from_read, from_read_with_decorator,
-    render::{RichDecorator, TrivialDecorator},
+    from_read_with_decorator,
+    render::{PlainDecorator, RichDecorator, TextDecorator, TrivialDecorator},
 };
 use indexmap::IndexMap;
-use pyo3::{prelude::*, types::PyBytes, IntoPyObject};
-use pythonize::pythonize;
+use pyo3::exceptions::{PyAssertionError, PyLookupError, PyTypeError, PyValueError};
+use pyo3::types::{PyBytes, PyDict, PyMemoryView, PySlice, PyTuple};
+use pyo3::{prelude::*, IntoPyObject};
+use pythonize::{depythonize, pythonize};
+use reqwest::StatusCode;
 use serde_json::from_slice;
+use std::collections::VecDeque;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::fs::File;
+use url::Url;
+
+/// Map an error from reading a stream chunk: transport errors (e.g. a stalled transfer)
+/// keep their httpr exception type, anything else is a `RuntimeError`.
+fn chunk_error(err: anyhow::Error) -> PyErr {
+    if err.downcast_ref::<reqwest::Error>().is_some() {
+        map_anyhow_error(err)
+    } else {
+        pyo3::exceptions::PyRuntimeError::new_err(err.to_string())
+    }
+}
+
+/// A flat, writable byte view of `buffer`, any object supporting the buffer protocol
+/// (`bytearray`, NumPy arrays, `mmap`, ...). Multi-dimensional buffers must be C-contiguous.
+fn byte_view<'py>(buffer: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    let view = PyMemoryView::from(buffer)?;
+    if view.getattr("readonly")?.extract::<bool>()? {
+        return Err(PyTypeError::new_err("buffer is read-only"));
+    }
+    if !view.getattr("c_contiguous")?.extract::<bool>()? {
+        return Err(PyValueError::new_err("buffer must be C-contiguous"));
+    }
+    view.call_method1("cast", ("B",))
+}
+
+/// Copy `data` into `view` at byte `offset`, failing if it does not fit.
+fn write_at(view: &Bound<'_, PyAny>, offset: usize, data: &Bound<'_, PyBytes>) -> PyResult<usize> {
+    let end = offset + data.as_bytes().len();
+    let capacity = view.len()?;
+    if end > capacity {
+        return Err(PyValueError::new_err(format!(
+            "Response body does not fit in the buffer ({} bytes)",
+            capacity
+        )));
+    }
+    let slice = PySlice::new(view.py(), offset as isize, end as isize, 1);
+    view.set_item(slice, data)?;
+    Ok(end)
+}
+
+/// Maximum number of body bytes shown in an `HTTPStatusError` message.
+const BODY_PREVIEW_LIMIT: usize = 1024;
+
+/// Fields of a JSON error body that usually carry the server's error message.
+const JSON_ERROR_FIELDS: [&str; 7] = [
+    "error",
+    "error_description",
+    "message",
+    "title",
+    "detail",
+    "code",
+    "errors",
+];
+
+/// The first `BODY_PREVIEW_LIMIT` bytes of `content` decoded with `encoding`, with a
+/// trailing `...` if the body was cut.
+fn body_preview(content: &[u8], encoding: &str) -> String {
+    let encoding = Encoding::for_label(encoding.as_bytes()).unwrap_or(encoding_rs::UTF_8);
+    let truncated = content.len() > BODY_PREVIEW_LIMIT;
+    let (text, _, _) = encoding.decode(&content[..content.len().min(BODY_PREVIEW_LIMIT)]);
+    let mut preview = text.into_owned();
+    if truncated {
+        // Drop a multi-byte character split by the cut
+        preview = preview.trim_end_matches('\u{FFFD}').to_string();
+        preview.push_str("...");
+    }
+    preview
+}
+
+/// The parsed body, if `content_type` is JSON and the body parses.
+fn json_error_body(content: &[u8], content_type: &str) -> Option<serde_json::Value> {
+    let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    if mime != "application/json" && !mime.ends_with("+json") {
+        return None;
+    }
+    from_slice(content).ok()
+}
+
+/// Well-known error fields of a JSON object body.
+fn json_error_fields(
+    body: &serde_json::Value,
+) -> Option<serde_json::Map<String, serde_json::Value>> {
+    let serde_json::Value::Object(body) = body else {
+        return None;
+    };
+    let fields: serde_json::Map<_, _> = body
+        .iter()
+        .filter(|(key, _)| JSON_ERROR_FIELDS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    (!fields.is_empty()).then_some(fields)
+}
 
 /// A struct representing an HTTP response.
 ///
@@ -138,14 +255,130 @@ pub struct Response {
     pub content: Py<PyBytes>,
     #[pyo3(get)]
     pub cookies: IndexMap<String, String, RandomState>,
-    #[pyo3(get, set)]
     pub encoding: String,
+    /// Whether `encoding` was assigned in Python rather than detected.
+    pub encoding_set: bool,
     #[pyo3(get)]
     pub headers: CaseInsensitiveHeaderMap,
     #[pyo3(get)]
     pub status_code: u16,
     #[pyo3(get)]
     pub url: String,
+    /// Time from sending the request until the body was read, as a `datetime.timedelta`.
+    #[pyo3(get)]
+    pub elapsed: Duration,
+    /// Request body bytes sent.
+    #[pyo3(get)]
+    pub request_body_size: u64,
+    /// Response body bytes as transferred, before content decoding.
+    #[pyo3(get)]
+    pub response_body_size: u64,
+    /// Response body bytes after content decoding, the length of `content`.
+    #[pyo3(get)]
+    pub decompressed_body_size: u64,
+    /// Metrics of the `Server-Timing` headers.
+    #[pyo3(get)]
+    pub server_timing: Vec<ServerTiming>,
+    /// The redirect responses that led to this one, oldest first.
+    pub history: Vec<ResponseParts>,
+    pub attempts: Vec<Attempt>,
+    /// What links and images in the HTML-to-text conversions are resolved against,
+    /// initially `url`; `None` keeps them as written.
+    pub link_base: Option<Url>,
+}
+
+/// Schema violations listed by `Response.expect()` before the rest are elided.
+const MAX_EXPECT_SCHEMA_ERRORS: usize = 5;
+
+/// `Response` objects for the redirect responses a request went through.
+fn history_responses(py: Python, history: &[ResponseParts]) -> Vec<Response> {
+    history
+        .iter()
+        .map(|parts| Response::from_parts(py, parts.clone()))
+        .collect()
+}
+
+/// The media type of a `Content-Type` value, lowercased and without parameters.
+fn media_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// The media type of the `Content-Type` header, if there is one.
+fn header_media_type(headers: &CaseInsensitiveHeaderMap) -> Option<String> {
+    headers
+        .get_value("content-type")
+        .map(|content_type| media_type(&content_type))
+        .filter(|media_type| !media_type.is_empty())
+}
+
+/// The `Link` header entries keyed by `rel`, for `links`.
+fn header_links(
+    headers: &CaseInsensitiveHeaderMap,
+    url: &str,
+) -> IndexMap<String, IndexMap<String, String, RandomState>, RandomState> {
+    let value = headers.get_value("link").unwrap_or_default();
+    links_by_rel(&value, url)
+}
+
+/// The first line of the `HTTPStatusError` message for a 4xx or 5xx status, or `None`
+/// for other statuses.
+fn status_error_message(status_code: u16, url: &str) -> PyResult<Option<String>> {
+    let status =
+        StatusCode::from_u16(status_code).map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?;
+    let kind = if status.is_client_error() {
+        "Client error"
+    } else if status.is_server_error() {
+        "Server error"
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(format!(
+        "{} '{} {}' for url '{}'",
+        kind,
+        status.as_u16(),
+        status.canonical_reason().unwrap_or(""),
+        url
+    )))
+}
+
+impl Response {
+    /// Build a `Response` from response items whose body has already been read.
+    pub fn from_parts(py: Python, parts: ResponseParts) -> Self {
+        let link_base = Url::parse(&parts.url).ok();
+        Response {
+            content: PyBytes::new(py, &parts.content).unbind(),
+            cookies: parts.cookies,
+            encoding: String::new(),
+            encoding_set: false,
+            headers: CaseInsensitiveHeaderMap::from_indexmap(parts.headers),
+            status_code: parts.status_code,
+            url: parts.url,
+            elapsed: parts.elapsed,
+            request_body_size: parts.request_body_size,
+            response_body_size: parts.response_body_size,
+            decompressed_body_size: parts.decompressed_body_size,
+            server_timing: parts.server_timing,
+            history: parts.history,
+            attempts: parts.attempts,
+            link_base,
+        }
+    }
+
+    /// The body rendered by html2text with the decorator `make` returns, links resolved
+    /// against `link_base`. Decorators are not `Send`, so it is made off the GIL.
+    fn html_to_text<D: TextDecorator>(&self, py: Python, make: fn() -> D) -> Result<String> {
+        let raw_bytes = self.content.bind(py).as_bytes();
+        let base = self.link_base.clone();
+        let text = py.detach(|| {
+            from_read_with_decorator(raw_bytes, 100, AbsoluteLinks::new(make(), base))
+        })?;
+        Ok(text)
+    }
 }
 
 #[pymethods]
@@ -161,6 +394,93 @@ impl Response {
         Ok(&self.encoding)
     }
 
+    /// Decode `text` with this encoding from now on, even if the body starts with a
+    /// byte order mark of another one. `None` restores detection.
+    #[setter]
+    fn set_encoding(&mut self, encoding: Option<String>) {
+        self.encoding_set = encoding.is_some();
+        self.encoding = encoding.unwrap_or_default();
+    }
+
+    /// The redirect responses followed to get this one, oldest first. Empty unless the
+    /// request was redirected.
+    #[getter]
+    fn history(&self, py: Python) -> Vec<Response> {
+        history_responses(py, &self.history)
+    }
+
+    /// Every time the request went out, oldest first: the first send, retries after a
+    /// connection error, a resend after an auth challenge and each redirect hop.
+    #[getter]
+    fn attempts<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.attempts
+            .iter()
+            .map(|attempt| attempt.to_dict(py))
+            .collect()
+    }
+
+    /// How many of `attempts` were retries after a failed attempt.
+    #[getter]
+    fn retries(&self) -> usize {
+        count_retries(&self.attempts)
+    }
+
+    /// The media type of the `Content-Type` header, lowercased and without parameters
+    /// such as `charset`, or `None` without one.
+    #[getter]
+    fn content_type(&self) -> Option<String> {
+        header_media_type(&self.headers)
+    }
+
+    /// Detect the media type of the body from its bytes, ignoring `Content-Type`, and
+    /// for text and HTML bodies their natural language.
+    ///
+    /// # Example
+    /// ```python
+    /// sniffed = response.sniff()
+    /// if sniffed.mime_type != sniffed.declared_type:
+    ///     print(f"claims {sniffed.declared_type}, looks like {sniffed.mime_type}")
+    /// ```
+    fn sniff(&mut self, py: Python) -> Result<SniffResult> {
+        let mime_type = sniff_mime_type(self.content.as_bytes(py));
+        let text = match mime_type {
+            "text/html" => Some(self.html_to_text(py, TrivialDecorator::new)?),
+            "text/plain" => Some(self.text(py)?),
+            _ => None,
+        };
+        Ok(SniffResult {
+            mime_type: mime_type.to_string(),
+            language: text.and_then(|text| detect_language(&text).map(String::from)),
+            declared_type: self.content_type(),
+        })
+    }
+
+    /// The entries of the `Link` header keyed by `rel`, each a dict of their parameters
+    /// and `url`, resolved against the response URL.
+    ///
+    /// # Example
+    /// ```python
+    /// next_url = response.links.get("next", {}).get("url")
+    /// ```
+    #[getter]
+    fn links(&self) -> IndexMap<String, IndexMap<String, String, RandomState>, RandomState> {
+        header_links(&self.headers, &self.url)
+    }
+
+    /// Copy the body into `buffer`, a writable object supporting the buffer protocol such
+    /// as a `bytearray`, NumPy array or `mmap`, and return the number of bytes written.
+    ///
+    /// # Example
+    /// ```python
+    /// array = numpy.empty(len(response.content) // 8, dtype=numpy.float64)
+    /// response.into_buffer(array)
+    /// ```
+    #[pyo3(name = "into_buffer")]
+    fn copy_into(&self, py: Python, buffer: &Bound<'_, PyAny>) -> PyResult<usize> {
+        let view = byte_view(buffer)?;
+        write_at(&view, 0, self.content.bind(py))
+    }
+
     #[getter]
     fn text(&mut self, py: Python) -> Result<String> {
         // If self.encoding is empty, call get_encoding to populate self.encoding
@@ -175,6 +495,11 @@ impl Response {
         py.detach(|| {
             let encoding = Encoding::for_label(self.encoding.as_bytes())
                 .ok_or_else(|| anyhow!("Unsupported charset: {}", self.encoding))?;
+            // An assigned encoding is authoritative; a detected one yields to a BOM
+            if self.encoding_set {
+                let (decoded_str, _) = encoding.decode_without_bom_handling(raw_bytes);
+                return Ok(decoded_str.to_string());
+            }
             let (decoded_str, detected_encoding, _) = encoding.decode(raw_bytes);
 
             // Update self.encoding based on the detected encoding
@@ -186,6 +511,20 @@ impl Response {
         })
     }
 
+    /// The body decoded with `encoding`, leaving `encoding` and `text` unchanged.
+    /// Raises `LookupError` for an unknown encoding.
+    ///
+    /// # Example
+    /// ```python
+    /// text = response.text_with_encoding("shift_jis")
+    /// ```
+    fn text_with_encoding(&self, py: Python, encoding: &str) -> PyResult<String> {
+        let encoding = Encoding::for_label(encoding.trim().as_bytes())
+            .ok_or_else(|| PyLookupError::new_err(format!("unknown encoding: {}", encoding)))?;
+        let raw_bytes = self.content.as_bytes(py);
+        Ok(py.detach(|| encoding.decode_without_bom_handling(raw_bytes).0.into_owned()))
+    }
+
     fn json(&mut self, py: Python) -> Result<Py<PyAny>> {
         // Check if Content-Type is application/cbor
         let content_type = self.headers.get("content-type".to_string(), None);
@@ -209,6 +548,62 @@ impl Response {
         }
     }
 
+    /// Validate the JSON body against a JSON Schema, parsing and checking it without
+    /// holding the GIL.
+    ///
+    /// Returns one dict per violation, with `path` (JSON Pointer into the body),
+    /// `schema_path` (JSON Pointer to the failing schema keyword) and `message`; an empty
+    /// list when the body is valid. Raises `ValueError` if the body is not JSON or the
+    /// schema is invalid.
+    fn validate<'py>(
+        &self,
+        py: Python<'py>,
+        schema: &Bound<'py, PyAny>,
+    ) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let schema: serde_json::Value = depythonize(schema)?;
+        let content = self.content.as_bytes(py);
+        let errors = py
+            .detach(|| {
+                let instance: serde_json::Value =
+                    from_slice(content).map_err(|e| anyhow!("Response body is not JSON: {}", e))?;
+                schema::validate(&schema, &instance)
+            })
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        errors
+            .into_iter()
+            .map(|error| {
+                let dict = PyDict::new(py);
+                dict.set_item("path", error.path)?;
+                dict.set_item("schema_path", error.schema_path)?;
+                dict.set_item("message", error.message)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    /// Select values from the JSON body with an RFC 9535 JSONPath expression, e.g.
+    /// `$.items[*].id`.
+    ///
+    /// The body is parsed and queried without holding the GIL; only the matches are
+    /// converted to Python objects. Returns the matches in document order (an empty
+    /// list when nothing matches). Raises `ValueError` if the path is invalid or the
+    /// body is not JSON.
+    fn jsonpath<'py>(&self, py: Python<'py>, path: &str) -> PyResult<Vec<Bound<'py, PyAny>>> {
+        let content = self.content.as_bytes(py);
+        let matches = py
+            .detach(|| {
+                let path = jsonpath::compile(path)?;
+                let value: serde_json::Value =
+                    from_slice(content).map_err(|e| anyhow!("Response body is not JSON: {}", e))?;
+                Ok::<_, anyhow::Error>(jsonpath::query(&path, &value))
+            })
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        matches
+            .iter()
+            .map(|value| pythonize(py, value).map_err(|e| PyValueError::new_err(e.to_string())))
+            .collect()
+    }
+
     fn cbor(&mut self, py: Python) -> Result<Py<PyAny>> {
         let cbor_value: serde_json::Value = serde_cbor_2::from_reader(self.content.as_bytes(py))
             .map_err(|e| anyhow!("Failed to deserialize CBOR: {}", e))?;
@@ -218,29 +613,268 @@ impl Response {
         Ok(result)
     }
 
+    /// Parse an Arrow IPC (stream or file format) or Parquet body into a `pyarrow.Table`.
+    ///
+    /// The format comes from the `Content-Type`, or from the body's magic bytes. The table
+    /// exports the Arrow C stream interface, so polars and other Arrow libraries can take
+    /// it without copying. Requires pyarrow.
+    ///
+    /// # Example
+    /// ```python
+    /// table = client.get("https://example.com/data.arrows").arrow()
+    /// df = polars.from_arrow(table)
+    /// ```
+    fn arrow<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let content_type = self.headers.get_value("content-type").unwrap_or_default();
+        let body = self.content.bind(py);
+        ArrowFormat::detect(&content_type, body.as_bytes()).read_table(py, body)
+    }
+
+    /// Parse the body as a Protocol Buffers message of `message_class`, through its
+    /// `FromString()` classmethod, or `ParseFromString()` on a new instance.
+    ///
+    /// # Example
+    /// ```python
+    /// user = client.get(url, headers={"Accept": "application/x-protobuf"}).proto_into(User)
+    /// ```
+    fn proto_into<'py>(
+        &self,
+        py: Python<'py>,
+        message_class: &Bound<'py, PyAny>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let content = self.content.bind(py);
+        if message_class.hasattr("FromString")? {
+            return message_class.call_method1("FromString", (content,));
+        }
+        let message = message_class.call0()?;
+        message.call_method1("ParseFromString", (content,))?;
+        Ok(message)
+    }
+
+    /// Parse an RFC 9457 problem details body (`application/problem+json` or
+    /// `application/problem+xml`). Returns None for other content types.
+    fn problem(&self, py: Python) -> Result<Option<ProblemDetails>> {
+        let content_type = self.headers.get("content-type".to_string(), None);
+        ProblemDetails::parse(self.content.as_bytes(py), &content_type).transpose()
+    }
+
+    /// Check the response against expectations, raising `AssertionError` listing every
+    /// failed check, otherwise return the response.
+    ///
+    /// `status` is a status code or a container of them (e.g. `range(200, 300)`),
+    /// `content_type` a media type compared without parameters, `json_schema` a JSON Schema
+    /// the body must satisfy and `max_latency` the most seconds `elapsed` may take.
+    #[pyo3(signature = (status=None, content_type=None, json_schema=None, max_latency=None))]
+    fn expect<'py>(
+        slf: Bound<'py, Self>,
+        status: Option<Bound<'py, PyAny>>,
+        content_type: Option<&str>,
+        json_schema: Option<Bound<'py, PyAny>>,
+        max_latency: Option<f64>,
+    ) -> PyResult<Bound<'py, Self>> {
+        let py = slf.py();
+        let response = slf.borrow();
+        let mut failures = Vec::new();
+        if let Some(status) = status {
+            let matches = match status.extract::<u16>() {
+                Ok(code) => code == response.status_code,
+                Err(_) => status.contains(response.status_code)?,
+            };
+            if !matches {
+                failures.push(format!(
+                    "status: expected {}, got {}",
+                    status.repr()?,
+                    response.status_code
+                ));
+            }
+        }
+        if let Some(expected) = content_type {
+            let actual = response.headers.get("content-type".to_string(), None);
+            if media_type(&actual) != media_type(expected) {
+                let actual = if actual.is_empty() { "none" } else { &actual };
+                failures.push(format!(
+                    "content type: expected {}, got {}",
+                    expected, actual
+                ));
+            }
+        }
+        if let Some(schema) = json_schema {
+            let schema: serde_json::Value = depythonize(&schema)?;
+            match from_slice::<serde_json::Value>(response.content.as_bytes(py)) {
+                Ok(instance) => {
+                    let errors = schema::validate(&schema, &instance)
+                        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+                    for error in errors.iter().take(MAX_EXPECT_SCHEMA_ERRORS) {
+                        let path = if error.path.is_empty() {
+                            "/"
+                        } else {
+                            &error.path
+                        };
+                        failures.push(format!("json_schema: {}: {}", path, error.message));
+                    }
+                    if errors.len() > MAX_EXPECT_SCHEMA_ERRORS {
+                        failures.push(format!(
+                            "json_schema: ... and {} more",
+                            errors.len() - MAX_EXPECT_SCHEMA_ERRORS
+                        ));
+                    }
+                }
+                Err(e) => failures.push(format!("json_schema: body is not JSON ({})", e)),
+            }
+        }
+        if let Some(max_latency) = max_latency {
+            let elapsed = response.elapsed.as_secs_f64();
+            if elapsed > max_latency {
+                failures.push(format!("latency: {:.3}s exceeds {}s", elapsed, max_latency));
+            }
+        }
+        if failures.is_empty() {
+            drop(response);
+            return Ok(slf);
+        }
+        Err(PyAssertionError::new_err(format!(
+            "Response from {} did not meet expectations:\n  - {}",
+            response.url,
+            failures.join("\n  - ")
+        )))
+    }
+
+    /// Raise `HTTPStatusError` if the status is 4xx or 5xx, otherwise return the response.
+    ///
+    /// The exception message includes a preview of the error body (at most 1 KiB, decoded
+    /// with the response charset) and, for JSON bodies, well-known error fields such as
+    /// `error`, `message` and `detail`. The exception carries `response`, `status_code`,
+    /// `body_preview`, `error_body` (the whole parsed JSON body, or None), `error_fields`
+    /// (a dict, or None) and `problem` (the RFC 9457 `ProblemDetails`, or None) attributes.
+    fn raise_for_status<'py>(slf: Bound<'py, Self>) -> PyResult<Bound<'py, Self>> {
+        let py = slf.py();
+        let (status_code, message, preview, body, fields, problem) = {
+            let mut response = slf.borrow_mut();
+            let Some(mut message) = status_error_message(response.status_code, &response.url)?
+            else {
+                return Ok(slf.clone());
+            };
+            let encoding = response.get_encoding(py)?.clone();
+            let content = response.content.as_bytes(py);
+            let preview = body_preview(content, &encoding);
+            let content_type = response.headers.get("content-type".to_string(), None);
+            let body = json_error_body(content, &content_type);
+            let fields = body.as_ref().and_then(json_error_fields);
+            let problem = ProblemDetails::parse(content, &content_type).and_then(Result::ok);
+
+            let summary = problem.as_ref().and_then(ProblemDetails::summary);
+            match (&fields, summary) {
+                (Some(fields), _) => {
+                    let fields: Vec<String> = fields
+                        .iter()
+                        .map(|(key, value)| match value {
+                            serde_json::Value::String(text) => format!("{}: {}", key, text),
+                            value => format!("{}: {}", key, value),
+                        })
+                        .collect();
+                    message.push_str(&format!("\n{}", fields.join(", ")));
+                }
+                (None, Some(summary)) => message.push_str(&format!("\n{}", summary)),
+                (None, None) if !preview.is_empty() => {
+                    message.push_str(&format!("\nResponse body: {}", preview));
+                }
+                (None, None) => {}
+            }
+            (
+                response.status_code,
+                message,
+                preview,
+                body,
+                fields,
+                problem,
+            )
+        };
+
+        let err = HTTPStatusError::new_err(message);
+        let value = err.value(py);
+        value.setattr("response", &slf)?;
+        value.setattr("status_code", status_code)?;
+        value.setattr("body_preview", preview)?;
+        let body = body.map(|body| pythonize(py, &body)).transpose()?;
+        value.setattr("error_body", body)?;
+        let fields = fields.map(|fields| pythonize(py, &fields)).transpose()?;
+        value.setattr("error_fields", fields)?;
+        value.setattr("problem", problem)?;
+        Err(err)
+    }
+
+    /// The URL relative `href` and `src` attributes are resolved against by
+    /// `text_markdown`, `text_plain` and `text_rich`. Defaults to the final response
+    /// URL; set another URL, or `None` to keep links as written.
+    #[getter]
+    fn get_link_base(&self) -> Option<String> {
+        self.link_base.as_ref().map(|url| url.to_string())
+    }
+
+    #[setter]
+    fn set_link_base(&mut self, link_base: Option<&str>) -> PyResult<()> {
+        self.link_base = link_base
+            .map(Url::parse)
+            .transpose()
+            .map_err(|e| PyValueError::new_err(format!("Invalid link_base: {e}")))?;
+        Ok(())
+    }
+
     #[getter]
     fn text_markdown(&mut self, py: Python) -> Result<String> {
-        let raw_bytes = self.content.bind(py).as_bytes();
-        let text = py.detach(|| from_read(raw_bytes, 100))?;
-        Ok(text)
+        self.html_to_text(py, PlainDecorator::new)
     }
 
     #[getter]
     fn text_plain(&mut self, py: Python) -> Result<String> {
-        let raw_bytes = self.content.bind(py).as_bytes();
-        let text =
-            py.detach(|| from_read_with_decorator(raw_bytes, 100, TrivialDecorator::new()))?;
-        Ok(text)
+        self.html_to_text(py, TrivialDecorator::new)
     }
 
     #[getter]
     fn text_rich(&mut self, py: Python) -> Result<String> {
-        let raw_bytes = self.content.bind(py).as_bytes();
-        let text = py.detach(|| from_read_with_decorator(raw_bytes, 100, RichDecorator::new()))?;
-        Ok(text)
+        self.html_to_text(py, RichDecorator::new)
     }
 }
 
+/// Read the next chunk of a streamed body without holding the GIL, marking the stream
+/// consumed at its end.
+fn read_chunk(
+    py: Python,
+    response: &Arc<Mutex<Option<reqwest::Response>>>,
+    consumed: &Arc<Mutex<bool>>,
+) -> PyResult<Option<Bytes>> {
+    let response_arc = Arc::clone(response);
+    let consumed_arc = Arc::clone(consumed);
+
+    let result = block_on(py, || async {
+        let mut response_guard = response_arc
+            .lock()
+            .map_err(|e| anyhow::anyhow!("Failed to acquire response lock: {}", e))?;
+
+        if let Some(ref mut resp) = *response_guard {
+            match resp.chunk().await {
+                Ok(Some(chunk)) => Ok(Some(chunk)),
+                Ok(None) => {
+                    // Stream exhausted, mark as consumed
+                    if let Ok(mut consumed) = consumed_arc.lock() {
+                        *consumed = true;
+                    }
+                    Ok(None)
+                }
+                Err(e) => Err(anyhow::Error::new(e.with_url(resp.url().clone()))),
+            }
+        } else {
+            // Response already taken, mark as consumed
+            if let Ok(mut consumed) = consumed_arc.lock() {
+                *consumed = true;
+            }
+            Ok(None)
+        }
+    })?;
+
+    result.map_err(chunk_error)
+}
+
 /// A streaming HTTP response that allows iterating over chunks of data.
 ///
 /// This struct holds the reqwest Response and provides methods to iterate over
@@ -256,32 +890,66 @@ pub struct StreamingResponse {
     pub status_code: u16,
     #[pyo3(get)]
     pub url: String,
+    /// Metrics of the `Server-Timing` headers.
+    #[pyo3(get)]
+    pub server_timing: Vec<ServerTiming>,
+    history: Vec<ResponseParts>,
+    attempts: Vec<Attempt>,
     closed: Arc<Mutex<bool>>,
     consumed: Arc<Mutex<bool>>,
     encoding: Arc<Mutex<Option<String>>>,
+    counts: Arc<ByteCounts>,
 }
 
 impl StreamingResponse {
     /// Create a new StreamingResponse from a reqwest::Response
     pub fn new(
-        response: reqwest::Response,
+        mut response: reqwest::Response,
         cookies: IndexMap<String, String, RandomState>,
         headers: CaseInsensitiveHeaderMap,
         status_code: u16,
         url: String,
     ) -> Self {
+        let counts = response
+            .extensions()
+            .get::<Transfer>()
+            .map(|transfer| Arc::clone(&transfer.0))
+            .unwrap_or_default();
+        let server_timing = ServerTiming::from_headers(response.headers());
+        let history = response
+            .extensions_mut()
+            .remove::<RedirectHistory>()
+            .unwrap_or_default();
+        let attempts = response
+            .extensions()
+            .get::<AttemptLog>()
+            .map(AttemptLog::entries)
+            .unwrap_or_default();
         StreamingResponse {
             response: Arc::new(Mutex::new(Some(response))),
             cookies,
             headers,
             status_code,
             url,
+            server_timing,
+            history: history.0,
+            attempts,
             closed: Arc::new(Mutex::new(false)),
             consumed: Arc::new(Mutex::new(false)),
             encoding: Arc::new(Mutex::new(None)),
+            counts,
         }
     }
 
+    /// Create a StreamingResponse from a reqwest::Response, taking the response items from it
+    pub fn from_response(response: reqwest::Response) -> Self {
+        let cookies = response_cookies(&response);
+        let headers = CaseInsensitiveHeaderMap::from_indexmap(response.headers().to_indexmap());
+        let status_code = response.status().as_u16();
+        let url = response.url().to_string();
+        StreamingResponse::new(response, cookies, headers, status_code, url)
+    }
+
     fn check_state(&self) -> PyResult<()> {
         let closed = self.closed.lock().map_err(|e| {
             pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to acquire lock: {}", e))
@@ -301,6 +969,23 @@ impl StreamingResponse {
         Ok(())
     }
 
+    /// Read the next chunk of the body, marking the stream consumed at its end.
+    fn next_chunk(&self, py: Python) -> PyResult<Option<Bytes>> {
+        read_chunk(py, &self.response, &self.consumed)
+    }
+
+    fn json_iterator(&self, source: JsonSource) -> JsonIterator {
+        JsonIterator {
+            response: Arc::clone(&self.response),
+            closed: Arc::clone(&self.closed),
+            consumed: Arc::clone(&self.consumed),
+            source,
+            pending: VecDeque::new(),
+            error: None,
+            finished: false,
+        }
+    }
+
     fn get_encoding_internal(&self) -> String {
         // Check if encoding is already cached
         if let Ok(encoding_guard) = self.encoding.lock() {
@@ -330,44 +1015,8 @@ impl StreamingResponse {
 
     fn __next__(&self, py: Python) -> PyResult<Option<Py<PyBytes>>> {
         self.check_state()?;
-
-        let response_arc = Arc::clone(&self.response);
-        let consumed_arc = Arc::clone(&self.consumed);
-
-        // Release GIL while fetching the next chunk
-        let result = py.detach(|| {
-            RUNTIME.block_on(async {
-                let mut response_guard = response_arc
-                    .lock()
-                    .map_err(|e| anyhow::anyhow!("Failed to acquire response lock: {}", e))?;
-
-                if let Some(ref mut resp) = *response_guard {
-                    match resp.chunk().await {
-                        Ok(Some(chunk)) => Ok(Some(chunk)),
-                        Ok(None) => {
-                            // Stream exhausted, mark as consumed
-                            if let Ok(mut consumed) = consumed_arc.lock() {
-                                *consumed = true;
-                            }
-                            Ok(None)
-                        }
-                        Err(e) => Err(anyhow::anyhow!("Error reading chunk: {}", e)),
-                    }
-                } else {
-                    // Response already taken, mark as consumed
-                    if let Ok(mut consumed) = consumed_arc.lock() {
-                        *consumed = true;
-                    }
-                    Ok(None)
-                }
-            })
-        });
-
-        match result {
-            Ok(Some(chunk)) => Ok(Some(PyBytes::new(py, &chunk).unbind())),
-            Ok(None) => Ok(None),
-            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(e.to_string())),
-        }
+        let chunk = self.next_chunk(py)?;
+        Ok(chunk.map(|chunk| PyBytes::new(py, &chunk).unbind()))
     }
 
     /// Iterate over the response body as bytes chunks.
@@ -427,6 +1076,88 @@ impl StreamingResponse {
         })
     }
 
+    /// Apply a JSONPath expression to each document of an NDJSON (newline-delimited
+    /// JSON) body, yielding every match as lines arrive.
+    ///
+    /// Lines are parsed and queried in Rust without holding the GIL, and only the
+    /// current line is buffered. Blank lines are skipped. Raises `ValueError` for an
+    /// invalid path, or while iterating when a line is not JSON.
+    ///
+    /// # Example
+    /// ```python
+    /// with client.stream("GET", url) as response:
+    ///     for user_id in response.jsonpath_stream("$.user.id"):
+    ///         print(user_id)
+    /// ```
+    fn jsonpath_stream(&self, path: &str) -> PyResult<JsonIterator> {
+        self.check_state()?;
+        let path = jsonpath::compile(path).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(self.json_iterator(JsonSource::Ndjson(NdjsonMatcher::new(path))))
+    }
+
+    /// Iterate over the elements of a JSON array in the body, parsing incrementally so
+    /// that memory is bounded by the largest element rather than the document.
+    ///
+    /// `path` is a dot-separated key path to the array (e.g. `data.items` for
+    /// `{"data": {"items": [...]}}`); by default the body must be a top-level array.
+    /// Reading stops once the array closes. Raises `ValueError` while iterating if the
+    /// array is missing, the value at `path` is not an array, or the JSON is invalid.
+    ///
+    /// # Example
+    /// ```python
+    /// with client.stream("GET", url) as response:
+    ///     for item in response.iter_json_array("items"):
+    ///         process(item)
+    /// ```
+    #[pyo3(signature = (path=None))]
+    fn iter_json_array(&self, path: Option<&str>) -> PyResult<JsonIterator> {
+        self.check_state()?;
+        Ok(self.json_iterator(JsonSource::Array(JsonArrayScanner::new(path))))
+    }
+
+    /// Iterate over the rows of a CSV (or TSV, with `delimiter="\t"`) body as they
+    /// arrive.
+    ///
+    /// Quoting and rows split across chunks are handled in Rust without holding the
+    /// GIL. With `header=True` the first row names the fields and each following row is
+    /// yielded as a dict; otherwise every row is yielded as a tuple of strings. Fields
+    /// are decoded with the response encoding. Raises `ValueError` for an invalid
+    /// delimiter, or while iterating when a row has a different number of fields than
+    /// the first.
+    ///
+    /// # Example
+    /// ```python
+    /// with client.stream("GET", url) as response:
+    ///     for row in response.iter_csv():
+    ///         print(row["id"])
+    /// ```
+    #[pyo3(signature = (delimiter=",", header=true))]
+    fn iter_csv(&self, delimiter: &str, header: bool) -> PyResult<CsvIterator> {
+        self.check_state()?;
+        let delimiter = match delimiter.as_bytes() {
+            [b] if b.is_ascii() => *b,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "delimiter must be a single ASCII character, got {:?}",
+                    delimiter
+                )))
+            }
+        };
+        let encoding = Encoding::for_label(self.get_encoding_internal().as_bytes())
+            .unwrap_or(encoding_rs::UTF_8);
+        Ok(CsvIterator {
+            response: Arc::clone(&self.response),
+            closed: Arc::clone(&self.closed),
+            consumed: Arc::clone(&self.consumed),
+            parser: CsvRows::new(delimiter, encoding),
+            header,
+            names: None,
+            pending: VecDeque::new(),
+            error: None,
+            finished: false,
+        })
+    }
+
     /// Read the entire response body into memory.
     ///
     /// This consumes the stream and returns all remaining bytes.
@@ -444,36 +1175,104 @@ impl StreamingResponse {
         let response_arc = Arc::clone(&self.response);
         let consumed_arc = Arc::clone(&self.consumed);
 
-        let result = py.detach(|| {
-            RUNTIME.block_on(async {
-                let mut response_guard = response_arc
-                    .lock()
-                    .map_err(|e| anyhow::anyhow!("Failed to acquire response lock: {}", e))?;
-
-                if let Some(resp) = response_guard.take() {
-                    let bytes = resp
-                        .bytes()
-                        .await
-                        .map_err(|e| anyhow::anyhow!("Error reading response body: {}", e))?;
-
-                    // Mark as consumed
-                    if let Ok(mut consumed) = consumed_arc.lock() {
-                        *consumed = true;
-                    }
-
-                    Ok(bytes)
-                } else {
-                    Err(anyhow::anyhow!("Response already consumed"))
+        let result = block_on(py, || async {
+            let mut response_guard = response_arc
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Failed to acquire response lock: {}", e))?;
+
+            if let Some(resp) = response_guard.take() {
+                let url = resp.url().clone();
+                let bytes = resp
+                    .bytes()
+                    .await
+                    .map_err(|e| anyhow::Error::new(e.with_url(url)))?;
+
+                // Mark as consumed
+                if let Ok(mut consumed) = consumed_arc.lock() {
+                    *consumed = true;
                 }
-            })
-        });
+
+                Ok(bytes)
+            } else {
+                Err(anyhow::anyhow!("Response already consumed"))
+            }
+        })?;
 
         match result {
             Ok(bytes) => Ok(PyBytes::new(py, &bytes).unbind()),
+            // Timeouts and connection errors while reading, as when iterating
+            Err(e) if e.downcast_ref::<reqwest::Error>().is_some() => Err(map_anyhow_error(e)),
             Err(e) => Err(StreamConsumed::new_err(e.to_string())),
         }
     }
 
+    /// Read the rest of the body directly into `buffer`, a writable object supporting the
+    /// buffer protocol such as a `bytearray`, NumPy array or `mmap`, without collecting it
+    /// in memory first. Returns the number of bytes written.
+    ///
+    /// Fails with `ValueError` if the body is larger than the buffer, checked up front
+    /// when the response has a `Content-Length`.
+    ///
+    /// # Example
+    /// ```python
+    /// with client.stream("GET", url) as response:
+    ///     array = numpy.empty(int(response.headers["content-length"]), dtype=numpy.uint8)
+    ///     response.read_into(array)
+    /// ```
+    fn read_into(&self, py: Python, buffer: &Bound<'_, PyAny>) -> PyResult<usize> {
+        self.check_state()?;
+        let view = byte_view(buffer)?;
+        let capacity = view.len()?;
+        let length = self
+            .headers
+            .get_value("content-length")
+            .and_then(|length| length.trim().parse::<usize>().ok());
+        if let Some(length) = length.filter(|length| *length > capacity) {
+            return Err(PyValueError::new_err(format!(
+                "Response body ({} bytes) does not fit in the buffer ({} bytes)",
+                length, capacity
+            )));
+        }
+        let mut written = 0;
+        while let Some(chunk) = self.next_chunk(py)? {
+            written = write_at(&view, written, &PyBytes::new(py, &chunk))?;
+        }
+        Ok(written)
+    }
+
+    /// Write the rest of the body to the file at `path`, replacing it, without holding the
+    /// body in memory. Returns the number of bytes written.
+    ///
+    /// # Example
+    /// ```python
+    /// with client.stream("GET", url) as response:
+    ///     if response.status_code == 200:
+    ///         response.stream_to("archive.tar.gz")
+    /// ```
+    fn stream_to(&self, py: Python, path: PathBuf) -> PyResult<u64> {
+        self.check_state()?;
+        let response = self
+            .response
+            .lock()
+            .map_err(|e| {
+                pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to acquire lock: {}", e))
+            })?
+            .take();
+        let Some(response) = response else {
+            return Err(StreamConsumed::new_err(
+                "Response stream has already been consumed",
+            ));
+        };
+        if let Ok(mut consumed) = self.consumed.lock() {
+            *consumed = true;
+        }
+        block_on(py, || async move {
+            let mut file = File::create(&path).await?;
+            write_body(response, &mut file).await
+        })?
+        .map_err(map_anyhow_error)
+    }
+
     /// Close the streaming response and release resources.
     ///
     /// After closing, no more data can be read from the stream.
@@ -510,6 +1309,101 @@ impl StreamingResponse {
         })?;
         Ok(*consumed)
     }
+
+    /// Request body bytes sent.
+    #[getter]
+    fn request_body_size(&self) -> u64 {
+        self.counts.sent()
+    }
+
+    /// Response body bytes received so far, before content decoding.
+    #[getter]
+    fn response_body_size(&self) -> u64 {
+        self.counts.received()
+    }
+
+    /// Response body bytes read so far, after content decoding.
+    #[getter]
+    fn decompressed_body_size(&self) -> u64 {
+        self.counts.decompressed()
+    }
+
+    /// Character encoding of `iter_text()`, `iter_lines()` and `iter_csv()`, from the
+    /// `Content-Type` header or UTF-8. Set it before iterating to override it; `None`
+    /// restores detection.
+    #[getter]
+    fn get_encoding(&self) -> String {
+        self.get_encoding_internal()
+    }
+
+    #[setter]
+    fn set_encoding(&self, encoding: Option<String>) -> PyResult<()> {
+        let mut guard = self.encoding.lock().map_err(|e| {
+            pyo3::exceptions::PyRuntimeError::new_err(format!("Failed to acquire lock: {}", e))
+        })?;
+        *guard = encoding;
+        Ok(())
+    }
+
+    /// The redirect responses followed to get this one, oldest first, with their bodies
+    /// read.
+    #[getter]
+    fn history(&self, py: Python) -> Vec<Response> {
+        history_responses(py, &self.history)
+    }
+
+    /// Every time the request went out, oldest first, as for `Response.attempts`.
+    #[getter]
+    fn attempts<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.attempts
+            .iter()
+            .map(|attempt| attempt.to_dict(py))
+            .collect()
+    }
+
+    /// How many of `attempts` were retries after a failed attempt.
+    #[getter]
+    fn retries(&self) -> usize {
+        count_retries(&self.attempts)
+    }
+
+    /// The media type of the `Content-Type` header, lowercased and without parameters
+    /// such as `charset`, or `None` without one.
+    #[getter]
+    fn content_type(&self) -> Option<String> {
+        header_media_type(&self.headers)
+    }
+
+    /// The entries of the `Link` header keyed by `rel`, as for `Response.links`.
+    #[getter]
+    fn links(&self) -> IndexMap<String, IndexMap<String, String, RandomState>, RandomState> {
+        header_links(&self.headers, &self.url)
+    }
+
+    /// Raise `HTTPStatusError` if the status is 4xx or 5xx, otherwise return the response.
+    ///
+    /// The body is left unread, so the exception has an empty `body_preview` and no
+    /// `error_body`, `error_fields` or `problem`.
+    fn raise_for_status<'py>(slf: Bound<'py, Self>) -> PyResult<Bound<'py, Self>> {
+        let py = slf.py();
+        let (status_code, message) = {
+            let response = slf.borrow();
+            let message = status_error_message(response.status_code, &response.url)?;
+            (response.status_code, message)
+        };
+        let Some(message) = message else {
+            return Ok(slf);
+        };
+        let err = HTTPStatusError::new_err(message);
+        let value = err.value(py);
+        value.setattr("response", &slf)?;
+        value.setattr("status_code", status_code)?;
+        value.setattr("body_preview", "")?;
+        value.setattr("error_body", py.None())?;
+        value.setattr("error_fields", py.None())?;
+        value.setattr("problem", py.None())?;
+        Err(err)
+    }
 }
 
 /// Iterator for text chunks
@@ -542,41 +1436,39 @@ impl TextIterator {
         let consumed_arc = Arc::clone(&self.consumed);
         let encoding_name = self.encoding.clone();
 
-        let result = py.detach(|| {
-            RUNTIME.block_on(async {
-                let mut response_guard = response_arc
-                    .lock()
-                    .map_err(|e| anyhow::anyhow!("Failed to acquire response lock: {}", e))?;
-
-                if let Some(ref mut resp) = *response_guard {
-                    match resp.chunk().await {
-                        Ok(Some(chunk)) => {
-                            // Decode the chunk using the encoding
-                            let encoding = Encoding::for_label(encoding_name.as_bytes())
-                                .unwrap_or(encoding_rs::UTF_8);
-                            let (decoded, _, _) = encoding.decode(&chunk);
-                            Ok(Some(decoded.to_string()))
-                        }
-                        Ok(None) => {
-                            if let Ok(mut consumed) = consumed_arc.lock() {
-                                *consumed = true;
-                            }
-                            Ok(None)
-                        }
-                        Err(e) => Err(anyhow::anyhow!("Error reading chunk: {}", e)),
+        let result = block_on(py, || async {
+            let mut response_guard = response_arc
+                .lock()
+                .map_err(|e| anyhow::anyhow!("Failed to acquire response lock: {}", e))?;
+
+            if let Some(ref mut resp) = *response_guard {
+                match resp.chunk().await {
+                    Ok(Some(chunk)) => {
+                        // Decode the chunk using the encoding
+                        let encoding = Encoding::for_label(encoding_name.as_bytes())
+                            .unwrap_or(encoding_rs::UTF_8);
+                        let (decoded, _, _) = encoding.decode(&chunk);
+                        Ok(Some(decoded.to_string()))
                     }
-                } else {
-                    if let Ok(mut consumed) = consumed_arc.lock() {
-                        *consumed = true;
+                    Ok(None) => {
+                        if let Ok(mut consumed) = consumed_arc.lock() {
+                            *consumed = true;
+                        }
+                        Ok(None)
                     }
-                    Ok(None)
+                    Err(e) => Err(anyhow::Error::new(e.with_url(resp.url().clone()))),
                 }
-            })
-        });
+            } else {
+                if let Ok(mut consumed) = consumed_arc.lock() {
+                    *consumed = true;
+                }
+                Ok(None)
+            }
+        })?;
 
         match result {
             Ok(opt) => Ok(opt),
-            Err(e) => Err(pyo3::exceptions::PyRuntimeError::new_err(e.to_string())),
+            Err(e) => Err(chunk_error(e)),
         }
     }
 }
@@ -620,36 +1512,34 @@ impl LineIterator {
         let encoding_name = self.encoding.clone();
 
         loop {
-            let result = py.detach(|| {
-                RUNTIME.block_on(async {
-                    let mut response_guard = response_arc
-                        .lock()
-                        .map_err(|e| anyhow::anyhow!("Failed to acquire response lock: {}", e))?;
-
-                    if let Some(ref mut resp) = *response_guard {
-                        match resp.chunk().await {
-                            Ok(Some(chunk)) => {
-                                let encoding = Encoding::for_label(encoding_name.as_bytes())
-                                    .unwrap_or(encoding_rs::UTF_8);
-                                let (decoded, _, _) = encoding.decode(&chunk);
-                                Ok(Some(decoded.to_string()))
-                            }
-                            Ok(None) => {
-                                if let Ok(mut consumed) = consumed_arc.lock() {
-                                    *consumed = true;
-                                }
-                                Ok(None)
-                            }
-                            Err(e) => Err(anyhow::anyhow!("Error reading chunk: {}", e)),
+            let result = block_on(py, || async {
+                let mut response_guard = response_arc
+                    .lock()
+                    .map_err(|e| anyhow::anyhow!("Failed to acquire response lock: {}", e))?;
+
+                if let Some(ref mut resp) = *response_guard {
+                    match resp.chunk().await {
+                        Ok(Some(chunk)) => {
+                            let encoding = Encoding::for_label(encoding_name.as_bytes())
+                                .unwrap_or(encoding_rs::UTF_8);
+                            let (decoded, _, _) = encoding.decode(&chunk);
+                            Ok(Some(decoded.to_string()))
                         }
-                    } else {
-                        if let Ok(mut consumed) = consumed_arc.lock() {
-                            *consumed = true;
+                        Ok(None) => {
+                            if let Ok(mut consumed) = consumed_arc.lock() {
+                                *consumed = true;
+                            }
+                            Ok(None)
                         }
-                        Ok(None)
+                        Err(e) => Err(anyhow::Error::new(e.with_url(resp.url().clone()))),
                     }
-                })
-            });
+                } else {
+                    if let Ok(mut consumed) = consumed_arc.lock() {
+                        *consumed = true;
+                    }
+                    Ok(None)
+                }
+            })?;
 
             match result {
                 Ok(Some(text)) => {
@@ -671,8 +1561,200 @@ impl LineIterator {
                     }
                     return Ok(None);
                 }
-                Err(e) => return Err(pyo3::exceptions::PyRuntimeError::new_err(e.to_string())),
+                Err(e) => return Err(chunk_error(e)),
             }
         }
     }
 }
+
+/// Incremental parser behind a `JsonIterator`
+enum JsonSource {
+    /// JSONPath matches from each line of an NDJSON body
+    Ndjson(NdjsonMatcher),
+    /// Elements of one array in a JSON document
+    Array(JsonArrayScanner),
+}
+
+impl JsonSource {
+    fn feed(&mut self, chunk: &[u8], values: &mut Vec<serde_json::Value>) -> Result<()> {
+        match self {
+            JsonSource::Ndjson(matcher) => matcher.feed(chunk, values),
+            JsonSource::Array(scanner) => scanner.feed(chunk, values),
+        }
+    }
+
+    fn finish(&mut self, values: &mut Vec<serde_json::Value>) -> Result<()> {
+        match self {
+            JsonSource::Ndjson(matcher) => matcher.finish(values),
+            JsonSource::Array(scanner) => scanner.finish(),
+        }
+    }
+
+    /// Whether the values are complete before the end of the body
+    fn is_done(&self) -> bool {
+        matches!(self, JsonSource::Array(scanner) if scanner.is_done())
+    }
+}
+
+/// Iterator over JSON values parsed incrementally from a streaming body
+#[pyclass]
+pub struct JsonIterator {
+    response: Arc<Mutex<Option<reqwest::Response>>>,
+    closed: Arc<Mutex<bool>>,
+    consumed: Arc<Mutex<bool>>,
+    source: JsonSource,
+    pending: VecDeque<serde_json::Value>,
+    error: Option<String>,
+    finished: bool,
+}
+
+#[pymethods]
+impl JsonIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<Py<PyAny>>> {
+        loop {
+            if let Some(value) = self.pending.pop_front() {
+                let value =
+                    pythonize(py, &value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+                return Ok(Some(value.unbind()));
+            }
+            if let Some(message) = self.error.take() {
+                return Err(PyValueError::new_err(message));
+            }
+            if self.finished {
+                return Ok(None);
+            }
+            {
+                let closed = self.closed.lock().map_err(|e| {
+                    pyo3::exceptions::PyRuntimeError::new_err(format!(
+                        "Failed to acquire lock: {}",
+                        e
+                    ))
+                })?;
+                if *closed {
+                    return Err(StreamClosed::new_err("Response stream has been closed"));
+                }
+            }
+
+            let chunk = read_chunk(py, &self.response, &self.consumed)?;
+            let source = &mut self.source;
+            let mut values = Vec::new();
+            let result = py.detach(|| match &chunk {
+                Some(chunk) => source.feed(chunk, &mut values),
+                None => source.finish(&mut values),
+            });
+            self.finished = chunk.is_none() || self.source.is_done();
+            self.pending.extend(values);
+            if let Err(e) = result {
+                // Yield the values parsed before the error first
+                self.error = Some(e.to_string());
+                self.finished = true;
+            }
+        }
+    }
+}
+
+/// Iterator over the rows of a CSV body
+#[pyclass]
+pub struct CsvIterator {
+    response: Arc<Mutex<Option<reqwest::Response>>>,
+    closed: Arc<Mutex<bool>>,
+    consumed: Arc<Mutex<bool>>,
+    parser: CsvRows,
+    header: bool,
+    names: Option<Vec<String>>,
+    pending: VecDeque<Vec<String>>,
+    error: Option<String>,
+    finished: bool,
+}
+
+#[pymethods]
+impl CsvIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<Py<PyAny>>> {
+        loop {
+            if let Some(row) = self.pending.pop_front() {
+                if !self.header {
+                    return Ok(Some(PyTuple::new(py, row)?.into_any().unbind()));
+                }
+                let Some(names) = &self.names else {
+                    self.names = Some(row);
+                    continue;
+                };
+                let dict = PyDict::new(py);
+                for (name, field) in names.iter().zip(row) {
+                    dict.set_item(name, field)?;
+                }
+                return Ok(Some(dict.into_any().unbind()));
+            }
+            if let Some(message) = self.error.take() {
+                return Err(PyValueError::new_err(message));
+            }
+            if self.finished {
+                return Ok(None);
+            }
+            {
+                let closed = self.closed.lock().map_err(|e| {
+                    pyo3::exceptions::PyRuntimeError::new_err(format!(
+                        "Failed to acquire lock: {}",
+                        e
+                    ))
+                })?;
+                if *closed {
+                    return Err(StreamClosed::new_err("Response stream has been closed"));
+                }
+            }
+
+            let chunk = read_chunk(py, &self.response, &self.consumed)?;
+            let parser = &mut self.parser;
+            let mut rows = Vec::new();
+            let result = py.detach(|| match &chunk {
+                Some(chunk) => parser.feed(chunk, &mut rows),
+                None => parser.finish(&mut rows),
+            });
+            self.finished = chunk.is_none();
+            self.pending.extend(rows);
+            if let Err(e) = result {
+                // Yield the rows before the invalid one first
+                self.error = Some(e.to_string());
+                self.finished = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_body_preview() {
+        assert_eq!(body_preview(b"not found", "utf-8"), "not found");
+        assert_eq!(body_preview(b"caf\xe9", "windows-1252"), "caf\u{e9}");
+
+        let long = "\u{e9}".repeat(BODY_PREVIEW_LIMIT);
+        let preview = body_preview(long.as_bytes(), "utf-8");
+        assert_eq!(preview.chars().count(), BODY_PREVIEW_LIMIT / 2 + 3);
+        assert!(preview.ends_with("\u{e9}..."));
+    }
+
+    #[test]
+    fn test_json_error_fields() {
+        let json_error_fields = |content: &[u8], content_type: &str| {
+            json_error_body(content, content_type).and_then(|body| json_error_fields(&body))
+        };
+        let body = br#"{"error": "invalid_grant", "message": "expired", "trace": "x"}"#;
+        let fields = json_error_fields(body, "application/json; charset=utf-8").unwrap();
+        assert_eq!(fields.keys().collect::<Vec<_>>(), vec!["error", "message"]);
+        assert!(json_error_fields(body, "text/plain").is_none());
+        assert!(json_error_fields(br#"{"trace": "x"}"#, "application/json").is_none());
+        assert!(json_error_fields(b"[1]", "application/problem+json").is_none());
+        assert!(json_error_fields(br#"{"detail": "x"}"#, "application/problem+json").is_some());
+    }
+}
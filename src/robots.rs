@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use pyo3::prelude::*;
+use reqwest::header::{HeaderMap, USER_AGENT};
+use url::Url;
+
+use crate::exceptions::RobotsDisallowed;
+use crate::transport::Transport;
+
+/// Bytes of a robots.txt file that are parsed; RFC 9309 requires at least 500 KiB.
+const ROBOTS_SIZE_LIMIT: usize = 500 * 1024;
+
+#[derive(Clone, Debug)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+/// Rules of consecutive `User-agent` lines.
+#[derive(Clone, Debug, Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+    crawl_delay: Option<f64>,
+}
+
+/// Parsed robots.txt rules (RFC 9309), from `client.robots()` or `RobotsTxt(text)`.
+#[pyclass(frozen, module = "httpr")]
+#[derive(Clone, Debug, Default)]
+pub struct RobotsTxt {
+    groups: Vec<Group>,
+    sitemaps: Vec<String>,
+}
+
+impl RobotsTxt {
+    pub fn parse(text: &str) -> Self {
+        let mut robots = RobotsTxt::default();
+        // A user-agent line after a rule starts a new group
+        let mut in_agents = false;
+        for line in text.trim_start_matches('\u{feff}').lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    if !in_agents {
+                        robots.groups.push(Group::default());
+                        in_agents = true;
+                    }
+                    if let Some(group) = robots.groups.last_mut() {
+                        group.agents.push(product_token(value));
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    in_agents = false;
+                    // An empty disallow allows everything, which is the default anyway
+                    if let (Some(group), false) = (robots.groups.last_mut(), value.is_empty()) {
+                        group.rules.push(Rule {
+                            allow: key == "allow",
+                            pattern: value.to_string(),
+                        });
+                    }
+                }
+                "crawl-delay" => {
+                    in_agents = false;
+                    if let Some(group) = robots.groups.last_mut() {
+                        group.crawl_delay = value
+                            .parse::<f64>()
+                            .ok()
+                            .filter(|delay| delay.is_finite() && *delay >= 0.0);
+                    }
+                }
+                // Sitemaps are not tied to a group
+                "sitemap" if !value.is_empty() => robots.sitemaps.push(value.to_string()),
+                _ => {}
+            }
+        }
+        robots
+    }
+
+    /// Rules for a server that could not be reached (5xx): everything is disallowed.
+    pub fn disallow_all() -> Self {
+        Self::parse("User-agent: *\nDisallow: /")
+    }
+
+    /// Groups for `user_agent`, falling back to the `*` groups.
+    fn groups_for(&self, user_agent: &str) -> Vec<&Group> {
+        let token = product_token(user_agent);
+        let matching = |agent: &str| {
+            self.groups
+                .iter()
+                .filter(|group| group.agents.iter().any(|a| a == agent))
+                .collect::<Vec<_>>()
+        };
+        let groups = matching(&token);
+        if groups.is_empty() {
+            matching("*")
+        } else {
+            groups
+        }
+    }
+
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        let path = request_path(path);
+        if path == "/robots.txt" {
+            return true;
+        }
+        // The longest matching pattern wins; on a tie allow wins
+        self.groups_for(user_agent)
+            .into_iter()
+            .flat_map(|group| &group.rules)
+            .filter(|rule| pattern_matches(&rule.pattern, &path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+
+    pub fn delay_for(&self, user_agent: &str) -> Option<f64> {
+        self.groups_for(user_agent)
+            .into_iter()
+            .find_map(|group| group.crawl_delay)
+    }
+}
+
+/// Lowercase product token of a user agent, e.g. `"mybot"` for `"MyBot/1.0 (+https://...)"`.
+fn product_token(user_agent: &str) -> String {
+    user_agent
+        .split(|c: char| c == '/' || c.is_whitespace())
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Path and query of `path`, which may also be a full URL.
+fn request_path(path: &str) -> String {
+    match Url::parse(path) {
+        Ok(url) => match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        },
+        Err(_) if path.is_empty() => "/".to_string(),
+        Err(_) => path.to_string(),
+    }
+}
+
+/// Match `path` against a robots.txt pattern, where `*` matches any run of characters
+/// and a trailing `$` anchors the end.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = path.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return !anchored || rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    if anchored {
+        rest.ends_with(last)
+    } else {
+        rest.contains(last)
+    }
+}
+
+/// URL of the robots.txt file governing `url`.
+pub fn robots_url(url: &str) -> Result<String> {
+    let mut url = Url::parse(url)?;
+    url.set_path("/robots.txt");
+    url.set_query(None);
+    url.set_fragment(None);
+    Ok(url.into())
+}
+
+/// Parse the response to a robots.txt request. Per RFC 9309 a 5xx status disallows
+/// everything and any other unsuccessful status allows everything.
+pub async fn from_response(resp: reqwest::Response) -> Result<RobotsTxt> {
+    let status = resp.status();
+    if status.is_server_error() {
+        return Ok(RobotsTxt::disallow_all());
+    }
+    if !status.is_success() {
+        return Ok(RobotsTxt::default());
+    }
+    let body = resp.bytes().await?;
+    let body = &body[..body.len().min(ROBOTS_SIZE_LIMIT)];
+    Ok(RobotsTxt::parse(&String::from_utf8_lossy(body)))
+}
+
+struct Cached {
+    fetched: Instant,
+    robots: Arc<RobotsTxt>,
+}
+
+/// Per-origin cache of robots.txt rules for `respect_robots_txt`. Clones share the cache.
+#[derive(Clone)]
+pub struct RobotsCache {
+    ttl: Duration,
+    entries: Arc<Mutex<HashMap<String, Cached>>>,
+}
+
+impl RobotsCache {
+    pub fn new(ttl: f64) -> Result<Self> {
+        if !ttl.is_finite() || ttl < 0.0 {
+            bail!("robots_cache_ttl must be a non-negative number of seconds");
+        }
+        Ok(RobotsCache {
+            ttl: Duration::from_secs_f64(ttl),
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    fn cached(&self, origin: &str) -> Option<Arc<RobotsTxt>> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries
+            .get(origin)
+            .filter(|cached| cached.fetched.elapsed() < self.ttl)
+            .map(|cached| Arc::clone(&cached.robots))
+    }
+
+    /// Rules for the origin of `url`, fetched with the client defaults (through `transport`
+    /// if there is one) if not cached.
+    async fn rules(
+        &self,
+        client: &reqwest::Client,
+        client_headers: &HeaderMap,
+        request: &reqwest::Request,
+        transport: Option<&Transport>,
+    ) -> Result<Arc<RobotsTxt>> {
+        let origin = request.url().origin().ascii_serialization();
+        if let Some(robots) = self.cached(&origin) {
+            return Ok(robots);
+        }
+        let mut headers = client_headers.clone();
+        if let Some(user_agent) = request.headers().get(USER_AGENT) {
+            headers.insert(USER_AGENT, user_agent.clone());
+        }
+        let mut builder = client
+            .get(robots_url(request.url().as_str())?)
+            .headers(headers);
+        if let Some(timeout) = request.timeout() {
+            builder = builder.timeout(*timeout);
+        }
+        let resp = match transport {
+            Some(transport) => transport.send(builder.build()?).await?,
+            None => builder.send().await?,
+        };
+        let robots = Arc::new(from_response(resp).await?);
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(
+                origin,
+                Cached {
+                    fetched: Instant::now(),
+                    robots: Arc::clone(&robots),
+                },
+            );
+        Ok(robots)
+    }
+
+    /// Fail with `RobotsDisallowed` unless the robots.txt rules of its host allow `request`
+    /// for the user agent it is sent with.
+    pub async fn check(
+        &self,
+        client: &reqwest::Client,
+        client_headers: &HeaderMap,
+        request: &reqwest::Request,
+        transport: Option<&Transport>,
+    ) -> Result<()> {
+        if !matches!(request.url().scheme(), "http" | "https") {
+            return Ok(());
+        }
+        let robots = self
+            .rules(client, client_headers, request, transport)
+            .await?;
+        let user_agent = request
+            .headers()
+            .get(USER_AGENT)
+            .or_else(|| client_headers.get(USER_AGENT))
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        let url = request.url();
+        if robots.is_allowed(user_agent, url.as_str()) {
+            return Ok(());
+        }
+        Err(RobotsDisallowed::new_err(format!(
+            "robots.txt of {} disallows {} for user agent {:?}",
+            url.origin().ascii_serialization(),
+            &url[url::Position::BeforePath..],
+            user_agent
+        ))
+        .into())
+    }
+}
+
+#[pymethods]
+impl RobotsTxt {
+    #[new]
+    fn py_new(text: &str) -> Self {
+        Self::parse(text)
+    }
+
+    /// Whether `user_agent` may fetch `path` (a path or a full URL).
+    fn allowed(&self, user_agent: &str, path: &str) -> bool {
+        self.is_allowed(user_agent, path)
+    }
+
+    /// The `Crawl-delay` in seconds for `user_agent`, if any.
+    fn crawl_delay(&self, user_agent: &str) -> Option<f64> {
+        self.delay_for(user_agent)
+    }
+
+    /// URLs of the `Sitemap` lines.
+    #[getter]
+    fn sitemaps(&self) -> Vec<String> {
+        self.sitemaps.clone()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "<RobotsTxt [{} groups, {} sitemaps]>",
+            self.groups.len(),
+            self.sitemaps.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+User-agent: *
+Disallow: /private/
+Allow: /private/public.html
+Disallow: /*.pdf$
+Crawl-delay: 2
+
+# Two agents share one group
+User-agent: BadBot
+User-agent: WorseBot
+Disallow: /
+
+User-agent: GoodBot
+Disallow:
+
+Sitemap: https://example.com/sitemap.xml
+";
+
+    #[test]
+    fn test_allowed() {
+        let robots = RobotsTxt::parse(ROBOTS);
+        assert!(robots.is_allowed("mybot", "/"));
+        assert!(!robots.is_allowed("mybot", "/private/data"));
+        assert!(robots.is_allowed("mybot", "/private/public.html"));
+        assert!(!robots.is_allowed("mybot", "https://example.com/docs/a.pdf"));
+        assert!(robots.is_allowed("mybot", "/docs/a.pdf?download=1"));
+        assert!(!robots.is_allowed("BadBot/2.1 (+https://bad.example)", "/index.html"));
+        assert!(!robots.is_allowed("worsebot", "/"));
+        assert!(robots.is_allowed("worsebot", "/robots.txt"));
+        assert!(robots.is_allowed("GoodBot", "/private/data"));
+
+        assert_eq!(robots.delay_for("mybot"), Some(2.0));
+        assert_eq!(robots.delay_for("goodbot"), None);
+        assert_eq!(robots.sitemaps, vec!["https://example.com/sitemap.xml"]);
+
+        assert!(RobotsTxt::parse("").is_allowed("mybot", "/private"));
+        assert!(!RobotsTxt::disallow_all().is_allowed("mybot", "/"));
+    }
+
+    #[test]
+    fn test_pattern_matches() {
+        assert!(pattern_matches("/fish", "/fish.html"));
+        assert!(!pattern_matches("/fish", "/Fish"));
+        assert!(pattern_matches("/fish*.php", "/fish/salmon.php?id=1"));
+        assert!(pattern_matches("/*.php$", "/folder/file.php"));
+        assert!(!pattern_matches("/*.php$", "/folder/file.php5"));
+        assert!(pattern_matches("/fish$", "/fish"));
+        assert!(!pattern_matches("/fish$", "/fish/"));
+        assert!(pattern_matches("/a*b*c", "/aXbYc"));
+        assert!(!pattern_matches("/a*b*c", "/aXcYb"));
+    }
+
+    #[test]
+    fn test_cache_ttl() {
+        assert!(RobotsCache::new(-1.0).is_err());
+        assert!(RobotsCache::new(f64::NAN).is_err());
+        let cache = RobotsCache::new(3600.0).unwrap();
+        assert!(cache.cached("https://example.com").is_none());
+        cache.entries.lock().unwrap().insert(
+            "https://example.com".into(),
+            Cached {
+                fetched: Instant::now(),
+                robots: Arc::new(RobotsTxt::default()),
+            },
+        );
+        assert!(cache.cached("https://example.com").is_some());
+        assert!(RobotsCache::new(0.0)
+            .unwrap()
+            .cached("https://example.com")
+            .is_none());
+    }
+
+    #[test]
+    fn test_robots_url() {
+        assert_eq!(
+            robots_url("https://example.com:8443/a/b?q=1#frag").unwrap(),
+            "https://example.com:8443/robots.txt"
+        );
+    }
+}
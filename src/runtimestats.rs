@@ -0,0 +1,72 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use pyo3::prelude::*;
+
+use crate::RUNTIME;
+
+/// Thread limit of the runtime's blocking pool, which runs DNS lookups and file reads.
+pub const MAX_BLOCKING_THREADS: usize = 512;
+
+/// Threads of the blocking pool currently alive. The runtime itself runs on the threads
+/// calling into httpr, so every thread it spawns belongs to the blocking pool.
+static BLOCKING_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+/// `on_thread_start` hook of the runtime.
+pub fn thread_started() {
+    BLOCKING_THREADS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// `on_thread_stop` hook of the runtime.
+pub fn thread_stopped() {
+    BLOCKING_THREADS.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// A snapshot of the shared Tokio runtime, from `httpr.runtime_stats()`.
+#[pyclass(frozen, module = "httpr")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuntimeStats {
+    /// Worker threads driving tasks; always 1 for the current-thread runtime.
+    #[pyo3(get)]
+    pub workers: usize,
+    /// Tasks spawned and not yet finished: open connections and the requests of
+    /// `fetch_all()` and `queue()`.
+    #[pyo3(get)]
+    pub alive_tasks: usize,
+    /// Tasks scheduled to run but waiting for the worker.
+    #[pyo3(get)]
+    pub queue_depth: usize,
+    /// Seconds the worker has spent running tasks since the runtime started.
+    #[pyo3(get)]
+    pub busy_time: f64,
+    /// Threads of the blocking pool currently alive (busy or idle).
+    #[pyo3(get)]
+    pub blocking_threads: usize,
+    #[pyo3(get)]
+    pub max_blocking_threads: usize,
+}
+
+#[pymethods]
+impl RuntimeStats {
+    fn __repr__(&self) -> String {
+        format!(
+            "<RuntimeStats alive_tasks={} queue_depth={} blocking_threads={}>",
+            self.alive_tasks, self.queue_depth, self.blocking_threads
+        )
+    }
+}
+
+/// Statistics of the Tokio runtime shared by all clients, to diagnose its saturation.
+#[pyfunction]
+pub fn runtime_stats() -> RuntimeStats {
+    let metrics = RUNTIME.metrics();
+    RuntimeStats {
+        workers: metrics.num_workers(),
+        alive_tasks: metrics.num_alive_tasks(),
+        queue_depth: metrics.global_queue_depth(),
+        busy_time: (0..metrics.num_workers())
+            .map(|worker| metrics.worker_total_busy_duration(worker).as_secs_f64())
+            .sum(),
+        blocking_threads: BLOCKING_THREADS.load(Ordering::Relaxed),
+        max_blocking_threads: MAX_BLOCKING_THREADS,
+    }
+}
@@ -0,0 +1,53 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+/// One way `instance` fails its schema.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SchemaError {
+    /// JSON Pointer to the offending value, e.g. `/items/0/id` (empty for the root).
+    pub path: String,
+    /// JSON Pointer to the failing keyword in the schema, e.g. `/properties/id/type`.
+    pub schema_path: String,
+    pub message: String,
+}
+
+/// Validate `instance` against the JSON Schema `schema` (the draft is taken from
+/// `$schema`, defaulting to 2020-12), returning every violation in document order.
+pub fn validate(schema: &Value, instance: &Value) -> Result<Vec<SchemaError>> {
+    let validator =
+        jsonschema::validator_for(schema).map_err(|e| anyhow!("Invalid JSON Schema: {}", e))?;
+    Ok(validator
+        .iter_errors(instance)
+        .map(|error| SchemaError {
+            path: error.instance_path().to_string(),
+            schema_path: error.schema_path().to_string(),
+            message: error.to_string(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate() {
+        let schema = json!({
+            "type": "object",
+            "required": ["items"],
+            "properties": {"items": {"type": "array", "items": {"type": "integer"}}}
+        });
+        assert!(validate(&schema, &json!({"items": [1, 2]}))
+            .unwrap()
+            .is_empty());
+
+        let errors = validate(&schema, &json!({"items": [1, "two"]})).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path, "/items/1");
+        assert_eq!(errors[0].schema_path, "/properties/items/items/type");
+        assert!(errors[0].message.contains("\"two\""));
+
+        assert!(validate(&json!({"type": 12}), &json!(null)).is_err());
+    }
+}
@@ -0,0 +1,456 @@
+use std::sync::Arc;
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple, PyType};
+use serde_json::{Map, Number, Value};
+
+/// How deeply `json=` values may nest, counting each `json_serializer` call as a level.
+const MAX_DEPTH: usize = 256;
+
+/// Python callable given as `json_serializer=`, converting objects in `json=` that are
+/// not dicts, lists, tuples, strings, numbers, booleans or None. It returns a
+/// JSON-compatible value, or `NotImplemented` to fall back to the built-in conversion.
+#[derive(Clone)]
+pub struct JsonSerializer(Arc<Py<PyAny>>);
+
+impl JsonSerializer {
+    pub fn new(callable: Py<PyAny>) -> PyResult<Self> {
+        Python::attach(|py| {
+            if !callable.bind(py).is_callable() {
+                return Err(PyValueError::new_err("json_serializer must be callable"));
+            }
+            Ok(JsonSerializer(Arc::new(callable)))
+        })
+    }
+}
+
+/// Convert a `json=` argument to a JSON value.
+///
+/// Besides plain Python values this accepts objects with a `model_dump()` method
+/// (pydantic models), dataclass instances, enums, `datetime`/`date`/`time` (ISO 8601),
+/// `UUID`s, and other objects through their public `__dict__` attributes, at any depth.
+pub fn to_json(obj: &Bound<'_, PyAny>, serializer: Option<&JsonSerializer>) -> PyResult<Value> {
+    convert(obj, serializer, 0)
+}
+
+fn convert(
+    obj: &Bound<'_, PyAny>,
+    serializer: Option<&JsonSerializer>,
+    depth: usize,
+) -> PyResult<Value> {
+    if depth > MAX_DEPTH {
+        return Err(PyValueError::new_err(format!(
+            "json= value is nested more than {} levels deep",
+            MAX_DEPTH
+        )));
+    }
+    if obj.is_none() {
+        return Ok(Value::Null);
+    }
+    if let Ok(value) = obj.cast::<PyBool>() {
+        return Ok(Value::Bool(value.is_true()));
+    }
+    if obj.is_instance_of::<PyInt>() {
+        if let Ok(value) = obj.extract::<i64>() {
+            return Ok(Value::from(value));
+        }
+        return obj
+            .extract::<u64>()
+            .map(Value::from)
+            .map_err(|_| PyValueError::new_err(format!("Integer {} is too large for json=", obj)));
+    }
+    if let Ok(value) = obj.cast::<PyFloat>() {
+        return Number::from_f64(value.value())
+            .map(Value::Number)
+            .ok_or_else(|| PyValueError::new_err(format!("Float {} is not allowed in JSON", obj)));
+    }
+    if let Ok(value) = obj.cast::<PyString>() {
+        return Ok(Value::String(value.extract()?));
+    }
+    if let Ok(dict) = obj.cast::<PyDict>() {
+        let mut map = Map::with_capacity(dict.len());
+        for (key, value) in dict.iter() {
+            let key = if let Ok(key) = key.cast::<PyString>() {
+                key.extract()?
+            } else if key.is_instance_of::<PyInt>() && !key.is_instance_of::<PyBool>() {
+                key.str()?.extract()?
+            } else {
+                return Err(PyTypeError::new_err(format!(
+                    "json= keys must be str or int, not {}",
+                    key.get_type().name()?
+                )));
+            };
+            map.insert(key, convert(&value, serializer, depth + 1)?);
+        }
+        return Ok(Value::Object(map));
+    }
+    if let Ok(list) = obj.cast::<PyList>() {
+        return list
+            .iter()
+            .map(|item| convert(&item, serializer, depth + 1))
+            .collect();
+    }
+    if let Ok(tuple) = obj.cast::<PyTuple>() {
+        return tuple
+            .iter()
+            .map(|item| convert(&item, serializer, depth + 1))
+            .collect();
+    }
+    convert_object(obj, serializer, depth)
+}
+
+/// Convert an object that is not a plain JSON value.
+fn convert_object(
+    obj: &Bound<'_, PyAny>,
+    serializer: Option<&JsonSerializer>,
+    depth: usize,
+) -> PyResult<Value> {
+    let py = obj.py();
+    if let Some(serializer) = serializer {
+        let value = serializer.0.bind(py).call1((obj,))?;
+        if !value.is(py.NotImplemented()) {
+            return convert(&value, Some(serializer), depth + 1);
+        }
+    }
+
+    if obj.hasattr("model_dump")? {
+        let value = obj.call_method0("model_dump")?;
+        return convert(&value, serializer, depth + 1);
+    }
+    if obj.hasattr("__dataclass_fields__")? && !obj.is_instance_of::<PyType>() {
+        let mut map = Map::new();
+        for field in py
+            .import("dataclasses")?
+            .call_method1("fields", (obj,))?
+            .try_iter()?
+        {
+            let name: String = field?.getattr("name")?.extract()?;
+            let value = obj.getattr(name.as_str())?;
+            map.insert(name, convert(&value, serializer, depth + 1)?);
+        }
+        return Ok(Value::Object(map));
+    }
+    if obj.is_instance(&py.import("enum")?.getattr("Enum")?)? {
+        return convert(&obj.getattr("value")?, serializer, depth + 1);
+    }
+    let datetime = py.import("datetime")?;
+    // `datetime` is a subclass of `date`
+    if obj.is_instance(&datetime.getattr("date")?)?
+        || obj.is_instance(&datetime.getattr("time")?)?
+    {
+        return Ok(Value::String(obj.call_method0("isoformat")?.extract()?));
+    }
+    if obj.is_instance(&py.import("uuid")?.getattr("UUID")?)? {
+        return Ok(Value::String(obj.str()?.extract()?));
+    }
+    if let Ok(attributes) = obj.getattr("__dict__") {
+        if let Ok(attributes) = attributes.cast::<PyDict>() {
+            let mut map = Map::new();
+            for (name, value) in attributes.iter() {
+                let name: String = name.extract()?;
+                if !name.starts_with('_') {
+                    map.insert(name, convert(&value, serializer, depth + 1)?);
+                }
+            }
+            return Ok(Value::Object(map));
+        }
+    }
+    Err(PyTypeError::new_err(format!(
+        "Object of type {} is not JSON serializable; pass json_serializer= to the client to convert it",
+        obj.get_type().name()?
+    )))
+}
+
+/// How `json=` bodies are written, from the client's JSON options.
+#[derive(Clone, Default)]
+pub enum JsonFormat {
+    /// serde_json's output: no whitespace, keys in insertion order, non-ASCII as is.
+    #[default]
+    Compact,
+    /// RFC 8785, for `json_canonical=`.
+    Canonical,
+    /// The `json.dumps` options `json_sort_keys`, `json_ensure_ascii`, `json_separators`
+    /// and `json_indent`.
+    Styled(JsonStyle),
+}
+
+#[derive(Clone)]
+pub struct JsonStyle {
+    sort_keys: bool,
+    ensure_ascii: bool,
+    item_separator: String,
+    key_separator: String,
+    indent: Option<usize>,
+}
+
+impl JsonFormat {
+    /// The format the client options ask for. `json_canonical` fixes every detail of the
+    /// output, so it excludes the other options.
+    pub fn from_args(
+        canonical: bool,
+        sort_keys: bool,
+        ensure_ascii: bool,
+        separators: Option<(String, String)>,
+        indent: Option<usize>,
+    ) -> PyResult<Self> {
+        let styled = sort_keys || ensure_ascii || separators.is_some() || indent.is_some();
+        if canonical {
+            if styled {
+                return Err(PyValueError::new_err(
+                    "json_canonical cannot be combined with json_sort_keys, json_ensure_ascii, \
+                     json_separators or json_indent",
+                ));
+            }
+            return Ok(JsonFormat::Canonical);
+        }
+        if !styled {
+            return Ok(JsonFormat::Compact);
+        }
+        // As `json.dumps`, a space after the colon once the output is indented
+        let (item_separator, key_separator) = separators.unwrap_or_else(|| {
+            let key_separator = if indent.is_some() { ": " } else { ":" };
+            (",".to_string(), key_separator.to_string())
+        });
+        Ok(JsonFormat::Styled(JsonStyle {
+            sort_keys,
+            ensure_ascii,
+            item_separator,
+            key_separator,
+            indent,
+        }))
+    }
+
+    /// `value` written in this format, or None for `Compact`, which reqwest writes itself.
+    pub fn serialize(&self, value: &Value) -> Option<String> {
+        match self {
+            JsonFormat::Compact => None,
+            JsonFormat::Canonical => Some(to_canonical_json(value)),
+            JsonFormat::Styled(style) => {
+                let mut out = String::new();
+                style.write(value, 0, &mut out);
+                Some(out)
+            }
+        }
+    }
+}
+
+impl JsonStyle {
+    /// Write `value`, nested `level` deep, the way `json.dumps` would with these options.
+    fn write(&self, value: &Value, level: usize, out: &mut String) {
+        match value {
+            Value::Array(items) if !items.is_empty() => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(&self.item_separator);
+                    }
+                    self.newline(level + 1, out);
+                    self.write(item, level + 1, out);
+                }
+                self.newline(level, out);
+                out.push(']');
+            }
+            Value::Object(map) if !map.is_empty() => {
+                let mut members: Vec<_> = map.iter().collect();
+                if self.sort_keys {
+                    members.sort_by_key(|(name, _)| *name);
+                }
+                out.push('{');
+                for (i, (name, value)) in members.into_iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(&self.item_separator);
+                    }
+                    self.newline(level + 1, out);
+                    self.write_string(name, out);
+                    out.push_str(&self.key_separator);
+                    self.write(value, level + 1, out);
+                }
+                self.newline(level, out);
+                out.push('}');
+            }
+            Value::String(text) => self.write_string(text, out),
+            // Empty arrays and objects stay on one line, as with `json.dumps`
+            _ => out.push_str(&value.to_string()),
+        }
+    }
+
+    /// A line break and the indentation of `level`, when indenting.
+    fn newline(&self, level: usize, out: &mut String) {
+        if let Some(indent) = self.indent {
+            out.push('\n');
+            out.push_str(&" ".repeat(indent * level));
+        }
+    }
+
+    /// `text` as a JSON string, with characters outside printable ASCII as `\uXXXX`
+    /// escapes (surrogate pairs beyond the BMP) if `ensure_ascii`.
+    fn write_string(&self, text: &str, out: &mut String) {
+        // serde_json escapes quotes, backslashes and control characters as `json.dumps` does
+        let quoted = Value::String(text.to_string()).to_string();
+        if !self.ensure_ascii {
+            out.push_str(&quoted);
+            return;
+        }
+        for c in quoted.chars() {
+            if c.is_ascii() && c != '\x7f' {
+                out.push(c);
+            } else {
+                let mut units = [0; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    out.push_str(&format!("\\u{:04x}", unit));
+                }
+            }
+        }
+    }
+}
+
+/// Serialize `value` in the JSON Canonicalization Scheme (RFC 8785), for `json_canonical=`:
+/// no whitespace, object members sorted by the UTF-16 code units of their names, and
+/// numbers as IEEE 754 doubles printed the way ECMAScript prints them.
+pub fn to_canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Number(number) => out.push_str(&canonical_number(
+            number.as_f64().expect("JSON numbers are finite"),
+        )),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut members: Vec<_> = map.iter().collect();
+            members.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (name, value)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                // serde_json escapes strings as RFC 8785 requires
+                out.push_str(&Value::String(name.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Null | Value::Bool(_) | Value::String(_) => out.push_str(&value.to_string()),
+    }
+}
+
+/// `x` as ECMAScript's `Number.prototype.toString` prints it: the shortest digits that
+/// round-trip, positioned by the exponent, in exponential notation beyond 1e21 and below 1e-6.
+fn canonical_number(x: f64) -> String {
+    if x == 0.0 {
+        return "0".to_string();
+    }
+    let sign = if x < 0.0 { "-" } else { "" };
+    // Shortest round-tripping digits, e.g. "1.2345e6"
+    let scientific = format!("{:e}", x.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("LowerExp output has an exponent");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    let n = exponent
+        .parse::<i32>()
+        .expect("LowerExp exponent is an integer")
+        + 1;
+    let body = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let (first, rest) = digits.split_at(1);
+        let fraction = if rest.is_empty() {
+            String::new()
+        } else {
+            format!(".{}", rest)
+        };
+        let exponent_sign = if n > 0 { "+" } else { "-" };
+        format!("{}{}e{}{}", first, fraction, exponent_sign, (n - 1).abs())
+    };
+    format!("{}{}", sign, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonical_number() {
+        let cases = [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (1.0, "1"),
+            (-1.5, "-1.5"),
+            (4.50, "4.5"),
+            (2e-3, "0.002"),
+            (0.000001, "0.000001"),
+            (1e-7, "1e-7"),
+            (1e21, "1e+21"),
+            (1e20, "100000000000000000000"),
+            (123456789012345680000.0, "123456789012345680000"),
+            (333333333.3333333, "333333333.3333333"),
+            (9007199254740992.0, "9007199254740992"),
+            (5e-324, "5e-324"),
+            (1.7976931348623157e308, "1.7976931348623157e+308"),
+        ];
+        for (x, expected) in cases {
+            assert_eq!(canonical_number(x), expected, "{}", x);
+        }
+    }
+
+    #[test]
+    fn test_styled_json() {
+        let value = json!({"b": [1, "é\u{1f600}", {}], "a": {"c": null, "\u{7f}": []}});
+        let format = |sort_keys, ensure_ascii, separators: Option<(&str, &str)>, indent| {
+            let separators = separators.map(|(item, key)| (item.to_string(), key.to_string()));
+            JsonFormat::from_args(false, sort_keys, ensure_ascii, separators, indent)
+                .unwrap()
+                .serialize(&value)
+                .unwrap()
+        };
+        assert_eq!(
+            format(true, false, None, None),
+            "{\"a\":{\"c\":null,\"\u{7f}\":[]},\"b\":[1,\"é\u{1f600}\",{}]}"
+        );
+        assert_eq!(
+            format(false, true, Some((", ", ": ")), None),
+            "{\"b\": [1, \"\\u00e9\\ud83d\\ude00\", {}], \"a\": {\"c\": null, \"\\u007f\": []}}"
+        );
+        assert_eq!(
+            format(true, false, None, Some(2)),
+            "{\n  \"a\": {\n    \"c\": null,\n    \"\u{7f}\": []\n  },\n  \"b\": [\n    1,\n    \"é\u{1f600}\",\n    {}\n  ]\n}"
+        );
+    }
+
+    #[test]
+    fn test_to_canonical_json() {
+        let value = json!({
+            "b": [1, 2.50, true, null],
+            "a": {"y": "\u{1f}\"é", "x": 1e30},
+            "\u{20ac}": 1,
+            "\u{fb33}": 2,
+            "\u{1f600}": 3,
+        });
+        assert_eq!(
+            to_canonical_json(&value),
+            "{\"a\":{\"x\":1e+30,\"y\":\"\\u001f\\\"é\"},\"b\":[1,2.5,true,null],\
+             \"\u{20ac}\":1,\"\u{1f600}\":3,\"\u{fb33}\":2}"
+        );
+    }
+}
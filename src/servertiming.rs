@@ -0,0 +1,156 @@
+use http::HeaderMap;
+use pyo3::prelude::*;
+
+/// One metric of a `Server-Timing` response header (W3C Server Timing), from
+/// `response.server_timing`.
+#[pyclass(frozen, module = "httpr")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerTiming {
+    #[pyo3(get)]
+    pub name: String,
+    /// Milliseconds, as sent by the server; `None` if absent or not a number.
+    #[pyo3(get)]
+    pub duration: Option<f64>,
+    #[pyo3(get)]
+    pub description: Option<String>,
+}
+
+impl ServerTiming {
+    /// Metrics of every `Server-Timing` header in `headers`, in order. Entries without
+    /// a name are skipped.
+    pub fn from_headers(headers: &HeaderMap) -> Vec<Self> {
+        headers
+            .get_all("server-timing")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| split_unquoted(value, ','))
+            .filter_map(Self::parse)
+            .collect()
+    }
+
+    /// Parse `name;dur=12.3;desc="..."`. Parameter names are case-insensitive and only
+    /// the first `dur` and `desc` count; other parameters are ignored.
+    fn parse(metric: &str) -> Option<Self> {
+        let mut params = split_unquoted(metric, ';').into_iter();
+        let name = params.next()?.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let mut duration = None;
+        let mut description = None;
+        for param in params {
+            let Some((key, value)) = param.split_once('=') else {
+                continue;
+            };
+            let value = unquote(value.trim());
+            match key.trim().to_ascii_lowercase().as_str() {
+                "dur" if duration.is_none() => {
+                    duration = Some(value.parse::<f64>().ok().filter(|d| d.is_finite()))
+                }
+                "desc" if description.is_none() => description = Some(value),
+                _ => {}
+            }
+        }
+        Some(ServerTiming {
+            name: name.to_string(),
+            duration: duration.flatten(),
+            description,
+        })
+    }
+}
+
+/// Split a header value at `separator`, except inside quoted strings.
+fn split_unquoted(value: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if quoted && c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            quoted = !quoted;
+        } else if c == separator && !quoted {
+            parts.push(&value[start..i]);
+            start = i + 1;
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+/// The content of a quoted string with escapes removed, or a token as is.
+fn unquote(value: &str) -> String {
+    let Some(inner) = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    else {
+        return value.to_string();
+    };
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unquoted.extend(chars.next()),
+            c => unquoted.push(c),
+        }
+    }
+    unquoted
+}
+
+#[pymethods]
+impl ServerTiming {
+    fn __repr__(&self) -> String {
+        let mut repr = format!("<ServerTiming {}", self.name);
+        if let Some(duration) = self.duration {
+            repr.push_str(&format!(" {}ms", duration));
+        }
+        if let Some(description) = &self.description {
+            repr.push_str(&format!(" {:?}", description));
+        }
+        repr.push('>');
+        repr
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metric(name: &str, duration: Option<f64>, description: Option<&str>) -> ServerTiming {
+        ServerTiming {
+            name: name.to_string(),
+            duration,
+            description: description.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            "server-timing",
+            r#"db;dur=53.2;desc="Query, \"users\"", cache;desc=hit, ;dur=1"#
+                .parse()
+                .unwrap(),
+        );
+        headers.append(
+            "Server-Timing",
+            "app;DUR=abc;dur=7, total;dur=120;dur=5;desc"
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(
+            ServerTiming::from_headers(&headers),
+            vec![
+                metric("db", Some(53.2), Some(r#"Query, "users""#)),
+                metric("cache", None, Some("hit")),
+                metric("app", None, None),
+                metric("total", Some(120.0), None),
+            ]
+        );
+        assert!(ServerTiming::from_headers(&HeaderMap::new()).is_empty());
+    }
+}
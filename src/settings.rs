@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use hyper_util::client::proxy::matcher::Matcher;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use reqwest::dns::Resolve;
+use reqwest::redirect::Policy;
+use reqwest::{Certificate, Identity};
+use tokio_rustls::rustls::pki_types::CertificateDer;
+use url::Url;
+
+use crate::connection::{Http2KeepAlive, Timeouts};
+use crate::cookies::RecordingJar;
+use crate::dns::{DnsCache, SystemResolver};
+use crate::events::ConnectionEvents;
+use crate::exceptions::map_reqwest_error;
+use crate::hosts::{GuardedResolver, HostPolicy};
+use crate::tls::TlsOptions;
+
+/// Everything the `reqwest` client of an `RClient` is built from but the proxy, kept so
+/// that setting `client.proxy` builds the same client again with the new proxy.
+pub struct ClientSettings {
+    pub cookie_provider: Arc<RecordingJar>,
+    /// Use the system proxy settings, and `http_proxy` and `https_proxy` without a proxy.
+    pub trust_env: bool,
+    /// `HTTPR_HTTP_PROXY` and `HTTPR_HTTPS_PROXY`.
+    pub http_proxy: Option<String>,
+    pub https_proxy: Option<String>,
+    pub timeouts: Timeouts,
+    pub hosts: Arc<HostPolicy>,
+    pub verify: bool,
+    /// Trusted in addition to the built-in roots, from `ca_cert_file` or `HTTPR_CA_BUNDLE`.
+    pub ca_certs: Vec<CertificateDer<'static>>,
+    /// Client certificate chain and private key, from `client_pem` or `client_pem_data`.
+    pub identity_pem: Option<Vec<u8>>,
+    pub tls_options: TlsOptions,
+    pub https_only: bool,
+    pub http2_only: bool,
+    pub keep_alive: Option<Http2KeepAlive>,
+    pub dns_cache: Option<DnsCache>,
+    pub connection_events: Option<ConnectionEvents>,
+}
+
+impl ClientSettings {
+    /// Build the client, sending requests through `proxy` if set.
+    pub fn build(&self, proxy: Option<&str>) -> PyResult<reqwest::Client> {
+        // Redirects are followed per request, see `RequestSpec::dispatch`
+        let mut client_builder = reqwest::Client::builder()
+            .cookie_provider(Arc::clone(&self.cookie_provider))
+            .redirect(Policy::none());
+
+        // Proxy; without trust_env the system proxy settings are ignored too
+        if !self.trust_env {
+            client_builder = client_builder.no_proxy();
+        }
+        let mut proxies: Vec<&str> = Vec::new();
+        if let Some(proxy) = proxy {
+            client_builder =
+                client_builder.proxy(reqwest::Proxy::all(proxy).map_err(map_reqwest_error)?);
+            proxies.push(proxy);
+        } else {
+            if let Some(proxy) = &self.http_proxy {
+                client_builder =
+                    client_builder.proxy(reqwest::Proxy::http(proxy).map_err(map_reqwest_error)?);
+                proxies.push(proxy);
+            }
+            if let Some(proxy) = &self.https_proxy {
+                client_builder =
+                    client_builder.proxy(reqwest::Proxy::https(proxy).map_err(map_reqwest_error)?);
+                proxies.push(proxy);
+            }
+        }
+
+        // Timeouts; the total timeout is applied per request
+        client_builder = self.timeouts.apply(client_builder);
+
+        // Decompression limits and size accounting need the compressed size, which
+        // reqwest's decoders hide
+        client_builder = client_builder.no_gzip().no_deflate().no_brotli().no_zstd();
+
+        // TLS settings reqwest has no options for replace its TLS config with our own
+        if self.tls_options.is_default() {
+            if self.verify {
+                client_builder = client_builder.tls_built_in_root_certs(true);
+                for cert in &self.ca_certs {
+                    client_builder = client_builder.add_root_certificate(
+                        Certificate::from_der(cert).map_err(map_reqwest_error)?,
+                    );
+                }
+            } else {
+                client_builder = client_builder.danger_accept_invalid_certs(true);
+            }
+
+            // Client mTLS identity must be applied regardless of `verify`: disabling
+            // server verification doesn't imply disabling client authentication.
+            if let Some(pem_bytes) = &self.identity_pem {
+                let identity = Identity::from_pem(pem_bytes).map_err(map_reqwest_error)?;
+                client_builder = client_builder.identity(identity);
+            }
+        } else {
+            let config = self
+                .tls_options
+                .client_config(
+                    self.verify,
+                    self.ca_certs.clone(),
+                    self.identity_pem.as_deref(),
+                )
+                .map_err(|e| PyValueError::new_err(format!("{:#}", e)))?;
+            client_builder = client_builder.use_preconfigured_tls(config);
+        }
+
+        if self.https_only {
+            client_builder = client_builder.https_only(true);
+        }
+        if self.http2_only {
+            client_builder = client_builder.http2_prior_knowledge();
+        }
+        if let Some(keep_alive) = &self.keep_alive {
+            client_builder = keep_alive.apply(client_builder);
+        }
+
+        // Connection events wrap the connector, and the resolver in `resolver`
+        if let Some(events) = &self.connection_events {
+            client_builder = client_builder.connector_layer(events.connector_layer());
+        }
+        client_builder = client_builder.dns_resolver2(self.resolver(proxies));
+
+        client_builder.build().map_err(map_reqwest_error)
+    }
+
+    /// The resolver of the client, which `proxies` bypass the private IP check of.
+    pub fn resolver<'a>(&self, proxies: impl IntoIterator<Item = &'a str>) -> Arc<dyn Resolve> {
+        // Scheme and host restrictions are checked for the resolved addresses too; the
+        // proxies may be on a private network
+        let mut resolver: Option<Arc<dyn Resolve>> = if self.hosts.blocks_private_ips() {
+            Some(Arc::new(GuardedResolver::new(
+                Arc::clone(&self.hosts),
+                self.dns_cache.clone(),
+                proxies,
+            )))
+        } else {
+            self.dns_cache
+                .clone()
+                .map(|dns_cache| Arc::new(dns_cache) as Arc<dyn Resolve>)
+        };
+        if let Some(events) = &self.connection_events {
+            resolver = Some(Arc::new(events.resolver(resolver)));
+        }
+        // Every resolver reports lookup failures as `DnsFailure`, raised as `DNSError`
+        resolver.unwrap_or_else(|| Arc::new(SystemResolver))
+    }
+
+    /// Whether the client built by `build` with `proxy` sends requests for `url` through a
+    /// proxy: `proxy` if set, otherwise `HTTPR_HTTP_PROXY` or `HTTPR_HTTPS_PROXY` by
+    /// scheme, otherwise with `trust_env` the proxy environment variables.
+    pub fn proxies_url(&self, proxy: Option<&str>, url: &Url) -> bool {
+        if proxy.is_some() {
+            return true;
+        }
+        // Like `reqwest::ClientBuilder::proxy`, either of these turns the system proxy off
+        if self.http_proxy.is_some() || self.https_proxy.is_some() {
+            let proxy = match url.scheme() {
+                "https" => &self.https_proxy,
+                _ => &self.http_proxy,
+            };
+            return proxy.is_some();
+        }
+        self.trust_env
+            && url
+                .as_str()
+                .parse::<http::Uri>()
+                .is_ok_and(|uri| Matcher::from_system().intercept(&uri).is_some())
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::{HashSet, VecDeque};
+use std::io::Read;
+
+use anyhow::{bail, Result};
+use flate2::read::GzDecoder;
+use pyo3::prelude::*;
+use reqwest::header::HeaderMap;
+
+use crate::block_on;
+use crate::exceptions::map_anyhow_error;
+use crate::request::RequestSpec;
+
+/// Largest uncompressed sitemap accepted, as set by the sitemaps.org protocol.
+const SITEMAP_SIZE_LIMIT: u64 = 50 * 1024 * 1024;
+
+/// Entries of one sitemap file.
+#[derive(Debug, PartialEq)]
+enum Sitemap {
+    /// `<urlset>` (or a plain text sitemap): page URLs.
+    Urls(Vec<String>),
+    /// `<sitemapindex>`: URLs of further sitemaps.
+    Index(Vec<String>),
+}
+
+/// Body of a sitemap file, gunzipped if it is gzip data.
+fn decompress(body: &[u8]) -> Result<Vec<u8>> {
+    if !body.starts_with(&[0x1f, 0x8b]) {
+        return Ok(body.to_vec());
+    }
+    let mut content = Vec::new();
+    GzDecoder::new(body)
+        .take(SITEMAP_SIZE_LIMIT + 1)
+        .read_to_end(&mut content)?;
+    if content.len() as u64 > SITEMAP_SIZE_LIMIT {
+        bail!("Sitemap exceeds {} bytes uncompressed", SITEMAP_SIZE_LIMIT);
+    }
+    Ok(content)
+}
+
+fn parse(body: &[u8]) -> Result<Sitemap> {
+    let content = decompress(body)?;
+    let text = std::str::from_utf8(&content)?.trim_start_matches('\u{feff}');
+    // Plain text sitemaps list one URL per line
+    if !text.trim_start().starts_with('<') {
+        return Ok(Sitemap::Urls(
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(String::from)
+                .collect(),
+        ));
+    }
+    let document = roxmltree::Document::parse(text)?;
+    let root = document.root_element();
+    let locs = |entry: &str| -> Vec<String> {
+        root.children()
+            .filter(|node| node.tag_name().name() == entry)
+            .filter_map(|node| {
+                node.children()
+                    .find(|child| child.tag_name().name() == "loc")
+                    .and_then(|loc| loc.text())
+            })
+            .map(|loc| loc.trim().to_string())
+            .filter(|loc| !loc.is_empty())
+            .collect()
+    };
+    match root.tag_name().name() {
+        "urlset" => Ok(Sitemap::Urls(locs("url"))),
+        "sitemapindex" => Ok(Sitemap::Index(locs("sitemap"))),
+        name => bail!("Unexpected sitemap root element <{}>", name),
+    }
+}
+
+/// Iterator over the `<loc>` URLs of a sitemap, returned by `client.sitemap()`.
+///
+/// Sitemap indexes are followed depth-first, fetching each child sitemap only once the
+/// entries of the previous one have been yielded. A sitemap is fetched at most once.
+#[pyclass(module = "httpr")]
+pub struct SitemapIterator {
+    client: reqwest::Client,
+    client_headers: HeaderMap,
+    spec: RequestSpec,
+    /// Sitemaps still to fetch.
+    queue: VecDeque<String>,
+    seen: HashSet<String>,
+    entries: VecDeque<String>,
+}
+
+impl SitemapIterator {
+    pub fn new(client: reqwest::Client, client_headers: HeaderMap, spec: RequestSpec) -> Self {
+        let url = spec.url.clone();
+        SitemapIterator {
+            client,
+            client_headers,
+            spec,
+            queue: VecDeque::from([url.clone()]),
+            seen: HashSet::from([url]),
+            entries: VecDeque::new(),
+        }
+    }
+
+    fn fetch(&mut self, py: Python, url: String) -> PyResult<()> {
+        let mut spec = self.spec.clone();
+        spec.url = url;
+        let client = self.client.clone();
+        let client_headers = self.client_headers.clone();
+        let future = async {
+            let resp = spec
+                .send(client, client_headers)
+                .await?
+                .error_for_status()?;
+            Ok::<_, anyhow::Error>(resp.bytes().await?)
+        };
+        let body = block_on(py, || future)?.map_err(map_anyhow_error)?;
+
+        match parse(&body).map_err(map_anyhow_error)? {
+            Sitemap::Urls(urls) => self.entries.extend(urls),
+            Sitemap::Index(sitemaps) => {
+                // Children go first so that each index is walked in document order
+                for sitemap in sitemaps.into_iter().rev() {
+                    if self.seen.insert(sitemap.clone()) {
+                        self.queue.push_front(sitemap);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl SitemapIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python) -> PyResult<Option<String>> {
+        loop {
+            if let Some(entry) = self.entries.pop_front() {
+                return Ok(Some(entry));
+            }
+            let Some(url) = self.queue.pop_front() else {
+                return Ok(None);
+            };
+            self.fetch(py, url)?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let urlset = br#"<?xml version="1.0" encoding="UTF-8"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <url><loc> https://example.com/a </loc><lastmod>2024-01-01</lastmod></url>
+              <url><loc>https://example.com/b</loc></url>
+            </urlset>"#;
+        assert_eq!(
+            parse(urlset).unwrap(),
+            Sitemap::Urls(vec![
+                "https://example.com/a".into(),
+                "https://example.com/b".into()
+            ])
+        );
+
+        let index = br#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+              <sitemap><loc>https://example.com/s1.xml.gz</loc></sitemap>
+            </sitemapindex>"#;
+        assert_eq!(
+            parse(index).unwrap(),
+            Sitemap::Index(vec!["https://example.com/s1.xml.gz".into()])
+        );
+
+        assert_eq!(
+            parse(b"https://example.com/a\n\nhttps://example.com/b\n").unwrap(),
+            Sitemap::Urls(vec![
+                "https://example.com/a".into(),
+                "https://example.com/b".into()
+            ])
+        );
+        assert!(parse(b"<feed></feed>").is_err());
+    }
+
+    #[test]
+    fn test_parse_gzip() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder
+            .write_all(b"<urlset><url><loc>https://example.com/</loc></url></urlset>")
+            .unwrap();
+        let body = encoder.finish().unwrap();
+        assert_eq!(
+            parse(&body).unwrap(),
+            Sitemap::Urls(vec!["https://example.com/".into()])
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use anyhow::{bail, Result};
+use pyo3::exceptions::PyValueError;
+use reqwest::header::{HeaderValue, HOST};
+use url::{Host, Url};
+
+/// Check a `sni_hostname=`: a domain name or IP address, without port.
+pub fn check_sni_hostname(hostname: &str) -> Result<()> {
+    if let Err(e) = Host::parse(hostname) {
+        bail!("Invalid sni_hostname '{}': {}", hostname, e);
+    }
+    Ok(())
+}
+
+/// Route an HTTPS request by `sni_hostname` and check it against its `Host` header.
+///
+/// With `sni_hostname`, the request is sent to that host: it is looked up, connected to,
+/// named in the TLS handshake and must match the certificate, while the `Host` header
+/// (unless given) keeps naming the host of the URL. Returns the URL to report on the
+/// response, which is the URL as requested.
+///
+/// A `Host` header naming another host than the TLS server name is refused unless
+/// `allow_mismatch` (`allow_sni_mismatch=True`), as servers and CDNs commonly route by
+/// one and authorize by the other.
+pub fn route(
+    request: &mut reqwest::Request,
+    sni_hostname: Option<&str>,
+    allow_mismatch: bool,
+) -> Result<Option<Url>> {
+    if request.url().scheme() != "https" {
+        if sni_hostname.is_some() {
+            bail!(PyValueError::new_err("sni_hostname requires an https URL"));
+        }
+        return Ok(None);
+    }
+    let url_host = request.url().host_str().unwrap_or_default().to_string();
+    let host = match request.headers().get(HOST) {
+        Some(value) => header_host(value.to_str().unwrap_or_default()).to_string(),
+        None => url_host.clone(),
+    };
+    let server_name = sni_hostname.unwrap_or(&url_host);
+    if !host.eq_ignore_ascii_case(server_name) && !allow_mismatch {
+        bail!(PyValueError::new_err(format!(
+            "Host '{}' does not match the TLS server name '{}'; set allow_sni_mismatch=True \
+             on the client to send them anyway",
+            host, server_name
+        )));
+    }
+    let Some(sni_hostname) = sni_hostname.filter(|name| !name.eq_ignore_ascii_case(&url_host))
+    else {
+        return Ok(None);
+    };
+
+    let original = request.url().clone();
+    if !request.headers().contains_key(HOST) {
+        let authority = match original.port() {
+            Some(port) => format!("{}:{}", url_host, port),
+            None => url_host,
+        };
+        request
+            .headers_mut()
+            .insert(HOST, HeaderValue::from_str(&authority)?);
+    }
+    request.url_mut().set_host(Some(sni_hostname))?;
+    Ok(Some(original))
+}
+
+/// Host of a `Host` header value, without port.
+fn header_host(value: &str) -> &str {
+    if let Some(end) = value.strip_prefix('[').and_then(|rest| rest.find(']')) {
+        return &value[..end + 2];
+    }
+    value.split(':').next().unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(url: &str, host: Option<&str>) -> reqwest::Request {
+        let mut request = reqwest::Request::new(reqwest::Method::GET, url.parse().unwrap());
+        if let Some(host) = host {
+            request
+                .headers_mut()
+                .insert(HOST, HeaderValue::from_str(host).unwrap());
+        }
+        request
+    }
+
+    #[test]
+    fn test_route() {
+        let mut req = request("https://origin.example:8443/a?b", None);
+        assert!(route(&mut req, Some("edge.example"), false).is_err());
+        let original = route(&mut req, Some("edge.example"), true).unwrap();
+        assert_eq!(
+            original.unwrap().as_str(),
+            "https://origin.example:8443/a?b"
+        );
+        assert_eq!(req.url().as_str(), "https://edge.example:8443/a?b");
+        assert_eq!(req.headers()[HOST], "origin.example:8443");
+
+        // A Host header matching the server name needs no flag
+        let mut req = request("https://origin.example/", Some("edge.example"));
+        assert!(route(&mut req, Some("edge.example"), false)
+            .unwrap()
+            .is_some());
+        assert_eq!(req.headers()[HOST], "edge.example");
+
+        let mut req = request("https://site.example/", Some("other.example"));
+        assert!(route(&mut req, None, false).is_err());
+        assert!(route(&mut req, None, true).unwrap().is_none());
+
+        let mut req = request("http://site.example/", Some("other.example"));
+        assert!(route(&mut req, None, false).unwrap().is_none());
+        assert!(route(&mut req, Some("edge.example"), true).is_err());
+    }
+
+    #[test]
+    fn test_header_host() {
+        assert_eq!(header_host("example.com:8080"), "example.com");
+        assert_eq!(header_host("example.com"), "example.com");
+        assert_eq!(header_host("[::1]:8080"), "[::1]");
+    }
+
+    #[test]
+    fn test_check_sni_hostname() {
+        assert!(check_sni_hostname("cdn.example.com").is_ok());
+        assert!(check_sni_hostname("203.0.113.5").is_ok());
+        assert!(check_sni_hostname("[::1]").is_ok());
+        for invalid in ["", "example.com:443", "user@example.com", "exa mple.com"] {
+            assert!(check_sni_hostname(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,440 @@
+use pyo3::prelude::*;
+
+/// What `Response.sniff()` makes of a body regardless of its `Content-Type`.
+#[pyclass(frozen, module = "httpr")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct SniffResult {
+    /// The media type the body looks like, `application/octet-stream` if unknown.
+    #[pyo3(get)]
+    pub mime_type: String,
+    /// ISO 639-1 code of the natural language of text bodies, if recognizable.
+    #[pyo3(get)]
+    pub language: Option<String>,
+    /// The media type of the `Content-Type` header, for comparison.
+    #[pyo3(get)]
+    pub declared_type: Option<String>,
+}
+
+#[pymethods]
+impl SniffResult {
+    fn __repr__(&self) -> String {
+        let mut repr = format!("<SniffResult {}", self.mime_type);
+        if let Some(language) = &self.language {
+            repr.push_str(&format!(" ({})", language));
+        }
+        repr.push('>');
+        repr
+    }
+}
+
+/// Leading bytes of binary formats and their media types, checked in order.
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"%PDF-", "application/pdf"),
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"II*\x00", "image/tiff"),
+    (b"MM\x00*", "image/tiff"),
+    (b"\x00\x00\x01\x00", "image/x-icon"),
+    (b"OggS\x00", "application/ogg"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b\x08", "application/gzip"),
+    (b"\xfd7zXZ\x00", "application/x-xz"),
+    (b"\x28\xb5\x2f\xfd", "application/zstd"),
+    (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    (b"Rar!\x1a\x07", "application/vnd.rar"),
+    (b"\x00asm", "application/wasm"),
+    (b"\x7fELF", "application/x-elf"),
+    (b"\x00\x01\x00\x00\x00", "font/ttf"),
+    (b"SQLite format 3\x00", "application/vnd.sqlite3"),
+    (b"%!PS", "application/postscript"),
+    (b"{\\rtf", "application/rtf"),
+];
+
+/// Signatures that could also start a text, only checked for bodies with binary bytes.
+const WEAK_SIGNATURES: &[(&[u8], &str)] = &[
+    (b"fLaC", "audio/flac"),
+    (b"ID3", "audio/mpeg"),
+    (b"BZh", "application/x-bzip2"),
+    (b"wOFF", "font/woff"),
+    (b"wOF2", "font/woff2"),
+    (b"OTTO", "font/otf"),
+    (b"PAR1", "application/vnd.apache.parquet"),
+    (b"ARROW1", "application/vnd.apache.arrow.file"),
+    (b"BM", "image/bmp"),
+    (b"MZ", "application/vnd.microsoft.portable-executable"),
+];
+
+/// Tags that start an HTML document (WHATWG MIME Sniffing), each followed by a space or `>`.
+const HTML_TAGS: &[&[u8]] = &[
+    b"<!doctype html",
+    b"<html",
+    b"<head",
+    b"<script",
+    b"<iframe",
+    b"<h1",
+    b"<div",
+    b"<font",
+    b"<table",
+    b"<a",
+    b"<style",
+    b"<title",
+    b"<b",
+    b"<body",
+    b"<br",
+    b"<p",
+    b"<!--",
+];
+
+/// How much of the body the text checks look at.
+const SNIFF_LEN: usize = 1024;
+
+/// The media type `body` looks like: a binary signature, else markup, JSON or plain
+/// text if it has no binary bytes, else `application/octet-stream`.
+pub fn sniff_mime_type(body: &[u8]) -> &'static str {
+    if let Some(mime_type) = sniff_binary(body) {
+        return mime_type;
+    }
+    let (head, utf16) = match body {
+        [0xef, 0xbb, 0xbf, rest @ ..] => (rest, false),
+        [0xfe, 0xff, ..] | [0xff, 0xfe, ..] => (body, true),
+        _ => (body, false),
+    };
+    if utf16 {
+        return "text/plain";
+    }
+    let head = &head[..head.len().min(SNIFF_LEN)];
+    if head.iter().any(|&b| is_binary_byte(b)) {
+        return WEAK_SIGNATURES
+            .iter()
+            .find(|(magic, _)| body.starts_with(magic))
+            .map_or("application/octet-stream", |&(_, mime_type)| mime_type);
+    }
+    let start = head
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(head.len());
+    let head = &head[start..];
+    if starts_with_tag(head, b"<?xml") || starts_with_tag(head, b"<svg") {
+        return sniff_xml(head);
+    }
+    if HTML_TAGS.iter().any(|tag| starts_with_tag(head, tag)) {
+        return "text/html";
+    }
+    if matches!(head.first(), Some(b'{' | b'['))
+        && serde_json::from_slice::<serde_json::Value>(body).is_ok()
+    {
+        return "application/json";
+    }
+    "text/plain"
+}
+
+fn sniff_binary(body: &[u8]) -> Option<&'static str> {
+    if body.len() >= 12 && &body[..4] == b"RIFF" {
+        return match &body[8..12] {
+            b"WEBP" => Some("image/webp"),
+            b"WAVE" => Some("audio/wav"),
+            b"AVI " => Some("video/x-msvideo"),
+            _ => None,
+        };
+    }
+    if body.len() >= 12 && &body[4..8] == b"ftyp" {
+        return Some(match &body[8..12] {
+            b"avif" | b"avis" => "image/avif",
+            b"heic" | b"heix" | b"mif1" => "image/heic",
+            b"qt  " => "video/quicktime",
+            b"M4A " => "audio/mp4",
+            _ => "video/mp4",
+        });
+    }
+    if body.starts_with(b"\x1a\x45\xdf\xa3") {
+        let head = &body[..body.len().min(64)];
+        let webm = head.windows(4).any(|w| w == b"webm");
+        return Some(if webm {
+            "video/webm"
+        } else {
+            "video/x-matroska"
+        });
+    }
+    // MPEG audio frame sync without an ID3 tag
+    if body.len() >= 2 && body[0] == 0xff && matches!(body[1], 0xfb | 0xf3 | 0xf2) {
+        return Some("audio/mpeg");
+    }
+    SIGNATURES
+        .iter()
+        .find(|(magic, _)| body.starts_with(magic))
+        .map(|&(_, mime_type)| mime_type)
+}
+
+/// The XML vocabulary of a document starting with `head`.
+fn sniff_xml(head: &[u8]) -> &'static str {
+    let lower = head.to_ascii_lowercase();
+    let contains = |needle: &[u8]| lower.windows(needle.len()).any(|w| w == needle);
+    if contains(b"<svg") {
+        "image/svg+xml"
+    } else if contains(b"<rss") {
+        "application/rss+xml"
+    } else if contains(b"<feed") {
+        "application/atom+xml"
+    } else {
+        "application/xml"
+    }
+}
+
+/// Whether `head` starts with `tag` (ASCII case-insensitive) followed by a space, `>`
+/// or the end of input.
+fn starts_with_tag(head: &[u8], tag: &[u8]) -> bool {
+    head.len() >= tag.len()
+        && head[..tag.len()].eq_ignore_ascii_case(tag)
+        && head
+            .get(tag.len())
+            .is_none_or(|&b| b == b'>' || b == b'/' || b.is_ascii_whitespace())
+}
+
+/// Control bytes that do not occur in text (WHATWG "binary data byte").
+fn is_binary_byte(b: u8) -> bool {
+    matches!(b, 0x00..=0x08 | 0x0b | 0x0e..=0x1a | 0x1c..=0x1f)
+}
+
+/// Frequent short words of languages written in Latin script, told apart by how many
+/// of the words of a text are among them.
+const STOPWORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "of", "to", "is", "in", "that", "it", "for", "with", "was", "on", "are",
+            "this", "you", "be", "have", "not",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "und", "das", "ist", "nicht", "ein", "eine", "zu", "mit", "den", "von",
+            "sich", "auf", "für", "ich", "es", "auch",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "des", "une", "un", "du", "dans", "que", "qui", "pour",
+            "pas", "sur", "avec", "il", "ce",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "la", "los", "las", "y", "es", "que", "de", "en", "un", "una", "por", "con",
+            "para", "del", "se", "no", "está",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "la", "che", "di", "e", "è", "un", "una", "per", "non", "con", "del", "della",
+            "sono", "gli", "le", "si", "nel",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "a", "os", "as", "e", "é", "que", "de", "do", "da", "em", "um", "uma", "para",
+            "com", "não", "se", "dos",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "van", "is", "dat", "niet", "op", "te", "met", "zijn",
+            "voor", "ik", "die", "er", "ook", "maar",
+        ],
+    ),
+    (
+        "sv",
+        &[
+            "och", "att", "det", "som", "en", "är", "på", "för", "med", "inte", "av", "den",
+            "till", "jag", "har", "de", "ett", "om",
+        ],
+    ),
+    (
+        "pl",
+        &[
+            "i", "w", "nie", "na", "się", "z", "jest", "to", "że", "do", "co", "jak", "ale", "o",
+            "tak", "od", "po", "przez",
+        ],
+    ),
+];
+
+/// Letters needed before a language is guessed.
+const MIN_LETTERS: usize = 20;
+
+/// Stopword hits needed to name a Latin-script language.
+const MIN_STOPWORDS: usize = 3;
+
+/// The natural language of `text` as an ISO 639-1 code: from the dominant script, and
+/// for Latin and Cyrillic script from frequent words and letters. Only the first
+/// 64 KiB are looked at; `None` if the text is too short or not recognized.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let text = &text[..floor_char_boundary(text, 64 * 1024)];
+    let mut counts = ScriptCounts::default();
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        counts.add(c);
+    }
+    if counts.total < MIN_LETTERS {
+        return None;
+    }
+    let cjk = counts.han + counts.kana;
+    let (script, count) = [
+        ("latin", counts.latin),
+        ("cyrillic", counts.cyrillic),
+        ("greek", counts.greek),
+        ("arabic", counts.arabic),
+        ("hebrew", counts.hebrew),
+        ("cjk", cjk),
+        ("hangul", counts.hangul),
+        ("devanagari", counts.devanagari),
+        ("thai", counts.thai),
+    ]
+    .into_iter()
+    .max_by_key(|&(_, count)| count)?;
+    if count * 2 < counts.total {
+        return None;
+    }
+    match script {
+        "latin" => latin_language(text),
+        "cyrillic" if text.contains(['і', 'ї', 'є', 'ґ', 'І', 'Ї', 'Є', 'Ґ']) => Some("uk"),
+        "cyrillic" => Some("ru"),
+        "greek" => Some("el"),
+        "arabic" => Some("ar"),
+        "hebrew" => Some("he"),
+        // Japanese mixes kanji with kana, Chinese has none
+        "cjk" if counts.kana * 10 >= cjk => Some("ja"),
+        "cjk" => Some("zh"),
+        "hangul" => Some("ko"),
+        "devanagari" => Some("hi"),
+        "thai" => Some("th"),
+        _ => None,
+    }
+}
+
+fn latin_language(text: &str) -> Option<&'static str> {
+    let mut hits = [0usize; STOPWORDS.len()];
+    for word in text.split(|c: char| !c.is_alphabetic()) {
+        if word.is_empty() || word.len() > 6 {
+            continue;
+        }
+        let word = word.to_lowercase();
+        for (i, (_, words)) in STOPWORDS.iter().enumerate() {
+            if words.contains(&word.as_str()) {
+                hits[i] += 1;
+            }
+        }
+    }
+    let (best, &count) = hits
+        .iter()
+        .enumerate()
+        .max_by_key(|&(i, count)| (count, usize::MAX - i))?;
+    (count >= MIN_STOPWORDS).then_some(STOPWORDS[best].0)
+}
+
+/// Letters of `text` by Unicode script.
+#[derive(Default)]
+struct ScriptCounts {
+    total: usize,
+    latin: usize,
+    cyrillic: usize,
+    greek: usize,
+    arabic: usize,
+    hebrew: usize,
+    han: usize,
+    kana: usize,
+    hangul: usize,
+    devanagari: usize,
+    thai: usize,
+}
+
+impl ScriptCounts {
+    fn add(&mut self, c: char) {
+        self.total += 1;
+        let counter = match c as u32 {
+            0x41..=0x24f | 0x1e00..=0x1eff => &mut self.latin,
+            0x370..=0x3ff | 0x1f00..=0x1fff => &mut self.greek,
+            0x400..=0x52f => &mut self.cyrillic,
+            0x590..=0x5ff => &mut self.hebrew,
+            0x600..=0x6ff | 0x750..=0x77f => &mut self.arabic,
+            0x900..=0x97f => &mut self.devanagari,
+            0xe00..=0xe7f => &mut self.thai,
+            0x3040..=0x30ff => &mut self.kana,
+            0x4e00..=0x9fff | 0x3400..=0x4dbf => &mut self.han,
+            0xac00..=0xd7af | 0x1100..=0x11ff => &mut self.hangul,
+            _ => return,
+        };
+        *counter += 1;
+    }
+}
+
+/// The largest char boundary of `text` at most `index`.
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_mime_type() {
+        assert_eq!(sniff_mime_type(b"\x89PNG\r\n\x1a\n\x00\x00"), "image/png");
+        assert_eq!(sniff_mime_type(b"%PDF-1.7\n"), "application/pdf");
+        assert_eq!(
+            sniff_mime_type(b"RIFF\x00\x00\x00\x00WEBPVP8 "),
+            "image/webp"
+        );
+        assert_eq!(sniff_mime_type(b"\x00\x00\x00\x18ftypmp42"), "video/mp4");
+        assert_eq!(sniff_mime_type(b"  <!DOCTYPE html><html>"), "text/html");
+        assert_eq!(sniff_mime_type(b"<p>Hi</p>"), "text/html");
+        assert_eq!(sniff_mime_type(b"<pre>x</pre>"), "text/plain");
+        assert_eq!(
+            sniff_mime_type(b"<?xml version=\"1.0\"?><rss>"),
+            "application/rss+xml"
+        );
+        assert_eq!(sniff_mime_type(b"<svg xmlns=\"\">"), "image/svg+xml");
+        assert_eq!(sniff_mime_type(br#"{"a": [1, 2]}"#), "application/json");
+        assert_eq!(sniff_mime_type(b"{not json"), "text/plain");
+        assert_eq!(sniff_mime_type(b"\xef\xbb\xbfhello"), "text/plain");
+        assert_eq!(sniff_mime_type(b"\x01\x02\x03"), "application/octet-stream");
+        assert_eq!(sniff_mime_type(b"BM\x36\x00\x0c\x00\x00\x00"), "image/bmp");
+        assert_eq!(sniff_mime_type(b"BMW makes cars"), "text/plain");
+        assert_eq!(sniff_mime_type(b""), "text/plain");
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let english = "The quick brown fox jumps over the lazy dog, and this is not the end of it.";
+        assert_eq!(detect_language(english), Some("en"));
+        let german =
+            "Der schnelle braune Fuchs springt über den faulen Hund, und das ist nicht alles.";
+        assert_eq!(detect_language(german), Some("de"));
+        let french =
+            "Le renard brun rapide saute par-dessus le chien paresseux, et ce n'est pas tout.";
+        assert_eq!(detect_language(french), Some("fr"));
+        assert_eq!(
+            detect_language("Быстрая коричневая лиса прыгает через ленивую собаку."),
+            Some("ru")
+        );
+        assert_eq!(
+            detect_language("素早い茶色の狐がのろまな犬を飛び越えるという文章です。"),
+            Some("ja")
+        );
+        assert_eq!(
+            detect_language("敏捷的棕色狐狸跳过了懒狗，这是一个测试句子而已。"),
+            Some("zh")
+        );
+        assert_eq!(detect_language("short"), None);
+        assert_eq!(detect_language("1234567890 !!! ??? 1234567890 ..."), None);
+    }
+}
@@ -0,0 +1,347 @@
+use anyhow::{anyhow, bail, Result};
+use bytes::Bytes;
+use foldhash::fast::RandomState;
+use indexmap::IndexMap;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pythonize::depythonize;
+use serde_json::Value;
+
+use crate::auth::Auth;
+use crate::exceptions::map_anyhow_error;
+use crate::lazyheaders::LazyHeaders;
+use crate::request::Request;
+use crate::serialize::to_json;
+
+type IndexMapSSR = IndexMap<String, String, RandomState>;
+
+/// Replace `{name}` placeholders in `template` using `lookup`.
+///
+/// Follows `str.format` conventions for named fields: `{{` and `}}` are literal braces,
+/// an unknown name or an unmatched brace is an error.
+pub(crate) fn render_str(
+    template: &str,
+    lookup: &dyn Fn(&str) -> Result<String>,
+) -> Result<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => bail!("Unclosed '{{' in template '{}'", template),
+                    }
+                }
+                out.push_str(&lookup(name.trim())?);
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '}' => bail!("Single '}}' encountered in template '{}'", template),
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// Collect placeholder names from `template`, in order of first appearance.
+fn collect_placeholders(template: &str, names: &mut Vec<String>) {
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '{' {
+            continue;
+        }
+        if chars.peek() == Some(&'{') {
+            chars.next();
+            continue;
+        }
+        let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+        let name = name.trim().to_string();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+}
+
+/// If `s` is exactly one placeholder (e.g. `"{user_id}"`), return its name.
+fn whole_placeholder(s: &str) -> Option<&str> {
+    let inner = s.strip_prefix('{')?.strip_suffix('}')?;
+    if inner.is_empty() || inner.contains(['{', '}']) {
+        return None;
+    }
+    Some(inner.trim())
+}
+
+/// Variables passed to `render()`, looked up by placeholder name.
+struct Vars<'a, 'py> {
+    vars: &'a Bound<'py, PyDict>,
+}
+
+impl Vars<'_, '_> {
+    fn get(&self, name: &str) -> Result<Bound<'_, PyAny>> {
+        self.vars
+            .get_item(name)?
+            .ok_or_else(|| anyhow!("Missing template variable '{}'", name))
+    }
+
+    /// The variable formatted with `str()`, as `str.format` would.
+    fn text(&self, name: &str) -> Result<String> {
+        let value = self.get(name)?;
+        Ok(value.str()?.to_string())
+    }
+
+    /// The variable converted to a JSON value, keeping its Python type.
+    fn value(&self, name: &str) -> Result<Value> {
+        let value = self.get(name)?;
+        Ok(to_json(&value, None)?)
+    }
+
+    fn render(&self, template: &str) -> Result<String> {
+        render_str(template, &|name| self.text(name))
+    }
+
+    fn render_map(&self, map: &Option<IndexMapSSR>) -> Result<Option<IndexMapSSR>> {
+        map.as_ref()
+            .map(|map| {
+                map.iter()
+                    .map(|(k, v)| Ok((self.render(k)?, self.render(v)?)))
+                    .collect()
+            })
+            .transpose()
+    }
+
+    /// Render a JSON body. A string that is exactly one placeholder is replaced by the
+    /// variable itself (so `{"id": "{user_id}"}` keeps `user_id` an integer); any other
+    /// string has its placeholders interpolated.
+    fn render_value(&self, value: &Value) -> Result<Value> {
+        Ok(match value {
+            Value::String(s) => match whole_placeholder(s) {
+                Some(name) => self.value(name)?,
+                None => Value::String(self.render(s)?),
+            },
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.render_value(item))
+                    .collect::<Result<_>>()?,
+            ),
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(k, v)| Ok((self.render(k)?, self.render_value(v)?)))
+                    .collect::<Result<_>>()?,
+            ),
+            other => other.clone(),
+        })
+    }
+}
+
+fn value_placeholders(value: &Value, names: &mut Vec<String>) {
+    match value {
+        Value::String(s) => collect_placeholders(s, names),
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| value_placeholders(item, names)),
+        Value::Object(map) => map.iter().for_each(|(k, v)| {
+            collect_placeholders(k, names);
+            value_placeholders(v, names);
+        }),
+        _ => {}
+    }
+}
+
+/// A reusable request description with `{name}` placeholders.
+///
+/// Placeholders may appear in the URL, query parameters, headers, cookies, a text
+/// `content` body, and anywhere inside `data`/`json` bodies. `render(**vars)` fills
+/// them in and returns a `Request` ready for `client.send()`.
+#[pyclass]
+pub struct RequestTemplate {
+    #[pyo3(get)]
+    method: String,
+    #[pyo3(get)]
+    url: String,
+    params: Option<IndexMapSSR>,
+    headers: Option<IndexMapSSR>,
+    cookies: Option<IndexMapSSR>,
+    content: Option<String>,
+    data: Option<Value>,
+    json: Option<Value>,
+    auth: Option<Auth>,
+    auth_bearer: Option<String>,
+    timeout: Option<f64>,
+}
+
+#[pymethods]
+impl RequestTemplate {
+    #[new]
+    #[pyo3(signature = (method, url, params=None, headers=None, cookies=None, content=None,
+        data=None, json=None, auth=None, auth_bearer=None, timeout=None))]
+    fn new(
+        method: String,
+        url: String,
+        params: Option<IndexMapSSR>,
+        headers: Option<IndexMapSSR>,
+        cookies: Option<IndexMapSSR>,
+        content: Option<String>,
+        data: Option<&Bound<'_, PyAny>>,
+        json: Option<&Bound<'_, PyAny>>,
+        auth: Option<Auth>,
+        auth_bearer: Option<String>,
+        timeout: Option<f64>,
+    ) -> PyResult<Self> {
+        let data: Option<Value> = data
+            .map(depythonize)
+            .transpose()
+            .map_err(|e| map_anyhow_error(anyhow::Error::new(e)))?;
+        let json: Option<Value> = json.map(|json| to_json(json, None)).transpose()?;
+        Ok(RequestTemplate {
+            method: method.to_uppercase(),
+            url,
+            params,
+            headers,
+            cookies,
+            content,
+            data,
+            json,
+            auth,
+            auth_bearer,
+            timeout,
+        })
+    }
+
+    /// Names of all placeholders used by the template, in order of first appearance.
+    #[getter]
+    fn placeholders(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        collect_placeholders(&self.url, &mut names);
+        for map in [&self.params, &self.headers, &self.cookies]
+            .into_iter()
+            .flatten()
+        {
+            for (k, v) in map {
+                collect_placeholders(k, &mut names);
+                collect_placeholders(v, &mut names);
+            }
+        }
+        if let Some(content) = &self.content {
+            collect_placeholders(content, &mut names);
+        }
+        for value in [&self.data, &self.json].into_iter().flatten() {
+            value_placeholders(value, &mut names);
+        }
+        names
+    }
+
+    /// Fill in the placeholders and return a `Request`.
+    ///
+    /// # Errors
+    ///
+    /// Raises `ValueError` if a placeholder has no matching variable or the template
+    /// contains an unmatched brace.
+    #[pyo3(signature = (**vars))]
+    fn render(&self, py: Python, vars: Option<&Bound<'_, PyDict>>) -> PyResult<Request> {
+        let empty = PyDict::new(py);
+        self.render_with(vars.unwrap_or(&empty))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<RequestTemplate [{} {}]>", self.method, self.url)
+    }
+}
+
+impl RequestTemplate {
+    pub fn render_with(&self, vars: &Bound<'_, PyDict>) -> PyResult<Request> {
+        let vars = Vars { vars };
+        let render = || -> Result<Request> {
+            Ok(Request {
+                method: self.method.clone(),
+                url: vars.render(&self.url)?,
+                params: vars.render_map(&self.params)?,
+                headers: vars.render_map(&self.headers)?,
+                removed_headers: Vec::new(),
+                lazy_headers: LazyHeaders::default(),
+                cookies: vars.render_map(&self.cookies)?,
+                content: self
+                    .content
+                    .as_ref()
+                    .map(|content| vars.render(content).map(Bytes::from))
+                    .transpose()?,
+                data: self
+                    .data
+                    .as_ref()
+                    .map(|data| vars.render_value(data))
+                    .transpose()?,
+                json: self
+                    .json
+                    .as_ref()
+                    .map(|json| vars.render_value(json))
+                    .transpose()?,
+                files: None,
+                auth: self.auth.clone(),
+                auth_bearer: self.auth_bearer.clone(),
+                timeout: self.timeout,
+            })
+        };
+        render().map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Result<String> {
+        match name {
+            "user_id" => Ok("42".to_string()),
+            "region" => Ok("eu".to_string()),
+            _ => bail!("Missing template variable '{}'", name),
+        }
+    }
+
+    #[test]
+    fn test_render_str_substitutes_placeholders() {
+        let out = render_str("https://{region}.example.com/users/{user_id}", &lookup).unwrap();
+        assert_eq!(out, "https://eu.example.com/users/42");
+    }
+
+    #[test]
+    fn test_render_str_escaped_braces() {
+        let out = render_str("{{literal}} {user_id}", &lookup).unwrap();
+        assert_eq!(out, "{literal} 42");
+    }
+
+    #[test]
+    fn test_render_str_missing_variable() {
+        assert!(render_str("/users/{missing}", &lookup).is_err());
+    }
+
+    #[test]
+    fn test_render_str_unmatched_braces() {
+        assert!(render_str("/users/{user_id", &lookup).is_err());
+        assert!(render_str("/users/}", &lookup).is_err());
+    }
+
+    #[test]
+    fn test_collect_placeholders() {
+        let mut names = Vec::new();
+        collect_placeholders("/{a}/{b}/{{c}}/{a}", &mut names);
+        assert_eq!(names, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    #[test]
+    fn test_whole_placeholder() {
+        assert_eq!(whole_placeholder("{user_id}"), Some("user_id"));
+        assert_eq!(whole_placeholder("id-{user_id}"), None);
+        assert_eq!(whole_placeholder("{a}{b}"), None);
+        assert_eq!(whole_placeholder("{}"), None);
+    }
+}
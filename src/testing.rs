@@ -0,0 +1,630 @@
+use std::convert::Infallible;
+use std::net::{SocketAddr, TcpListener as StdTcpListener};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use bytes::Bytes;
+use foldhash::fast::RandomState;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::service::service_fn;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
+use indexmap::IndexMap;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyString};
+use pythonize::depythonize;
+use reqwest::header::LOCATION;
+use reqwest::Method;
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::{HandshakeKind, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+
+type IndexMapSSR = IndexMap<String, String, RandomState>;
+
+/// Response headers given as a dict, or as a list of pairs to repeat a name (e.g. `Set-Cookie`).
+#[derive(FromPyObject)]
+enum HeaderList {
+    Map(IndexMapSSR),
+    Pairs(Vec<(String, String)>),
+}
+
+impl HeaderList {
+    fn into_pairs(self) -> Vec<(String, String)> {
+        match self {
+            HeaderList::Map(map) => map.into_iter().collect(),
+            HeaderList::Pairs(pairs) => pairs,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Action {
+    Respond {
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: Bytes,
+    },
+    /// Answer with a JSON description of the request.
+    Echo,
+}
+
+#[derive(Clone)]
+struct Route {
+    method: Option<Method>,
+    path: String,
+    delay: Option<Duration>,
+    action: Action,
+}
+
+/// A request received by the server.
+struct Recorded {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Bytes,
+    /// Whether the TLS session of the connection was resumed, None without TLS.
+    tls_resumed: Option<bool>,
+}
+
+#[derive(Default)]
+struct State {
+    routes: Mutex<Vec<Route>>,
+    requests: Mutex<Vec<Recorded>>,
+}
+
+impl State {
+    /// The most recently added route matching `method` and `path`.
+    fn find(&self, method: &Method, path: &str) -> Option<Route> {
+        let routes = self.routes.lock().unwrap_or_else(|e| e.into_inner());
+        routes
+            .iter()
+            .rev()
+            .find(|route| route.path == path && route.method.as_ref().is_none_or(|m| m == method))
+            .cloned()
+    }
+
+    async fn handle(
+        &self,
+        request: hyper::Request<Incoming>,
+        tls_resumed: Option<bool>,
+    ) -> hyper::Response<Full<Bytes>> {
+        let (parts, body) = request.into_parts();
+        let body = body
+            .collect()
+            .await
+            .map(|collected| collected.to_bytes())
+            .unwrap_or_default();
+        let path = parts
+            .uri
+            .path_and_query()
+            .map(|pq| pq.as_str().to_string())
+            .unwrap_or_else(|| "/".to_string());
+        let headers: Vec<(String, String)> = parts
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                (
+                    name.as_str().to_string(),
+                    String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                )
+            })
+            .collect();
+        let recorded = Recorded {
+            method: parts.method.to_string(),
+            path,
+            headers,
+            body,
+            tls_resumed,
+        };
+
+        let route = self.find(&parts.method, parts.uri.path());
+        if let Some(delay) = route.as_ref().and_then(|route| route.delay) {
+            tokio::time::sleep(delay).await;
+        }
+        let response = match route.map(|route| route.action) {
+            None => respond(404, &[], Bytes::from_static(b"Not Found")),
+            Some(Action::Respond {
+                status,
+                headers,
+                body,
+            }) => respond(status, &headers, body),
+            Some(Action::Echo) => {
+                let mut header_map = serde_json::Map::new();
+                for (name, value) in &recorded.headers {
+                    match header_map.get_mut(name) {
+                        Some(Value::String(existing)) => {
+                            existing.push_str(", ");
+                            existing.push_str(value);
+                        }
+                        _ => {
+                            header_map.insert(name.clone(), Value::String(value.clone()));
+                        }
+                    }
+                }
+                let echo = json!({
+                    "method": recorded.method,
+                    "path": recorded.path,
+                    "headers": header_map,
+                    "body": String::from_utf8_lossy(&recorded.body),
+                });
+                let headers = [("content-type".to_string(), "application/json".to_string())];
+                respond(200, &headers, Bytes::from(echo.to_string()))
+            }
+        };
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(recorded);
+        response
+    }
+}
+
+fn respond(status: u16, headers: &[(String, String)], body: Bytes) -> hyper::Response<Full<Bytes>> {
+    let mut builder = hyper::Response::builder().status(status);
+    for (name, value) in headers {
+        builder = builder.header(name, value);
+    }
+    builder.body(Full::new(body)).unwrap_or_else(|e| {
+        let mut response = hyper::Response::new(Full::new(Bytes::from(e.to_string())));
+        *response.status_mut() = hyper::StatusCode::INTERNAL_SERVER_ERROR;
+        response
+    })
+}
+
+/// Accept connections until `shutdown` fires, serving HTTP/1.1 and HTTP/2.
+async fn serve(
+    listener: StdTcpListener,
+    state: Arc<State>,
+    tls: Option<TlsAcceptor>,
+    mut shutdown: oneshot::Receiver<()>,
+) -> Result<()> {
+    let listener = TcpListener::from_std(listener)?;
+    loop {
+        let stream = tokio::select! {
+            _ = &mut shutdown => return Ok(()),
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(_) => continue,
+            },
+        };
+        let state = state.clone();
+        let tls = tls.clone();
+        tokio::spawn(async move {
+            let service = |tls_resumed: Option<bool>| {
+                service_fn(move |request| {
+                    let state = state.clone();
+                    async move { Ok::<_, Infallible>(state.handle(request, tls_resumed).await) }
+                })
+            };
+            let builder = auto::Builder::new(TokioExecutor::new());
+            match tls {
+                Some(acceptor) => {
+                    if let Ok(stream) = acceptor.accept(stream).await {
+                        let resumed =
+                            stream.get_ref().1.handshake_kind() == Some(HandshakeKind::Resumed);
+                        let _ = builder
+                            .serve_connection(TokioIo::new(stream), service(Some(resumed)))
+                            .await;
+                    }
+                }
+                None => {
+                    let _ = builder
+                        .serve_connection(TokioIo::new(stream), service(None))
+                        .await;
+                }
+            }
+        });
+    }
+}
+
+/// Build a rustls server config from PEM files, offering HTTP/2 and HTTP/1.1 via ALPN.
+fn tls_config(cert_file: &str, key_file: &str) -> Result<Arc<ServerConfig>> {
+    let cert_pem = std::fs::read(cert_file)
+        .with_context(|| format!("Failed to read TLS certificate {}", cert_file))?;
+    let certs: Vec<CertificateDer<'static>> = rustls_pemfile::certs(&mut cert_pem.as_slice())
+        .collect::<std::result::Result<_, _>>()
+        .context("Failed to parse TLS certificate")?;
+    if certs.is_empty() {
+        bail!("No certificates found in {}", cert_file);
+    }
+    let key_pem =
+        std::fs::read(key_file).with_context(|| format!("Failed to read TLS key {}", key_file))?;
+    let key: PrivateKeyDer<'static> = rustls_pemfile::private_key(&mut key_pem.as_slice())
+        .context("Failed to parse TLS key")?
+        .ok_or_else(|| anyhow!("No private key found in {}", key_file))?;
+    let mut config = ServerConfig::builder_with_provider(Arc::new(
+        tokio_rustls::rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()?
+    .with_no_client_auth()
+    .with_single_cert(certs, key)?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(Arc::new(config))
+}
+
+struct Running {
+    addr: SocketAddr,
+    shutdown: oneshot::Sender<()>,
+    thread: JoinHandle<()>,
+}
+
+/// A local HTTP server for tests, configured with canned routes.
+///
+/// The server runs on a background thread with its own Tokio runtime and records every
+/// request it receives. Routes can be added before or after `start()`; when several
+/// routes match, the most recently added one wins. Unmatched requests get a 404.
+#[pyclass(module = "httpr.testing")]
+pub struct LocalServer {
+    host: String,
+    port: u16,
+    tls: Option<Arc<ServerConfig>>,
+    state: Arc<State>,
+    running: Option<Running>,
+}
+
+impl LocalServer {
+    fn add(
+        &self,
+        path: String,
+        method: Option<&str>,
+        delay: Option<f64>,
+        action: Action,
+    ) -> PyResult<()> {
+        if !path.starts_with('/') {
+            return Err(PyValueError::new_err("path must start with '/'"));
+        }
+        let method = method
+            .map(|m| Method::from_bytes(m.to_ascii_uppercase().as_bytes()))
+            .transpose()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let delay = delay
+            .map(|seconds| {
+                Duration::try_from_secs_f64(seconds)
+                    .map_err(|_| PyValueError::new_err("delay must be a non-negative number"))
+            })
+            .transpose()?;
+        self.state
+            .routes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Route {
+                method,
+                path,
+                delay,
+                action,
+            });
+        Ok(())
+    }
+
+    fn addr(&self) -> PyResult<SocketAddr> {
+        self.running
+            .as_ref()
+            .map(|running| running.addr)
+            .ok_or_else(|| PyRuntimeError::new_err("LocalServer is not running; call start()"))
+    }
+}
+
+#[pymethods]
+impl LocalServer {
+    /// Create a server. It does not listen until `start()` (or `with`).
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - Address to bind. Default is `"127.0.0.1"`.
+    /// * `port` - Port to bind; 0 picks a free port. Default is 0.
+    /// * `tls_cert` - Path to a PEM certificate chain; serves HTTPS together with `tls_key`.
+    /// * `tls_key` - Path to the PEM private key for `tls_cert`.
+    #[new]
+    #[pyo3(signature = (host="127.0.0.1", port=0, tls_cert=None, tls_key=None))]
+    fn new(
+        host: &str,
+        port: u16,
+        tls_cert: Option<String>,
+        tls_key: Option<String>,
+    ) -> PyResult<Self> {
+        let tls = match (tls_cert, tls_key) {
+            (Some(cert), Some(key)) => Some(
+                tls_config(&cert, &key).map_err(|e| PyValueError::new_err(format!("{:#}", e)))?,
+            ),
+            (None, None) => None,
+            _ => {
+                return Err(PyValueError::new_err(
+                    "tls_cert and tls_key must be given together",
+                ))
+            }
+        };
+        Ok(LocalServer {
+            host: host.to_string(),
+            port,
+            tls,
+            state: Arc::default(),
+            running: None,
+        })
+    }
+
+    /// Answer `path` with a canned response.
+    ///
+    /// `body` may be `bytes` or `str`; `json` serializes a value and sets `Content-Type`.
+    /// `headers` is a dict, or a list of `(name, value)` pairs to repeat a header.
+    /// `method=None` matches any method. `delay` waits that many seconds before answering.
+    #[pyo3(signature = (path, *, method=None, status=200, body=None, json=None, headers=None, delay=None))]
+    fn route(
+        &self,
+        path: String,
+        method: Option<&str>,
+        status: u16,
+        body: Option<&Bound<'_, PyAny>>,
+        json: Option<&Bound<'_, PyAny>>,
+        headers: Option<HeaderList>,
+        delay: Option<f64>,
+    ) -> PyResult<()> {
+        let mut headers = headers.map(HeaderList::into_pairs).unwrap_or_default();
+        let body = match (body, json) {
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "Only one of body or json may be set.",
+                ))
+            }
+            (Some(body), None) => match body.cast::<PyString>() {
+                Ok(text) => Bytes::from(text.to_cow()?.into_owned()),
+                Err(_) => Bytes::from(body.cast::<PyBytes>()?.as_bytes().to_vec()),
+            },
+            (None, Some(value)) => {
+                let value: Value = depythonize(value)?;
+                if !headers
+                    .iter()
+                    .any(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+                {
+                    headers.push(("content-type".into(), "application/json".into()));
+                }
+                Bytes::from(value.to_string())
+            }
+            (None, None) => Bytes::new(),
+        };
+        self.add(
+            path,
+            method,
+            delay,
+            Action::Respond {
+                status,
+                headers,
+                body,
+            },
+        )
+    }
+
+    /// Redirect `path` to `location` with a 3xx `status`.
+    #[pyo3(signature = (path, location, *, status=302, method=None))]
+    fn redirect(
+        &self,
+        path: String,
+        location: String,
+        status: u16,
+        method: Option<&str>,
+    ) -> PyResult<()> {
+        if !(300..400).contains(&status) {
+            return Err(PyValueError::new_err("redirect status must be 3xx"));
+        }
+        let headers = vec![(LOCATION.as_str().to_string(), location)];
+        self.add(
+            path,
+            method,
+            None,
+            Action::Respond {
+                status,
+                headers,
+                body: Bytes::new(),
+            },
+        )
+    }
+
+    /// Chain of `hops` redirects: `path` -> `path/1` -> ... -> `path/{hops-1}` -> `target`.
+    #[pyo3(signature = (path, target, hops, *, status=302))]
+    fn redirect_chain(
+        &self,
+        path: String,
+        target: String,
+        hops: usize,
+        status: u16,
+    ) -> PyResult<()> {
+        if hops == 0 {
+            return Err(PyValueError::new_err("hops must be at least 1"));
+        }
+        let base = path.trim_end_matches('/').to_string();
+        let hop_path = |i: usize| {
+            if i == 0 {
+                path.clone()
+            } else {
+                format!("{}/{}", base, i)
+            }
+        };
+        for i in 0..hops {
+            let location = if i + 1 == hops {
+                target.clone()
+            } else {
+                hop_path(i + 1)
+            };
+            self.redirect(hop_path(i), location, status, None)?;
+        }
+        Ok(())
+    }
+
+    /// Answer `path` with a JSON description of the request: `method`, `path` (with the
+    /// query string), `headers` (lowercase names) and `body` (decoded as UTF-8).
+    #[pyo3(signature = (path, *, method=None, delay=None))]
+    fn echo(&self, path: String, method: Option<&str>, delay: Option<f64>) -> PyResult<()> {
+        self.add(path, method, delay, Action::Echo)
+    }
+
+    /// Start listening on a background thread. Does nothing if already running.
+    fn start(&mut self) -> PyResult<()> {
+        if self.running.is_some() {
+            return Ok(());
+        }
+        let listener = StdTcpListener::bind((self.host.as_str(), self.port)).map_err(|e| {
+            PyRuntimeError::new_err(format!("Failed to bind {}:{}: {}", self.host, self.port, e))
+        })?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let (shutdown, shutdown_rx) = oneshot::channel();
+        let state = self.state.clone();
+        let tls = self.tls.clone().map(TlsAcceptor::from);
+        let thread = std::thread::Builder::new()
+            .name("httpr-local-server".into())
+            .spawn(move || {
+                if let Err(e) = runtime.block_on(serve(listener, state, tls, shutdown_rx)) {
+                    tracing::error!("LocalServer stopped: {}", e);
+                }
+            })
+            .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        self.running = Some(Running {
+            addr,
+            shutdown,
+            thread,
+        });
+        Ok(())
+    }
+
+    /// Stop the server and close its connections. Does nothing if not running.
+    fn stop(&mut self, py: Python<'_>) {
+        if let Some(running) = self.running.take() {
+            let _ = running.shutdown.send(());
+            let _ = py.detach(|| running.thread.join());
+        }
+    }
+
+    fn __enter__(mut slf: PyRefMut<'_, Self>) -> PyResult<PyRefMut<'_, Self>> {
+        slf.start()?;
+        Ok(slf)
+    }
+
+    fn __exit__(
+        &mut self,
+        py: Python<'_>,
+        _exc_type: &Bound<'_, PyAny>,
+        _exc_value: &Bound<'_, PyAny>,
+        _traceback: &Bound<'_, PyAny>,
+    ) {
+        self.stop(py);
+    }
+
+    /// Base URL of the running server, e.g. `http://127.0.0.1:54321`.
+    #[getter]
+    fn url(&self) -> PyResult<String> {
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+        Ok(format!("{}://{}", scheme, self.addr()?))
+    }
+
+    /// Port the running server listens on.
+    #[getter]
+    fn port(&self) -> PyResult<u16> {
+        Ok(self.addr()?.port())
+    }
+
+    /// Requests received so far, oldest first, as dicts with `method`, `path` (with the
+    /// query string), `headers` (lowercase names), `body` (bytes) and `tls_resumed`
+    /// (whether the connection resumed a TLS session, None over plain HTTP).
+    #[getter]
+    fn requests<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let requests = self
+            .state
+            .requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        requests
+            .iter()
+            .map(|request| {
+                let dict = PyDict::new(py);
+                dict.set_item("method", &request.method)?;
+                dict.set_item("path", &request.path)?;
+                let headers = PyDict::new(py);
+                for (name, value) in &request.headers {
+                    match headers.get_item(name)? {
+                        Some(existing) => {
+                            headers.set_item(name, format!("{}, {}", existing, value))?
+                        }
+                        None => headers.set_item(name, value)?,
+                    }
+                }
+                dict.set_item("headers", headers)?;
+                dict.set_item("body", PyBytes::new(py, &request.body))?;
+                dict.set_item("tls_resumed", request.tls_resumed)?;
+                Ok(dict)
+            })
+            .collect()
+    }
+
+    /// Forget the recorded requests.
+    fn clear_requests(&self) {
+        self.state
+            .requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+impl Drop for LocalServer {
+    fn drop(&mut self) {
+        if let Some(running) = self.running.take() {
+            let _ = running.shutdown.send(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route(method: Option<Method>, path: &str, status: u16) -> Route {
+        Route {
+            method,
+            path: path.to_string(),
+            delay: None,
+            action: Action::Respond {
+                status,
+                headers: Vec::new(),
+                body: Bytes::new(),
+            },
+        }
+    }
+
+    fn status(route: Option<Route>) -> Option<u16> {
+        match route?.action {
+            Action::Respond { status, .. } => Some(status),
+            Action::Echo => None,
+        }
+    }
+
+    #[test]
+    fn test_find_route() {
+        let state = State::default();
+        state.routes.lock().unwrap().extend([
+            route(None, "/a", 200),
+            route(Some(Method::POST), "/a", 201),
+            route(None, "/b", 202),
+        ]);
+        assert_eq!(status(state.find(&Method::GET, "/a")), Some(200));
+        assert_eq!(status(state.find(&Method::POST, "/a")), Some(201));
+        assert_eq!(status(state.find(&Method::GET, "/b")), Some(202));
+        assert!(state.find(&Method::GET, "/c").is_none());
+
+        state.routes.lock().unwrap().push(route(None, "/a", 503));
+        assert_eq!(status(state.find(&Method::POST, "/a")), Some(503));
+    }
+}
@@ -0,0 +1,480 @@
+use std::error::Error;
+use std::io;
+use std::sync::Arc;
+
+use anyhow::{bail, Context, Result};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::client::{
+    ClientSessionMemoryCache, ClientSessionStore, Resumption, Tls12ClientSessionValue,
+    Tls12Resumption, Tls13ClientSessionValue, WebPkiServerVerifier,
+};
+use tokio_rustls::rustls::crypto::CryptoProvider;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{
+    self, CertificateError, ClientConfig, DigitallySignedStruct, NamedGroup, OtherError,
+    RootCertStore, SignatureScheme,
+};
+
+/// ALPN protocols hyper can speak, by their registered identifiers.
+const ALPN_PROTOCOLS: [&str; 2] = ["h2", "http/1.1"];
+
+/// Servers whose sessions are kept for resumption by default.
+const DEFAULT_SESSION_CACHE_SIZE: usize = 256;
+
+/// TLS 1.3 tickets rustls keeps per server. Its session cache is sized in sessions, this
+/// many to a server, and always has a server slot fewer than that size makes room for.
+const TICKETS_PER_SERVER: usize = 8;
+
+/// A failed TLS handshake, raised as `CertificateVerifyError` when the server
+/// certificate was rejected and as `SSLError` otherwise.
+#[derive(Debug, PartialEq)]
+pub struct TlsFailure {
+    pub certificate: bool,
+    /// Reason code, e.g. `"expired"`, `"hostname_mismatch"` or `"unknown_ca"`.
+    pub reason: &'static str,
+    pub message: String,
+}
+
+impl TlsFailure {
+    /// The failure behind `err`, one link of an error source chain, if it is a rustls
+    /// error. rustls errors reach reqwest wrapped in (nested) `io::Error`s, whose
+    /// `source()` skips the wrapped error, so those are unwrapped here.
+    pub fn from_error(mut err: &(dyn Error + 'static)) -> Option<Self> {
+        while let Some(inner) = err.downcast_ref::<io::Error>().and_then(io::Error::get_ref) {
+            err = inner;
+        }
+        err.downcast_ref::<rustls::Error>().map(Self::new)
+    }
+
+    /// The exception a `verify_hook` raised, if the handshake behind `err` failed on it.
+    pub fn hook_error<'a>(mut err: &'a (dyn Error + 'static)) -> Option<&'a PyErr> {
+        while let Some(inner) = err.downcast_ref::<io::Error>().and_then(io::Error::get_ref) {
+            err = inner;
+        }
+        match err.downcast_ref::<rustls::Error>()? {
+            rustls::Error::Other(OtherError(inner)) => inner.downcast_ref::<PyErr>(),
+            _ => None,
+        }
+    }
+
+    fn new(err: &rustls::Error) -> Self {
+        let (certificate, reason) = match err {
+            rustls::Error::InvalidCertificate(cert_error) => (
+                true,
+                match cert_error {
+                    CertificateError::Expired | CertificateError::ExpiredContext { .. } => {
+                        "expired"
+                    }
+                    CertificateError::NotValidYet | CertificateError::NotValidYetContext { .. } => {
+                        "not_yet_valid"
+                    }
+                    CertificateError::NotValidForName
+                    | CertificateError::NotValidForNameContext { .. } => "hostname_mismatch",
+                    CertificateError::UnknownIssuer => "unknown_ca",
+                    CertificateError::Revoked => "revoked",
+                    CertificateError::BadSignature => "bad_signature",
+                    CertificateError::InvalidPurpose
+                    | CertificateError::InvalidPurposeContext { .. } => "invalid_purpose",
+                    CertificateError::ApplicationVerificationFailure => "rejected_by_hook",
+                    _ => "other",
+                },
+            ),
+            rustls::Error::NoCertificatesPresented => (true, "no_certificate"),
+            rustls::Error::AlertReceived(_) => (false, "alert_received"),
+            rustls::Error::PeerIncompatible(_) => (false, "peer_incompatible"),
+            rustls::Error::PeerMisbehaved(_)
+            | rustls::Error::InvalidMessage(_)
+            | rustls::Error::InappropriateMessage { .. }
+            | rustls::Error::InappropriateHandshakeMessage { .. } => (false, "protocol_error"),
+            _ => (false, "other"),
+        };
+        TlsFailure {
+            certificate,
+            reason,
+            message: format!("TLS handshake failed: {}", err),
+        }
+    }
+}
+
+/// TLS settings reqwest has no options for.
+///
+/// A client with any of them set connects with a rustls config built by `client_config`
+/// (reqwest's `use_preconfigured_tls`) instead of reqwest's own, which then also takes
+/// over the root certificates, `verify` and the client identity.
+#[derive(Clone, Debug, Default)]
+pub struct TlsOptions {
+    /// `alpn_protocols=`: the protocols offered in the handshake, in order of preference.
+    alpn_protocols: Option<Vec<Vec<u8>>>,
+    /// Offer only `h2` by default, as reqwest does for `http2_only`.
+    http2_only: bool,
+    /// `tls_session_tickets=`: resume sessions from tickets, rather than TLS 1.2 session
+    /// IDs only.
+    session_tickets: bool,
+    /// `tls_session_cache_size=`: servers whose sessions are kept for resumption; 0
+    /// disables resumption.
+    session_cache_size: Option<usize>,
+    /// `verify_hook=`: overrules the certificate verification.
+    verify_hook: Option<VerifyHook>,
+}
+
+impl TlsOptions {
+    pub fn from_args(
+        alpn_protocols: Option<Vec<String>>,
+        http2_only: bool,
+        session_tickets: bool,
+        session_cache_size: Option<usize>,
+        verify_hook: Option<VerifyHook>,
+    ) -> Result<Self> {
+        if let Some(protocols) = &alpn_protocols {
+            for protocol in protocols {
+                if !ALPN_PROTOCOLS.contains(&protocol.as_str()) {
+                    bail!(
+                        "Unsupported ALPN protocol '{}': expected 'h2' or 'http/1.1'",
+                        protocol
+                    );
+                }
+            }
+            if http2_only && !protocols.iter().any(|protocol| protocol == "h2") {
+                bail!("alpn_protocols must include 'h2' with http2_only=True");
+            }
+        }
+        Ok(TlsOptions {
+            alpn_protocols: alpn_protocols
+                .map(|protocols| protocols.into_iter().map(String::into_bytes).collect()),
+            http2_only,
+            session_tickets,
+            session_cache_size,
+            verify_hook,
+        })
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.alpn_protocols.is_none()
+            && self.session_tickets
+            && self.session_cache_size.is_none()
+            && self.verify_hook.is_none()
+    }
+
+    /// The rustls config for these options: the built-in roots and `ca_certs` with
+    /// `verify`, no certificate checks without, either overruled by the `verify_hook`, and
+    /// the client identity in `identity_pem` (certificate chain and private key), if any.
+    pub fn client_config(
+        &self,
+        verify: bool,
+        ca_certs: Vec<CertificateDer<'static>>,
+        identity_pem: Option<&[u8]>,
+    ) -> Result<ClientConfig> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let builder = ClientConfig::builder_with_provider(Arc::clone(&provider))
+            .with_safe_default_protocol_versions()?;
+        let verifier: Arc<dyn ServerCertVerifier> = if verify {
+            let mut roots = RootCertStore::empty();
+            roots.add_parsable_certificates(
+                webpki_root_certs::TLS_SERVER_ROOT_CERTS.iter().cloned(),
+            );
+            for cert in ca_certs {
+                roots.add(cert).context("Invalid CA certificate")?;
+            }
+            WebPkiServerVerifier::builder_with_provider(Arc::new(roots), Arc::clone(&provider))
+                .build()?
+        } else {
+            Arc::new(NoVerification(provider))
+        };
+        let verifier = match &self.verify_hook {
+            Some(hook) => Arc::new(HookVerifier {
+                inner: verifier,
+                hook: hook.clone(),
+            }),
+            None => verifier,
+        };
+        let builder = builder
+            .dangerous()
+            .with_custom_certificate_verifier(verifier);
+        let mut config = match identity_pem {
+            Some(pem) => {
+                let certs = rustls_pemfile::certs(&mut &*pem)
+                    .collect::<Result<Vec<_>, _>>()
+                    .context("Invalid client certificate")?;
+                let key = rustls_pemfile::private_key(&mut &*pem)
+                    .context("Invalid client private key")?
+                    .context("No private key found in the client PEM")?;
+                builder.with_client_auth_cert(certs, key)?
+            }
+            None => builder.with_no_client_auth(),
+        };
+        config.alpn_protocols = self.alpn_protocols.clone().unwrap_or_else(|| {
+            let protocols = if self.http2_only {
+                &ALPN_PROTOCOLS[..1]
+            } else {
+                &ALPN_PROTOCOLS[..]
+            };
+            protocols
+                .iter()
+                .map(|protocol| protocol.as_bytes().to_vec())
+                .collect()
+        });
+        config.resumption = self.resumption();
+        Ok(config)
+    }
+
+    fn resumption(&self) -> Resumption {
+        let servers = self
+            .session_cache_size
+            .unwrap_or(DEFAULT_SESSION_CACHE_SIZE);
+        let size = servers.saturating_add(1).saturating_mul(TICKETS_PER_SERVER);
+        if servers == 0 {
+            Resumption::disabled()
+        } else if self.session_tickets {
+            Resumption::in_memory_sessions(size)
+        } else {
+            Resumption::store(Arc::new(NoTicketStore(ClientSessionMemoryCache::new(size))))
+                .tls12_resumption(Tls12Resumption::SessionIdOnly)
+        }
+    }
+}
+
+/// Session store for `tls_session_tickets=False`: drops TLS 1.3 tickets, the only way to
+/// resume those sessions, while TLS 1.2 sessions still resume by session ID.
+#[derive(Debug)]
+struct NoTicketStore(ClientSessionMemoryCache);
+
+impl ClientSessionStore for NoTicketStore {
+    fn set_kx_hint(&self, server_name: ServerName<'static>, group: NamedGroup) {
+        self.0.set_kx_hint(server_name, group);
+    }
+
+    fn kx_hint(&self, server_name: &ServerName<'_>) -> Option<NamedGroup> {
+        self.0.kx_hint(server_name)
+    }
+
+    fn set_tls12_session(&self, server_name: ServerName<'static>, value: Tls12ClientSessionValue) {
+        self.0.set_tls12_session(server_name, value);
+    }
+
+    fn tls12_session(&self, server_name: &ServerName<'_>) -> Option<Tls12ClientSessionValue> {
+        self.0.tls12_session(server_name)
+    }
+
+    fn remove_tls12_session(&self, server_name: &ServerName<'static>) {
+        self.0.remove_tls12_session(server_name);
+    }
+
+    fn insert_tls13_ticket(
+        &self,
+        _server_name: ServerName<'static>,
+        _value: Tls13ClientSessionValue,
+    ) {
+    }
+
+    fn take_tls13_ticket(
+        &self,
+        _server_name: &ServerName<'static>,
+    ) -> Option<Tls13ClientSessionValue> {
+        None
+    }
+}
+
+/// Python callable given as `verify_hook=`, deciding on the certificate of every TLS
+/// handshake. It receives `(hostname, chain, error)`, where `chain` is the DER of the
+/// server certificate followed by the intermediates, and `error` the reason code of the
+/// default verification failing (e.g. `"expired"`) or None, and returns whether to accept.
+#[derive(Clone, Debug)]
+pub struct VerifyHook(Arc<Py<PyAny>>);
+
+impl VerifyHook {
+    pub fn new(callable: Py<PyAny>) -> PyResult<Self> {
+        Python::attach(|py| {
+            if !callable.bind(py).is_callable() {
+                return Err(PyValueError::new_err("verify_hook must be callable"));
+            }
+            Ok(VerifyHook(Arc::new(callable)))
+        })
+    }
+
+    fn call(
+        &self,
+        server_name: &ServerName<'_>,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        error: Option<&'static str>,
+    ) -> PyResult<bool> {
+        Python::attach(|py| {
+            let chain: Vec<Bound<'_, PyBytes>> = std::iter::once(end_entity)
+                .chain(intermediates)
+                .map(|cert| PyBytes::new(py, cert))
+                .collect();
+            self.0
+                .bind(py)
+                .call1((server_name.to_str(), chain, error))?
+                .is_truthy()
+        })
+    }
+}
+
+/// Runs the default verification, then lets the `verify_hook` accept or reject the
+/// certificate either way. Signatures are still checked by the default verifier.
+#[derive(Debug)]
+struct HookVerifier {
+    inner: Arc<dyn ServerCertVerifier>,
+    hook: VerifyHook,
+}
+
+impl ServerCertVerifier for HookVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let result = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        );
+        let error = result.as_ref().err().map(|err| TlsFailure::new(err).reason);
+        match self
+            .hook
+            .call(server_name, end_entity, intermediates, error)
+        {
+            Ok(true) => Ok(ServerCertVerified::assertion()),
+            Ok(false) => result.and(Err(rustls::Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            ))),
+            Err(err) => Err(rustls::Error::Other(OtherError(Arc::new(err)))),
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// Accepts any server certificate, for `verify=False`.
+#[derive(Debug)]
+struct NoVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_rustls::rustls::AlertDescription;
+
+    #[test]
+    fn test_from_error() {
+        let wrapped = io::Error::other(io::Error::new(
+            io::ErrorKind::InvalidData,
+            rustls::Error::InvalidCertificate(CertificateError::UnknownIssuer),
+        ));
+        let failure = TlsFailure::from_error(&wrapped).unwrap();
+        assert!(failure.certificate);
+        assert_eq!(failure.reason, "unknown_ca");
+        assert!(failure
+            .message
+            .starts_with("TLS handshake failed: invalid peer certificate"));
+
+        let alert = rustls::Error::AlertReceived(AlertDescription::HandshakeFailure);
+        let failure = TlsFailure::from_error(&alert).unwrap();
+        assert_eq!(
+            (failure.certificate, failure.reason),
+            (false, "alert_received")
+        );
+
+        assert!(TlsFailure::from_error(&io::Error::other("reset")).is_none());
+    }
+
+    #[test]
+    fn test_alpn_protocols() {
+        let options =
+            TlsOptions::from_args(Some(vec!["http/1.1".into()]), false, true, None, None).unwrap();
+        assert!(!options.is_default());
+        let config = options.client_config(true, Vec::new(), None).unwrap();
+        assert_eq!(config.alpn_protocols, vec![b"http/1.1".to_vec()]);
+
+        let config = TlsOptions::default()
+            .client_config(false, Vec::new(), None)
+            .unwrap();
+        assert_eq!(
+            config.alpn_protocols,
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        );
+
+        assert!(TlsOptions::from_args(Some(vec!["h3".into()]), false, true, None, None).is_err());
+        assert!(
+            TlsOptions::from_args(Some(vec!["http/1.1".into()]), true, true, None, None).is_err()
+        );
+        assert!(TlsOptions::from_args(Some(Vec::new()), false, true, None, None).is_ok());
+    }
+
+    #[test]
+    fn test_session_options() {
+        assert!(TlsOptions::from_args(None, false, true, None, None)
+            .unwrap()
+            .is_default());
+        for (tickets, size) in [(false, None), (true, Some(0)), (false, Some(16))] {
+            let options = TlsOptions::from_args(None, false, tickets, size, None).unwrap();
+            assert!(!options.is_default());
+            assert!(options.client_config(true, Vec::new(), None).is_ok());
+        }
+    }
+}
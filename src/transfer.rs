@@ -0,0 +1,129 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use futures_util::StreamExt;
+use http_body_util::BodyExt;
+use reqwest::Body;
+
+/// Body bytes moved by one exchange, or by every exchange of a client.
+#[derive(Default)]
+pub struct ByteCounts {
+    /// Request body bytes sent.
+    sent: AtomicU64,
+    /// Response body bytes as received, before content decoding.
+    received: AtomicU64,
+    /// Response body bytes after content decoding.
+    decompressed: AtomicU64,
+}
+
+impl ByteCounts {
+    pub fn sent(&self) -> u64 {
+        self.sent.load(Ordering::Relaxed)
+    }
+
+    pub fn received(&self) -> u64 {
+        self.received.load(Ordering::Relaxed)
+    }
+
+    pub fn decompressed(&self) -> u64 {
+        self.decompressed.load(Ordering::Relaxed)
+    }
+}
+
+/// The `ByteCounts` of an exchange, attached to its response by `RequestSpec::send`.
+#[derive(Clone)]
+pub struct Transfer(pub Arc<ByteCounts>);
+
+/// Counts body bytes as they stream, into the counts of the current exchange and the
+/// running totals of the client.
+#[derive(Clone, Default)]
+pub struct Meter {
+    exchange: Arc<ByteCounts>,
+    totals: Arc<ByteCounts>,
+}
+
+impl Meter {
+    pub fn new(totals: Arc<ByteCounts>) -> Self {
+        Meter {
+            exchange: Arc::default(),
+            totals,
+        }
+    }
+
+    /// A meter for a new exchange, adding to the same client totals.
+    pub fn fresh(&self) -> Self {
+        Meter::new(Arc::clone(&self.totals))
+    }
+
+    /// The running totals of the client.
+    pub fn totals(&self) -> &ByteCounts {
+        &self.totals
+    }
+
+    pub fn transfer(&self) -> Transfer {
+        Transfer(Arc::clone(&self.exchange))
+    }
+
+    /// Count a request body. Bodies held in memory are counted at once, so that they
+    /// keep their `Content-Length`; streamed bodies as they are sent.
+    pub fn count_sent(&self, body: Body) -> Body {
+        if let Some(bytes) = body.as_bytes() {
+            self.add(|counts| &counts.sent, bytes.len());
+            return body;
+        }
+        self.count(body, |counts| &counts.sent)
+    }
+
+    /// Count a response body before content decoding.
+    pub fn count_received(&self, body: Body) -> Body {
+        self.count(body, |counts| &counts.received)
+    }
+
+    /// Count a response body after content decoding.
+    pub fn count_decompressed(&self, body: Body) -> Body {
+        self.count(body, |counts| &counts.decompressed)
+    }
+
+    fn add(&self, field: fn(&ByteCounts) -> &AtomicU64, len: usize) {
+        field(&self.exchange).fetch_add(len as u64, Ordering::Relaxed);
+        field(&self.totals).fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    fn count(&self, body: Body, field: fn(&ByteCounts) -> &AtomicU64) -> Body {
+        let meter = self.clone();
+        Body::wrap_stream(body.into_data_stream().inspect(move |chunk| {
+            if let Ok(chunk) = chunk {
+                meter.add(field, chunk.len());
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_meter_counts_exchange_and_totals() {
+        let client = Meter::default();
+        let meter = client.fresh();
+        let body = meter.count_sent(Body::from("hello"));
+        assert_eq!(body.as_bytes(), Some(&b"hello"[..]));
+        let body = meter.count_received(Body::from(vec![0u8; 100]));
+        body.collect().await.unwrap();
+
+        let exchange = meter.transfer().0;
+        assert_eq!((exchange.sent(), exchange.received()), (5, 100));
+        let other = client.fresh();
+        other
+            .count_received(Body::from("abc"))
+            .collect()
+            .await
+            .unwrap();
+        assert_eq!(other.transfer().0.received(), 3);
+        assert_eq!(
+            (client.totals().sent(), client.totals().received()),
+            (5, 103)
+        );
+    }
+}
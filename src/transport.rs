@@ -0,0 +1,251 @@
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use bytes::Bytes;
+use futures_util::stream::{try_unfold, Stream, StreamExt};
+use http_body_util::BodyDataStream;
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyIterator, PyMapping};
+use reqwest::cookie::CookieStore;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, COOKIE, SET_COOKIE};
+use reqwest::{Body, ResponseBuilderExt};
+use url::Url;
+
+use crate::exceptions::map_reqwest_error;
+use crate::request::{chunk_bytes, content_bytes};
+use crate::traits::HeadersTraits;
+use crate::RUNTIME;
+
+type IndexMapSSR = indexmap::IndexMap<String, String, foldhash::fast::RandomState>;
+type BodyStream = Pin<Box<dyn Stream<Item = Result<Bytes, reqwest::Error>> + Send>>;
+
+/// Sends the requests of a client through a Python callable instead of its own
+/// connections, given as `Client(transport=PythonTransport(handler))`.
+///
+/// The handler is called with a `TransportRequest` on a worker thread and returns a
+/// `(status, headers, body)` tuple. Everything around the send still happens in httpr:
+/// redirects, cookies, auth, decompression, `response.attempts` and so on.
+#[pyclass(frozen, module = "httpr")]
+pub struct PythonTransport {
+    handler: Arc<Py<PyAny>>,
+}
+
+#[pymethods]
+impl PythonTransport {
+    /// Create a transport calling `handler` for every request.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - A callable taking a `TransportRequest` and returning `(status, headers, body)`:
+    ///         an `int` status, headers as a mapping or a sequence of `(name, value)` pairs (which
+    ///         may repeat a name), and the body as `bytes`, `str`, a buffer, None, or an iterable
+    ///         of `bytes` chunks, which is streamed.
+    #[new]
+    fn new(py: Python, handler: Py<PyAny>) -> PyResult<Self> {
+        if !handler.bind(py).is_callable() {
+            return Err(PyValueError::new_err("transport handler must be callable"));
+        }
+        Ok(PythonTransport {
+            handler: Arc::new(handler),
+        })
+    }
+
+    /// The callable requests are sent through.
+    #[getter]
+    fn handler(&self, py: Python) -> Py<PyAny> {
+        self.handler.clone_ref(py)
+    }
+
+    fn __repr__(&self, py: Python) -> String {
+        format!("<PythonTransport {}>", self.handler.bind(py))
+    }
+}
+
+/// A request as handed to the handler of a `PythonTransport`, with its headers final:
+/// client defaults, auth, cookies, `Accept-Encoding` and any signature are applied.
+///
+/// The body is read with `read()` or by iterating over its chunks, either of which
+/// consumes it.
+#[pyclass(frozen, module = "httpr")]
+pub struct TransportRequest {
+    #[pyo3(get)]
+    method: String,
+    #[pyo3(get)]
+    url: String,
+    #[pyo3(get)]
+    headers: IndexMapSSR,
+    /// Total timeout of the request in seconds, or None; the handler should honour it.
+    #[pyo3(get)]
+    timeout: Option<f64>,
+    body: Mutex<Option<BodyStream>>,
+}
+
+impl TransportRequest {
+    /// The next chunk of the body, waiting for it with the GIL released.
+    fn next_chunk(&self, py: Python) -> PyResult<Option<Bytes>> {
+        let mut body = self.body.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(stream) = body.as_mut() else {
+            return Ok(None);
+        };
+        match py.detach(|| RUNTIME.handle().block_on(stream.next())) {
+            Some(Ok(chunk)) => Ok(Some(chunk)),
+            Some(Err(err)) => Err(map_reqwest_error(err)),
+            None => {
+                *body = None;
+                Ok(None)
+            }
+        }
+    }
+}
+
+#[pymethods]
+impl TransportRequest {
+    /// The rest of the body; empty when there is none or it has been read.
+    fn read<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        let mut content = Vec::new();
+        while let Some(chunk) = self.next_chunk(py)? {
+            content.extend_from_slice(&chunk);
+        }
+        Ok(PyBytes::new(py, &content))
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyBytes>>> {
+        Ok(self.next_chunk(py)?.map(|chunk| PyBytes::new(py, &chunk)))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<TransportRequest {} {}>", self.method, self.url)
+    }
+}
+
+/// The `PythonTransport` of a client, with the cookie store reqwest would apply when
+/// sending through its own connections.
+#[derive(Clone)]
+pub struct Transport {
+    handler: Arc<Py<PyAny>>,
+    cookies: Arc<dyn CookieStore>,
+}
+
+impl Transport {
+    pub fn new(transport: &PythonTransport, cookies: Arc<dyn CookieStore>) -> Self {
+        Transport {
+            handler: Arc::clone(&transport.handler),
+            cookies,
+        }
+    }
+
+    /// Send `request` through the handler, which runs on the blocking thread pool so that
+    /// it may itself block, and build the response it returns. Exceptions it raises are
+    /// re-raised to the caller as is.
+    pub async fn send(&self, mut request: reqwest::Request) -> Result<reqwest::Response> {
+        let url = request.url().clone();
+        if !request.headers().contains_key(COOKIE) {
+            if let Some(value) = self.cookies.cookies(&url) {
+                request.headers_mut().insert(COOKIE, value);
+            }
+        }
+        let body = request
+            .body_mut()
+            .take()
+            .map(|body| Box::pin(BodyDataStream::new(body)) as BodyStream);
+        let transport_request = TransportRequest {
+            method: request.method().to_string(),
+            url: url.to_string(),
+            headers: request.headers().to_indexmap(),
+            timeout: request.timeout().map(|timeout| timeout.as_secs_f64()),
+            body: Mutex::new(body),
+        };
+        let handler = Arc::clone(&self.handler);
+        let response_url = url.clone();
+        let resp = tokio::task::spawn_blocking(move || {
+            Python::attach(|py| {
+                let returned = handler.bind(py).call1((transport_request,))?;
+                build_response(&returned, response_url)
+            })
+        })
+        .await??;
+        self.cookies
+            .set_cookies(&mut resp.headers().get_all(SET_COOKIE).iter(), &url);
+        Ok(resp)
+    }
+}
+
+/// The response for a `(status, headers, body)` tuple returned by a handler.
+fn build_response(returned: &Bound<'_, PyAny>, url: Url) -> PyResult<reqwest::Response> {
+    let (status, headers, body) = returned
+        .extract::<(u16, Bound<'_, PyAny>, Bound<'_, PyAny>)>()
+        .map_err(|_| {
+            PyTypeError::new_err("transport handler must return a (status, headers, body) tuple")
+        })?;
+    let mut builder = http::Response::builder().status(status).url(url);
+    if let Some(header_map) = builder.headers_mut() {
+        *header_map = header_pairs(&headers)?;
+    }
+    let body = if body.is_none() {
+        Body::from(Bytes::new())
+    } else {
+        match content_bytes(&body, None) {
+            Ok(content) => Body::from(content),
+            Err(_) => {
+                let chunks = body.try_iter().map_err(|_| {
+                    PyTypeError::new_err(
+                        "transport response body must be bytes, str, a buffer, None or an iterable of bytes",
+                    )
+                })?;
+                stream_body(Arc::new(chunks.unbind()))
+            }
+        }
+    };
+    let resp = builder
+        .body(body)
+        .map_err(|e| PyValueError::new_err(format!("Invalid transport response: {}", e)))?;
+    Ok(reqwest::Response::from(resp))
+}
+
+/// Response headers given as a mapping or a sequence of `(name, value)` pairs.
+fn header_pairs(headers: &Bound<'_, PyAny>) -> PyResult<HeaderMap> {
+    let items = match headers.cast::<PyMapping>() {
+        Ok(mapping) => mapping.items()?.into_any(),
+        Err(_) => headers.clone(),
+    };
+    let mut header_map = HeaderMap::new();
+    for item in items.try_iter()? {
+        let (name, value) = item?.extract::<(String, String)>().map_err(|_| {
+            PyTypeError::new_err(
+                "transport response headers must be a mapping or a sequence of (name, value) pairs",
+            )
+        })?;
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| PyValueError::new_err(format!("Invalid header name '{}': {}", name, e)))?;
+        let header_value = HeaderValue::from_str(&value).map_err(|e| {
+            PyValueError::new_err(format!("Invalid value for header '{}': {}", name, e))
+        })?;
+        header_map.append(header_name, header_value);
+    }
+    Ok(header_map)
+}
+
+/// A body pulling its chunks from a Python iterator, one call to `next()` on the blocking
+/// thread pool per chunk, as they are read.
+pub fn stream_body(chunks: Arc<Py<PyIterator>>) -> Body {
+    Body::wrap_stream(try_unfold(chunks, |chunks| async move {
+        let next = tokio::task::spawn_blocking({
+            let chunks = Arc::clone(&chunks);
+            move || {
+                Python::attach(|py| match chunks.bind(py).clone().next() {
+                    Some(chunk) => chunk_bytes(&chunk?).map(Some),
+                    None => Ok(None),
+                })
+            }
+        })
+        .await
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))??;
+        Ok::<_, PyErr>(next.map(|chunk| (chunk, chunks)))
+    }))
+}
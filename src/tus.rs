@@ -0,0 +1,326 @@
+use std::io::SeekFrom;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use bytes::Bytes;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use reqwest::{
+    header::{HeaderMap, LOCATION},
+    Method, StatusCode,
+};
+use ring::digest;
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt},
+};
+
+use crate::request::RequestSpec;
+use crate::upload::{is_retryable, RETRY_DELAY};
+
+/// The protocol version sent as `Tus-Resumable` with every request.
+const TUS_VERSION: &str = "1.0.0";
+
+/// Status of a PATCH whose `Upload-Checksum` did not match the received chunk.
+const CHECKSUM_MISMATCH: u16 = 460;
+
+/// Algorithm of the `Upload-Checksum` sent with each chunk (tus checksum extension).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TusChecksum {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl TusChecksum {
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "sha1" => Ok(TusChecksum::Sha1),
+            "sha256" => Ok(TusChecksum::Sha256),
+            "sha512" => Ok(TusChecksum::Sha512),
+            _ => bail!(
+                "checksum must be 'sha1', 'sha256' or 'sha512', got {:?}",
+                name
+            ),
+        }
+    }
+
+    /// `Upload-Checksum` value for `chunk`: the algorithm and the base64 digest.
+    fn header(self, chunk: &[u8]) -> String {
+        let (name, algorithm) = match self {
+            TusChecksum::Sha1 => ("sha1", &digest::SHA1_FOR_LEGACY_USE_ONLY),
+            TusChecksum::Sha256 => ("sha256", &digest::SHA256),
+            TusChecksum::Sha512 => ("sha512", &digest::SHA512),
+        };
+        format!(
+            "{} {}",
+            name,
+            STANDARD.encode(digest::digest(algorithm, chunk))
+        )
+    }
+}
+
+/// Chunk size, checksum and retries of `client.upload_tus()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TusOptions {
+    chunk_size: u64,
+    checksum: Option<TusChecksum>,
+    retries: u32,
+}
+
+impl TusOptions {
+    pub fn from_args(chunk_size: u64, checksum: Option<&str>, retries: u32) -> Result<Self> {
+        if chunk_size == 0 {
+            bail!("chunk_size must be at least 1 byte");
+        }
+        Ok(TusOptions {
+            chunk_size,
+            checksum: checksum.map(TusChecksum::from_name).transpose()?,
+            retries,
+        })
+    }
+}
+
+/// `Upload-Metadata` value: comma-separated `key base64(value)` pairs.
+pub fn encode_metadata(metadata: &[(String, String)]) -> Result<String> {
+    let mut pairs = Vec::with_capacity(metadata.len());
+    for (key, value) in metadata {
+        if key.is_empty() || key.contains([' ', ',']) || !key.is_ascii() {
+            bail!(
+                "Invalid metadata key {:?}: must be ASCII without spaces or commas",
+                key
+            );
+        }
+        if value.is_empty() {
+            pairs.push(key.clone());
+        } else {
+            pairs.push(format!("{} {}", key, STANDARD.encode(value)));
+        }
+    }
+    Ok(pairs.join(","))
+}
+
+/// A finished tus upload.
+pub struct TusUpload {
+    pub upload_url: String,
+    pub size: u64,
+    /// Offset the upload continued from: 0 for a new upload.
+    pub resumed_from: u64,
+}
+
+impl TusUpload {
+    pub fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        dict.set_item("upload_url", &self.upload_url)?;
+        dict.set_item("size", self.size)?;
+        dict.set_item("resumed_from", self.resumed_from)?;
+        Ok(dict)
+    }
+}
+
+/// Upload the file at `path` with the tus resumable upload protocol.
+///
+/// Unless `upload_url` names an upload created earlier, one is created with a `POST` to
+/// the URL of `spec`. The offset of an existing upload is queried with `HEAD`, and the
+/// rest of the file is sent in `PATCH` requests of `chunk_size` bytes. A chunk that fails
+/// with a retryable error or a checksum mismatch is followed by an offset query, and the
+/// upload continues from wherever the server says it is.
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_tus(
+    spec: RequestSpec,
+    client: reqwest::Client,
+    client_headers: HeaderMap,
+    path: &Path,
+    metadata: Option<String>,
+    upload_url: Option<String>,
+    options: TusOptions,
+    on_progress: Option<Py<PyAny>>,
+) -> Result<TusUpload> {
+    let size = tokio::fs::metadata(path)
+        .await
+        .with_context(|| format!("Failed to read file {}", path.display()))?
+        .len();
+    let send = |spec: RequestSpec| spec.send(client.clone(), client_headers.clone());
+
+    let (upload_url, mut offset) = match upload_url {
+        Some(upload_url) => {
+            let offset =
+                query_offset(send(tus_spec(&spec, Method::HEAD, &upload_url)).await, size)?;
+            (upload_url, offset)
+        }
+        None => {
+            let mut create = tus_spec(&spec, Method::POST, &spec.url);
+            create.params = spec.params.clone();
+            let headers = create.headers.get_or_insert_with(Default::default);
+            headers.insert("Upload-Length".to_string(), size.to_string());
+            if let Some(metadata) = metadata {
+                headers.insert("Upload-Metadata".to_string(), metadata);
+            }
+            create.content = Some(Bytes::new());
+            let resp = send(create).await?.error_for_status()?;
+            let location = resp
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .context("Creating the upload returned no Location")?;
+            let upload_url = resp.url().join(location)?;
+            (upload_url.into(), 0)
+        }
+    };
+    let resumed_from = offset;
+    if let Some(callback) = &on_progress {
+        report(callback, &upload_url, offset, size);
+    }
+
+    let mut file = File::open(path)
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut attempt = 0;
+    while offset < size {
+        let end = (offset + options.chunk_size).min(size);
+        let mut chunk = vec![0; (end - offset) as usize];
+        file.seek(SeekFrom::Start(offset)).await?;
+        file.read_exact(&mut chunk).await?;
+
+        let mut patch = tus_spec(&spec, Method::PATCH, &upload_url);
+        let headers = patch.headers.get_or_insert_with(Default::default);
+        headers.insert(
+            "Content-Type".to_string(),
+            "application/offset+octet-stream".to_string(),
+        );
+        headers.insert("Upload-Offset".to_string(), offset.to_string());
+        if let Some(checksum) = options.checksum {
+            headers.insert("Upload-Checksum".to_string(), checksum.header(&chunk));
+        }
+        patch.content = Some(Bytes::from(chunk));
+
+        match send(patch).await.and_then(upload_offset) {
+            Ok(next) if next > offset && next <= size => {
+                offset = next;
+                attempt = 0;
+                if let Some(callback) = &on_progress {
+                    report(callback, &upload_url, offset, size);
+                }
+            }
+            Ok(next) => bail!(
+                "Server reported Upload-Offset {} after a chunk sent at offset {}",
+                next,
+                offset
+            ),
+            Err(err) if attempt < options.retries && is_resendable(&err) => {
+                tracing::debug!("resuming upload at {}: {}", upload_url, err);
+                tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt)).await;
+                attempt += 1;
+                offset =
+                    query_offset(send(tus_spec(&spec, Method::HEAD, &upload_url)).await, size)?;
+            }
+            Err(err) => return Err(err.context(format!("Uploading at offset {} failed", offset))),
+        }
+    }
+    Ok(TusUpload {
+        upload_url,
+        size,
+        resumed_from,
+    })
+}
+
+/// `spec` as a `method` request to `url` with `Tus-Resumable` and no body. Query
+/// parameters are only sent with the creation request; the upload URL is used as given.
+fn tus_spec(spec: &RequestSpec, method: Method, url: &str) -> RequestSpec {
+    let mut spec = spec.clone();
+    spec.method = method;
+    spec.url = url.to_string();
+    spec.params = None;
+    spec.content = None;
+    spec.headers
+        .get_or_insert_with(Default::default)
+        .insert("Tus-Resumable".to_string(), TUS_VERSION.to_string());
+    spec
+}
+
+/// The `Upload-Offset` of a successful `HEAD` or `PATCH` response.
+fn upload_offset(resp: reqwest::Response) -> Result<u64> {
+    let resp = resp.error_for_status()?;
+    resp.headers()
+        .get("upload-offset")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .context("Response has no valid Upload-Offset")
+}
+
+/// The `Upload-Offset` of an offset query, which must be within the `size` byte file.
+fn query_offset(resp: Result<reqwest::Response>, size: u64) -> Result<u64> {
+    let offset = upload_offset(resp?)?;
+    if offset > size {
+        bail!(
+            "Upload-Offset {} is beyond the file size of {} bytes",
+            offset,
+            size
+        );
+    }
+    Ok(offset)
+}
+
+/// Whether the upload may continue after a chunk failed with `err`: the retryable
+/// failures of multipart uploads, an offset conflict and a checksum mismatch.
+fn is_resendable(err: &anyhow::Error) -> bool {
+    let status = err
+        .downcast_ref::<reqwest::Error>()
+        .and_then(|err| err.status());
+    matches!(status, Some(StatusCode::CONFLICT))
+        || status.is_some_and(|status| status.as_u16() == CHECKSUM_MISMATCH)
+        || is_retryable(err)
+}
+
+/// Call `on_progress` once the upload exists and after each chunk. Exceptions it raises
+/// are reported as unraisable instead of failing the upload.
+fn report(callback: &Py<PyAny>, upload_url: &str, sent: u64, total: u64) {
+    Python::attach(|py| {
+        let dict = PyDict::new(py);
+        let result = dict
+            .set_item("upload_url", upload_url)
+            .and_then(|_| dict.set_item("sent", sent))
+            .and_then(|_| dict.set_item("total", total))
+            .and_then(|_| callback.bind(py).call1((dict,)));
+        if let Err(err) = result {
+            err.write_unraisable(py, Some(callback.bind(py)));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_metadata() {
+        let metadata = [
+            (
+                "filename".to_string(),
+                "world_domination_plan.pdf".to_string(),
+            ),
+            ("is_confidential".to_string(), String::new()),
+        ];
+        assert_eq!(
+            encode_metadata(&metadata).unwrap(),
+            "filename d29ybGRfZG9taW5hdGlvbl9wbGFuLnBkZg==,is_confidential"
+        );
+        assert!(encode_metadata(&[("a b".to_string(), "x".to_string())]).is_err());
+        assert!(encode_metadata(&[(String::new(), "x".to_string())]).is_err());
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert_eq!(
+            TusChecksum::Sha1.header(b"hello"),
+            "sha1 qvTGHdzF6KLavt4PO0gs2a6pQ00="
+        );
+        assert_eq!(
+            TusChecksum::from_name("SHA256").unwrap(),
+            TusChecksum::Sha256
+        );
+        assert!(TusChecksum::from_name("md5").is_err());
+        assert!(TusOptions::from_args(0, None, 3).is_err());
+    }
+}
@@ -0,0 +1,305 @@
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use reqwest::dns::{Name, Resolve};
+use reqwest::StatusCode;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadHalf, WriteHalf};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::block_on;
+use crate::exceptions::{
+    map_anyhow_error, ConnectError, ConnectTimeout, ProxyError, ReadError, ReadTimeout,
+    RemoteProtocolError, StreamClosed, WriteError,
+};
+use crate::request::{content_bytes, RequestSpec};
+use crate::response::CaseInsensitiveHeaderMap;
+use crate::traits::HeadersTraits;
+
+/// Connections a `Stream` can wrap: upgraded HTTP connections and plain TCP.
+trait Io: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Io for T {}
+
+/// A raw bidirectional byte stream: a connection taken over from HTTP by an `Upgrade:`
+/// handshake (`client.upgrade()`) or a tunnel (`client.connect_tunnel()`), for protocols
+/// httpr does not speak itself.
+///
+/// Reading and writing are independent, so one thread may wait in `read()` while another
+/// writes. `close()` also ends a `read()` waiting in another thread.
+///
+/// # Example
+///
+/// ```python
+/// with client.connect_tunnel("mail.example.com", 25) as stream:
+///     print(stream.read())
+///     stream.write(b"EHLO client.example.com\r\n")
+/// ```
+#[pyclass(frozen, module = "httpr")]
+pub struct Stream {
+    reader: Mutex<Option<ReadHalf<Box<dyn Io>>>>,
+    writer: Mutex<Option<WriteHalf<Box<dyn Io>>>>,
+    closed: CancellationToken,
+    /// Status of the response that handed over the connection, None for a direct tunnel.
+    #[pyo3(get)]
+    status_code: Option<u16>,
+    /// Headers of the response that handed over the connection.
+    #[pyo3(get)]
+    headers: CaseInsensitiveHeaderMap,
+}
+
+impl Stream {
+    fn new(
+        io: impl Io + 'static,
+        status_code: Option<u16>,
+        headers: CaseInsensitiveHeaderMap,
+    ) -> Self {
+        let (reader, writer) = tokio::io::split(Box::new(io) as Box<dyn Io>);
+        Stream {
+            reader: Mutex::new(Some(reader)),
+            writer: Mutex::new(Some(writer)),
+            closed: CancellationToken::new(),
+            status_code,
+            headers,
+        }
+    }
+
+    /// Take over the connection of `resp`, which agreed to an upgrade or opened a tunnel.
+    async fn take_over(resp: reqwest::Response) -> Result<Self> {
+        let status_code = resp.status().as_u16();
+        let headers = CaseInsensitiveHeaderMap::from_indexmap(resp.headers().to_indexmap());
+        let upgraded = resp.upgrade().await?;
+        Ok(Stream::new(upgraded, Some(status_code), headers))
+    }
+}
+
+#[pymethods]
+impl Stream {
+    /// Read up to `max_bytes`, waiting until some arrive. Returns `b""` once the peer has
+    /// closed its side.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_bytes` - The most bytes to return. Default is 65536.
+    /// * `timeout` - Seconds to wait before raising `ReadTimeout`. Default is None (no limit).
+    #[pyo3(signature = (max_bytes=65536, timeout=None))]
+    fn read<'py>(
+        &self,
+        py: Python<'py>,
+        max_bytes: usize,
+        timeout: Option<f64>,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let data = block_on(py, || async {
+            let mut reader = self.reader.lock().await;
+            let Some(reader) = reader.as_mut() else {
+                bail!(StreamClosed::new_err("Stream has been closed"));
+            };
+            let mut buf = vec![0; max_bytes];
+            let read = reader.read(&mut buf);
+            let n = tokio::select! {
+                n = read_within(read, timeout) => n?,
+                _ = self.closed.cancelled() => {
+                    bail!(StreamClosed::new_err("Stream has been closed"))
+                }
+            };
+            buf.truncate(n);
+            Ok(buf)
+        })?
+        .map_err(map_anyhow_error)?;
+        Ok(PyBytes::new(py, &data))
+    }
+
+    /// Write all of `data` (`bytes`, `str` or a buffer) and flush it.
+    fn write(&self, py: Python, data: &Bound<'_, PyAny>) -> PyResult<()> {
+        let data = content_bytes(data, None)?;
+        block_on(py, || async {
+            let mut writer = self.writer.lock().await;
+            let Some(writer) = writer.as_mut() else {
+                bail!(StreamClosed::new_err("Stream has been closed"));
+            };
+            let written = async {
+                writer.write_all(&data).await?;
+                writer.flush().await
+            };
+            written
+                .await
+                .map_err(|e| WriteError::new_err(format!("Error writing to stream: {}", e)))?;
+            Ok(())
+        })?
+        .map_err(map_anyhow_error)
+    }
+
+    /// Close the stream: shut down the sending side, which the peer sees as the end of the
+    /// data, and release the connection. Reads waiting in other threads raise `StreamClosed`.
+    fn close(&self, py: Python) -> PyResult<()> {
+        self.closed.cancel();
+        block_on(py, || async {
+            if let Some(mut writer) = self.writer.lock().await.take() {
+                // The peer may already be gone, which is no reason to fail closing
+                let _ = writer.shutdown().await;
+            }
+            self.reader.lock().await.take();
+        })
+    }
+
+    /// Whether `close()` has been called.
+    #[getter]
+    fn is_closed(&self) -> bool {
+        self.closed.is_cancelled()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __exit__(
+        &self,
+        py: Python,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<()> {
+        self.close(py)
+    }
+
+    fn __repr__(&self) -> String {
+        match self.status_code {
+            Some(status_code) => format!("<Stream [{}]>", status_code),
+            None => "<Stream>".to_string(),
+        }
+    }
+}
+
+/// `read`, failing with `ReadError`, or with `ReadTimeout` after `timeout` seconds.
+async fn read_within(
+    read: impl std::future::Future<Output = std::io::Result<usize>>,
+    timeout: Option<f64>,
+) -> Result<usize> {
+    let result = match timeout {
+        Some(seconds) => match tokio::time::timeout(Duration::from_secs_f64(seconds), read).await {
+            Ok(result) => result,
+            Err(_) => bail!(ReadTimeout::new_err("Timed out reading from stream")),
+        },
+        None => read.await,
+    };
+    Ok(result.map_err(|e| ReadError::new_err(format!("Error reading from stream: {}", e)))?)
+}
+
+/// Send `spec`, a request with `Upgrade: <protocol>`, and take over its connection once
+/// the server agrees with `101 Switching Protocols`.
+pub async fn upgrade(
+    spec: RequestSpec,
+    client: reqwest::Client,
+    client_headers: reqwest::header::HeaderMap,
+    protocol: &str,
+) -> Result<Stream> {
+    let resp = spec.send(client, client_headers).await?;
+    if resp.status() != StatusCode::SWITCHING_PROTOCOLS {
+        bail!(RemoteProtocolError::new_err(format!(
+            "Server did not switch to protocol '{}': {}",
+            protocol,
+            resp.status()
+        )));
+    }
+    Stream::take_over(resp).await
+}
+
+/// Send `spec`, a `CONNECT` request going to the client proxy, and take over the
+/// connection once the proxy has opened the tunnel.
+pub async fn connect_via_proxy(
+    spec: RequestSpec,
+    client: reqwest::Client,
+    client_headers: reqwest::header::HeaderMap,
+    target: &str,
+) -> Result<Stream> {
+    let resp = spec.send(client, client_headers).await?;
+    if !resp.status().is_success() {
+        bail!(ProxyError::new_err(format!(
+            "Proxy refused to open a tunnel to {}: {}",
+            target,
+            resp.status()
+        )));
+    }
+    Stream::take_over(resp).await
+}
+
+/// Open a TCP connection to `host:port`, looking `host` up with `resolver` unless it is an
+/// IP address, within `timeout` seconds if given.
+pub async fn connect_direct(
+    host: &str,
+    port: u16,
+    resolver: Arc<dyn Resolve>,
+    timeout: Option<f64>,
+) -> Result<Stream> {
+    let connect = connect_tcp(host, port, resolver);
+    let tcp = match timeout {
+        Some(seconds) => tokio::time::timeout(Duration::from_secs_f64(seconds), connect)
+            .await
+            .map_err(|_| {
+                ConnectTimeout::new_err(format!("Connect timed out: {}:{}", host, port))
+            })??,
+        None => connect.await?,
+    };
+    tcp.set_nodelay(true)?;
+    let headers = CaseInsensitiveHeaderMap::from_indexmap(Default::default());
+    Ok(Stream::new(tcp, None, headers))
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A failed lookup, with the resolver error as its source so that `map_anyhow_error` finds
+/// the `Refused` or `DnsFailure` in it.
+#[derive(Debug)]
+struct ResolveError(BoxError);
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for ResolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&*self.0)
+    }
+}
+
+/// Connect to the addresses of `host` in turn, returning the first connection made.
+async fn connect_tcp(host: &str, port: u16, resolver: Arc<dyn Resolve>) -> Result<TcpStream> {
+    let addrs: Vec<SocketAddr> = match host.parse::<IpAddr>() {
+        Ok(ip) => vec![SocketAddr::new(ip, port)],
+        Err(_) => {
+            let name = Name::from_str(host)?;
+            resolver
+                .resolve(name)
+                .await
+                .map_err(ResolveError)?
+                .map(|addr| SocketAddr::new(addr.ip(), port))
+                .collect()
+        }
+    };
+    let mut last_error = None;
+    for addr in addrs {
+        match TcpStream::connect(addr).await {
+            Ok(tcp) => return Ok(tcp),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    match last_error {
+        Some(e) => bail!(ConnectError::new_err(format!(
+            "Failed to connect to {}:{}: {}",
+            host, port, e
+        ))),
+        None => bail!(ConnectError::new_err(format!(
+            "No addresses to connect to for {}",
+            host
+        ))),
+    }
+}
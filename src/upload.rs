@@ -0,0 +1,567 @@
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use bytes::Bytes;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use reqwest::{
+    header::{HeaderMap, ETAG},
+    Method, StatusCode,
+};
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt},
+    task::JoinSet,
+};
+use url::{form_urlencoded, Url};
+
+use crate::block_on;
+use crate::exceptions::map_anyhow_error;
+use crate::request::RequestSpec;
+
+/// Delay before the first retry of a failed part, doubled for each further attempt.
+pub const RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Part size, concurrency and retries of a `MultipartUploader`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UploadOptions {
+    part_size: u64,
+    max_concurrency: usize,
+    part_retries: u32,
+}
+
+impl UploadOptions {
+    pub fn from_args(part_size: u64, max_concurrency: usize, part_retries: u32) -> Result<Self> {
+        if part_size == 0 {
+            bail!("part_size must be at least 1 byte");
+        }
+        if max_concurrency == 0 {
+            bail!("max_concurrency must be at least 1");
+        }
+        Ok(UploadOptions {
+            part_size,
+            max_concurrency,
+            part_retries,
+        })
+    }
+
+    /// Inclusive-exclusive byte ranges of the parts of a `size` byte file. An empty file
+    /// is sent as one empty part.
+    fn ranges(&self, size: u64) -> Vec<(u64, u64)> {
+        let parts = size.div_ceil(self.part_size).max(1);
+        (0..parts)
+            .map(|i| (i * self.part_size, ((i + 1) * self.part_size).min(size)))
+            .collect()
+    }
+}
+
+/// How the URL of each part is made: a template with `{upload_id}` (URL-encoded) and
+/// `{part_number}` placeholders, or a callable taking both.
+pub enum PartUrl {
+    Template(String),
+    Callable(Py<PyAny>),
+}
+
+impl<'a, 'py> FromPyObject<'a, 'py> for PartUrl {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        if let Ok(template) = obj.extract::<String>() {
+            return Ok(PartUrl::Template(template));
+        }
+        if obj.is_callable() {
+            return Ok(PartUrl::Callable(obj.to_owned().unbind()));
+        }
+        Err(PyValueError::new_err(
+            "part_url must be a URL template or a callable",
+        ))
+    }
+}
+
+/// One uploaded part, as passed to `complete`.
+#[derive(Clone, Debug, PartialEq)]
+struct UploadedPart {
+    part_number: usize,
+    etag: Option<String>,
+    size: u64,
+}
+
+/// Uploads files in parts through an initiate / upload part / complete protocol, as
+/// returned by `client.multipart_uploader()`.
+///
+/// Each step is either the S3 multipart upload API of the object at `url` (also spoken
+/// by Google Cloud Storage's XML API and most S3-compatible stores) or a Python callable.
+/// Parts are read from the file and sent as `PUT` requests concurrently on the runtime;
+/// a part that fails with a connection error, a timeout or a 408, 429 or 5xx response is
+/// retried on its own.
+#[pyclass(module = "httpr")]
+pub struct MultipartUploader {
+    spec: RequestSpec,
+    client: reqwest::Client,
+    client_headers: HeaderMap,
+    url: Option<Url>,
+    initiate: Option<Py<PyAny>>,
+    part_url: Option<PartUrl>,
+    complete: Option<Py<PyAny>>,
+    abort: Option<Py<PyAny>>,
+    options: UploadOptions,
+    on_progress: Option<Py<PyAny>>,
+}
+
+impl MultipartUploader {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        py: Python,
+        spec: RequestSpec,
+        client: reqwest::Client,
+        client_headers: HeaderMap,
+        url: Option<&str>,
+        initiate: Option<Py<PyAny>>,
+        part_url: Option<PartUrl>,
+        complete: Option<Py<PyAny>>,
+        abort: Option<Py<PyAny>>,
+        options: UploadOptions,
+        on_progress: Option<Py<PyAny>>,
+    ) -> PyResult<Self> {
+        let url = url
+            .map(Url::parse)
+            .transpose()
+            .map_err(|e| PyValueError::new_err(format!("Invalid url: {}", e)))?;
+        if url.is_none() && (initiate.is_none() || part_url.is_none() || complete.is_none()) {
+            return Err(PyValueError::new_err(
+                "Without url, initiate, part_url and complete must all be given",
+            ));
+        }
+        for (name, callback) in [
+            ("initiate", &initiate),
+            ("complete", &complete),
+            ("abort", &abort),
+            ("on_progress", &on_progress),
+        ] {
+            if callback
+                .as_ref()
+                .is_some_and(|callback| !callback.bind(py).is_callable())
+            {
+                return Err(PyValueError::new_err(format!("{} must be callable", name)));
+            }
+        }
+        Ok(MultipartUploader {
+            spec,
+            client,
+            client_headers,
+            url,
+            initiate,
+            part_url,
+            complete,
+            abort,
+            options,
+            on_progress,
+        })
+    }
+
+    /// A request for `url` with the uploader's headers and the client defaults.
+    fn request(&self, method: Method, url: String, content: Option<Bytes>) -> RequestSpec {
+        let mut spec = self.spec.clone();
+        spec.method = method;
+        spec.url = url;
+        spec.content = content;
+        spec
+    }
+
+    /// `url` with `pairs` appended to its query; `None` values make key-only entries.
+    fn s3_url(&self, pairs: &[(&str, Option<&str>)]) -> String {
+        let mut url = self.url.clone().expect("url is set without callbacks");
+        {
+            let mut query = url.query_pairs_mut();
+            for (key, value) in pairs {
+                match value {
+                    Some(value) => query.append_pair(key, value),
+                    None => query.append_key_only(key),
+                };
+            }
+        }
+        url.into()
+    }
+
+    fn start(&self, py: Python) -> PyResult<String> {
+        if let Some(initiate) = &self.initiate {
+            return initiate.call0(py)?.extract(py);
+        }
+        let spec = self.request(Method::POST, self.s3_url(&[("uploads", None)]), None);
+        let (client, client_headers) = (self.client.clone(), self.client_headers.clone());
+        let future = async move {
+            let resp = spec
+                .send(client, client_headers)
+                .await?
+                .error_for_status()?;
+            s3_upload_id(&resp.bytes().await?)
+        };
+        block_on(py, || future)?.map_err(map_anyhow_error)
+    }
+
+    fn part_urls(&self, py: Python, upload_id: &str, parts: usize) -> PyResult<Vec<String>> {
+        (1..=parts)
+            .map(|part_number| match &self.part_url {
+                Some(PartUrl::Template(template)) => Ok(template
+                    .replace(
+                        "{upload_id}",
+                        &form_urlencoded::byte_serialize(upload_id.as_bytes()).collect::<String>(),
+                    )
+                    .replace("{part_number}", &part_number.to_string())),
+                Some(PartUrl::Callable(callable)) => {
+                    callable.call1(py, (upload_id, part_number))?.extract(py)
+                }
+                None => Ok(self.s3_url(&[
+                    ("partNumber", Some(&part_number.to_string())),
+                    ("uploadId", Some(upload_id)),
+                ])),
+            })
+            .collect()
+    }
+
+    fn finish(&self, py: Python, upload_id: &str, parts: &[UploadedPart]) -> PyResult<Py<PyAny>> {
+        if let Some(complete) = &self.complete {
+            return complete.call1(py, (upload_id, parts_to_py(py, parts)?));
+        }
+        let spec = self.request(
+            Method::POST,
+            self.s3_url(&[("uploadId", Some(upload_id))]),
+            Some(Bytes::from(s3_complete_body(parts))),
+        );
+        let (client, client_headers) = (self.client.clone(), self.client_headers.clone());
+        let future = async move {
+            let resp = spec
+                .send(client, client_headers)
+                .await?
+                .error_for_status()?;
+            // S3 may report a failed completion in the body of a 200 response
+            let body = resp.bytes().await?;
+            if let Some(message) = s3_error(&body) {
+                bail!("Completing the upload failed: {}", message);
+            }
+            Ok(())
+        };
+        block_on(py, || future)?.map_err(map_anyhow_error)?;
+        Ok(py.None())
+    }
+
+    /// Abandon the upload after a failure, so that the server can discard its parts.
+    /// Errors are reported as unraisable: the original failure is the one raised.
+    fn cancel(&self, py: Python, upload_id: &str) {
+        let result = match &self.abort {
+            Some(abort) => abort.call1(py, (upload_id,)).map(drop),
+            None if self.initiate.is_none() => {
+                let spec = self.request(
+                    Method::DELETE,
+                    self.s3_url(&[("uploadId", Some(upload_id))]),
+                    None,
+                );
+                let (client, client_headers) = (self.client.clone(), self.client_headers.clone());
+                let future = async move { spec.send(client, client_headers).await.map(drop) };
+                block_on(py, || future).and_then(|result| result.map_err(map_anyhow_error))
+            }
+            None => Ok(()),
+        };
+        if let Err(err) = result {
+            err.write_unraisable(py, self.abort.as_ref().map(|abort| abort.bind(py)));
+        }
+    }
+}
+
+#[pymethods]
+impl MultipartUploader {
+    /// Upload the file at `path` and return a dict with its `upload_id`, `size`, the
+    /// uploaded `parts` and the `result` returned by `complete` (None for the S3 API).
+    ///
+    /// If a part still fails after its retries, or completing fails, the upload is
+    /// aborted and the error raised.
+    fn upload<'py>(&self, py: Python<'py>, path: PathBuf) -> PyResult<Bound<'py, PyDict>> {
+        let size = std::fs::metadata(&path)
+            .with_context(|| format!("Failed to read file {}", path.display()))
+            .map_err(map_anyhow_error)?
+            .len();
+        let ranges = self.options.ranges(size);
+        let upload_id = self.start(py)?;
+        let result = self
+            .part_urls(py, &upload_id, ranges.len())
+            .and_then(|urls| {
+                let future = upload_parts(self, &path, urls, ranges, size);
+                block_on(py, || future)?.map_err(map_anyhow_error)
+            })
+            .and_then(|parts| {
+                let result = self.finish(py, &upload_id, &parts)?;
+                Ok((parts, result))
+            });
+        let (parts, result) = match result {
+            Ok(done) => done,
+            Err(err) => {
+                self.cancel(py, &upload_id);
+                return Err(err);
+            }
+        };
+        let dict = PyDict::new(py);
+        dict.set_item("upload_id", upload_id)?;
+        dict.set_item("size", size)?;
+        dict.set_item("parts", parts_to_py(py, &parts)?)?;
+        dict.set_item("result", result)?;
+        Ok(dict)
+    }
+}
+
+/// Send every part of the file at `path`, at most `max_concurrency` at a time, returning
+/// them in part order.
+async fn upload_parts(
+    uploader: &MultipartUploader,
+    path: &Path,
+    urls: Vec<String>,
+    ranges: Vec<(u64, u64)>,
+    size: u64,
+) -> Result<Vec<UploadedPart>> {
+    let total_parts = ranges.len();
+    let mut pending = urls.into_iter().zip(ranges).enumerate();
+    let mut tasks = JoinSet::new();
+    let mut parts = Vec::with_capacity(total_parts);
+    let mut sent = 0;
+    loop {
+        while tasks.len() < uploader.options.max_concurrency {
+            let Some((index, (url, range))) = pending.next() else {
+                break;
+            };
+            let spec = uploader.request(Method::PUT, url, None);
+            tasks.spawn(upload_part(
+                spec,
+                uploader.client.clone(),
+                uploader.client_headers.clone(),
+                path.to_path_buf(),
+                index + 1,
+                range,
+                uploader.options.part_retries,
+            ));
+        }
+        let Some(joined) = tasks.join_next().await else {
+            break;
+        };
+        let part = joined??;
+        sent += part.size;
+        if let Some(callback) = &uploader.on_progress {
+            report(callback, &part, parts.len() + 1, total_parts, sent, size);
+        }
+        parts.push(part);
+    }
+    parts.sort_by_key(|part| part.part_number);
+    Ok(parts)
+}
+
+/// Read one part of the file and `PUT` it, retrying retryable failures.
+async fn upload_part(
+    spec: RequestSpec,
+    client: reqwest::Client,
+    client_headers: HeaderMap,
+    path: PathBuf,
+    part_number: usize,
+    (start, end): (u64, u64),
+    retries: u32,
+) -> Result<UploadedPart> {
+    let mut file = File::open(&path)
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.seek(SeekFrom::Start(start)).await?;
+    let mut content = vec![0; (end - start) as usize];
+    file.read_exact(&mut content).await?;
+    let content = Bytes::from(content);
+    drop(file);
+
+    let mut attempt = 0;
+    loop {
+        let mut spec = spec.clone();
+        spec.content = Some(content.clone());
+        let result = async {
+            let resp = spec
+                .send(client.clone(), client_headers.clone())
+                .await?
+                .error_for_status()?;
+            Ok(resp
+                .headers()
+                .get(ETAG)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string))
+        }
+        .await;
+        match result {
+            Ok(etag) => {
+                return Ok(UploadedPart {
+                    part_number,
+                    etag,
+                    size: end - start,
+                })
+            }
+            Err(err) if attempt < retries && is_retryable(&err) => {
+                tracing::debug!("retrying part {}: {}", part_number, err);
+                tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt)).await;
+                attempt += 1;
+            }
+            Err(err) => return Err(err.context(format!("Uploading part {} failed", part_number))),
+        }
+    }
+}
+
+/// Whether a part that failed with `err` may succeed when sent again.
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    let Some(err) = err.downcast_ref::<reqwest::Error>() else {
+        return false;
+    };
+    match err.status() {
+        Some(status) => {
+            status.is_server_error()
+                || status == StatusCode::REQUEST_TIMEOUT
+                || status == StatusCode::TOO_MANY_REQUESTS
+        }
+        None => err.is_timeout() || err.is_connect() || err.is_request() || err.is_body(),
+    }
+}
+
+/// Call `on_progress` after a part is done. Exceptions it raises are reported as
+/// unraisable instead of failing the upload.
+fn report(
+    callback: &Py<PyAny>,
+    part: &UploadedPart,
+    completed: usize,
+    parts: usize,
+    sent: u64,
+    total: u64,
+) {
+    Python::attach(|py| {
+        let dict = PyDict::new(py);
+        let result = dict
+            .set_item("part_number", part.part_number)
+            .and_then(|_| dict.set_item("completed", completed))
+            .and_then(|_| dict.set_item("parts", parts))
+            .and_then(|_| dict.set_item("sent", sent))
+            .and_then(|_| dict.set_item("total", total))
+            .and_then(|_| callback.bind(py).call1((dict,)));
+        if let Err(err) = result {
+            err.write_unraisable(py, Some(callback.bind(py)));
+        }
+    });
+}
+
+fn parts_to_py<'py>(py: Python<'py>, parts: &[UploadedPart]) -> PyResult<Bound<'py, PyList>> {
+    let list = PyList::empty(py);
+    for part in parts {
+        let dict = PyDict::new(py);
+        dict.set_item("part_number", part.part_number)?;
+        dict.set_item("etag", &part.etag)?;
+        dict.set_item("size", part.size)?;
+        list.append(dict)?;
+    }
+    Ok(list)
+}
+
+/// `UploadId` of an S3 `InitiateMultipartUploadResult`.
+fn s3_upload_id(body: &[u8]) -> Result<String> {
+    let text = std::str::from_utf8(body)?;
+    let document = roxmltree::Document::parse(text)?;
+    document
+        .descendants()
+        .find(|node| node.tag_name().name() == "UploadId")
+        .and_then(|node| node.text())
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .context("Initiating the upload returned no UploadId")
+}
+
+/// The `Message` (or `Code`) of an S3 `<Error>` document.
+fn s3_error(body: &[u8]) -> Option<String> {
+    let document = roxmltree::Document::parse(std::str::from_utf8(body).ok()?).ok()?;
+    let root = document.root_element();
+    if root.tag_name().name() != "Error" {
+        return None;
+    }
+    let field = |name: &str| {
+        root.children()
+            .find(|node| node.tag_name().name() == name)
+            .and_then(|node| node.text())
+    };
+    Some(
+        field("Message")
+            .or_else(|| field("Code"))
+            .unwrap_or("unknown error")
+            .to_string(),
+    )
+}
+
+/// `CompleteMultipartUpload` document listing `parts`.
+fn s3_complete_body(parts: &[UploadedPart]) -> String {
+    let mut body = String::from("<CompleteMultipartUpload>");
+    for part in parts {
+        body.push_str(&format!(
+            "<Part><PartNumber>{}</PartNumber>",
+            part.part_number
+        ));
+        if let Some(etag) = &part.etag {
+            body.push_str(&format!("<ETag>{}</ETag>", xml_escape(etag)));
+        }
+        body.push_str("</Part>");
+    }
+    body.push_str("</CompleteMultipartUpload>");
+    body
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranges() {
+        let options = UploadOptions::from_args(10, 4, 3).unwrap();
+        assert_eq!(options.ranges(25), vec![(0, 10), (10, 20), (20, 25)]);
+        assert_eq!(options.ranges(20), vec![(0, 10), (10, 20)]);
+        assert_eq!(options.ranges(0), vec![(0, 0)]);
+        assert!(UploadOptions::from_args(0, 4, 3).is_err());
+        assert!(UploadOptions::from_args(10, 0, 3).is_err());
+    }
+
+    #[test]
+    fn test_s3_documents() {
+        let initiated = br#"<?xml version="1.0" encoding="UTF-8"?>
+<InitiateMultipartUploadResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Bucket>bucket</Bucket><Key>big.iso</Key><UploadId>VXBsb2FkIElE</UploadId>
+</InitiateMultipartUploadResult>"#;
+        assert_eq!(s3_upload_id(initiated).unwrap(), "VXBsb2FkIElE");
+        assert!(s3_upload_id(b"<Result/>").is_err());
+
+        let parts = [
+            UploadedPart {
+                part_number: 1,
+                etag: Some("\"a1\"".to_string()),
+                size: 10,
+            },
+            UploadedPart {
+                part_number: 2,
+                etag: None,
+                size: 5,
+            },
+        ];
+        assert_eq!(
+            s3_complete_body(&parts),
+            "<CompleteMultipartUpload><Part><PartNumber>1</PartNumber><ETag>&quot;a1&quot;</ETag></Part>\
+             <Part><PartNumber>2</PartNumber></Part></CompleteMultipartUpload>"
+        );
+
+        assert_eq!(
+            s3_error(b"<Error><Code>InternalError</Code><Message>Try again</Message></Error>"),
+            Some("Try again".to_string())
+        );
+        assert_eq!(s3_error(b"<CompleteMultipartUploadResult/>"), None);
+    }
+}
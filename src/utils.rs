@@ -1,13 +1,17 @@
 use std::cmp::min;
 
-use reqwest::Certificate;
 use std::{env, fs};
+use tokio_rustls::rustls::pki_types::CertificateDer;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use url::Url;
 
-/// Load CA certificates from a file specified by the environment variable `HTTPR_CA_BUNDLE`.
-pub fn load_ca_certs() -> Result<Vec<Certificate>> {
-    let ca_bundle_path = env::var("HTTPR_CA_BUNDLE").ok();
+/// Load CA certificates from `ca_cert_file`, or else from the file specified by the
+/// environment variable `HTTPR_CA_BUNDLE`.
+pub fn load_ca_certs(ca_cert_file: Option<&str>) -> Result<Vec<CertificateDer<'static>>> {
+    let ca_bundle_path = ca_cert_file
+        .map(str::to_owned)
+        .or_else(|| env::var("HTTPR_CA_BUNDLE").ok());
 
     match ca_bundle_path {
         Some(path) => {
@@ -25,7 +29,7 @@ pub fn load_ca_certs() -> Result<Vec<Certificate>> {
     }
 }
 
-fn read_pem_certificates(path: &str) -> Result<Vec<Certificate>> {
+fn read_pem_certificates(path: &str) -> Result<Vec<CertificateDer<'static>>> {
     let cert_bytes = fs::read(path).context("Failed to read certificate file")?;
     let mut certificates = vec![];
     let mut cursor = std::io::Cursor::new(cert_bytes);
@@ -35,8 +39,7 @@ fn read_pem_certificates(path: &str) -> Result<Vec<Certificate>> {
         {
             None => break,
             Some(rustls_pemfile::Item::X509Certificate(cert)) => {
-                let certificate = Certificate::from_der(&cert)?;
-                certificates.push(certificate);
+                certificates.push(cert);
             }
             Some(_) => {
                 tracing::warn!("Skipping non-certificate item");
@@ -102,6 +105,64 @@ pub fn get_encoding_from_content(raw_bytes: &[u8]) -> Option<String> {
     None
 }
 
+/// Parse the `base_url` client option, which must be an absolute http(s) URL.
+pub fn parse_base_url(base_url: &str) -> Result<Url> {
+    match Url::parse(base_url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(url),
+        _ => bail!(
+            "base_url must be an absolute http or https URL, got {:?}",
+            base_url
+        ),
+    }
+}
+
+/// `url` resolved against `base`. Absolute URLs are returned unchanged; others are
+/// appended to the base path, so `users` and `/users` on `https://api.example.com/v1`
+/// both give `https://api.example.com/v1/users`.
+pub fn join_base_url(base: &Url, url: &str) -> Result<String> {
+    if Url::parse(url) != Err(url::ParseError::RelativeUrlWithoutBase) {
+        return Ok(url.to_string());
+    }
+    let mut base = base.clone();
+    if !base.path().ends_with('/') {
+        let path = format!("{}/", base.path());
+        base.set_path(&path);
+    }
+    let joined = base
+        .join(url.trim_start_matches('/'))
+        .with_context(|| format!("Failed to join {:?} to base_url {}", url, base))?;
+    Ok(joined.to_string())
+}
+
+#[cfg(test)]
+mod base_url_tests {
+    use super::*;
+
+    #[test]
+    fn test_join_base_url() {
+        let base = parse_base_url("https://api.example.com/v1").unwrap();
+        assert_eq!(
+            join_base_url(&base, "users?page=2").unwrap(),
+            "https://api.example.com/v1/users?page=2"
+        );
+        assert_eq!(
+            join_base_url(&base, "/users").unwrap(),
+            "https://api.example.com/v1/users"
+        );
+        assert_eq!(
+            join_base_url(&base, "http://other.example.com/x").unwrap(),
+            "http://other.example.com/x"
+        );
+        let root = parse_base_url("http://localhost:8080").unwrap();
+        assert_eq!(
+            join_base_url(&root, "health").unwrap(),
+            "http://localhost:8080/health"
+        );
+        assert!(parse_base_url("/v1").is_err());
+        assert!(parse_base_url("ftp://example.com/").is_err());
+    }
+}
+
 #[cfg(test)]
 mod load_ca_certs_tests {
     use super::*;
@@ -131,7 +192,7 @@ Q29uc3VsdGF0aW9uczEiMCAGCSqGSIb3DQEJARYTcGVyc29uYWwtZW1haWwuY29t
         env::set_var("HTTPR_CA_BUNDLE", ca_cert_path);
 
         // Call the function
-        let result = load_ca_certs();
+        let result = load_ca_certs(None);
 
         // Check the result
         assert!(result.is_ok());
@@ -145,7 +206,7 @@ Q29uc3VsdGF0aW9uczEiMCAGCSqGSIb3DQEJARYTcGVyc29uYWwtZW1haWwuY29t
     fn test_load_ca_certs_without_env_var() {
         env::remove_var("HTTPR_CA_BUNDLE");
         // Call the function
-        let result = load_ca_certs();
+        let result = load_ca_certs(None);
 
         // Check the result
         assert!(result.is_ok());
@@ -158,12 +219,10 @@ Q29uc3VsdGF0aW9uczEiMCAGCSqGSIb3DQEJARYTcGVyc29uYWwtZW1haWwuY29t
         // a silent fall-back to built-in roots).
         let path = Path::new("test_ca_malformed.pem");
         fs::write(path, b"this is not a PEM certificate at all").unwrap();
-        env::set_var("HTTPR_CA_BUNDLE", path);
 
-        let result = load_ca_certs();
+        let result = load_ca_certs(path.to_str());
 
         fs::remove_file(path).unwrap();
-        env::remove_var("HTTPR_CA_BUNDLE");
 
         assert!(
             result.is_err(),
@@ -179,12 +238,10 @@ Q29uc3VsdGF0aW9uczEiMCAGCSqGSIb3DQEJARYTcGVyc29uYWwtZW1haWwuY29t
         // and must error rather than silently fall back to built-in roots.
         let path = Path::new("test_ca_no_certs.pem");
         fs::write(path, b"# only a comment, no certs here\n").unwrap();
-        env::set_var("HTTPR_CA_BUNDLE", path);
 
-        let result = load_ca_certs();
+        let result = load_ca_certs(path.to_str());
 
         fs::remove_file(path).unwrap();
-        env::remove_var("HTTPR_CA_BUNDLE");
 
         assert!(
             result.is_err(),
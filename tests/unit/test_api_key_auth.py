@@ -0,0 +1,90 @@
+"""Tests for ApiKeyAuth."""
+
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+class EchoHandler(BaseHTTPRequestHandler):
+    """Stores the last request's path and headers."""
+
+    last = {}
+
+    def do_GET(self):
+        EchoHandler.last = {"path": self.path, "headers": self.headers}
+        self.send_response(200)
+        self.send_header("Content-Length", "0")
+        self.end_headers()
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def base_url():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), EchoHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{server.server_address[1]}"
+    server.shutdown()
+
+
+def test_api_key_header(base_url):
+    client = httpr.Client(auth=httpr.ApiKeyAuth("secret-key"))
+    client.get(base_url + "/items")
+    assert EchoHandler.last["headers"]["X-API-Key"] == "secret-key"
+    assert EchoHandler.last["path"] == "/items"
+
+
+def test_api_key_custom_header(base_url):
+    client = httpr.Client(auth=httpr.ApiKeyAuth("secret-key", name="Api-Key"))
+    client.get(base_url + "/items")
+    assert EchoHandler.last["headers"]["Api-Key"] == "secret-key"
+    assert "X-API-Key" not in EchoHandler.last["headers"]
+
+
+def test_api_key_query(base_url):
+    client = httpr.Client(auth=httpr.ApiKeyAuth("secret key", where="query", name="api_key"))
+    client.get(base_url + "/items", params={"page": "2"})
+    assert EchoHandler.last["path"] == "/items?page=2&api_key=secret+key"
+    assert "X-API-Key" not in EchoHandler.last["headers"]
+
+
+def test_api_key_not_sent_to_redirect_host():
+    with LocalServer() as server:
+        server.echo("/final")
+        server.redirect("/start", "/final")
+        server.redirect("/away", f"http://localhost:{server.port}/final")
+        client = httpr.Client(auth=httpr.ApiKeyAuth("secret-key"))
+
+        client.get(f"{server.url}/start")
+        assert server.requests[-1]["headers"]["x-api-key"] == "secret-key"
+
+        client.get(f"{server.url}/away")
+        assert server.requests[-1]["path"] == "/final"
+        assert "x-api-key" not in server.requests[-1]["headers"]
+
+
+def test_per_request_auth_overrides_client(base_url):
+    client = httpr.Client(auth=httpr.ApiKeyAuth("client-key"))
+    client.get(base_url + "/items", auth=httpr.ApiKeyAuth("request-key"))
+    assert EchoHandler.last["headers"]["X-API-Key"] == "request-key"
+
+
+def test_api_key_properties():
+    auth = httpr.ApiKeyAuth("s3cr3t", where="query", name="token")
+    assert auth.where == "query"
+    assert auth.name == "token"
+    assert "s3cr3t" not in repr(auth)
+    assert httpr.Client(auth=auth).auth.name == "token"
+
+
+def test_api_key_invalid():
+    with pytest.raises(ValueError, match="where"):
+        httpr.ApiKeyAuth("k", where="cookie")
+    with pytest.raises(ValueError):
+        httpr.ApiKeyAuth("k", name="bad header")
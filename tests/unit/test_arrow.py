@@ -0,0 +1,85 @@
+"""Tests for Response.arrow()."""
+
+import sys
+import types
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        yield server
+
+
+def test_arrow_stream(server):
+    pa = pytest.importorskip("pyarrow")
+    table = pa.table({"id": [1, 2, 3], "name": ["a", "b", "c"]})
+    sink = pa.BufferOutputStream()
+    with pa.ipc.new_stream(sink, table.schema) as writer:
+        writer.write_table(table)
+    server.route(
+        "/data", body=sink.getvalue().to_pybytes(), headers={"Content-Type": "application/vnd.apache.arrow.stream"}
+    )
+    assert httpr.get(f"{server.url}/data").arrow().equals(table)
+
+
+def test_arrow_file_and_parquet(server):
+    pa = pytest.importorskip("pyarrow")
+    pq = pytest.importorskip("pyarrow.parquet")
+    table = pa.table({"x": [1.5, 2.5]})
+    sink = pa.BufferOutputStream()
+    with pa.ipc.new_file(sink, table.schema) as writer:
+        writer.write_table(table)
+    server.route("/file", body=sink.getvalue().to_pybytes(), headers={"Content-Type": "application/octet-stream"})
+    sink = pa.BufferOutputStream()
+    pq.write_table(table, sink)
+    server.route("/parquet", body=sink.getvalue().to_pybytes())
+    assert httpr.get(f"{server.url}/file").arrow().equals(table)
+    assert httpr.get(f"{server.url}/parquet").arrow().equals(table)
+
+
+@pytest.fixture
+def fake_pyarrow(monkeypatch):
+    """Minimal stand-in for pyarrow recording which reader gets the body."""
+    calls = []
+
+    class Reader:
+        def __init__(self, kind, source):
+            calls.append((kind, bytes(source)))
+
+        def read_all(self):
+            return "table"
+
+    pyarrow = types.ModuleType("pyarrow")
+    pyarrow.py_buffer = lambda data: memoryview(data)
+    pyarrow.BufferReader = lambda buffer: buffer
+    ipc = types.ModuleType("pyarrow.ipc")
+    ipc.open_stream = lambda source: Reader("stream", source)
+    ipc.open_file = lambda source: Reader("file", source)
+    parquet = types.ModuleType("pyarrow.parquet")
+    parquet.read_table = lambda source: Reader("parquet", source).read_all()
+    pyarrow.ipc, pyarrow.parquet = ipc, parquet
+    monkeypatch.setitem(sys.modules, "pyarrow", pyarrow)
+    monkeypatch.setitem(sys.modules, "pyarrow.ipc", ipc)
+    monkeypatch.setitem(sys.modules, "pyarrow.parquet", parquet)
+    return calls
+
+
+def test_format_detection(server, fake_pyarrow):
+    server.route("/stream", body=b"\xff\xff\xff\xffdata", headers={"Content-Type": "application/vnd.apache.arrow.stream"})
+    server.route("/file", body=b"ARROW1data")
+    server.route("/parquet", body=b"data", headers={"Content-Type": "application/vnd.apache.parquet"})
+    for path in ["/stream", "/file", "/parquet"]:
+        assert httpr.get(f"{server.url}{path}").arrow() == "table"
+    assert fake_pyarrow == [("stream", b"\xff\xff\xff\xffdata"), ("file", b"ARROW1data"), ("parquet", b"data")]
+
+
+def test_requires_pyarrow(server, monkeypatch):
+    monkeypatch.setitem(sys.modules, "pyarrow", None)
+    server.route("/data", body=b"ARROW1")
+    with pytest.raises(ImportError, match="requires pyarrow"):
+        httpr.get(f"{server.url}/data").arrow()
@@ -0,0 +1,109 @@
+"""Tests for auth_mode="on_challenge"."""
+
+import base64
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+
+CREDENTIALS = "Basic " + base64.b64encode(b"user:pass").decode()
+
+
+class ChallengeHandler(BaseHTTPRequestHandler):
+    """Records the Authorization header of every request."""
+
+    protocol_version = "HTTP/1.1"
+    seen: list = []
+
+    def respond(self, status, body=b"", challenge=None):
+        self.send_response(status)
+        if challenge:
+            self.send_header("WWW-Authenticate", challenge)
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def handle_auth(self):
+        length = int(self.headers.get("Content-Length", 0))
+        body = self.rfile.read(length)
+        auth = self.headers.get("Authorization")
+        ChallengeHandler.seen.append(auth)
+        if self.path == "/open":
+            return self.respond(200, b"open")
+        if self.path == "/no-challenge":
+            return self.respond(401)
+        if self.path == "/bearer-only" and auth is None:
+            return self.respond(401, challenge='Bearer realm="api"')
+        if auth is None:
+            return self.respond(401, challenge='Basic realm="test", charset="UTF-8"')
+        if auth in (CREDENTIALS, "Bearer token"):
+            return self.respond(200, b"ok:" + body)
+        return self.respond(403)
+
+    do_GET = handle_auth
+    do_POST = handle_auth
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def base_url():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), ChallengeHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{server.server_address[1]}"
+    server.shutdown()
+
+
+@pytest.fixture(autouse=True)
+def reset_seen():
+    ChallengeHandler.seen = []
+
+
+def test_preemptive_is_default(base_url):
+    client = httpr.Client(auth=("user", "pass"))
+    assert client.get(base_url + "/protected").status_code == 200
+    assert ChallengeHandler.seen == [CREDENTIALS]
+
+
+def test_on_challenge_retries_with_credentials(base_url):
+    client = httpr.Client(auth=("user", "pass"), auth_mode="on_challenge")
+    response = client.post(base_url + "/protected", content=b"payload")
+    assert response.status_code == 200
+    assert response.text == "ok:payload"
+    assert ChallengeHandler.seen == [None, CREDENTIALS]
+
+
+def test_on_challenge_without_challenge_needed(base_url):
+    client = httpr.Client(auth=("user", "pass"), auth_mode="on_challenge")
+    assert client.get(base_url + "/open").text == "open"
+    assert ChallengeHandler.seen == [None]
+
+
+def test_on_challenge_requires_matching_scheme(base_url):
+    client = httpr.Client(auth=("user", "pass"), auth_mode="on_challenge")
+    assert client.get(base_url + "/bearer-only").status_code == 401
+    assert client.get(base_url + "/no-challenge").status_code == 401
+    assert ChallengeHandler.seen == [None, None]
+
+
+def test_on_challenge_bearer(base_url):
+    client = httpr.Client(auth_bearer="token", auth_mode="on_challenge")
+    assert client.get(base_url + "/bearer-only").status_code == 200
+    assert ChallengeHandler.seen == [None, "Bearer token"]
+
+
+def test_invalid_auth_mode():
+    with pytest.raises(ValueError, match="auth_mode"):
+        httpr.Client(auth_mode="sometimes")
+
+
+@pytest.mark.asyncio
+async def test_async_on_challenge(base_url):
+    async with httpr.AsyncClient(auth=("user", "pass"), auth_mode="on_challenge") as client:
+        response = await client.get(base_url + "/protected")
+    assert response.status_code == 200
+    assert ChallengeHandler.seen == [None, CREDENTIALS]
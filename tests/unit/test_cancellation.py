@@ -0,0 +1,112 @@
+"""Tests for CancellationToken and Client.request_later()."""
+
+import socket
+import threading
+import time
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/fast", body="fast")
+        server.route("/slow", body="slow", delay=2)
+        yield server
+
+
+def test_token():
+    token = httpr.CancellationToken()
+    assert not token.cancelled
+    token.cancel()
+    token.cancel()
+    assert token.cancelled
+    assert "cancelled=true" in repr(token)
+
+
+def test_cancelled_before_sending(server):
+    token = httpr.CancellationToken()
+    token.cancel()
+    with pytest.raises(httpr.RequestCancelled):
+        httpr.Client().get(f"{server.url}/fast", cancel_token=token)
+    assert server.requests == []
+
+
+def test_cancel_in_flight(server):
+    token = httpr.CancellationToken()
+    threading.Timer(0.1, token.cancel).start()
+    started = time.monotonic()
+    with pytest.raises(httpr.RequestCancelled):
+        httpr.Client().get(f"{server.url}/slow", cancel_token=token)
+    assert time.monotonic() - started < 1
+
+
+def test_uncancelled_token(server):
+    token = httpr.CancellationToken()
+    response = httpr.Client().get(f"{server.url}/fast", cancel_token=token)
+    assert response.text == "fast"
+
+
+def test_cancel_streaming_body_closes_connection():
+    listener = socket.socket()
+    listener.bind(("127.0.0.1", 0))
+    listener.listen()
+    closed = threading.Event()
+
+    def serve():
+        conn, _ = listener.accept()
+        conn.recv(65536)
+        conn.sendall(b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\nhello")
+        conn.settimeout(5)
+        if conn.recv(1) == b"":
+            closed.set()
+        conn.close()
+
+    thread = threading.Thread(target=serve, daemon=True)
+    thread.start()
+    token = httpr.CancellationToken()
+    url = f"http://127.0.0.1:{listener.getsockname()[1]}/"
+    with httpr.Client().stream("GET", url, cancel_token=token) as response:
+        chunks = response.iter_bytes()
+        assert next(chunks) == b"hello"
+        threading.Timer(0.1, token.cancel).start()
+        with pytest.raises(httpr.RequestCancelled):
+            next(chunks)
+    # The connection is closed by its task on the runtime, which runs during other requests
+    with LocalServer() as other:
+        other.route("/", body="ok")
+        httpr.Client().get(other.url)
+    thread.join(timeout=5)
+    listener.close()
+    assert closed.is_set()
+
+
+def test_request_later(server):
+    started = time.monotonic()
+    future = httpr.Client().request_later(0.2, "GET", f"{server.url}/fast")
+    assert future.result(timeout=5).text == "fast"
+    assert time.monotonic() - started >= 0.2
+
+
+def test_request_later_cancel_future(server):
+    future = httpr.Client().request_later(0.2, "GET", f"{server.url}/fast")
+    assert future.cancel()
+    time.sleep(0.4)
+    assert server.requests == []
+
+
+def test_request_later_cancel_token(server):
+    token = httpr.CancellationToken()
+    future = httpr.Client().request_later(0.05, "GET", f"{server.url}/slow", cancel_token=token)
+    time.sleep(0.2)
+    token.cancel()
+    with pytest.raises(httpr.RequestCancelled):
+        future.result(timeout=1)
+
+
+def test_request_later_negative_delay(server):
+    with pytest.raises(ValueError):
+        httpr.Client().request_later(-1, "GET", f"{server.url}/fast")
@@ -0,0 +1,89 @@
+"""Tests for custom content codecs and request body compression."""
+
+import gzip
+import zlib
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+def reverse(data: bytes) -> bytes:
+    return data[::-1]
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        yield server
+
+
+def test_decode_custom_encoding(server):
+    server.route("/", body=reverse(b"plain text"), headers={"Content-Encoding": "x-reverse"})
+    client = httpr.Client(codecs={"x-reverse": (None, reverse)})
+    response = client.get(f"{server.url}/")
+    assert response.text == "plain text"
+    assert "content-encoding" not in response.headers
+    assert server.requests[-1]["headers"]["accept-encoding"] == "zstd,gzip,deflate,br,x-reverse"
+
+    # Without the codec the body is left encoded
+    assert httpr.Client().get(f"{server.url}/").content == reverse(b"plain text")
+
+
+def test_decode_errors(server):
+    def fail(data: bytes) -> bytes:
+        raise RuntimeError("corrupt frame")
+
+    server.route("/", body=b"x", headers={"Content-Encoding": "x-broken"})
+    client = httpr.Client(codecs={"x-broken": (None, fail)})
+    with pytest.raises(httpr.DecodingError, match="x-broken"):
+        client.get(f"{server.url}/")
+
+
+def test_request_encoding(server):
+    server.echo("/echo")
+    client = httpr.Client(codecs={"x-reverse": (reverse, reverse)}, request_encoding="x-reverse")
+    client.post(f"{server.url}/echo", content=b"abc")
+    request = server.requests[-1]
+    assert request["headers"]["content-encoding"] == "x-reverse"
+    assert request["body"] == b"cba"
+
+    client = httpr.Client(request_encoding="gzip")
+    client.post(f"{server.url}/echo", json={"a": 1})
+    request = server.requests[-1]
+    assert request["headers"]["content-encoding"] == "gzip"
+    assert gzip.decompress(request["body"]) == b'{"a":1}'
+
+    # A body encoded by the caller is sent as is
+    body = zlib.compress(b"abc")
+    client.post(f"{server.url}/echo", content=body, headers={"Content-Encoding": "deflate"})
+    assert server.requests[-1]["body"] == body
+    client.get(f"{server.url}/echo")
+    assert "content-encoding" not in server.requests[-1]["headers"]
+
+
+def test_streamed_body_with_builtin_encoding(server, tmp_path):
+    server.echo("/echo")
+    path = tmp_path / "data.txt"
+    path.write_bytes(b"line\n" * 1000)
+    client = httpr.Client(request_encoding="zstd")
+    client.post(f"{server.url}/echo", files={"file": str(path)})
+    request = server.requests[-1]
+    assert request["headers"]["content-encoding"] == "zstd"
+    assert len(request["body"]) < 1000
+
+
+@pytest.mark.parametrize(
+    ("kwargs", "message"),
+    [
+        ({"codecs": {"gzip": (None, reverse)}}, "cannot replace the built-in"),
+        ({"codecs": {"x-none": (None, None)}}, "pair of callables"),
+        ({"codecs": {"x-bad": ("reverse", None)}}, "pair of callables"),
+        ({"request_encoding": "lz4"}, "request_encoding must be"),
+        ({"codecs": {"x-in": (None, reverse)}, "request_encoding": "x-in"}, "request_encoding must be"),
+    ],
+)
+def test_invalid_options(kwargs, message):
+    with pytest.raises(ValueError, match=message):
+        httpr.Client(**kwargs)
@@ -0,0 +1,80 @@
+"""Tests for Client.from_file() configuration files and the base_url option."""
+
+import json
+
+import pytest
+
+import httpr
+from httpr.config import load_profile
+from httpr.testing import LocalServer
+
+CONFIG = """
+timeout = 30
+headers = { "User-Agent" = "billing/1.0", "X-Team" = "payments" }
+
+[profiles.staging]
+base_url = "{url}/api"
+read_timeout = 5
+headers = { "X-Environment" = "staging" }
+"""
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/api/invoices", body=b"invoices")
+        yield server
+
+
+def test_toml_profile(server, tmp_path):
+    path = tmp_path / "httpr.toml"
+    path.write_text(CONFIG.replace("{url}", server.url))
+    client = httpr.Client.from_file(path, profile="staging")
+    assert client.timeout == 30.0
+    assert client.read_timeout == 5.0
+    assert client.base_url == f"{server.url}/api"
+
+    assert client.get("invoices").text == "invoices"
+    headers = server.requests[-1]["headers"]
+    assert headers["user-agent"] == "billing/1.0"
+    assert headers["x-team"] == "payments"
+    assert headers["x-environment"] == "staging"
+
+
+def test_top_level_only_and_overrides(server, tmp_path):
+    path = tmp_path / "httpr.toml"
+    path.write_text(CONFIG.replace("{url}", server.url))
+    client = httpr.Client.from_file(path, timeout=5)
+    assert client.timeout == 5.0
+    assert client.base_url is None
+    assert client.headers == {"user-agent": "billing/1.0", "x-team": "payments"}
+
+    client = httpr.AsyncClient.from_file(path, profile="staging")
+    assert isinstance(client, httpr.AsyncClient)
+
+
+def test_json_and_relative_paths(tmp_path):
+    path = tmp_path / "client.json"
+    path.write_text(json.dumps({"auth": ["user", "secret"], "profiles": {"ci": {"ca_cert_file": "certs/ca.pem"}}}))
+    client = httpr.Client.from_file(path)
+    assert client.auth == ("user", "secret")
+    # Paths are resolved against the directory of the file
+    options = load_profile(path, "ci", {"auth", "ca_cert_file"})
+    assert options["ca_cert_file"] == str(tmp_path / "certs" / "ca.pem")
+
+
+def test_invalid_files(tmp_path):
+    path = tmp_path / "httpr.toml"
+    path.write_text("timeout = 5\nretries = 3\n[profiles.prod]\ntimeout = 10\n")
+    with pytest.raises(ValueError, match="unknown client option 'retries'"):
+        httpr.Client.from_file(path)
+    with pytest.raises(ValueError, match=r"unknown profile 'staging' \(available: prod\)"):
+        httpr.Client.from_file(path, profile="staging")
+
+
+def test_base_url(server):
+    client = httpr.Client(base_url=f"{server.url}/api/")
+    assert client.get("/invoices").text == "invoices"
+    assert client.get(f"{server.url}/api/invoices").status_code == 200
+    with pytest.raises(ValueError, match="base_url must be an absolute http or https URL"):
+        httpr.Client(base_url="/api")
@@ -0,0 +1,60 @@
+"""Tests for on_connection_event."""
+
+import sys
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/ok", body="ok")
+        yield server
+
+
+def test_created_then_reused(server):
+    events = []
+    client = httpr.Client(on_connection_event=events.append)
+    url = server.url.replace("127.0.0.1", "localhost")
+    client.get(f"{url}/ok")
+    client.get(f"{url}/ok")
+
+    dns = [e for e in events if e["event"] == "dns_resolved"]
+    assert dns and dns[0]["host"] == "localhost" and dns[0]["error"] is None
+    assert dns[0]["elapsed"] >= 0
+
+    connections = [e for e in events if e["event"] != "dns_resolved"]
+    assert [e["event"] for e in connections] == ["connection_created", "connection_reused"]
+    created, reused = connections
+    assert created["url"] == f"{url}/ok"
+    assert created["tls"] is False and created["http2"] is False
+    assert created["elapsed"] >= 0
+    assert created["remote_addr"].endswith(f":{server.url.rsplit(':', 1)[1]}")
+    assert reused["local_addr"] == created["local_addr"]
+
+
+def test_failed_lookup_and_callback_errors(server, monkeypatch):
+    events = []
+    unraisable = []
+    monkeypatch.setattr(sys, "unraisablehook", unraisable.append)
+
+    def on_event(event):
+        events.append(event)
+        raise RuntimeError("ignored")
+
+    client = httpr.Client(on_connection_event=on_event)
+    with pytest.raises(httpr.ConnectError):
+        client.get("http://does-not-exist.invalid/")
+    assert events[0]["event"] == "dns_resolved"
+    assert events[0]["addresses"] == [] and events[0]["error"]
+    assert client.get(f"{server.url}/ok").text == "ok"
+    assert len(unraisable) == len(events) == 2
+    assert isinstance(unraisable[0].exc_value, RuntimeError)
+
+
+def test_not_callable():
+    with pytest.raises(ValueError, match="on_connection_event must be callable"):
+        httpr.Client(on_connection_event=1)
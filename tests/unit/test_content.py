@@ -0,0 +1,75 @@
+"""Tests for the types accepted by content= and encoding=."""
+
+import array
+import mmap
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/upload", method="POST")
+        yield server
+
+
+def test_buffer_types(server):
+    client = httpr.Client()
+    numbers = array.array("H", [1, 2, 3])
+    with mmap.mmap(-1, 4) as mapped:
+        mapped.write(b"mmap")
+        bodies = [b"bytes", bytearray(b"bytearray"), memoryview(b"a view")[2:], numbers, mapped]
+        for body in bodies:
+            client.post(f"{server.url}/upload", content=body)
+    assert [r["body"] for r in server.requests] == [b"bytes", b"bytearray", b"view", numbers.tobytes(), b"mmap"]
+
+
+def test_buffer_is_shared(server):
+    buffer = bytearray(b"hello")
+    request = httpr.Request("POST", f"{server.url}/upload", content=buffer)
+    with pytest.raises(BufferError):
+        buffer.extend(b"!")
+    buffer[0:1] = b"j"
+    assert request.content == b"jello"
+    httpr.Client().send(request)
+    assert server.requests[0]["body"] == b"jello"
+    del request
+    buffer.extend(b"!")
+
+
+def test_reused_chunk_buffer(server):
+    def chunks():
+        buffer = bytearray(2)
+        for chunk in (b"ab", b"cd", b"ef"):
+            buffer[:] = chunk
+            yield buffer
+
+    httpr.post(f"{server.url}/upload", content=chunks())
+    assert server.requests[0]["body"] == b"abcdef"
+
+
+def test_str_with_encoding(server):
+    httpr.post(f"{server.url}/upload", content="café")
+    httpr.post(f"{server.url}/upload", content="café", encoding="latin-1")
+    with httpr.Client().stream("POST", f"{server.url}/upload", content="€", encoding="utf-16-le") as response:
+        response.read()
+    request = httpr.Request("POST", f"{server.url}/upload", content="ü", encoding="cp1252")
+    assert request.content == b"\xfc"
+    httpr.Client().send(request)
+    bodies = [r["body"] for r in server.requests]
+    assert bodies == [b"caf\xc3\xa9", b"caf\xe9", b"\xac\x20", b"\xfc"]
+
+
+def test_invalid_content(server):
+    with pytest.raises(TypeError, match="content must be bytes, str or a buffer"):
+        httpr.post(f"{server.url}/upload", content=42)
+    with pytest.raises(TypeError, match="only applies to str"):
+        httpr.post(f"{server.url}/upload", content=b"x", encoding="utf-8")
+    with pytest.raises(LookupError):
+        httpr.post(f"{server.url}/upload", content="x", encoding="no-such-codec")
+    with pytest.raises(UnicodeEncodeError):
+        httpr.post(f"{server.url}/upload", content="€", encoding="ascii")
+    assert server.requests == []
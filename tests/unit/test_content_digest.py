@@ -0,0 +1,73 @@
+"""Tests for send_content_digest."""
+
+import base64
+import hashlib
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+BODY = b'{"hello": "world"}'
+
+
+def digest_header(body: bytes, algorithm: str = "sha256") -> str:
+    key = {"sha256": "sha-256", "sha512": "sha-512"}[algorithm]
+    return f"{key}=:{base64.b64encode(hashlib.new(algorithm, body).digest()).decode()}:"
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.echo("/echo")
+        server.route("/good", body=BODY, headers={"Content-Digest": digest_header(BODY)})
+        server.route("/bad", body=BODY, headers={"Content-Digest": digest_header(b"something else")})
+        server.route("/legacy", body=BODY, headers={"Digest": "SHA-256=" + digest_header(b"other")[9:-1]})
+        server.route("/unknown", body=BODY, headers={"Content-Digest": "md5=:AAAA:"})
+        yield server
+
+
+def test_request_digest(server):
+    client = httpr.Client(send_content_digest="sha-256")
+    client.post(f"{server.url}/echo", content=BODY)
+    assert server.requests[-1]["headers"]["content-digest"] == "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:"
+    client.get(f"{server.url}/echo")
+    assert "content-digest" not in server.requests[-1]["headers"]
+
+    client = httpr.Client(send_content_digest="sha-512")
+    client.post(f"{server.url}/echo", content=BODY, headers={"Content-Digest": "sha-256=:AAAA:"})
+    assert server.requests[-1]["headers"]["content-digest"] == "sha-256=:AAAA:"
+    client.post(f"{server.url}/echo", content=BODY)
+    assert server.requests[-1]["headers"]["content-digest"] == digest_header(BODY, "sha512")
+
+
+def test_streamed_request_digest(server, tmp_path):
+    path = tmp_path / "upload.txt"
+    path.write_bytes(b"streamed file contents")
+    client = httpr.Client(send_content_digest="sha-256")
+    client.post(f"{server.url}/echo", files={"document": str(path)})
+    request = server.requests[-1]
+    assert b"streamed file contents" in request["body"]
+    assert request["headers"]["trailer"] == "content-digest"
+    assert "content-length" not in request["headers"]
+
+
+def test_response_digest(server):
+    client = httpr.Client(send_content_digest="sha-256")
+    assert client.get(f"{server.url}/good").content == BODY
+    assert client.get(f"{server.url}/unknown").content == BODY
+    with pytest.raises(httpr.DecodingError, match="does not match its Content-Digest sha-256 digest"):
+        client.get(f"{server.url}/bad")
+    with pytest.raises(httpr.DecodingError, match="does not match its Digest sha-256 digest"):
+        client.get(f"{server.url}/legacy")
+    with pytest.raises(httpr.DecodingError):
+        with client.stream("GET", f"{server.url}/bad") as response:
+            for _ in response.iter_bytes():
+                pass
+    assert client.head(f"{server.url}/bad").status_code == 200
+    assert httpr.Client().get(f"{server.url}/bad").content == BODY
+
+
+def test_invalid_algorithm():
+    with pytest.raises(ValueError, match="Unknown send_content_digest 'md5'"):
+        httpr.Client(send_content_digest="md5")
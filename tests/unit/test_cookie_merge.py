@@ -0,0 +1,53 @@
+"""Tests for combining client and per-request cookies with cookie_merge."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/", body=b"ok")
+        yield server
+
+
+def sent_cookie(server):
+    return server.requests[-1]["headers"].get("cookie")
+
+
+def test_merge_is_default(server):
+    client = httpr.Client(cookies={"a": "1", "b": "2"})
+    client.get(f"{server.url}/", cookies={"b": "3", "c": "4"})
+    assert sent_cookie(server) == "a=1; b=3; c=4"
+
+    # Client cookies are unchanged and sent alone without request cookies
+    client.get(f"{server.url}/")
+    assert sent_cookie(server) == "a=1; b=2"
+
+
+def test_replace(server):
+    client = httpr.Client(cookies={"a": "1", "b": "2"}, cookie_merge="replace")
+    client.get(f"{server.url}/", cookies={"b": "3", "c": "4"})
+    assert sent_cookie(server) == "b=3; c=4"
+
+    client.get(f"{server.url}/")
+    assert sent_cookie(server) == "a=1; b=2"
+
+
+def test_request_cookies_without_client_cookies(server):
+    httpr.get(f"{server.url}/", cookies={"c": "4"})
+    assert sent_cookie(server) == "c=4"
+
+
+def test_stream(server):
+    client = httpr.Client(cookies={"a": "1"})
+    with client.stream("GET", f"{server.url}/", cookies={"c": "4"}) as response:
+        response.read()
+    assert sent_cookie(server) == "a=1; c=4"
+
+
+def test_invalid_cookie_merge():
+    with pytest.raises(ValueError, match="cookie_merge must be 'merge' or 'replace'"):
+        httpr.Client(cookie_merge="append")
@@ -0,0 +1,97 @@
+"""Tests for the cookie policy options of Client."""
+
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from urllib.parse import parse_qs, urlparse
+
+import pytest
+
+import httpr
+
+
+class CookieHandler(BaseHTTPRequestHandler):
+    """/set?name=value sets cookies, /redirect?to=url redirects, anything else echoes Cookie."""
+
+    def do_GET(self):
+        parsed = urlparse(self.path)
+        query = parse_qs(parsed.query)
+        if parsed.path == "/redirect":
+            self.send_response(302)
+            self.send_header("Location", query["to"][0])
+            self.send_header("Content-Length", "0")
+            self.end_headers()
+            return
+        if parsed.path == "/set":
+            self.send_response(200)
+            for name, values in query.items():
+                self.send_header("Set-Cookie", f"{name}={values[0]}")
+            body = b""
+        else:
+            self.send_response(200)
+            body = self.headers.get("Cookie", "").encode()
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def port():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), CookieHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield server.server_address[1]
+    server.shutdown()
+
+
+def cookie_names(text):
+    return sorted(part.split("=")[0] for part in text.split("; ") if part)
+
+
+def test_third_party_cookies_from_redirect(port):
+    # 127.0.0.1 redirects to localhost, a different site
+    redirect = f"http://127.0.0.1:{port}/redirect?to=http://localhost:{port}/set%3Ftracker%3D1"
+
+    client = httpr.Client()
+    client.get(redirect)
+    assert client.get(f"http://localhost:{port}/").text == "tracker=1"
+
+    client = httpr.Client(reject_third_party_cookies=True)
+    client.get(redirect)
+    assert client.get(f"http://localhost:{port}/").text == ""
+    # First-party cookies are still stored
+    client.get(f"http://localhost:{port}/set?session=1")
+    assert client.get(f"http://localhost:{port}/").text == "session=1"
+
+
+def test_blocked_cookie_domains(port):
+    client = httpr.Client(blocked_cookie_domains=["localhost"])
+    client.get(f"http://localhost:{port}/set?a=1")
+    client.get(f"http://127.0.0.1:{port}/set?b=2")
+    assert client.get(f"http://localhost:{port}/").text == ""
+    assert client.get(f"http://127.0.0.1:{port}/").text == "b=2"
+
+
+def test_cookie_limits(port):
+    client = httpr.Client(max_cookies_per_domain=2, max_cookie_size=10)
+    client.get(f"http://127.0.0.1:{port}/set?a=1&b=2&c=3")
+    client.get(f"http://127.0.0.1:{port}/set?big=0123456789")
+    assert cookie_names(client.get(f"http://127.0.0.1:{port}/").text) == ["a", "b"]
+
+
+def test_cookie_public_suffix_option(port):
+    client = httpr.Client(cookie_public_suffix=True)
+    client.get(f"http://127.0.0.1:{port}/set?a=1")
+    assert client.get(f"http://127.0.0.1:{port}/").text == "a=1"
+
+
+def test_cookie_policy_requires_cookie_store():
+    with pytest.raises(ValueError, match="cookie_store"):
+        httpr.Client(cookie_store=False, max_cookie_size=100)
+
+
+def test_invalid_cookie_limits():
+    with pytest.raises(ValueError):
+        httpr.Client(max_cookies_per_domain=0)
@@ -0,0 +1,51 @@
+"""Tests for StreamingResponse.iter_csv()."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+CSV = '\ufeffid,name,note\r\n1,Ada,"likes ""quotes"", commas"\r\n2,Grace,"multi\nline"\r\n\r\n3,Linus,\r\n'
+ROWS = [str(i) + "\t" + "x" * 100 for i in range(2000)]
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/export.csv", body=CSV, headers={"Content-Type": "text/csv; charset=utf-8"})
+        server.route("/export.tsv", body="\n".join(ROWS), headers={"Content-Type": "text/tab-separated-values"})
+        server.route(
+            "/latin1.csv", body="name\ncaf\xe9\n".encode("latin-1"), headers={"Content-Type": "text/csv; charset=iso-8859-1"}
+        )
+        server.route("/ragged.csv", body="a,b\n1,2\n3\n4,5\n")
+        yield server
+
+
+def test_iter_csv_dicts(server):
+    with httpr.Client().stream("GET", f"{server.url}/export.csv") as response:
+        assert list(response.iter_csv()) == [
+            {"id": "1", "name": "Ada", "note": 'likes "quotes", commas'},
+            {"id": "2", "name": "Grace", "note": "multi\nline"},
+            {"id": "3", "name": "Linus", "note": ""},
+        ]
+
+
+def test_iter_csv_tuples(server):
+    client = httpr.Client()
+    with client.stream("GET", f"{server.url}/export.tsv") as response:
+        rows = list(response.iter_csv(delimiter="\t", header=False))
+    assert rows == [tuple(row.split("\t")) for row in ROWS]
+    with client.stream("GET", f"{server.url}/latin1.csv") as response:
+        assert list(response.iter_csv()) == [{"name": "café"}]
+
+
+def test_iter_csv_errors(server):
+    client = httpr.Client()
+    with client.stream("GET", f"{server.url}/export.csv") as response:
+        with pytest.raises(ValueError, match="single ASCII character"):
+            response.iter_csv(delimiter="||")
+    with client.stream("GET", f"{server.url}/ragged.csv") as response:
+        rows = response.iter_csv()
+        assert next(rows) == {"a": "1", "b": "2"}
+        with pytest.raises(ValueError, match="CSV row 3 has 1 fields, expected 2"):
+            next(rows)
@@ -0,0 +1,56 @@
+"""Tests for max_decompressed_size and max_decompression_ratio."""
+
+import gzip
+import zlib
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+TEXT = b"".join(b"line %d of a normal response\n" % i for i in range(20_000))
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/bomb", body=gzip.compress(b"\0" * (20 << 20)), headers={"Content-Encoding": "gzip"})
+        server.route("/text", body=gzip.compress(TEXT), headers={"Content-Encoding": "gzip"})
+        server.route("/deflate", body=zlib.compress(TEXT), headers={"Content-Encoding": "deflate"})
+        server.route("/corrupt", body=b"not gzip data", headers={"Content-Encoding": "gzip"})
+        yield server
+
+
+def test_max_decompression_ratio(server):
+    client = httpr.Client(max_decompression_ratio=100)
+    with pytest.raises(httpr.DecodingError, match="more than max_decompression_ratio"):
+        client.get(f"{server.url}/bomb")
+    response = client.get(f"{server.url}/text")
+    assert response.content == TEXT
+    assert "content-encoding" not in response.headers
+    assert client.get(f"{server.url}/deflate").content == TEXT
+    assert server.requests[0]["headers"]["accept-encoding"] == "zstd,gzip,deflate,br"
+
+
+def test_max_decompressed_size(server):
+    client = httpr.Client(max_decompressed_size=100_000)
+    with pytest.raises(httpr.DecodingError, match="max_decompressed_size"):
+        client.get(f"{server.url}/text")
+    with pytest.raises(httpr.DecodingError, match="max_decompressed_size"):
+        with client.stream("GET", f"{server.url}/bomb") as response:
+            for _ in response.iter_bytes():
+                pass
+    assert httpr.Client(max_decompressed_size=len(TEXT)).get(f"{server.url}/text").content == TEXT
+
+
+def test_corrupt_body(server):
+    client = httpr.Client(max_decompressed_size=100_000)
+    with pytest.raises(httpr.DecodingError, match="Error decoding gzip"):
+        client.get(f"{server.url}/corrupt")
+
+
+def test_invalid_limits():
+    with pytest.raises(ValueError, match="max_decompressed_size"):
+        httpr.Client(max_decompressed_size=0)
+    with pytest.raises(ValueError, match="max_decompression_ratio"):
+        httpr.Client(max_decompression_ratio=0.5)
@@ -0,0 +1,68 @@
+"""Tests for the client-side DNS cache."""
+
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+
+
+class OkHandler(BaseHTTPRequestHandler):
+    def do_GET(self):
+        body = b"ok"
+        self.send_response(200)
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def local_server():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), OkHandler)
+    port = server.server_address[1]
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield port
+    server.shutdown()
+
+
+def test_dns_cache_resolves_and_flushes(local_server):
+    client = httpr.Client(dns_cache_ttl=60)
+    url = f"http://localhost:{local_server}/"
+    assert client.get(url).text == "ok"
+    assert client.get(url).text == "ok"
+    client.flush_dns()
+    assert client.get(url).text == "ok"
+
+
+def test_flush_dns_without_cache_is_noop():
+    httpr.Client().flush_dns()
+
+
+def test_negative_caching_keeps_failing():
+    client = httpr.Client(dns_cache_ttl=60, dns_cache_negative_ttl=60, timeout=5)
+    for _ in range(2):
+        with pytest.raises(httpr.ConnectError):
+            client.get("http://does-not-exist.invalid/")
+
+
+def test_negative_ttl_requires_ttl():
+    with pytest.raises(ValueError, match="dns_cache_ttl"):
+        httpr.Client(dns_cache_negative_ttl=5)
+
+
+def test_invalid_ttl():
+    with pytest.raises(ValueError):
+        httpr.Client(dns_cache_ttl=-1)
+
+
+@pytest.mark.asyncio
+async def test_async_client_dns_cache(local_server):
+    async with httpr.AsyncClient(dns_cache_ttl=60) as client:
+        response = await client.get(f"http://localhost:{local_server}/")
+        client.flush_dns()
+    assert response.text == "ok"
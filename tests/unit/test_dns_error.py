@@ -0,0 +1,26 @@
+"""Tests for DNSError raised on host name resolution failures."""
+
+import pytest
+
+import httpr
+
+
+@pytest.mark.parametrize(
+    "options",
+    [{}, {"dns_cache_ttl": 60, "dns_cache_negative_ttl": 60}, {"block_private_ips": True}],
+)
+def test_unresolvable_host(options):
+    client = httpr.Client(**options)
+    for _ in range(2):
+        with pytest.raises(httpr.DNSError) as exc_info:
+            client.get("http://does-not-exist.invalid/")
+        assert exc_info.value.host == "does-not-exist.invalid"
+        assert exc_info.value.kind in ("not_found", "temporary", "other")
+        assert "does-not-exist.invalid" in str(exc_info.value)
+    assert isinstance(exc_info.value, httpr.ConnectError)
+
+
+def test_refused_connection_is_not_dns_error():
+    with pytest.raises(httpr.ConnectError) as exc_info:
+        httpr.get("http://127.0.0.1:1/")
+    assert not isinstance(exc_info.value, httpr.DNSError)
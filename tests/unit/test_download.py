@@ -0,0 +1,102 @@
+"""Tests for client.download_parallel()."""
+
+import hashlib
+import os
+import re
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+
+PAYLOAD = os.urandom(3 * 1024 * 1024 + 17)
+
+
+class RangeHandler(BaseHTTPRequestHandler):
+    """Serves PAYLOAD, honouring single byte ranges under /ranged/ only."""
+
+    protocol_version = "HTTP/1.1"
+    range_requests = 0
+
+    def do_GET(self):
+        match = re.fullmatch(r"bytes=(\d+)-(\d+)", self.headers.get("Range", ""))
+        if self.path.startswith("/ranged/") and match:
+            RangeHandler.range_requests += 1
+            start, end = int(match.group(1)), min(int(match.group(2)), len(PAYLOAD) - 1)
+            body = PAYLOAD[start : end + 1]
+            self.send_response(206)
+            self.send_header("Content-Range", f"bytes {start}-{end}/{len(PAYLOAD)}")
+            self.send_header("ETag", '"v1"')
+        elif self.path.startswith("/missing"):
+            body = b""
+            self.send_response(404)
+        else:
+            body = PAYLOAD
+            self.send_response(200)
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def range_server():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), RangeHandler)
+    port = server.server_address[1]
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{port}"
+    server.shutdown()
+
+
+def test_download_parallel_ranges(range_server, tmp_path):
+    RangeHandler.range_requests = 0
+    target = tmp_path / "file.bin"
+    size = httpr.Client().download_parallel(range_server + "/ranged/file.bin", target, connections=4)
+    assert size == len(PAYLOAD)
+    assert target.read_bytes() == PAYLOAD
+    assert not (tmp_path / "file.bin.part").exists()
+    # One probe plus one request per range
+    assert RangeHandler.range_requests == 5
+
+
+def test_download_parallel_without_range_support(range_server, tmp_path):
+    target = tmp_path / "plain.bin"
+    size = httpr.Client().download_parallel(range_server + "/plain/file.bin", str(target))
+    assert size == len(PAYLOAD)
+    assert target.read_bytes() == PAYLOAD
+
+
+def test_download_parallel_sha256(range_server, tmp_path):
+    target = tmp_path / "checked.bin"
+    digest = hashlib.sha256(PAYLOAD).hexdigest()
+    httpr.Client().download_parallel(range_server + "/ranged/file.bin", target, sha256=digest)
+    assert target.read_bytes() == PAYLOAD
+
+    bad = tmp_path / "bad.bin"
+    with pytest.raises(httpr.RequestError, match="SHA-256"):
+        httpr.Client().download_parallel(range_server + "/ranged/file.bin", bad, sha256="00" * 32)
+    assert not bad.exists()
+    assert not (tmp_path / "bad.bin.part").exists()
+
+
+def test_download_parallel_http_error(range_server, tmp_path):
+    with pytest.raises(httpr.HTTPStatusError):
+        httpr.Client().download_parallel(range_server + "/missing", tmp_path / "missing.bin")
+
+
+def test_download_parallel_invalid_connections(range_server, tmp_path):
+    with pytest.raises(ValueError):
+        httpr.Client().download_parallel(range_server + "/ranged/file.bin", tmp_path / "x.bin", connections=0)
+
+
+@pytest.mark.asyncio
+async def test_async_download_parallel(range_server, tmp_path):
+    target = tmp_path / "async.bin"
+    async with httpr.AsyncClient() as client:
+        size = await client.download_parallel(range_server + "/ranged/file.bin", target, connections=2)
+    assert size == len(PAYLOAD)
+    assert target.read_bytes() == PAYLOAD
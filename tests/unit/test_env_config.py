@@ -0,0 +1,59 @@
+"""Tests for client defaults from HTTPR_* environment variables and trust_env."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/", body=b"ok")
+        yield server
+
+
+def test_default_headers(server, monkeypatch):
+    monkeypatch.setenv("HTTPR_DEFAULT_HEADERS", '{"X-Service": "billing", "User-Agent": "env/1.0"}')
+    client = httpr.Client(headers={"user-agent": "app/2.0"})
+    client.get(f"{server.url}/")
+    headers = server.requests[-1]["headers"]
+    assert headers["x-service"] == "billing"
+    assert headers["user-agent"] == "app/2.0"
+
+
+def test_timeout_and_verify(monkeypatch):
+    monkeypatch.setenv("HTTPR_TIMEOUT", "12.5")
+    monkeypatch.setenv("HTTPR_VERIFY", "false")
+    assert httpr.Client().timeout == 12.5
+    assert httpr.Client(timeout=3).timeout == 3.0
+
+
+def test_scheme_proxy(server, monkeypatch):
+    monkeypatch.setenv("HTTPR_HTTP_PROXY", server.url)
+    response = httpr.Client().get("http://service.invalid/")
+    assert response.text == "ok"
+    assert server.requests[-1]["headers"]["host"] == "service.invalid"
+
+
+def test_trust_env_false(server, monkeypatch):
+    monkeypatch.setenv("HTTPR_DEFAULT_HEADERS", '{"X-Service": "billing"}')
+    monkeypatch.setenv("HTTPR_TIMEOUT", "not-a-number")
+    client = httpr.Client(trust_env=False)
+    assert client.timeout is None
+    client.get(f"{server.url}/")
+    assert "x-service" not in server.requests[-1]["headers"]
+
+
+@pytest.mark.parametrize(
+    ("name", "value", "message"),
+    [
+        ("HTTPR_TIMEOUT", "soon", "HTTPR_TIMEOUT must be a number of seconds"),
+        ("HTTPR_VERIFY", "maybe", "HTTPR_VERIFY must be true or false"),
+        ("HTTPR_DEFAULT_HEADERS", '{"X-Count": 1}', "HTTPR_DEFAULT_HEADERS must be a JSON object of strings"),
+    ],
+)
+def test_invalid_values(monkeypatch, name, value, message):
+    monkeypatch.setenv(name, value)
+    with pytest.raises(ValueError, match=message):
+        httpr.Client()
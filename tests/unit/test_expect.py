@@ -0,0 +1,88 @@
+"""Tests for Response.expect(), Response.validate() and Response.elapsed."""
+
+from datetime import timedelta
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+ITEMS_SCHEMA = {
+    "type": "object",
+    "required": ["items"],
+    "properties": {"items": {"type": "array", "items": {"type": "integer"}}},
+}
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/items", json={"items": [1, 2, 3]})
+        server.route("/bad", json={"items": [1, "two", None]})
+        server.route("/html", status=404, body="<h1>Not Found</h1>", headers={"Content-Type": "text/html"})
+        server.route("/slow", json={"items": []}, delay=0.2)
+        yield server
+
+
+def test_elapsed(server):
+    fast = httpr.get(f"{server.url}/items")
+    slow = httpr.get(f"{server.url}/slow")
+    assert isinstance(fast.elapsed, timedelta)
+    assert slow.elapsed >= timedelta(seconds=0.2) > fast.elapsed
+
+
+def test_expect_passes_and_chains(server):
+    response = httpr.get(f"{server.url}/items")
+    assert response.expect() is response
+    data = (
+        response.expect(status=200, content_type="application/json", json_schema=ITEMS_SCHEMA, max_latency=5)
+        .json()
+    )
+    assert data == {"items": [1, 2, 3]}
+    response.expect(status=range(200, 300), content_type="Application/JSON; charset=utf-8")
+    response.expect(status={200, 204})
+
+
+def test_expect_lists_every_failure(server):
+    response = httpr.get(f"{server.url}/html")
+    with pytest.raises(AssertionError) as exc_info:
+        response.expect(status=200, content_type="application/json", json_schema=ITEMS_SCHEMA)
+    message = str(exc_info.value)
+    assert f"{server.url}/html" in message
+    assert "status: expected 200, got 404" in message
+    assert "content type: expected application/json, got text/html" in message
+    assert "json_schema: body is not JSON" in message
+
+
+def test_expect_schema_paths(server):
+    with pytest.raises(AssertionError) as exc_info:
+        httpr.get(f"{server.url}/bad").expect(json_schema=ITEMS_SCHEMA)
+    message = str(exc_info.value)
+    assert "json_schema: /items/1:" in message
+    assert "json_schema: /items/2:" in message
+    assert "/items/0" not in message
+
+
+def test_expect_latency(server):
+    with pytest.raises(AssertionError, match=r"latency: 0\.\d+s exceeds 0\.05s"):
+        httpr.get(f"{server.url}/slow").expect(max_latency=0.05)
+
+
+def test_expect_invalid_schema(server):
+    with pytest.raises(ValueError, match="Invalid JSON Schema"):
+        httpr.get(f"{server.url}/items").expect(json_schema={"type": 12})
+
+
+def test_validate(server):
+    assert httpr.get(f"{server.url}/items").validate(ITEMS_SCHEMA) == []
+    violations = httpr.get(f"{server.url}/bad").validate(ITEMS_SCHEMA)
+    assert [v["path"] for v in violations] == ["/items/1", "/items/2"]
+    assert violations[0]["schema_path"] == "/properties/items/items/type"
+    assert '"two"' in violations[0]["message"]
+
+
+def test_validate_errors(server):
+    with pytest.raises(ValueError, match="not JSON"):
+        httpr.get(f"{server.url}/html").validate(ITEMS_SCHEMA)
+    with pytest.raises(ValueError, match="Invalid JSON Schema"):
+        httpr.get(f"{server.url}/items").validate({"type": 12})
@@ -0,0 +1,122 @@
+"""Tests for client.fetch_all()."""
+
+import asyncio
+import time
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        yield server
+
+
+def test_completion_order(server):
+    server.route("/slow", body="slow", delay=0.3)
+    server.route("/fast", body="fast")
+    client = httpr.Client()
+    results = client.fetch_all([f"{server.url}/slow", f"{server.url}/fast"])
+    assert len(results) == 2
+    assert [response.text for response in results] == ["fast", "slow"]
+    assert len(results) == 0
+
+
+def test_ordered(server):
+    server.route("/slow", body="slow", delay=0.3)
+    server.route("/fast", body="fast")
+    client = httpr.Client()
+    urls = [f"{server.url}/slow", f"{server.url}/fast", f"{server.url}/fast"]
+    results = client.fetch_all(urls, ordered=True)
+    assert [response.text for response in results] == ["slow", "fast", "fast"]
+
+
+def test_bounded_concurrency(server):
+    server.route("/item", body="ok", delay=0.2)
+    client = httpr.Client()
+    urls = [f"{server.url}/item"] * 4
+
+    start = time.monotonic()
+    assert all(r.status_code == 200 for r in client.fetch_all(urls, max_concurrency=4))
+    assert time.monotonic() - start < 0.6
+
+    start = time.monotonic()
+    assert all(r.status_code == 200 for r in client.fetch_all(urls, max_concurrency=2))
+    assert time.monotonic() - start >= 0.4
+
+
+def test_errors_are_yielded(server):
+    server.route("/ok", body="ok")
+    client = httpr.Client()
+    results = list(client.fetch_all([f"{server.url}/ok", "http://127.0.0.1:1/"], ordered=True))
+    assert results[0].status_code == 200
+    assert isinstance(results[1], httpr.ConnectError)
+
+
+def test_request_options(server):
+    server.echo("/echo")
+    client = httpr.Client()
+    results = list(client.fetch_all([f"{server.url}/echo"], params={"q": "x"}, headers={"X-Test": "1"}))
+    assert server.requests[0]["path"] == "/echo?q=x"
+    assert server.requests[0]["headers"]["x-test"] == "1"
+    assert len(results) == 1
+
+
+def test_header_values_and_removal(server):
+    server.echo("/echo")
+    client = httpr.Client(headers={"X-Client": "1"})
+    tokens = iter(["a", "b"])
+    headers = {"X-Client": None, "X-Token": lambda: next(tokens)}
+    results = list(client.fetch_all([f"{server.url}/echo"] * 2, headers=headers))
+    assert len(results) == 2
+    assert sorted(request["headers"]["x-token"] for request in server.requests) == ["a", "b"]
+    assert all("x-client" not in request["headers"] for request in server.requests)
+
+
+def test_invalid_concurrency():
+    with pytest.raises(ValueError, match="max_concurrency"):
+        httpr.Client().fetch_all(["http://example.com"], max_concurrency=0)
+
+
+def test_async_client(server):
+    server.route("/a", body="a")
+    server.route("/b", body="b")
+
+    async def collect():
+        async with httpr.AsyncClient() as client:
+            return [r.text async for r in client.fetch_all([f"{server.url}/a", f"{server.url}/b"], ordered=True)]
+
+    assert asyncio.run(collect()) == ["a", "b"]
+
+
+def test_stream(server):
+    server.route("/a", body=b"a" * 100_000)
+    server.route("/b", body=b"b")
+    client = httpr.Client()
+    results = client.fetch_all([f"{server.url}/a", f"{server.url}/b"], ordered=True, stream=True)
+    first = next(results)
+    assert isinstance(first, httpr.StreamingResponse)
+    assert b"".join(first.iter_bytes()) == b"a" * 100_000
+    second = next(results)
+    assert second.status_code == 200
+    assert second.read() == b"b"
+
+
+def test_stream_bounds_open_streams(server):
+    server.route("/item", body=b"ok")
+    client = httpr.Client()
+    results = client.fetch_all([f"{server.url}/item"] * 3, max_concurrency=1, stream=True)
+    first = next(results)
+    time.sleep(0.2)
+    # The unread stream holds the only slot
+    assert len(server.requests) == 1
+    first.close()
+    second = next(results)
+    assert second.read() == b"ok"
+    third = next(results)
+    assert third.read() == b"ok"
+    assert len(server.requests) == 3
+
@@ -0,0 +1,40 @@
+"""Tests for max_header_bytes and max_header_count."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/many", body="ok", headers={f"X-Header-{i}": str(i) for i in range(50)})
+        server.route("/large", body="ok", headers={"X-Large": "a" * 10_000})
+        server.redirect("/redirect", "/large")
+        yield server
+
+
+def test_max_header_count(server):
+    client = httpr.Client(max_header_count=20)
+    with pytest.raises(httpr.RemoteProtocolError, match="more than max_header_count"):
+        client.get(f"{server.url}/many")
+    assert client.get(f"{server.url}/large").text == "ok"
+    assert httpr.Client(max_header_count=100).get(f"{server.url}/many").text == "ok"
+
+
+def test_max_header_bytes(server):
+    client = httpr.Client(max_header_bytes=4096)
+    with pytest.raises(httpr.RemoteProtocolError, match="more than max_header_bytes"):
+        client.get(f"{server.url}/large")
+    # Limits apply to each response, including the one at the end of a redirect
+    with pytest.raises(httpr.RemoteProtocolError):
+        client.get(f"{server.url}/redirect")
+    assert client.get(f"{server.url}/many").text == "ok"
+
+
+def test_invalid_limits():
+    with pytest.raises(ValueError, match="max_header_bytes"):
+        httpr.Client(max_header_bytes=0)
+    with pytest.raises(ValueError, match="max_header_count"):
+        httpr.Client(max_header_count=0)
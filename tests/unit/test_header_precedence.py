@@ -0,0 +1,72 @@
+"""Tests for the precedence of request, client and generated headers, and removal with None."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/", body=b"ok")
+        yield server
+
+
+def sent_headers(server):
+    return server.requests[-1]["headers"]
+
+
+def test_request_headers_override_client_headers(server):
+    client = httpr.Client(headers={"X-Tenant": "acme", "User-Agent": "client/1.0"})
+    client.get(f"{server.url}/", headers={"user-agent": "request/2.0"})
+    headers = sent_headers(server)
+    assert headers["user-agent"] == "request/2.0"
+    assert headers["x-tenant"] == "acme"
+
+
+def test_none_removes_client_header(server):
+    client = httpr.Client(headers={"X-Tenant": "acme", "User-Agent": "client/1.0"})
+    client.get(f"{server.url}/", headers={"User-Agent": None})
+    headers = sent_headers(server)
+    assert "user-agent" not in headers
+    assert headers["x-tenant"] == "acme"
+
+    # Only that request is affected
+    client.get(f"{server.url}/")
+    assert sent_headers(server)["user-agent"] == "client/1.0"
+
+    with client.stream("GET", f"{server.url}/", headers={"X-Tenant": None}) as response:
+        response.read()
+    assert "x-tenant" not in sent_headers(server)
+
+
+def test_none_removes_generated_header(server):
+    httpr.get(f"{server.url}/", headers={"Accept-Encoding": None})
+    assert "accept-encoding" not in sent_headers(server)
+    httpr.get(f"{server.url}/", headers={"Accept-Encoding": "identity"})
+    assert sent_headers(server)["accept-encoding"] == "identity"
+
+
+def test_request_headers_override_generated_content_type(server):
+    httpr.post(f"{server.url}/", json={"a": 1}, headers={"Content-Type": "application/vnd.api+json"})
+    assert sent_headers(server)["content-type"] == "application/vnd.api+json"
+
+
+def test_replaced_client_headers_are_not_sent(server):
+    client = httpr.Client(headers={"X-Old": "1"})
+    client.headers = {"X-New": "2"}
+    client.get(f"{server.url}/")
+    headers = sent_headers(server)
+    assert "x-old" not in headers
+    assert headers["x-new"] == "2"
+
+
+def test_request_object(server):
+    client = httpr.Client(headers={"X-Tenant": "acme"})
+    request = httpr.Request("GET", f"{server.url}/", headers={"X-Tenant": None, "X-Request": "1"})
+    assert request.headers == {"X-Request": "1"}
+    client.send(request)
+    headers = sent_headers(server)
+    assert "x-tenant" not in headers
+    assert headers["x-request"] == "1"
@@ -0,0 +1,68 @@
+"""Tests for the client request history."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture(scope="module")
+def server():
+    with LocalServer() as server:
+        server.route("/ok", body="ok")
+        server.route("/missing", status=404)
+        server.route("/slow", delay=1.0)
+        server.redirect("/moved", "/ok")
+        yield server
+
+
+def test_history_disabled_by_default(server):
+    client = httpr.Client()
+    client.get(f"{server.url}/ok")
+    assert client.history() == []
+
+
+def test_records_requests(server):
+    client = httpr.Client(history_size=10)
+    client.get(f"{server.url}/ok")
+    client.post(f"{server.url}/moved", content=b"x")
+
+    first, second = client.history()
+    assert first["method"] == "GET"
+    assert first["url"] == f"{server.url}/ok"
+    assert first["status"] == 200
+    assert first["error"] is None
+    assert first["elapsed"] >= 0
+    assert first["started"] <= second["started"]
+    assert second["method"] == "POST"
+    assert second["url"] == f"{server.url}/ok"
+
+
+def test_records_error_status_and_failures(server):
+    client = httpr.Client(history_size=10)
+    client.get(f"{server.url}/missing")
+    with pytest.raises(httpr.TimeoutException):
+        client.get(f"{server.url}/slow", timeout=0.2)
+
+    missing, slow = client.history()
+    assert missing["status"] == 404
+    assert slow["status"] is None
+    assert slow["url"] == f"{server.url}/slow"
+    assert slow["error"]
+
+
+def test_keeps_last_n(server):
+    client = httpr.Client(history_size=2)
+    for i in range(5):
+        client.get(f"{server.url}/ok", params={"i": str(i)})
+    assert [entry["url"] for entry in client.history()] == [
+        f"{server.url}/ok?i=3",
+        f"{server.url}/ok?i=4",
+    ]
+    client.clear_history()
+    assert client.history() == []
+
+
+def test_invalid_history_size():
+    with pytest.raises(ValueError):
+        httpr.Client(history_size=0)
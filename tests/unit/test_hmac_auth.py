@@ -0,0 +1,127 @@
+"""Tests for HmacAuth request signing."""
+
+import base64
+import hashlib
+import hmac
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+class EchoHandler(BaseHTTPRequestHandler):
+    """Stores the last request's headers and body."""
+
+    last = {}
+
+    def handle_request(self):
+        length = int(self.headers.get("Content-Length", 0))
+        EchoHandler.last = {
+            "method": self.command,
+            "path": self.path,
+            "headers": self.headers,
+            "body": self.rfile.read(length),
+        }
+        self.send_response(200)
+        self.send_header("Content-Length", "0")
+        self.end_headers()
+
+    do_GET = handle_request
+    do_POST = handle_request
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def base_url():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), EchoHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{server.server_address[1]}"
+    server.shutdown()
+
+
+def expected_signature(secret, message, digest=hashlib.sha256):
+    return base64.b64encode(hmac.new(secret, message.encode(), digest).digest()).decode()
+
+
+def test_hmac_default_scheme(base_url):
+    auth = httpr.HmacAuth("key-1", "secret", timestamp_header="X-Timestamp")
+    client = httpr.Client(auth=auth, params={"page": "2"})
+    client.post(base_url + "/v1/items", content=b"payload")
+
+    last = EchoHandler.last
+    timestamp = last["headers"]["X-Timestamp"]
+    body_hash = hashlib.sha256(b"payload").hexdigest()
+    message = f"POST\n/v1/items?page=2\n{timestamp}\n{body_hash}"
+    assert last["headers"]["Authorization"] == f"HMAC key-1:{expected_signature(b'secret', message)}"
+
+
+def test_hmac_custom_templates(base_url):
+    auth = httpr.HmacAuth(
+        "svc",
+        b"\x00binary-secret",
+        algorithm="sha512",
+        string_to_sign="{method} {host} {path} {nonce}",
+        header="X-Signature",
+        header_template="keyId={key_id},nonce={nonce},sig={signature}",
+        encoding="hex",
+    )
+    httpr.Client().get(base_url + "/status", auth=auth)
+
+    value = EchoHandler.last["headers"]["X-Signature"]
+    fields = dict(part.split("=", 1) for part in value.split(","))
+    host = base_url.removeprefix("http://")
+    message = f"GET {host} /status {fields['nonce']}"
+    expected = hmac.new(b"\x00binary-secret", message.encode(), hashlib.sha512).hexdigest()
+    assert fields["keyId"] == "svc"
+    assert fields["sig"] == expected
+    assert "Authorization" not in EchoHandler.last["headers"]
+
+
+def test_hmac_signs_each_redirect_hop():
+    with LocalServer() as server:
+        server.echo("/final")
+        server.redirect("/start", "/final")
+        server.redirect("/away", f"http://localhost:{server.port}/final")
+        client = httpr.Client(auth=httpr.HmacAuth("key-1", "secret", timestamp_header="X-Timestamp"))
+
+        client.get(f"{server.url}/start")
+        headers = server.requests[-1]["headers"]
+        message = f"GET\n/final\n{headers['x-timestamp']}\n{hashlib.sha256(b'').hexdigest()}"
+        assert headers["authorization"] == f"HMAC key-1:{expected_signature(b'secret', message)}"
+
+        # No signature, in the default or the timestamp header, for another host
+        client.get(f"{server.url}/away")
+        headers = server.requests[-1]["headers"]
+        assert "authorization" not in headers
+        assert "x-timestamp" not in headers
+
+
+def test_hmac_auth_property(base_url):
+    auth = httpr.HmacAuth("key-1", "secret")
+    client = httpr.Client(auth=auth)
+    assert isinstance(client.auth, httpr.HmacAuth)
+    assert client.auth.key_id == "key-1"
+    client.auth = ("user", "pass")
+    assert client.auth == ("user", "pass")
+
+
+def test_hmac_invalid_arguments():
+    with pytest.raises(ValueError, match="placeholder"):
+        httpr.HmacAuth("k", "s", header_template="{bogus}")
+    with pytest.raises(ValueError, match="signature"):
+        httpr.HmacAuth("k", "s", string_to_sign="{signature}")
+    with pytest.raises(ValueError, match="algorithm"):
+        httpr.HmacAuth("k", "s", algorithm="md5")
+
+
+@pytest.mark.asyncio
+async def test_async_hmac(base_url):
+    async with httpr.AsyncClient(auth=httpr.HmacAuth("key-1", "secret")) as client:
+        await client.get(base_url + "/async")
+    assert EchoHandler.last["headers"]["Authorization"].startswith("HMAC key-1:")
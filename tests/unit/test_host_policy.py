@@ -0,0 +1,68 @@
+"""Tests for allowed_schemes, allow_hosts, block_hosts and block_private_ips."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/ok", body="ok")
+        yield server
+
+
+def test_block_private_ips(server):
+    client = httpr.Client(block_private_ips=True)
+    with pytest.raises(httpr.HostBlocked, match="127.0.0.1 is a private address"):
+        client.get(f"{server.url}/ok")
+    # Host names are checked after DNS resolution, with or without the DNS cache
+    for client in [client, httpr.Client(block_private_ips=True, dns_cache_ttl=60)]:
+        with pytest.raises(httpr.HostBlocked, match="resolves to the private address"):
+            client.get(f"http://localhost:{server.port}/ok")
+    assert server.requests == []
+
+
+def test_allow_hosts(server):
+    client = httpr.Client(allow_hosts=["127.0.0.1", "*.example.com"])
+    assert client.get(f"{server.url}/ok").text == "ok"
+    with pytest.raises(httpr.HostBlocked, match="not in allow_hosts"):
+        client.get(f"http://localhost:{server.port}/ok")
+    assert len(server.requests) == 1
+
+
+def test_redirect_targets_are_checked(server):
+    server.redirect("/redirect", f"http://localhost:{server.port}/ok")
+    client = httpr.Client(block_hosts=["localhost"])
+    with pytest.raises(httpr.HostBlocked, match="is in block_hosts"):
+        client.get(f"{server.url}/redirect")
+    assert [r["path"] for r in server.requests] == ["/redirect"]
+    # Without restrictions the redirect is followed
+    assert httpr.Client().get(f"{server.url}/redirect").text == "ok"
+
+
+def test_invalid_entries():
+    with pytest.raises(ValueError, match="allow_hosts"):
+        httpr.Client(allow_hosts=[""])
+
+
+def test_allowed_schemes(server):
+    client = httpr.Client()
+    for url in ["ftp://127.0.0.1/file.txt", "file:///etc/passwd"]:
+        with pytest.raises(httpr.UnsupportedProtocol, match="is not allowed"):
+            client.get(url)
+    # Redirects to other schemes are refused too
+    server.redirect("/ftp", "ftp://127.0.0.1/file.txt")
+    server.redirect("/file", "file:///etc/passwd")
+    for path in ["/ftp", "/file"]:
+        with pytest.raises(httpr.UnsupportedProtocol):
+            client.get(f"{server.url}{path}")
+
+    client = httpr.Client(allowed_schemes=["https"])
+    with pytest.raises(httpr.UnsupportedProtocol, match='Scheme "http" is not allowed'):
+        client.get(f"{server.url}/ok")
+    assert [r["path"] for r in server.requests] == ["/ftp", "/file"]
+
+    with pytest.raises(ValueError, match="allowed_schemes"):
+        httpr.Client(allowed_schemes=["ftp"])
@@ -0,0 +1,60 @@
+"""Tests for the idempotency_key request option."""
+
+import asyncio
+import uuid
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/pay", json={"ok": True})
+        server.redirect("/old-pay", "/pay", status=307)
+        yield server
+
+
+def keys(server):
+    return [r["headers"].get("idempotency-key") for r in server.requests]
+
+
+def test_generated_key(server):
+    client = httpr.Client()
+    client.post(f"{server.url}/pay", json={"amount": 10}, idempotency_key=True)
+    client.post(f"{server.url}/pay", json={"amount": 10}, idempotency_key=True)
+    first, second = keys(server)
+    assert uuid.UUID(first).version == 4
+    assert first != second
+
+
+def test_custom_and_propagated_key(server):
+    client = httpr.Client()
+    client.post(f"{server.url}/pay", idempotency_key="order-42")
+    # True keeps a key that is already in the headers
+    client.post(f"{server.url}/pay", headers={"idempotency-key": "order-43"}, idempotency_key=True)
+    client.post(f"{server.url}/pay")
+    assert keys(server) == ["order-42", "order-43", None]
+
+
+def test_same_key_across_attempts(server):
+    client = httpr.Client()
+    client.post(f"{server.url}/old-pay", content=b"x", idempotency_key=True)
+    first, second = keys(server)
+    assert first is not None
+    assert first == second
+
+
+def test_stream_and_async(server):
+    client = httpr.Client()
+    with client.stream("POST", f"{server.url}/pay", idempotency_key="stream-key") as response:
+        response.read()
+
+    async def post():
+        async with httpr.AsyncClient() as client:
+            await client.post(f"{server.url}/pay", idempotency_key="async-key")
+
+    asyncio.run(post())
+    assert keys(server) == ["stream-key", "async-key"]
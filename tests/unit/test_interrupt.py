@@ -0,0 +1,52 @@
+"""Tests that blocking calls can be interrupted with Ctrl-C."""
+
+import _thread
+import threading
+import time
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/slow", body="slow", delay=5)
+        yield server
+
+
+def interrupt_after(seconds):
+    # Simulates Ctrl-C: sets the pending SIGINT flag of the main thread
+    threading.Timer(seconds, _thread.interrupt_main).start()
+
+
+def test_request_interrupted(server):
+    client = httpr.Client()
+    interrupt_after(0.2)
+    started = time.monotonic()
+    with pytest.raises(KeyboardInterrupt):
+        client.get(f"{server.url}/slow")
+    assert time.monotonic() - started < 2
+
+
+def test_queue_join_interrupted(server):
+    client = httpr.Client()
+    queue = client.queue()
+    queue.submit(httpr.Request("GET", f"{server.url}/slow"))
+    interrupt_after(0.2)
+    started = time.monotonic()
+    with pytest.raises(KeyboardInterrupt):
+        queue.join()
+    assert time.monotonic() - started < 2
+    queue.close(wait=False)
+
+
+def test_other_threads_unaffected(server):
+    server.route("/fast", body="fast")
+    results = []
+    thread = threading.Thread(target=lambda: results.append(httpr.get(f"{server.url}/fast").text))
+    thread.start()
+    thread.join()
+    assert results == ["fast"]
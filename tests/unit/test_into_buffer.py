@@ -0,0 +1,77 @@
+"""Tests for Response.into_buffer() and StreamingResponse.read_into()."""
+
+import array
+import mmap
+import struct
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+DOUBLES = struct.pack("<4d", 1.0, 2.5, -3.0, 4.25)
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/doubles", body=DOUBLES, headers={"Content-Type": "application/octet-stream"})
+        server.route("/text", body="hello world")
+        yield server
+
+
+def test_into_buffer(server):
+    response = httpr.get(f"{server.url}/doubles")
+    values = array.array("d", [0.0] * 4)
+    assert response.into_buffer(values) == len(DOUBLES)
+    assert list(values) == [1.0, 2.5, -3.0, 4.25]
+
+
+def test_into_larger_buffer(server):
+    response = httpr.get(f"{server.url}/text")
+    buffer = bytearray(b"-" * 16)
+    assert response.into_buffer(buffer) == 11
+    assert bytes(buffer) == b"hello world-----"
+    # Writes at an offset through a memoryview slice
+    assert response.into_buffer(memoryview(buffer)[5:]) == 11
+    assert bytes(buffer) == b"hellohello world"
+
+
+def test_into_buffer_errors(server):
+    response = httpr.get(f"{server.url}/text")
+    with pytest.raises(ValueError, match="does not fit"):
+        response.into_buffer(bytearray(4))
+    with pytest.raises(TypeError, match="read-only"):
+        response.into_buffer(b"x" * 16)
+    with pytest.raises(TypeError):
+        response.into_buffer("not a buffer")
+
+
+def test_read_into(server):
+    with httpr.Client().stream("GET", f"{server.url}/doubles") as response:
+        with mmap.mmap(-1, len(DOUBLES)) as target:
+            assert response.read_into(target) == len(DOUBLES)
+            assert target[:] == DOUBLES
+        assert response.is_consumed
+        with pytest.raises(httpr.StreamConsumed):
+            response.read_into(bytearray(64))
+
+
+def test_read_into_too_small(server):
+    with httpr.Client().stream("GET", f"{server.url}/text") as response:
+        buffer = bytearray(4)
+        with pytest.raises(ValueError, match="11 bytes"):
+            response.read_into(buffer)
+        # Checked against Content-Length before reading
+        assert buffer == bytearray(4)
+        assert response.read() == b"hello world"
+
+
+def test_numpy(server):
+    numpy = pytest.importorskip("numpy")
+    with httpr.Client().stream("GET", f"{server.url}/doubles") as response:
+        values = numpy.zeros((2, 2), dtype="<f8")
+        response.read_into(values)
+    assert values.tolist() == [[1.0, 2.5], [-3.0, 4.25]]
+    with pytest.raises(ValueError, match="C-contiguous"):
+        httpr.get(f"{server.url}/doubles").into_buffer(numpy.zeros((2, 2)).T)
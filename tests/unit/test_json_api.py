@@ -0,0 +1,72 @@
+"""Tests for Client.get_json(), Client.post_json() and HTTPStatusError.error_body."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture(scope="module")
+def server():
+    with LocalServer() as server:
+        server.route("/user", json={"id": 1, "name": "Ada"})
+        server.route("/problem", json={"id": 1}, headers={"Content-Type": "application/vnd.api+json"})
+        server.route("/created", status=201, json={"id": 2})
+        server.route("/empty", status=204)
+        server.route("/invalid", status=422, json={"errors": [{"field": "email", "message": "is required"}]})
+        server.route("/html", body="<html>Sign in</html>", headers={"Content-Type": "text/html"})
+        server.route("/down", status=503, body="upstream is down")
+        yield server
+
+
+def test_get_json(server):
+    assert httpr.Client().get_json(f"{server.url}/user") == {"id": 1, "name": "Ada"}
+    assert server.requests[-1]["headers"]["accept"] == "application/json"
+
+
+def test_get_json_keeps_accept(server):
+    httpr.Client().get_json(f"{server.url}/problem", headers={"accept": "application/vnd.api+json"})
+    assert server.requests[-1]["headers"]["accept"] == "application/vnd.api+json"
+
+
+def test_post_json(server):
+    assert httpr.Client().post_json(f"{server.url}/created", {"name": "Grace"}) == {"id": 2}
+    request = server.requests[-1]
+    assert request["method"] == "POST"
+    assert request["body"] == b'{"name":"Grace"}'
+
+
+def test_empty_body(server):
+    assert httpr.Client().get_json(f"{server.url}/empty") is None
+
+
+def test_error_body(server):
+    with pytest.raises(httpr.HTTPStatusError) as exc_info:
+        httpr.Client().post_json(f"{server.url}/invalid", {})
+    err = exc_info.value
+    assert err.status_code == 422
+    assert err.error_body == {"errors": [{"field": "email", "message": "is required"}]}
+    assert err.error_fields == {"errors": [{"field": "email", "message": "is required"}]}
+
+
+def test_error_body_not_json(server):
+    with pytest.raises(httpr.HTTPStatusError) as exc_info:
+        httpr.Client().get_json(f"{server.url}/down")
+    assert exc_info.value.error_body is None
+    assert exc_info.value.body_preview == "upstream is down"
+
+
+def test_not_json(server):
+    with pytest.raises(httpr.DecodingError, match="Expected a JSON response .* got text/html") as exc_info:
+        httpr.Client().get_json(f"{server.url}/html")
+    assert exc_info.value.response.text == "<html>Sign in</html>"
+
+
+@pytest.mark.asyncio
+async def test_async(server):
+    async with httpr.AsyncClient() as client:
+        assert await client.get_json(f"{server.url}/user") == {"id": 1, "name": "Ada"}
+        assert await client.post_json(f"{server.url}/created", {"name": "Grace"}) == {"id": 2}
+        with pytest.raises(httpr.HTTPStatusError) as exc_info:
+            await client.get_json(f"{server.url}/invalid")
+    assert exc_info.value.error_body["errors"][0]["field"] == "email"
@@ -0,0 +1,48 @@
+"""Tests for StreamingResponse.iter_json_array()."""
+
+import json
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+ITEMS = [{"id": i, "name": f"item {i}", "tags": ["]", "}", '"']} for i in range(5000)]
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/export", json={"meta": {"items": []}, "total": len(ITEMS), "items": ITEMS})
+        server.route("/nested", json={"data": {"results": [1, "two", None, [3]]}})
+        server.route("/top", json=[True, 1.5, {"a": {}}])
+        server.route("/object", json={"items": {"id": 1}})
+        server.route("/truncated", body='{"items": [{"id": 1}, {"id": 2}, {"id"')
+        yield server
+
+
+def test_iter_json_array(server):
+    client = httpr.Client()
+    with client.stream("GET", f"{server.url}/export") as response:
+        items = response.iter_json_array("items")
+        assert next(items) == ITEMS[0]
+        assert list(items) == ITEMS[1:]
+    with client.stream("GET", f"{server.url}/nested") as response:
+        assert list(response.iter_json_array("data.results")) == [1, "two", None, [3]]
+    with client.stream("GET", f"{server.url}/top") as response:
+        assert list(response.iter_json_array()) == json.loads('[true, 1.5, {"a": {}}]')
+
+
+def test_iter_json_array_errors(server):
+    client = httpr.Client()
+    with client.stream("GET", f"{server.url}/export") as response:
+        with pytest.raises(ValueError, match='No JSON array found at "missing"'):
+            list(response.iter_json_array("missing"))
+    with client.stream("GET", f"{server.url}/object") as response:
+        with pytest.raises(ValueError, match='Expected a JSON array at "items"'):
+            list(response.iter_json_array("items"))
+    with client.stream("GET", f"{server.url}/truncated") as response:
+        items = response.iter_json_array("items")
+        assert [next(items), next(items)] == [{"id": 1}, {"id": 2}]
+        with pytest.raises(ValueError, match="ended inside the array"):
+            next(items)
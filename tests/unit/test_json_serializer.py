@@ -0,0 +1,132 @@
+"""Tests for object conversion of json=, the json_serializer hook and the JSON format options."""
+
+import json
+import uuid
+from dataclasses import dataclass, field
+from datetime import date, datetime, timezone
+from decimal import Decimal
+from enum import Enum
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+class Color(Enum):
+    RED = "red"
+
+
+class Model:
+    """Pydantic-like: converted through model_dump()."""
+
+    def __init__(self, **values):
+        self.values = values
+
+    def model_dump(self):
+        return self.values
+
+
+class Plain:
+    def __init__(self):
+        self.name = "plain"
+        self._secret = "hidden"
+
+
+@dataclass
+class Order:
+    id: int
+    placed: date
+    color: Color
+    lines: list = field(default_factory=list)
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/echo", method="POST")
+        yield server
+
+
+def sent(server):
+    return json.loads(server.requests[-1]["body"])
+
+
+def test_objects_are_converted(server):
+    order = Order(1, date(2026, 10, 16), Color.RED, [Model(sku="a", qty=2), Plain()])
+    ident = uuid.UUID(int=1)
+    at = datetime(2026, 10, 16, 13, 40, tzinfo=timezone.utc)
+    httpr.post(f"{server.url}/echo", json={"order": order, "id": ident, "at": at, 7: (1, None)})
+    assert sent(server) == {
+        "order": {
+            "id": 1,
+            "placed": "2026-10-16",
+            "color": "red",
+            "lines": [{"sku": "a", "qty": 2}, {"name": "plain"}],
+        },
+        "id": "00000000-0000-0000-0000-000000000001",
+        "at": "2026-10-16T13:40:00+00:00",
+        "7": [1, None],
+    }
+    assert server.requests[-1]["headers"]["content-type"] == "application/json"
+
+
+def test_json_serializer(server):
+    def serialize(obj):
+        if isinstance(obj, Decimal):
+            return str(obj)
+        if isinstance(obj, set):
+            return sorted(obj)
+        return NotImplemented
+
+    client = httpr.Client(json_serializer=serialize)
+    client.post(f"{server.url}/echo", json={"price": Decimal("9.99"), "tags": {"b", "a"}, "color": Color.RED})
+    assert sent(server) == {"price": "9.99", "tags": ["a", "b"], "color": "red"}
+
+    with pytest.raises(ValueError, match="json_serializer must be callable"):
+        httpr.Client(json_serializer="str")
+
+
+def test_json_canonical(server):
+    client = httpr.Client(json_canonical=True)
+    client.post(f"{server.url}/echo", json={"b": [2.50, 1e21, None], "a": {"y": "\u20ac", "x": 1e-7}, "n": 10})
+    assert server.requests[-1]["body"].decode() == '{"a":{"x":1e-7,"y":"\u20ac"},"b":[2.5,1e+21,null],"n":10}'
+    assert server.requests[-1]["headers"]["content-type"] == "application/json"
+
+    client.post(f"{server.url}/echo", json=[1], headers={"Content-Type": "application/jose+json"})
+    assert server.requests[-1]["headers"]["content-type"] == "application/jose+json"
+
+
+def test_insertion_order(server):
+    httpr.Client().post(f"{server.url}/echo", json={"b": 1, "a": {"d": 2, "c": 3}})
+    assert server.requests[-1]["body"] == b'{"b":1,"a":{"d":2,"c":3}}'
+
+
+def test_json_dumps_options(server):
+    payload = {"name": "Zo\u00eb \U0001f600", "id": 7, "tags": ["a", "b"], "meta": {}, "price": 2.5, "none": None}
+    client = httpr.Client(json_sort_keys=True, json_ensure_ascii=True, json_separators=(", ", ": "))
+    client.post(f"{server.url}/echo", json=payload)
+    assert server.requests[-1]["body"].decode() == json.dumps(payload, sort_keys=True, ensure_ascii=True)
+    assert server.requests[-1]["headers"]["content-type"] == "application/json"
+
+    client = httpr.Client(json_indent=2)
+    client.post(f"{server.url}/echo", json=payload)
+    assert server.requests[-1]["body"].decode("utf-8") == json.dumps(payload, indent=2, ensure_ascii=False)
+
+
+def test_json_canonical_excludes_options():
+    with pytest.raises(ValueError, match="json_canonical cannot be combined"):
+        httpr.Client(json_canonical=True, json_sort_keys=True)
+
+
+def test_unserializable(server):
+    client = httpr.Client()
+    with pytest.raises(TypeError, match="Object of type Decimal is not JSON serializable"):
+        client.post(f"{server.url}/echo", json={"price": Decimal("1")})
+    with pytest.raises(TypeError, match="keys must be str or int"):
+        client.post(f"{server.url}/echo", json={(1, 2): "x"})
+    with pytest.raises(ValueError, match="not allowed in JSON"):
+        client.post(f"{server.url}/echo", json=[float("nan")])
+    with pytest.raises(ValueError, match="nested more than"):
+        httpr.Client(json_serializer=lambda obj: [obj]).post(f"{server.url}/echo", json=Decimal("1"))
+    assert server.requests == []
@@ -0,0 +1,52 @@
+"""Tests for Response.jsonpath() and StreamingResponse.jsonpath_stream()."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+NDJSON = '{"user": {"id": 1}}\n{"user": {"id": 2}, "tags": ["a"]}\n\n{"event": "ping"}\n{"user": {"id": 3}}'
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/items", json={"items": [{"id": 1, "price": 5}, {"id": 2, "price": 50}, {"name": "x"}]})
+        server.route("/events", body=NDJSON, headers={"Content-Type": "application/x-ndjson"})
+        server.route("/broken", body='{"user": {"id": 1}}\nnot json\n')
+        server.route("/html", body="<h1>hi</h1>", headers={"Content-Type": "text/html"})
+        yield server
+
+
+def test_jsonpath(server):
+    response = httpr.get(f"{server.url}/items")
+    assert response.jsonpath("$.items[*].id") == [1, 2]
+    assert response.jsonpath("$.items[?@.price < 10]") == [{"id": 1, "price": 5}]
+    assert response.jsonpath("$.missing") == []
+
+
+def test_jsonpath_errors(server):
+    with pytest.raises(ValueError, match="Invalid JSONPath"):
+        httpr.get(f"{server.url}/items").jsonpath("items[")
+    with pytest.raises(ValueError, match="not JSON"):
+        httpr.get(f"{server.url}/html").jsonpath("$")
+
+
+def test_jsonpath_stream(server):
+    client = httpr.Client()
+    with client.stream("GET", f"{server.url}/events") as response:
+        assert list(response.jsonpath_stream("$.user.id")) == [1, 2, 3]
+    with client.stream("GET", f"{server.url}/events") as response:
+        assert list(response.jsonpath_stream("$.tags[*]")) == ["a"]
+
+
+def test_jsonpath_stream_errors(server):
+    client = httpr.Client()
+    with client.stream("GET", f"{server.url}/events") as response:
+        with pytest.raises(ValueError, match="Invalid JSONPath"):
+            response.jsonpath_stream("$[")
+    with client.stream("GET", f"{server.url}/broken") as response:
+        matches = response.jsonpath_stream("$.user.id")
+        assert next(matches) == 1
+        with pytest.raises(ValueError, match="line 2"):
+            next(matches)
@@ -0,0 +1,70 @@
+"""Tests for header values given as callables."""
+
+import itertools
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.echo("/echo")
+        server.redirect_chain("/start", "/echo", hops=2)
+        yield server
+
+
+def counter():
+    count = itertools.count(1)
+    return lambda: str(next(count))
+
+
+def test_client_lazy_header(server):
+    client = httpr.Client(headers={"X-Static": "a", "X-Count": counter()})
+    assert client.get(f"{server.url}/echo").json()["headers"]["x-count"] == "1"
+    assert client.get(f"{server.url}/echo").json()["headers"]["x-count"] == "2"
+    assert client.headers["x-static"] == "a"
+    assert callable(client.headers["x-count"])
+
+    client.headers = {"X-Static": "b"}
+    headers = client.get(f"{server.url}/echo").json()["headers"]
+    assert headers["x-static"] == "b"
+    assert "x-count" not in headers
+
+
+def test_request_lazy_header(server):
+    client = httpr.Client(headers={"X-Count": counter()})
+    headers = client.get(f"{server.url}/echo", headers={"X-Nonce": lambda: "n1"}).json()["headers"]
+    assert headers["x-nonce"] == "n1"
+    assert headers["x-count"] == "1"
+
+    # Request values override client callables, which are then not called
+    headers = client.get(f"{server.url}/echo", headers={"X-Count": "fixed"}).json()["headers"]
+    assert headers["x-count"] == "fixed"
+    headers = client.get(f"{server.url}/echo", headers={"X-Count": None}).json()["headers"]
+    assert "x-count" not in headers
+    assert client.get(f"{server.url}/echo").json()["headers"]["x-count"] == "2"
+
+
+def test_evaluated_per_attempt(server):
+    client = httpr.Client(headers={"X-Count": counter()})
+    client.get(f"{server.url}/start")
+    sent = [request["headers"].get("x-count") for request in server.requests]
+    assert sent == ["1", "2", "3"]
+
+
+def test_none_leaves_header_out(server):
+    headers = httpr.Client().get(f"{server.url}/echo", headers={"X-Maybe": lambda: None}).json()["headers"]
+    assert "x-maybe" not in headers
+
+
+def test_errors(server):
+    client = httpr.Client()
+    with pytest.raises(ValueError, match="X-Bad"):
+        client.get(f"{server.url}/echo", headers={"X-Bad": 1})
+    with pytest.raises(ZeroDivisionError):
+        client.get(f"{server.url}/echo", headers={"X-Fail": lambda: 1 / 0})
+    with pytest.raises(Exception, match="must return a str"):
+        client.get(f"{server.url}/echo", headers={"X-Int": lambda: 1})
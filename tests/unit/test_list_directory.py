@@ -0,0 +1,125 @@
+"""Tests for Client.list_directory() and httpr.listing."""
+
+import asyncio
+from datetime import datetime, timezone
+
+import pytest
+
+import httpr
+from httpr.listing import parse_index
+from httpr.testing import LocalServer
+
+NGINX = """<html>
+<head><title>Index of /pub/</title></head>
+<body>
+<h1>Index of /pub/</h1><hr><pre><a href="../">../</a>
+<a href="docs/">docs/</a>                                              16-Oct-2026 13:40                   -
+<a href="release%201.0.tar.gz">release 1.0.tar.gz</a>                                 15-Oct-2026 09:05             1048576
+<a href="a-very-long-file-name-that-nginx-truncates.txt">a-very-long-file-name-that-nginx-truncat..&gt;</a> 01-Jan-2026 00:00   12
+</pre><hr></body>
+</html>"""
+
+APACHE = """<!DOCTYPE HTML PUBLIC "-//W3C//DTD HTML 3.2 Final//EN">
+<html><head><title>Index of /pub</title></head><body>
+<h1>Index of /pub</h1>
+<table>
+<tr><th><a href="?C=N;O=D">Name</a></th><th><a href="?C=M;O=A">Last modified</a></th><th><a href="?C=S;O=A">Size</a></th></tr>
+<tr><td><a href="/">Parent Directory</a></td><td>&nbsp;</td><td align="right">  - </td></tr>
+<tr><td><a href="notes.txt">notes.txt</a></td><td align="right">2026-10-16 13:40  </td><td align="right">1.5K</td></tr>
+<tr><td><a href="img/">img/</a></td><td align="right">2026-10-14 08:00  </td><td align="right">  - </td></tr>
+</table></body></html>"""
+
+MULTISTATUS = b"""<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:">
+  <d:response>
+    <d:href>/dav/</d:href>
+    <d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+    <d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/report%20final.pdf</d:href>
+    <d:propstat><d:prop>
+      <d:resourcetype/>
+      <d:getcontentlength>2048</d:getcontentlength>
+      <d:getlastmodified>Fri, 16 Oct 2026 13:40:00 GMT</d:getlastmodified>
+    </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/photos/</d:href>
+    <d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop>
+    <d:status>HTTP/1.1 200 OK</d:status></d:propstat>
+  </d:response>
+</d:multistatus>
+"""
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/pub/", method="GET", body=NGINX, headers={"Content-Type": "text/html"})
+        server.route("/pub/", method="PROPFIND", status=405)
+        server.route("/dav/", method="PROPFIND", status=207, body=MULTISTATUS)
+        server.route("/private/", status=403)
+        yield server
+
+
+def test_parse_nginx():
+    entries = parse_index(NGINX, "https://mirror.example.com/pub/")
+    assert [entry["name"] for entry in entries] == [
+        "docs",
+        "release 1.0.tar.gz",
+        "a-very-long-file-name-that-nginx-truncates.txt",
+    ]
+    docs, release, long_name = entries
+    assert docs["is_dir"] and docs["size"] is None
+    assert docs["url"] == "https://mirror.example.com/pub/docs/"
+    assert release["size"] == 1048576
+    assert release["mtime"] == datetime(2026, 10, 15, 9, 5, tzinfo=timezone.utc)
+    assert not release["is_dir"]
+    assert long_name["size"] == 12
+
+
+def test_parse_apache():
+    entries = parse_index(APACHE, "https://mirror.example.com/pub/")
+    assert [(entry["name"], entry["is_dir"]) for entry in entries] == [("notes.txt", False), ("img", True)]
+    assert entries[0]["size"] == 1536
+    assert entries[0]["mtime"] == datetime(2026, 10, 16, 13, 40, tzinfo=timezone.utc)
+
+
+def test_list_directory_falls_back_to_index(server):
+    entries = httpr.Client().list_directory(f"{server.url}/pub/")
+    assert [entry["name"] for entry in entries][:2] == ["docs", "release 1.0.tar.gz"]
+    assert [request["method"] for request in server.requests] == ["PROPFIND", "GET"]
+
+
+def test_list_directory_webdav(server):
+    entries = httpr.Client().list_directory(f"{server.url}/dav/")
+    assert entries == [
+        {
+            "name": "report final.pdf",
+            "url": f"{server.url}/dav/report%20final.pdf",
+            "size": 2048,
+            "mtime": datetime(2026, 10, 16, 13, 40, tzinfo=timezone.utc),
+            "is_dir": False,
+        },
+        {"name": "photos", "url": f"{server.url}/dav/photos/", "size": None, "mtime": None, "is_dir": True},
+    ]
+    assert server.requests[0]["headers"]["depth"] == "1"
+
+
+def test_list_directory_modes(server):
+    client = httpr.Client()
+    client.list_directory(f"{server.url}/pub/", webdav=False)
+    assert [request["method"] for request in server.requests] == ["GET"]
+    with pytest.raises(httpr.HTTPStatusError, match="405"):
+        client.list_directory(f"{server.url}/pub/", webdav=True)
+    with pytest.raises(httpr.HTTPStatusError, match="403"):
+        client.list_directory(f"{server.url}/private/")
+
+
+def test_list_directory_async(server):
+    async def main():
+        async with httpr.AsyncClient() as client:
+            return await client.list_directory(f"{server.url}/dav/")
+
+    assert len(asyncio.run(main())) == 2
@@ -0,0 +1,118 @@
+"""Tests for httpr.testing.LocalServer."""
+
+import shutil
+import subprocess
+import time
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        yield server
+
+
+def test_route_and_recorded_requests(server):
+    server.route("/hello", body="hi", headers={"X-Test": "1"})
+    response = httpr.get(f"{server.url}/hello?a=b", headers={"X-Client": "yes"})
+    assert response.status_code == 200
+    assert response.text == "hi"
+    assert response.headers["x-test"] == "1"
+
+    [request] = server.requests
+    assert request["method"] == "GET"
+    assert request["path"] == "/hello?a=b"
+    assert request["headers"]["x-client"] == "yes"
+    assert request["tls_resumed"] is None
+    server.clear_requests()
+    assert server.requests == []
+
+
+def test_json_method_and_unmatched(server):
+    server.route("/items", method="POST", status=201, json={"id": 1})
+    response = httpr.post(f"{server.url}/items", json={"name": "x"})
+    assert response.status_code == 201
+    assert response.json() == {"id": 1}
+    assert response.headers["content-type"] == "application/json"
+    assert server.requests[0]["body"] == b'{"name":"x"}'
+
+    assert httpr.get(f"{server.url}/items").status_code == 404
+
+
+def test_latest_route_wins(server):
+    server.route("/flaky", status=503)
+    assert httpr.get(f"{server.url}/flaky").status_code == 503
+    server.route("/flaky", body="recovered")
+    assert httpr.get(f"{server.url}/flaky").text == "recovered"
+
+
+def test_echo(server):
+    server.echo("/echo")
+    data = httpr.put(f"{server.url}/echo?x=1", content=b"payload").json()
+    assert data["method"] == "PUT"
+    assert data["path"] == "/echo?x=1"
+    assert data["body"] == "payload"
+
+
+def test_delay_triggers_timeout(server):
+    server.route("/slow", delay=1.0)
+    with pytest.raises(httpr.TimeoutException):
+        httpr.get(f"{server.url}/slow", timeout=0.2)
+
+
+def test_redirect_chain(server):
+    server.route("/final", body="done")
+    server.redirect_chain("/start", "/final", hops=3)
+    response = httpr.get(f"{server.url}/start")
+    assert response.text == "done"
+    assert [r["path"] for r in server.requests] == ["/start", "/start/1", "/start/2", "/final"]
+
+
+def test_repeated_headers(server):
+    server.route("/cookies", headers=[("Set-Cookie", "a=1"), ("Set-Cookie", "b=2")])
+    client = httpr.Client()
+    client.get(f"{server.url}/cookies")
+    client.get(f"{server.url}/cookies")
+    assert server.requests[1]["headers"]["cookie"] in ("a=1; b=2", "b=2; a=1")
+
+
+def test_start_stop():
+    server = LocalServer()
+    with pytest.raises(RuntimeError):
+        server.url
+    server.start()
+    url = server.url
+    server.stop()
+    with pytest.raises(httpr.ConnectError):
+        httpr.get(url, timeout=2)
+
+
+def test_invalid_arguments():
+    with pytest.raises(ValueError):
+        LocalServer(tls_cert="cert.pem")
+    with pytest.raises(ValueError):
+        LocalServer().route("no-slash")
+    with pytest.raises(ValueError):
+        LocalServer().redirect("/a", "/b", status=200)
+
+
+@pytest.mark.skipif(shutil.which("openssl") is None, reason="openssl CLI not available")
+def test_tls(tmp_path):
+    cert, key = tmp_path / "cert.pem", tmp_path / "key.pem"
+    subprocess.run(
+        ["openssl", "req", "-x509", "-newkey", "rsa:2048", "-nodes", "-days", "1", "-subj", "/CN=localhost"]
+        + ["-keyout", str(key), "-out", str(cert)],
+        check=True,
+        capture_output=True,
+    )
+    with LocalServer(tls_cert=str(cert), tls_key=str(key)) as server:
+        server.route("/", body="secure")
+        assert server.url.startswith("https://")
+        start = time.monotonic()
+        response = httpr.get(server.url, verify=False)
+        assert response.text == "secure"
+        assert time.monotonic() - start < 5
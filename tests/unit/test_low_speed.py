@@ -0,0 +1,76 @@
+"""Tests for low_speed_limit / low_speed_time stalled-transfer detection."""
+
+import threading
+import time
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+
+
+class TrickleHandler(BaseHTTPRequestHandler):
+    """/trickle sends one byte every 100ms; /fast sends the whole body at once."""
+
+    def do_GET(self):
+        size = 20
+        self.send_response(200)
+        self.send_header("Content-Length", str(size))
+        self.end_headers()
+        if self.path.startswith("/fast"):
+            self.wfile.write(b"x" * size)
+            return
+        try:
+            for _ in range(size):
+                self.wfile.write(b"x")
+                self.wfile.flush()
+                time.sleep(0.1)
+        except (BrokenPipeError, ConnectionResetError):
+            pass
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def trickle_server():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), TrickleHandler)
+    port = server.server_address[1]
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{port}"
+    server.shutdown()
+
+
+def test_low_speed_aborts_trickling_body(trickle_server):
+    client = httpr.Client()
+    start = time.monotonic()
+    with pytest.raises(httpr.ReadTimeout):
+        client.get(trickle_server + "/trickle", low_speed_limit=100, low_speed_time=0.5)
+    assert time.monotonic() - start < 1.5
+
+
+def test_low_speed_client_default(trickle_server):
+    client = httpr.Client(low_speed_limit=100, low_speed_time=0.5)
+    with pytest.raises(httpr.ReadTimeout):
+        client.get(trickle_server + "/trickle")
+    # Disabled per request with a limit of 0
+    assert client.get(trickle_server + "/trickle", low_speed_limit=0).content == b"x" * 20
+
+
+def test_low_speed_streaming(trickle_server):
+    client = httpr.Client()
+    with pytest.raises(httpr.ReadTimeout):
+        with client.stream("GET", trickle_server + "/trickle", low_speed_limit=100, low_speed_time=0.5) as response:
+            for _ in response.iter_bytes():
+                pass
+
+
+def test_low_speed_fast_transfer_passes(trickle_server):
+    client = httpr.Client(low_speed_limit=100, low_speed_time=0.5)
+    assert client.get(trickle_server + "/fast").content == b"x" * 20
+
+
+def test_low_speed_time_requires_limit():
+    with pytest.raises(ValueError):
+        httpr.Client(low_speed_time=10)
@@ -0,0 +1,112 @@
+"""Tests for multipart file uploads with `files=` and `on_file_progress`."""
+
+import os
+import sys
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/upload", method="POST", body=b"ok")
+        yield server
+
+
+def write_files(directory, count):
+    paths = []
+    for i in range(count):
+        path = directory / f"part{i}.txt"
+        path.write_text(f"contents of part {i}\n")
+        paths.append(path)
+    return paths
+
+
+def test_mapping(server, tmp_path):
+    (path,) = write_files(tmp_path, 1)
+    response = httpr.post(f"{server.url}/upload", files={"document": str(path)})
+    assert response.status_code == 200
+    body = server.requests[0]["body"]
+    assert b'name="document"; filename="part0.txt"' in body
+    assert b"contents of part 0" in body
+
+
+def test_pairs_repeat_field(server, tmp_path):
+    paths = write_files(tmp_path, 300)
+    httpr.post(f"{server.url}/upload", files=[("upload", path) for path in paths])
+    body = server.requests[0]["body"]
+    assert body.count(b'name="upload"') == 300
+    for i in (0, 150, 299):
+        assert f'filename="part{i}.txt"'.encode() in body
+        assert f"contents of part {i}\n".encode() in body
+
+
+def test_request_files_keep_shape(tmp_path):
+    (path,) = write_files(tmp_path, 1)
+    request = httpr.Request("POST", "http://example.com", files={"a": str(path)})
+    assert request.files == {"a": str(path)}
+    request = httpr.Request("POST", "http://example.com", files=[("a", str(path)), ("a", str(path))])
+    assert request.files == [("a", str(path)), ("a", str(path))]
+
+
+def test_progress(server, tmp_path):
+    paths = write_files(tmp_path, 3)
+    big = tmp_path / "big.bin"
+    big.write_bytes(os.urandom(200_000))
+    paths.append(big)
+    events = []
+    httpr.post(
+        f"{server.url}/upload",
+        files=[("upload", path) for path in paths],
+        on_file_progress=events.append,
+    )
+    assert [event["index"] for event in events if event["sent"] == 0] == [0, 1, 2, 3]
+    assert {event["parts"] for event in events} == {4}
+    last = [event for event in events if event["index"] == 3]
+    assert last[0]["filename"] == "big.bin"
+    assert last[0]["field"] == "upload"
+    assert [event["sent"] for event in last] == sorted(event["sent"] for event in last)
+    assert last[-1]["sent"] == last[-1]["total"] == 200_000
+    assert len(last) > 2
+
+
+@pytest.mark.skipif(not sys.platform.startswith("linux"), reason="reads /proc/self/fd")
+def test_files_opened_lazily(server, tmp_path):
+    paths = write_files(tmp_path, 50)
+    open_counts = []
+
+    def count_open(event):
+        fds = os.listdir("/proc/self/fd")
+        targets = []
+        for fd in fds:
+            try:
+                targets.append(os.readlink(f"/proc/self/fd/{fd}"))
+            except OSError:
+                pass
+        open_counts.append(sum(target.startswith(str(tmp_path)) for target in targets))
+
+    httpr.post(
+        f"{server.url}/upload",
+        files=[("upload", path) for path in paths],
+        on_file_progress=count_open,
+    )
+    assert max(open_counts) <= 2
+
+
+def test_missing_file_fails_before_sending(server, tmp_path):
+    (path,) = write_files(tmp_path, 1)
+    with pytest.raises(httpr.RequestError, match="missing.txt"):
+        httpr.post(f"{server.url}/upload", files=[("a", path), ("b", tmp_path / "missing.txt")])
+    assert server.requests == []
+
+
+def test_invalid_arguments(server, tmp_path):
+    with pytest.raises(TypeError, match="files must be"):
+        httpr.post(f"{server.url}/upload", files=["not-a-pair"])
+    with pytest.raises(ValueError, match="on_file_progress requires files"):
+        httpr.post(f"{server.url}/upload", on_file_progress=print)
+    with pytest.raises(ValueError, match="must be callable"):
+        httpr.post(f"{server.url}/upload", files={"a": "x"}, on_file_progress=1)
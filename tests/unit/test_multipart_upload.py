@@ -0,0 +1,189 @@
+"""Tests for client.multipart_uploader()."""
+
+import os
+import re
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from urllib.parse import parse_qs, urlsplit
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+PAYLOAD = os.urandom(3500)
+
+
+class S3Handler(BaseHTTPRequestHandler):
+    """A minimal S3 multipart upload API keeping uploads in memory."""
+
+    protocol_version = "HTTP/1.1"
+    uploads: dict = {}
+    objects: dict = {}
+    aborted: list = []
+    part_requests: list = []
+    # part number -> (status, remaining failures)
+    failures: dict = {}
+
+    def reply(self, status, body=b"", headers=()):
+        self.send_response(status)
+        for name, value in headers:
+            self.send_header(name, value)
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def read_body(self):
+        return self.rfile.read(int(self.headers.get("Content-Length", 0)))
+
+    def do_POST(self):
+        url = urlsplit(self.path)
+        body = self.read_body()
+        if url.query == "uploads":
+            upload_id = f"upload-{len(S3Handler.uploads) + 1}"
+            S3Handler.uploads[upload_id] = {}
+            xml = f"<InitiateMultipartUploadResult><UploadId>{upload_id}</UploadId></InitiateMultipartUploadResult>"
+            return self.reply(200, xml.encode())
+        upload_id = parse_qs(url.query)["uploadId"][0]
+        parts = S3Handler.uploads.pop(upload_id)
+        numbers = [int(n) for n in re.findall(rb"<PartNumber>(\d+)</PartNumber>", body)]
+        etags = re.findall(rb"<ETag>([^<]*)</ETag>", body)
+        assert etags == [f"&quot;etag-{n}&quot;".encode() for n in numbers]
+        S3Handler.objects[url.path] = b"".join(parts[n] for n in numbers)
+        self.reply(200, b"<CompleteMultipartUploadResult/>")
+
+    def do_PUT(self):
+        url = urlsplit(self.path)
+        query = parse_qs(url.query)
+        part_number = int(query["partNumber"][0])
+        body = self.read_body()
+        S3Handler.part_requests.append(part_number)
+        status, remaining = S3Handler.failures.get(part_number, (200, 0))
+        if remaining:
+            S3Handler.failures[part_number] = (status, remaining - 1)
+            return self.reply(status)
+        S3Handler.uploads[query["uploadId"][0]][part_number] = body
+        self.reply(200, headers=[("ETag", f'"etag-{part_number}"')])
+
+    def do_DELETE(self):
+        S3Handler.aborted.append(parse_qs(urlsplit(self.path).query)["uploadId"][0])
+        self.reply(204)
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture
+def s3():
+    S3Handler.uploads, S3Handler.objects, S3Handler.aborted = {}, {}, []
+    S3Handler.part_requests, S3Handler.failures = [], {}
+    server = ThreadingHTTPServer(("127.0.0.1", 0), S3Handler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{server.server_address[1]}"
+    server.shutdown()
+
+
+@pytest.fixture
+def payload(tmp_path):
+    path = tmp_path / "payload.bin"
+    path.write_bytes(PAYLOAD)
+    return path
+
+
+def test_s3_upload(s3, payload):
+    events = []
+    uploader = httpr.Client().multipart_uploader(
+        f"{s3}/bucket/payload.bin", part_size=1000, max_concurrency=3, on_progress=events.append
+    )
+    result = uploader.upload(payload)
+    assert S3Handler.objects["/bucket/payload.bin"] == PAYLOAD
+    assert result["upload_id"] == "upload-1"
+    assert result["size"] == len(PAYLOAD)
+    assert result["result"] is None
+    assert result["parts"] == [
+        {"part_number": n, "etag": f'"etag-{n}"', "size": 1000 if n < 4 else 500} for n in range(1, 5)
+    ]
+    assert [event["completed"] for event in events] == [1, 2, 3, 4]
+    assert sorted(event["part_number"] for event in events) == [1, 2, 3, 4]
+    assert events[-1]["sent"] == events[-1]["total"] == len(PAYLOAD)
+    assert {event["parts"] for event in events} == {4}
+
+
+def test_failed_part_is_retried(s3, payload):
+    S3Handler.failures = {2: (503, 2)}
+    uploader = httpr.Client().multipart_uploader(f"{s3}/bucket/payload.bin", part_size=1000)
+    uploader.upload(payload)
+    assert S3Handler.objects["/bucket/payload.bin"] == PAYLOAD
+    assert S3Handler.part_requests.count(2) == 3
+    assert S3Handler.part_requests.count(1) == 1
+
+
+def test_failed_upload_is_aborted(s3, payload):
+    S3Handler.failures = {3: (403, 1)}
+    uploader = httpr.Client().multipart_uploader(f"{s3}/bucket/payload.bin", part_size=1000)
+    with pytest.raises(httpr.HTTPStatusError):
+        uploader.upload(payload)
+    # Client errors other than 408 and 429 are not retried
+    assert S3Handler.part_requests.count(3) == 1
+    assert S3Handler.aborted == ["upload-1"]
+    assert S3Handler.objects == {}
+
+    S3Handler.failures = {1: (500, 5)}
+    uploader = httpr.Client().multipart_uploader(f"{s3}/bucket/payload.bin", part_size=1000, part_retries=1)
+    with pytest.raises(httpr.HTTPStatusError):
+        uploader.upload(payload)
+    assert S3Handler.aborted == ["upload-1", "upload-2"]
+
+
+def test_callbacks(payload):
+    completed = []
+    with LocalServer() as server:
+        server.route("/parts", method="PUT", headers={"ETag": '"x"'})
+        uploader = httpr.Client().multipart_uploader(
+            initiate=lambda: "id/1",
+            part_url=f"{server.url}/parts?upload={{upload_id}}&n={{part_number}}",
+            complete=lambda upload_id, parts: completed.append((upload_id, parts)) or "done",
+            part_size=2000,
+            headers={"X-Upload": "yes"},
+        )
+        result = uploader.upload(payload)
+        requests = sorted(server.requests, key=lambda request: request["path"])
+    assert result["result"] == "done"
+    assert [request["path"] for request in requests] == ["/parts?upload=id%2F1&n=1", "/parts?upload=id%2F1&n=2"]
+    assert requests[0]["body"] + requests[1]["body"] == PAYLOAD
+    assert all(request["headers"]["x-upload"] == "yes" for request in requests)
+    upload_id, parts = completed[0]
+    assert upload_id == "id/1"
+    assert [part["part_number"] for part in parts] == [1, 2]
+
+
+def test_callback_failure_aborts(payload):
+    aborted = []
+
+    def complete(upload_id, parts):
+        raise RuntimeError("rejected")
+
+    with LocalServer() as server:
+        server.route("/parts", method="PUT")
+        uploader = httpr.Client().multipart_uploader(
+            initiate=lambda: "id",
+            part_url=lambda upload_id, part_number: f"{server.url}/parts",
+            complete=complete,
+            abort=aborted.append,
+        )
+        with pytest.raises(RuntimeError, match="rejected"):
+            uploader.upload(payload)
+    assert aborted == ["id"]
+
+
+def test_invalid_arguments(payload):
+    client = httpr.Client()
+    with pytest.raises(ValueError, match="initiate, part_url and complete"):
+        client.multipart_uploader(initiate=lambda: "id")
+    with pytest.raises(ValueError, match="part_size"):
+        client.multipart_uploader("http://127.0.0.1/object", part_size=0)
+    with pytest.raises(ValueError, match="must be callable"):
+        client.multipart_uploader("http://127.0.0.1/object", on_progress=1)
+    with pytest.raises(httpr.RequestError, match="missing"):
+        client.multipart_uploader("http://127.0.0.1/object").upload(payload.parent / "missing")
@@ -0,0 +1,99 @@
+"""Tests for client.paginate()."""
+
+import asyncio
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        yield server
+
+
+def paths(server):
+    return [request["path"] for request in server.requests]
+
+
+def test_cursor(server):
+    server.route("/users", json={"data": {"items": [1, 2], "next_cursor": "c2"}})
+    client = httpr.Client()
+    paginator = client.paginate(
+        f"{server.url}/users", strategy="cursor", item_path="data.items", next_path="data.next_cursor"
+    )
+    assert next(paginator) == 1
+    assert paths(server) == ["/users"]
+    # The second page repeats the cursor, which ends pagination
+    server.route("/users", json={"data": {"items": [3], "next_cursor": "c2"}})
+    assert list(paginator) == [2, 3]
+    assert paths(server) == ["/users", "/users?cursor=c2"]
+    assert paginator.pages == 2
+
+
+def test_header_callable_per_page(server):
+    server.route("/items", json=[1])
+    client = httpr.Client()
+    pages = iter(["1", "2"])
+    paginator = client.paginate(
+        f"{server.url}/items", strategy="page", max_pages=2, headers={"X-Page": lambda: next(pages)}
+    )
+    assert list(paginator) == [1, 1]
+    assert [request["headers"]["x-page"] for request in server.requests] == ["1", "2"]
+
+
+def test_page_numbers(server):
+    server.route("/items", json=[1, 2])
+    client = httpr.Client()
+    paginator = client.paginate(f"{server.url}/items", strategy="page", page_size=2, max_pages=3)
+    assert list(paginator) == [1, 2] * 3
+    assert paths(server) == [
+        "/items?limit=2&page=1",
+        "/items?limit=2&page=2",
+        "/items?limit=2&page=3",
+    ]
+
+
+def test_offset_stops_on_short_page(server):
+    server.route("/rows", json={"results": [{"id": 1}, {"id": 2}]})
+    client = httpr.Client()
+    paginator = client.paginate(
+        f"{server.url}/rows", strategy="offset", item_path="results", page_size=3, params={"q": "x"}
+    )
+    assert [row["id"] for row in paginator] == [1, 2]
+    assert paths(server) == ["/rows?q=x&limit=3&offset=0"]
+
+
+def test_link_header(server):
+    server.route("/p1", json=[1], headers={"Link": '</p2?token=a>; rel="next"'})
+    server.route("/p2", json=[2], headers={"Link": '</p1>; rel="prev"'})
+    client = httpr.Client()
+    assert list(client.paginate(f"{server.url}/p1", strategy="link")) == [1, 2]
+    assert paths(server) == ["/p1", "/p2?token=a"]
+
+
+def test_error_status(server):
+    server.route("/broken", status=500, json={"error": "boom"})
+    client = httpr.Client()
+    with pytest.raises(httpr.HTTPStatusError):
+        list(client.paginate(f"{server.url}/broken", strategy="page"))
+
+
+def test_invalid_arguments():
+    client = httpr.Client()
+    with pytest.raises(ValueError):
+        client.paginate("http://example.com", strategy="cursor")
+    with pytest.raises(ValueError):
+        client.paginate("http://example.com", strategy="pages")
+
+
+def test_async_paginate(server):
+    server.route("/items", json={"items": [1, 2], "next": None})
+
+    async def collect():
+        async with httpr.AsyncClient() as client:
+            return [item async for item in client.paginate(f"{server.url}/items", item_path="items", next_path="next")]
+
+    assert asyncio.run(collect()) == [1, 2]
@@ -0,0 +1,86 @@
+"""Tests for RFC 9457 problem details parsing."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+PROBLEM_XML = """<?xml version="1.0" encoding="UTF-8"?>
+<problem xmlns="urn:ietf:rfc:7807">
+  <type>https://example.com/probs/out-of-credit</type>
+  <title>You do not have enough credit.</title>
+  <status>403</status>
+  <balance>30</balance>
+  <accounts><i>/account/12345</i><i>/account/67890</i></accounts>
+</problem>"""
+
+
+@pytest.fixture(scope="module")
+def server():
+    with LocalServer() as server:
+        server.route(
+            "/json",
+            status=403,
+            json={
+                "type": "https://example.com/probs/out-of-credit",
+                "title": "You do not have enough credit.",
+                "status": 403,
+                "detail": "Your current balance is 30, but that costs 50.",
+                "instance": "/account/12345/msgs/abc",
+                "balance": 30,
+            },
+            headers={"Content-Type": "application/problem+json"},
+        )
+        server.route(
+            "/xml",
+            status=403,
+            body=PROBLEM_XML,
+            headers={"Content-Type": "application/problem+xml"},
+        )
+        server.route("/plain", status=404, json={"detail": "nope"})
+        server.route("/broken", status=500, body="{", headers={"Content-Type": "application/problem+json"})
+        yield server
+
+
+def test_problem_json(server):
+    problem = httpr.get(f"{server.url}/json").problem()
+    assert isinstance(problem, httpr.ProblemDetails)
+    assert problem.type == "https://example.com/probs/out-of-credit"
+    assert problem.title == "You do not have enough credit."
+    assert problem.status == 403
+    assert problem.detail == "Your current balance is 30, but that costs 50."
+    assert problem.instance == "/account/12345/msgs/abc"
+    assert problem.extensions == {"balance": 30}
+
+
+def test_problem_xml(server):
+    problem = httpr.get(f"{server.url}/xml").problem()
+    assert problem.status == 403
+    assert problem.title == "You do not have enough credit."
+    assert problem.detail is None
+    assert problem.extensions == {"balance": "30", "accounts": ["/account/12345", "/account/67890"]}
+
+
+def test_not_a_problem(server):
+    assert httpr.get(f"{server.url}/plain").problem() is None
+
+
+def test_invalid_problem_body(server):
+    response = httpr.get(f"{server.url}/broken")
+    with pytest.raises(Exception):
+        response.problem()
+    with pytest.raises(httpr.HTTPStatusError) as exc_info:
+        response.raise_for_status()
+    assert exc_info.value.problem is None
+
+
+def test_raise_for_status_attaches_problem(server):
+    with pytest.raises(httpr.HTTPStatusError) as exc_info:
+        httpr.get(f"{server.url}/xml").raise_for_status()
+    err = exc_info.value
+    assert err.problem.type == "https://example.com/probs/out-of-credit"
+    assert "You do not have enough credit." in str(err)
+
+    with pytest.raises(httpr.HTTPStatusError) as exc_info:
+        httpr.get(f"{server.url}/plain").raise_for_status()
+    assert exc_info.value.problem is None
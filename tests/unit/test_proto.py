@@ -0,0 +1,73 @@
+"""Tests for the proto= request option and Response.proto_into()."""
+
+import asyncio
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+class Message:
+    """Stand-in for a generated protobuf class (SerializeToString / ParseFromString)."""
+
+    def __init__(self, payload=b""):
+        self.payload = payload
+
+    def SerializeToString(self):
+        return b"\x0a" + bytes([len(self.payload)]) + self.payload
+
+    def ParseFromString(self, data):
+        self.payload = data[2:]
+
+
+class FromStringMessage(Message):
+    @classmethod
+    def FromString(cls, data):
+        return cls(b"via FromString: " + data[2:])
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/rpc", body=b"\x0a\x02ok", headers={"Content-Type": "application/x-protobuf"})
+        yield server
+
+
+def test_proto_bytes(server):
+    httpr.Client().post(f"{server.url}/rpc", proto=b"\x08\x96\x01")
+    request = server.requests[0]
+    assert request["body"] == b"\x08\x96\x01"
+    assert request["headers"]["content-type"] == "application/x-protobuf"
+
+
+def test_proto_message_and_content_type(server):
+    client = httpr.Client()
+    client.post(f"{server.url}/rpc", proto=Message(b"hi"))
+    client.put(f"{server.url}/rpc", proto=b"\x01", headers={"Content-Type": "application/protobuf"})
+    first, second = server.requests
+    assert first["body"] == b"\x0a\x02hi"
+    assert second["headers"]["content-type"] == "application/protobuf"
+
+
+def test_proto_conflicts(server):
+    with pytest.raises(ValueError, match="proto cannot be combined"):
+        httpr.Client().post(f"{server.url}/rpc", proto=b"\x01", json={"a": 1})
+    assert server.requests == []
+
+
+def test_proto_async_stream(server):
+    async def main():
+        async with httpr.AsyncClient() as client, client.stream("POST", f"{server.url}/rpc", proto=b"\x02") as response:
+            return response.read()
+
+    assert asyncio.run(main()) == b"\x0a\x02ok"
+    assert server.requests[0]["body"] == b"\x02"
+
+
+def test_proto_into(server):
+    response = httpr.get(f"{server.url}/rpc")
+    message = response.proto_into(Message)
+    assert isinstance(message, Message)
+    assert message.payload == b"ok"
+    assert response.proto_into(FromStringMessage).payload == b"via FromString: ok"
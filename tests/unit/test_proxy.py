@@ -0,0 +1,120 @@
+"""Tests for requests sent through client.proxy."""
+
+import socket
+import threading
+
+import pytest
+import trustme
+
+import httpr
+from httpr.testing import LocalServer
+
+
+class ForwardProxy:
+    """HTTP proxy forwarding plain requests and tunneling CONNECT, recording the request lines."""
+
+    def __init__(self):
+        self.listener = socket.create_server(("127.0.0.1", 0))
+        self.url = f"http://127.0.0.1:{self.listener.getsockname()[1]}"
+        self.lines = []
+        threading.Thread(target=self.serve, daemon=True).start()
+
+    def serve(self):
+        while True:
+            try:
+                conn, _ = self.listener.accept()
+            except OSError:
+                return
+            threading.Thread(target=self.handle, args=(conn,), daemon=True).start()
+
+    def handle(self, conn):
+        head = b""
+        while not head.endswith(b"\r\n\r\n"):
+            data = conn.recv(1)
+            if not data:
+                conn.close()
+                return
+            head += data
+        line = head.split(b"\r\n", 1)[0].decode()
+        self.lines.append(line)
+        method, target, _ = line.split(" ")
+        if method == "CONNECT":
+            authority = target
+        else:
+            authority = target.split("://", 1)[1].split("/", 1)[0]
+        host, port = authority.rsplit(":", 1)
+        upstream = socket.create_connection((host, int(port)))
+        if method == "CONNECT":
+            conn.sendall(b"HTTP/1.1 200 Connection established\r\n\r\n")
+        else:
+            upstream.sendall(head)
+        threading.Thread(target=relay, args=(upstream, conn), daemon=True).start()
+        relay(conn, upstream)
+
+    def close(self):
+        self.listener.close()
+
+
+def relay(source, target):
+    try:
+        while data := source.recv(65536):
+            target.sendall(data)
+    except OSError:
+        pass
+    finally:
+        for sock in (source, target):
+            try:
+                sock.shutdown(socket.SHUT_RDWR)
+            except OSError:
+                pass
+
+
+@pytest.fixture
+def proxy():
+    proxy = ForwardProxy()
+    yield proxy
+    proxy.close()
+
+
+@pytest.fixture(scope="module")
+def ca():
+    return trustme.CA()
+
+
+@pytest.fixture
+def tls_server(ca, tmp_path):
+    cert = ca.issue_cert("127.0.0.1")
+    cert_path, key_path = tmp_path / "cert.pem", tmp_path / "key.pem"
+    cert.private_key_and_cert_chain_pem.write_to_path(str(cert_path))
+    cert.private_key_pem.write_to_path(str(key_path))
+    with LocalServer(tls_cert=str(cert_path), tls_key=str(key_path)) as server:
+        server.echo("/echo")
+        yield server
+
+
+def test_set_proxy_keeps_follow_redirects(proxy):
+    with LocalServer() as server:
+        server.redirect("/old", "/new")
+        server.echo("/new")
+        client = httpr.Client(follow_redirects=False, timeout=10)
+        client.proxy = proxy.url
+        url = f"{server.url}/old"
+        response = client.get(url)
+    assert response.status_code == 302
+    assert client.proxy == proxy.url
+    assert proxy.lines == [f"GET {url} HTTP/1.1"]
+
+
+def test_set_proxy_keeps_tls_settings(proxy, tls_server, ca, tmp_path):
+    ca_file = tmp_path / "ca.pem"
+    ca.cert_pem.write_to_path(str(ca_file))
+    authority = tls_server.url.split("://", 1)[1]
+
+    client = httpr.Client(ca_cert_file=str(ca_file), timeout=10)
+    client.proxy = proxy.url
+    assert client.get(f"{tls_server.url}/echo").status_code == 200
+
+    client = httpr.Client(verify=False, timeout=10)
+    client.proxy = proxy.url
+    assert client.get(f"{tls_server.url}/echo").status_code == 200
+    assert proxy.lines == [f"CONNECT {authority} HTTP/1.1"] * 2
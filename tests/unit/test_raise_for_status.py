@@ -0,0 +1,63 @@
+"""Tests for Response.raise_for_status()."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture(scope="module")
+def server():
+    with LocalServer() as server:
+        server.route("/ok", body="fine")
+        server.route("/text", status=503, body="upstream is down")
+        server.route(
+            "/json",
+            status=400,
+            json={"error": "invalid_request", "message": "name is required", "trace_id": "abc"},
+        )
+        server.route(
+            "/latin1",
+            status=500,
+            body="caf\xe9".encode("latin-1"),
+            headers={"Content-Type": "text/plain; charset=iso-8859-1"},
+        )
+        server.route("/big", status=500, body="x" * 5000)
+        yield server
+
+
+def test_success_returns_response(server):
+    response = httpr.get(f"{server.url}/ok")
+    assert response.raise_for_status() is response
+
+
+def test_text_body_preview(server):
+    response = httpr.get(f"{server.url}/text")
+    with pytest.raises(httpr.HTTPStatusError) as exc_info:
+        response.raise_for_status()
+    err = exc_info.value
+    assert "Server error '503 Service Unavailable'" in str(err)
+    assert "upstream is down" in str(err)
+    assert err.status_code == 503
+    assert err.body_preview == "upstream is down"
+    assert err.error_fields is None
+    assert err.response is response
+
+
+def test_json_error_fields(server):
+    with pytest.raises(httpr.HTTPStatusError) as exc_info:
+        httpr.get(f"{server.url}/json").raise_for_status()
+    err = exc_info.value
+    assert "Client error '400 Bad Request'" in str(err)
+    assert "message: name is required" in str(err)
+    assert err.error_fields == {"error": "invalid_request", "message": "name is required"}
+
+
+def test_preview_is_decoded_and_capped(server):
+    with pytest.raises(httpr.HTTPStatusError) as exc_info:
+        httpr.get(f"{server.url}/latin1").raise_for_status()
+    assert exc_info.value.body_preview == "caf\xe9"
+
+    with pytest.raises(httpr.HTTPStatusError) as exc_info:
+        httpr.get(f"{server.url}/big").raise_for_status()
+    assert exc_info.value.body_preview == "x" * 1024 + "..."
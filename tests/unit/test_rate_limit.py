@@ -0,0 +1,89 @@
+"""Tests for max_download_rate / max_upload_rate."""
+
+import threading
+import time
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+
+PAYLOAD = b"x" * 200_000
+
+
+class BytesHandler(BaseHTTPRequestHandler):
+    """GET returns PAYLOAD, POST returns the size of the received body."""
+
+    def do_GET(self):
+        self.send_response(200)
+        self.send_header("Content-Length", str(len(PAYLOAD)))
+        self.end_headers()
+        self.wfile.write(PAYLOAD)
+
+    def do_POST(self):
+        length = int(self.headers.get("Content-Length", 0))
+        body = str(len(self.rfile.read(length))).encode()
+        self.send_response(200)
+        self.send_header("Content-Length", str(len(body)))
+        self.end_headers()
+        self.wfile.write(body)
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def bytes_server():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), BytesHandler)
+    port = server.server_address[1]
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{port}"
+    server.shutdown()
+
+
+def test_download_rate_per_request(bytes_server):
+    client = httpr.Client()
+    start = time.monotonic()
+    response = client.get(bytes_server, max_download_rate="400KB/s")
+    elapsed = time.monotonic() - start
+    assert response.content == PAYLOAD
+    assert elapsed >= 0.4
+
+
+def test_download_rate_client_default(bytes_server):
+    client = httpr.Client(max_download_rate=400_000)
+    start = time.monotonic()
+    assert len(client.get(bytes_server).content) == len(PAYLOAD)
+    assert time.monotonic() - start >= 0.4
+
+
+def test_upload_rate(bytes_server):
+    client = httpr.Client()
+    start = time.monotonic()
+    response = client.post(bytes_server, content=PAYLOAD, max_upload_rate="400 KB/s")
+    elapsed = time.monotonic() - start
+    assert response.text == str(len(PAYLOAD))
+    assert elapsed >= 0.4
+
+
+def test_streaming_download_rate(bytes_server):
+    client = httpr.Client()
+    start = time.monotonic()
+    with client.stream("GET", bytes_server, max_download_rate="400kB/s") as response:
+        received = b"".join(response.iter_bytes())
+    assert received == PAYLOAD
+    assert time.monotonic() - start >= 0.4
+
+
+def test_no_rate_limit_by_default(bytes_server):
+    client = httpr.Client()
+    start = time.monotonic()
+    client.get(bytes_server)
+    assert time.monotonic() - start < 0.4
+
+
+@pytest.mark.parametrize("rate", ["fast", "5 parsecs/s", 0, -1])
+def test_invalid_rate(rate):
+    with pytest.raises(ValueError):
+        httpr.Client(max_download_rate=rate)
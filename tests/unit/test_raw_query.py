@@ -0,0 +1,51 @@
+"""Tests for raw_query= and fragment preservation."""
+
+import asyncio
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/files")
+        server.route("/old", status=302, headers={"Location": "/files"})
+        yield server
+
+
+def test_raw_query_is_appended_verbatim(server):
+    client = httpr.Client(params={"key": "k"})
+    response = client.get(f"{server.url}/files?x=%2f", params={"page": "1"}, raw_query="a=1&a=2&weird=%2F&sig=a+b/=")
+    expected = "/files?x=%2f&page=1&a=1&a=2&weird=%2F&sig=a+b/="
+    assert server.requests[0]["path"] == expected
+    assert response.url == f"{server.url}{expected}"
+
+    httpr.get(f"{server.url}/files", raw_query="only=1")
+    assert server.requests[1]["path"] == "/files?only=1"
+
+    with client.stream("GET", f"{server.url}/files", raw_query="s=%20") as response:
+        response.read()
+    assert server.requests[2]["path"] == "/files?key=k&s=%20"
+
+
+def test_raw_query_rejects_unencoded(server):
+    for raw_query in ["a=b c", "a=1#x", "q=café"]:
+        with pytest.raises(ValueError, match="raw_query must be percent-encoded"):
+            httpr.get(f"{server.url}/files", raw_query=raw_query)
+    assert server.requests == []
+
+
+def test_fragment_is_preserved(server):
+    client = httpr.Client()
+    assert client.get(f"{server.url}/files?a=1#section-2").url == f"{server.url}/files?a=1#section-2"
+    assert client.get(f"{server.url}/old#top").url == f"{server.url}/files#top"
+    assert server.requests[0]["path"] == "/files?a=1"
+
+    async def main():
+        async with httpr.AsyncClient() as client:
+            return await client.get(f"{server.url}/files#x", raw_query="b=2")
+
+    assert asyncio.run(main()).url == f"{server.url}/files?b=2#x"
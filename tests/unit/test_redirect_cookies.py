@@ -0,0 +1,81 @@
+"""Tests for cookies set on redirect responses showing up in Response.cookies."""
+
+import asyncio
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+
+
+class LoginHandler(BaseHTTPRequestHandler):
+    """/login sets a session and redirects to /step, which sets another cookie and redirects to /home."""
+
+    def do_GET(self):
+        if self.path == "/login":
+            self.redirect("/step", ["session=abc", "step=1"])
+        elif self.path == "/step":
+            self.redirect("/home", ["step=2"])
+        else:
+            body = self.headers.get("Cookie", "").encode()
+            self.send_response(200)
+            self.send_header("Set-Cookie", "seen=yes")
+            self.send_header("Content-Length", str(len(body)))
+            self.end_headers()
+            self.wfile.write(body)
+
+    def redirect(self, location, cookies):
+        self.send_response(302)
+        self.send_header("Location", location)
+        for cookie in cookies:
+            self.send_header("Set-Cookie", cookie)
+        self.send_header("Content-Length", "0")
+        self.end_headers()
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def server():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), LoginHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{server.server_address[1]}"
+    server.shutdown()
+
+
+def test_redirect_cookies_in_response(server):
+    response = httpr.Client().get(f"{server}/login")
+    assert response.url == f"{server}/home"
+    assert response.cookies == {"session": "abc", "step": "2", "seen": "yes"}
+    # The jar sent the cookies of earlier hops to the final one
+    assert sorted(response.text.split("; ")) == ["session=abc", "step=2"]
+
+
+def test_redirect_cookies_without_cookie_store(server):
+    response = httpr.Client(cookie_store=False).get(f"{server}/login")
+    assert response.cookies == {"session": "abc", "step": "2", "seen": "yes"}
+    assert response.text == ""
+
+
+def test_stream(server):
+    with httpr.Client().stream("GET", f"{server}/login") as response:
+        assert response.cookies == {"session": "abc", "step": "2", "seen": "yes"}
+
+
+def test_cookies_are_per_request(server):
+    client = httpr.Client()
+    client.get(f"{server}/login")
+    response = client.get(f"{server}/home")
+    assert response.cookies == {"seen": "yes"}
+
+
+def test_async_client(server):
+    async def main():
+        async with httpr.AsyncClient() as client:
+            return await client.get(f"{server}/login")
+
+    response = asyncio.run(main())
+    assert response.cookies == {"session": "abc", "step": "2", "seen": "yes"}
@@ -0,0 +1,95 @@
+"""Tests for per-request redirect options and Response.history."""
+
+import asyncio
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.echo("/final")
+        server.redirect_chain("/start", "/final", hops=3)
+        server.redirect("/form", "/final", status=303, method="POST")
+        server.redirect("/keep", "/final", status=307, method="POST")
+        yield server
+
+
+def test_history(server):
+    response = httpr.Client().get(f"{server.url}/start")
+    assert response.url == f"{server.url}/final"
+    assert [hop.url for hop in response.history] == [
+        f"{server.url}/start",
+        f"{server.url}/start/1",
+        f"{server.url}/start/2",
+    ]
+    assert [hop.status_code for hop in response.history] == [302, 302, 302]
+    assert response.history[0].headers["location"] == "/start/1"
+    assert response.history[0].history == []
+
+    response = httpr.Client().get(f"{server.url}/final")
+    assert response.history == []
+
+
+def test_attempts_per_hop(server):
+    response = httpr.Client().get(f"{server.url}/start")
+    assert [attempt["url"] for attempt in response.attempts] == [
+        f"{server.url}/start",
+        f"{server.url}/start/1",
+        f"{server.url}/start/2",
+        f"{server.url}/final",
+    ]
+    assert [attempt["status"] for attempt in response.attempts] == [302, 302, 302, 200]
+    assert all(attempt["duration"] >= 0 for attempt in response.attempts)
+    assert response.retries == 0
+
+
+def test_follow_redirects_per_request(server):
+    client = httpr.Client()
+    response = client.get(f"{server.url}/start", follow_redirects=False)
+    assert response.status_code == 302
+    assert response.history == []
+
+    client = httpr.Client(follow_redirects=False)
+    assert client.get(f"{server.url}/start").status_code == 302
+    assert client.get(f"{server.url}/start", follow_redirects=True).status_code == 200
+
+
+def test_max_redirects_per_request(server):
+    client = httpr.Client(max_redirects=1)
+    with pytest.raises(httpr.TooManyRedirects):
+        client.get(f"{server.url}/start")
+    assert len(client.get(f"{server.url}/start", max_redirects=3).history) == 3
+
+    client = httpr.Client()
+    with pytest.raises(httpr.TooManyRedirects):
+        client.get(f"{server.url}/start", max_redirects=2)
+
+
+def test_method_and_body_on_redirect(server):
+    client = httpr.Client()
+    response = client.post(f"{server.url}/form", content=b"payload")
+    assert response.json()["method"] == "GET"
+    assert response.json()["body"] == ""
+
+    response = client.post(f"{server.url}/keep", content=b"payload")
+    assert response.json()["method"] == "POST"
+    assert response.json()["body"] == "payload"
+    assert response.json()["headers"]["referer"] == f"{server.url}/keep"
+
+
+def test_streaming_history(server):
+    with httpr.Client().stream("GET", f"{server.url}/start") as response:
+        assert len(response.history) == 3
+        assert response.history[-1].url == f"{server.url}/start/2"
+
+
+def test_async_per_request(server):
+    async def main():
+        async with httpr.AsyncClient() as client:
+            return await client.get(f"{server.url}/start", follow_redirects=False)
+
+    assert asyncio.run(main()).status_code == 302
@@ -0,0 +1,108 @@
+"""Tests for Client.queue() and RequestQueue."""
+
+import asyncio
+import threading
+import time
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/slow", body="slow", delay=0.2)
+        for name in ["a", "b", "c", "d"]:
+            server.route(f"/{name}", body=name)
+        yield server
+
+
+def test_submit_and_join(server):
+    client = httpr.Client()
+    with client.queue() as queue:
+        futures = [queue.submit(httpr.Request("GET", f"{server.url}/{name}")) for name in "abcd"]
+    assert queue.closed
+    assert len(queue) == 0
+    assert [future.result().text for future in futures] == ["a", "b", "c", "d"]
+    with pytest.raises(RuntimeError, match="closed"):
+        queue.submit(httpr.Request("GET", f"{server.url}/a"))
+
+
+def test_priority(server):
+    client = httpr.Client()
+    with client.queue(max_concurrency=1) as queue:
+        # Occupies the only slot while the others are queued
+        queue.submit(httpr.Request("GET", f"{server.url}/slow"))
+        time.sleep(0.05)
+        for name, priority in [("a", 0), ("b", 5), ("c", 0), ("d", 9)]:
+            queue.submit(httpr.Request("GET", f"{server.url}/{name}"), priority=priority)
+    assert [r["path"] for r in server.requests] == ["/slow", "/d", "/b", "/a", "/c"]
+
+
+def test_deadline(server):
+    client = httpr.Client()
+    queue = client.queue(max_concurrency=1)
+    queue.submit(httpr.Request("GET", f"{server.url}/slow"), priority=1)
+    late = queue.submit(httpr.Request("GET", f"{server.url}/a"), deadline=0.05)
+    with pytest.raises(httpr.PoolTimeout, match="Deadline passed"):
+        late.result(timeout=5)
+    assert queue.join(timeout=5)
+    queue.close()
+    assert [r["path"] for r in server.requests] == ["/slow"]
+
+
+def test_max_per_host(server):
+    client = httpr.Client()
+    started = time.monotonic()
+    with client.queue(max_per_host=1) as queue:
+        futures = [queue.submit(httpr.Request("GET", f"{server.url}/slow")) for _ in range(3)]
+    assert time.monotonic() - started >= 0.6
+    assert all(future.result().text == "slow" for future in futures)
+
+
+def test_per_host_rate(server):
+    client = httpr.Client()
+    started = time.monotonic()
+    with client.queue(per_host_rate=10) as queue:
+        for _ in range(4):
+            queue.submit(httpr.Request("GET", f"{server.url}/a"))
+    # Starts at 0, 0.1, 0.2 and 0.3 seconds
+    assert time.monotonic() - started >= 0.3
+
+
+def test_errors_callbacks_and_cancel(server):
+    client = httpr.Client()
+    called = threading.Event()
+    with client.queue(max_concurrency=1) as queue:
+        queue.submit(httpr.Request("GET", f"{server.url}/slow"))
+        failed = queue.submit(
+            httpr.Request("GET", "http://127.0.0.1:1/"), callback=lambda future: called.set()
+        )
+        cancelled = queue.submit(httpr.Request("GET", f"{server.url}/a"))
+        assert cancelled.cancel()
+    assert isinstance(failed.exception(), httpr.ConnectError)
+    assert called.wait(5)
+    assert cancelled.cancelled()
+    assert "/a" not in [r["path"] for r in server.requests]
+
+
+def test_asyncio(server):
+    async def main():
+        async with httpr.AsyncClient() as client:
+            with client.queue() as queue:
+                future = queue.submit(httpr.Request("GET", f"{server.url}/b"))
+                response = await asyncio.wrap_future(future)
+        return response.text
+
+    assert asyncio.run(main()) == "b"
+
+
+def test_invalid_limits():
+    client = httpr.Client()
+    for kwargs in [{"max_concurrency": 0}, {"max_per_host": 0}, {"per_host_rate": 0}]:
+        with pytest.raises(ValueError):
+            client.queue(**kwargs)
+    with client.queue() as queue, pytest.raises(ValueError, match="deadline"):
+        queue.submit(httpr.Request("GET", "http://127.0.0.1/"), deadline=-1)
@@ -0,0 +1,48 @@
+"""Tests for Response.encoding overrides and Response.text_with_encoding()."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+JAPANESE = "日本語のテキスト"
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        utf8 = {"Content-Type": "text/plain; charset=utf-8"}
+        server.route("/sjis", body=JAPANESE.encode("shift_jis"), headers=utf8)
+        latin1 = {"Content-Type": "text/plain; charset=iso-8859-1"}
+        server.route("/bom", body=b"\xef\xbb\xbfcaf\xc3\xa9", headers=latin1)
+        yield server
+
+
+def test_set_encoding_before_text(server):
+    response = httpr.get(f"{server.url}/sjis")
+    response.encoding = "shift_jis"
+    assert response.text == JAPANESE
+    assert response.encoding == "shift_jis"
+
+
+def test_set_encoding_overrides_bom(server):
+    response = httpr.get(f"{server.url}/bom")
+    # A detected encoding yields to the byte order mark
+    assert response.text == "café"
+    assert response.encoding == "UTF-8"
+
+    response.encoding = "iso-8859-1"
+    assert response.text == "ï»¿cafÃ©"
+    assert response.encoding == "iso-8859-1"
+
+    response.encoding = None
+    assert response.text == "café"
+
+
+def test_text_with_encoding(server):
+    response = httpr.get(f"{server.url}/sjis")
+    assert response.text_with_encoding("shift_jis") == JAPANESE
+    assert response.encoding == "utf-8"
+    assert response.text != JAPANESE
+    with pytest.raises(LookupError, match="unknown encoding: klingon"):
+        response.text_with_encoding("klingon")
@@ -0,0 +1,135 @@
+"""Tests for client.robots() and client.sitemap()."""
+
+import asyncio
+import gzip
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+ROBOTS = """\
+User-agent: *
+Disallow: /private/
+Crawl-delay: 1.5
+
+User-agent: BadBot
+Disallow: /
+
+Sitemap: https://example.com/sitemap.xml
+"""
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        yield server
+
+
+def urlset(*locs):
+    entries = "".join(f"<url><loc>{loc}</loc></url>" for loc in locs)
+    return f'<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">{entries}</urlset>'
+
+
+def test_robots(server):
+    server.route("/robots.txt", body=ROBOTS)
+    client = httpr.Client()
+    robots = client.robots(f"{server.url}/some/page?q=1")
+    assert server.requests[0]["path"] == "/robots.txt"
+    assert robots.allowed("MyBot/1.0", "/public")
+    assert not robots.allowed("MyBot/1.0", f"{server.url}/private/x")
+    assert not robots.allowed("badbot", "/public")
+    assert robots.crawl_delay("mybot") == 1.5
+    assert robots.crawl_delay("badbot") is None
+    assert robots.sitemaps == ["https://example.com/sitemap.xml"]
+
+
+def test_robots_status(server):
+    client = httpr.Client()
+    # No route: 404 allows everything
+    assert client.robots(server.url).allowed("mybot", "/private/")
+    server.route("/robots.txt", status=503)
+    assert not client.robots(server.url).allowed("mybot", "/")
+
+
+def test_robots_parse():
+    robots = httpr.RobotsTxt("User-agent: *\nDisallow: /*.pdf$\n")
+    assert not robots.allowed("mybot", "/a.pdf")
+    assert robots.allowed("mybot", "/a.pdf.html")
+    assert robots.sitemaps == []
+
+
+def test_sitemap_index(server):
+    server.route(
+        "/sitemap.xml",
+        body=(
+            '<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">'
+            f"<sitemap><loc>{server.url}/a.xml.gz</loc></sitemap>"
+            f"<sitemap><loc>{server.url}/b.txt</loc></sitemap>"
+            f"<sitemap><loc>{server.url}/sitemap.xml</loc></sitemap>"
+            "</sitemapindex>"
+        ),
+    )
+    server.route(
+        "/a.xml.gz",
+        body=gzip.compress(urlset("https://example.com/1", "https://example.com/2").encode()),
+        headers={"Content-Type": "application/gzip"},
+    )
+    server.route("/b.txt", body="https://example.com/3\n")
+    client = httpr.Client()
+    entries = client.sitemap(f"{server.url}/sitemap.xml")
+    assert next(entries) == "https://example.com/1"
+    assert [r["path"] for r in server.requests] == ["/sitemap.xml", "/a.xml.gz"]
+    assert list(entries) == ["https://example.com/2", "https://example.com/3"]
+    # The index listing itself is not fetched again
+    assert [r["path"] for r in server.requests] == ["/sitemap.xml", "/a.xml.gz", "/b.txt"]
+
+
+def test_sitemap_errors(server):
+    server.route("/feed.xml", body="<feed></feed>")
+    client = httpr.Client()
+    with pytest.raises(Exception, match="root element"):
+        list(client.sitemap(f"{server.url}/feed.xml"))
+    with pytest.raises(httpr.HTTPStatusError):
+        list(client.sitemap(f"{server.url}/missing.xml"))
+
+
+def test_async_client(server):
+    server.route("/robots.txt", body=ROBOTS)
+    server.route("/sitemap.xml", body=urlset("https://example.com/1"))
+
+    async def run():
+        async with httpr.AsyncClient() as client:
+            robots = await client.robots(server.url)
+            entries = [entry async for entry in client.sitemap(f"{server.url}/sitemap.xml")]
+            return robots.allowed("mybot", "/private/"), entries
+
+    assert asyncio.run(run()) == (False, ["https://example.com/1"])
+
+
+def test_respect_robots_txt(server):
+    server.route("/robots.txt", body=ROBOTS)
+    server.route("/public", body="ok")
+    server.route("/private/data", body="secret")
+    client = httpr.Client(headers={"User-Agent": "MyBot/1.0"}, respect_robots_txt=True)
+    assert client.get(f"{server.url}/public").text == "ok"
+    with pytest.raises(httpr.RobotsDisallowed, match="/private/data"):
+        client.get(f"{server.url}/private/data")
+    # A per-request user agent selects its own group
+    with pytest.raises(httpr.RobotsDisallowed):
+        client.get(f"{server.url}/public", headers={"User-Agent": "BadBot"})
+    # robots.txt is fetched once and cached
+    assert [r["path"] for r in server.requests] == ["/robots.txt", "/public"]
+    assert server.requests[0]["headers"]["user-agent"] == "MyBot/1.0"
+
+
+def test_robots_cache_ttl(server):
+    server.route("/robots.txt", body="User-agent: *\nDisallow: /a\n")
+    server.route("/a", body="a")
+    client = httpr.Client(respect_robots_txt=True, robots_cache_ttl=0)
+    with pytest.raises(httpr.RobotsDisallowed):
+        client.get(f"{server.url}/a")
+    server.route("/robots.txt", body="")
+    assert client.get(f"{server.url}/a").text == "a"
+    with pytest.raises(ValueError, match="robots_cache_ttl"):
+        httpr.Client(respect_robots_txt=True, robots_cache_ttl=-1)
@@ -0,0 +1,30 @@
+"""Tests for httpr.runtime_stats()."""
+
+import httpr
+from httpr.testing import LocalServer
+
+
+def test_snapshot():
+    stats = httpr.runtime_stats()
+    assert isinstance(stats, httpr.RuntimeStats)
+    assert stats.workers == 1
+    assert stats.max_blocking_threads == 512
+    assert stats.queue_depth >= 0
+    assert stats.busy_time >= 0.0
+    assert "alive_tasks=" in repr(stats)
+
+
+def test_counts_tasks_and_blocking_threads():
+    with LocalServer() as server:
+        server.route("/slow", body=b"ok", delay=0.3)
+        client = httpr.Client()
+        before = httpr.runtime_stats()
+        results = client.fetch_all([f"{server.url}/slow"] * 3)
+        assert httpr.runtime_stats().alive_tasks >= before.alive_tasks + 3
+        assert all(response.status_code == 200 for response in results)
+
+        # DNS lookups run on the blocking pool, whose threads linger when idle
+        port = server.url.rsplit(":", 1)[1]
+        client.get(f"http://localhost:{port}/slow")
+        assert httpr.runtime_stats().blocking_threads >= 1
+        assert httpr.runtime_stats().busy_time > before.busy_time
@@ -0,0 +1,29 @@
+"""Tests for parsing Server-Timing headers into response.server_timing."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/timed", body=b"ok", headers={"Server-Timing": 'db;dur=53.2;desc="Query, users", cache;desc=hit'})
+        server.route("/plain", body=b"ok")
+        yield server
+
+
+def test_server_timing(server):
+    response = httpr.get(f"{server.url}/timed")
+    db, cache = response.server_timing
+    assert (db.name, db.duration, db.description) == ("db", 53.2, "Query, users")
+    assert (cache.name, cache.duration, cache.description) == ("cache", None, "hit")
+    assert isinstance(db, httpr.ServerTiming)
+    assert repr(db) == '<ServerTiming db 53.2ms "Query, users">'
+    assert httpr.get(f"{server.url}/plain").server_timing == []
+
+
+def test_streaming_server_timing(server):
+    with httpr.Client().stream("GET", f"{server.url}/timed") as response:
+        assert [metric.name for metric in response.server_timing] == ["db", "cache"]
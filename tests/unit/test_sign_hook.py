@@ -0,0 +1,104 @@
+"""Tests for the client-level sign= hook."""
+
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+
+
+class EchoHandler(BaseHTTPRequestHandler):
+    """Stores the last request's path and headers."""
+
+    last = {}
+
+    def handle_request(self):
+        length = int(self.headers.get("Content-Length", 0))
+        self.rfile.read(length)
+        EchoHandler.last = {"path": self.path, "headers": self.headers}
+        self.send_response(200)
+        self.send_header("Content-Length", "0")
+        self.end_headers()
+
+    do_GET = handle_request
+    do_POST = handle_request
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def base_url():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), EchoHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{server.server_address[1]}"
+    server.shutdown()
+
+
+def test_sign_sees_final_request(base_url):
+    calls = []
+
+    def sign(method, url, headers, body):
+        calls.append((method, url, headers, body))
+        return {"X-Signature": f"{method}:{len(body)}"}
+
+    client = httpr.Client(
+        sign=sign,
+        headers={"X-Client": "1"},
+        params={"v": "2"},
+        auth_bearer="token",
+    )
+    client.post(base_url + "/items", json={"a": 1}, headers={"X-Request": "1"})
+
+    method, url, headers, body = calls[0]
+    assert method == "POST"
+    assert url == base_url + "/items?v=2"
+    assert headers["x-client"] == "1"
+    assert headers["x-request"] == "1"
+    assert headers["authorization"] == "Bearer token"
+    assert headers["content-type"] == "application/json"
+    assert body == b'{"a":1}'
+    assert EchoHandler.last["headers"]["X-Signature"] == "POST:7"
+
+
+def test_sign_without_body_and_none_result(base_url):
+    seen = []
+    client = httpr.Client(sign=lambda method, url, headers, body: seen.append(body))
+    client.get(base_url + "/plain")
+    assert seen == [None]
+    assert EchoHandler.last["path"] == "/plain"
+
+
+def test_sign_sees_hmac_auth_header(base_url):
+    seen = {}
+
+    def sign(method, url, headers, body):
+        seen.update(headers)
+
+    client = httpr.Client(auth=httpr.HmacAuth("key", "secret"), sign=sign)
+    client.get(base_url + "/")
+    assert seen["authorization"].startswith("HMAC key:")
+
+
+def test_sign_exception_propagates(base_url):
+    def sign(method, url, headers, body):
+        raise KeyError("no key")
+
+    EchoHandler.last = {}
+    with pytest.raises(KeyError, match="no key"):
+        httpr.Client(sign=sign).get(base_url + "/never")
+    assert EchoHandler.last == {}
+
+
+def test_sign_must_be_callable():
+    with pytest.raises(ValueError, match="callable"):
+        httpr.Client(sign="not callable")
+
+
+@pytest.mark.asyncio
+async def test_async_sign(base_url):
+    async with httpr.AsyncClient(sign=lambda *args: {"X-Signature": "async"}) as client:
+        await client.get(base_url + "/async")
+    assert EchoHandler.last["headers"]["X-Signature"] == "async"
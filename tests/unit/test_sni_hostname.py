@@ -0,0 +1,61 @@
+"""Tests for sni_hostname and allow_sni_mismatch."""
+
+import pytest
+import trustme
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture(scope="module")
+def ca():
+    return trustme.CA()
+
+
+@pytest.fixture
+def ca_file(ca, tmp_path):
+    path = tmp_path / "ca.pem"
+    ca.cert_pem.write_to_path(str(path))
+    return str(path)
+
+
+@pytest.fixture
+def server(ca, tmp_path):
+    cert = ca.issue_cert("localhost", "127.0.0.1")
+    cert_path, key_path = tmp_path / "cert.pem", tmp_path / "key.pem"
+    cert.private_key_and_cert_chain_pem.write_to_path(str(cert_path))
+    cert.private_key_pem.write_to_path(str(key_path))
+    with LocalServer(tls_cert=str(cert_path), tls_key=str(key_path)) as server:
+        server.echo("/echo")
+        yield server
+
+
+def test_sni_hostname(server, ca_file):
+    client = httpr.Client(ca_cert_file=ca_file, allow_sni_mismatch=True)
+    url = f"https://origin.invalid:{server.port}/echo?a=1"
+    response = client.get(url, sni_hostname="localhost")
+    assert response.status_code == 200
+    assert response.url == url
+    assert server.requests[-1]["headers"]["host"] == f"origin.invalid:{server.port}"
+
+
+def test_sni_mismatch_needs_flag(server, ca_file):
+    client = httpr.Client(ca_cert_file=ca_file)
+    url = f"https://origin.invalid:{server.port}/echo"
+    with pytest.raises(ValueError, match="allow_sni_mismatch"):
+        client.get(url, sni_hostname="localhost")
+    with pytest.raises(ValueError, match="does not match the TLS server name '127.0.0.1'"):
+        client.get(f"{server.url}/echo", headers={"Host": "other.invalid"})
+    assert server.requests == []
+
+    # A Host header naming the server name is no mismatch
+    response = client.get(url, sni_hostname="localhost", headers={"Host": f"localhost:{server.port}"})
+    assert response.status_code == 200
+
+
+def test_invalid_sni_hostname(server):
+    client = httpr.Client(allow_sni_mismatch=True)
+    with pytest.raises(ValueError, match="Invalid sni_hostname"):
+        client.get(f"{server.url}/echo", sni_hostname="localhost:443")
+    with pytest.raises(ValueError, match="requires an https URL"):
+        client.get("http://127.0.0.1:1/", sni_hostname="localhost")
@@ -0,0 +1,39 @@
+"""Tests for Response.sniff()."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+PNG = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR"
+ENGLISH = "<html><body><p>The server says this is an image, but it is not the truth of the matter.</p></body></html>"
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/png", body=PNG, headers={"Content-Type": "text/html"})
+        server.route("/page", body=ENGLISH, headers={"Content-Type": "image/png"})
+        server.route("/json", body='{"a": [1, 2]}', headers={"Content-Type": "text/plain"})
+        server.route("/german", body="Das ist nicht der Hund, und es ist auch nicht die Katze von mir.")
+        yield server
+
+
+def test_sniff_binary(server):
+    sniffed = httpr.Client().get(f"{server.url}/png").sniff()
+    assert sniffed.mime_type == "image/png"
+    assert sniffed.declared_type == "text/html"
+    assert sniffed.language is None
+
+
+def test_sniff_text(server):
+    client = httpr.Client()
+    sniffed = client.get(f"{server.url}/page").sniff()
+    assert (sniffed.mime_type, sniffed.language, sniffed.declared_type) == ("text/html", "en", "image/png")
+    assert repr(sniffed) == "<SniffResult text/html (en)>"
+
+    sniffed = client.get(f"{server.url}/json").sniff()
+    assert (sniffed.mime_type, sniffed.language) == ("application/json", None)
+
+    sniffed = client.get(f"{server.url}/german").sniff()
+    assert (sniffed.mime_type, sniffed.language) == ("text/plain", "de")
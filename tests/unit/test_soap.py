@@ -0,0 +1,131 @@
+"""Tests for Client.soap() and the httpr.soap helpers."""
+
+import asyncio
+import xml.etree.ElementTree as ET
+
+import pytest
+
+import httpr
+from httpr.soap import SOAP11_NS, SOAP12_NS, build_envelope, parse_envelope
+from httpr.testing import LocalServer
+
+STOCK_NS = "urn:example:stock"
+
+PRICE_RESPONSE = f"""<?xml version="1.0"?>
+<soap:Envelope xmlns:soap="{SOAP11_NS}" xmlns:m="{STOCK_NS}">
+  <soap:Header><m:RequestId>abc</m:RequestId></soap:Header>
+  <soap:Body>
+    <m:GetPriceResponse>
+      <m:Price>34.5</m:Price>
+      <m:Tag>a</m:Tag>
+      <m:Tag>b</m:Tag>
+    </m:GetPriceResponse>
+  </soap:Body>
+</soap:Envelope>"""
+
+FAULT_11 = f"""<soap:Envelope xmlns:soap="{SOAP11_NS}">
+  <soap:Body>
+    <soap:Fault>
+      <faultcode>soap:Client</faultcode>
+      <faultstring>Unknown symbol</faultstring>
+      <faultactor>https://example.com/stock</faultactor>
+      <detail><StockError><Symbol>XXX</Symbol></StockError></detail>
+    </soap:Fault>
+  </soap:Body>
+</soap:Envelope>"""
+
+FAULT_12 = f"""<env:Envelope xmlns:env="{SOAP12_NS}">
+  <env:Body>
+    <env:Fault>
+      <env:Code><env:Value>env:Receiver</env:Value></env:Code>
+      <env:Reason><env:Text xml:lang="en">Database down</env:Text></env:Reason>
+    </env:Fault>
+  </env:Body>
+</env:Envelope>"""
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/stock", body=PRICE_RESPONSE, headers={"Content-Type": "text/xml"})
+        server.route("/fault11", status=500, body=FAULT_11, headers={"Content-Type": "text/xml"})
+        server.route("/fault12", status=500, body=FAULT_12, headers={"Content-Type": "application/soap+xml"})
+        server.route("/broken", status=503, body="Service Unavailable")
+        yield server
+
+
+def test_build_envelope():
+    xml = build_envelope(
+        {"GetPrice": {"Symbol": "ACME", "Exchange": None, "Tag": ["a", "b"], "Live": True}},
+        header={"{urn:auth}Token": "secret"},
+        namespace=STOCK_NS,
+    )
+    root = ET.fromstring(xml)
+    assert root.tag == f"{{{SOAP11_NS}}}Envelope"
+    assert root.findtext(f"{{{SOAP11_NS}}}Header/{{urn:auth}}Token") == "secret"
+    operation = root.find(f"{{{SOAP11_NS}}}Body/{{{STOCK_NS}}}GetPrice")
+    assert operation.findtext(f"{{{STOCK_NS}}}Symbol") == "ACME"
+    assert operation.findtext(f"{{{STOCK_NS}}}Live") == "true"
+    assert [tag.text for tag in operation.findall(f"{{{STOCK_NS}}}Tag")] == ["a", "b"]
+    nil = operation.find(f"{{{STOCK_NS}}}Exchange")
+    assert nil.get("{http://www.w3.org/2001/XMLSchema-instance}nil") == "true"
+
+
+def test_parse_envelope():
+    result = parse_envelope(PRICE_RESPONSE.encode())
+    assert result["header"] == {"RequestId": "abc"}
+    assert result["body"] == {"GetPriceResponse": {"Price": "34.5", "Tag": ["a", "b"]}}
+    with pytest.raises(ValueError, match="Expected a SOAP Envelope"):
+        parse_envelope(b"<html/>")
+
+
+def test_soap_11_dict(server):
+    result = httpr.Client().soap(
+        f"{server.url}/stock", "urn:example:stock#GetPrice", {"GetPrice": {"Symbol": "ACME"}}, namespace=STOCK_NS
+    )
+    assert result["body"]["GetPriceResponse"]["Price"] == "34.5"
+    request = server.requests[0]
+    assert request["method"] == "POST"
+    assert request["headers"]["soapaction"] == '"urn:example:stock#GetPrice"'
+    assert request["headers"]["content-type"] == "text/xml; charset=utf-8"
+    assert ET.fromstring(request["body"]).findtext(f".//{{{STOCK_NS}}}Symbol") == "ACME"
+
+
+def test_soap_12_xml(server):
+    envelope = f'<env:Envelope xmlns:env="{SOAP12_NS}"><env:Body><GetPrice/></env:Body></env:Envelope>'
+    httpr.Client().soap(f"{server.url}/stock", "urn:GetPrice", envelope)
+    request = server.requests[0]
+    assert "soapaction" not in request["headers"]
+    assert request["headers"]["content-type"] == 'application/soap+xml; charset=utf-8; action="urn:GetPrice"'
+    assert request["body"] == envelope.encode()
+
+
+def test_soap_fault_11(server):
+    with pytest.raises(httpr.SoapFault) as exc_info:
+        httpr.Client().soap(f"{server.url}/fault11", "urn:GetPrice", {"GetPrice": {}})
+    fault = exc_info.value
+    assert isinstance(fault, httpr.HTTPError)
+    assert fault.code == "soap:Client"
+    assert fault.reason == "Unknown symbol"
+    assert fault.actor == "https://example.com/stock"
+    assert fault.detail == {"StockError": {"Symbol": "XXX"}}
+    assert fault.response.status_code == 500
+
+
+def test_soap_fault_12(server):
+    with pytest.raises(httpr.SoapFault, match="env:Receiver: Database down") as exc_info:
+        httpr.Client().soap(f"{server.url}/fault12", "urn:GetPrice", {"GetPrice": {}}, version="1.2")
+    assert exc_info.value.detail is None
+
+
+def test_soap_http_error(server):
+    with pytest.raises(httpr.HTTPStatusError, match="503"):
+        httpr.Client().soap(f"{server.url}/broken", "urn:GetPrice", {"GetPrice": {}})
+
+
+def test_soap_async(server):
+    async def main():
+        async with httpr.AsyncClient() as client:
+            return await client.soap(f"{server.url}/stock", "urn:GetPrice", {"GetPrice": {}})
+
+    assert asyncio.run(main())["header"] == {"RequestId": "abc"}
@@ -0,0 +1,69 @@
+"""Tests for the errno, os_error and remote_address of network errors."""
+
+import errno
+import socket
+import struct
+import threading
+
+import pytest
+
+import httpr
+
+
+def closed_port():
+    sock = socket.socket()
+    sock.bind(("127.0.0.1", 0))
+    port = sock.getsockname()[1]
+    sock.close()
+    return port
+
+
+@pytest.fixture
+def resetting_server():
+    """Sends the head and part of the body, then resets the connection."""
+    listener = socket.socket()
+    listener.bind(("127.0.0.1", 0))
+    listener.listen()
+
+    def serve():
+        try:
+            conn, _ = listener.accept()
+        except OSError:
+            return
+        data = b""
+        while b"\r\n\r\n" not in data:
+            data += conn.recv(65536)
+        conn.sendall(b"HTTP/1.1 200 OK\r\nContent-Length: 1000\r\n\r\npartial")
+        # Close with SO_LINGER 0 to send RST instead of FIN
+        conn.setsockopt(socket.SOL_SOCKET, socket.SO_LINGER, struct.pack("ii", 1, 0))
+        conn.close()
+
+    threading.Thread(target=serve, daemon=True).start()
+    yield listener.getsockname()[1]
+    listener.close()
+
+
+def test_connection_refused():
+    port = closed_port()
+    client = httpr.Client(retry_idempotent_on_connection_error=False)
+    with pytest.raises(httpr.ConnectError) as exc_info:
+        client.get(f"http://127.0.0.1:{port}/")
+    assert exc_info.value.errno == errno.ECONNREFUSED
+    assert exc_info.value.os_error == "Connection refused"
+    assert exc_info.value.remote_address == ("127.0.0.1", port)
+
+
+def test_connection_reset_while_reading(resetting_server):
+    client = httpr.Client(retry_idempotent_on_connection_error=False)
+    with pytest.raises(httpr.ReadError) as exc_info:
+        client.get(f"http://127.0.0.1:{resetting_server}/")
+    assert exc_info.value.errno == errno.ECONNRESET
+    assert exc_info.value.remote_address == ("127.0.0.1", resetting_server)
+
+
+def test_defaults_without_socket_error():
+    with pytest.raises(httpr.DNSError) as exc_info:
+        httpr.Client().get("http://thishostdoesnotexist12345.invalid/")
+    assert exc_info.value.errno is None
+    assert exc_info.value.os_error is None
+    assert exc_info.value.remote_address is None
@@ -0,0 +1,126 @@
+"""Tests for retry_idempotent_on_connection_error and the HTTP/2 keep-alive options."""
+
+import socket
+import threading
+
+import pytest
+
+import httpr
+
+RESPONSE = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: keep-alive\r\n\r\nok"
+
+
+def read_request(conn):
+    data = b""
+    while b"\r\n\r\n" not in data:
+        chunk = conn.recv(65536)
+        if not chunk:
+            return None
+        data += chunk
+    head, _, body = data.partition(b"\r\n\r\n")
+    for line in head.split(b"\r\n"):
+        if line.lower().startswith(b"content-length:"):
+            length = int(line.split(b":")[1])
+            while len(body) < length:
+                body += conn.recv(65536)
+    return head.split(b" ")[0]
+
+
+@pytest.fixture
+def stale_server():
+    """Answers one request per connection, then closes it without answering the next."""
+    listener = socket.socket()
+    listener.bind(("127.0.0.1", 0))
+    listener.listen()
+    methods = []
+
+    def serve():
+        while True:
+            try:
+                conn, _ = listener.accept()
+            except OSError:
+                return
+            with conn:
+                method = read_request(conn)
+                methods.append(method)
+                conn.sendall(RESPONSE)
+                # Drop the keep-alive connection as soon as the next request arrives
+                method = read_request(conn)
+                if method:
+                    methods.append(method)
+
+    threading.Thread(target=serve, daemon=True).start()
+    yield f"http://127.0.0.1:{listener.getsockname()[1]}/", methods
+    listener.close()
+
+
+def test_idempotent_request_is_replayed(stale_server):
+    url, methods = stale_server
+    client = httpr.Client()
+    assert client.get(url).text == "ok"
+    assert client.get(url).text == "ok"
+    assert client.put(url, content=b"data").text == "ok"
+    assert methods == [b"GET", b"GET", b"GET", b"PUT", b"PUT"]
+
+
+def test_replay_recorded_as_retry(stale_server):
+    url, _ = stale_server
+    client = httpr.Client()
+    first = client.get(url)
+    assert first.retries == 0
+    assert [attempt["outcome"] for attempt in first.attempts] == ["response"]
+
+    response = client.get(url)
+    assert response.retries == 1
+    failed, retried = response.attempts
+    assert (failed["outcome"], failed["status"], failed["retry"]) == ("error", None, False)
+    assert failed["error"]
+    assert (retried["outcome"], retried["status"], retried["retry"]) == ("response", 200, True)
+    assert retried["started"] >= failed["started"]
+
+
+def test_post_is_not_replayed(stale_server):
+    url, methods = stale_server
+    client = httpr.Client()
+    client.get(url)
+    with pytest.raises(httpr.RequestError):
+        client.post(url, content=b"data")
+    assert methods == [b"GET", b"POST"]
+
+
+def test_retry_can_be_disabled(stale_server):
+    url, methods = stale_server
+    client = httpr.Client(retry_idempotent_on_connection_error=False)
+    client.get(url)
+    with pytest.raises(httpr.RequestError):
+        client.get(url)
+    assert methods == [b"GET", b"GET"]
+
+
+def test_connection_closed_before_response_is_retried():
+    listener = socket.socket()
+    listener.bind(("127.0.0.1", 0))
+    listener.listen()
+    attempts = []
+
+    def serve():
+        for attempt in range(2):
+            conn, _ = listener.accept()
+            with conn:
+                attempts.append(read_request(conn))
+                if attempt == 1:
+                    conn.sendall(RESPONSE)
+
+    threading.Thread(target=serve, daemon=True).start()
+    url = f"http://127.0.0.1:{listener.getsockname()[1]}/"
+    assert httpr.Client().delete(url).text == "ok"
+    assert attempts == [b"DELETE", b"DELETE"]
+    listener.close()
+
+
+def test_http2_keep_alive_options():
+    httpr.Client(http2_keep_alive_interval=30, http2_keep_alive_timeout=5, http2_keep_alive_while_idle=True)
+    with pytest.raises(ValueError, match="http2_keep_alive_interval must be a positive"):
+        httpr.Client(http2_keep_alive_interval=0)
+    with pytest.raises(ValueError, match="require http2_keep_alive_interval"):
+        httpr.Client(http2_keep_alive_timeout=5)
@@ -0,0 +1,53 @@
+"""Tests for deciding on a streamed response from its head: stream_to(), read() and close()."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+BODY = b"0123456789" * 10_000
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/file", body=BODY, headers={"Content-Type": "application/octet-stream"})
+        server.route("/missing", status=404, body=b"gone")
+        yield server
+
+
+def test_stream_to(server, tmp_path):
+    path = tmp_path / "file.bin"
+    path.write_bytes(b"previous content that is longer than nothing")
+    with httpr.Client().stream("GET", f"{server.url}/file") as response:
+        assert response.status_code == 200
+        assert response.stream_to(path) == len(BODY)
+        assert response.is_consumed
+        with pytest.raises(httpr.StreamConsumed):
+            response.read()
+    assert path.read_bytes() == BODY
+
+
+def test_stream_to_after_partial_read(server, tmp_path):
+    path = tmp_path / "rest.bin"
+    with httpr.Client().stream("GET", f"{server.url}/file") as response:
+        first = next(response)
+        written = response.stream_to(str(path))
+    assert first + path.read_bytes() == BODY
+    assert written == len(BODY) - len(first)
+
+
+def test_stream_to_missing_directory(server, tmp_path):
+    with httpr.Client().stream("GET", f"{server.url}/file") as response:
+        with pytest.raises(httpr.RequestError, match="No such file"):
+            response.stream_to(tmp_path / "missing" / "file.bin")
+
+
+def test_decide_on_head(server, tmp_path):
+    client = httpr.Client()
+    with client.stream("GET", f"{server.url}/missing") as response:
+        assert response.status_code == 404
+    assert response.is_closed
+    with pytest.raises(httpr.StreamClosed):
+        response.stream_to(tmp_path / "file.bin")
+    assert not (tmp_path / "file.bin").exists()
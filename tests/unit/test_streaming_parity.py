@@ -0,0 +1,52 @@
+"""Tests for the Response surface shared by StreamingResponse."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+LINK = '</items?page=3>; rel="next", <https://api.example.com/items?page=1>; rel="prev"; title="First, page"'
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        headers = {"Content-Type": "text/plain; charset=UTF-8", "Link": LINK}
+        server.route("/items", body="café".encode("cp1252"), headers=headers)
+        server.route("/missing", status=404, body=b"gone")
+        yield server
+
+
+def test_content_type_and_links(server):
+    response = httpr.get(f"{server.url}/items")
+    assert response.content_type == "text/plain"
+    assert response.links["next"] == {"url": f"{server.url}/items?page=3", "rel": "next"}
+    assert response.links["prev"]["title"] == "First, page"
+    assert httpr.get(f"{server.url}/missing").links == {}
+
+    with httpr.Client().stream("GET", f"{server.url}/items") as streamed:
+        assert streamed.content_type == "text/plain"
+        assert streamed.links == response.links
+
+
+def test_streaming_encoding(server):
+    with httpr.Client().stream("GET", f"{server.url}/items") as response:
+        assert response.encoding == "utf-8"
+        response.encoding = "cp1252"
+        assert response.encoding == "cp1252"
+        assert "".join(response.iter_text()) == "café"
+
+
+def test_streaming_raise_for_status(server):
+    client = httpr.Client()
+    with client.stream("GET", f"{server.url}/items") as response:
+        assert response.raise_for_status() is response
+
+    with client.stream("GET", f"{server.url}/missing") as response:
+        with pytest.raises(httpr.HTTPStatusError, match="Client error '404 Not Found'") as exc_info:
+            response.raise_for_status()
+        assert exc_info.value.response is response
+        assert exc_info.value.status_code == 404
+        assert exc_info.value.body_preview == ""
+        # The body is still there to read
+        assert response.read() == b"gone"
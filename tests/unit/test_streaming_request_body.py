@@ -0,0 +1,103 @@
+"""Tests for request bodies given as iterators, and duplex use with stream()."""
+
+import queue
+import socket
+import threading
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.echo("/echo")
+        yield server
+
+
+def test_generator_body(server):
+    def chunks():
+        yield b"hello, "
+        yield "wörld"
+
+    response = httpr.post(f"{server.url}/echo", content=chunks())
+    assert response.json()["body"] == "hello, wörld"
+    [request] = server.requests
+    assert request["headers"]["transfer-encoding"] == "chunked"
+    assert "content-length" not in request["headers"]
+
+
+def test_iterator_body_with_stream(server):
+    with httpr.Client().stream("PUT", f"{server.url}/echo", content=iter([b"a", b"b", b"c"])) as response:
+        assert b"abc" in response.read()
+
+
+def test_generator_exception_is_raised(server):
+    def chunks():
+        yield b"first"
+        raise KeyError("source went away")
+
+    with pytest.raises(KeyError, match="source went away"):
+        httpr.post(f"{server.url}/echo", content=chunks())
+
+
+def test_encoding_with_iterator(server):
+    with pytest.raises(TypeError, match="encoding= only applies to str content"):
+        httpr.post(f"{server.url}/echo", content=iter([b"a"]), encoding="latin-1")
+
+
+def test_list_is_not_streamed(server):
+    with pytest.raises(TypeError):
+        httpr.post(f"{server.url}/echo", content=[b"a", b"b"])
+
+
+class DuplexEchoServer:
+    """Answers with a chunked response head at once, then echoes every request body chunk back."""
+
+    def __init__(self):
+        self.listener = socket.create_server(("127.0.0.1", 0))
+        self.url = f"http://127.0.0.1:{self.listener.getsockname()[1]}/"
+        self.thread = threading.Thread(target=self.serve, daemon=True)
+        self.thread.start()
+
+    def serve(self):
+        conn, _ = self.listener.accept()
+        with conn, conn.makefile("rb") as reader:
+            while reader.readline() not in (b"\r\n", b""):
+                pass
+            conn.sendall(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n")
+            while True:
+                size = int(reader.readline().split(b";")[0], 16)
+                data = reader.read(size + 2)[:size]
+                conn.sendall(b"%x\r\n%s\r\n" % (len(data), data))
+                if size == 0:
+                    break
+
+    def close(self):
+        self.listener.close()
+        self.thread.join(timeout=5)
+
+
+def test_duplex():
+    server = DuplexEchoServer()
+    outgoing = queue.Queue()
+
+    def messages():
+        while (message := outgoing.get(timeout=5)) is not None:
+            yield message
+
+    try:
+        client = httpr.Client(timeout=10)
+        with client.stream("POST", server.url, content=messages()) as response:
+            # The head arrived while the request body is still open
+            assert response.status_code == 200
+            lines = response.iter_lines()
+            for message in ["hello\n", "how are you?\n"]:
+                outgoing.put(message.encode())
+                assert next(lines) == message
+            outgoing.put(None)
+            assert list(lines) == []
+    finally:
+        server.close()
@@ -0,0 +1,112 @@
+"""Tests for Client.sync() (ETag / Last-Modified mirroring)."""
+
+import asyncio
+import json
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+
+LAST_MODIFIED = "Fri, 16 Oct 2026 13:40:00 GMT"
+
+
+class MirrorHandler(BaseHTTPRequestHandler):
+    """Serves `RESOURCES`, answering matching validators with 304 and recording them."""
+
+    resources: dict = {}
+    requests: list = []
+
+    def do_GET(self):
+        validators = (self.headers.get("If-None-Match"), self.headers.get("If-Modified-Since"))
+        MirrorHandler.requests.append((self.path, *validators))
+        resource = MirrorHandler.resources.get(self.path)
+        if resource is None:
+            self.send_response(404)
+            self.send_header("Content-Length", "0")
+            self.end_headers()
+            return
+        body, etag = resource
+        if etag is not None and self.headers.get("If-None-Match") == etag:
+            self.send_response(304)
+            self.end_headers()
+            return
+        self.send_response(200)
+        self.send_header("Content-Length", str(len(body)))
+        if etag is not None:
+            self.send_header("ETag", etag)
+        else:
+            self.send_header("Last-Modified", LAST_MODIFIED)
+        self.end_headers()
+        self.wfile.write(body)
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture
+def mirror():
+    MirrorHandler.resources = {"/a.json": (b'{"v": 1}', '"v1"'), "/b.txt": (b"plain", None)}
+    MirrorHandler.requests = []
+    server = ThreadingHTTPServer(("127.0.0.1", 0), MirrorHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{server.server_address[1]}"
+    server.shutdown()
+
+
+def test_sync_downloads_then_skips_unchanged(mirror, tmp_path):
+    files = {f"{mirror}/a.json": tmp_path / "out" / "a.json", f"{mirror}/b.txt": tmp_path / "b.txt"}
+    state_file = tmp_path / "state.json"
+    client = httpr.Client()
+
+    first = client.sync(files, state_file=state_file)
+    assert [result["status"] for result in first.values()] == ["created", "created"]
+    assert first[f"{mirror}/a.json"]["bytes"] == 8
+    assert (tmp_path / "out" / "a.json").read_bytes() == b'{"v": 1}'
+    state = json.loads(state_file.read_text())
+    assert state == {f"{mirror}/a.json": {"etag": '"v1"'}, f"{mirror}/b.txt": {"last_modified": LAST_MODIFIED}}
+
+    second = client.sync(files, state_file=state_file)
+    assert second[f"{mirror}/a.json"]["status"] == "unchanged"
+    assert MirrorHandler.requests[-2:] == [("/a.json", '"v1"', None), ("/b.txt", None, LAST_MODIFIED)]
+
+    MirrorHandler.resources["/a.json"] = (b'{"v": 2}', '"v2"')
+    third = client.sync(files, state_file=state_file)
+    assert third[f"{mirror}/a.json"]["status"] == "updated"
+    assert (tmp_path / "out" / "a.json").read_bytes() == b'{"v": 2}'
+    assert json.loads(state_file.read_text())[f"{mirror}/a.json"] == {"etag": '"v2"'}
+    assert sorted(path.name for path in (tmp_path / "out").iterdir()) == ["a.json"]
+
+
+def test_sync_redownloads_missing_file(mirror, tmp_path):
+    path = tmp_path / "a.json"
+    state_file = tmp_path / "state.json"
+    client = httpr.Client()
+    client.sync({f"{mirror}/a.json": path}, state_file=state_file)
+    path.unlink()
+    report = client.sync({f"{mirror}/a.json": path}, state_file=state_file)
+    assert report[f"{mirror}/a.json"]["status"] == "created"
+    assert MirrorHandler.requests[-1] == ("/a.json", None, None)
+    assert path.exists()
+
+
+def test_sync_failure_keeps_existing_file(mirror, tmp_path):
+    path = tmp_path / "missing.txt"
+    path.write_bytes(b"old")
+    report = httpr.Client().sync({f"{mirror}/missing.txt": path, f"{mirror}/b.txt": tmp_path / "b.txt"})
+    failed = report[f"{mirror}/missing.txt"]
+    assert failed["status"] == "failed"
+    assert failed["status_code"] == 404
+    assert "404" in failed["error"]
+    assert path.read_bytes() == b"old"
+    assert report[f"{mirror}/b.txt"]["status"] == "created"
+
+
+def test_sync_async(mirror, tmp_path):
+    async def main():
+        async with httpr.AsyncClient() as client:
+            return await client.sync({f"{mirror}/b.txt": tmp_path / "b.txt"})
+
+    assert asyncio.run(main())[f"{mirror}/b.txt"]["status"] == "created"
@@ -0,0 +1,175 @@
+"""Tests for Request, RequestTemplate and client.send_template_many()."""
+
+import json
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+class EchoHandler(BaseHTTPRequestHandler):
+    """Echoes the request back as JSON."""
+
+    def _echo(self):
+        length = int(self.headers.get("Content-Length", 0))
+        body = self.rfile.read(length).decode() if length else ""
+        if self.path.startswith("/fail"):
+            self.send_response(500)
+            self.send_header("Content-Length", "0")
+            self.end_headers()
+            return
+        payload = json.dumps(
+            {
+                "method": self.command,
+                "path": self.path,
+                "headers": {k.lower(): v for k, v in self.headers.items()},
+                "body": body,
+            }
+        ).encode()
+        self.send_response(200)
+        self.send_header("Content-Type", "application/json")
+        self.send_header("Content-Length", str(len(payload)))
+        self.end_headers()
+        self.wfile.write(payload)
+
+    do_GET = do_POST = do_PUT = _echo
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def echo_server():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), EchoHandler)
+    port = server.server_address[1]
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{port}"
+    server.shutdown()
+
+
+def test_render_url_headers_and_params(echo_server):
+    template = httpr.RequestTemplate(
+        "GET",
+        echo_server + "/users/{user_id}",
+        params={"region": "{region}"},
+        headers={"X-Tenant": "{tenant}"},
+    )
+    request = template.render(user_id=42, region="eu", tenant="acme")
+    assert request.method == "GET"
+    assert request.url == echo_server + "/users/42"
+    assert request.params == {"region": "eu"}
+    assert request.headers == {"X-Tenant": "acme"}
+
+
+def test_render_json_keeps_types():
+    template = httpr.RequestTemplate(
+        "PUT",
+        "http://example.com/users/{user_id}",
+        json={"id": "{user_id}", "label": "user-{user_id}", "tags": ["{tag}"], "active": True},
+    )
+    request = template.render(user_id=7, tag="new")
+    assert request.json == {"id": 7, "label": "user-7", "tags": ["new"], "active": True}
+
+
+def test_render_escaped_braces_and_content():
+    template = httpr.RequestTemplate("POST", "http://example.com/", content="{{id: {id}}}")
+    assert template.render(id=3).content == b"{id: 3}"
+
+
+def test_render_missing_variable_raises():
+    template = httpr.RequestTemplate("GET", "http://example.com/users/{user_id}")
+    with pytest.raises(ValueError, match="user_id"):
+        template.render()
+
+
+def test_placeholders():
+    template = httpr.RequestTemplate(
+        "POST",
+        "http://example.com/{org}/{repo}",
+        headers={"X-Org": "{org}"},
+        json={"title": "{title}"},
+    )
+    assert template.placeholders == ["org", "repo", "title"]
+
+
+def test_send_request(echo_server):
+    client = httpr.Client(headers={"X-Client": "default"})
+    request = httpr.Request("POST", echo_server + "/submit", json={"a": 1})
+    response = client.send(request)
+    assert response.status_code == 200
+    data = response.json()
+    assert data["method"] == "POST"
+    assert json.loads(data["body"]) == {"a": 1}
+    assert data["headers"]["x-client"] == "default"
+
+
+def test_send_template_many_preserves_order(echo_server):
+    client = httpr.Client()
+    template = httpr.RequestTemplate("PUT", echo_server + "/users/{user_id}", json={"id": "{user_id}"})
+    vars = [{"user_id": i} for i in range(20)]
+    responses = client.send_template_many(template, vars, max_concurrency=4)
+    assert [r.json()["path"] for r in responses] == [f"/users/{i}" for i in range(20)]
+    assert [json.loads(r.json()["body"])["id"] for r in responses] == list(range(20))
+
+
+def test_send_template_many_return_exceptions(echo_server):
+    client = httpr.Client()
+    template = httpr.RequestTemplate("GET", "{base}/ok")
+    responses = client.send_template_many(
+        template,
+        [{"base": echo_server}, {"base": "http://127.0.0.1:1"}],
+        return_exceptions=True,
+    )
+    assert responses[0].status_code == 200
+    assert isinstance(responses[1], httpr.ConnectError)
+
+    with pytest.raises(httpr.ConnectError):
+        client.send_template_many(template, [{"base": "http://127.0.0.1:1"}])
+
+
+def test_send_template_many_renders_before_sending(echo_server):
+    client = httpr.Client()
+    template = httpr.RequestTemplate("GET", echo_server + "/users/{user_id}")
+    with pytest.raises(ValueError):
+        client.send_template_many(template, [{"user_id": 1}, {}])
+
+
+def test_send_template_many_dedupe_on():
+    with LocalServer() as server:
+        server.route("/orders", method="POST", status=201, body="created")
+        client = httpr.Client()
+        template = httpr.RequestTemplate("POST", server.url + "/orders", json={"sku": "{sku}"})
+        rows = [{"sku": "a"}, {"sku": "b"}, {"sku": "a"}, {"sku": "b"}, {"sku": "c"}]
+        responses = client.send_template_many(template, rows, dedupe_on=("method", "url", "body"))
+        assert [r.status_code for r in responses] == [201] * 5
+        assert responses[0] is responses[2]
+        assert sorted(json.loads(r["body"])["sku"] for r in server.requests) == ["a", "b", "c"]
+
+        server.clear_requests()
+        client.send_template_many(template, rows, dedupe_on=["method", "url"])
+        assert len(server.requests) == 1
+
+        with pytest.raises(ValueError, match="dedupe_on"):
+            client.send_template_many(template, rows, dedupe_on=("cookies",))
+
+
+def test_send_template_many_dedupe_shares_exceptions():
+    client = httpr.Client()
+    template = httpr.RequestTemplate("GET", "{base}/ok")
+    vars = [{"base": "http://127.0.0.1:1"}] * 2
+    results = client.send_template_many(template, vars, return_exceptions=True, dedupe_on=("url",))
+    assert all(isinstance(result, httpr.ConnectError) for result in results)
+
+
+@pytest.mark.asyncio
+async def test_async_send_template_many(echo_server):
+    async with httpr.AsyncClient() as client:
+        template = httpr.RequestTemplate("GET", echo_server + "/items/{n}")
+        responses = await client.send_template_many(template, [{"n": 1}, {"n": 2}])
+        assert [r.json()["path"] for r in responses] == ["/items/1", "/items/2"]
+        response = await client.send(template.render(n=3))
+        assert response.json()["path"] == "/items/3"
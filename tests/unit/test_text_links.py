@@ -0,0 +1,44 @@
+"""Tests for link resolution in the HTML-to-text conversions."""
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+PAGE = """<html><body>
+<a href="next.html">Next</a>
+<a href="/about">About</a>
+<a href="https://other.example/x">Other</a>
+<img src="../img/logo.png" alt="Logo">
+</body></html>"""
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/docs/guide/index.html", body=PAGE, headers={"Content-Type": "text/html"})
+        server.redirect("/start", "/docs/guide/index.html")
+        yield server
+
+
+def test_links_resolved_against_final_url(server):
+    response = httpr.Client().get(f"{server.url}/start")
+    assert response.link_base == f"{server.url}/docs/guide/index.html"
+    markdown = response.text_markdown
+    assert f"[1]: {server.url}/docs/guide/next.html" in markdown
+    assert f"[2]: {server.url}/about" in markdown
+    assert "[3]: https://other.example/x" in markdown
+
+
+def test_link_base_configurable(server):
+    response = httpr.Client().get(f"{server.url}/docs/guide/index.html")
+    response.link_base = "https://mirror.example/site/"
+    assert "[1]: https://mirror.example/site/next.html" in response.text_markdown
+
+    response.link_base = None
+    assert response.link_base is None
+    assert "[1]: next.html" in response.text_markdown
+    assert "[2]: /about" in response.text_markdown
+
+    with pytest.raises(ValueError, match="link_base"):
+        response.link_base = "not a url"
@@ -0,0 +1,102 @@
+"""Tests for the client timeout, connect_timeout and read_timeout options."""
+
+import threading
+import time
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+class TrickleHandler(BaseHTTPRequestHandler):
+    """Sends a body of 6 chunks, one every 0.1 seconds, or on /events two events 0.5 seconds apart."""
+
+    protocol_version = "HTTP/1.1"
+
+    def do_GET(self):
+        if self.path == "/events":
+            chunks, pause = [b"data: 1\n\n", b"data: 2\n\n"], 0.5
+        else:
+            chunks, pause = [b"x"] * 6, 0.1
+        self.send_response(200)
+        self.send_header("Content-Length", str(sum(map(len, chunks))))
+        self.end_headers()
+        try:
+            for chunk in chunks:
+                self.wfile.write(chunk)
+                self.wfile.flush()
+                time.sleep(pause)
+        except (BrokenPipeError, ConnectionResetError):
+            pass  # The client gave up on the response
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def trickle():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), TrickleHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{server.server_address[1]}"
+    server.shutdown()
+
+
+def test_defaults():
+    client = httpr.Client()
+    assert client.timeout is None
+    assert client.connect_timeout == 30.0
+    assert client.read_timeout == 30.0
+
+    client = httpr.Client(timeout=5, connect_timeout=None, read_timeout=2.5)
+    assert client.timeout == 5.0
+    assert client.connect_timeout is None
+    assert client.read_timeout == 2.5
+
+
+def test_read_timeout_fires_on_stalled_response():
+    with LocalServer() as server:
+        server.route("/slow", body=b"late", delay=1.0)
+        client = httpr.Client(read_timeout=0.2)
+        with pytest.raises(httpr.TimeoutException):
+            client.get(f"{server.url}/slow")
+
+
+def test_read_timeout_allows_steady_download(trickle):
+    response = httpr.Client(read_timeout=0.3).get(trickle)
+    assert response.content == b"xxxxxx"
+
+
+def test_total_timeout_caps_steady_download(trickle):
+    client = httpr.Client(timeout=0.3)
+    with pytest.raises(httpr.TimeoutException):
+        client.get(trickle)
+    # Changing the client timeout applies to later requests
+    client.timeout = None
+    assert client.get(trickle).content == b"xxxxxx"
+
+
+def test_request_timeout_none_turns_off_client_timeout(trickle):
+    client = httpr.Client(timeout=0.3)
+    assert client.get(trickle, timeout=None).content == b"xxxxxx"
+    with pytest.raises(httpr.TimeoutException):
+        client.get(trickle)
+
+
+def test_read_timeout_none_keeps_idle_stream_open(trickle):
+    # The read timeout also ends an event stream that goes quiet for longer
+    with pytest.raises(httpr.TimeoutException):
+        with httpr.Client(read_timeout=0.2).stream("GET", f"{trickle}/events") as response:
+            response.read()
+    with httpr.Client(read_timeout=None).stream("GET", f"{trickle}/events") as response:
+        assert response.read() == b"data: 1\n\ndata: 2\n\n"
+
+
+def test_invalid_timeouts():
+    for kwargs in ({"timeout": 0}, {"connect_timeout": -1}, {"read_timeout": float("nan")}):
+        with pytest.raises(ValueError, match="must be a positive number of seconds"):
+            httpr.Client(**kwargs)
+    with pytest.raises(ValueError, match="non-negative number of seconds or None"):
+        httpr.Client().get("http://127.0.0.1:1/", timeout=-1)
@@ -0,0 +1,63 @@
+"""Tests for SSLError and CertificateVerifyError reason codes."""
+
+import datetime
+
+import pytest
+import trustme
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture(scope="module")
+def ca():
+    return trustme.CA()
+
+
+@pytest.fixture(scope="module")
+def ca_file(ca, tmp_path_factory):
+    path = tmp_path_factory.mktemp("ca") / "ca.pem"
+    ca.cert_pem.write_to_path(str(path))
+    return str(path)
+
+
+def serve(ca, tmp_path, *identities, **validity):
+    cert = ca.issue_cert(*identities, **validity)
+    cert_path, key_path = tmp_path / "cert.pem", tmp_path / "key.pem"
+    cert.private_key_and_cert_chain_pem.write_to_path(str(cert_path))
+    cert.private_key_pem.write_to_path(str(key_path))
+    server = LocalServer(tls_cert=str(cert_path), tls_key=str(key_path))
+    server.route("/", body="secure")
+    return server
+
+
+def test_valid_certificate(ca, ca_file, tmp_path):
+    with serve(ca, tmp_path, "127.0.0.1") as server:
+        assert httpr.get(server.url, ca_cert_file=ca_file).text == "secure"
+
+
+def test_unknown_ca(tmp_path):
+    with serve(trustme.CA(), tmp_path, "127.0.0.1") as server:
+        with pytest.raises(httpr.CertificateVerifyError) as exc_info:
+            httpr.get(server.url)
+        assert exc_info.value.reason == "unknown_ca"
+        assert isinstance(exc_info.value, httpr.SSLError)
+        assert isinstance(exc_info.value, httpr.ConnectError)
+        assert httpr.get(server.url, verify=False).text == "secure"
+
+
+def test_hostname_mismatch(ca, ca_file, tmp_path):
+    with serve(ca, tmp_path, "example.org") as server:
+        with pytest.raises(httpr.CertificateVerifyError) as exc_info:
+            httpr.get(server.url, ca_cert_file=ca_file)
+        assert exc_info.value.reason == "hostname_mismatch"
+
+
+def test_expired(ca, ca_file, tmp_path):
+    now = datetime.datetime.now(datetime.timezone.utc)
+    validity = {"not_before": now - datetime.timedelta(days=30), "not_after": now - datetime.timedelta(days=1)}
+    with serve(ca, tmp_path, "127.0.0.1", **validity) as server:
+        with pytest.raises(httpr.CertificateVerifyError) as exc_info:
+            httpr.get(server.url, ca_cert_file=ca_file)
+        assert exc_info.value.reason == "expired"
+        assert "TLS handshake failed" in str(exc_info.value)
@@ -0,0 +1,87 @@
+"""Tests for alpn_protocols and the TLS session resumption options."""
+
+import pytest
+import trustme
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture(scope="module")
+def ca():
+    return trustme.CA()
+
+
+@pytest.fixture
+def ca_file(ca, tmp_path):
+    path = tmp_path / "ca.pem"
+    ca.cert_pem.write_to_path(str(path))
+    return str(path)
+
+
+def serve(ca, tmp_path):
+    cert = ca.issue_cert("127.0.0.1")
+    cert_path, key_path = tmp_path / "cert.pem", tmp_path / "key.pem"
+    cert.private_key_and_cert_chain_pem.write_to_path(str(cert_path))
+    cert.private_key_pem.write_to_path(str(key_path))
+    # Offers h2 and http/1.1
+    server = LocalServer(tls_cert=str(cert_path), tls_key=str(key_path))
+    server.echo("/echo")
+    return server
+
+
+@pytest.fixture
+def server(ca, tmp_path):
+    with serve(ca, tmp_path) as server:
+        yield server
+
+
+def test_alpn_protocols(server, ca_file):
+    # Requests over HTTP/1.1 carry a Host header, over HTTP/2 an :authority instead
+    httpr.Client(ca_cert_file=ca_file).get(f"{server.url}/echo")
+    assert "host" not in server.requests[-1]["headers"]
+    httpr.Client(ca_cert_file=ca_file, alpn_protocols=["http/1.1"]).get(f"{server.url}/echo")
+    assert "host" in server.requests[-1]["headers"]
+    httpr.Client(ca_cert_file=ca_file, alpn_protocols=["h2", "http/1.1"]).get(f"{server.url}/echo")
+    assert "host" not in server.requests[-1]["headers"]
+
+
+def test_alpn_protocols_verify(tmp_path):
+    with serve(trustme.CA(), tmp_path) as server:
+        client = httpr.Client(alpn_protocols=["http/1.1"])
+        with pytest.raises(httpr.CertificateVerifyError):
+            client.get(f"{server.url}/echo")
+        client = httpr.Client(alpn_protocols=["http/1.1"], verify=False)
+        assert client.get(f"{server.url}/echo").status_code == 200
+
+
+def test_ca_cert_file_applies_to_its_client(server, ca_file):
+    assert httpr.Client(ca_cert_file=ca_file).get(f"{server.url}/echo").status_code == 200
+    with pytest.raises(httpr.CertificateVerifyError):
+        httpr.Client().get(f"{server.url}/echo")
+
+
+def test_invalid_alpn_protocols():
+    with pytest.raises(ValueError, match="Unsupported ALPN protocol 'h3'"):
+        httpr.Client(alpn_protocols=["h3"])
+    with pytest.raises(ValueError, match="must include 'h2'"):
+        httpr.Client(alpn_protocols=["http/1.1"], http2_only=True)
+
+
+@pytest.mark.parametrize(
+    ("options", "resumed"),
+    [
+        ({}, True),
+        ({"tls_session_cache_size": 1}, True),
+        ({"tls_session_tickets": False}, False),
+        ({"tls_session_cache_size": 0}, False),
+    ],
+)
+def test_session_resumption_options(server, ca_file, options, resumed):
+    # Every response closes its connection, so each request makes a handshake of its own.
+    # The server speaks TLS 1.3, whose sessions only resume from tickets.
+    server.route("/close", headers={"Connection": "close"})
+    client = httpr.Client(ca_cert_file=ca_file, alpn_protocols=["http/1.1"], **options)
+    for _ in range(3):
+        assert client.get(f"{server.url}/close").status_code == 200
+    assert [request["tls_resumed"] for request in server.requests] == [False, resumed, resumed]
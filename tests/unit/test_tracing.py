@@ -0,0 +1,89 @@
+"""Tests for W3C trace context propagation (httpr.tracing and trace_context=)."""
+
+import asyncio
+import threading
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+from httpr.tracing import current_trace_context, trace_context
+
+TRACEPARENT = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+OTHER = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-00"
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/", body="ok")
+        yield server
+
+
+def sent(server, name="traceparent"):
+    return [r["headers"].get(name) for r in server.requests]
+
+
+def test_request_option(server):
+    client = httpr.Client()
+    client.get(server.url, trace_context={"traceparent": TRACEPARENT, "tracestate": "vendor=1"})
+    client.get(server.url)
+    assert sent(server) == [TRACEPARENT, None]
+    assert sent(server, "tracestate") == ["vendor=1", None]
+
+
+def test_context_block(server):
+    client = httpr.Client()
+    assert current_trace_context() is None
+    with trace_context(TRACEPARENT, baggage="user=1"):
+        assert current_trace_context() == {"traceparent": TRACEPARENT, "baggage": "user=1"}
+        client.get(server.url)
+        with client.stream("GET", server.url) as response:
+            response.read()
+        # The request option and explicit headers take precedence
+        client.get(server.url, trace_context={"traceparent": OTHER})
+        client.get(server.url, headers={"Traceparent": OTHER})
+    client.get(server.url)
+    assert sent(server) == [TRACEPARENT, TRACEPARENT, OTHER, OTHER, None]
+    assert sent(server, "baggage") == ["user=1", "user=1", None, None, None]
+
+
+def test_other_threads_do_not_inherit(server):
+    with trace_context(TRACEPARENT):
+        thread = threading.Thread(target=lambda: httpr.get(server.url))
+        thread.start()
+        thread.join()
+    assert sent(server) == [None]
+
+
+def test_async_client(server):
+    async def main():
+        async with httpr.AsyncClient() as client:
+            with trace_context(TRACEPARENT):
+                await client.get(server.url)
+
+    asyncio.run(main())
+    assert sent(server) == [TRACEPARENT]
+
+
+def test_request_later(server):
+    with trace_context(TRACEPARENT):
+        future = httpr.Client().request_later(0, "GET", server.url)
+    future.result(timeout=5)
+    assert sent(server) == [TRACEPARENT]
+
+
+@pytest.mark.parametrize(
+    "context",
+    [
+        {"traceparent": "00-abc-def-01"},
+        {"traceparent": "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"},
+        {"traceparent": "00-00000000000000000000000000000000-00f067aa0ba902b7-01"},
+        {"tracestate": "vendor=1"},
+        {"traceparent": TRACEPARENT, "x-trace": "1"},
+    ],
+)
+def test_invalid(server, context):
+    with pytest.raises(ValueError):
+        httpr.Client().get(server.url, trace_context=context)
+    assert server.requests == []
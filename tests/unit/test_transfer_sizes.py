@@ -0,0 +1,63 @@
+"""Tests for request/response body sizes and the client byte counters."""
+
+import gzip
+import os
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+TEXT = b"".join(b"line %d of a compressible response\n" % i for i in range(2_000))
+COMPRESSED = gzip.compress(TEXT)
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.route("/gzip", body=COMPRESSED, headers={"Content-Encoding": "gzip"})
+        server.route("/plain", body=b"hello")
+        server.route("/upload", method="POST", body=b"ok")
+        yield server
+
+
+def test_response_sizes(server):
+    response = httpr.get(f"{server.url}/gzip")
+    assert response.content == TEXT
+    assert response.request_body_size == 0
+    assert response.response_body_size == len(COMPRESSED)
+    assert response.decompressed_body_size == len(TEXT)
+
+    response = httpr.post(f"{server.url}/upload", content=b"x" * 1000)
+    assert response.request_body_size == 1000
+    assert response.response_body_size == response.decompressed_body_size == 2
+
+
+def test_multipart_upload_size(server, tmp_path):
+    path = tmp_path / "data.bin"
+    path.write_bytes(os.urandom(10_000))
+    response = httpr.post(f"{server.url}/upload", files={"data": str(path)})
+    assert response.request_body_size == len(server.requests[0]["body"]) > 10_000
+
+
+def test_streaming_sizes(server):
+    with httpr.Client().stream("GET", f"{server.url}/gzip") as response:
+        assert response.decompressed_body_size == 0
+        body = b"".join(response.iter_bytes())
+        assert body == TEXT
+        assert response.response_body_size == len(COMPRESSED)
+        assert response.decompressed_body_size == len(TEXT)
+
+
+def test_client_totals(server):
+    client = httpr.Client()
+    assert (client.bytes_sent, client.bytes_received, client.bytes_decompressed) == (0, 0, 0)
+    client.get(f"{server.url}/gzip")
+    client.get(f"{server.url}/plain")
+    client.post(f"{server.url}/upload", json={"a": 1})
+    assert client.bytes_sent == len(b'{"a":1}')
+    assert client.bytes_received == len(COMPRESSED) + 5 + 2
+    assert client.bytes_decompressed == len(TEXT) + 5 + 2
+    # Counters are per client
+    httpr.Client().get(f"{server.url}/plain")
+    assert client.bytes_received == len(COMPRESSED) + 7
@@ -0,0 +1,78 @@
+"""Tests for sending requests through a PythonTransport."""
+
+import gzip
+
+import pytest
+
+import httpr
+
+
+def test_transport_request_and_response():
+    seen = []
+
+    def handler(request):
+        seen.append((request.method, request.url, request.headers.get("x-test"), request.read()))
+        return 201, {"Content-Type": "text/plain"}, b"created"
+
+    client = httpr.Client(transport=httpr.PythonTransport(handler), headers={"X-Test": "1"})
+    response = client.post("https://example.invalid/items", content=b"payload")
+    assert response.status_code == 201
+    assert response.text == "created"
+    assert response.headers["content-type"] == "text/plain"
+    assert seen == [("POST", "https://example.invalid/items", "1", b"payload")]
+
+
+def test_streamed_bodies():
+    def handler(request):
+        received = b"".join(request)
+        return 200, [("X-Size", str(len(received)))], (bytes([i]) * 1000 for i in range(3))
+
+    client = httpr.Client(transport=httpr.PythonTransport(handler))
+    response = client.post("https://example.invalid/", content=b"x" * 100_000)
+    assert response.headers["x-size"] == "100000"
+    assert response.content == b"\x00" * 1000 + b"\x01" * 1000 + b"\x02" * 1000
+
+    with client.stream("GET", "https://example.invalid/") as response:
+        assert b"".join(response.iter_bytes()) == b"\x00" * 1000 + b"\x01" * 1000 + b"\x02" * 1000
+
+
+def test_redirects_cookies_and_decompression():
+    calls = []
+
+    def handler(request):
+        calls.append((request.url, request.headers.get("cookie")))
+        if request.url.endswith("/login"):
+            return 302, [("Location", "/home"), ("Set-Cookie", "session=abc")], None
+        return 200, {"Content-Encoding": "gzip"}, gzip.compress(b"welcome")
+
+    client = httpr.Client(transport=httpr.PythonTransport(handler))
+    response = client.get("https://example.invalid/login")
+    assert response.text == "welcome"
+    assert response.url == "https://example.invalid/home"
+    assert calls == [("https://example.invalid/login", None), ("https://example.invalid/home", "session=abc")]
+    assert len(response.attempts) == 2
+
+
+def test_handler_exception_propagates():
+    def handler(request):
+        raise httpr.ConnectError("tunnel down")
+
+    client = httpr.Client(transport=httpr.PythonTransport(handler))
+    with pytest.raises(httpr.ConnectError, match="tunnel down"):
+        client.get("https://example.invalid/")
+
+
+def test_invalid_handler_result():
+    client = httpr.Client(transport=httpr.PythonTransport(lambda request: "nope"))
+    with pytest.raises(TypeError, match="status, headers, body"):
+        client.get("https://example.invalid/")
+
+    with pytest.raises(ValueError, match="callable"):
+        httpr.PythonTransport("not callable")
+
+
+@pytest.mark.asyncio
+async def test_async_client_transport():
+    client = httpr.AsyncClient(transport=httpr.PythonTransport(lambda request: (200, {}, "ok")))
+    response = await client.get("https://example.invalid/")
+    assert response.text == "ok"
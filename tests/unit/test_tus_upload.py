@@ -0,0 +1,160 @@
+"""Tests for client.upload_tus()."""
+
+import base64
+import hashlib
+import os
+import threading
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+
+PAYLOAD = os.urandom(3500)
+
+
+class TusHandler(BaseHTTPRequestHandler):
+    """A minimal tus server keeping uploads in memory."""
+
+    protocol_version = "HTTP/1.1"
+    uploads: dict = {}
+    created: list = []
+    patches: list = []
+    # number of PATCH requests to fail by accepting only half of the chunk
+    truncate: int = 0
+
+    def reply(self, status, headers=()):
+        self.send_response(status)
+        for name, value in headers:
+            self.send_header(name, value)
+        self.send_header("Content-Length", "0")
+        self.end_headers()
+
+    def read_body(self):
+        return self.rfile.read(int(self.headers.get("Content-Length", 0)))
+
+    def do_POST(self):
+        self.read_body()
+        assert self.headers["Tus-Resumable"] == "1.0.0"
+        upload_id = f"{len(TusHandler.uploads) + 1}"
+        TusHandler.uploads[upload_id] = b""
+        TusHandler.created.append(self.headers)
+        self.reply(201, [("Location", f"/files/{upload_id}"), ("Tus-Resumable", "1.0.0")])
+
+    def do_HEAD(self):
+        upload_id = self.path.rsplit("/", 1)[-1]
+        if upload_id not in TusHandler.uploads:
+            return self.reply(404)
+        self.reply(200, [("Upload-Offset", str(len(TusHandler.uploads[upload_id])))])
+
+    def do_PATCH(self):
+        upload_id = self.path.rsplit("/", 1)[-1]
+        body = self.read_body()
+        offset = int(self.headers["Upload-Offset"])
+        TusHandler.patches.append((offset, len(body), self.headers.get("Upload-Checksum")))
+        assert self.headers["Content-Type"] == "application/offset+octet-stream"
+        if offset != len(TusHandler.uploads[upload_id]):
+            return self.reply(409)
+        if TusHandler.truncate:
+            # A connection that dropped mid-chunk: part of it arrived, the client sees an error
+            TusHandler.truncate -= 1
+            TusHandler.uploads[upload_id] += body[: len(body) // 2]
+            return self.reply(503)
+        TusHandler.uploads[upload_id] += body
+        self.reply(204, [("Upload-Offset", str(len(TusHandler.uploads[upload_id])))])
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture
+def tus():
+    TusHandler.uploads, TusHandler.created, TusHandler.patches = {}, [], []
+    TusHandler.truncate = 0
+    server = ThreadingHTTPServer(("127.0.0.1", 0), TusHandler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{server.server_address[1]}"
+    server.shutdown()
+
+
+@pytest.fixture
+def payload(tmp_path):
+    path = tmp_path / "payload.bin"
+    path.write_bytes(PAYLOAD)
+    return path
+
+
+def test_upload(tus, payload):
+    events = []
+    result = httpr.Client().upload_tus(
+        f"{tus}/files/",
+        payload,
+        chunk_size=1000,
+        metadata={"filename": "payload.bin", "private": ""},
+        checksum="sha1",
+        on_progress=events.append,
+    )
+    assert result == {"upload_url": f"{tus}/files/1", "size": len(PAYLOAD), "resumed_from": 0}
+    assert TusHandler.uploads["1"] == PAYLOAD
+    created = TusHandler.created[0]
+    assert created["Upload-Length"] == str(len(PAYLOAD))
+    assert created["Upload-Metadata"] == f"filename {base64.b64encode(b'payload.bin').decode()},private"
+    assert [(offset, size) for offset, size, _ in TusHandler.patches] == [
+        (0, 1000),
+        (1000, 1000),
+        (2000, 1000),
+        (3000, 500),
+    ]
+    digest = base64.b64encode(hashlib.sha1(PAYLOAD[:1000]).digest()).decode()
+    assert TusHandler.patches[0][2] == f"sha1 {digest}"
+    assert [event["sent"] for event in events] == [0, 1000, 2000, 3000, 3500]
+    assert {event["upload_url"] for event in events} == {f"{tus}/files/1"}
+
+
+def test_failed_chunk_resumes_from_server_offset(tus, payload):
+    TusHandler.truncate = 2
+    httpr.Client().upload_tus(f"{tus}/files/", payload, chunk_size=1000)
+    assert TusHandler.uploads["1"] == PAYLOAD
+    # Each failed PATCH stored half of its chunk, and the next one starts where the server is
+    assert [offset for offset, _, _ in TusHandler.patches] == [0, 500, 1000, 2000, 3000]
+
+
+def test_retries_exhausted(tus, payload):
+    TusHandler.truncate = 5
+    with pytest.raises(httpr.HTTPStatusError):
+        httpr.Client().upload_tus(f"{tus}/files/", payload, chunk_size=1000, retries=1)
+    assert len(TusHandler.patches) == 2
+
+
+def test_resume_upload_url(tus, payload):
+    TusHandler.uploads["7"] = PAYLOAD[:2500]
+    result = httpr.Client().upload_tus(f"{tus}/files/", payload, upload_url=f"{tus}/files/7")
+    assert result["resumed_from"] == 2500
+    assert TusHandler.uploads["7"] == PAYLOAD
+    assert TusHandler.created == []
+    assert TusHandler.patches == [(2500, 1000, None)]
+
+    with pytest.raises(httpr.HTTPStatusError):
+        httpr.Client().upload_tus(f"{tus}/files/", payload, upload_url=f"{tus}/files/missing")
+
+
+def test_empty_file(tus, tmp_path):
+    path = tmp_path / "empty.bin"
+    path.write_bytes(b"")
+    result = httpr.Client().upload_tus(f"{tus}/files/", path)
+    assert result["size"] == 0
+    assert TusHandler.created[0]["Upload-Length"] == "0"
+    assert TusHandler.patches == []
+
+
+def test_invalid_arguments(payload):
+    client = httpr.Client()
+    with pytest.raises(ValueError, match="chunk_size"):
+        client.upload_tus("http://127.0.0.1/files/", payload, chunk_size=0)
+    with pytest.raises(ValueError, match="checksum"):
+        client.upload_tus("http://127.0.0.1/files/", payload, checksum="md5")
+    with pytest.raises(ValueError, match="metadata key"):
+        client.upload_tus("http://127.0.0.1/files/", payload, metadata={"file name": "x"})
+    with pytest.raises(ValueError, match="must be callable"):
+        client.upload_tus("http://127.0.0.1/files/", payload, on_progress=1)
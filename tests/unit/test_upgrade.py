@@ -0,0 +1,206 @@
+"""Tests for client.upgrade(), client.connect_tunnel() and the raw Stream they return."""
+
+import socket
+import threading
+
+import pytest
+
+import httpr
+
+
+class RawServer:
+    """Accepts one connection and hands it to `handler`."""
+
+    def __init__(self, handler):
+        self.listener = socket.create_server(("127.0.0.1", 0))
+        self.port = self.listener.getsockname()[1]
+        self.url = f"http://127.0.0.1:{self.port}/"
+        self.handler = handler
+        self.head = b""
+        self.thread = threading.Thread(target=self.serve, daemon=True)
+        self.thread.start()
+
+    def serve(self):
+        conn, _ = self.listener.accept()
+        with conn:
+            self.handler(self, conn)
+
+    def read_head(self, conn):
+        while not self.head.endswith(b"\r\n\r\n"):
+            data = conn.recv(1)
+            if not data:
+                break
+            self.head += data
+
+    def close(self):
+        self.listener.close()
+        self.thread.join(timeout=5)
+
+
+def echo(conn):
+    while data := conn.recv(65536):
+        conn.sendall(data)
+
+
+def upgrading(server, conn):
+    server.read_head(conn)
+    conn.sendall(b"HTTP/1.1 101 Switching Protocols\r\nConnection: Upgrade\r\nUpgrade: echo/1\r\n\r\n")
+    echo(conn)
+
+
+def tunneling(server, conn):
+    server.read_head(conn)
+    conn.sendall(b"HTTP/1.1 200 Connection established\r\n\r\n")
+    echo(conn)
+
+
+def refusing(server, conn):
+    server.read_head(conn)
+    conn.sendall(b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\n\r\n")
+
+
+def echoing(server, conn):
+    echo(conn)
+
+
+@pytest.fixture
+def raw_server():
+    servers = []
+
+    def start(handler):
+        server = RawServer(handler)
+        servers.append(server)
+        return server
+
+    yield start
+    for server in servers:
+        server.close()
+
+
+def test_upgrade(raw_server):
+    server = raw_server(upgrading)
+    with httpr.Client(timeout=10).upgrade(server.url, "echo/1") as stream:
+        assert stream.status_code == 101
+        assert stream.headers["upgrade"] == "echo/1"
+        stream.write(b"ping")
+        assert stream.read(timeout=5) == b"ping"
+    assert stream.is_closed
+    head = server.head.decode().lower()
+    assert "connection: upgrade" in head
+    assert "upgrade: echo/1" in head
+
+
+def test_upgrade_refused(raw_server):
+    server = raw_server(refusing)
+    with pytest.raises(httpr.RemoteProtocolError, match="did not switch to protocol 'echo/1'"):
+        httpr.Client(timeout=10).upgrade(server.url, "echo/1")
+
+
+def test_connect_tunnel_via_proxy(raw_server):
+    proxy = raw_server(tunneling)
+    client = httpr.Client(proxy=proxy.url, timeout=10)
+    with client.connect_tunnel("mail.example.com", 25) as stream:
+        assert stream.status_code == 200
+        stream.write("EHLO client.example.com\r\n")
+        assert stream.read(timeout=5) == b"EHLO client.example.com\r\n"
+    assert proxy.head.startswith(b"CONNECT mail.example.com:25 HTTP/1.1\r\n")
+
+
+def test_connect_tunnel_refused(raw_server):
+    proxy = raw_server(refusing)
+    client = httpr.Client(proxy=proxy.url, timeout=10)
+    with pytest.raises(httpr.ProxyError, match="refused to open a tunnel to mail.example.com:25"):
+        client.connect_tunnel("mail.example.com", 25)
+
+
+def test_connect_tunnel_direct(raw_server):
+    server = raw_server(echoing)
+    with httpr.Client().connect_tunnel("127.0.0.1", server.port, via_proxy=False) as stream:
+        assert stream.status_code is None
+        stream.write(b"hello")
+        assert stream.read(timeout=5) == b"hello"
+
+
+def test_connect_tunnel_direct_blocked():
+    client = httpr.Client(block_hosts=["127.0.0.1"])
+    with pytest.raises(httpr.HostBlocked, match="is in block_hosts"):
+        client.connect_tunnel("127.0.0.1", 1, via_proxy=False)
+
+
+def test_connect_tunnel_direct_private_name():
+    client = httpr.Client(block_private_ips=True)
+    with pytest.raises(httpr.HostBlocked, match="resolves to the private address"):
+        client.connect_tunnel("localhost", 1, via_proxy=False)
+
+
+def test_connect_tunnel_direct_timeout():
+    # With the accept queue full, further connection attempts go unanswered
+    listener = socket.create_server(("127.0.0.1", 0), backlog=0)
+    port = listener.getsockname()[1]
+    queued = []
+    try:
+        for _ in range(4):
+            sock = socket.socket()
+            sock.setblocking(False)
+            sock.connect_ex(("127.0.0.1", port))
+            queued.append(sock)
+        client = httpr.Client(connect_timeout=0.5)
+        with pytest.raises(httpr.ConnectTimeout):
+            client.connect_tunnel("127.0.0.1", port, via_proxy=False)
+    finally:
+        for sock in queued:
+            sock.close()
+        listener.close()
+
+
+def test_connect_tunnel_needs_proxy(monkeypatch):
+    for name in ("HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"):
+        monkeypatch.delenv(name, raising=False)
+    with pytest.raises(ValueError, match="needs a proxy for this host"):
+        httpr.Client().connect_tunnel("127.0.0.1", 25)
+
+
+@pytest.mark.parametrize("env", ["HTTPR_HTTP_PROXY", "HTTP_PROXY"])
+def test_connect_tunnel_env_proxy(raw_server, monkeypatch, env):
+    proxy = raw_server(tunneling)
+    for name in ("NO_PROXY", "no_proxy"):
+        monkeypatch.delenv(name, raising=False)
+    monkeypatch.setenv(env, proxy.url)
+    with httpr.Client(timeout=10).connect_tunnel("mail.example.com", 25) as stream:
+        stream.write(b"hello")
+        assert stream.read(timeout=5) == b"hello"
+    assert proxy.head.startswith(b"CONNECT mail.example.com:25 HTTP/1.1\r\n")
+
+
+def test_read_timeout_and_eof(raw_server):
+    def close_after_one(server, conn):
+        conn.sendall(conn.recv(1))
+
+    server = raw_server(close_after_one)
+    with httpr.Client().connect_tunnel("127.0.0.1", server.port, via_proxy=False) as stream:
+        with pytest.raises(httpr.ReadTimeout):
+            stream.read(timeout=0.1)
+        stream.write(b"x")
+        assert stream.read(timeout=5) == b"x"
+        assert stream.read(timeout=5) == b""
+
+
+def test_close_wakes_reader(raw_server):
+    server = raw_server(echoing)
+    stream = httpr.Client().connect_tunnel("127.0.0.1", server.port, via_proxy=False)
+    errors = []
+
+    def read():
+        try:
+            stream.read()
+        except httpr.StreamClosed as exc:
+            errors.append(exc)
+
+    reader = threading.Thread(target=read)
+    reader.start()
+    threading.Event().wait(0.1)
+    stream.close()
+    reader.join(timeout=5)
+    assert len(errors) == 1
+    with pytest.raises(httpr.StreamClosed):
+        stream.write(b"late")
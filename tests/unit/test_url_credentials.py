@@ -0,0 +1,58 @@
+"""Tests for credentials given in the URL userinfo."""
+
+import base64
+
+import pytest
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture
+def server():
+    with LocalServer() as server:
+        server.echo("/echo")
+        yield server
+
+
+def basic(username, password):
+    return "Basic " + base64.b64encode(f"{username}:{password}".encode()).decode()
+
+
+def with_userinfo(url, userinfo):
+    return url.replace("://", f"://{userinfo}@", 1)
+
+
+def test_userinfo_sent_as_basic_auth(server):
+    client = httpr.Client(history_size=5)
+    response = client.get(with_userinfo(f"{server.url}/echo", "us%40er:p%3Ass"))
+    assert response.json()["headers"]["authorization"] == basic("us@er", "p:ss")
+    assert response.url == f"{server.url}/echo"
+    assert client.history()[0]["url"] == f"{server.url}/echo"
+
+
+def test_explicit_auth_takes_precedence(server):
+    response = httpr.Client().get(with_userinfo(f"{server.url}/echo", "user:pass"), auth=("other", "secret"))
+    assert response.json()["headers"]["authorization"] == basic("other", "secret")
+
+
+def test_opt_out(server):
+    response = httpr.Client(url_credentials=False).get(with_userinfo(f"{server.url}/echo", "user:pass"))
+    assert "authorization" not in response.json()["headers"]
+    assert response.url == f"{server.url}/echo"
+
+
+def test_not_sent_to_other_host(server):
+    with LocalServer() as other:
+        other.echo("/echo")
+        server.redirect("/away", f"{other.url}/echo")
+        response = httpr.Client().get(with_userinfo(f"{server.url}/away", "user:pass"))
+        assert response.url == f"{other.url}/echo"
+    assert "authorization" not in response.json()["headers"]
+
+
+def test_location_userinfo_ignored(server):
+    server.redirect("/leak", with_userinfo(f"{server.url}/echo", "user:pass"))
+    response = httpr.Client().get(f"{server.url}/leak")
+    assert response.url == f"{server.url}/echo"
+    assert "authorization" not in response.json()["headers"]
@@ -0,0 +1,106 @@
+"""Tests for the verify_hook client option."""
+
+import datetime
+
+import pytest
+import trustme
+
+import httpr
+from httpr.testing import LocalServer
+
+
+@pytest.fixture(scope="module")
+def ca():
+    return trustme.CA()
+
+
+@pytest.fixture(scope="module")
+def ca_file(ca, tmp_path_factory):
+    path = tmp_path_factory.mktemp("ca") / "ca.pem"
+    ca.cert_pem.write_to_path(str(path))
+    return str(path)
+
+
+def serve(ca, tmp_path, **validity):
+    cert = ca.issue_cert("127.0.0.1", **validity)
+    cert_path, key_path = tmp_path / "cert.pem", tmp_path / "key.pem"
+    cert.private_key_and_cert_chain_pem.write_to_path(str(cert_path))
+    cert.private_key_pem.write_to_path(str(key_path))
+    server = LocalServer(tls_cert=str(cert_path), tls_key=str(key_path))
+    server.route("/", body="secure")
+    return server
+
+
+def test_hook_sees_chain(ca, ca_file, tmp_path):
+    calls = []
+
+    def hook(hostname, chain, error):
+        calls.append((hostname, chain, error))
+        return error is None
+
+    with serve(ca, tmp_path) as server:
+        client = httpr.Client(ca_cert_file=ca_file, verify_hook=hook)
+        assert client.get(server.url).text == "secure"
+    [(hostname, chain, error)] = calls
+    assert hostname == "127.0.0.1"
+    assert chain and all(isinstance(cert, bytes) for cert in chain)
+    assert error is None
+
+
+def test_hook_accepts_expired(ca, ca_file, tmp_path):
+    now = datetime.datetime.now(datetime.timezone.utc)
+    validity = {"not_before": now - datetime.timedelta(days=30), "not_after": now - datetime.timedelta(days=1)}
+    errors = []
+
+    def hook(hostname, chain, error):
+        errors.append(error)
+        return error == "expired" and hostname == "127.0.0.1"
+
+    with serve(ca, tmp_path, **validity) as server:
+        client = httpr.Client(ca_cert_file=ca_file, verify_hook=hook)
+        assert client.get(server.url).text == "secure"
+    assert errors == ["expired"]
+
+
+def test_hook_rejects(ca, ca_file, tmp_path):
+    with serve(ca, tmp_path) as server:
+        client = httpr.Client(ca_cert_file=ca_file, verify_hook=lambda hostname, chain, error: False)
+        with pytest.raises(httpr.CertificateVerifyError) as exc_info:
+            client.get(server.url)
+        assert exc_info.value.reason == "rejected_by_hook"
+
+
+def test_hook_keeps_default_reason(tmp_path):
+    with serve(trustme.CA(), tmp_path) as server:
+        client = httpr.Client(verify_hook=lambda hostname, chain, error: None)
+        with pytest.raises(httpr.CertificateVerifyError) as exc_info:
+            client.get(server.url)
+        assert exc_info.value.reason == "unknown_ca"
+
+
+def test_hook_without_verify(tmp_path):
+    errors = []
+
+    def hook(hostname, chain, error):
+        errors.append(error)
+        return True
+
+    with serve(trustme.CA(), tmp_path) as server:
+        client = httpr.Client(verify=False, verify_hook=hook)
+        assert client.get(server.url).text == "secure"
+    assert errors == [None]
+
+
+def test_hook_exception_is_reraised(ca, ca_file, tmp_path):
+    def hook(hostname, chain, error):
+        raise KeyError("pinned certificate not found")
+
+    with serve(ca, tmp_path) as server:
+        client = httpr.Client(ca_cert_file=ca_file, verify_hook=hook)
+        with pytest.raises(KeyError, match="pinned certificate not found"):
+            client.get(server.url)
+
+
+def test_hook_must_be_callable():
+    with pytest.raises(ValueError, match="verify_hook must be callable"):
+        httpr.Client(verify_hook="not callable")
@@ -0,0 +1,154 @@
+"""Tests for the WebDAV helpers (propfind, mkcol, copy, move)."""
+
+import threading
+import xml.etree.ElementTree as ET
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+
+import pytest
+
+import httpr
+from httpr.webdav import build_propfind, parse_multistatus
+
+MULTISTATUS = b"""<?xml version="1.0" encoding="utf-8"?>
+<d:multistatus xmlns:d="DAV:" xmlns:oc="http://owncloud.org/ns">
+  <d:response>
+    <d:href>/dav/files/</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:displayname>files</d:displayname>
+        <d:resourcetype><d:collection/></d:resourcetype>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/dav/files/report.pdf</d:href>
+    <d:propstat>
+      <d:prop>
+        <d:getcontentlength>1024</d:getcontentlength>
+        <d:resourcetype/>
+        <oc:fileid>42</oc:fileid>
+      </d:prop>
+      <d:status>HTTP/1.1 200 OK</d:status>
+    </d:propstat>
+    <d:propstat>
+      <d:prop><d:quota-used-bytes/></d:prop>
+      <d:status>HTTP/1.1 404 Not Found</d:status>
+    </d:propstat>
+  </d:response>
+</d:multistatus>
+"""
+
+
+class DavHandler(BaseHTTPRequestHandler):
+    """Records the last request and answers like a minimal WebDAV server."""
+
+    last: dict = {}
+
+    def _handle(self):
+        length = int(self.headers.get("Content-Length", 0))
+        body = self.rfile.read(length) if length else b""
+        DavHandler.last = {
+            "method": self.command,
+            "path": self.path,
+            "headers": {k.lower(): v for k, v in self.headers.items()},
+            "body": body,
+        }
+        if self.command == "PROPFIND":
+            status, payload = (207, MULTISTATUS) if self.path.startswith("/dav") else (404, b"")
+        else:
+            status, payload = 201, b""
+        self.send_response(status)
+        self.send_header("Content-Length", str(len(payload)))
+        self.end_headers()
+        self.wfile.write(payload)
+
+    do_PROPFIND = do_MKCOL = do_COPY = do_MOVE = _handle
+
+    def log_message(self, format, *args):
+        pass  # Suppress logs during tests
+
+
+@pytest.fixture(scope="module")
+def dav_server():
+    server = ThreadingHTTPServer(("127.0.0.1", 0), DavHandler)
+    port = server.server_address[1]
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://127.0.0.1:{port}"
+    server.shutdown()
+
+
+def test_build_propfind_named_properties():
+    root = ET.fromstring(build_propfind(["displayname", "{http://owncloud.org/ns}fileid"]))
+    assert root.tag == "{DAV:}propfind"
+    assert [child.tag for child in root.find("{DAV:}prop")] == [
+        "{DAV:}displayname",
+        "{http://owncloud.org/ns}fileid",
+    ]
+
+
+def test_build_propfind_allprop():
+    root = ET.fromstring(build_propfind())
+    assert root.find("{DAV:}allprop") is not None
+
+
+def test_parse_multistatus():
+    resources = parse_multistatus(MULTISTATUS)
+    assert resources[0] == {
+        "href": "/dav/files/",
+        "status": None,
+        "properties": {"displayname": "files", "resourcetype": ["collection"]},
+    }
+    assert resources[1]["properties"] == {
+        "getcontentlength": "1024",
+        "resourcetype": "",
+        "{http://owncloud.org/ns}fileid": "42",
+    }
+
+
+def test_parse_multistatus_rejects_other_documents():
+    with pytest.raises(ValueError):
+        parse_multistatus(b"<html/>")
+    with pytest.raises(ValueError):
+        parse_multistatus(b"not xml")
+
+
+def test_propfind(dav_server):
+    client = httpr.Client()
+    resources = client.propfind(dav_server + "/dav/files/", depth=1, properties=["displayname"])
+    assert [r["href"] for r in resources] == ["/dav/files/", "/dav/files/report.pdf"]
+    assert DavHandler.last["method"] == "PROPFIND"
+    assert DavHandler.last["headers"]["depth"] == "1"
+    assert b"displayname" in DavHandler.last["body"]
+
+
+def test_propfind_unexpected_status(dav_server):
+    client = httpr.Client()
+    with pytest.raises(httpr.HTTPStatusError):
+        client.propfind(dav_server + "/missing")
+
+
+def test_mkcol_copy_move(dav_server):
+    client = httpr.Client()
+    assert client.mkcol(dav_server + "/dav/new/").status_code == 201
+    assert DavHandler.last["method"] == "MKCOL"
+
+    client.copy(dav_server + "/dav/a.txt", dav_server + "/dav/b.txt", overwrite=False)
+    assert DavHandler.last["method"] == "COPY"
+    assert DavHandler.last["headers"]["destination"] == dav_server + "/dav/b.txt"
+    assert DavHandler.last["headers"]["overwrite"] == "F"
+    assert DavHandler.last["headers"]["depth"] == "infinity"
+
+    client.move(dav_server + "/dav/b.txt", dav_server + "/dav/c.txt")
+    assert DavHandler.last["method"] == "MOVE"
+    assert DavHandler.last["headers"]["overwrite"] == "T"
+
+
+@pytest.mark.asyncio
+async def test_async_propfind(dav_server):
+    async with httpr.AsyncClient() as client:
+        resources = await client.propfind(dav_server + "/dav/files/", depth=0)
+        assert len(resources) == 2
+        assert DavHandler.last["headers"]["depth"] == "0"
+        assert b"allprop" in DavHandler.last["body"]